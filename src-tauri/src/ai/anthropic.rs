@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 use super::provider::{AiModel, AiProvider, AiProviderType, ChatCompletion, ChatMessage, ChatRole, TokenUsage};
+use super::retry::{send_with_retry, AiProviderError, RetryConfig};
 
 /// Anthropic API configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -105,20 +106,6 @@ struct AnthropicUsage {
     output_tokens: u32,
 }
 
-/// Anthropic error response
-#[derive(Debug, Deserialize)]
-struct AnthropicErrorResponse {
-    error: AnthropicError,
-}
-
-#[derive(Debug, Deserialize)]
-struct AnthropicError {
-    message: String,
-    #[allow(dead_code)]
-    #[serde(rename = "type")]
-    error_type: String,
-}
-
 /// Available Claude models with their context windows
 const CLAUDE_MODELS: &[(&str, &str, u32)] = &[
     ("claude-sonnet-4-20250514", "Claude Sonnet 4", 200_000),
@@ -133,6 +120,7 @@ const CLAUDE_MODELS: &[(&str, &str, u32)] = &[
 pub struct AnthropicProvider {
     client: Client,
     config: AnthropicConfig,
+    retry: RetryConfig,
 }
 
 impl AnthropicProvider {
@@ -150,7 +138,17 @@ impl AnthropicProvider {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client, config }
+        Self {
+            client,
+            config,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Use `retry` for rate-limited and failed requests
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
     }
 
     /// Get the current configuration
@@ -175,7 +173,7 @@ impl AiProvider for AnthropicProvider {
         !self.config.api_key.is_empty()
     }
 
-    async fn list_models(&self) -> Result<Vec<AiModel>, String> {
+    async fn list_models(&self) -> Result<Vec<AiModel>, AiProviderError> {
         // Anthropic doesn't have a models list endpoint, so we return hardcoded list
         let models = CLAUDE_MODELS
             .iter()
@@ -197,9 +195,9 @@ impl AiProvider for AnthropicProvider {
         model: Option<&str>,
         temperature: Option<f32>,
         max_tokens: Option<u32>,
-    ) -> Result<ChatCompletion, String> {
+    ) -> Result<ChatCompletion, AiProviderError> {
         if self.config.api_key.is_empty() {
-            return Err("Anthropic API key not configured".to_string());
+            return Err(AiProviderError::Auth("Anthropic API key not configured".to_string()));
         }
 
         let url = format!("{}/v1/messages", self.config.base_url);
@@ -208,7 +206,7 @@ impl AiProvider for AnthropicProvider {
 
         // Ensure we have at least one message
         if anthropic_messages.is_empty() {
-            return Err("At least one non-system message is required".to_string());
+            return Err(AiProviderError::Api("At least one non-system message is required".to_string()));
         }
 
         let request_body = AnthropicRequest {
@@ -219,25 +217,14 @@ impl AiProvider for AnthropicProvider {
             temperature,
         };
 
-        let response = self.client.post(&url)
-            .header("x-api-key", &self.config.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("Content-Type", "application/json")
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to send request: {}", e))?;
-
-        if !response.status().is_success() {
-            let error: AnthropicErrorResponse = response.json().await
-                .unwrap_or(AnthropicErrorResponse {
-                    error: AnthropicError {
-                        message: "Unknown error".to_string(),
-                        error_type: "unknown".to_string(),
-                    },
-                });
-            return Err(format!("Anthropic API error: {}", error.error.message));
-        }
+        let response = send_with_retry(&self.retry, "Anthropic", || {
+            self.client.post(&url)
+                .header("x-api-key", &self.config.api_key)
+                .header("anthropic-version", "2023-06-01")
+                .header("Content-Type", "application/json")
+                .json(&request_body)
+        })
+        .await?;
 
         let api_response: AnthropicResponse = response
             .json()
@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 use super::provider::{AiModel, AiProvider, AiProviderType, ChatCompletion, ChatMessage, ChatRole, TokenUsage};
+use super::retry::{send_with_retry, AiProviderError, RetryConfig};
 
 /// Google AI API configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -123,19 +124,6 @@ struct GeminiUsageMetadata {
     total_token_count: u32,
 }
 
-/// Gemini error response
-#[derive(Debug, Deserialize)]
-struct GeminiErrorResponse {
-    error: GeminiError,
-}
-
-#[derive(Debug, Deserialize)]
-struct GeminiError {
-    message: String,
-    #[allow(dead_code)]
-    status: String,
-}
-
 /// Gemini models list response
 #[derive(Debug, Deserialize)]
 struct GeminiModelsResponse {
@@ -169,6 +157,7 @@ const GEMINI_MODELS: &[(&str, &str, u32)] = &[
 pub struct GoogleAiProvider {
     client: Client,
     config: GoogleAiConfig,
+    retry: RetryConfig,
 }
 
 impl GoogleAiProvider {
@@ -186,7 +175,17 @@ impl GoogleAiProvider {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client, config }
+        Self {
+            client,
+            config,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Use `retry` for rate-limited and failed requests
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
     }
 
     /// Get the current configuration
@@ -220,7 +219,7 @@ impl AiProvider for GoogleAiProvider {
         }
     }
 
-    async fn list_models(&self) -> Result<Vec<AiModel>, String> {
+    async fn list_models(&self) -> Result<Vec<AiModel>, AiProviderError> {
         if self.config.api_key.is_empty() {
             // Return hardcoded list if no API key
             return Ok(GEMINI_MODELS
@@ -306,9 +305,9 @@ impl AiProvider for GoogleAiProvider {
         model: Option<&str>,
         temperature: Option<f32>,
         max_tokens: Option<u32>,
-    ) -> Result<ChatCompletion, String> {
+    ) -> Result<ChatCompletion, AiProviderError> {
         if self.config.api_key.is_empty() {
-            return Err("Google AI API key not configured".to_string());
+            return Err(AiProviderError::Auth("Google AI API key not configured".to_string()));
         }
 
         let model_id = model.unwrap_or(&self.config.default_model);
@@ -321,7 +320,7 @@ impl AiProvider for GoogleAiProvider {
 
         // Ensure we have at least one message
         if contents.is_empty() {
-            return Err("At least one non-system message is required".to_string());
+            return Err(AiProviderError::Api("At least one non-system message is required".to_string()));
         }
 
         let request_body = GeminiRequest {
@@ -333,23 +332,12 @@ impl AiProvider for GoogleAiProvider {
             }),
         };
 
-        let response = self.client.post(&url)
-            .header("Content-Type", "application/json")
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to send request: {}", e))?;
-
-        if !response.status().is_success() {
-            let error: GeminiErrorResponse = response.json().await
-                .unwrap_or(GeminiErrorResponse {
-                    error: GeminiError {
-                        message: "Unknown error".to_string(),
-                        status: "UNKNOWN".to_string(),
-                    },
-                });
-            return Err(format!("Google AI API error: {}", error.error.message));
-        }
+        let response = send_with_retry(&self.retry, "Google AI", || {
+            self.client.post(&url)
+                .header("Content-Type", "application/json")
+                .json(&request_body)
+        })
+        .await?;
 
         let api_response: GeminiResponse = response
             .json()
@@ -4,6 +4,13 @@
 //! - Natural language to SQL conversion
 //! - Query explanation
 //! - Query optimization suggestions
+//! - Query error diagnosis
+//!
+//! System prompts for each operation are user-editable templates (see `prompts`).
+//! Schema context is kept within a token budget (see `schema_summary`).
+//! Requests to hosted providers are retried on rate limits and transient
+//! failures (see `retry`). Hostnames and sample values can be removed from
+//! prompts bound for hosted providers (see `scrub`).
 //!
 //! Supported providers:
 //! - Ollama (local LLM)
@@ -13,6 +20,10 @@
 //! - OpenRouter (multi-model gateway)
 
 pub mod provider;
+pub mod prompts;
+pub mod retry;
+pub mod schema_summary;
+pub mod scrub;
 pub mod ollama;
 pub mod openai;
 pub mod anthropic;
@@ -25,6 +36,11 @@ pub use provider::{
     ChatCompletion, TokenUsage, extract_sql,
 };
 
+pub use prompts::{AiPrompts, PromptOperation};
+pub use retry::{AiProviderError, RetryConfig};
+pub use schema_summary::SchemaSummary;
+pub use scrub::Scrubber;
+
 // Re-export providers
 pub use ollama::{OllamaProvider, OllamaConfig};
 pub use openai::{OpenAiProvider, OpenAiConfig};
@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 use super::provider::{AiModel, AiProvider, AiProviderType, ChatCompletion, ChatMessage, ChatRole, TokenUsage};
+use super::retry::AiProviderError;
 
 /// Ollama API configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -174,17 +175,17 @@ impl AiProvider for OllamaProvider {
         }
     }
 
-    async fn list_models(&self) -> Result<Vec<AiModel>, String> {
+    async fn list_models(&self) -> Result<Vec<AiModel>, AiProviderError> {
         let url = format!("{}/api/tags", self.config.base_url);
 
         let response = self.client
             .get(&url)
             .send()
             .await
-            .map_err(|e| format!("Failed to connect to Ollama: {}", e))?;
+            .map_err(|e| AiProviderError::Network(format!("Failed to connect to Ollama: {}", e)))?;
 
         if !response.status().is_success() {
-            return Err(format!("Ollama API error: {}", response.status()));
+            return Err(AiProviderError::Api(format!("Ollama API error: {}", response.status())));
         }
 
         let data: ListModelsResponse = response
@@ -220,7 +221,7 @@ impl AiProvider for OllamaProvider {
         model: Option<&str>,
         temperature: Option<f32>,
         max_tokens: Option<u32>,
-    ) -> Result<ChatCompletion, String> {
+    ) -> Result<ChatCompletion, AiProviderError> {
         let url = format!("{}/api/chat", self.config.base_url);
 
         let ollama_messages: Vec<OllamaChatMessage> = messages.iter().map(|m| m.into()).collect();
@@ -240,11 +241,11 @@ impl AiProvider for OllamaProvider {
             .json(&request)
             .send()
             .await
-            .map_err(|e| format!("Failed to send request: {}", e))?;
+            .map_err(|e| AiProviderError::Network(format!("Failed to send request: {}", e)))?;
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            return Err(format!("Ollama API error: {}", error_text));
+            return Err(AiProviderError::Api(format!("Ollama API error: {}", error_text)));
         }
 
         let chat_response: OllamaChatResponse = response
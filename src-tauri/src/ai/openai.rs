@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 use super::provider::{AiModel, AiProvider, AiProviderType, ChatCompletion, ChatMessage, ChatRole, TokenUsage};
+use super::retry::{send_with_retry, AiProviderError, RetryConfig};
 
 /// OpenAI API configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,24 +108,11 @@ struct OpenAiModelInfo {
     owned_by: String,
 }
 
-/// OpenAI error response
-#[derive(Debug, Deserialize)]
-struct OpenAiErrorResponse {
-    error: OpenAiError,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenAiError {
-    message: String,
-    #[allow(dead_code)]
-    #[serde(rename = "type")]
-    error_type: Option<String>,
-}
-
 /// OpenAI API provider
 pub struct OpenAiProvider {
     client: Client,
     config: OpenAiConfig,
+    retry: RetryConfig,
 }
 
 impl OpenAiProvider {
@@ -142,7 +130,17 @@ impl OpenAiProvider {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client, config }
+        Self {
+            client,
+            config,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Use `retry` for rate-limited and failed requests
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
     }
 
     /// Get the current configuration
@@ -217,35 +215,23 @@ impl AiProvider for OpenAiProvider {
         }
     }
 
-    async fn list_models(&self) -> Result<Vec<AiModel>, String> {
+    async fn list_models(&self) -> Result<Vec<AiModel>, AiProviderError> {
         if self.config.api_key.is_empty() {
-            return Err("OpenAI API key not configured".to_string());
+            return Err(AiProviderError::Auth("OpenAI API key not configured".to_string()));
         }
 
         let url = format!("{}/models", self.config.base_url);
 
-        let mut request = self.client.get(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key));
+        let response = send_with_retry(&self.retry, "OpenAI", || {
+            let mut request = self.client.get(&url)
+                .header("Authorization", format!("Bearer {}", self.config.api_key));
 
-        if let Some(org) = &self.config.organization {
-            request = request.header("OpenAI-Organization", org);
-        }
-
-        let response = request
-            .send()
-            .await
-            .map_err(|e| format!("Failed to connect to OpenAI: {}", e))?;
-
-        if !response.status().is_success() {
-            let error: OpenAiErrorResponse = response.json().await
-                .unwrap_or(OpenAiErrorResponse {
-                    error: OpenAiError {
-                        message: "Unknown error".to_string(),
-                        error_type: None,
-                    },
-                });
-            return Err(format!("OpenAI API error: {}", error.error.message));
-        }
+            if let Some(org) = &self.config.organization {
+                request = request.header("OpenAI-Organization", org);
+            }
+            request
+        })
+        .await?;
 
         let data: OpenAiModelsResponse = response
             .json()
@@ -288,9 +274,9 @@ impl AiProvider for OpenAiProvider {
         model: Option<&str>,
         temperature: Option<f32>,
         max_tokens: Option<u32>,
-    ) -> Result<ChatCompletion, String> {
+    ) -> Result<ChatCompletion, AiProviderError> {
         if self.config.api_key.is_empty() {
-            return Err("OpenAI API key not configured".to_string());
+            return Err(AiProviderError::Auth("OpenAI API key not configured".to_string()));
         }
 
         let url = format!("{}/chat/completions", self.config.base_url);
@@ -304,30 +290,17 @@ impl AiProvider for OpenAiProvider {
             max_tokens,
         };
 
-        let mut request = self.client.post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json");
+        let response = send_with_retry(&self.retry, "OpenAI", || {
+            let mut request = self.client.post(&url)
+                .header("Authorization", format!("Bearer {}", self.config.api_key))
+                .header("Content-Type", "application/json");
 
-        if let Some(org) = &self.config.organization {
-            request = request.header("OpenAI-Organization", org);
-        }
-
-        let response = request
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to send request: {}", e))?;
-
-        if !response.status().is_success() {
-            let error: OpenAiErrorResponse = response.json().await
-                .unwrap_or(OpenAiErrorResponse {
-                    error: OpenAiError {
-                        message: "Unknown error".to_string(),
-                        error_type: None,
-                    },
-                });
-            return Err(format!("OpenAI API error: {}", error.error.message));
-        }
+            if let Some(org) = &self.config.organization {
+                request = request.header("OpenAI-Organization", org);
+            }
+            request.json(&request_body)
+        })
+        .await?;
 
         let chat_response: OpenAiChatResponse = response
             .json()
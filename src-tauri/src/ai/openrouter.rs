@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 use super::provider::{AiModel, AiProvider, AiProviderType, ChatCompletion, ChatMessage, ChatRole, TokenUsage};
+use super::retry::{send_with_retry, AiProviderError, RetryConfig};
 
 /// OpenRouter API configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -112,20 +113,6 @@ struct OpenRouterModelInfo {
     context_length: Option<u32>,
 }
 
-/// OpenRouter error response (OpenAI-style)
-#[derive(Debug, Deserialize)]
-struct OpenRouterErrorResponse {
-    error: OpenRouterError,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenRouterError {
-    message: String,
-    #[allow(dead_code)]
-    #[serde(default, rename = "type")]
-    error_type: Option<String>,
-}
-
 /// Recommended headers for OpenRouter usage analytics.
 const REFERER_HEADER: &str = "https://db-hive.app";
 const TITLE_HEADER: &str = "DB Hive";
@@ -134,6 +121,7 @@ const TITLE_HEADER: &str = "DB Hive";
 pub struct OpenRouterProvider {
     client: Client,
     config: OpenRouterConfig,
+    retry: RetryConfig,
 }
 
 impl OpenRouterProvider {
@@ -151,7 +139,17 @@ impl OpenRouterProvider {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client, config }
+        Self {
+            client,
+            config,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Use `retry` for rate-limited and failed requests
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
     }
 
     /// Get the current configuration
@@ -184,33 +182,20 @@ impl AiProvider for OpenRouterProvider {
         }
     }
 
-    async fn list_models(&self) -> Result<Vec<AiModel>, String> {
+    async fn list_models(&self) -> Result<Vec<AiModel>, AiProviderError> {
         if self.config.api_key.is_empty() {
-            return Err("OpenRouter API key not configured".to_string());
+            return Err(AiProviderError::Auth("OpenRouter API key not configured".to_string()));
         }
 
         let url = format!("{}/models", self.config.base_url);
 
-        let request = self.client.get(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("HTTP-Referer", REFERER_HEADER)
-            .header("X-Title", TITLE_HEADER);
-
-        let response = request
-            .send()
-            .await
-            .map_err(|e| format!("Failed to connect to OpenRouter: {}", e))?;
-
-        if !response.status().is_success() {
-            let error: OpenRouterErrorResponse = response.json().await
-                .unwrap_or(OpenRouterErrorResponse {
-                    error: OpenRouterError {
-                        message: "Unknown error".to_string(),
-                        error_type: None,
-                    },
-                });
-            return Err(format!("OpenRouter API error: {}", error.error.message));
-        }
+        let response = send_with_retry(&self.retry, "OpenRouter", || {
+            self.client.get(&url)
+                .header("Authorization", format!("Bearer {}", self.config.api_key))
+                .header("HTTP-Referer", REFERER_HEADER)
+                .header("X-Title", TITLE_HEADER)
+        })
+        .await?;
 
         let data: OpenRouterModelsResponse = response
             .json()
@@ -237,9 +222,9 @@ impl AiProvider for OpenRouterProvider {
         model: Option<&str>,
         temperature: Option<f32>,
         max_tokens: Option<u32>,
-    ) -> Result<ChatCompletion, String> {
+    ) -> Result<ChatCompletion, AiProviderError> {
         if self.config.api_key.is_empty() {
-            return Err("OpenRouter API key not configured".to_string());
+            return Err(AiProviderError::Auth("OpenRouter API key not configured".to_string()));
         }
 
         let url = format!("{}/chat/completions", self.config.base_url);
@@ -253,28 +238,15 @@ impl AiProvider for OpenRouterProvider {
             max_tokens,
         };
 
-        let request = self.client.post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .header("HTTP-Referer", REFERER_HEADER)
-            .header("X-Title", TITLE_HEADER);
-
-        let response = request
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to send request: {}", e))?;
-
-        if !response.status().is_success() {
-            let error: OpenRouterErrorResponse = response.json().await
-                .unwrap_or(OpenRouterErrorResponse {
-                    error: OpenRouterError {
-                        message: "Unknown error".to_string(),
-                        error_type: None,
-                    },
-                });
-            return Err(format!("OpenRouter API error: {}", error.error.message));
-        }
+        let response = send_with_retry(&self.retry, "OpenRouter", || {
+            self.client.post(&url)
+                .header("Authorization", format!("Bearer {}", self.config.api_key))
+                .header("Content-Type", "application/json")
+                .header("HTTP-Referer", REFERER_HEADER)
+                .header("X-Title", TITLE_HEADER)
+                .json(&request_body)
+        })
+        .await?;
 
         let chat_response: OpenRouterChatResponse = response
             .json()
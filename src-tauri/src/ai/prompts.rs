@@ -0,0 +1,351 @@
+//! AI Prompt Templates
+//!
+//! System prompts sent to the providers for each AI operation. Users can
+//! replace any of them; the built-in text is used for operations without an
+//! override. Templates refer to request values through `{name}` placeholders
+//! that are filled in by [`render`].
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::DbDriver;
+
+/// AI operation a prompt template belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptOperation {
+    GenerateSql,
+    ExplainQuery,
+    OptimizeQuery,
+    FixQuery,
+    ExplainError,
+    Chat,
+}
+
+impl PromptOperation {
+    pub const ALL: [PromptOperation; 6] = [
+        PromptOperation::GenerateSql,
+        PromptOperation::ExplainQuery,
+        PromptOperation::OptimizeQuery,
+        PromptOperation::FixQuery,
+        PromptOperation::ExplainError,
+        PromptOperation::Chat,
+    ];
+
+    /// Placeholders a template for this operation may use
+    pub fn variables(self) -> &'static [&'static str] {
+        match self {
+            PromptOperation::GenerateSql => &["dialect", "schema", "question"],
+            PromptOperation::ExplainQuery => &["dialect", "sql"],
+            PromptOperation::OptimizeQuery => &["dialect", "schema", "sql"],
+            PromptOperation::FixQuery | PromptOperation::ExplainError => {
+                &["dialect", "schema", "sql", "error"]
+            }
+            PromptOperation::Chat => &["dialect"],
+        }
+    }
+
+    /// Placeholders a template for this operation must contain
+    ///
+    /// The schema is the only context the model gets about the database, so a
+    /// template that drops it produces queries against made-up tables.
+    pub fn required_variables(self) -> &'static [&'static str] {
+        match self {
+            PromptOperation::GenerateSql
+            | PromptOperation::OptimizeQuery
+            | PromptOperation::FixQuery
+            | PromptOperation::ExplainError => &["schema"],
+            PromptOperation::ExplainQuery | PromptOperation::Chat => &[],
+        }
+    }
+
+    /// Built-in template
+    pub fn default_template(self) -> &'static str {
+        match self {
+            PromptOperation::GenerateSql => {
+                r#"You are a SQL expert assistant. Generate {dialect} queries based on natural language requests.
+
+DATABASE SCHEMA:
+{schema}
+
+RULES:
+1. Generate only valid SQL that matches the schema above
+2. Use proper table and column names exactly as shown in the schema
+3. Include appropriate JOINs when querying related tables
+4. Add LIMIT clauses for SELECT queries unless counting
+5. Use parameterized placeholders ($1, $2) for user-provided values when appropriate
+6. Output ONLY the SQL query, no explanations or markdown
+
+Generate the SQL query for the following request:"#
+            }
+            PromptOperation::ExplainQuery => {
+                r#"You are a SQL expert. Explain {dialect} queries in clear, simple terms.
+
+Provide:
+1. A brief summary of what the query does
+2. Step-by-step breakdown of each clause
+3. Any potential performance considerations
+4. Suggestions for improvement if applicable
+
+Be concise but thorough."#
+            }
+            PromptOperation::OptimizeQuery => {
+                r#"You are a SQL performance optimization expert for {dialect}.
+
+DATABASE SCHEMA:
+{schema}
+
+Analyze the provided SQL query and suggest optimizations. Consider:
+1. Index usage and suggestions
+2. Query structure improvements
+3. JOIN optimization
+4. Subquery vs JOIN alternatives
+5. Potential N+1 query issues
+6. Appropriate use of LIMIT/OFFSET
+
+Provide the optimized query and explain the improvements."#
+            }
+            PromptOperation::FixQuery => {
+                r#"You are a SQL debugging expert for {dialect}.
+
+DATABASE SCHEMA:
+{schema}
+
+Fix the SQL query based on the error message. Output ONLY the corrected SQL query, no explanations."#
+            }
+            PromptOperation::ExplainError => {
+                r#"You are a SQL debugging expert helping someone understand a {dialect} error.
+
+DATABASE SCHEMA (may be partial or empty):
+{schema}
+
+Explain the error in plain English:
+1. What the error means, in one or two sentences
+2. What in this particular query causes it
+3. How to fix it
+
+If the fix is a change to the query, end with the corrected query in a single ```sql code block. If the problem is outside the query (permissions, missing objects, server settings), say so and do not include a code block."#
+            }
+            PromptOperation::Chat => {
+                r#"You are a helpful database assistant working with {dialect}. Answer questions about SQL, schema design and query performance. Put any SQL in ```sql code blocks."#
+            }
+        }
+    }
+}
+
+/// User overrides of the built-in templates
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AiPrompts {
+    overrides: HashMap<PromptOperation, String>,
+}
+
+impl AiPrompts {
+    /// Template in effect for `operation`
+    pub fn template(&self, operation: PromptOperation) -> &str {
+        self.overrides
+            .get(&operation)
+            .map(String::as_str)
+            .unwrap_or_else(|| operation.default_template())
+    }
+
+    /// Whether `operation` uses a user-supplied template
+    pub fn is_custom(&self, operation: PromptOperation) -> bool {
+        self.overrides.contains_key(&operation)
+    }
+
+    /// Replace the template for `operation` after validating it
+    pub fn set(&mut self, operation: PromptOperation, template: String) -> Result<(), String> {
+        validate_template(operation, &template)?;
+        self.overrides.insert(operation, template);
+        Ok(())
+    }
+
+    /// Go back to the built-in template for `operation`, or for all operations
+    pub fn reset(&mut self, operation: Option<PromptOperation>) {
+        match operation {
+            Some(operation) => {
+                self.overrides.remove(&operation);
+            }
+            None => self.overrides.clear(),
+        }
+    }
+}
+
+/// Check that `template` is usable for `operation`
+///
+/// Every required placeholder must be present and every placeholder must be
+/// one the operation provides. Braces around anything other than a plain name
+/// (e.g. a JSON example) are left alone.
+pub fn validate_template(operation: PromptOperation, template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("Prompt template cannot be empty".to_string());
+    }
+
+    let used = placeholders(template);
+    if let Some(unknown) = used
+        .iter()
+        .find(|name| !operation.variables().contains(name))
+    {
+        return Err(format!(
+            "Unknown placeholder {{{}}}; available: {}",
+            unknown,
+            format_variables(operation.variables())
+        ));
+    }
+    let missing: Vec<&str> = operation
+        .required_variables()
+        .iter()
+        .copied()
+        .filter(|name| !used.contains(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Prompt template must contain {}",
+            format_variables(&missing)
+        ));
+    }
+    Ok(())
+}
+
+fn format_variables(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|name| format!("{{{}}}", name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `{name}` placeholders in `template`, in order of appearance
+fn placeholders(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        rest = &rest[open + 1..];
+        match placeholder_at(rest) {
+            Some(name) => {
+                names.push(name);
+                rest = &rest[name.len() + 1..];
+            }
+            None => continue,
+        }
+    }
+    names
+}
+
+/// Placeholder name at the start of `s` (just after a `{`), if it is one
+fn placeholder_at(s: &str) -> Option<&str> {
+    let len = s
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(s.len());
+    let name = &s[..len];
+    let starts_ok = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+    (starts_ok && s[len..].starts_with('}')).then_some(name)
+}
+
+/// Fill the placeholders of `template` from `vars`
+///
+/// Substitution is a single pass, so a value that itself contains `{schema}`
+/// (say, a column comment) is inserted verbatim. Placeholders without a value
+/// are left as written.
+pub fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        rest = &rest[open + 1..];
+        let value = placeholder_at(rest)
+            .and_then(|name| vars.iter().find(|(n, _)| *n == name).map(|v| (name, v.1)));
+        match value {
+            Some((name, value)) => {
+                out.push_str(value);
+                rest = &rest[name.len() + 1..];
+            }
+            None => out.push('{'),
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Dialect name used for the `{dialect}` placeholder
+pub fn dialect_name(driver: Option<&DbDriver>) -> &'static str {
+    match driver {
+        Some(DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon) => "PostgreSQL",
+        Some(DbDriver::MySql) => "MySQL",
+        Some(DbDriver::Sqlite | DbDriver::Turso) => "SQLite",
+        Some(DbDriver::SqlServer) => "SQL Server (T-SQL)",
+        Some(DbDriver::MongoDb) => "MongoDB",
+        Some(DbDriver::Redis) => "Redis",
+        None => "standard SQL",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_templates_are_valid() {
+        for operation in PromptOperation::ALL {
+            assert_eq!(
+                validate_template(operation, operation.default_template()),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_template() {
+        let op = PromptOperation::GenerateSql;
+        assert!(validate_template(op, "Write {dialect}.\n{schema}").is_ok());
+        assert!(validate_template(op, "  ").is_err());
+        assert_eq!(
+            validate_template(op, "Write SQL."),
+            Err("Prompt template must contain {schema}".to_string())
+        );
+        assert!(validate_template(op, "{schema} {sql}")
+            .unwrap_err()
+            .starts_with("Unknown placeholder {sql}"));
+        // Not placeholders
+        assert!(validate_template(op, r#"{schema} Reply as {"sql": "..."} or {}"#).is_ok());
+    }
+
+    #[test]
+    fn test_render() {
+        let template = "Dialect: {dialect}\n{schema}\nKeep {unknown} and {\"json\": 1}";
+        let rendered = render(
+            template,
+            &[
+                ("dialect", "MySQL"),
+                ("schema", "users(note text -- {dialect})"),
+            ],
+        );
+        assert_eq!(
+            rendered,
+            "Dialect: MySQL\nusers(note text -- {dialect})\nKeep {unknown} and {\"json\": 1}"
+        );
+    }
+
+    #[test]
+    fn test_ai_prompts_overrides() {
+        let mut prompts = AiPrompts::default();
+        let op = PromptOperation::FixQuery;
+        assert_eq!(prompts.template(op), op.default_template());
+
+        assert!(prompts.set(op, "Fix it.".to_string()).is_err());
+        prompts.set(op, "Fix it.\n{schema}".to_string()).unwrap();
+        assert!(prompts.is_custom(op));
+        assert_eq!(prompts.template(op), "Fix it.\n{schema}");
+
+        let json = serde_json::to_value(&prompts).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "fix_query": "Fix it.\n{schema}" })
+        );
+
+        prompts.reset(None);
+        assert_eq!(prompts.template(op), op.default_template());
+    }
+}
@@ -5,6 +5,8 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+use super::retry::AiProviderError;
+
 /// AI Provider types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -110,7 +112,7 @@ pub trait AiProvider: Send + Sync {
     async fn is_available(&self) -> bool;
 
     /// List available models
-    async fn list_models(&self) -> Result<Vec<AiModel>, String>;
+    async fn list_models(&self) -> Result<Vec<AiModel>, AiProviderError>;
 
     /// Send a chat completion request
     async fn chat(
@@ -119,33 +121,18 @@ pub trait AiProvider: Send + Sync {
         model: Option<&str>,
         temperature: Option<f32>,
         max_tokens: Option<u32>,
-    ) -> Result<ChatCompletion, String>;
+    ) -> Result<ChatCompletion, AiProviderError>;
 
     /// Generate SQL from natural language
+    ///
+    /// `system_prompt` is the rendered template for the operation (see
+    /// `ai::prompts`); the request itself is sent as the user message.
     async fn generate_sql(
         &self,
+        system_prompt: &str,
         prompt: &str,
-        schema_context: &str,
         model: Option<&str>,
-    ) -> Result<String, String> {
-        let system_prompt = format!(
-            r#"You are a SQL expert assistant. Generate SQL queries based on natural language requests.
-
-DATABASE SCHEMA:
-{}
-
-RULES:
-1. Generate only valid SQL that matches the schema above
-2. Use proper table and column names exactly as shown in the schema
-3. Include appropriate JOINs when querying related tables
-4. Add LIMIT clauses for SELECT queries unless counting
-5. Use parameterized placeholders ($1, $2) for user-provided values when appropriate
-6. Output ONLY the SQL query, no explanations or markdown
-
-Generate the SQL query for the following request:"#,
-            schema_context
-        );
-
+    ) -> Result<String, AiProviderError> {
         let messages = vec![
             ChatMessage::system(system_prompt),
             ChatMessage::user(prompt),
@@ -156,17 +143,12 @@ Generate the SQL query for the following request:"#,
     }
 
     /// Explain a SQL query in plain English
-    async fn explain_query(&self, sql: &str, model: Option<&str>) -> Result<String, String> {
-        let system_prompt = r#"You are a SQL expert. Explain SQL queries in clear, simple terms.
-
-Provide:
-1. A brief summary of what the query does
-2. Step-by-step breakdown of each clause
-3. Any potential performance considerations
-4. Suggestions for improvement if applicable
-
-Be concise but thorough."#;
-
+    async fn explain_query(
+        &self,
+        system_prompt: &str,
+        sql: &str,
+        model: Option<&str>,
+    ) -> Result<String, AiProviderError> {
         let messages = vec![
             ChatMessage::system(system_prompt),
             ChatMessage::user(format!("Explain this SQL query:\n\n```sql\n{}\n```", sql)),
@@ -179,28 +161,10 @@ Be concise but thorough."#;
     /// Suggest optimizations for a SQL query
     async fn optimize_query(
         &self,
+        system_prompt: &str,
         sql: &str,
-        schema_context: &str,
         model: Option<&str>,
-    ) -> Result<String, String> {
-        let system_prompt = format!(
-            r#"You are a SQL performance optimization expert.
-
-DATABASE SCHEMA:
-{}
-
-Analyze the provided SQL query and suggest optimizations. Consider:
-1. Index usage and suggestions
-2. Query structure improvements
-3. JOIN optimization
-4. Subquery vs JOIN alternatives
-5. Potential N+1 query issues
-6. Appropriate use of LIMIT/OFFSET
-
-Provide the optimized query and explain the improvements."#,
-            schema_context
-        );
-
+    ) -> Result<String, AiProviderError> {
         let messages = vec![
             ChatMessage::system(system_prompt),
             ChatMessage::user(format!("Optimize this SQL query:\n\n```sql\n{}\n```", sql)),
@@ -213,21 +177,11 @@ Provide the optimized query and explain the improvements."#,
     /// Fix SQL syntax errors
     async fn fix_query(
         &self,
+        system_prompt: &str,
         sql: &str,
         error_message: &str,
-        schema_context: &str,
         model: Option<&str>,
-    ) -> Result<String, String> {
-        let system_prompt = format!(
-            r#"You are a SQL debugging expert.
-
-DATABASE SCHEMA:
-{}
-
-Fix the SQL query based on the error message. Output ONLY the corrected SQL query, no explanations."#,
-            schema_context
-        );
-
+    ) -> Result<String, AiProviderError> {
         let user_prompt = format!(
             "Fix this SQL query:\n\n```sql\n{}\n```\n\nError: {}",
             sql, error_message
@@ -241,6 +195,54 @@ Fix the SQL query based on the error message. Output ONLY the corrected SQL quer
         let response = self.chat(messages, model, Some(0.1), Some(2048)).await?;
         Ok(extract_sql(&response.content))
     }
+
+    /// Explain why a query failed, with a corrected query where one is clear
+    ///
+    /// Returns the explanation and, if the model offered one, the corrected SQL.
+    async fn explain_error(
+        &self,
+        system_prompt: &str,
+        sql: &str,
+        error_message: &str,
+        model: Option<&str>,
+    ) -> Result<(String, Option<String>), AiProviderError> {
+        let user_prompt = format!(
+            "This query failed:\n\n```sql\n{}\n```\n\nError: {}",
+            sql, error_message
+        );
+
+        let messages = vec![
+            ChatMessage::system(system_prompt),
+            ChatMessage::user(user_prompt),
+        ];
+
+        let response = self.chat(messages, model, Some(0.2), Some(2048)).await?;
+        Ok(split_explanation(&response.content))
+    }
+}
+
+/// Split a response into its prose and the SQL of its first code block
+///
+/// The code block is removed from the prose. Without a code block the whole
+/// response is prose and there is no SQL.
+pub fn split_explanation(content: &str) -> (String, Option<String>) {
+    let Some(start) = content.find("```") else {
+        return (content.trim().to_string(), None);
+    };
+    let body = start + 3;
+    let Some(len) = content[body..].find("```") else {
+        return (content.trim().to_string(), None);
+    };
+    let end = body + len + 3;
+
+    let sql = extract_sql(&content[start..end]);
+    let prose = format!(
+        "{}\n\n{}",
+        content[..start].trim_end(),
+        content[end..].trim_start()
+    );
+    let sql = if sql.is_empty() { None } else { Some(sql) };
+    (prose.trim().to_string(), sql)
 }
 
 /// Extract SQL from a response that might contain markdown
@@ -289,6 +291,21 @@ mod tests {
         assert_eq!(extract_sql(input), "SELECT * FROM users;");
     }
 
+    #[test]
+    fn test_split_explanation() {
+        let input = "The column is misspelled.\n\n```sql\nSELECT name FROM users;\n```\nRun it again.";
+        assert_eq!(
+            split_explanation(input),
+            (
+                "The column is misspelled.\n\nRun it again.".to_string(),
+                Some("SELECT name FROM users;".to_string())
+            )
+        );
+
+        let input = "You lack SELECT permission on users.";
+        assert_eq!(split_explanation(input), (input.to_string(), None));
+    }
+
     #[test]
     fn test_chat_message_constructors() {
         let system = ChatMessage::system("You are helpful");
@@ -0,0 +1,326 @@
+//! Retries for Cloud AI Providers
+//!
+//! Hosted APIs answer bursts with HTTP 429 and occasionally fail with 5xx or
+//! dropped connections. Requests are retried with exponential backoff and
+//! jitter, honouring `Retry-After`, and failures are classified so callers
+//! can tell a rate limit from a bad API key or an unreachable host.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime};
+
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::models::DbError;
+
+/// Retry policy shared by the cloud providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetryConfig {
+    /// Retries after the first attempt (0 disables retrying)
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on each further retry
+    pub base_delay_ms: u64,
+    /// Upper bound for a single delay, including `Retry-After`
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 30_000,
+        }
+    }
+}
+
+/// Failure of a provider request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AiProviderError {
+    /// HTTP 429; `retry_after` is the wait the provider asked for
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    /// Missing or rejected API key (HTTP 401/403)
+    Auth(String),
+    /// The provider could not be reached or dropped the connection
+    Network(String),
+    /// HTTP 5xx, including Anthropic's 529 "overloaded"
+    Server { status: u16, message: String },
+    /// Any other rejected request or unusable response
+    Api(String),
+}
+
+impl AiProviderError {
+    /// Whether trying again later can succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            AiProviderError::RateLimited { .. }
+                | AiProviderError::Network(_)
+                | AiProviderError::Server { .. }
+        )
+    }
+
+    /// Classify an unsuccessful HTTP response
+    pub fn from_status(status: StatusCode, message: String, retry_after: Option<Duration>) -> Self {
+        match status.as_u16() {
+            429 => AiProviderError::RateLimited {
+                message,
+                retry_after,
+            },
+            401 | 403 => AiProviderError::Auth(message),
+            code @ 500..=599 => AiProviderError::Server {
+                status: code,
+                message,
+            },
+            _ => AiProviderError::Api(message),
+        }
+    }
+}
+
+impl std::fmt::Display for AiProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AiProviderError::RateLimited { message, .. }
+            | AiProviderError::Auth(message)
+            | AiProviderError::Network(message)
+            | AiProviderError::Server { message, .. }
+            | AiProviderError::Api(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AiProviderError {}
+
+/// Parse and decode failures that need no classification
+impl From<String> for AiProviderError {
+    fn from(message: String) -> Self {
+        AiProviderError::Api(message)
+    }
+}
+
+impl From<AiProviderError> for DbError {
+    fn from(error: AiProviderError) -> Self {
+        let message = error.to_string();
+        match error {
+            AiProviderError::RateLimited { .. } => DbError::AiRateLimited(message),
+            AiProviderError::Auth(_) => DbError::AiAuthError(message),
+            AiProviderError::Network(_) => DbError::AiNetworkError(message),
+            AiProviderError::Server { .. } | AiProviderError::Api(_) => DbError::AiError(message),
+        }
+    }
+}
+
+/// Send a request, retrying rate limits, 5xx responses and connection errors
+///
+/// `build` is called for every attempt since a request builder is consumed
+/// by sending it. Returns the first successful response; after the last
+/// attempt, or on a failure that retrying cannot fix, returns the classified
+/// error with `provider` prefixed to its message.
+pub async fn send_with_retry<F>(
+    retry: &RetryConfig,
+    provider: &str,
+    mut build: F,
+) -> Result<Response, AiProviderError>
+where
+    F: FnMut() -> RequestBuilder,
+{
+    let mut attempt = 0;
+    loop {
+        let error = match build().send().await {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) => error_from_response(response, provider).await,
+            Err(e) => AiProviderError::Network(format!("Failed to reach {}: {}", provider, e)),
+        };
+
+        if !error.is_retryable() || attempt >= retry.max_retries {
+            return Err(error);
+        }
+        let retry_after = match &error {
+            AiProviderError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        };
+        tokio::time::sleep(backoff_delay(retry, attempt, retry_after, jitter())).await;
+        attempt += 1;
+    }
+}
+
+/// Classify a failed response, taking the message from its JSON body if any
+async fn error_from_response(response: Response, provider: &str) -> AiProviderError {
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| parse_retry_after(v, SystemTime::now()));
+    let body = response.text().await.unwrap_or_default();
+    let message = format!("{} API error: {}", provider, error_message(&body, status));
+    AiProviderError::from_status(status, message, retry_after)
+}
+
+/// Message of an error body
+///
+/// OpenAI, Anthropic, Gemini and OpenRouter all nest it as `error.message`;
+/// anything else falls back to the raw body or the status text.
+fn error_message(body: &str, status: StatusCode) -> String {
+    let json: Option<serde_json::Value> = serde_json::from_str(body).ok();
+    let nested = json.as_ref().and_then(|v| {
+        v.pointer("/error/message")
+            .or_else(|| v.get("error"))
+            .or_else(|| v.get("message"))
+            .and_then(|m| m.as_str())
+    });
+    match nested {
+        Some(message) => message.to_string(),
+        None if !body.trim().is_empty() && json.is_none() => {
+            body.trim().chars().take(300).collect()
+        }
+        None => status
+            .canonical_reason()
+            .unwrap_or("Unknown error")
+            .to_string(),
+    }
+}
+
+/// Parse a `Retry-After` value: delay seconds or an HTTP date
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<f64>() {
+        return (secs >= 0.0 && secs.is_finite()).then(|| Duration::from_secs_f64(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let at = SystemTime::from(at.with_timezone(&chrono::Utc));
+    Some(at.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+/// Delay before retry number `attempt` (0-based)
+///
+/// A `Retry-After` wait is used as given. Otherwise the delay is
+/// `base * 2^attempt`, of which a random half (`jitter` in `[0, 1)`) is
+/// applied so that clients hitting the same limit spread out. Both are capped
+/// at `max_delay_ms`.
+pub fn backoff_delay(
+    retry: &RetryConfig,
+    attempt: u32,
+    retry_after: Option<Duration>,
+    jitter: f64,
+) -> Duration {
+    let max = Duration::from_millis(retry.max_delay_ms);
+    if let Some(wait) = retry_after {
+        return wait.min(max);
+    }
+    let exponential = retry
+        .base_delay_ms
+        .saturating_mul(1u64 << attempt.min(20))
+        .min(retry.max_delay_ms);
+    let half = exponential as f64 / 2.0;
+    Duration::from_millis((half + half * jitter.clamp(0.0, 1.0)) as u64)
+}
+
+/// Random number in `[0, 1)` from the standard library's hasher seeds
+fn jitter() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_status() {
+        let rate_limited = AiProviderError::from_status(
+            StatusCode::TOO_MANY_REQUESTS,
+            "slow down".to_string(),
+            Some(Duration::from_secs(2)),
+        );
+        assert!(rate_limited.is_retryable());
+        assert!(matches!(
+            DbError::from(rate_limited),
+            DbError::AiRateLimited(_)
+        ));
+
+        let auth = AiProviderError::from_status(StatusCode::UNAUTHORIZED, "bad key".into(), None);
+        assert!(!auth.is_retryable());
+        assert!(matches!(DbError::from(auth), DbError::AiAuthError(_)));
+
+        let overloaded = AiProviderError::from_status(
+            StatusCode::from_u16(529).unwrap(),
+            "overloaded".into(),
+            None,
+        );
+        assert!(overloaded.is_retryable());
+
+        let bad_request =
+            AiProviderError::from_status(StatusCode::BAD_REQUEST, "bad model".into(), None);
+        assert!(!bad_request.is_retryable());
+        assert_eq!(bad_request, AiProviderError::Api("bad model".to_string()));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_445_412_470);
+        assert_eq!(parse_retry_after("7", now), Some(Duration::from_secs(7)));
+        assert_eq!(
+            parse_retry_after("0.5", now),
+            Some(Duration::from_millis(500))
+        );
+        // 2015-10-21 07:28:00 UTC is 10 seconds after `now`
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+        assert_eq!(parse_retry_after("-1", now), None);
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let retry = RetryConfig {
+            max_retries: 5,
+            base_delay_ms: 1_000,
+            max_delay_ms: 5_000,
+        };
+        assert_eq!(
+            backoff_delay(&retry, 0, None, 0.0),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            backoff_delay(&retry, 2, None, 0.999),
+            Duration::from_millis(3_998)
+        );
+        // Capped at max_delay_ms, both for backoff and Retry-After
+        assert_eq!(
+            backoff_delay(&retry, 10, None, 1.0),
+            Duration::from_millis(5_000)
+        );
+        assert_eq!(
+            backoff_delay(&retry, 0, Some(Duration::from_secs(60)), 0.5),
+            Duration::from_millis(5_000)
+        );
+        assert_eq!(
+            backoff_delay(&retry, 3, Some(Duration::from_secs(2)), 0.5),
+            Duration::from_secs(2)
+        );
+    }
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(
+            error_message(
+                r#"{"error":{"message":"Invalid API key","type":"auth"}}"#,
+                StatusCode::UNAUTHORIZED
+            ),
+            "Invalid API key"
+        );
+        assert_eq!(
+            error_message("upstream timeout", StatusCode::BAD_GATEWAY),
+            "upstream timeout"
+        );
+        assert_eq!(error_message("", StatusCode::BAD_GATEWAY), "Bad Gateway");
+    }
+}
@@ -0,0 +1,287 @@
+//! Schema Summaries for Prompts
+//!
+//! A full schema dump of a large database does not fit in a model's context
+//! window. This module renders a compact, one-line-per-table description and
+//! drops the least relevant tables once a token budget is used up.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
+
+use crate::models::{ColumnInfo, ForeignKeyInfo};
+
+/// Schemas that never belong in a prompt
+const SYSTEM_SCHEMAS: &[&str] = &[
+    "information_schema",
+    "pg_catalog",
+    "mysql",
+    "performance_schema",
+    "sys",
+];
+
+/// Tokens set aside for the note about omitted tables
+const OMISSION_NOTE_TOKENS: usize = 12;
+
+/// Schema text for a prompt, with what had to be left out
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaSummary {
+    /// One `schema.table(column type, ...)` line per included table
+    pub text: String,
+    /// Included tables as `schema.table`, most relevant first
+    pub included_tables: Vec<String>,
+    /// Tables that did not fit in the budget, most relevant first
+    pub omitted_tables: Vec<String>,
+    pub estimated_tokens: usize,
+}
+
+/// Rough token count: about four characters per token for English and SQL
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Whether `schema` holds catalog tables rather than user data
+pub fn is_system_schema(schema: &str) -> bool {
+    SYSTEM_SCHEMAS.contains(&schema.to_lowercase().as_str())
+}
+
+/// Summarize `columns` (keyed by `schema.table`) within `max_tokens`
+///
+/// Tables are ranked as: those named in `focus_tables` (as `table` or
+/// `schema.table`, case-insensitive), then tables reachable from them through
+/// foreign keys, nearest first, then everything else alphabetically. Tables
+/// are added in that order until the next one no longer fits.
+pub fn summarize_schema(
+    columns: &HashMap<String, Vec<ColumnInfo>>,
+    foreign_keys: &[ForeignKeyInfo],
+    focus_tables: &[String],
+    max_tokens: usize,
+) -> SchemaSummary {
+    let mut keys: Vec<&str> = columns
+        .keys()
+        .map(String::as_str)
+        .filter(|key| {
+            !key.split_once('.')
+                .is_some_and(|(s, _)| is_system_schema(s))
+        })
+        .collect();
+    keys.sort_unstable();
+
+    let mut neighbours: HashMap<String, Vec<String>> = HashMap::new();
+    let mut references: HashMap<String, Vec<&ForeignKeyInfo>> = HashMap::new();
+    for fk in foreign_keys {
+        let from = format!("{}.{}", fk.schema, fk.table);
+        let to = format!("{}.{}", fk.referenced_schema, fk.referenced_table);
+        neighbours.entry(from.clone()).or_default().push(to.clone());
+        neighbours.entry(to).or_default().push(from.clone());
+        references.entry(from).or_default().push(fk);
+    }
+
+    let order = rank_tables(&keys, &neighbours, focus_tables);
+    let lines: Vec<String> = order
+        .iter()
+        .map(|key| {
+            let fks = references.get(*key).map(Vec::as_slice).unwrap_or(&[]);
+            table_line(key, &columns[*key], fks)
+        })
+        .collect();
+
+    // Every line costs its own tokens plus one for the newline
+    let costs: Vec<usize> = lines.iter().map(|l| estimate_tokens(l) + 1).collect();
+    let budget = if costs.iter().sum::<usize>() <= max_tokens {
+        max_tokens
+    } else {
+        max_tokens.saturating_sub(OMISSION_NOTE_TOKENS)
+    };
+    let mut used = 0;
+    let fitting = costs
+        .iter()
+        .take_while(|cost| {
+            used += **cost;
+            used <= budget
+        })
+        .count();
+
+    let mut text = lines[..fitting].join("\n");
+    let omitted = order.len() - fitting;
+    if omitted > 0 {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&format!("-- {} more tables omitted", omitted));
+    }
+
+    SchemaSummary {
+        estimated_tokens: estimate_tokens(&text),
+        text,
+        included_tables: order[..fitting].iter().map(|k| k.to_string()).collect(),
+        omitted_tables: order[fitting..].iter().map(|k| k.to_string()).collect(),
+    }
+}
+
+/// Order `keys` by relevance to `focus_tables` (see [`summarize_schema`])
+fn rank_tables<'a>(
+    keys: &[&'a str],
+    neighbours: &HashMap<String, Vec<String>>,
+    focus_tables: &[String],
+) -> Vec<&'a str> {
+    let focus: HashSet<String> = focus_tables.iter().map(|t| t.to_lowercase()).collect();
+    let is_focus = |key: &str| {
+        let key = key.to_lowercase();
+        let table = key.split_once('.').map_or(key.as_str(), |(_, t)| t);
+        focus.contains(&key) || focus.contains(table)
+    };
+
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+    for key in keys.iter().copied().filter(|k| is_focus(k)) {
+        seen.insert(key);
+        queue.push_back(key);
+    }
+
+    // Breadth-first over foreign keys, so closer tables come first
+    let mut order = Vec::with_capacity(keys.len());
+    while let Some(key) = queue.pop_front() {
+        order.push(key);
+        let mut next: Vec<&str> = neighbours
+            .get(key)
+            .into_iter()
+            .flatten()
+            .filter_map(|n| keys.iter().copied().find(|k| *k == n.as_str()))
+            .filter(|k| !seen.contains(k))
+            .collect();
+        next.sort_unstable();
+        next.dedup();
+        for key in next {
+            seen.insert(key);
+            queue.push_back(key);
+        }
+    }
+
+    order.extend(keys.iter().copied().filter(|k| !seen.contains(k)));
+    order
+}
+
+/// `schema.table(id integer PK, owner_id integer -> schema.users.id, ...)`
+fn table_line(key: &str, columns: &[ColumnInfo], fks: &[&ForeignKeyInfo]) -> String {
+    let columns: Vec<String> = columns
+        .iter()
+        .map(|c| {
+            let mut col = format!("{} {}", c.name, c.data_type);
+            if c.is_primary_key {
+                col.push_str(" PK");
+            }
+            for fk in fks {
+                if let Some(i) = fk.columns.iter().position(|fc| *fc == c.name) {
+                    if let Some(target) = fk.referenced_columns.get(i) {
+                        col.push_str(&format!(
+                            " -> {}.{}.{}",
+                            fk.referenced_schema, fk.referenced_table, target
+                        ));
+                    }
+                }
+            }
+            col
+        })
+        .collect();
+    format!("{}({})", key, columns.join(", "))
+}
+
+/// Cut caller-supplied schema text down to `max_tokens`, at line boundaries
+pub fn truncate_context(context: &str, max_tokens: usize) -> String {
+    if estimate_tokens(context) <= max_tokens {
+        return context.to_string();
+    }
+
+    let budget = max_tokens.saturating_sub(OMISSION_NOTE_TOKENS);
+    let lines: Vec<&str> = context.lines().collect();
+    let mut used = 0;
+    let fitting = lines
+        .iter()
+        .take_while(|line| {
+            used += estimate_tokens(line) + 1;
+            used <= budget
+        })
+        .count();
+
+    let mut text = lines[..fitting].join("\n");
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text.push_str(&format!(
+        "-- schema truncated, {} more lines omitted",
+        lines.len() - fitting
+    ));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, pk: bool) -> ColumnInfo {
+        let mut c = ColumnInfo::new(name.to_string(), "integer".to_string(), !pk);
+        c.is_primary_key = pk;
+        c
+    }
+
+    fn schema() -> (HashMap<String, Vec<ColumnInfo>>, Vec<ForeignKeyInfo>) {
+        let mut columns = HashMap::new();
+        columns.insert(
+            "public.orders".to_string(),
+            vec![column("id", true), column("customer_id", false)],
+        );
+        columns.insert("public.customers".to_string(), vec![column("id", true)]);
+        columns.insert("public.audit".to_string(), vec![column("id", true)]);
+        columns.insert("pg_catalog.pg_class".to_string(), vec![column("oid", true)]);
+        let fks = vec![ForeignKeyInfo::new(
+            "orders_customer_fk".to_string(),
+            "orders".to_string(),
+            "public".to_string(),
+            vec!["customer_id".to_string()],
+            "customers".to_string(),
+            "public".to_string(),
+            vec!["id".to_string()],
+        )];
+        (columns, fks)
+    }
+
+    #[test]
+    fn test_summarize_schema_prioritizes_focus_and_related_tables() {
+        let (columns, fks) = schema();
+        let summary = summarize_schema(&columns, &fks, &["ORDERS".to_string()], 1_000);
+        assert_eq!(
+            summary.included_tables,
+            vec!["public.orders", "public.customers", "public.audit"]
+        );
+        assert!(summary.omitted_tables.is_empty());
+        assert_eq!(
+            summary.text.lines().next(),
+            Some("public.orders(id integer PK, customer_id integer -> public.customers.id)")
+        );
+    }
+
+    #[test]
+    fn test_summarize_schema_truncates_to_budget() {
+        let (columns, fks) = schema();
+        // Room for the orders line and the omission note only
+        let summary = summarize_schema(&columns, &fks, &["orders".to_string()], 32);
+        assert_eq!(summary.included_tables, vec!["public.orders"]);
+        assert_eq!(
+            summary.omitted_tables,
+            vec!["public.customers", "public.audit"]
+        );
+        assert!(summary.text.ends_with("-- 2 more tables omitted"));
+        assert!(summary.estimated_tokens <= 32);
+    }
+
+    #[test]
+    fn test_truncate_context() {
+        let context = "a.b(x int)\n".repeat(100);
+        assert_eq!(truncate_context("a.b(x int)", 100), "a.b(x int)");
+
+        let truncated = truncate_context(&context, 40);
+        assert!(estimate_tokens(&truncated) <= 40);
+        assert!(truncated.ends_with("more lines omitted"));
+    }
+}
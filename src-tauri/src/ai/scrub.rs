@@ -0,0 +1,182 @@
+//! Prompt Scrubbing
+//!
+//! Removes identifying details from text before it is sent to a hosted
+//! provider: server hostnames, IP addresses, email addresses and, in SQL,
+//! string literals (which usually hold sample data). Table and column names
+//! are kept since the model cannot help without them.
+
+use crate::sql::tokenizer::{tokenize, TokenKind};
+
+/// Replaces identifying details in prompt text
+#[derive(Debug, Clone, Default)]
+pub struct Scrubber {
+    /// Lowercased hostnames to hide, longest first
+    hosts: Vec<String>,
+}
+
+impl Scrubber {
+    /// Create a scrubber that also hides `hosts` (e.g. from connection profiles)
+    ///
+    /// Loopback names are skipped; they identify nothing.
+    pub fn new(hosts: impl IntoIterator<Item = String>) -> Self {
+        let mut hosts: Vec<String> = hosts
+            .into_iter()
+            .map(|h| h.trim().to_ascii_lowercase())
+            .filter(|h| !h.is_empty() && !matches!(h.as_str(), "localhost" | "127.0.0.1" | "::1"))
+            .collect();
+        hosts.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        hosts.dedup();
+        Self { hosts }
+    }
+
+    /// Scrub a SQL statement: string literals become `'<redacted>'`, then
+    /// the rest is scrubbed like [`Scrubber::text`]
+    pub fn sql(&self, sql: &str) -> String {
+        let mut out = String::with_capacity(sql.len());
+        let mut last = 0;
+        for token in tokenize(sql).filter(|t| t.kind == TokenKind::String) {
+            out.push_str(&sql[last..token.start]);
+            out.push_str("'<redacted>'");
+            last = token.start + token.text.len();
+        }
+        out.push_str(&sql[last..]);
+        self.text(&out)
+    }
+
+    /// Scrub free text: known hosts, IPv4 addresses and email addresses
+    pub fn text(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for host in &self.hosts {
+            text = replace_host(&text, host);
+        }
+        replace_emails(&replace_ipv4(&text))
+    }
+}
+
+fn is_host_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '.'
+}
+
+/// Replace whole-word, case-insensitive occurrences of `host` with `<host>`
+fn replace_host(text: &str, host: &str) -> String {
+    // ASCII lowercasing keeps byte offsets aligned with `text`
+    let lower = text.to_ascii_lowercase();
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut from = 0;
+    while let Some(pos) = lower[from..].find(host).map(|p| p + from) {
+        let end = pos + host.len();
+        let before = text[..pos].chars().next_back();
+        let after = text[end..].chars().next();
+        if !before.is_some_and(is_host_char) && !after.is_some_and(is_host_char) {
+            out.push_str(&text[last..pos]);
+            out.push_str("<host>");
+            last = end;
+        }
+        from = end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// Replace dotted-quad IPv4 addresses with `<ip>`
+fn replace_ipv4(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        let len = rest[start..]
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len() - start);
+        let candidate = &rest[start..start + len];
+        let preceded = rest[..start].chars().next_back().is_some_and(is_host_char);
+        out.push_str(&rest[..start]);
+        if !preceded && is_ipv4(candidate.trim_end_matches('.')) {
+            let addr = candidate.trim_end_matches('.');
+            out.push_str("<ip>");
+            out.push_str(&candidate[addr.len()..]);
+        } else {
+            out.push_str(candidate);
+        }
+        rest = &rest[start + len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_ipv4(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('.').collect();
+    parts.len() == 4
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.len() <= 3 && p.parse::<u16>().is_ok_and(|n| n <= 255))
+}
+
+/// Replace `local@domain.tld` addresses with `<email>`
+fn replace_emails(text: &str) -> String {
+    let is_local = |c: char| c.is_ascii_alphanumeric() || "._%+-".contains(c);
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for (at, _) in text.match_indices('@') {
+        if at < last {
+            continue;
+        }
+        let start = text[last..at]
+            .rfind(|c: char| !is_local(c))
+            .map_or(last, |i| last + i + 1);
+        let end = text[at + 1..]
+            .find(|c: char| !is_host_char(c))
+            .map_or(text.len(), |i| at + 1 + i);
+        let domain = text[at + 1..end].trim_end_matches('.');
+        let end = at + 1 + domain.len();
+        let valid_domain = domain
+            .rsplit_once('.')
+            .is_some_and(|(name, tld)| !name.is_empty() && tld.len() >= 2);
+        if start < at && valid_domain {
+            out.push_str(&text[last..start]);
+            out.push_str("<email>");
+            last = end;
+        }
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrub_sql() {
+        let scrubber = Scrubber::default();
+        assert_eq!(
+            scrubber.sql("SELECT * FROM users WHERE email = 'bob@example.com' AND id = 4"),
+            "SELECT * FROM users WHERE email = '<redacted>' AND id = 4"
+        );
+        // Quoted identifiers are names, not data
+        assert_eq!(
+            scrubber.sql(r#"SELECT "it's" FROM t"#),
+            r#"SELECT "it's" FROM t"#
+        );
+    }
+
+    #[test]
+    fn test_scrub_text() {
+        let scrubber = Scrubber::new(vec![
+            "DB.prod.internal".to_string(),
+            "localhost".to_string(),
+        ]);
+        assert_eq!(
+            scrubber.text(
+                "could not connect to db.prod.internal (10.0.3.17), ask ops@acme.io; localhost is fine"
+            ),
+            "could not connect to <host> (<ip>), ask <email>; localhost is fine"
+        );
+        // Partial matches and version numbers are left alone
+        assert_eq!(
+            scrubber.text("mydb.prod.internal.example runs 16.4.1.2.3"),
+            "mydb.prod.internal.example runs 16.4.1.2.3"
+        );
+        assert_eq!(scrubber.text("Server 192.168.1.300."), "Server 192.168.1.300.");
+        assert_eq!(scrubber.text("at 10.1.2.3."), "at <ip>.");
+    }
+}
@@ -3,11 +3,14 @@
 //! This module provides Tauri commands for retrieving query logs, calculating
 //! statistics, and managing activity data.
 
+use chrono::{DateTime, Utc};
 use std::sync::Mutex;
-use tauri::State;
+use tauri::{AppHandle, State};
 
+use crate::commands::settings::load_settings;
 use crate::models::{
-    ActivityStats, DbError, ExportFormat, QueryLogFilter, QueryLogResponse, QueryLogSort,
+    ActivityBucket, ActivityStats, DbError, ExportFormat, QueryLogFilter, QueryLogResponse,
+    QueryLogSort, SlowQueryGroup, TimeBucket,
 };
 use crate::state::AppState;
 
@@ -86,6 +89,108 @@ pub async fn get_activity_stats(
     Ok(stats)
 }
 
+/// Get activity aggregated into time buckets for charting
+///
+/// Every bucket in the range is returned, including empty ones, so the chart
+/// gets continuous points. Buckets are aligned to the caller's local time.
+///
+/// # Arguments
+///
+/// * `filter` - Filter criteria (optional); `startDate`/`endDate` set the range
+/// * `bucket` - Bucket size (hourly, daily, weekly)
+/// * `utc_offset_minutes` - Local offset from UTC in minutes, east positive (default 0)
+/// * `state` - Application state
+///
+/// # Returns
+///
+/// Buckets in chronological order
+///
+/// # Example
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const series = await invoke<ActivityBucket[]>('get_activity_timeseries', {
+///     filter: { startDate: weekAgo.toISOString(), endDate: now.toISOString() },
+///     bucket: 'daily',
+///     utcOffsetMinutes: -new Date().getTimezoneOffset()
+/// });
+/// ```
+#[tauri::command]
+pub async fn get_activity_timeseries(
+    filter: Option<QueryLogFilter>,
+    bucket: TimeBucket,
+    utc_offset_minutes: Option<i32>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<ActivityBucket>, DbError> {
+    let utc_offset_minutes = utc_offset_minutes.unwrap_or(0);
+    if utc_offset_minutes.abs() > 14 * 60 {
+        return Err(DbError::InvalidInput(format!(
+            "Invalid UTC offset: {} minutes",
+            utc_offset_minutes
+        )));
+    }
+
+    let state_guard = state.lock().unwrap();
+    let series = state_guard
+        .activity_logger
+        .get_timeseries(filter, bucket, utc_offset_minutes);
+    Ok(series)
+}
+
+/// Get the slowest queries, grouped by normalized SQL
+///
+/// Uses the `slowQueryThresholdMs` query setting to decide what counts as
+/// slow.
+///
+/// # Arguments
+///
+/// * `limit` - Maximum number of groups to return (default 20)
+/// * `since` - Only consider queries started at or after this time (ISO 8601, optional)
+/// * `state` - Application state
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// Slow query groups, worst 95th percentile first
+///
+/// # Example
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const slow = await invoke<SlowQueryGroup[]>('get_slow_queries', {
+///     limit: 10,
+///     since: lastWeek.toISOString()
+/// });
+/// ```
+#[tauri::command]
+pub async fn get_slow_queries(
+    limit: Option<usize>,
+    since: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<Vec<SlowQueryGroup>, DbError> {
+    let since = since
+        .map(|s| {
+            DateTime::parse_from_rfc3339(&s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|e| DbError::InvalidInput(format!("Invalid date '{}': {}", s, e)))
+        })
+        .transpose()?;
+    let threshold_ms = load_settings(&app)
+        .map(|s| s.query)
+        .unwrap_or_default()
+        .slow_query_threshold_ms;
+
+    let state_guard = state.lock().unwrap();
+    let slow =
+        state_guard
+            .activity_logger
+            .get_slow_queries(threshold_ms, since, limit.unwrap_or(20));
+    Ok(slow)
+}
+
 /// Clear all query logs
 ///
 /// # Arguments
@@ -4,17 +4,34 @@
 //! Supports multiple providers: Ollama, OpenAI, Anthropic, Google.
 
 use crate::ai::{
-    AiProvider, AiProviderType, AiModel as ProviderAiModel, ChatMessage, ChatCompletion,
+    AiProvider, AiProviderType, AiModel as ProviderAiModel, ChatMessage, ChatCompletion, ChatRole,
     OllamaProvider, OllamaConfig,
     OpenAiProvider, OpenAiConfig,
     AnthropicProvider, AnthropicConfig,
     GoogleAiProvider, GoogleAiConfig,
     OpenRouterProvider, OpenRouterConfig,
+    AiPrompts, PromptOperation, RetryConfig, SchemaSummary, Scrubber,
 };
-use crate::models::DbError;
+use crate::ai::prompts::{dialect_name, render};
+use crate::ai::schema_summary::{is_system_schema, summarize_schema, truncate_context};
+use crate::commands::schema::fetch_metadata_cache;
+use crate::models::{DbDriver, DbError};
+use crate::sql::tokenizer::{tokenize, TokenKind};
+use crate::state::{AppState, MetadataCache, QueryPriority};
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-use tauri::State;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+use tauri_plugin_store::StoreExt;
+
+/// Number of error explanations kept for reuse
+const ERROR_EXPLANATION_CACHE_SIZE: usize = 50;
+
+/// Most tables described to the model when explaining an error
+const MAX_CONTEXT_TABLES: usize = 20;
+
+/// Token budget for the schema part of SQL generation, optimization and fix prompts
+const SCHEMA_TOKEN_BUDGET: usize = 6_000;
 
 /// AI configuration for all providers
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -32,21 +49,64 @@ pub struct AiConfig {
     pub google: GoogleAiConfig,
     /// OpenRouter configuration
     pub openrouter: OpenRouterConfig,
+    /// Retry policy for the hosted providers (not Ollama)
+    #[serde(default)]
+    pub retry: RetryConfig,
+    /// Only use Ollama on this machine; requests to hosted providers are refused
+    #[serde(default)]
+    pub offline_mode: bool,
+    /// Strip hostnames, IP and email addresses and SQL string literals from
+    /// prompts sent to hosted providers. Queries the model returns then
+    /// contain `'<redacted>'` where the literals were.
+    #[serde(default)]
+    pub scrub_sensitive: bool,
 }
 
 /// AI Assistant state
 pub struct AiState {
     pub config: Mutex<AiConfig>,
+    /// System prompt templates, persisted in `ai_prompts.json`
+    pub prompts: Mutex<AiPrompts>,
+    /// Recent error explanations keyed by provider, model, SQL and error,
+    /// oldest first
+    pub error_explanations: Mutex<VecDeque<(String, AiErrorExplanation)>>,
 }
 
 impl Default for AiState {
     fn default() -> Self {
         Self {
             config: Mutex::new(AiConfig::default()),
+            prompts: Mutex::new(AiPrompts::default()),
+            error_explanations: Mutex::new(VecDeque::new()),
         }
     }
 }
 
+impl AiState {
+    /// Create the state with the prompt templates saved by the user
+    pub fn load(app: &AppHandle) -> Self {
+        let prompts = load_ai_prompts(app).unwrap_or_else(|e| {
+            eprintln!("Failed to load AI prompts, using defaults: {}", e);
+            AiPrompts::default()
+        });
+        Self {
+            prompts: Mutex::new(prompts),
+            ..Self::default()
+        }
+    }
+
+    /// Render the system prompt for `operation` from the current template
+    fn system_prompt(
+        &self,
+        operation: PromptOperation,
+        vars: &[(&str, &str)],
+    ) -> Result<String, DbError> {
+        let prompts = self.prompts.lock()
+            .map_err(|e| DbError::AiError(format!("Failed to access prompts: {}", e)))?;
+        Ok(render(prompts.template(operation), vars))
+    }
+}
+
 /// AI model information for frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -91,6 +151,60 @@ impl AiChatResponse {
     }
 }
 
+/// Diagnosis of a failed query
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiErrorExplanation {
+    pub explanation: String,
+    /// Corrected query, when the fix is a change to the query
+    pub suggested_sql: Option<String>,
+    pub model: String,
+    pub provider: AiProviderType,
+    pub duration_ms: u64,
+    /// Served from the cache without calling the provider
+    pub cached: bool,
+}
+
+/// Prompt template of one AI operation
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiPromptInfo {
+    pub operation: PromptOperation,
+    /// Template in effect
+    pub template: String,
+    pub default_template: String,
+    /// Whether `template` is a user override
+    pub is_custom: bool,
+    /// Placeholders the template may use
+    pub variables: Vec<String>,
+    /// Placeholders the template must contain
+    pub required_variables: Vec<String>,
+}
+
+impl AiPromptInfo {
+    fn new(prompts: &AiPrompts, operation: PromptOperation) -> Self {
+        Self {
+            operation,
+            template: prompts.template(operation).to_string(),
+            default_template: operation.default_template().to_string(),
+            is_custom: prompts.is_custom(operation),
+            variables: operation.variables().iter().map(|v| v.to_string()).collect(),
+            required_variables: operation
+                .required_variables()
+                .iter()
+                .map(|v| v.to_string())
+                .collect(),
+        }
+    }
+
+    fn all(prompts: &AiPrompts) -> Vec<Self> {
+        PromptOperation::ALL
+            .iter()
+            .map(|op| Self::new(prompts, *op))
+            .collect()
+    }
+}
+
 /// Provider status response
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -100,17 +214,84 @@ pub struct ProviderStatus {
     pub configured: bool,
 }
 
+/// Whether `url` points at this machine
+fn is_local_url(url: &str) -> bool {
+    let Some(host) = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_matches(['[', ']']).to_ascii_lowercase()))
+    else {
+        return false;
+    };
+    match host.parse::<std::net::IpAddr>() {
+        Ok(ip) => ip.is_loopback(),
+        Err(_) => host == "localhost" || host.ends_with(".localhost"),
+    }
+}
+
+/// Refuse `provider_type` if it would leave the machine while in offline mode
+fn check_offline(config: &AiConfig, provider_type: AiProviderType) -> Result<(), DbError> {
+    if !config.offline_mode {
+        return Ok(());
+    }
+    if provider_type != AiProviderType::Ollama {
+        return Err(DbError::InvalidInput(format!(
+            "{} is a hosted provider and offline mode is on; use Ollama or turn offline mode off",
+            provider_type
+        )));
+    }
+    if !is_local_url(&config.ollama.base_url) {
+        return Err(DbError::InvalidInput(format!(
+            "Offline mode only allows a local Ollama server, not {}",
+            config.ollama.base_url
+        )));
+    }
+    Ok(())
+}
+
 /// Get a provider instance based on the config
-fn get_provider(config: &AiConfig, provider_type: Option<AiProviderType>) -> Box<dyn AiProvider> {
+///
+/// Fails in offline mode for anything but a local Ollama server.
+fn get_provider(
+    config: &AiConfig,
+    provider_type: Option<AiProviderType>,
+) -> Result<Box<dyn AiProvider>, DbError> {
     let provider_type = provider_type.unwrap_or(config.active_provider);
+    check_offline(config, provider_type)?;
 
-    match provider_type {
+    Ok(match provider_type {
         AiProviderType::Ollama => Box::new(OllamaProvider::with_config(config.ollama.clone())),
-        AiProviderType::OpenAI => Box::new(OpenAiProvider::with_config(config.openai.clone())),
-        AiProviderType::Anthropic => Box::new(AnthropicProvider::with_config(config.anthropic.clone())),
-        AiProviderType::Google => Box::new(GoogleAiProvider::with_config(config.google.clone())),
-        AiProviderType::OpenRouter => Box::new(OpenRouterProvider::with_config(config.openrouter.clone())),
+        AiProviderType::OpenAI => Box::new(
+            OpenAiProvider::with_config(config.openai.clone()).with_retry(config.retry),
+        ),
+        AiProviderType::Anthropic => Box::new(
+            AnthropicProvider::with_config(config.anthropic.clone()).with_retry(config.retry),
+        ),
+        AiProviderType::Google => Box::new(
+            GoogleAiProvider::with_config(config.google.clone()).with_retry(config.retry),
+        ),
+        AiProviderType::OpenRouter => Box::new(
+            OpenRouterProvider::with_config(config.openrouter.clone()).with_retry(config.retry),
+        ),
+    })
+}
+
+/// Scrubber for prompts, when `scrub_sensitive` applies
+///
+/// Offline mode keeps prompts on this machine, so nothing is scrubbed then.
+/// The hosts of all saved connections (and their SSH tunnels) are hidden.
+fn prompt_scrubber(config: &AiConfig, app_state: &Mutex<AppState>) -> Option<Scrubber> {
+    if !config.scrub_sensitive || config.offline_mode {
+        return None;
     }
+    let app_state = app_state.lock().unwrap();
+    let hosts = app_state
+        .connection_profiles
+        .values()
+        .filter(|p| !matches!(p.driver, DbDriver::Sqlite))
+        .flat_map(|p| {
+            std::iter::once(p.host.clone()).chain(p.ssh_tunnel.as_ref().map(|t| t.host.clone()))
+        });
+    Some(Scrubber::new(hosts))
 }
 
 /// Check provider availability status
@@ -124,7 +305,7 @@ pub async fn check_ai_provider_status(
         .clone();
 
     let provider_type = provider.unwrap_or(config.active_provider);
-    let ai_provider = get_provider(&config, Some(provider_type));
+    let ai_provider = get_provider(&config, Some(provider_type))?;
 
     let available = ai_provider.is_available().await;
 
@@ -163,11 +344,20 @@ pub async fn get_ai_config(
 }
 
 /// Update AI configuration
+///
+/// With `offline_mode` set the active provider is switched to Ollama, and the
+/// configuration is rejected if the Ollama URL is not on this machine.
 #[tauri::command]
 pub async fn set_ai_config(
     state: State<'_, AiState>,
     config: AiConfig,
 ) -> Result<(), DbError> {
+    let mut config = config;
+    if config.offline_mode {
+        config.active_provider = AiProviderType::Ollama;
+        check_offline(&config, AiProviderType::Ollama)?;
+    }
+
     let mut current_config = state.config.lock()
         .map_err(|e| DbError::AiError(format!("Failed to access config: {}", e)))?;
     *current_config = config;
@@ -175,6 +365,8 @@ pub async fn set_ai_config(
 }
 
 /// Set the active AI provider
+///
+/// Hosted providers are rejected while offline mode is on.
 #[tauri::command]
 pub async fn set_active_ai_provider(
     state: State<'_, AiState>,
@@ -182,6 +374,7 @@ pub async fn set_active_ai_provider(
 ) -> Result<(), DbError> {
     let mut config = state.config.lock()
         .map_err(|e| DbError::AiError(format!("Failed to access config: {}", e)))?;
+    check_offline(&config, provider)?;
     config.active_provider = provider;
     Ok(())
 }
@@ -217,32 +410,156 @@ pub async fn list_ai_models(
         .map_err(|e| DbError::AiError(format!("Failed to access config: {}", e)))?
         .clone();
 
-    let ai_provider = get_provider(&config, provider);
+    let ai_provider = get_provider(&config, provider)?;
 
     let models = ai_provider.list_models().await
-        .map_err(|e| DbError::AiError(e))?;
+        .map_err(DbError::from)?;
 
     Ok(models.into_iter().map(AiModelInfo::from).collect())
 }
 
+/// Load the saved prompt overrides, falling back to the built-in templates
+fn load_ai_prompts(app: &AppHandle) -> Result<AiPrompts, DbError> {
+    let store = app
+        .store("ai_prompts.json")
+        .map_err(|e| DbError::InternalError(format!("Failed to access AI prompts store: {}", e)))?;
+
+    match store.get("prompts") {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| DbError::InternalError(format!("Failed to deserialize AI prompts: {}", e))),
+        None => Ok(AiPrompts::default()),
+    }
+}
+
+fn save_ai_prompts(app: &AppHandle, prompts: &AiPrompts) -> Result<(), DbError> {
+    let store = app
+        .store("ai_prompts.json")
+        .map_err(|e| DbError::InternalError(format!("Failed to access AI prompts store: {}", e)))?;
+
+    let value = serde_json::to_value(prompts)
+        .map_err(|e| DbError::InternalError(format!("Failed to serialize AI prompts: {}", e)))?;
+    store.set("prompts", value);
+    store
+        .save()
+        .map_err(|e| DbError::InternalError(format!("Failed to persist AI prompts: {}", e)))?;
+
+    Ok(())
+}
+
+/// Drop cached error explanations, which were produced with the old prompt
+fn clear_error_explanations(state: &AiState) {
+    if let Ok(mut cache) = state.error_explanations.lock() {
+        cache.clear();
+    }
+}
+
+/// Get the system prompt template of every AI operation
+#[tauri::command]
+pub async fn get_ai_prompts(
+    state: State<'_, AiState>,
+) -> Result<Vec<AiPromptInfo>, DbError> {
+    let prompts = state.prompts.lock()
+        .map_err(|e| DbError::AiError(format!("Failed to access prompts: {}", e)))?;
+    Ok(AiPromptInfo::all(&prompts))
+}
+
+/// Replace the system prompt template of an AI operation
+///
+/// The template must contain the operation's required placeholders (e.g.
+/// `{schema}` for SQL generation) and may only use the placeholders listed
+/// by `get_ai_prompts`; otherwise `DbError::InvalidInput` is returned and
+/// nothing is saved.
+///
+/// # Example
+///
+/// ```typescript
+/// await invoke('set_ai_prompt', {
+///     operation: 'generate_sql',
+///     template: 'Write {dialect} for this schema:\n{schema}\nAlways alias tables.'
+/// });
+/// ```
+#[tauri::command]
+pub async fn set_ai_prompt(
+    state: State<'_, AiState>,
+    app: AppHandle,
+    operation: PromptOperation,
+    template: String,
+) -> Result<AiPromptInfo, DbError> {
+    let mut prompts = state.prompts.lock()
+        .map_err(|e| DbError::AiError(format!("Failed to access prompts: {}", e)))?;
+
+    let mut updated = prompts.clone();
+    updated.set(operation, template).map_err(DbError::InvalidInput)?;
+    save_ai_prompts(&app, &updated)?;
+    *prompts = updated;
+    clear_error_explanations(&state);
+
+    Ok(AiPromptInfo::new(&prompts, operation))
+}
+
+/// Restore the built-in template of one operation, or of all when `operation` is omitted
+#[tauri::command]
+pub async fn reset_ai_prompts(
+    state: State<'_, AiState>,
+    app: AppHandle,
+    operation: Option<PromptOperation>,
+) -> Result<Vec<AiPromptInfo>, DbError> {
+    let mut prompts = state.prompts.lock()
+        .map_err(|e| DbError::AiError(format!("Failed to access prompts: {}", e)))?;
+
+    let mut updated = prompts.clone();
+    updated.reset(operation);
+    save_ai_prompts(&app, &updated)?;
+    *prompts = updated;
+    clear_error_explanations(&state);
+
+    Ok(AiPromptInfo::all(&prompts))
+}
+
 /// Generate SQL from natural language
+///
+/// `dialect` fills the `{dialect}` placeholder of the prompt template; it
+/// defaults to "standard SQL". The other AI commands take it the same way.
+///
+/// When `connection_id` is given the schema context is built from the
+/// connection's metadata with `summarize_schema_for_ai`, favouring tables the
+/// request mentions; otherwise the supplied `schema_context` is used, cut
+/// down to the token budget if needed. The optimize and fix commands work
+/// the same way.
 #[tauri::command]
 pub async fn ai_generate_sql(
     state: State<'_, AiState>,
+    app_state: State<'_, Mutex<AppState>>,
     prompt: String,
     schema_context: String,
     model: Option<String>,
     provider: Option<AiProviderType>,
+    dialect: Option<DbDriver>,
+    connection_id: Option<String>,
 ) -> Result<AiChatResponse, DbError> {
     let config = state.config.lock()
         .map_err(|e| DbError::AiError(format!("Failed to access config: {}", e)))?
         .clone();
+    let schema_context =
+        prompt_schema_context(&app_state, connection_id.as_deref(), &schema_context, &prompt).await;
+    let (prompt, schema_context) = match prompt_scrubber(&config, &app_state) {
+        Some(scrubber) => (scrubber.text(&prompt), scrubber.text(&schema_context)),
+        None => (prompt, schema_context),
+    };
+    let system_prompt = state.system_prompt(
+        PromptOperation::GenerateSql,
+        &[
+            ("dialect", dialect_name(dialect.as_ref())),
+            ("schema", &schema_context),
+            ("question", &prompt),
+        ],
+    )?;
 
-    let ai_provider = get_provider(&config, provider);
+    let ai_provider = get_provider(&config, provider)?;
 
     let start = std::time::Instant::now();
-    let sql = ai_provider.generate_sql(&prompt, &schema_context, model.as_deref()).await
-        .map_err(|e| DbError::AiError(e))?;
+    let sql = ai_provider.generate_sql(&system_prompt, &prompt, model.as_deref()).await
+        .map_err(DbError::from)?;
     let duration_ms = start.elapsed().as_millis() as u64;
 
     Ok(AiChatResponse {
@@ -257,19 +574,29 @@ pub async fn ai_generate_sql(
 #[tauri::command]
 pub async fn ai_explain_query(
     state: State<'_, AiState>,
+    app_state: State<'_, Mutex<AppState>>,
     sql: String,
     model: Option<String>,
     provider: Option<AiProviderType>,
+    dialect: Option<DbDriver>,
 ) -> Result<AiChatResponse, DbError> {
     let config = state.config.lock()
         .map_err(|e| DbError::AiError(format!("Failed to access config: {}", e)))?
         .clone();
+    let sql = match prompt_scrubber(&config, &app_state) {
+        Some(scrubber) => scrubber.sql(&sql),
+        None => sql,
+    };
+    let system_prompt = state.system_prompt(
+        PromptOperation::ExplainQuery,
+        &[("dialect", dialect_name(dialect.as_ref())), ("sql", &sql)],
+    )?;
 
-    let ai_provider = get_provider(&config, provider);
+    let ai_provider = get_provider(&config, provider)?;
 
     let start = std::time::Instant::now();
-    let explanation = ai_provider.explain_query(&sql, model.as_deref()).await
-        .map_err(|e| DbError::AiError(e))?;
+    let explanation = ai_provider.explain_query(&system_prompt, &sql, model.as_deref()).await
+        .map_err(DbError::from)?;
     let duration_ms = start.elapsed().as_millis() as u64;
 
     Ok(AiChatResponse {
@@ -284,20 +611,37 @@ pub async fn ai_explain_query(
 #[tauri::command]
 pub async fn ai_optimize_query(
     state: State<'_, AiState>,
+    app_state: State<'_, Mutex<AppState>>,
     sql: String,
     schema_context: String,
     model: Option<String>,
     provider: Option<AiProviderType>,
+    dialect: Option<DbDriver>,
+    connection_id: Option<String>,
 ) -> Result<AiChatResponse, DbError> {
     let config = state.config.lock()
         .map_err(|e| DbError::AiError(format!("Failed to access config: {}", e)))?
         .clone();
+    let schema_context =
+        prompt_schema_context(&app_state, connection_id.as_deref(), &schema_context, &sql).await;
+    let (sql, schema_context) = match prompt_scrubber(&config, &app_state) {
+        Some(scrubber) => (scrubber.sql(&sql), scrubber.text(&schema_context)),
+        None => (sql, schema_context),
+    };
+    let system_prompt = state.system_prompt(
+        PromptOperation::OptimizeQuery,
+        &[
+            ("dialect", dialect_name(dialect.as_ref())),
+            ("schema", &schema_context),
+            ("sql", &sql),
+        ],
+    )?;
 
-    let ai_provider = get_provider(&config, provider);
+    let ai_provider = get_provider(&config, provider)?;
 
     let start = std::time::Instant::now();
-    let optimization = ai_provider.optimize_query(&sql, &schema_context, model.as_deref()).await
-        .map_err(|e| DbError::AiError(e))?;
+    let optimization = ai_provider.optimize_query(&system_prompt, &sql, model.as_deref()).await
+        .map_err(DbError::from)?;
     let duration_ms = start.elapsed().as_millis() as u64;
 
     Ok(AiChatResponse {
@@ -312,21 +656,43 @@ pub async fn ai_optimize_query(
 #[tauri::command]
 pub async fn ai_fix_query(
     state: State<'_, AiState>,
+    app_state: State<'_, Mutex<AppState>>,
     sql: String,
     error_message: String,
     schema_context: String,
     model: Option<String>,
     provider: Option<AiProviderType>,
+    dialect: Option<DbDriver>,
+    connection_id: Option<String>,
 ) -> Result<AiChatResponse, DbError> {
     let config = state.config.lock()
         .map_err(|e| DbError::AiError(format!("Failed to access config: {}", e)))?
         .clone();
+    let schema_context =
+        prompt_schema_context(&app_state, connection_id.as_deref(), &schema_context, &sql).await;
+    let (sql, error_message, schema_context) = match prompt_scrubber(&config, &app_state) {
+        Some(scrubber) => (
+            scrubber.sql(&sql),
+            scrubber.text(&error_message),
+            scrubber.text(&schema_context),
+        ),
+        None => (sql, error_message, schema_context),
+    };
+    let system_prompt = state.system_prompt(
+        PromptOperation::FixQuery,
+        &[
+            ("dialect", dialect_name(dialect.as_ref())),
+            ("schema", &schema_context),
+            ("sql", &sql),
+            ("error", &error_message),
+        ],
+    )?;
 
-    let ai_provider = get_provider(&config, provider);
+    let ai_provider = get_provider(&config, provider)?;
 
     let start = std::time::Instant::now();
-    let fixed = ai_provider.fix_query(&sql, &error_message, &schema_context, model.as_deref()).await
-        .map_err(|e| DbError::AiError(e))?;
+    let fixed = ai_provider.fix_query(&system_prompt, &sql, &error_message, model.as_deref()).await
+        .map_err(DbError::from)?;
     let duration_ms = start.elapsed().as_millis() as u64;
 
     Ok(AiChatResponse {
@@ -337,23 +703,297 @@ pub async fn ai_fix_query(
     })
 }
 
+/// Explain why a query failed
+///
+/// Unlike `ai_fix_query`, which only returns a corrected query, this explains
+/// the error and adds a corrected query only when the fix lies in the query.
+/// Columns of the tables the query mentions are taken from the connection's
+/// metadata cache, if it has been loaded. Identical failures (same provider,
+/// model, SQL and error) are answered from a small in-memory cache.
+///
+/// `error_code` is the failed query's `sqlstate` or `errorCode`, if its
+/// `DbError` had one; it is added to the error text, which lets the model
+/// tell apart failures whose messages read alike.
+#[tauri::command]
+pub async fn ai_explain_error(
+    state: State<'_, AiState>,
+    app_state: State<'_, Mutex<AppState>>,
+    connection_id: String,
+    sql: String,
+    error_message: String,
+    error_code: Option<String>,
+    model: Option<String>,
+    provider: Option<AiProviderType>,
+) -> Result<AiErrorExplanation, DbError> {
+    let error_message = match error_code {
+        Some(code) => format!("{} (error code {})", error_message, code),
+        None => error_message,
+    };
+    let config = state.config.lock()
+        .map_err(|e| DbError::AiError(format!("Failed to access config: {}", e)))?
+        .clone();
+
+    let provider_type = provider.unwrap_or(config.active_provider);
+    let model_name = model.clone().unwrap_or_else(|| get_default_model(&config, provider));
+    let key = format!(
+        "{:?}\n{}\n{}\n{}",
+        provider_type,
+        model_name,
+        sql.trim(),
+        error_message.trim()
+    );
+
+    {
+        let cache = state.error_explanations.lock()
+            .map_err(|e| DbError::AiError(format!("Failed to access cache: {}", e)))?;
+        if let Some((_, hit)) = cache.iter().find(|(k, _)| *k == key) {
+            return Ok(AiErrorExplanation {
+                duration_ms: 0,
+                cached: true,
+                ..hit.clone()
+            });
+        }
+    }
+
+    let (schema_context, driver) = {
+        let app_state = app_state.lock().unwrap();
+        let schema_context = app_state
+            .metadata_cache
+            .get(&connection_id)
+            .map(|cache| schema_context_for(cache, &sql))
+            .unwrap_or_default();
+        let driver = app_state.get_profile(&connection_id).map(|p| p.driver.clone());
+        (schema_context, driver)
+    };
+    let (sql, error_message, schema_context) = match prompt_scrubber(&config, &app_state) {
+        Some(scrubber) => (
+            scrubber.sql(&sql),
+            scrubber.text(&error_message),
+            scrubber.text(&schema_context),
+        ),
+        None => (sql, error_message, schema_context),
+    };
+    let system_prompt = state.system_prompt(
+        PromptOperation::ExplainError,
+        &[
+            ("dialect", dialect_name(driver.as_ref())),
+            ("schema", &schema_context),
+            ("sql", &sql),
+            ("error", &error_message),
+        ],
+    )?;
+
+    let ai_provider = get_provider(&config, provider)?;
+
+    let start = std::time::Instant::now();
+    let (explanation, suggested_sql) = ai_provider
+        .explain_error(&system_prompt, &sql, &error_message, model.as_deref())
+        .await
+        .map_err(DbError::from)?;
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    let result = AiErrorExplanation {
+        explanation,
+        suggested_sql,
+        model: model_name,
+        provider: provider_type,
+        duration_ms,
+        cached: false,
+    };
+
+    let mut cache = state.error_explanations.lock()
+        .map_err(|e| DbError::AiError(format!("Failed to access cache: {}", e)))?;
+    if cache.len() >= ERROR_EXPLANATION_CACHE_SIZE {
+        cache.pop_front();
+    }
+    cache.push_back((key, result.clone()));
+
+    Ok(result)
+}
+
+/// Summarize a connection's schema for an AI prompt
+///
+/// Produces one compact line per table, with primary and foreign keys, and
+/// stops adding tables once `max_tokens` (estimated at four characters per
+/// token) is reached. Tables named in `focus_tables` come first, then tables
+/// linked to them by foreign keys, then the rest. The result lists which
+/// tables were included and which were left out.
+///
+/// Uses the connection's metadata cache, loading it if needed. Foreign keys
+/// are read per schema on a best-effort basis.
+///
+/// # Example
+///
+/// ```typescript
+/// const summary = await invoke<SchemaSummary>('summarize_schema_for_ai', {
+///     connectionId, maxTokens: 2000, focusTables: ['orders']
+/// });
+/// // summary.text: "public.orders(id integer PK, customer_id integer -> public.customers.id)\n..."
+/// ```
+#[tauri::command]
+pub async fn summarize_schema_for_ai(
+    app_state: State<'_, Mutex<AppState>>,
+    connection_id: String,
+    max_tokens: usize,
+    focus_tables: Option<Vec<String>>,
+) -> Result<SchemaSummary, DbError> {
+    build_schema_summary(&app_state, &connection_id, max_tokens, &focus_tables.unwrap_or_default())
+        .await
+}
+
+async fn build_schema_summary(
+    app_state: &State<'_, Mutex<AppState>>,
+    connection_id: &str,
+    max_tokens: usize,
+    focus_tables: &[String],
+) -> Result<SchemaSummary, DbError> {
+    let (connection, cached, database, scheduler) = {
+        let app_state = app_state.lock().unwrap();
+        let connection = app_state
+            .get_connection(connection_id)
+            .ok_or_else(|| DbError::ConnectionError("Connection not found".to_string()))?
+            .clone();
+        let cached = app_state
+            .metadata_cache
+            .get(connection_id)
+            .filter(|cache| !cache.is_stale())
+            .map(|cache| cache.columns.clone());
+        let database = app_state
+            .get_profile(connection_id)
+            .and_then(|p| p.database.clone())
+            .unwrap_or_default();
+        let scheduler = Arc::clone(&app_state.query_scheduler);
+        (connection, cached, database, scheduler)
+    };
+
+    let columns = match cached {
+        Some(columns) => columns,
+        None => {
+            let ticket = scheduler
+                .enter(connection_id, QueryPriority::Background)
+                .await;
+            let cache = fetch_metadata_cache(connection.clone(), &database, &ticket).await?;
+            let columns = cache.columns.clone();
+            app_state
+                .lock()
+                .unwrap()
+                .metadata_cache
+                .insert(connection_id.to_string(), cache);
+            columns
+        }
+    };
+
+    let mut schemas: Vec<&str> = columns
+        .keys()
+        .filter_map(|key| key.split_once('.').map(|(schema, _)| schema))
+        .filter(|schema| !is_system_schema(schema))
+        .collect();
+    schemas.sort_unstable();
+    schemas.dedup();
+
+    let mut foreign_keys = Vec::new();
+    for schema in schemas {
+        if let Ok(mut fks) = connection.get_foreign_keys(schema).await {
+            foreign_keys.append(&mut fks);
+        }
+    }
+
+    Ok(summarize_schema(&columns, &foreign_keys, focus_tables, max_tokens))
+}
+
+/// Schema context for a prompt, kept within `SCHEMA_TOKEN_BUDGET`
+///
+/// Summarizes the connection's schema around the words of `text` when a
+/// connection is given. Falls back to truncating the caller's context, so a
+/// large schema shortens the prompt instead of failing the request.
+async fn prompt_schema_context(
+    app_state: &State<'_, Mutex<AppState>>,
+    connection_id: Option<&str>,
+    provided: &str,
+    text: &str,
+) -> String {
+    if let Some(connection_id) = connection_id {
+        let words: Vec<String> = text
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|w| !w.is_empty())
+            .map(str::to_string)
+            .collect();
+        match build_schema_summary(app_state, connection_id, SCHEMA_TOKEN_BUDGET, &words).await {
+            Ok(summary) => return summary.text,
+            Err(e) => eprintln!("Failed to summarize schema for AI prompt: {}", e),
+        }
+    }
+    truncate_context(provided, SCHEMA_TOKEN_BUDGET)
+}
+
+/// Describe the cached tables that `sql` mentions, one `schema.table(column type, ...)` per line
+fn schema_context_for(cache: &MetadataCache, sql: &str) -> String {
+    let words: HashSet<String> = tokenize(sql)
+        .filter(|t| matches!(t.kind, TokenKind::Word | TokenKind::QuotedIdentifier))
+        .map(|t| t.text.trim_matches(|c| c == '"' || c == '`').to_lowercase())
+        .collect();
+
+    let mut tables: Vec<_> = cache
+        .columns
+        .iter()
+        .filter(|(key, _)| {
+            key.rsplit('.')
+                .next()
+                .is_some_and(|table| words.contains(&table.to_lowercase()))
+        })
+        .collect();
+    tables.sort_by_key(|(key, _)| key.as_str());
+
+    tables
+        .into_iter()
+        .take(MAX_CONTEXT_TABLES)
+        .map(|(key, columns)| {
+            let columns: Vec<String> = columns
+                .iter()
+                .map(|c| format!("{} {}", c.name, c.data_type))
+                .collect();
+            format!("{}({})", key, columns.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// General chat with the AI
+///
+/// The chat prompt template is added as the system message unless the
+/// conversation already starts with one. Messages are scrubbed as free text,
+/// so string literals in pasted SQL are kept.
 #[tauri::command]
 pub async fn ai_chat(
     state: State<'_, AiState>,
-    messages: Vec<ChatMessage>,
+    app_state: State<'_, Mutex<AppState>>,
+    mut messages: Vec<ChatMessage>,
     model: Option<String>,
     provider: Option<AiProviderType>,
+    dialect: Option<DbDriver>,
 ) -> Result<AiChatResponse, DbError> {
     let config = state.config.lock()
         .map_err(|e| DbError::AiError(format!("Failed to access config: {}", e)))?
         .clone();
 
-    let ai_provider = get_provider(&config, provider);
+    if messages.first().map_or(true, |m| m.role != ChatRole::System) {
+        let system_prompt = state.system_prompt(
+            PromptOperation::Chat,
+            &[("dialect", dialect_name(dialect.as_ref()))],
+        )?;
+        messages.insert(0, ChatMessage::system(system_prompt));
+    }
+    if let Some(scrubber) = prompt_scrubber(&config, &app_state) {
+        for message in &mut messages {
+            message.content = scrubber.text(&message.content);
+        }
+    }
+
+    let ai_provider = get_provider(&config, provider)?;
 
     let start = std::time::Instant::now();
     let completion = ai_provider.chat(messages, model.as_deref(), Some(0.7), None).await
-        .map_err(|e| DbError::AiError(e))?;
+        .map_err(DbError::from)?;
     let duration_ms = start.elapsed().as_millis() as u64;
 
     Ok(AiChatResponse::from_completion(completion, duration_ms))
@@ -369,3 +1009,51 @@ fn get_default_model(config: &AiConfig, provider: Option<AiProviderType>) -> Str
         AiProviderType::OpenRouter => config.openrouter.default_model.clone(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ColumnInfo;
+
+    #[test]
+    fn test_schema_context_for() {
+        let mut cache = MetadataCache::new();
+        cache.columns.insert(
+            "public.users".to_string(),
+            vec![
+                ColumnInfo::new("id".to_string(), "integer".to_string(), false),
+                ColumnInfo::new("email".to_string(), "text".to_string(), false),
+            ],
+        );
+        cache.columns.insert(
+            "public.orders".to_string(),
+            vec![ColumnInfo::new("id".to_string(), "integer".to_string(), false)],
+        );
+
+        assert_eq!(
+            schema_context_for(&cache, "SELECT emial FROM \"Users\" WHERE id = 1"),
+            "public.users(id integer, email text)"
+        );
+        assert_eq!(schema_context_for(&cache, "SELECT 1"), "");
+    }
+
+    #[test]
+    fn test_check_offline() {
+        let mut config = AiConfig::default();
+        assert!(check_offline(&config, AiProviderType::OpenAI).is_ok());
+
+        config.offline_mode = true;
+        assert!(check_offline(&config, AiProviderType::Ollama).is_ok());
+        assert!(matches!(
+            check_offline(&config, AiProviderType::Anthropic),
+            Err(DbError::InvalidInput(_))
+        ));
+
+        for url in ["http://127.0.0.1:11434", "http://[::1]:11434", "http://ollama.localhost"] {
+            config.ollama.base_url = url.to_string();
+            assert!(check_offline(&config, AiProviderType::Ollama).is_ok(), "{}", url);
+        }
+        config.ollama.base_url = "http://gpu-box.lan:11434".to_string();
+        assert!(check_offline(&config, AiProviderType::Ollama).is_err());
+    }
+}
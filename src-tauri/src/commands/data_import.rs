@@ -3,14 +3,25 @@
 //! Provides commands for importing CSV and Excel files into database tables
 //! with column mapping support.
 
-use crate::models::DbError;
+use crate::ddl::get_ddl_generator;
+use crate::drivers::{DatabaseDriver, StatementCallback};
+use crate::models::ddl::{ColumnDefinition, ColumnType, TableDefinition};
+use crate::models::{ColumnInfo, DbDriver, DbError};
+use crate::sql::literal::escape_literal;
 use crate::state::AppState;
 use calamine::{open_workbook, Reader, Xlsx, Xls};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::sync::Mutex;
-use tauri::State;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, State};
+
+/// Event emitted every `batch_size` rows of `import_data_to_table`, and once
+/// more when it ends
+pub const IMPORT_PROGRESS_EVENT: &str = "import-progress";
 
 /// Preview data from a file (first N rows)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +38,45 @@ pub struct ImportPreview {
     pub file_type: String,
     /// Sheet names (for Excel files)
     pub sheet_names: Option<Vec<String>>,
+    /// Dialect the CSV was read with, detected unless given (for CSV files)
+    #[serde(default)]
+    pub dialect: Option<CsvDialect>,
+}
+
+/// How a CSV file is delimited and quoted
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CsvDialect {
+    pub delimiter: char,
+    pub quote: char,
+    /// Character escaping the quote inside quoted fields; `None` when
+    /// quotes are escaped by doubling them
+    pub escape: Option<char>,
+    pub has_header: bool,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            escape: None,
+            has_header: true,
+        }
+    }
+}
+
+impl CsvDialect {
+    fn reader_builder(&self) -> csv::ReaderBuilder {
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter as u8)
+            .quote(self.quote as u8)
+            .escape(self.escape.map(|c| c as u8))
+            .double_quote(self.escape.is_none())
+            .has_headers(self.has_header)
+            .flexible(true);
+        builder
+    }
 }
 
 /// Column mapping configuration
@@ -36,7 +86,8 @@ pub struct ColumnMapping {
     pub source_column: String,
     /// Target column name in the database table
     pub target_column: String,
-    /// Target data type (for type conversion)
+    /// Target data type (for type conversion); overrides the detected type
+    /// when the import creates the table
     pub target_type: Option<String>,
     /// Default value if source is empty
     pub default_value: Option<String>,
@@ -56,7 +107,8 @@ pub struct DataImportOptions {
     pub column_mappings: Vec<ColumnMapping>,
     /// Number of rows to skip (e.g., for headers)
     pub skip_rows: usize,
-    /// Whether to create the table if it doesn't exist
+    /// Whether to create the table if it doesn't exist, with columns
+    /// inferred from the file
     pub create_table: bool,
     /// Whether to truncate the table before import
     pub truncate_before: bool,
@@ -64,10 +116,33 @@ pub struct DataImportOptions {
     pub batch_size: usize,
     /// CSV delimiter (for CSV files)
     pub delimiter: Option<char>,
+    /// CSV quote character, `"` if not given
+    #[serde(default)]
+    pub quote: Option<char>,
+    /// CSV escape character for quotes, if they aren't escaped by doubling
+    #[serde(default)]
+    pub escape: Option<char>,
     /// Sheet name (for Excel files)
     pub sheet_name: Option<String>,
     /// Whether first row is header
     pub first_row_is_header: bool,
+    /// Insert inside transactions, so a failed batch leaves nothing behind
+    #[serde(default)]
+    pub use_transaction: bool,
+    /// With `use_transaction`, commit after every `batch_size` rows instead
+    /// of once at the end
+    #[serde(default)]
+    pub commit_each_batch: bool,
+    /// Keep importing after a row fails. A failed transactional batch is
+    /// then retried row by row outside a transaction. Without it the import
+    /// stops at the first failure, and its batch is rolled back.
+    #[serde(default = "default_continue_on_error")]
+    pub continue_on_error: bool,
+}
+
+/// Imports used to always carry on past failed rows
+fn default_continue_on_error() -> bool {
+    true
 }
 
 /// Import result
@@ -82,14 +157,66 @@ pub struct ImportResult {
     pub errors: Vec<String>,
     /// Whether the import completed successfully
     pub success: bool,
+    /// Transactions committed (0 without `use_transaction`)
+    #[serde(default)]
+    pub batches_committed: usize,
+    /// Whether `cancel_import` stopped the import
+    #[serde(default)]
+    pub cancelled: bool,
+    /// Mapped target columns left out because the database generates them
+    #[serde(default)]
+    pub skipped_generated: Vec<String>,
+}
+
+/// Payload of `import-progress`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProgressEvent {
+    pub import_id: String,
+    /// Rows run so far, including failed ones and ones in a transaction
+    /// that isn't committed yet
+    pub rows_processed: usize,
+    pub rows_failed: usize,
+    pub total_rows: usize,
+    /// Estimated time left, from the rate so far
+    pub eta_ms: Option<u64>,
+    /// Whether this is the last event of the import
+    pub done: bool,
+    pub cancelled: bool,
+}
+
+fn emit_progress(app: &AppHandle, progress: ImportProgressEvent) {
+    if let Err(e) = app.emit(IMPORT_PROGRESS_EVENT, progress) {
+        eprintln!("Failed to emit {} event: {}", IMPORT_PROGRESS_EVENT, e);
+    }
+}
+
+/// Removes the import's cancel flag however `import_data_to_table` exits
+struct ActiveImportGuard<'a> {
+    state: &'a Mutex<AppState>,
+    import_id: String,
+}
+
+impl Drop for ActiveImportGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.active_imports.remove(&self.import_id);
+        }
+    }
 }
 
 /// Preview a file for import
+///
+/// For CSV files the delimiter, quote character, escape and header row are
+/// detected from the start of the file; `delimiter`, `quote` and
+/// `first_row_is_header` override the detected values.
 #[tauri::command]
 pub async fn preview_import_file(
     file_path: String,
     sheet_name: Option<String>,
     delimiter: Option<char>,
+    quote: Option<char>,
+    first_row_is_header: Option<bool>,
     max_rows: Option<usize>,
 ) -> Result<ImportPreview, String> {
     let path = Path::new(&file_path);
@@ -102,7 +229,10 @@ pub async fn preview_import_file(
     let max_rows = max_rows.unwrap_or(100);
 
     match extension.as_str() {
-        "csv" | "tsv" | "txt" => preview_csv(&file_path, delimiter, max_rows).map_err(|e| e.to_string()),
+        "csv" | "tsv" | "txt" => {
+            preview_csv(&file_path, delimiter, quote, first_row_is_header, max_rows)
+                .map_err(|e| e.to_string())
+        }
         "xlsx" => preview_xlsx(&file_path, sheet_name, max_rows).map_err(|e| e.to_string()),
         "xls" => preview_xls(&file_path, sheet_name, max_rows).map_err(|e| e.to_string()),
         _ => Err(format!("Unsupported file type: {}", extension)),
@@ -110,26 +240,37 @@ pub async fn preview_import_file(
 }
 
 /// Preview CSV file
-fn preview_csv(file_path: &str, delimiter: Option<char>, max_rows: usize) -> Result<ImportPreview, DbError> {
-    let delimiter = delimiter.unwrap_or(',') as u8;
+fn preview_csv(
+    file_path: &str,
+    delimiter: Option<char>,
+    quote: Option<char>,
+    first_row_is_header: Option<bool>,
+    max_rows: usize,
+) -> Result<ImportPreview, DbError> {
+    let sample = read_sample(file_path)?;
+    let mut dialect = sniff_csv_dialect(&sample, delimiter, quote);
+    if let Some(has_header) = first_row_is_header {
+        dialect.has_header = has_header;
+    }
 
-    let mut reader = csv::ReaderBuilder::new()
-        .delimiter(delimiter)
-        .has_headers(true)
-        .flexible(true)
+    let mut reader = dialect
+        .reader_builder()
         .from_path(file_path)
         .map_err(|e| DbError::ImportError(format!("Failed to open CSV: {}", e)))?;
 
     // Get headers
-    let headers = reader
-        .headers()
-        .map_err(|e| DbError::ImportError(format!("Failed to read headers: {}", e)))?
-        .iter()
-        .map(|s| s.to_string())
-        .collect::<Vec<_>>();
+    let mut headers = if dialect.has_header {
+        reader
+            .headers()
+            .map_err(|e| DbError::ImportError(format!("Failed to read headers: {}", e)))?
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
 
     let mut rows: Vec<Vec<String>> = Vec::new();
-    let mut type_samples: Vec<Vec<String>> = vec![Vec::new(); headers.len()];
 
     for (i, result) in reader.records().enumerate() {
         if i >= max_rows {
@@ -137,24 +278,29 @@ fn preview_csv(file_path: &str, delimiter: Option<char>, max_rows: usize) -> Res
         }
 
         match result {
-            Ok(record) => {
-                let row: Vec<String> = record.iter().map(|s| s.to_string()).collect();
-
-                // Collect samples for type detection
-                for (j, value) in row.iter().enumerate() {
-                    if j < type_samples.len() && !value.is_empty() {
-                        type_samples[j].push(value.clone());
-                    }
-                }
-
-                rows.push(row);
-            }
+            Ok(record) => rows.push(record.iter().map(|s| s.to_string()).collect()),
             Err(e) => {
                 eprintln!("Error reading row {}: {}", i, e);
             }
         }
     }
 
+    // Same names `read_csv_data` gives a file without a header row
+    if !dialect.has_header {
+        let width = rows.first().map_or(0, |row| row.len());
+        headers = (0..width).map(|i| format!("Column{}", i + 1)).collect();
+    }
+
+    // Collect samples for type detection
+    let mut type_samples: Vec<Vec<String>> = vec![Vec::new(); headers.len()];
+    for row in &rows {
+        for (j, value) in row.iter().enumerate() {
+            if j < type_samples.len() && !value.is_empty() {
+                type_samples[j].push(value.clone());
+            }
+        }
+    }
+
     // Detect types
     let detected_types = type_samples
         .iter()
@@ -168,9 +314,189 @@ fn preview_csv(file_path: &str, delimiter: Option<char>, max_rows: usize) -> Res
         detected_types,
         file_type: "csv".to_string(),
         sheet_names: None,
+        dialect: Some(dialect),
     })
 }
 
+/// Bytes read from the start of a CSV file to detect its dialect
+const SNIFF_BYTES: u64 = 16 * 1024;
+
+/// Delimiters `sniff_csv_dialect` chooses from, in order of preference
+const CANDIDATE_DELIMITERS: [char; 4] = [',', '\t', ';', '|'];
+
+/// Records of the sample used to pick the delimiter and header
+const SNIFF_RECORDS: usize = 20;
+
+/// Start of a CSV file as text, cut after its last complete line
+fn read_sample(file_path: &str) -> Result<String, DbError> {
+    use std::io::Read;
+
+    let file = std::fs::File::open(file_path)
+        .map_err(|e| DbError::ImportError(format!("Failed to open CSV: {}", e)))?;
+    let mut bytes = Vec::new();
+    file.take(SNIFF_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|e| DbError::ImportError(format!("Failed to read CSV: {}", e)))?;
+
+    if bytes.len() as u64 == SNIFF_BYTES {
+        if let Some(end) = bytes.iter().rposition(|&b| b == b'\n') {
+            bytes.truncate(end + 1);
+        }
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    Ok(text.strip_prefix('\u{feff}').unwrap_or(&text).to_string())
+}
+
+/// Detect the dialect of a CSV sample
+///
+/// The delimiter is the candidate whose count per record is the most
+/// consistent (ties go to the higher count, then to the candidate listed
+/// first); the quote is whichever of `"` and `'` opens more fields. The
+/// first record is taken as a header when its values don't fit the types
+/// of the records below it (`id` above a column of integers), or, with only
+/// text columns, when its values are all present and distinct. `delimiter`
+/// and `quote` are used as given when set.
+fn sniff_csv_dialect(sample: &str, delimiter: Option<char>, quote: Option<char>) -> CsvDialect {
+    let quote = quote.unwrap_or_else(|| detect_quote(sample));
+    let delimiter = delimiter.unwrap_or_else(|| detect_delimiter(sample, quote));
+    let escape = sample.contains(&format!("\\{}", quote)).then_some('\\');
+
+    let mut dialect = CsvDialect {
+        delimiter,
+        quote,
+        escape,
+        has_header: true,
+    };
+    let records: Vec<Vec<String>> = dialect
+        .reader_builder()
+        .has_headers(false)
+        .from_reader(sample.as_bytes())
+        .records()
+        .take(SNIFF_RECORDS)
+        .filter_map(|r| r.ok())
+        .map(|r| r.iter().map(|v| v.to_string()).collect())
+        .collect();
+    dialect.has_header = detect_header(&records);
+    dialect
+}
+
+/// `"` unless `'` opens more fields
+fn detect_quote(sample: &str) -> char {
+    let opens = |quote: char| {
+        let mut previous = '\n';
+        let mut count = 0;
+        for c in sample.chars() {
+            if c == quote && (previous == '\n' || CANDIDATE_DELIMITERS.contains(&previous)) {
+                count += 1;
+            }
+            previous = c;
+        }
+        count
+    };
+    if opens('\'') > opens('"') {
+        '\''
+    } else {
+        '"'
+    }
+}
+
+/// Candidate delimiter that splits the sample's records most consistently
+fn detect_delimiter(sample: &str, quote: char) -> char {
+    let mut best = (',', 0.0, 0);
+    for &delimiter in &CANDIDATE_DELIMITERS {
+        let counts = delimiter_counts(sample, delimiter, quote);
+        // Most common count per record, and the share of records with it
+        let mut frequency: HashMap<usize, usize> = HashMap::new();
+        for &count in &counts {
+            *frequency.entry(count).or_default() += 1;
+        }
+        let Some((mode, records)) = frequency
+            .into_iter()
+            .filter(|&(count, _)| count > 0)
+            .max_by_key(|&(count, records)| (records, count))
+        else {
+            continue;
+        };
+        let consistency = records as f64 / counts.len() as f64;
+        if (consistency, mode) > (best.1, best.2) {
+            best = (delimiter, consistency, mode);
+        }
+    }
+    best.0
+}
+
+/// Number of unquoted `delimiter`s in each non-empty record of the sample
+fn delimiter_counts(sample: &str, delimiter: char, quote: char) -> Vec<usize> {
+    let mut counts = Vec::new();
+    let mut in_quotes = false;
+    let mut count = 0;
+    let mut empty = true;
+    for c in sample.chars() {
+        match c {
+            _ if c == quote => in_quotes = !in_quotes,
+            _ if in_quotes => {}
+            '\n' => {
+                if !empty {
+                    counts.push(count);
+                    if counts.len() == SNIFF_RECORDS {
+                        return counts;
+                    }
+                }
+                count = 0;
+                empty = true;
+                continue;
+            }
+            '\r' => continue,
+            _ if c == delimiter => count += 1,
+            _ => {}
+        }
+        empty = false;
+    }
+    if !empty {
+        counts.push(count);
+    }
+    counts
+}
+
+/// Whether the first of `records` looks like a header row
+fn detect_header(records: &[Vec<String>]) -> bool {
+    let Some((first, rest)) = records.split_first() else {
+        return true;
+    };
+    if rest.is_empty() {
+        return true;
+    }
+
+    // +1 for each typed column whose first value doesn't fit the type of
+    // the values below it, -1 for each where it does
+    let mut votes = 0;
+    for (j, value) in first.iter().enumerate() {
+        let samples: Vec<String> = rest
+            .iter()
+            .filter_map(|row| row.get(j))
+            .filter(|v| !v.is_empty())
+            .cloned()
+            .collect();
+        let column_type = detect_column_type(&samples);
+        if samples.is_empty() || column_type == "TEXT" || value.is_empty() {
+            continue;
+        }
+        let mut with_first = samples;
+        with_first.push(value.clone());
+        if detect_column_type(&with_first) == column_type {
+            votes -= 1;
+        } else {
+            votes += 1;
+        }
+    }
+
+    if votes != 0 {
+        return votes > 0;
+    }
+    let distinct: HashSet<&String> = first.iter().collect();
+    first.iter().all(|v| !v.trim().is_empty()) && distinct.len() == first.len()
+}
+
 /// Preview XLSX file
 fn preview_xlsx(file_path: &str, sheet_name: Option<String>, max_rows: usize) -> Result<ImportPreview, DbError> {
     let mut workbook: Xlsx<_> = open_workbook(file_path)
@@ -220,6 +546,7 @@ fn preview_xlsx(file_path: &str, sheet_name: Option<String>, max_rows: usize) ->
                 detected_types,
                 file_type: "xlsx".to_string(),
                 sheet_names: Some(sheet_names),
+                dialect: None,
             })
         }
         Err(e) => Err(DbError::ImportError(format!("Failed to read sheet '{}': {}", sheet, e))),
@@ -273,6 +600,7 @@ fn preview_xls(file_path: &str, sheet_name: Option<String>, max_rows: usize) ->
                 detected_types,
                 file_type: "xls".to_string(),
                 sheet_names: Some(sheet_names),
+                dialect: None,
             })
         }
         Err(e) => Err(DbError::ImportError(format!("Failed to read sheet '{}': {}", sheet, e))),
@@ -364,46 +692,57 @@ fn detect_column_type(samples: &[String]) -> String {
 }
 
 /// Import data from a file into a database table
+///
+/// Emits `import-progress` every `batch_size` rows. The import can be
+/// stopped with `cancel_import(import_id)`; a random ID is used if none is
+/// given, in which case it can't be cancelled.
 #[tauri::command]
 pub async fn import_data_to_table(
     connection_id: String,
     file_path: String,
-    options: DataImportOptions,
+    mut options: DataImportOptions,
+    import_id: Option<String>,
     state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
 ) -> Result<ImportResult, String> {
-    // Read file data
-    let path = Path::new(&file_path);
-    let extension = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-
-    let (columns, rows) = match extension.as_str() {
-        "csv" | "tsv" | "txt" => read_csv_data(&file_path, options.delimiter, options.skip_rows, options.first_row_is_header)
-            .map_err(|e| e.to_string())?,
-        "xlsx" => read_xlsx_data(&file_path, options.sheet_name.as_deref(), options.skip_rows, options.first_row_is_header)
-            .map_err(|e| e.to_string())?,
-        "xls" => read_xls_data(&file_path, options.sheet_name.as_deref(), options.skip_rows, options.first_row_is_header)
-            .map_err(|e| e.to_string())?,
-        _ => return Err(format!("Unsupported file type: {}", extension)),
+    let import_id = import_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut state = state.lock().map_err(|e| e.to_string())?;
+        if state.active_imports.contains_key(&import_id) {
+            return Err(format!("Import {} is already running", import_id));
+        }
+        state
+            .active_imports
+            .insert(import_id.clone(), Arc::clone(&cancel));
+    }
+    let _guard = ActiveImportGuard {
+        state: state.inner(),
+        import_id: import_id.clone(),
     };
 
-    // Build column mapping (used for potential future enhancements)
-    let _mapping: HashMap<String, &ColumnMapping> = options
-        .column_mappings
-        .iter()
-        .filter(|m| !m.skip)
-        .map(|m| (m.source_column.clone(), m))
-        .collect();
+    // Read file data
+    let (columns, rows) = read_file_data(&file_path, &options).map_err(|e| e.to_string())?;
 
     // Get connection
-    let connection = {
+    let (connection, driver, schema) = {
         let state = state.lock().map_err(|e| e.to_string())?;
-        state
+        let connection = state
             .get_connection(&connection_id)
             .ok_or_else(|| format!("Connection not found: {}", connection_id))?
-            .clone()
+            .clone();
+        let driver = state
+            .connection_profiles
+            .get(&connection_id)
+            .map(|p| p.driver.clone())
+            .ok_or_else(|| format!("Connection profile not found: {}", connection_id))?;
+        if options.schema.is_none() {
+            options.schema = state.default_schema(&connection_id);
+        }
+        let schema = state
+            .resolve_schema(&connection_id, options.schema.as_deref())
+            .map_err(|e| e.to_string())?;
+        (connection, driver, schema)
     };
 
     // Build table name with schema, quoting identifiers per dialect to
@@ -411,6 +750,25 @@ pub async fn import_data_to_table(
     if options.table_name.trim().is_empty() {
         return Err("Table name cannot be empty".to_string());
     }
+
+    // Create the table from the file's columns if requested and missing.
+    // This renames mapping targets to the sanitized column names.
+    if options.create_table
+        && !table_exists(&connection, &schema, &options.table_name)
+            .await
+            .map_err(|e| e.to_string())?
+    {
+        let table = build_import_table(&mut options, &columns, &rows).map_err(|e| e.to_string())?;
+        let ddl = get_ddl_generator(&driver)
+            .and_then(|generator| generator.generate_create_table(&table))
+            .map_err(|e| e.to_string())?;
+        for sql in &ddl.sql {
+            connection
+                .execute_query(sql)
+                .await
+                .map_err(|e| format!("Failed to create table: {}", e))?;
+        }
+    }
     let full_table_name = if let Some(schema) = &options.schema {
         format!(
             "{}.{}",
@@ -430,6 +788,15 @@ pub async fn import_data_to_table(
             .map_err(|e| format!("Failed to truncate table: {}", e))?;
     }
 
+    // Generated columns can't be inserted into; leave them to the database
+    // rather than failing every row. Introspection errors are not fatal, the
+    // INSERTs will report any real problem with the table.
+    let skipped_generated = connection
+        .get_table_schema(&schema, &options.table_name)
+        .await
+        .map(|table| skip_generated_columns(&mut options.column_mappings, &table.columns))
+        .unwrap_or_default();
+
     // Build INSERT column list, quoting identifiers per dialect
     let target_columns: Vec<String> = options
         .column_mappings
@@ -441,19 +808,18 @@ pub async fn import_data_to_table(
         return Err("No columns mapped for import".to_string());
     }
 
-    // Note: Using string formatting for VALUES instead of prepared statements
-    // for cross-database compatibility (placeholder syntax differs). Values
-    // are escaped per dialect via escape_string_literal to prevent SQL
-    // injection from untrusted file contents.
+    let insert = RowInsert::new(
+        connection.as_ref(),
+        driver,
+        &full_table_name,
+        &target_columns,
+    );
 
-    let mut rows_imported = 0;
-    let mut rows_failed = 0;
-    let mut errors: Vec<String> = Vec::new();
-
-    // Import in batches
+    // Collect each row's values, keeping its 1-based row number for errors
+    let mut statements: Vec<(usize, Vec<Value>)> = Vec::with_capacity(rows.len());
     for (row_idx, row) in rows.iter().enumerate() {
         // Build values based on mapping
-        let mut values: Vec<String> = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
 
         for col_mapping in options.column_mappings.iter().filter(|m| !m.skip) {
             let source_idx = columns.iter().position(|c| c == &col_mapping.source_column);
@@ -465,56 +831,755 @@ pub async fn import_data_to_table(
             };
 
             values.push(if value.is_empty() {
-                "NULL".to_string()
+                Value::Null
             } else {
-                format!("'{}'", connection.escape_string_literal(&value))
+                Value::String(value)
             });
         }
+        statements.push((row_idx + 1, values));
+    }
 
-        // Build actual INSERT for this row
-        let row_sql = format!(
-            "INSERT INTO {} ({}) VALUES ({})",
-            full_table_name,
-            target_columns.join(", "),
-            values.join(", ")
-        );
+    let total_rows = statements.len();
+    let started = Instant::now();
+    let report = {
+        let app = app.clone();
+        let import_id = import_id.clone();
+        move |processed: usize, failed: usize| {
+            let elapsed = started.elapsed().as_millis() as u64;
+            let remaining = total_rows.saturating_sub(processed) as u64;
+            emit_progress(
+                &app,
+                ImportProgressEvent {
+                    import_id: import_id.clone(),
+                    rows_processed: processed,
+                    rows_failed: failed,
+                    total_rows,
+                    eta_ms: (processed > 0).then(|| elapsed * remaining / processed as u64),
+                    done: false,
+                    cancelled: false,
+                },
+            );
+        }
+    };
+    let monitor = ImportMonitor {
+        cancel,
+        report: Arc::new(report),
+        every: options.batch_size.max(1),
+    };
+    let progress = insert_rows(
+        connection.as_ref(),
+        &insert,
+        &statements,
+        &options,
+        &monitor,
+    )
+    .await;
+    // Batches committed before a failure have changed the table as well
+    state
+        .lock()
+        .map_err(|e| e.to_string())?
+        .result_cache
+        .invalidate_table(&connection_id, &options.table_name);
+    let progress = progress?;
+
+    emit_progress(
+        &app,
+        ImportProgressEvent {
+            import_id,
+            rows_processed: progress.rows_imported + progress.rows_failed,
+            rows_failed: progress.rows_failed,
+            total_rows,
+            eta_ms: None,
+            done: true,
+            cancelled: progress.cancelled,
+        },
+    );
+    Ok(ImportResult {
+        rows_imported: progress.rows_imported,
+        rows_failed: progress.rows_failed,
+        success: progress.rows_failed == 0 && !progress.cancelled,
+        errors: progress.errors,
+        batches_committed: progress.batches_committed,
+        cancelled: progress.cancelled,
+        skipped_generated,
+    })
+}
 
-        match connection.execute_query(&row_sql).await {
-            Ok(_) => rows_imported += 1,
-            Err(e) => {
-                rows_failed += 1;
-                if errors.len() < 10 {
-                    errors.push(format!("Row {}: {}", row_idx + 1, e));
+/// Mark mappings onto generated columns as skipped
+///
+/// Returns the names of the target columns that were skipped.
+fn skip_generated_columns(mappings: &mut [ColumnMapping], columns: &[ColumnInfo]) -> Vec<String> {
+    let mut skipped = Vec::new();
+    for mapping in mappings.iter_mut().filter(|m| !m.skip) {
+        let generated = columns
+            .iter()
+            .any(|c| c.is_generated && c.name == mapping.target_column);
+        if generated {
+            mapping.skip = true;
+            skipped.push(mapping.target_column.clone());
+        }
+    }
+    skipped
+}
+
+/// Progress reporting and cancellation of one import
+#[derive(Clone)]
+struct ImportMonitor {
+    cancel: Arc<AtomicBool>,
+    /// Called with the rows processed and failed so far, every `every` rows
+    report: Arc<dyn Fn(usize, usize) + Send + Sync>,
+    every: usize,
+}
+
+impl ImportMonitor {
+    fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    fn row_done(&self, processed: usize, failed: usize) {
+        if processed % self.every == 0 {
+            (self.report)(processed, failed);
+        }
+    }
+}
+
+/// The INSERT of an import's rows
+///
+/// Rows run one at a time bind their values as parameters, so file contents
+/// never become SQL. Transaction batches go through `execute_in_transaction`,
+/// which takes plain statements, so there the values are inlined with
+/// `escape_literal`.
+struct RowInsert {
+    /// `INSERT INTO t (a, b) VALUES `
+    prefix: String,
+    /// The parameterized statement
+    sql: String,
+    dialect: DbDriver,
+}
+
+impl RowInsert {
+    /// `table` and `columns` are already quoted
+    fn new(
+        connection: &dyn DatabaseDriver,
+        dialect: DbDriver,
+        table: &str,
+        columns: &[String],
+    ) -> Self {
+        let style = connection.placeholder_style();
+        let placeholders: Vec<String> = (1..=columns.len()).map(|i| style.placeholder(i)).collect();
+        let prefix = format!("INSERT INTO {} ({}) VALUES ", table, columns.join(", "));
+        Self {
+            sql: format!("{}({})", prefix, placeholders.join(", ")),
+            prefix,
+            dialect,
+        }
+    }
+
+    /// The statement with `values` inlined as literals
+    fn literal(&self, values: &[Value]) -> String {
+        let literals: Vec<String> = values
+            .iter()
+            .map(|value| match value {
+                Value::String(s) => escape_literal(s, &self.dialect),
+                Value::Null => "NULL".to_string(),
+                other => other.to_string(),
+            })
+            .collect();
+        format!("{}({})", self.prefix, literals.join(", "))
+    }
+}
+
+/// Run the INSERTs of an import as `options` asks: in transactions of one
+/// batch or of the whole import, or one statement at a time
+///
+/// A cancelled import stops before its next row; a transaction in progress
+/// is rolled back.
+async fn insert_rows(
+    connection: &dyn DatabaseDriver,
+    insert: &RowInsert,
+    statements: &[(usize, Vec<Value>)],
+    options: &DataImportOptions,
+    monitor: &ImportMonitor,
+) -> Result<ImportProgress, String> {
+    let mut progress = ImportProgress::default();
+    if options.use_transaction {
+        // `execute_in_transaction` runs a batch on one connection, so BEGIN
+        // and COMMIT can't land on different pooled sessions
+        let batch_size = if options.commit_each_batch {
+            options.batch_size.max(1)
+        } else {
+            statements.len().max(1)
+        };
+        for batch in statements.chunks(batch_size) {
+            if monitor.is_cancelled() {
+                progress.cancelled = true;
+                break;
+            }
+            let sql: Vec<String> = batch
+                .iter()
+                .map(|(_, values)| insert.literal(values))
+                .collect();
+            let before = progress.rows_imported + progress.rows_failed;
+            let failed = progress.rows_failed;
+            let batch_monitor = monitor.clone();
+            let after_statement: StatementCallback = Box::new(move |run| {
+                batch_monitor.row_done(before + run, failed);
+                !batch_monitor.is_cancelled()
+            });
+            match connection
+                .execute_in_transaction_with(&sql, after_statement)
+                .await
+            {
+                Ok(Some(_)) => {
+                    progress.rows_imported += batch.len();
+                    progress.batches_committed += 1;
+                }
+                // Cancelled mid-batch and rolled back
+                Ok(None) => {
+                    progress.cancelled = true;
+                    break;
+                }
+                // The driver has no transactions; nothing was run
+                Err(DbError::InvalidInput(e)) => return Err(e),
+                Err(e) if options.continue_on_error => {
+                    progress.record_error(format!(
+                        "Rows {}-{} rolled back, retrying row by row: {}",
+                        batch[0].0,
+                        batch[batch.len() - 1].0,
+                        e
+                    ));
+                    progress
+                        .run_rows(connection, insert, batch, true, monitor)
+                        .await;
+                }
+                Err(e) => {
+                    progress.rows_failed += batch.len();
+                    progress.record_error(format!(
+                        "Rows {}-{} rolled back: {}",
+                        batch[0].0,
+                        batch[batch.len() - 1].0,
+                        e
+                    ));
+                    break;
                 }
             }
         }
+    } else {
+        progress
+            .run_rows(
+                connection,
+                insert,
+                statements,
+                options.continue_on_error,
+                monitor,
+            )
+            .await;
     }
+    Ok(progress)
+}
 
-    Ok(ImportResult {
-        rows_imported,
-        rows_failed,
-        errors,
-        success: rows_failed == 0,
+/// Running totals of `import_data_to_table`
+#[derive(Default)]
+struct ImportProgress {
+    rows_imported: usize,
+    rows_failed: usize,
+    batches_committed: usize,
+    errors: Vec<String>,
+    cancelled: bool,
+}
+
+impl ImportProgress {
+    /// Keep the first 10 errors
+    fn record_error(&mut self, error: String) {
+        if self.errors.len() < 10 {
+            self.errors.push(error);
+        }
+    }
+
+    /// Insert rows one statement at a time, outside a transaction, stopping
+    /// at the first failure unless `continue_on_error`
+    async fn run_rows(
+        &mut self,
+        connection: &dyn DatabaseDriver,
+        insert: &RowInsert,
+        statements: &[(usize, Vec<Value>)],
+        continue_on_error: bool,
+        monitor: &ImportMonitor,
+    ) {
+        for (row_number, values) in statements {
+            if monitor.is_cancelled() {
+                self.cancelled = true;
+                break;
+            }
+            let result = match connection
+                .execute_query_with_params(&insert.sql, values)
+                .await
+            {
+                // The driver can't bind parameters, or can't convert a value
+                // to the column's type (Postgres binds strictly typed, but
+                // accepts 'on' as a boolean literal, for one)
+                Err(DbError::InvalidInput(_)) => {
+                    connection.execute_query(&insert.literal(values)).await
+                }
+                result => result,
+            };
+            match result {
+                Ok(_) => self.rows_imported += 1,
+                Err(e) => {
+                    self.rows_failed += 1;
+                    self.record_error(format!("Row {}: {}", row_number, e));
+                    if !continue_on_error {
+                        break;
+                    }
+                }
+            }
+            monitor.row_done(self.rows_imported + self.rows_failed, self.rows_failed);
+        }
+    }
+}
+
+/// Kind of problem found in an import's column mappings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MappingIssueKind {
+    /// The target column does not exist in the table
+    UnknownTargetColumn,
+    /// More than one source column maps to the same target column
+    DuplicateTarget,
+    /// The source values don't fit the target column's type
+    TypeMismatch,
+    /// A NOT NULL column without a default would receive NULLs
+    MissingRequiredValue,
+}
+
+/// Problem found in an import's column mappings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappingIssue {
+    pub kind: MappingIssueKind,
+    /// Source column, if the issue concerns a specific mapping
+    pub source_column: Option<String>,
+    pub target_column: String,
+    pub message: String,
+}
+
+/// Check an import's column mappings against the target table
+///
+/// Reads the file with the same options the import uses and compares every
+/// non-skipped mapping with the live table schema. An empty result means the
+/// mapping is coherent; otherwise the import should not be started.
+#[tauri::command]
+pub async fn validate_import_mapping(
+    connection_id: String,
+    file_path: String,
+    mut options: DataImportOptions,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<MappingIssue>, String> {
+    let (columns, rows) = read_file_data(&file_path, &options).map_err(|e| e.to_string())?;
+
+    let (connection, schema_name) = {
+        let state = state.lock().map_err(|e| e.to_string())?;
+        if options.schema.is_none() {
+            options.schema = state.default_schema(&connection_id);
+        }
+        let connection = state
+            .get_connection(&connection_id)
+            .ok_or_else(|| format!("Connection not found: {}", connection_id))?
+            .clone();
+        let schema_name = state
+            .resolve_schema(&connection_id, options.schema.as_deref())
+            .map_err(|e| e.to_string())?;
+        (connection, schema_name)
+    };
+
+    // A table created by the import is built from the mapping itself
+    if options.create_table
+        && !table_exists(&connection, &schema_name, &options.table_name)
+            .await
+            .map_err(|e| e.to_string())?
+    {
+        return Ok(Vec::new());
+    }
+
+    let table_schema = connection
+        .get_table_schema(&schema_name, &options.table_name)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(check_column_mappings(
+        &options.column_mappings,
+        &columns,
+        &rows,
+        &table_schema.columns,
+    ))
+}
+
+/// Validate mappings against file data and the target table's columns
+fn check_column_mappings(
+    mappings: &[ColumnMapping],
+    columns: &[String],
+    rows: &[Vec<String>],
+    table_columns: &[ColumnInfo],
+) -> Vec<MappingIssue> {
+    let mut issues = Vec::new();
+    let mut mapped_targets: HashMap<&str, &str> = HashMap::new();
+
+    for mapping in mappings.iter().filter(|m| !m.skip) {
+        let issue = |kind, message: String| MappingIssue {
+            kind,
+            source_column: Some(mapping.source_column.clone()),
+            target_column: mapping.target_column.clone(),
+            message,
+        };
+
+        if let Some(previous) =
+            mapped_targets.insert(&mapping.target_column, &mapping.source_column)
+        {
+            issues.push(issue(
+                MappingIssueKind::DuplicateTarget,
+                format!(
+                    "Columns '{}' and '{}' both map to '{}'",
+                    previous, mapping.source_column, mapping.target_column
+                ),
+            ));
+        }
+
+        let Some(target) = table_columns
+            .iter()
+            .find(|c| c.name == mapping.target_column)
+        else {
+            let hint = table_columns
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(&mapping.target_column))
+                .map(|c| format!(" (did you mean '{}'?)", c.name))
+                .unwrap_or_default();
+            issues.push(issue(
+                MappingIssueKind::UnknownTargetColumn,
+                format!(
+                    "Column '{}' does not exist in the table{}",
+                    mapping.target_column, hint
+                ),
+            ));
+            continue;
+        };
+
+        // Values the import would insert for this mapping (empty means NULL)
+        let source_idx = columns.iter().position(|c| c == &mapping.source_column);
+        let default = mapping.default_value.clone().unwrap_or_default();
+        let values: Vec<String> = match source_idx {
+            Some(idx) => rows
+                .iter()
+                .map(|row| row.get(idx).cloned().unwrap_or_default())
+                .collect(),
+            None => vec![default; rows.len()],
+        };
+        let samples: Vec<String> = values.iter().filter(|v| !v.is_empty()).cloned().collect();
+
+        if !samples.is_empty() {
+            let source_type = detect_column_type(&samples);
+            if !is_type_compatible(&source_type, &target.data_type, &samples) {
+                issues.push(issue(
+                    MappingIssueKind::TypeMismatch,
+                    format!(
+                        "Source column '{}' looks like {} but '{}' is {}",
+                        mapping.source_column, source_type, target.name, target.data_type
+                    ),
+                ));
+            }
+        }
+
+        if is_required(target) && values.iter().any(String::is_empty) {
+            issues.push(issue(
+                MappingIssueKind::MissingRequiredValue,
+                format!(
+                    "'{}' is NOT NULL without a default, but '{}' has empty values",
+                    target.name, mapping.source_column
+                ),
+            ));
+        }
+    }
+
+    // Required columns the mapping leaves out entirely
+    for column in table_columns.iter().filter(|c| is_required(c)) {
+        if !mapped_targets.contains_key(column.name.as_str()) {
+            issues.push(MappingIssue {
+                kind: MappingIssueKind::MissingRequiredValue,
+                source_column: None,
+                target_column: column.name.clone(),
+                message: format!(
+                    "'{}' is NOT NULL without a default and is not mapped",
+                    column.name
+                ),
+            });
+        }
+    }
+
+    issues
+}
+
+/// Whether a column must receive a value on insert
+fn is_required(column: &ColumnInfo) -> bool {
+    !column.nullable && column.default_value.is_none() && !column.is_auto_increment
+}
+
+/// Map a database column type onto the type names used by
+/// `detect_column_type`, or `None` when the type isn't one of them
+fn target_type_family(data_type: &str) -> Option<&'static str> {
+    let lower = data_type.trim().to_lowercase();
+    if lower == "bit" || lower.starts_with("bool") || lower == "tinyint(1)" {
+        return Some("BOOLEAN");
+    }
+    let base = lower
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(" unsigned")
+        .trim();
+
+    match base {
+        "int" | "integer" | "bigint" | "smallint" | "tinyint" | "mediumint" | "int2" | "int4"
+        | "int8" | "serial" | "bigserial" | "smallserial" => Some("INTEGER"),
+        "numeric" | "decimal" | "real" | "float" | "float4" | "float8" | "double"
+        | "double precision" | "money" | "smallmoney" | "number" => Some("DECIMAL"),
+        "char" | "varchar" | "character" | "character varying" | "nchar" | "nvarchar"
+        | "text" | "ntext" | "tinytext" | "mediumtext" | "longtext" | "citext" | "clob"
+        | "string" | "bpchar" => Some("TEXT"),
+        _ if base.starts_with("date") || base.starts_with("time") || base == "smalldatetime" => {
+            Some("DATE")
+        }
+        _ => None,
+    }
+}
+
+/// Whether values detected as `source_type` can be stored in a column of
+/// type `target_type`. Unrecognized target types are assumed compatible.
+fn is_type_compatible(source_type: &str, target_type: &str, samples: &[String]) -> bool {
+    let Some(target) = target_type_family(target_type) else {
+        return true;
+    };
+    match (source_type, target) {
+        (_, "TEXT") => true,
+        (source, target) if source == target => true,
+        ("INTEGER", "DECIMAL") => true,
+        // 0/1 columns are detected as BOOLEAN but fit numeric columns
+        ("BOOLEAN", "INTEGER" | "DECIMAL") => {
+            samples.iter().all(|v| v.trim().parse::<i64>().is_ok())
+        }
+        _ => false,
+    }
+}
+
+/// Whether the import's target table already exists
+async fn table_exists(
+    connection: &Arc<dyn DatabaseDriver>,
+    schema: &str,
+    table_name: &str,
+) -> Result<bool, DbError> {
+    let tables = connection.get_tables(schema).await?;
+    Ok(tables.iter().any(|t| t.name == table_name))
+}
+
+/// Define a new table for the imported file
+///
+/// Every non-skipped mapping becomes a nullable column. Column names are
+/// sanitized and deduplicated, and the mappings are updated to point at the
+/// final names. Types come from `target_type` when set, otherwise they are
+/// inferred from the file's values. Without any mappings, every file column
+/// is imported under its own name.
+fn build_import_table(
+    options: &mut DataImportOptions,
+    columns: &[String],
+    rows: &[Vec<String>],
+) -> Result<TableDefinition, DbError> {
+    if options.column_mappings.is_empty() {
+        options.column_mappings = columns
+            .iter()
+            .map(|c| ColumnMapping {
+                source_column: c.clone(),
+                target_column: c.clone(),
+                target_type: None,
+                default_value: None,
+                skip: false,
+            })
+            .collect();
+    }
+
+    let mut used_names = HashSet::new();
+    let mut definitions = Vec::new();
+    for mapping in options.column_mappings.iter_mut().filter(|m| !m.skip) {
+        let base = sanitize_identifier(&mapping.target_column);
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !used_names.insert(name.clone()) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        mapping.target_column = name.clone();
+
+        let column_type = match mapping.target_type.as_deref() {
+            Some(type_name) if !type_name.trim().is_empty() => column_type_from_name(type_name)?,
+            _ => {
+                let samples: Vec<String> = columns
+                    .iter()
+                    .position(|c| c == &mapping.source_column)
+                    .map(|idx| {
+                        rows.iter()
+                            .filter_map(|row| row.get(idx))
+                            .filter(|v| !v.is_empty())
+                            .cloned()
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                infer_column_type(&samples)
+            }
+        };
+
+        definitions.push(ColumnDefinition {
+            name,
+            column_type,
+            nullable: true,
+            default: None,
+            primary_key: false,
+            auto_increment: false,
+            comment: None,
+        });
+    }
+    if definitions.is_empty() {
+        return Err(DbError::ImportError("No columns mapped for import".to_string()));
+    }
+
+    Ok(TableDefinition {
+        schema: options.schema.clone(),
+        name: options.table_name.clone(),
+        columns: definitions,
+        primary_key: None,
+        foreign_keys: Vec::new(),
+        unique_constraints: Vec::new(),
+        check_constraints: Vec::new(),
+        comment: None,
+        if_not_exists: true,
     })
 }
 
+/// Turn a file header into a lowercase identifier of letters, digits and
+/// underscores that doesn't start with a digit
+fn sanitize_identifier(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+
+    let mut out = match out.trim_matches('_') {
+        "" => "column".to_string(),
+        trimmed => trimmed.to_string(),
+    };
+    if out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert_str(0, "col_");
+    }
+    out.truncate(63);
+    out
+}
+
+/// Column type for values detected by `detect_column_type`
+fn infer_column_type(samples: &[String]) -> ColumnType {
+    match detect_column_type(samples).as_str() {
+        "INTEGER" => ColumnType::BigInt,
+        "DECIMAL" => ColumnType::DoublePrecision,
+        "BOOLEAN" => ColumnType::Boolean,
+        // Date detection only checks the start of the value; keep times of day
+        "DATE" if samples.iter().any(|v| v.trim().len() > 10) => ColumnType::Timestamp,
+        "DATE" => ColumnType::Date,
+        _ => ColumnType::Text,
+    }
+}
+
+/// Column type for a user-supplied `target_type`
+///
+/// The detected type names map to the same types inference would pick; any
+/// other name is passed to the database as written.
+fn column_type_from_name(type_name: &str) -> Result<ColumnType, DbError> {
+    let type_name = type_name.trim();
+    let column_type = match type_name.to_uppercase().as_str() {
+        "INTEGER" => ColumnType::BigInt,
+        "DECIMAL" => ColumnType::DoublePrecision,
+        "BOOLEAN" => ColumnType::Boolean,
+        "DATE" => ColumnType::Date,
+        "TEXT" => ColumnType::Text,
+        _ => {
+            let valid = type_name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '_' | '(' | ')' | ','));
+            if !valid {
+                return Err(DbError::InvalidInput(format!(
+                    "Invalid column type: {}",
+                    type_name
+                )));
+            }
+            ColumnType::Custom {
+                type_name: type_name.to_string(),
+            }
+        }
+    };
+    Ok(column_type)
+}
+
+/// Read all rows of an import file, honoring the header and skip options
+fn read_file_data(
+    file_path: &str,
+    options: &DataImportOptions,
+) -> Result<(Vec<String>, Vec<Vec<String>>), DbError> {
+    let extension = Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    match extension.as_str() {
+        "csv" | "tsv" | "txt" => read_csv_data(
+            file_path,
+            CsvDialect {
+                delimiter: options.delimiter.unwrap_or(','),
+                quote: options.quote.unwrap_or('"'),
+                escape: options.escape,
+                has_header: options.first_row_is_header,
+            },
+            options.skip_rows,
+        ),
+        "xlsx" => read_xlsx_data(
+            file_path,
+            options.sheet_name.as_deref(),
+            options.skip_rows,
+            options.first_row_is_header,
+        ),
+        "xls" => read_xls_data(
+            file_path,
+            options.sheet_name.as_deref(),
+            options.skip_rows,
+            options.first_row_is_header,
+        ),
+        _ => Err(DbError::ImportError(format!(
+            "Unsupported file type: {}",
+            extension
+        ))),
+    }
+}
+
 /// Read CSV data
 fn read_csv_data(
     file_path: &str,
-    delimiter: Option<char>,
+    dialect: CsvDialect,
     skip_rows: usize,
-    first_row_is_header: bool,
 ) -> Result<(Vec<String>, Vec<Vec<String>>), DbError> {
-    let delimiter = delimiter.unwrap_or(',') as u8;
-
-    let mut reader = csv::ReaderBuilder::new()
-        .delimiter(delimiter)
-        .has_headers(first_row_is_header)
-        .flexible(true)
+    let mut reader = dialect
+        .reader_builder()
         .from_path(file_path)
         .map_err(|e| DbError::ImportError(format!("Failed to open CSV: {}", e)))?;
 
-    let headers = if first_row_is_header {
+    let headers = if dialect.has_header {
         reader
             .headers()
             .map_err(|e| DbError::ImportError(format!("Failed to read headers: {}", e)))?
@@ -648,16 +1713,20 @@ pub async fn get_tables_for_import(
     schema: Option<String>,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<Vec<String>, String> {
-    let connection = {
+    let (connection, schema) = {
         let state = state.lock().map_err(|e| e.to_string())?;
-        state
+        let connection = state
             .get_connection(&connection_id)
             .ok_or_else(|| format!("Connection not found: {}", connection_id))?
-            .clone()
+            .clone();
+        let schema = state
+            .resolve_schema(&connection_id, schema.as_deref())
+            .map_err(|e| e.to_string())?;
+        (connection, schema)
     };
 
     let tables = connection
-        .get_tables(&schema.unwrap_or_else(|| "public".to_string()))
+        .get_tables(&schema)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -672,17 +1741,20 @@ pub async fn get_table_columns_for_import(
     schema: Option<String>,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<Vec<TableColumnInfo>, String> {
-    let connection = {
+    let (connection, schema) = {
         let state = state.lock().map_err(|e| e.to_string())?;
-        state
+        let connection = state
             .get_connection(&connection_id)
             .ok_or_else(|| format!("Connection not found: {}", connection_id))?
-            .clone()
+            .clone();
+        let schema = state
+            .resolve_schema(&connection_id, schema.as_deref())
+            .map_err(|e| e.to_string())?;
+        (connection, schema)
     };
 
-    let schema_name = schema.unwrap_or_else(|| "public".to_string());
     let table_schema = connection
-        .get_table_schema(&schema_name, &table_name)
+        .get_table_schema(&schema, &table_name)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -709,3 +1781,331 @@ pub struct TableColumnInfo {
     pub default_value: Option<String>,
     pub is_primary_key: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(source: &str, target: &str) -> ColumnMapping {
+        ColumnMapping {
+            source_column: source.to_string(),
+            target_column: target.to_string(),
+            target_type: None,
+            default_value: None,
+            skip: false,
+        }
+    }
+
+    fn column(name: &str, data_type: &str, nullable: bool) -> ColumnInfo {
+        ColumnInfo::new(name.to_string(), data_type.to_string(), nullable)
+    }
+
+    #[test]
+    fn test_sniff_csv_dialect() {
+        let dialect = sniff_csv_dialect(
+            "id;name;note\n1;Ada;\"a, b; c\"\n2;Grace;x\n3;Linus;y\n",
+            None,
+            None,
+        );
+        assert_eq!(
+            dialect,
+            CsvDialect {
+                delimiter: ';',
+                ..Default::default()
+            }
+        );
+
+        let dialect = sniff_csv_dialect("1\t2.5\tx\n2\t3.5\ty\n3\t4\tz\n", None, None);
+        assert_eq!(dialect.delimiter, '\t');
+        assert!(!dialect.has_header);
+
+        let dialect = sniff_csv_dialect(
+            "'name'|'quote'\n'Ada'|'said \\'hi\\''\n'Grace'|'ok'\n",
+            None,
+            None,
+        );
+        assert_eq!(dialect.delimiter, '|');
+        assert_eq!(dialect.quote, '\'');
+        assert_eq!(dialect.escape, Some('\\'));
+        assert!(dialect.has_header);
+
+        // A given delimiter is kept even if another looks more consistent
+        let dialect = sniff_csv_dialect("a,b;c\nd,e;f\n", Some(';'), None);
+        assert_eq!(dialect.delimiter, ';');
+    }
+
+    #[test]
+    fn test_sanitize_identifier() {
+        assert_eq!(sanitize_identifier("Customer Name"), "customer_name");
+        assert_eq!(sanitize_identifier("  Price ($) "), "price");
+        assert_eq!(sanitize_identifier("2024 total"), "col_2024_total");
+        assert_eq!(sanitize_identifier("***"), "column");
+    }
+
+    #[test]
+    fn test_skip_generated_columns() {
+        let mut total = column("total", "numeric", true);
+        total.is_generated = true;
+        total.generation_expression = Some("price * qty".to_string());
+        let columns = vec![column("price", "numeric", true), total];
+        let mut mappings = vec![mapping("Price", "price"), mapping("Total", "total")];
+
+        let skipped = skip_generated_columns(&mut mappings, &columns);
+        assert_eq!(skipped, vec!["total".to_string()]);
+        assert!(!mappings[0].skip);
+        assert!(mappings[1].skip);
+    }
+
+    #[test]
+    fn test_build_import_table() {
+        let columns = vec![
+            "ID".to_string(),
+            "Amount".to_string(),
+            "Created".to_string(),
+            "id".to_string(),
+            "Note".to_string(),
+        ];
+        let rows = vec![
+            vec!["1", "9.5", "2024-01-02 10:00:00", "x", ""],
+            vec!["2", "10", "2024-01-03", "y", ""],
+        ]
+        .into_iter()
+        .map(|row| row.into_iter().map(String::from).collect())
+        .collect::<Vec<Vec<String>>>();
+
+        let mut options = DataImportOptions {
+            table_name: "orders".to_string(),
+            schema: None,
+            column_mappings: columns.iter().map(|c| mapping(c, c)).collect(),
+            skip_rows: 0,
+            create_table: true,
+            truncate_before: false,
+            batch_size: 100,
+            delimiter: None,
+            quote: None,
+            escape: None,
+            sheet_name: None,
+            first_row_is_header: true,
+            use_transaction: false,
+            commit_each_batch: false,
+            continue_on_error: true,
+        };
+        options.column_mappings[4].target_type = Some("VARCHAR(20)".to_string());
+
+        let table = build_import_table(&mut options, &columns, &rows).unwrap();
+        let names: Vec<_> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "amount", "created", "id_2", "note"]);
+        let types: Vec<_> = table.columns.iter().map(|c| c.column_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                ColumnType::BigInt,
+                ColumnType::DoublePrecision,
+                ColumnType::Timestamp,
+                ColumnType::Text,
+                ColumnType::Custom {
+                    type_name: "VARCHAR(20)".to_string()
+                },
+            ]
+        );
+        // The import inserts into the sanitized names
+        assert_eq!(options.column_mappings[3].target_column, "id_2");
+
+        options.column_mappings[0].target_type = Some("INT; DROP TABLE x".to_string());
+        assert!(build_import_table(&mut options, &columns, &rows).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_insert_rows_in_transactions() {
+        use crate::drivers::sqlite::SqliteDriver;
+        use crate::drivers::ConnectionOptions;
+
+        let db_path = std::env::temp_dir().join("test_import_transactions.sqlite");
+        std::fs::remove_file(&db_path).ok();
+        let driver = SqliteDriver::connect(ConnectionOptions {
+            host: String::new(),
+            port: 0,
+            username: String::new(),
+            password: None,
+            database: Some(db_path.to_str().unwrap().to_string()),
+            timeout: None,
+            require_tls: false,
+            application_name: None,
+            default_schema: None,
+            sqlite: Default::default(),
+        })
+        .await
+        .unwrap();
+        driver
+            .execute_query("CREATE TABLE t (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+        let count = || async {
+            let result = driver
+                .execute_query("SELECT COUNT(*) FROM t")
+                .await
+                .unwrap();
+            result.rows[0][0].as_u64().unwrap()
+        };
+
+        // The duplicate 2 fails the second batch of [1, 2], [2, 3], [4]
+        let insert = RowInsert::new(&driver, DbDriver::Sqlite, "t", &["id".to_string()]);
+        assert_eq!(insert.sql, "INSERT INTO t (id) VALUES (?)");
+        assert_eq!(
+            insert.literal(&[Value::String("O'Hara".to_string())]),
+            "INSERT INTO t (id) VALUES ('O''Hara')"
+        );
+        let statements: Vec<(usize, Vec<Value>)> = ["1", "2", "2", "3", "4"]
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (i + 1, vec![Value::String(id.to_string())]))
+            .collect();
+        let mut options = DataImportOptions {
+            table_name: "t".to_string(),
+            schema: None,
+            column_mappings: Vec::new(),
+            skip_rows: 0,
+            create_table: false,
+            truncate_before: false,
+            batch_size: 2,
+            delimiter: None,
+            quote: None,
+            escape: None,
+            sheet_name: None,
+            first_row_is_header: true,
+            use_transaction: true,
+            commit_each_batch: true,
+            continue_on_error: false,
+        };
+        let monitor = ImportMonitor {
+            cancel: Arc::new(AtomicBool::new(false)),
+            report: Arc::new(|_, _| {}),
+            every: 2,
+        };
+
+        let progress = insert_rows(&driver, &insert, &statements, &options, &monitor)
+            .await
+            .unwrap();
+        let totals = |p: &ImportProgress| (p.rows_imported, p.rows_failed, p.batches_committed);
+        assert_eq!(totals(&progress), (2, 2, 1));
+        assert_eq!(count().await, 2);
+
+        // Continuing retries the failed batch row by row
+        driver.execute_query("DELETE FROM t").await.unwrap();
+        options.continue_on_error = true;
+        let progress = insert_rows(&driver, &insert, &statements, &options, &monitor)
+            .await
+            .unwrap();
+        assert_eq!(totals(&progress), (4, 1, 2));
+        assert_eq!(count().await, 4);
+
+        // One transaction for the whole import: nothing is kept
+        driver.execute_query("DELETE FROM t").await.unwrap();
+        options.commit_each_batch = false;
+        options.continue_on_error = false;
+        let progress = insert_rows(&driver, &insert, &statements, &options, &monitor)
+            .await
+            .unwrap();
+        assert_eq!(totals(&progress), (0, 5, 0));
+        assert_eq!(count().await, 0);
+
+        // Without a transaction rows are bound as parameters
+        options.use_transaction = false;
+        options.continue_on_error = true;
+        let progress = insert_rows(&driver, &insert, &statements, &options, &monitor)
+            .await
+            .unwrap();
+        assert_eq!(totals(&progress), (4, 1, 0));
+        assert_eq!(count().await, 4);
+        driver.execute_query("DELETE FROM t").await.unwrap();
+        options.use_transaction = true;
+        options.continue_on_error = false;
+
+        // Cancelling after two rows rolls the whole import back
+        let statements = &statements[..2];
+        let cancel = Arc::new(AtomicBool::new(false));
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let monitor = ImportMonitor {
+            cancel: Arc::clone(&cancel),
+            report: {
+                let reported = Arc::clone(&reported);
+                Arc::new(move |processed, _| {
+                    reported.lock().unwrap().push(processed);
+                    cancel.store(true, Ordering::Relaxed);
+                })
+            },
+            every: 2,
+        };
+        let progress = insert_rows(&driver, &insert, statements, &options, &monitor)
+            .await
+            .unwrap();
+        assert!(progress.cancelled);
+        assert_eq!(totals(&progress), (0, 0, 0));
+        assert_eq!(*reported.lock().unwrap(), [2]);
+        assert_eq!(count().await, 0);
+
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_type_compatibility() {
+        assert!(is_type_compatible("INTEGER", "numeric(10,2)", &[]));
+        assert!(is_type_compatible("DATE", "timestamp without time zone", &[]));
+        assert!(is_type_compatible("DECIMAL", "VARCHAR(20)", &[]));
+        assert!(is_type_compatible("TEXT", "jsonb", &[]));
+        assert!(!is_type_compatible("DECIMAL", "int(11) unsigned", &[]));
+        assert!(!is_type_compatible("TEXT", "integer", &[]));
+        assert!(!is_type_compatible("INTEGER", "tinyint(1)", &[]));
+
+        let flags = vec!["0".to_string(), "1".to_string()];
+        assert!(is_type_compatible("BOOLEAN", "bigint", &flags));
+        let words = vec!["yes".to_string(), "no".to_string()];
+        assert!(!is_type_compatible("BOOLEAN", "bigint", &words));
+    }
+
+    #[test]
+    fn test_check_column_mappings() {
+        let columns = vec!["id".to_string(), "name".to_string(), "price".to_string()];
+        let rows = vec![
+            vec!["1".to_string(), "Widget".to_string(), "9.99".to_string()],
+            vec!["2".to_string(), String::new(), "12.50".to_string()],
+        ];
+        let mut id = column("id", "integer", false);
+        id.is_auto_increment = true;
+        let mut table = vec![
+            id,
+            column("name", "varchar(100)", false),
+            column("quantity", "integer", true),
+            column("sku", "text", false),
+        ];
+
+        let mappings = vec![
+            mapping("id", "id"),
+            mapping("name", "name"),
+            mapping("price", "quantity"),
+            mapping("price", "Quantity"),
+        ];
+        let issues = check_column_mappings(&mappings, &columns, &rows, &table);
+        let kinds: Vec<_> = issues.iter().map(|i| (i.kind, i.target_column.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (MappingIssueKind::MissingRequiredValue, "name"),
+                (MappingIssueKind::TypeMismatch, "quantity"),
+                (MappingIssueKind::UnknownTargetColumn, "Quantity"),
+                (MappingIssueKind::MissingRequiredValue, "sku"),
+            ]
+        );
+        assert!(issues[2].message.contains("did you mean 'quantity'"));
+
+        // Defaults fill missing sources; reusing a target is reported
+        table.truncate(2);
+        let mut with_default = mapping("missing", "name");
+        with_default.default_value = Some("unknown".to_string());
+        let mappings = vec![mapping("id", "id"), with_default, mapping("price", "id")];
+        let issues = check_column_mappings(&mappings, &columns, &rows, &table);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].kind, MappingIssueKind::DuplicateTarget);
+        assert_eq!(issues[1].kind, MappingIssueKind::TypeMismatch);
+    }
+}
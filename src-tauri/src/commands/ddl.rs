@@ -2,14 +2,24 @@
 //!
 //! Tauri commands for creating, altering, and dropping database objects.
 
+use crate::commands::query::invalidate_results_of;
+use crate::commands::settings::load_settings;
 use crate::ddl::get_ddl_generator;
+use crate::drivers::DatabaseDriver;
 use crate::models::{
-    ddl::{AlterTableDefinition, DdlResult, DropTableDefinition, TableDefinition},
-    DbDriver, DbError,
+    ddl::{
+        AlterTableDefinition, ColumnDefinition, ColumnType, DdlResult, DropTableDefinition,
+        DropViewDefinition, IndexDefinition, IndexType, TableDefinition, ViewDefinition,
+    },
+    ColumnInfo, DbDriver, DbError, Environment, ForeignKeyInfo, TableSchema,
 };
+use crate::sql::literal::escape_literal;
+use crate::sql::quote::{quote_identifier, quote_list, quote_qualified};
+use crate::sql::tokenizer::Quoting;
 use crate::state::AppState;
-use std::sync::Mutex;
-use tauri::State;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
 
 fn validate_identifier(name: &str) -> Result<(), DbError> {
     if name.is_empty() {
@@ -34,6 +44,38 @@ fn validate_identifier(name: &str) -> Result<(), DbError> {
     Ok(())
 }
 
+/// Run DDL statements in order, stopping at the first failure
+///
+/// The connection's cached schema tree and the cached query results reading
+/// the tables the statements name are dropped either way, since the
+/// statements before a failing one may already have changed them.
+async fn execute_ddl(
+    state: &Mutex<AppState>,
+    connection_id: &str,
+    driver: &dyn DatabaseDriver,
+    statements: &[String],
+) -> Result<(), DbError> {
+    let mut result = Ok(());
+    for sql in statements {
+        result = driver.execute_query(sql).await.map(|_| ());
+        if result.is_err() {
+            break;
+        }
+    }
+    forget_schema_tree(state, connection_id);
+    let mut state = state.lock().unwrap();
+    for sql in statements {
+        invalidate_results_of(&mut state, connection_id, sql);
+    }
+    result
+}
+
+/// Drop the cached schema tree of a connection after DDL, so the next
+/// expand reloads it
+fn forget_schema_tree(state: &Mutex<AppState>, connection_id: &str) {
+    state.lock().unwrap().metadata_cache.remove(connection_id);
+}
+
 /// Create a new database on the connected server
 ///
 /// Executes `CREATE DATABASE` for SQL drivers that support it. SQLite is
@@ -63,12 +105,13 @@ pub async fn create_database(
         (conn, profile.driver.clone())
     };
 
+    let quoted = quote_identifier(&name, Quoting::for_driver(&db_kind));
     let sql = match db_kind {
-        DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon => {
-            format!("CREATE DATABASE \"{}\"", name)
-        }
-        DbDriver::MySql => format!("CREATE DATABASE `{}`", name),
-        DbDriver::SqlServer => format!("CREATE DATABASE [{}]", name),
+        DbDriver::Postgres
+        | DbDriver::Supabase
+        | DbDriver::Neon
+        | DbDriver::MySql
+        | DbDriver::SqlServer => format!("CREATE DATABASE {}", quoted),
         DbDriver::Sqlite => {
             return Err(DbError::InvalidInput(
                 "SQLite databases are individual files and cannot be created from an active connection".to_string(),
@@ -92,6 +135,7 @@ pub async fn create_database(
     };
 
     driver.execute_query(&sql).await?;
+    forget_schema_tree(&state, &connection_id);
 
     Ok(DdlResult {
         sql: vec![sql],
@@ -177,9 +221,7 @@ pub async fn create_table(
         driver
     };
 
-    for sql in &preview_result.sql {
-        driver.execute_query(sql).await?;
-    }
+    execute_ddl(&state, &connection_id, driver.as_ref(), &preview_result.sql).await?;
 
     Ok(preview_result)
 }
@@ -255,9 +297,7 @@ pub async fn alter_table(
         driver
     };
 
-    for sql in &preview_result.sql {
-        driver.execute_query(sql).await?;
-    }
+    execute_ddl(&state, &connection_id, driver.as_ref(), &preview_result.sql).await?;
 
     Ok(preview_result)
 }
@@ -331,9 +371,1001 @@ pub async fn drop_table(
         driver
     };
 
-    for sql in &preview_result.sql {
-        driver.execute_query(sql).await?;
+    execute_ddl(&state, &connection_id, driver.as_ref(), &preview_result.sql).await?;
+
+    Ok(preview_result)
+}
+
+/// Preview CREATE VIEW SQL without executing it
+///
+/// Validates the view name and query and returns the generated statement(s).
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `view` - View definition with its name, optional column list and query
+/// * `state` - Application state containing active connections
+///
+/// # Returns
+///
+/// Returns a `DdlResult` with the generated SQL and a success message.
+#[tauri::command]
+pub async fn preview_create_view(
+    connection_id: String,
+    view: ViewDefinition,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<DdlResult, DbError> {
+    let state_guard = state.lock().unwrap();
+
+    // Verify connection exists
+    if !state_guard.connections.contains_key(&connection_id) {
+        return Err(DbError::NotFound(format!("Connection '{}' not found", connection_id)));
     }
 
+    // Get the connection profile to determine the database driver
+    let profile = state_guard
+        .connection_profiles
+        .get(&connection_id)
+        .ok_or_else(|| DbError::NotFound(format!("Connection profile for '{}' not found", connection_id)))?;
+
+    let generator = get_ddl_generator(&profile.driver)?;
+
+    generator.generate_create_view(&view)
+}
+
+/// Create a view
+///
+/// Generates and executes the SQL to create (or replace) a view. Drivers
+/// without CREATE OR REPLACE drop the existing view first.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `view` - View definition with its name, optional column list and query
+/// * `state` - Application state containing active connections
+///
+/// # Returns
+///
+/// Returns a `DdlResult` with the executed SQL and a success message.
+#[tauri::command]
+pub async fn create_view(
+    connection_id: String,
+    view: ViewDefinition,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<DdlResult, DbError> {
+    let preview_result = preview_create_view(connection_id.clone(), view, state.clone()).await?;
+
+    let driver = {
+        let state_guard = state.lock().unwrap();
+        let driver = state_guard
+            .connections
+            .get(&connection_id)
+            .ok_or_else(|| DbError::NotFound(format!("Connection '{}' not found", connection_id)))?
+            .clone();
+        driver
+    };
+
+    execute_ddl(&state, &connection_id, driver.as_ref(), &preview_result.sql).await?;
+
     Ok(preview_result)
 }
+
+/// Preview DROP VIEW SQL without executing it
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `drop` - View drop definition
+/// * `state` - Application state containing active connections
+///
+/// # Returns
+///
+/// Returns a `DdlResult` with the generated SQL and a success message.
+#[tauri::command]
+pub async fn preview_drop_view(
+    connection_id: String,
+    drop: DropViewDefinition,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<DdlResult, DbError> {
+    let state_guard = state.lock().unwrap();
+
+    // Verify connection exists
+    if !state_guard.connections.contains_key(&connection_id) {
+        return Err(DbError::NotFound(format!("Connection '{}' not found", connection_id)));
+    }
+
+    // Get the connection profile to determine the database driver
+    let profile = state_guard
+        .connection_profiles
+        .get(&connection_id)
+        .ok_or_else(|| DbError::NotFound(format!("Connection profile for '{}' not found", connection_id)))?;
+
+    let generator = get_ddl_generator(&profile.driver)?;
+
+    generator.generate_drop_view(&drop)
+}
+
+/// Drop a view
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `drop` - View drop definition
+/// * `state` - Application state containing active connections
+///
+/// # Returns
+///
+/// Returns a `DdlResult` with the executed SQL and a success message.
+#[tauri::command]
+pub async fn drop_view(
+    connection_id: String,
+    drop: DropViewDefinition,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<DdlResult, DbError> {
+    let preview_result = preview_drop_view(connection_id.clone(), drop, state.clone()).await?;
+
+    let driver = {
+        let state_guard = state.lock().unwrap();
+        let driver = state_guard
+            .connections
+            .get(&connection_id)
+            .ok_or_else(|| DbError::NotFound(format!("Connection '{}' not found", connection_id)))?
+            .clone();
+        driver
+    };
+
+    execute_ddl(&state, &connection_id, driver.as_ref(), &preview_result.sql).await?;
+
+    Ok(preview_result)
+}
+
+/// Result of duplicating a table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateTableResult {
+    /// Executed SQL statements
+    pub sql: Vec<String>,
+
+    /// Rows copied into the new table, if data was included
+    pub rows_copied: Option<u64>,
+
+    /// Foreign keys on or pointing at the source table; none of them are
+    /// recreated for the copy
+    pub skipped_foreign_keys: Vec<ForeignKeyInfo>,
+
+    /// Success message
+    pub message: String,
+}
+
+/// Create a new table with the same structure as an existing one
+///
+/// Rebuilds the source's columns, primary key and indexes from its
+/// introspected schema and creates them under `new_table`, optionally
+/// copying the rows as well. Auto-increment columns get their own sequence
+/// or identity. Foreign keys are not copied; they are listed in the result
+/// so the user can decide which ones the copy should have.
+///
+/// On PostgreSQL `use_like` (the default there) uses
+/// `CREATE TABLE ... (LIKE source INCLUDING ALL)` instead, which keeps exact
+/// column types and check constraints, but serial columns keep drawing from
+/// the source table's sequence.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the source table; the copy is created there too
+/// * `source_table` - Table to copy
+/// * `new_table` - Name of the table to create
+/// * `include_data` - Also copy all rows
+/// * `use_like` - PostgreSQL only: use `CREATE TABLE ... (LIKE ...)`
+/// * `state` - Application state containing active connections
+#[tauri::command]
+pub async fn duplicate_table_structure(
+    connection_id: String,
+    schema: String,
+    source_table: String,
+    new_table: String,
+    include_data: bool,
+    use_like: Option<bool>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<DuplicateTableResult, DbError> {
+    crate::ddl::validate_identifier(&new_table, "Table name")?;
+
+    let (connection, db_kind) = {
+        let state_guard = state.lock().unwrap();
+        let connection = state_guard
+            .connections
+            .get(&connection_id)
+            .ok_or_else(|| DbError::NotFound(format!("Connection '{}' not found", connection_id)))?
+            .clone();
+        let profile = state_guard
+            .connection_profiles
+            .get(&connection_id)
+            .ok_or_else(|| {
+                DbError::NotFound(format!("Connection profile for '{}' not found", connection_id))
+            })?;
+        (connection, profile.driver.clone())
+    };
+
+    let source = connection.get_table_schema(&schema, &source_table).await?;
+    if source.columns.is_empty() {
+        return Err(DbError::NotFound(format!(
+            "Table '{}.{}' not found",
+            schema, source_table
+        )));
+    }
+
+    let skipped_foreign_keys: Vec<ForeignKeyInfo> = connection
+        .get_foreign_keys(&schema)
+        .await?
+        .into_iter()
+        .filter(|fk| fk.table == source_table || fk.referenced_table == source_table)
+        .collect();
+
+    let use_like = db_kind.is_postgres_compatible() && use_like.unwrap_or(true);
+    let mut sql = if use_like {
+        vec![format!(
+            "CREATE TABLE {} (LIKE {} INCLUDING ALL);",
+            quote_qualified(Some(&schema), &new_table, Quoting::Standard),
+            quote_qualified(Some(&schema), &source_table, Quoting::Standard)
+        )]
+    } else {
+        let generator = get_ddl_generator(&db_kind)?;
+        let (table, indexes) = duplicate_table_definition(&source, &schema, &new_table)?;
+        let mut sql = generator.generate_create_table(&table)?.sql;
+        for index in &indexes {
+            let index_sql = generator.generate_create_index(Some(&schema), &new_table, index)?;
+            sql.extend(index_sql.sql);
+        }
+        sql
+    };
+
+    execute_ddl(&state, &connection_id, connection.as_ref(), &sql).await?;
+
+    let mut rows_copied = None;
+    if include_data {
+        let copy = copy_rows_sql(&db_kind, &schema, &source_table, &new_table, &source.columns);
+        rows_copied = connection.execute_query(&copy).await?.rows_affected;
+        state
+            .lock()
+            .unwrap()
+            .result_cache
+            .invalidate_table(&connection_id, &new_table);
+        sql.push(copy);
+
+        // Move the new table's sequences past the copied ids
+        if db_kind.is_postgres_compatible() && !use_like {
+            for column in source.columns.iter().filter(|c| c.is_auto_increment) {
+                let table = quote_qualified(Some(&schema), &new_table, Quoting::Standard);
+                let reset = format!(
+                    "SELECT setval(pg_get_serial_sequence('{}', '{}'), MAX({})) FROM {}",
+                    table.replace('\'', "''"),
+                    column.name.replace('\'', "''"),
+                    quote_identifier(&column.name, Quoting::Standard),
+                    table
+                );
+                connection.execute_query(&reset).await?;
+                sql.push(reset);
+            }
+        }
+    }
+
+    Ok(DuplicateTableResult {
+        sql,
+        rows_copied,
+        skipped_foreign_keys,
+        message: format!("Table '{}' created from '{}'", new_table, source_table),
+    })
+}
+
+/// Rebuild the definition of `source` under a new name, along with its
+/// secondary indexes
+///
+/// Column types are reused verbatim except for auto-increment columns, which
+/// need a plain integer type for the generators to emit SERIAL, IDENTITY or
+/// AUTO_INCREMENT; their sequence default is dropped so the copy gets its own.
+fn duplicate_table_definition(
+    source: &TableSchema,
+    schema: &str,
+    new_table: &str,
+) -> Result<(TableDefinition, Vec<IndexDefinition>), DbError> {
+    let pk_columns: Vec<String> = source
+        .primary_key_columns()
+        .iter()
+        .map(|c| c.name.clone())
+        .collect();
+    let composite_pk = pk_columns.len() > 1;
+
+    let mut columns = Vec::with_capacity(source.columns.len());
+    for col in &source.columns {
+        // information_schema reports arrays and enums/domains without the
+        // actual type, so they can't be recreated from this metadata
+        if matches!(col.data_type.as_str(), "ARRAY" | "USER-DEFINED") {
+            return Err(DbError::InvalidInput(format!(
+                "Column '{}' has a {} type that can't be copied from metadata; use the LIKE option instead",
+                col.name, col.data_type
+            )));
+        }
+
+        let integer_type = col
+            .is_auto_increment
+            .then(|| integer_column_type(&col.data_type))
+            .flatten();
+        let auto_increment = integer_type.is_some();
+        columns.push(ColumnDefinition {
+            name: col.name.clone(),
+            column_type: integer_type.unwrap_or_else(|| ColumnType::Custom {
+                type_name: col.data_type.clone(),
+            }),
+            nullable: col.nullable,
+            default: if auto_increment {
+                None
+            } else {
+                col.default_value.clone()
+            },
+            primary_key: col.is_primary_key && !composite_pk,
+            auto_increment,
+            comment: None,
+        });
+    }
+
+    let indexes = source
+        .indexes
+        .iter()
+        .filter(|index| !index.is_primary)
+        .map(|index| IndexDefinition {
+            name: duplicate_index_name(&index.name, &index.columns, &source.table.name, new_table),
+            columns: index.columns.clone(),
+            unique: index.is_unique,
+            index_type: IndexType::BTree,
+        })
+        .collect();
+
+    let table = TableDefinition {
+        schema: Some(schema.to_string()),
+        name: new_table.to_string(),
+        columns,
+        primary_key: composite_pk.then_some(pk_columns),
+        foreign_keys: vec![],
+        unique_constraints: vec![],
+        check_constraints: vec![],
+        comment: None,
+        if_not_exists: false,
+    };
+    Ok((table, indexes))
+}
+
+/// Integer column type for a driver-reported type name such as `integer`,
+/// `int(11)` or `int8`
+fn integer_column_type(data_type: &str) -> Option<ColumnType> {
+    let lower = data_type.to_ascii_lowercase();
+    let base = lower.split('(').next().unwrap_or_default().trim();
+    match base {
+        "smallint" | "int2" | "smallserial" => Some(ColumnType::SmallInt),
+        "integer" | "int" | "int4" | "serial" => Some(ColumnType::Integer),
+        "bigint" | "int8" | "bigserial" => Some(ColumnType::BigInt),
+        _ => None,
+    }
+}
+
+/// Name for the copy of an index; index names are unique per schema, so the
+/// source table's name in it is swapped for the new one
+fn duplicate_index_name(
+    name: &str,
+    columns: &[String],
+    source_table: &str,
+    new_table: &str,
+) -> String {
+    let renamed = if name.starts_with("sqlite_") {
+        // SQLite reserves this prefix for the indexes behind UNIQUE constraints
+        format!("{}_{}_key", new_table, columns.join("_"))
+    } else if name.contains(source_table) {
+        name.replacen(source_table, new_table, 1)
+    } else {
+        format!("{}_{}", new_table, name)
+    };
+    renamed.chars().take(63).collect()
+}
+
+/// `INSERT ... SELECT` copying every row of `source_table` into `new_table`
+fn copy_rows_sql(
+    driver: &DbDriver,
+    schema: &str,
+    source_table: &str,
+    new_table: &str,
+    columns: &[ColumnInfo],
+) -> String {
+    let dialect = Quoting::for_driver(driver);
+    let qualify = |table: &str| match driver {
+        DbDriver::Sqlite | DbDriver::Turso => quote_identifier(table, dialect),
+        _ => quote_qualified(Some(schema), table, dialect),
+    };
+
+    let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+    let column_list = quote_list(&names, dialect);
+    let insert = format!(
+        "INSERT INTO {} ({}) SELECT {} FROM {}",
+        qualify(new_table),
+        column_list,
+        column_list,
+        qualify(source_table)
+    );
+
+    // SQL Server refuses explicit values for identity columns unless asked
+    if matches!(driver, DbDriver::SqlServer) && columns.iter().any(|c| c.is_auto_increment) {
+        let target = qualify(new_table);
+        format!(
+            "SET IDENTITY_INSERT {} ON; {}; SET IDENTITY_INSERT {} OFF;",
+            target, insert, target
+        )
+    } else {
+        format!("{};", insert)
+    }
+}
+
+/// Object that refers to a table by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableDependent {
+    /// "view", "foreignKey" or "sequence"
+    pub kind: String,
+
+    /// Schema of the dependent object
+    pub schema: String,
+
+    /// Name of the dependent object
+    pub name: String,
+
+    /// What the rename does to it
+    pub note: String,
+}
+
+/// Result of renaming a table, or of previewing the rename
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameTableResult {
+    /// SQL statements executed (or to be executed)
+    pub sql: Vec<String>,
+
+    /// Views, foreign keys and sequences referring to the old name
+    pub dependents: Vec<TableDependent>,
+
+    /// Success message
+    pub message: String,
+}
+
+/// Preview renaming a table without executing it
+///
+/// Checks the table exists and the new name is free, and lists the objects
+/// referring to the old name so the user can review them first.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `old_name` - Current table name
+/// * `new_name` - Name to give the table
+/// * `state` - Application state containing active connections
+#[tauri::command]
+pub async fn preview_rename_table(
+    connection_id: String,
+    schema: String,
+    old_name: String,
+    new_name: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<RenameTableResult, DbError> {
+    let (connection, db_kind) = connection_and_driver(&state, &connection_id)?;
+    plan_rename_table(connection.as_ref(), &db_kind, &schema, &old_name, &new_name).await
+}
+
+/// Rename a table
+///
+/// Runs `ALTER TABLE ... RENAME TO` (PostgreSQL, SQLite), `RENAME TABLE`
+/// (MySQL) or `sp_rename` (SQL Server). Renaming breaks queries and views
+/// that name the old table, so it is refused with
+/// `DbError::ConfirmationRequired` unless `confirmed` is true when the
+/// `confirmDestructive` setting is on or the connection is tagged as
+/// production. The cached schema tree entries of both names are dropped.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `old_name` - Current table name
+/// * `new_name` - Name to give the table
+/// * `confirmed` - The user confirmed the rename
+/// * `state` - Application state containing active connections
+/// * `app` - Application handle, for the settings
+///
+/// # Returns
+///
+/// The executed SQL and the objects that referred to the old name
+#[tauri::command]
+pub async fn rename_table(
+    connection_id: String,
+    schema: String,
+    old_name: String,
+    new_name: String,
+    confirmed: Option<bool>,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<RenameTableResult, DbError> {
+    if !confirmed.unwrap_or(false) {
+        let confirm_setting = load_settings(&app)?.query.confirm_destructive;
+        let production = state
+            .lock()
+            .unwrap()
+            .get_profile(&connection_id)
+            .and_then(|p| p.environment.as_ref())
+            .is_some_and(Environment::requires_confirmation);
+        if confirm_setting || production {
+            return Err(DbError::ConfirmationRequired(format!(
+                "Rename table {}.{} to {}",
+                schema, old_name, new_name
+            )));
+        }
+    }
+
+    let (connection, db_kind) = connection_and_driver(&state, &connection_id)?;
+    let plan =
+        plan_rename_table(connection.as_ref(), &db_kind, &schema, &old_name, &new_name).await?;
+
+    let mut result = Ok(());
+    for sql in &plan.sql {
+        result = connection.execute_query(sql).await.map(|_| ());
+        if result.is_err() {
+            break;
+        }
+    }
+    {
+        let mut state = state.lock().unwrap();
+        if let Some(cache) = state.metadata_cache.get_mut(&connection_id) {
+            cache.forget_table(&schema, &old_name);
+            cache.forget_table(&schema, &new_name);
+        }
+        state.result_cache.invalidate_table(&connection_id, &old_name);
+        state.result_cache.invalidate_table(&connection_id, &new_name);
+    }
+    result?;
+
+    Ok(plan)
+}
+
+/// The active connection and its driver type
+fn connection_and_driver(
+    state: &Mutex<AppState>,
+    connection_id: &str,
+) -> Result<(Arc<dyn DatabaseDriver>, DbDriver), DbError> {
+    let state_guard = state.lock().unwrap();
+    let connection = state_guard
+        .connections
+        .get(connection_id)
+        .ok_or_else(|| DbError::NotFound(format!("Connection '{}' not found", connection_id)))?
+        .clone();
+    let profile = state_guard
+        .connection_profiles
+        .get(connection_id)
+        .ok_or_else(|| {
+            DbError::NotFound(format!(
+                "Connection profile for '{}' not found",
+                connection_id
+            ))
+        })?;
+    Ok((connection, profile.driver.clone()))
+}
+
+/// Check a rename can go ahead and collect its SQL and the objects it affects
+async fn plan_rename_table(
+    connection: &dyn DatabaseDriver,
+    db_kind: &DbDriver,
+    schema: &str,
+    old_name: &str,
+    new_name: &str,
+) -> Result<RenameTableResult, DbError> {
+    if old_name == new_name {
+        return Err(DbError::InvalidInput(
+            "The new table name is the same as the current one".to_string(),
+        ));
+    }
+    let ddl =
+        get_ddl_generator(db_kind)?.generate_rename_table(Some(schema), old_name, new_name)?;
+
+    let tables = connection.get_tables(schema).await?;
+    if !tables.iter().any(|t| t.name == old_name) {
+        return Err(DbError::NotFound(format!(
+            "Table '{}.{}' not found",
+            schema, old_name
+        )));
+    }
+    if tables.iter().any(|t| t.name == new_name) {
+        return Err(DbError::InvalidInput(format!(
+            "'{}.{}' already exists",
+            schema, new_name
+        )));
+    }
+
+    let mut dependents: Vec<TableDependent> = connection
+        .get_foreign_keys(schema)
+        .await?
+        .into_iter()
+        .filter(|fk| fk.referenced_table == old_name && fk.table != old_name)
+        .map(|fk| TableDependent {
+            kind: "foreignKey".to_string(),
+            note: format!(
+                "Constraint on '{}'; the database points it at the new name",
+                fk.table
+            ),
+            schema: fk.schema,
+            name: fk.name,
+        })
+        .collect();
+
+    let literal = |value: &str| escape_literal(value, db_kind);
+    for (kind, note, sql) in dependent_queries(db_kind, schema, old_name, literal) {
+        let result = connection.execute_query(&sql).await?;
+        for row in result.rows {
+            let text = |i: usize| {
+                row.get(i)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            dependents.push(TableDependent {
+                kind: kind.to_string(),
+                schema: text(0),
+                name: text(1),
+                note: note.to_string(),
+            });
+        }
+    }
+
+    Ok(RenameTableResult {
+        sql: ddl.sql,
+        dependents,
+        message: ddl.message,
+    })
+}
+
+/// Queries listing the views and sequences that refer to `schema.table`
+///
+/// Each returns (schema, name) rows and comes with the kind and note to
+/// report for them. `literal` quotes a string literal for the connection.
+/// Name matches on MySQL and SQLite search the view text, so they may
+/// include views that merely mention the name.
+fn dependent_queries(
+    db_kind: &DbDriver,
+    schema: &str,
+    table: &str,
+    literal: impl Fn(&str) -> String,
+) -> Vec<(&'static str, &'static str, String)> {
+    let (schema_lit, table_lit) = (literal(schema), literal(table));
+    match db_kind {
+        DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon => vec![
+            (
+                "view",
+                "Refers to the table itself and follows the rename; queries in functions do not",
+                format!(
+                    "SELECT DISTINCT view_schema, view_name \
+                     FROM information_schema.view_table_usage \
+                     WHERE table_schema = {} AND table_name = {}",
+                    schema_lit, table_lit
+                ),
+            ),
+            (
+                "sequence",
+                "Keeps its old name; rename it as well if it should match",
+                format!(
+                    "SELECT sn.nspname, s.relname \
+                     FROM pg_class s \
+                     JOIN pg_namespace sn ON sn.oid = s.relnamespace \
+                     JOIN pg_depend d ON d.objid = s.oid \
+                       AND d.classid = 'pg_class'::regclass AND d.deptype IN ('a', 'i') \
+                     JOIN pg_class t ON t.oid = d.refobjid \
+                     JOIN pg_namespace tn ON tn.oid = t.relnamespace \
+                     WHERE s.relkind = 'S' AND tn.nspname = {} AND t.relname = {}",
+                    schema_lit, table_lit
+                ),
+            ),
+        ],
+        DbDriver::MySql => vec![(
+            "view",
+            "Names the old table in its definition and fails until recreated",
+            format!(
+                "SELECT TABLE_SCHEMA, TABLE_NAME FROM information_schema.VIEWS \
+                 WHERE TABLE_SCHEMA = DATABASE() AND VIEW_DEFINITION LIKE {}",
+                literal(&format!("%{}%", quote_identifier(table, Quoting::MySql)))
+            ),
+        )],
+        DbDriver::SqlServer => vec![(
+            "view",
+            "Names the old table in its definition and fails until altered",
+            format!(
+                "SELECT DISTINCT OBJECT_SCHEMA_NAME(d.referencing_id), \
+                 OBJECT_NAME(d.referencing_id) \
+                 FROM sys.sql_expression_dependencies d \
+                 JOIN sys.views v ON v.object_id = d.referencing_id \
+                 WHERE d.referenced_entity_name = {} \
+                 AND COALESCE(d.referenced_schema_name, {}) = {}",
+                table_lit, schema_lit, schema_lit
+            ),
+        )],
+        DbDriver::Sqlite | DbDriver::Turso => vec![(
+            "view",
+            "SQLite rewrites it to the new name unless legacy_alter_table is on",
+            format!(
+                "SELECT {}, name FROM sqlite_master WHERE type = 'view' AND sql LIKE {}",
+                schema_lit,
+                literal(&format!("%{}%", table))
+            ),
+        )],
+        DbDriver::MongoDb | DbDriver::Redis => Vec::new(),
+    }
+}
+
+/// Set or remove the comment of a table
+///
+/// Runs `COMMENT ON TABLE` (PostgreSQL), `ALTER TABLE ... COMMENT`
+/// (MySQL) or sets the `MS_Description` extended property (SQL Server).
+/// SQLite has no comments and returns an error.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table to comment on
+/// * `comment` - New comment; `None` or blank removes it
+/// * `state` - Application state containing active connections
+#[tauri::command]
+pub async fn set_table_comment(
+    connection_id: String,
+    schema: String,
+    table: String,
+    comment: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<DdlResult, DbError> {
+    let (connection, db_kind) = connection_and_driver(&state, &connection_id)?;
+    let ddl = get_ddl_generator(&db_kind)?.generate_table_comment(
+        Some(&schema),
+        &table,
+        non_blank(comment.as_deref()),
+    )?;
+
+    execute_comment(
+        &state,
+        &connection_id,
+        connection.as_ref(),
+        &schema,
+        &table,
+        &ddl,
+    )
+    .await?;
+    Ok(ddl)
+}
+
+/// Set or remove the comment of a column
+///
+/// Runs `COMMENT ON COLUMN` (PostgreSQL), `ALTER TABLE ... MODIFY COLUMN`
+/// restating the column with its new comment (MySQL) or sets the
+/// `MS_Description` extended property (SQL Server). SQLite has no comments
+/// and returns an error.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table containing the column
+/// * `column` - Column to comment on
+/// * `comment` - New comment; `None` or blank removes it
+/// * `state` - Application state containing active connections
+#[tauri::command]
+pub async fn set_column_comment(
+    connection_id: String,
+    schema: String,
+    table: String,
+    column: String,
+    comment: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<DdlResult, DbError> {
+    let (connection, db_kind) = connection_and_driver(&state, &connection_id)?;
+    let generator = get_ddl_generator(&db_kind)?;
+
+    let table_schema = connection.get_table_schema(&schema, &table).await?;
+    let column_info = table_schema
+        .columns
+        .iter()
+        .find(|c| c.name == column)
+        .ok_or_else(|| {
+            DbError::NotFound(format!(
+                "Column '{}' not found in '{}.{}'",
+                column, schema, table
+            ))
+        })?;
+    let ddl = generator.generate_column_comment(
+        Some(&schema),
+        &table,
+        column_info,
+        non_blank(comment.as_deref()),
+    )?;
+
+    execute_comment(
+        &state,
+        &connection_id,
+        connection.as_ref(),
+        &schema,
+        &table,
+        &ddl,
+    )
+    .await?;
+    Ok(ddl)
+}
+
+/// `comment` unless it is empty or only whitespace
+fn non_blank(comment: Option<&str>) -> Option<&str> {
+    comment.filter(|c| !c.trim().is_empty())
+}
+
+/// Run comment statements, then drop the table's cached schema and query
+/// results so the new comment shows up on the next load
+async fn execute_comment(
+    state: &Mutex<AppState>,
+    connection_id: &str,
+    connection: &dyn DatabaseDriver,
+    schema: &str,
+    table: &str,
+    ddl: &DdlResult,
+) -> Result<(), DbError> {
+    let mut result = Ok(());
+    for sql in &ddl.sql {
+        result = connection.execute_query(sql).await.map(|_| ());
+        if result.is_err() {
+            break;
+        }
+    }
+    let mut state = state.lock().unwrap();
+    if let Some(cache) = state.metadata_cache.get_mut(connection_id) {
+        cache.forget_table(schema, table);
+    }
+    state.result_cache.invalidate_table(connection_id, table);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{IndexInfo, TableInfo};
+
+    fn column(name: &str, data_type: &str, primary: bool, auto: bool) -> ColumnInfo {
+        ColumnInfo {
+            is_primary_key: primary,
+            is_auto_increment: auto,
+            default_value: auto.then(|| format!("nextval('orders_{}_seq'::regclass)", name)),
+            ..ColumnInfo::new(name.to_string(), data_type.to_string(), !primary)
+        }
+    }
+
+    fn orders_schema() -> TableSchema {
+        TableSchema::new(
+            TableInfo {
+                name: "orders".to_string(),
+                schema: "public".to_string(),
+                row_count: None,
+                table_type: "TABLE".to_string(),
+            },
+            vec![
+                column("id", "integer", true, true),
+                column("note", "character varying", false, false),
+            ],
+            vec![
+                IndexInfo::new("orders_pkey".to_string(), vec!["id".to_string()], true, true),
+                IndexInfo::new("idx_note".to_string(), vec!["note".to_string()], false, false),
+                IndexInfo::new("orders_note_key".to_string(), vec!["note".to_string()], true, false),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_duplicate_table_definition() {
+        let (table, indexes) =
+            duplicate_table_definition(&orders_schema(), "public", "orders_copy").unwrap();
+
+        assert_eq!(table.name, "orders_copy");
+        assert_eq!(table.primary_key, None);
+        let id = &table.columns[0];
+        assert!(matches!(id.column_type, ColumnType::Integer));
+        assert!(id.auto_increment && id.primary_key);
+        // The source's sequence must not be shared
+        assert_eq!(id.default, None);
+        assert!(matches!(
+            &table.columns[1].column_type,
+            ColumnType::Custom { type_name } if type_name == "character varying"
+        ));
+
+        let names: Vec<&str> = indexes.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["orders_copy_idx_note", "orders_copy_note_key"]);
+        assert!(indexes[1].unique);
+    }
+
+    #[test]
+    fn test_duplicate_index_name_for_sqlite_autoindex() {
+        assert_eq!(
+            duplicate_index_name(
+                "sqlite_autoindex_orders_1",
+                &["a".to_string(), "b".to_string()],
+                "orders",
+                "copy"
+            ),
+            "copy_a_b_key"
+        );
+    }
+
+    #[test]
+    fn test_copy_rows_sql() {
+        let columns = orders_schema().columns;
+        assert_eq!(
+            copy_rows_sql(&DbDriver::Postgres, "public", "orders", "orders_copy", &columns),
+            "INSERT INTO \"public\".\"orders_copy\" (\"id\", \"note\") SELECT \"id\", \"note\" FROM \"public\".\"orders\";"
+        );
+        assert_eq!(
+            copy_rows_sql(&DbDriver::SqlServer, "dbo", "orders", "orders_copy", &columns),
+            "SET IDENTITY_INSERT [dbo].[orders_copy] ON; \
+             INSERT INTO [dbo].[orders_copy] ([id], [note]) SELECT [id], [note] FROM [dbo].[orders]; \
+             SET IDENTITY_INSERT [dbo].[orders_copy] OFF;"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plan_rename_table() {
+        use crate::drivers::sqlite::SqliteDriver;
+        use crate::drivers::ConnectionOptions;
+
+        let db_path = std::env::temp_dir().join("test_rename_table.sqlite");
+        std::fs::remove_file(&db_path).ok();
+        let driver = SqliteDriver::connect(ConnectionOptions {
+            host: String::new(),
+            port: 0,
+            username: String::new(),
+            password: None,
+            database: Some(db_path.to_str().unwrap().to_string()),
+            timeout: None,
+            require_tls: false,
+            application_name: None,
+            default_schema: None,
+            sqlite: Default::default(),
+        })
+        .await
+        .unwrap();
+        driver
+            .execute_query("CREATE TABLE orders (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+        driver
+            .execute_query("CREATE VIEW recent_orders AS SELECT id FROM orders")
+            .await
+            .unwrap();
+
+        let plan = plan_rename_table(&driver, &DbDriver::Sqlite, "main", "orders", "purchases")
+            .await
+            .unwrap();
+        assert_eq!(
+            plan.sql,
+            vec!["ALTER TABLE \"orders\" RENAME TO \"purchases\""]
+        );
+        assert_eq!(plan.dependents.len(), 1);
+        assert_eq!(plan.dependents[0].kind, "view");
+        assert_eq!(plan.dependents[0].name, "recent_orders");
+
+        let taken = plan_rename_table(
+            &driver,
+            &DbDriver::Sqlite,
+            "main",
+            "orders",
+            "recent_orders",
+        );
+        assert!(matches!(taken.await, Err(DbError::InvalidInput(_))));
+        let missing = plan_rename_table(&driver, &DbDriver::Sqlite, "main", "carts", "baskets");
+        assert!(matches!(missing.await, Err(DbError::NotFound(_))));
+
+        driver.close().await.ok();
+        std::fs::remove_file(&db_path).ok();
+    }
+}
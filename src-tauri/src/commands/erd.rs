@@ -0,0 +1,292 @@
+//! Entity-relationship diagram commands
+//!
+//! Collects a schema's tables, columns and foreign keys into one
+//! `SchemaGraph` for the ERD view.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tokio::task::JoinSet;
+
+use crate::drivers::DatabaseDriver;
+use crate::models::{ColumnInfo, DbError, ForeignKeyInfo, TableInfo};
+use crate::state::AppState;
+
+/// Column schemas fetched at once when the metadata cache doesn't have them
+const MAX_INFLIGHT: usize = 16;
+
+/// A table (or view) node of a `SchemaGraph`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphTable {
+    pub name: String,
+    /// "TABLE", "VIEW", etc., as reported by the driver
+    pub table_type: String,
+    pub columns: Vec<ColumnInfo>,
+    /// Primary key columns, in table order
+    pub primary_key: Vec<String>,
+    /// Every column belongs to a foreign key, and there are at least two:
+    /// the table links other tables many-to-many
+    pub is_join_table: bool,
+}
+
+/// A foreign key edge of a `SchemaGraph`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphEdge {
+    /// Constraint name
+    pub name: String,
+    pub source_table: String,
+    pub source_columns: Vec<String>,
+    /// Differs from the graph's schema for cross-schema references
+    pub target_schema: String,
+    pub target_table: String,
+    pub target_columns: Vec<String>,
+    /// The foreign key points back at its own table
+    pub self_reference: bool,
+    pub on_delete: Option<String>,
+    pub on_update: Option<String>,
+}
+
+/// Tables and foreign keys of one schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaGraph {
+    pub schema: String,
+    pub tables: Vec<GraphTable>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// Assemble a `SchemaGraph` from already fetched metadata
+///
+/// `columns` is keyed by table name. Tables missing from it get no columns.
+fn build_schema_graph(
+    schema: &str,
+    tables: Vec<TableInfo>,
+    mut columns: HashMap<String, Vec<ColumnInfo>>,
+    foreign_keys: Vec<ForeignKeyInfo>,
+) -> SchemaGraph {
+    let edges: Vec<GraphEdge> = foreign_keys
+        .into_iter()
+        .filter(|fk| fk.schema == schema)
+        .map(|fk| GraphEdge {
+            self_reference: fk.referenced_schema == fk.schema && fk.referenced_table == fk.table,
+            name: fk.name,
+            source_table: fk.table,
+            source_columns: fk.columns,
+            target_schema: fk.referenced_schema,
+            target_table: fk.referenced_table,
+            target_columns: fk.referenced_columns,
+            on_delete: fk.on_delete,
+            on_update: fk.on_update,
+        })
+        .collect();
+
+    let tables = tables
+        .into_iter()
+        .map(|table| {
+            let columns = columns.remove(&table.name).unwrap_or_default();
+            let primary_key = columns
+                .iter()
+                .filter(|c| c.is_primary_key)
+                .map(|c| c.name.clone())
+                .collect();
+
+            let outgoing: Vec<&GraphEdge> = edges
+                .iter()
+                .filter(|e| e.source_table == table.name)
+                .collect();
+            let fk_columns: HashSet<&str> = outgoing
+                .iter()
+                .flat_map(|e| e.source_columns.iter().map(String::as_str))
+                .collect();
+            let is_join_table = outgoing.len() >= 2
+                && !columns.is_empty()
+                && columns.iter().all(|c| fk_columns.contains(c.name.as_str()));
+
+            GraphTable {
+                name: table.name,
+                table_type: table.table_type,
+                columns,
+                primary_key,
+                is_join_table,
+            }
+        })
+        .collect();
+
+    SchemaGraph {
+        schema: schema.to_string(),
+        tables,
+        edges,
+    }
+}
+
+/// Get the entity-relationship graph of a schema
+///
+/// Tables and foreign keys are each listed in one call. Columns come from
+/// the connection's `MetadataCache` when it is fresh; otherwise each table's
+/// schema is fetched, up to `MAX_INFLIGHT` at a time.
+///
+/// # Arguments
+/// * `connection_id` - UUID of the active connection
+/// * `schema` - Schema to graph
+///
+/// # Returns
+/// Every table and view with its columns and primary key, and every foreign
+/// key declared in the schema, self-references included
+#[tauri::command]
+pub async fn get_schema_graph(
+    connection_id: String,
+    schema: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<SchemaGraph, DbError> {
+    let (connection, cached) = {
+        let state = state.lock().unwrap();
+        let connection = state
+            .get_connection(&connection_id)
+            .ok_or_else(|| DbError::ConnectionError("Connection not found".to_string()))?
+            .clone();
+        let cached: HashMap<String, Vec<ColumnInfo>> = state
+            .metadata_cache
+            .get(&connection_id)
+            .filter(|cache| !cache.is_stale())
+            .map(|cache| {
+                let prefix = format!("{}.", schema);
+                cache
+                    .columns
+                    .iter()
+                    .filter_map(|(key, columns)| {
+                        let table = key.strip_prefix(&prefix)?;
+                        Some((table.to_string(), columns.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        (connection, cached)
+    };
+
+    let tables = connection.get_tables(&schema).await?;
+    let foreign_keys = connection.get_foreign_keys(&schema).await?;
+
+    let missing: Vec<String> = tables
+        .iter()
+        .filter(|t| !cached.contains_key(&t.name))
+        .map(|t| t.name.clone())
+        .collect();
+    let mut columns = cached;
+    columns.extend(fetch_columns(connection, &schema, missing).await?);
+
+    Ok(build_schema_graph(&schema, tables, columns, foreign_keys))
+}
+
+/// Fetch the columns of `tables`, keyed by table name
+async fn fetch_columns(
+    connection: Arc<dyn DatabaseDriver>,
+    schema: &str,
+    tables: Vec<String>,
+) -> Result<HashMap<String, Vec<ColumnInfo>>, DbError> {
+    let mut columns = HashMap::new();
+    let mut pending = tables.into_iter();
+    let mut set: JoinSet<Result<(String, Vec<ColumnInfo>), DbError>> = JoinSet::new();
+
+    let spawn = |set: &mut JoinSet<Result<(String, Vec<ColumnInfo>), DbError>>, table: String| {
+        let connection = Arc::clone(&connection);
+        let schema = schema.to_string();
+        set.spawn(async move {
+            let table_schema = connection.get_table_schema(&schema, &table).await?;
+            Ok((table, table_schema.columns))
+        });
+    };
+
+    for table in pending.by_ref().take(MAX_INFLIGHT) {
+        spawn(&mut set, table);
+    }
+    while let Some(joined) = set.join_next().await {
+        let (table, table_columns) = joined
+            .map_err(|e| DbError::InternalError(format!("Metadata task failed: {}", e)))??;
+        columns.insert(table, table_columns);
+        if let Some(table) = pending.next() {
+            spawn(&mut set, table);
+        }
+    }
+
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fk(name: &str, table: &str, columns: &[&str], referenced_table: &str) -> ForeignKeyInfo {
+        ForeignKeyInfo::new(
+            name.to_string(),
+            table.to_string(),
+            "public".to_string(),
+            columns.iter().map(|c| c.to_string()).collect(),
+            referenced_table.to_string(),
+            "public".to_string(),
+            vec!["id".to_string()],
+        )
+    }
+
+    fn columns(names: &[&str], primary_key: &[&str]) -> Vec<ColumnInfo> {
+        names
+            .iter()
+            .map(|name| {
+                let mut column = ColumnInfo::new(name.to_string(), "integer".to_string(), false);
+                column.is_primary_key = primary_key.contains(name);
+                column
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_schema_graph() {
+        let table = |name: &str| {
+            TableInfo::new(name.to_string(), "public".to_string(), "TABLE".to_string())
+        };
+        let tables = vec![
+            table("students"),
+            table("courses"),
+            table("enrollments"),
+            table("employees"),
+        ];
+        let mut cols = HashMap::new();
+        cols.insert("students".to_string(), columns(&["id", "name"], &["id"]));
+        cols.insert(
+            "enrollments".to_string(),
+            columns(&["student_id", "course_id"], &["student_id", "course_id"]),
+        );
+        cols.insert(
+            "employees".to_string(),
+            columns(&["id", "manager_id"], &["id"]),
+        );
+        let fks = vec![
+            fk("student_fk", "enrollments", &["student_id"], "students"),
+            fk("course_fk", "enrollments", &["course_id"], "courses"),
+            fk("manager_fk", "employees", &["manager_id"], "employees"),
+        ];
+
+        let graph = build_schema_graph("public", tables, cols, fks);
+
+        assert_eq!(graph.tables.len(), 4);
+        let by_name = |name: &str| graph.tables.iter().find(|t| t.name == name).unwrap();
+        assert_eq!(by_name("students").primary_key, vec!["id"]);
+        assert!(by_name("courses").columns.is_empty());
+        assert!(by_name("enrollments").is_join_table);
+        // One foreign key doesn't make a join table
+        assert!(!by_name("employees").is_join_table);
+        assert!(!by_name("students").is_join_table);
+
+        assert_eq!(graph.edges.len(), 3);
+        let self_refs: Vec<&str> = graph
+            .edges
+            .iter()
+            .filter(|e| e.self_reference)
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(self_refs, vec!["manager_fk"]);
+    }
+}
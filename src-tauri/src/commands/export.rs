@@ -6,11 +6,17 @@
 //! - Importing SQL dumps back into databases
 //! Uses native file dialogs for save/load locations.
 
+use crate::drivers::blob::blob_bytes;
+use crate::drivers::unique_column_names;
 use crate::models::connection::DbDriver;
-use crate::models::DbError;
+use crate::models::{DbError, QueryType};
+use crate::sql::literal::{binary_literal, escape_literal};
+use crate::sql::quote::{quote_identifier, quote_list, quote_qualified};
+use crate::sql::tokenizer::{tokenize_with, Quoting, TokenKind};
 use crate::state::AppState;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
@@ -30,8 +36,19 @@ pub struct SqlExportOptions {
     pub include_data: bool,
     /// Filter by specific tables (empty = all tables)
     pub tables: Vec<String>,
-    /// Schema to export from (PostgreSQL/MySQL)
+    /// Schema to export from; `None` uses the connection's default schema
     pub schema: Option<String>,
+    /// Rows per INSERT statement; 1 writes one INSERT per row
+    #[serde(default = "default_rows_per_insert")]
+    pub rows_per_insert: usize,
+    /// Name the columns in each INSERT so the dump loads even if the target
+    /// table's columns are in a different order
+    #[serde(default)]
+    pub include_column_names: bool,
+}
+
+fn default_rows_per_insert() -> usize {
+    100
 }
 
 impl Default for SqlExportOptions {
@@ -42,10 +59,260 @@ impl Default for SqlExportOptions {
             include_data: true,
             tables: Vec::new(),
             schema: None,
+            rows_per_insert: default_rows_per_insert(),
+            include_column_names: false,
         }
     }
 }
 
+/// How NULL is written in text exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NullRepresentation {
+    /// Empty field (the default)
+    #[default]
+    #[serde(rename = "empty")]
+    Empty,
+    /// The literal `NULL`
+    #[serde(rename = "NULL")]
+    Null,
+    /// `\N`, as read by PostgreSQL `COPY` and MySQL `LOAD DATA`
+    #[serde(rename = "\\N")]
+    BackslashN,
+}
+
+impl NullRepresentation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Empty => "",
+            Self::Null => "NULL",
+            Self::BackslashN => "\\N",
+        }
+    }
+}
+
+/// How binary values are written in exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryEncoding {
+    /// `0x`-prefixed lowercase hex (the default)
+    #[default]
+    Hex,
+    /// Standard base64
+    Base64,
+}
+
+/// Value rendering options for CSV/JSON exports
+///
+/// Drivers report binary values as tagged objects (see `drivers::blob`) and
+/// dates as ISO 8601 strings. Dates, and binary values from older saved
+/// results (`0x`-prefixed hex strings), are recognised by their text form: a
+/// text column that happens to hold such a value is re-encoded the same way.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportOptions {
+    /// NULL rendering in CSV; JSON always uses `null`
+    pub null_representation: NullRepresentation,
+    /// Binary value rendering
+    pub binary_encoding: BinaryEncoding,
+    /// chrono `strftime` pattern for date/time values; `None` keeps them as
+    /// returned by the driver
+    pub date_format: Option<String>,
+}
+
+impl ExportOptions {
+    /// Reject date formats chrono cannot render.
+    pub(crate) fn validate(&self) -> Result<(), DbError> {
+        validate_date_format(self.date_format.as_deref())
+    }
+
+    /// Re-render a string value that holds binary data or a date/time.
+    ///
+    /// Returns `None` when the value is left as is.
+    fn format_string(&self, value: &str) -> Option<String> {
+        if let Some(bytes) = decode_hex_literal(value) {
+            return match self.binary_encoding {
+                BinaryEncoding::Hex => None,
+                BinaryEncoding::Base64 => Some(self.format_binary(&bytes)),
+            };
+        }
+
+        self.date_format
+            .as_deref()
+            .and_then(|format| format_date(value, format))
+    }
+
+    /// Render binary data with the chosen encoding.
+    fn format_binary(&self, bytes: &[u8]) -> String {
+        match self.binary_encoding {
+            BinaryEncoding::Hex => format!("0x{}", hex::encode(bytes)),
+            BinaryEncoding::Base64 => {
+                use base64::Engine;
+                base64::engine::general_purpose::STANDARD.encode(bytes)
+            }
+        }
+    }
+
+    /// Apply the binary/date options to a JSON value (nulls and numbers are
+    /// kept as JSON `null` and numbers).
+    pub(crate) fn render_json(&self, value: &Value) -> Value {
+        if let Some(bytes) = blob_bytes(value) {
+            return Value::String(self.format_binary(&bytes));
+        }
+        match value {
+            Value::String(s) => self
+                .format_string(s)
+                .map(Value::String)
+                .unwrap_or_else(|| value.clone()),
+            _ => value.clone(),
+        }
+    }
+}
+
+/// Reject date formats chrono cannot render.
+fn validate_date_format(format: Option<&str>) -> Result<(), DbError> {
+    use chrono::format::{Item, StrftimeItems};
+
+    if let Some(format) = format {
+        if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+            return Err(DbError::InvalidInput(format!(
+                "Invalid date format: {}",
+                format
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Display formatting for query results (see `execute_query`'s `format`)
+///
+/// Turns every value into the string the grid should show, so the grid,
+/// copied rows and exports made from them agree. Dates use the same
+/// recognition as [`ExportOptions`] and binary values are written as
+/// `0x`-prefixed hex.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResultFormatOptions {
+    /// chrono `strftime` pattern for date/time values; `None` keeps them as
+    /// returned by the driver
+    pub date_format: Option<String>,
+    /// Digits after the decimal point for non-integer numbers; `None` keeps
+    /// them as returned
+    pub decimal_places: Option<u32>,
+    /// Group the integer part of numbers with `,` (off by default, so
+    /// numbers stay raw)
+    pub thousands_separator: bool,
+    /// Text shown for NULL; `None` keeps NULL as JSON `null`
+    pub null_sentinel: Option<String>,
+}
+
+impl ResultFormatOptions {
+    /// Reject date formats chrono cannot render.
+    pub(crate) fn validate(&self) -> Result<(), DbError> {
+        validate_date_format(self.date_format.as_deref())
+    }
+
+    /// Format every value of `rows` in place
+    pub(crate) fn format_rows(&self, rows: &mut [Vec<Value>]) {
+        for value in rows.iter_mut().flatten() {
+            *value = self.format_value(value);
+        }
+    }
+
+    /// Format one value as a string (NULL stays `null` without a sentinel)
+    pub(crate) fn format_value(&self, value: &Value) -> Value {
+        if let Some(bytes) = blob_bytes(value) {
+            return Value::String(format!("0x{}", hex::encode(bytes)));
+        }
+        let text = match value {
+            Value::Null => match &self.null_sentinel {
+                Some(sentinel) => sentinel.clone(),
+                None => return Value::Null,
+            },
+            Value::Bool(b) => b.to_string(),
+            Value::Number(n) => self.format_number(n),
+            Value::String(s) => self
+                .date_format
+                .as_deref()
+                .and_then(|format| format_date(s, format))
+                .unwrap_or_else(|| s.clone()),
+            Value::Array(_) | Value::Object(_) => value.to_string(),
+        };
+        Value::String(text)
+    }
+
+    fn format_number(&self, n: &serde_json::Number) -> String {
+        let text = match (self.decimal_places, n.as_f64()) {
+            (Some(places), Some(f)) if !n.is_i64() && !n.is_u64() => {
+                format!("{:.*}", places as usize, f)
+            }
+            _ => n.to_string(),
+        };
+        if self.thousands_separator {
+            group_thousands(&text)
+        } else {
+            text
+        }
+    }
+}
+
+/// Insert `,` between groups of three digits in the integer part of a
+/// formatted number; exponent notation is left alone
+fn group_thousands(number: &str) -> String {
+    if number.contains(['e', 'E']) {
+        return number.to_string();
+    }
+    let (sign, unsigned) = match number.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", number),
+    };
+    let (int_part, fraction) = match unsigned.find('.') {
+        Some(dot) => unsigned.split_at(dot),
+        None => (unsigned, ""),
+    };
+
+    let mut grouped = String::with_capacity(number.len() + int_part.len() / 3);
+    for (i, digit) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("{}{}{}", sign, grouped, fraction)
+}
+
+/// Decode a driver-rendered binary value (`0x` followed by hex pairs).
+fn decode_hex_literal(value: &str) -> Option<Vec<u8>> {
+    let digits = value.strip_prefix("0x")?;
+    if digits.is_empty() {
+        return None;
+    }
+    hex::decode(digits).ok()
+}
+
+/// Reformat an ISO 8601 date, date-time or RFC 3339 timestamp.
+///
+/// Returns `None` if `value` is not a date or the format needs fields the
+/// value lacks (e.g. a time pattern applied to a plain date).
+fn format_date(value: &str, format: &str) -> Option<String> {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime};
+    use std::fmt::Write as _;
+
+    let mut out = String::new();
+    let written = if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        write!(out, "{}", dt.format(format))
+    } else if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f"))
+    {
+        write!(out, "{}", dt.format(format))
+    } else if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        write!(out, "{}", date.format(format))
+    } else {
+        return None;
+    };
+
+    written.ok().map(|_| out)
+}
+
 /// Options for SQL import
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -54,6 +321,9 @@ pub struct SqlImportOptions {
     pub continue_on_error: bool,
     /// Use transaction (rollback all on error)
     pub use_transaction: bool,
+    /// Parse and validate the file without executing anything
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 /// Result returned by import_from_sql
@@ -68,6 +338,36 @@ pub struct SqlImportResult {
     pub cancelled: bool,
     /// Absolute path to the error log file, or None if there were no errors
     pub log_file: Option<String>,
+    /// Validation summary, present only for a dry run
+    #[serde(default)]
+    pub dry_run: Option<SqlDryRunSummary>,
+}
+
+/// What a dry-run SQL import found in the file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlDryRunSummary {
+    /// Number of statements of each type
+    pub statement_counts: HashMap<QueryType, usize>,
+    /// Statements the server accepted through `EXPLAIN`
+    pub validated: usize,
+    /// Statements that were only counted: DDL, session commands, drivers
+    /// without a safe check, and DML on tables the file itself creates
+    pub unchecked: usize,
+    /// Statements the server rejected
+    pub failures: Vec<SqlDryRunFailure>,
+}
+
+/// A statement rejected during a dry-run SQL import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlDryRunFailure {
+    /// 1-based position of the statement in the file
+    pub statement_index: usize,
+    pub query_type: QueryType,
+    /// Start of the statement text
+    pub statement: String,
+    pub error: String,
 }
 
 /// Export query results to CSV format
@@ -80,6 +380,7 @@ pub struct SqlImportResult {
 /// * `file_path` - Absolute path where the CSV file should be saved
 /// * `columns` - Column names for the CSV header row
 /// * `rows` - Data rows to export (each row is a vector of JSON values)
+/// * `options` - NULL, binary and date rendering (defaults if omitted)
 ///
 /// # Returns
 ///
@@ -111,7 +412,10 @@ pub fn export_to_csv(
     file_path: String,
     columns: Vec<String>,
     rows: Vec<Vec<Value>>,
+    options: Option<ExportOptions>,
 ) -> Result<(), DbError> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
     let path = Path::new(&file_path);
 
     // Create the file
@@ -120,11 +424,7 @@ pub fn export_to_csv(
     })?;
 
     // Write CSV header
-    let header = columns
-        .iter()
-        .map(|col| escape_csv_value(col))
-        .collect::<Vec<_>>()
-        .join(",");
+    let header = csv_record(columns.iter().cloned());
 
     writeln!(file, "{}", header).map_err(|e| {
         DbError::InternalError(format!("Failed to write CSV header: {}", e))
@@ -132,14 +432,7 @@ pub fn export_to_csv(
 
     // Write data rows
     for row in rows {
-        let row_str = row
-            .iter()
-            .map(|val| {
-                let str_val = json_value_to_string(val);
-                escape_csv_value(&str_val)
-            })
-            .collect::<Vec<_>>()
-            .join(",");
+        let row_str = csv_record(row.iter().map(|val| json_value_to_string(val, &options)));
 
         writeln!(file, "{}", row_str).map_err(|e| {
             DbError::InternalError(format!("Failed to write CSV row: {}", e))
@@ -159,6 +452,8 @@ pub fn export_to_csv(
 /// * `file_path` - Absolute path where the JSON file should be saved
 /// * `columns` - Column names
 /// * `rows` - Data rows to export
+/// * `options` - Binary and date rendering (defaults if omitted); NULLs are
+///   always written as `null`
 ///
 /// # Returns
 ///
@@ -199,21 +494,16 @@ pub fn export_to_json(
     file_path: String,
     columns: Vec<String>,
     rows: Vec<Vec<Value>>,
+    options: Option<ExportOptions>,
 ) -> Result<(), DbError> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
     let path = Path::new(&file_path);
 
     // Convert rows to JSON objects
     let json_rows: Vec<serde_json::Map<String, Value>> = rows
         .iter()
-        .map(|row| {
-            let mut obj = serde_json::Map::new();
-            for (i, value) in row.iter().enumerate() {
-                if let Some(col_name) = columns.get(i) {
-                    obj.insert(col_name.clone(), value.clone());
-                }
-            }
-            obj
-        })
+        .map(|row| row_to_json_object(&columns, row, &options))
         .collect();
 
     // Serialize to pretty JSON
@@ -233,13 +523,43 @@ pub fn export_to_json(
     Ok(())
 }
 
+/// Build a JSON object for one row, keyed by column name
+///
+/// Every column is present; NULLs stay JSON `null` and numbers stay numbers.
+pub(crate) fn row_to_json_object(
+    columns: &[String],
+    row: &[Value],
+    options: &ExportOptions,
+) -> serde_json::Map<String, Value> {
+    // Repeated names (self-joins) would overwrite each other's keys
+    unique_column_names(columns)
+        .into_iter()
+        .enumerate()
+        .map(|(i, col_name)| {
+            let value = row.get(i).map_or(Value::Null, |v| options.render_json(v));
+            (col_name, value)
+        })
+        .collect()
+}
+
+/// Join already-stringified values into one CSV line (without terminator)
+pub(crate) fn csv_record(values: impl Iterator<Item = String>) -> String {
+    values
+        .map(|value| escape_csv_value(&value))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 /// Convert a JSON value to a string representation
-fn json_value_to_string(value: &Value) -> String {
+pub(crate) fn json_value_to_string(value: &Value, options: &ExportOptions) -> String {
+    if let Some(bytes) = blob_bytes(value) {
+        return options.format_binary(&bytes);
+    }
     match value {
-        Value::Null => String::new(),
+        Value::Null => options.null_representation.as_str().to_string(),
         Value::Bool(b) => b.to_string(),
         Value::Number(n) => n.to_string(),
-        Value::String(s) => s.clone(),
+        Value::String(s) => options.format_string(s).unwrap_or_else(|| s.clone()),
         Value::Array(_) | Value::Object(_) => value.to_string(),
     }
 }
@@ -288,7 +608,9 @@ fn escape_csv_value(value: &str) -> String {
 ///       includeCreate: true,
 ///       includeData: true,
 ///       tables: [], // empty = all tables
-///       schema: 'public'
+///       schema: 'public',
+///       rowsPerInsert: 100, // 1 = one INSERT per row
+///       includeColumnNames: true
 ///     }
 ///   });
 /// }
@@ -300,8 +622,8 @@ pub async fn export_to_sql(
     options: SqlExportOptions,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<(), DbError> {
-    // Get driver type and verify connection exists
-    let driver = {
+    // Get driver type and schema, and verify connection exists
+    let (driver, schema) = {
         let state_lock = state.lock().unwrap();
 
         // Verify connection exists
@@ -310,11 +632,13 @@ pub async fn export_to_sql(
         }
 
         // Get driver type from connection profile (connection_id == profile_id)
-        state_lock
+        let driver = state_lock
             .connection_profiles
             .get(&connection_id)
             .map(|profile| profile.driver.clone())
-            .ok_or_else(|| DbError::NotFound(format!("Connection profile {} not found", connection_id)))?
+            .ok_or_else(|| DbError::NotFound(format!("Connection profile {} not found", connection_id)))?;
+        let schema = state_lock.resolve_schema(&connection_id, options.schema.as_deref())?;
+        (driver, schema)
     };
 
     // Create output file
@@ -332,11 +656,11 @@ pub async fn export_to_sql(
         .map_err(|e| DbError::InternalError(format!("Failed to write SQL: {}", e)))?;
 
     // Get list of tables to export
-    let schema = options.schema.as_deref().unwrap_or("public");
+    let schema = schema.as_str();
     let tables = if options.tables.is_empty() {
         // Get all tables from schema
         use crate::commands::schema::get_tables;
-        get_tables(connection_id.clone(), schema.to_string(), state.clone()).await?
+        get_tables(connection_id.clone(), schema.to_string(), Some(true), state.clone()).await?
     } else {
         // Use specified tables
         options.tables.iter().map(|name| crate::models::metadata::TableInfo {
@@ -381,21 +705,23 @@ async fn export_table_to_sql(
 
     // DROP statement
     if options.include_drop {
+        let target = qualified_table(driver, schema, table);
         let drop_stmt = match driver {
             DbDriver::Postgres | DbDriver::Sqlite | DbDriver::Supabase | DbDriver::Neon => {
-                format!("DROP TABLE IF EXISTS \"{}\".\"{}\" CASCADE;", schema, table)
-            }
-            DbDriver::Turso => {
-                format!("DROP TABLE IF EXISTS \"{}\";", table)
+                format!("DROP TABLE IF EXISTS {} CASCADE;", target)
             }
-            DbDriver::MySql => {
-                format!("DROP TABLE IF EXISTS `{}`.`{}`;", schema, table)
+            DbDriver::Turso | DbDriver::MySql => {
+                format!("DROP TABLE IF EXISTS {};", target)
             }
             DbDriver::MongoDb | DbDriver::Redis => {
                 format!("// db.{}.drop();", table)
             }
             DbDriver::SqlServer => {
-                format!("IF OBJECT_ID('{}.{}', 'U') IS NOT NULL DROP TABLE {}.{};", schema, table, schema, table)
+                format!(
+                    "IF OBJECT_ID('{}', 'U') IS NOT NULL DROP TABLE {};",
+                    target.replace('\'', "''"),
+                    target
+                )
             }
             DbDriver::Redis => {
                 format!("// DEL {}", table)
@@ -414,7 +740,7 @@ async fn export_table_to_sql(
 
     // INSERT statements (data)
     if options.include_data {
-        export_table_data_to_sql(file, connection_id, schema, table, driver, state).await?;
+        export_table_data_to_sql(file, connection_id, schema, table, driver, options, state).await?;
     }
 
     Ok(())
@@ -430,19 +756,17 @@ async fn get_create_table_statement(
 ) -> Result<String, DbError> {
     // Get table schema
     use crate::commands::schema::get_table_schema;
-    let table_schema = get_table_schema(connection_id.to_string(), schema.to_string(), table.to_string(), state.clone()).await?;
+    let table_schema = get_table_schema(connection_id.to_string(), schema.to_string(), table.to_string(), Some(true), state.clone()).await?;
 
     // Build CREATE TABLE statement
+    let target = qualified_table(driver, schema, table);
     let mut create_stmt = match driver {
-        DbDriver::Postgres | DbDriver::Sqlite | DbDriver::Supabase | DbDriver::Neon => {
-            format!("CREATE TABLE \"{}\".\"{}\" (\n", schema, table)
-        }
-        DbDriver::Turso => {
-            format!("CREATE TABLE \"{}\" (\n", table)
-        }
-        DbDriver::MySql => {
-            format!("CREATE TABLE `{}`.`{}` (\n", schema, table)
-        }
+        DbDriver::Postgres
+        | DbDriver::Sqlite
+        | DbDriver::Supabase
+        | DbDriver::Neon
+        | DbDriver::Turso
+        | DbDriver::MySql => format!("CREATE TABLE {} (\n", target),
         _ => {
             return Err(DbError::InvalidInput(format!("CREATE TABLE export not supported for {:?}", driver)));
         }
@@ -451,7 +775,7 @@ async fn get_create_table_statement(
     // Add columns
     let columns_sql: Vec<String> = table_schema.columns.iter().map(|col| {
         let mut parts = vec![
-            format!("  \"{}\"", col.name),
+            format!("  {}", quote_identifier(&col.name, Quoting::for_driver(driver))),
             col.data_type.clone(),
         ];
 
@@ -479,27 +803,26 @@ async fn export_table_data_to_sql(
     schema: &str,
     table: &str,
     driver: &DbDriver,
+    options: &SqlExportOptions,
     state: &State<'_, Mutex<AppState>>,
 ) -> Result<(), DbError> {
     // Query all data from table
-    use crate::commands::query::execute_query;
+    use crate::commands::query::run_query;
 
+    let target = qualified_table(driver, schema, table);
     let query = match driver {
-        DbDriver::Postgres | DbDriver::Sqlite | DbDriver::Supabase | DbDriver::Neon => {
-            format!("SELECT * FROM \"{}\".\"{}\"", schema, table)
-        }
-        DbDriver::Turso => {
-            format!("SELECT * FROM \"{}\"", table)
-        }
-        DbDriver::MySql => {
-            format!("SELECT * FROM `{}`.`{}`", schema, table)
-        }
+        DbDriver::Postgres
+        | DbDriver::Sqlite
+        | DbDriver::Supabase
+        | DbDriver::Neon
+        | DbDriver::Turso
+        | DbDriver::MySql => format!("SELECT * FROM {}", target),
         _ => {
             return Ok(()); // Skip data export for unsupported drivers
         }
     };
 
-    let result = execute_query(connection_id.to_string(), query, state.clone()).await?;
+    let result = run_query(connection_id.to_string(), query, state.clone(), None).await?;
 
     if result.rows.is_empty() {
         writeln!(file, "-- No data in table")
@@ -511,41 +834,226 @@ async fn export_table_data_to_sql(
     writeln!(file, "\n-- Data for table {}.{}", schema, table)
         .map_err(|e| DbError::InternalError(format!("Failed to write comment: {}", e)))?;
 
-    for row in result.rows {
-        let insert_stmt = match driver {
-            DbDriver::Postgres | DbDriver::Sqlite | DbDriver::Supabase | DbDriver::Neon => {
-                let values: Vec<String> = row.iter().map(|v| sql_value_to_string(v)).collect();
-                format!("INSERT INTO \"{}\".\"{}\" VALUES ({});", schema, table, values.join(", "))
-            }
-            DbDriver::Turso => {
-                let values: Vec<String> = row.iter().map(|v| sql_value_to_string(v)).collect();
-                format!("INSERT INTO \"{}\" VALUES ({});", table, values.join(", "))
-            }
-            DbDriver::MySql => {
-                let values: Vec<String> = row.iter().map(|v| sql_value_to_string(v)).collect();
-                format!("INSERT INTO `{}`.`{}` VALUES ({});", schema, table, values.join(", "))
-            }
-            _ => String::new(),
-        };
+    let column_list = options
+        .include_column_names
+        .then(|| quote_list(&result.columns, Quoting::for_driver(driver)));
 
-        writeln!(file, "{}", insert_stmt)
+    for chunk in result.rows.chunks(rows_per_insert(driver, options.rows_per_insert)) {
+        writeln!(file, "{}", format_insert(&target, column_list.as_deref(), chunk, driver))
             .map_err(|e| DbError::InternalError(format!("Failed to write INSERT: {}", e)))?;
     }
 
     Ok(())
 }
 
-/// Convert JSON value to SQL literal
-fn sql_value_to_string(value: &Value) -> String {
+/// Convert JSON value to a SQL literal for `dialect`
+///
+/// Strings are escaped by `escape_literal`. SQL Server has no TRUE/FALSE
+/// literals.
+fn sql_literal(value: &Value, dialect: &DbDriver) -> String {
+    if let Some(bytes) = blob_bytes(value) {
+        return binary_literal(&bytes, dialect);
+    }
     match value {
         Value::Null => "NULL".to_string(),
-        Value::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        Value::Bool(b) => match (dialect, b) {
+            (DbDriver::SqlServer, true) => "1".to_string(),
+            (DbDriver::SqlServer, false) => "0".to_string(),
+            (_, true) => "TRUE".to_string(),
+            (_, false) => "FALSE".to_string(),
+        },
         Value::Number(n) => n.to_string(),
-        Value::String(s) => format!("'{}'", s.replace('\'', "''")), // Escape single quotes
-        Value::Array(_) | Value::Object(_) => format!("'{}'", value.to_string().replace('\'', "''")),
+        Value::String(s) => escape_literal(s, dialect),
+        Value::Array(_) | Value::Object(_) => escape_literal(&value.to_string(), dialect),
+    }
+}
+
+/// `schema.table` quoted for `driver`; Turso tables aren't qualified
+fn qualified_table(driver: &DbDriver, schema: &str, table: &str) -> String {
+    let schema = (*driver != DbDriver::Turso).then_some(schema);
+    quote_qualified(schema, table, Quoting::for_driver(driver))
+}
+
+/// Number of rows to put in each INSERT for `driver`
+///
+/// SQL Server rejects a VALUES list of more than 1000 rows.
+fn rows_per_insert(driver: &DbDriver, requested: usize) -> usize {
+    let max = match driver {
+        DbDriver::SqlServer => 1000,
+        _ => usize::MAX,
+    };
+    requested.clamp(1, max)
+}
+
+/// Build one INSERT statement for `rows`
+///
+/// A single row stays on one line; multiple rows get one line each so large
+/// dumps remain readable and diffable.
+fn format_insert(
+    target: &str,
+    column_list: Option<&str>,
+    rows: &[Vec<Value>],
+    dialect: &DbDriver,
+) -> String {
+    let columns = column_list
+        .map(|list| format!(" ({})", list))
+        .unwrap_or_default();
+    let tuples: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let values: Vec<String> = row.iter().map(|v| sql_literal(v, dialect)).collect();
+            format!("({})", values.join(", "))
+        })
+        .collect();
+
+    if tuples.len() == 1 {
+        format!("INSERT INTO {}{} VALUES {};", target, columns, tuples[0])
+    } else {
+        format!("INSERT INTO {}{} VALUES\n  {};", target, columns, tuples.join(",\n  "))
     }
 }
 
+/// Clipboard format for [`format_rows_as`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RowCopyFormat {
+    /// One INSERT per row, quoted for the given dialect
+    InsertStatements,
+    /// A pipe table with padded columns
+    Markdown,
+    /// Pretty-printed array of objects keyed by column name
+    Json,
+    /// Header line plus one line per row
+    Csv,
+}
+
+/// Format result rows as text for the clipboard
+///
+/// Pure transformation of the rows already shown in the grid; nothing is
+/// read from the database.
+///
+/// # Arguments
+///
+/// * `columns` - Column names
+/// * `rows` - Rows to format
+/// * `target_table` - Table named in INSERT statements; may be
+///   schema-qualified as `schema.table`. Required for `InsertStatements`.
+/// * `format` - Output format
+/// * `dialect` - Quoting rules for `InsertStatements` (PostgreSQL if omitted)
+#[tauri::command]
+pub fn format_rows_as(
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+    target_table: Option<String>,
+    format: RowCopyFormat,
+    dialect: Option<DbDriver>,
+) -> Result<String, DbError> {
+    let options = ExportOptions::default();
+    match format {
+        RowCopyFormat::InsertStatements => {
+            let table = target_table
+                .filter(|t| !t.trim().is_empty())
+                .ok_or_else(|| {
+                    DbError::InvalidInput(
+                        "A target table is required for INSERT statements".to_string(),
+                    )
+                })?;
+            let dialect = dialect.unwrap_or(DbDriver::Postgres);
+            let quoting = Quoting::for_driver(&dialect);
+            let target = table
+                .split('.')
+                .map(|part| quote_identifier(part, quoting))
+                .collect::<Vec<_>>()
+                .join(".");
+            let column_list = quote_list(&columns, quoting);
+            Ok(rows
+                .iter()
+                .map(|row| {
+                    format_insert(
+                        &target,
+                        Some(&column_list),
+                        std::slice::from_ref(row),
+                        &dialect,
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        RowCopyFormat::Markdown => Ok(format_markdown_table(&columns, &rows, &options)),
+        RowCopyFormat::Json => {
+            let objects: Vec<serde_json::Map<String, Value>> = rows
+                .iter()
+                .map(|row| row_to_json_object(&columns, row, &options))
+                .collect();
+            serde_json::to_string_pretty(&objects)
+                .map_err(|e| DbError::InternalError(format!("Failed to serialize JSON: {}", e)))
+        }
+        RowCopyFormat::Csv => {
+            let mut lines = vec![csv_record(columns.iter().cloned())];
+            lines.extend(
+                rows.iter()
+                    .map(|row| csv_record(row.iter().map(|v| json_value_to_string(v, &options)))),
+            );
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
+/// Render rows as a Markdown pipe table
+///
+/// Columns are padded to a common width so the table also reads well as
+/// plain text. Pipes are escaped and line breaks become `<br>`; NULL is
+/// written as `NULL` so it stays distinguishable from an empty string.
+fn format_markdown_table(
+    columns: &[String],
+    rows: &[Vec<Value>],
+    options: &ExportOptions,
+) -> String {
+    let escape = |s: &str| {
+        s.replace('|', "\\|")
+            .replace("\r\n", "<br>")
+            .replace('\n', "<br>")
+    };
+
+    let header: Vec<String> = columns.iter().map(|c| escape(c)).collect();
+    let body: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            (0..columns.len())
+                .map(|i| match row.get(i) {
+                    None | Some(Value::Null) => "NULL".to_string(),
+                    Some(value) => escape(&json_value_to_string(value, options)),
+                })
+                .collect()
+        })
+        .collect();
+
+    // Separator cells need at least three dashes
+    let widths: Vec<usize> = (0..columns.len())
+        .map(|i| {
+            body.iter()
+                .map(|cells| cells[i].chars().count())
+                .chain(std::iter::once(header[i].chars().count()))
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect();
+
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+
+    let mut lines = vec![line(&header), line(&separator)];
+    lines.extend(body.iter().map(|cells| line(cells)));
+    lines.join("\n")
+}
+
 /// Import SQL dump file into database
 ///
 /// Imports a SQL dump file by executing all SQL statements in it.
@@ -572,15 +1080,33 @@ fn sql_value_to_string(value: &Value) -> String {
 ///     filePath,
 ///     options: {
 ///       continueOnError: false,
-///       useTransaction: true
+///       useTransaction: true,
+///       dryRun: false
 ///     }
 ///   });
 /// }
 /// ```
-/// Signal an in-progress import to stop after the current statement.
+/// Signal an in-progress import to stop.
+///
+/// With `import_id`, stops that `import_data_to_table` run before its next
+/// row, rolling back its current transaction. Without it, stops the running
+/// SQL import after the current statement.
 #[tauri::command]
-pub async fn cancel_import(cancel_flag: State<'_, Arc<AtomicBool>>) -> Result<(), DbError> {
-    cancel_flag.store(true, Ordering::Relaxed);
+pub async fn cancel_import(
+    import_id: Option<String>,
+    cancel_flag: State<'_, Arc<AtomicBool>>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<(), DbError> {
+    let Some(import_id) = import_id else {
+        cancel_flag.store(true, Ordering::Relaxed);
+        return Ok(());
+    };
+    let state = state.lock().unwrap();
+    let cancel = state
+        .active_imports
+        .get(&import_id)
+        .ok_or_else(|| DbError::NotFound(format!("No import {} is running", import_id)))?;
+    cancel.store(true, Ordering::Relaxed);
     Ok(())
 }
 
@@ -592,7 +1118,7 @@ pub async fn import_from_sql(
     state: State<'_, Mutex<AppState>>,
     cancel_flag: State<'_, Arc<AtomicBool>>,
 ) -> Result<SqlImportResult, DbError> {
-    use crate::commands::query::execute_query;
+    use crate::commands::query::run_query;
 
     // Reset cancel flag at the start of each import
     cancel_flag.store(false, Ordering::Relaxed);
@@ -612,8 +1138,14 @@ pub async fn import_from_sql(
         .map_err(|e| DbError::InternalError(format!("Failed to open SQL file: {}", e)))?;
     let reader = BufReader::new(file);
 
+    // A dry run counts and validates statements but sends nothing that could
+    // change data or session state, so all setup and teardown is skipped
+    let mut dry_run = options.dry_run.then(SqlDryRunSummary::default);
+    let use_transaction = options.use_transaction && !options.dry_run;
+    let mut created_objects: HashSet<String> = HashSet::new();
+
     // For MySQL dumps: disable FK/unique checks and strict mode for duration of import
-    if matches!(driver, DbDriver::MySql) {
+    if matches!(driver, DbDriver::MySql) && !options.dry_run {
         for stmt in &[
             "SET SESSION foreign_key_checks = 0",
             "SET SESSION unique_checks = 0",
@@ -624,18 +1156,18 @@ pub async fn import_from_sql(
             // Requires SUPER privilege — silently ignored if the user lacks it.
             "SET GLOBAL max_allowed_packet = 1073741824",
         ] {
-            let _ = execute_query(connection_id.clone(), stmt.to_string(), state.clone()).await;
+            let _ = run_query(connection_id.clone(), stmt.to_string(), state.clone(), None).await;
         }
     }
 
     // Begin transaction if requested
-    if options.use_transaction {
+    if use_transaction {
         let begin_stmt = match driver {
             DbDriver::Postgres | DbDriver::Sqlite | DbDriver::Supabase | DbDriver::Neon | DbDriver::Turso => "BEGIN",
             DbDriver::MySql => "START TRANSACTION",
             _ => return Err(DbError::InvalidInput("Transactions not supported for this driver".to_string())),
         };
-        execute_query(connection_id.clone(), begin_stmt.to_string(), state.clone()).await
+        run_query(connection_id.clone(), begin_stmt.to_string(), state.clone(), None).await
             .map_err(|e| DbError::QueryError(format!("Failed to begin transaction: {}", e)))?;
     }
 
@@ -734,8 +1266,22 @@ pub async fn import_from_sql(
                 vec![stmt.clone()]
             };
 
+            if let Some(summary) = dry_run.as_mut() {
+                dry_run_statement(
+                    &connection_id,
+                    stmt_index,
+                    &sub_stmts,
+                    &driver,
+                    &mut created_objects,
+                    summary,
+                    &state,
+                )
+                .await;
+                continue;
+            }
+
             'sub: for sub_stmt in &sub_stmts {
-                match execute_query(connection_id.clone(), sub_stmt.clone(), state.clone()).await {
+                match run_query(connection_id.clone(), sub_stmt.clone(), state.clone(), None).await {
                     Ok(_) => executed += 1,
                     Err(ref e) => {
                         let msg = e.to_string().to_lowercase();
@@ -752,7 +1298,7 @@ pub async fn import_from_sql(
                             e
                         ));
                         if !options.continue_on_error {
-                            if options.use_transaction {
+                            if use_transaction {
                                 let rollback = match driver {
                                     DbDriver::Postgres
                                     | DbDriver::Sqlite
@@ -763,10 +1309,11 @@ pub async fn import_from_sql(
                                     _ => "",
                                 };
                                 if !rollback.is_empty() {
-                                    let _ = execute_query(
+                                    let _ = run_query(
                                         connection_id.clone(),
                                         rollback.to_string(),
                                         state.clone(),
+                                        None,
                                     ).await;
                                 }
                             }
@@ -789,11 +1336,31 @@ pub async fn import_from_sql(
     // Execute any remaining statement that lacked a trailing delimiter
     let remaining = current_statement.trim().to_string();
     if !remaining.is_empty() && !remaining.starts_with("--") {
-        let _ = execute_query(connection_id.clone(), remaining, state.clone()).await;
+        if let Some(summary) = dry_run.as_mut() {
+            stmt_index += 1;
+            dry_run_statement(
+                &connection_id,
+                stmt_index,
+                &[remaining],
+                &driver,
+                &mut created_objects,
+                summary,
+                &state,
+            )
+            .await;
+        } else {
+            let _ = run_query(connection_id.clone(), remaining, state.clone(), None).await;
+        }
+    }
+
+    if let Some(summary) = &dry_run {
+        errors.extend(summary.failures.iter().map(|failure| {
+            format!("Statement {}: {}", failure.statement_index, failure.error)
+        }));
     }
 
     // Commit transaction
-    if options.use_transaction {
+    if use_transaction {
         let commit_stmt = match driver {
             DbDriver::Postgres
             | DbDriver::Sqlite
@@ -804,19 +1371,19 @@ pub async fn import_from_sql(
             _ => "",
         };
         if !commit_stmt.is_empty() {
-            execute_query(connection_id.clone(), commit_stmt.to_string(), state.clone()).await
+            run_query(connection_id.clone(), commit_stmt.to_string(), state.clone(), None).await
                 .map_err(|e| DbError::QueryError(format!("Failed to commit: {}", e)))?;
         }
     }
 
     // Restore MySQL session settings
-    if matches!(driver, DbDriver::MySql) {
+    if matches!(driver, DbDriver::MySql) && !options.dry_run {
         for stmt in &[
             "SET SESSION foreign_key_checks = 1",
             "SET SESSION unique_checks = 1",
             "SET SESSION sql_notes = 1",
         ] {
-            let _ = execute_query(connection_id.clone(), stmt.to_string(), state.clone()).await;
+            let _ = run_query(connection_id.clone(), stmt.to_string(), state.clone(), None).await;
         }
     }
 
@@ -838,9 +1405,141 @@ pub async fn import_from_sql(
         first_error: errors.first().cloned(),
         cancelled: cancel_flag.load(Ordering::Relaxed),
         log_file,
+        dry_run,
     })
 }
 
+/// Count one dump statement and, where it is safe, have the server check it
+///
+/// `parts` is the statement as it would be sent, i.e. already split into
+/// smaller INSERTs when it was oversized.
+async fn dry_run_statement(
+    connection_id: &str,
+    stmt_index: usize,
+    parts: &[String],
+    driver: &DbDriver,
+    created_objects: &mut HashSet<String>,
+    summary: &mut SqlDryRunSummary,
+    state: &State<'_, Mutex<AppState>>,
+) {
+    use crate::commands::query::run_query;
+
+    let Some(first) = parts.first() else {
+        return;
+    };
+    let query_type = QueryType::from_sql(first);
+    *summary.statement_counts.entry(query_type).or_insert(0) += 1;
+
+    let outcome = 'check: {
+        for part in parts {
+            let Some(check) = dry_run_check_sql(driver, query_type, part, created_objects) else {
+                break 'check None;
+            };
+            if let Err(e) = run_query(connection_id.to_string(), check, state.clone(), None).await {
+                break 'check Some(Err(e));
+            }
+        }
+        Some(Ok(()))
+    };
+    match outcome {
+        None => summary.unchecked += 1,
+        Some(Ok(())) => summary.validated += 1,
+        Some(Err(e)) => summary.failures.push(SqlDryRunFailure {
+            statement_index: stmt_index,
+            query_type,
+            statement: first.chars().take(200).collect(),
+            error: e.to_string(),
+        }),
+    }
+
+    if query_type == QueryType::Create {
+        if let Some(name) = created_object_name(first, driver) {
+            created_objects.insert(name);
+        }
+    }
+}
+
+/// The statement that validates `stmt` without running it, or `None` when it
+/// can't be checked safely
+///
+/// Plain `EXPLAIN` (without ANALYZE) plans DML on PostgreSQL, MySQL and
+/// SQLite without executing it, and unlike `PREPARE` leaves nothing behind
+/// on the session. DDL is never sent. Statements touching a table created
+/// earlier in the same file are skipped too, since that table doesn't exist
+/// yet and the check would fail for the wrong reason.
+fn dry_run_check_sql(
+    driver: &DbDriver,
+    query_type: QueryType,
+    stmt: &str,
+    created_objects: &HashSet<String>,
+) -> Option<String> {
+    let explainable = driver.is_postgres_compatible()
+        || matches!(driver, DbDriver::MySql | DbDriver::Sqlite | DbDriver::Turso);
+    let is_dml = matches!(
+        query_type,
+        QueryType::Select | QueryType::Insert | QueryType::Update | QueryType::Delete
+    );
+    if !explainable || !is_dml {
+        return None;
+    }
+
+    let references_created = tokenize_with(stmt, Quoting::for_driver(driver))
+        .filter(|t| matches!(t.kind, TokenKind::Word | TokenKind::QuotedIdentifier))
+        .any(|t| created_objects.contains(&unquote_identifier(t.text)));
+    if references_created {
+        return None;
+    }
+    Some(format!("EXPLAIN {}", stmt))
+}
+
+/// Name of the table or view defined by a `CREATE` statement, unquoted and
+/// lowercased, without its schema
+fn created_object_name(stmt: &str, driver: &DbDriver) -> Option<String> {
+    let mut tokens = tokenize_with(stmt, Quoting::for_driver(driver)).filter(|t| !t.is_trivia());
+    if !tokens
+        .next()
+        .is_some_and(|t| t.text.eq_ignore_ascii_case("CREATE"))
+    {
+        return None;
+    }
+    // Skip modifiers such as OR REPLACE, TEMPORARY or MATERIALIZED
+    tokens.find(|t| {
+        t.kind == TokenKind::Word
+            && (t.text.eq_ignore_ascii_case("TABLE") || t.text.eq_ignore_ascii_case("VIEW"))
+    })?;
+
+    // The name is the last part of a possibly schema-qualified identifier
+    let mut name = None;
+    let mut expect_part = true;
+    for token in tokens {
+        let is_ident = matches!(token.kind, TokenKind::Word | TokenKind::QuotedIdentifier);
+        if name.is_none()
+            && token.kind == TokenKind::Word
+            && ["IF", "NOT", "EXISTS"]
+                .iter()
+                .any(|kw| token.text.eq_ignore_ascii_case(kw))
+        {
+            continue;
+        }
+        if expect_part && is_ident {
+            name = Some(unquote_identifier(token.text));
+            expect_part = false;
+        } else if !expect_part && token.text == "." {
+            expect_part = true;
+        } else {
+            break;
+        }
+    }
+    name
+}
+
+/// Strip identifier quotes and lowercase, for loose name matching
+fn unquote_identifier(ident: &str) -> String {
+    ident
+        .trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+        .to_lowercase()
+}
+
 /// Derive a log file path from the SQL file path.
 /// e.g. `/path/to/dump.sql` → `/path/to/dump_import_errors.log`
 fn derive_log_path(sql_path: &str) -> String {
@@ -990,6 +1689,7 @@ fn normalize_dump_stmt(stmt: String) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::drivers::blob::blob_value;
     use serde_json::json;
     use std::fs;
 
@@ -1016,11 +1716,152 @@ mod tests {
 
     #[test]
     fn test_json_value_to_string() {
-        assert_eq!(json_value_to_string(&Value::Null), "");
-        assert_eq!(json_value_to_string(&json!(true)), "true");
-        assert_eq!(json_value_to_string(&json!(42)), "42");
-        assert_eq!(json_value_to_string(&json!("hello")), "hello");
-        assert_eq!(json_value_to_string(&json!({"key": "value"})), "{\"key\":\"value\"}");
+        let options = ExportOptions::default();
+        assert_eq!(json_value_to_string(&Value::Null, &options), "");
+        assert_eq!(json_value_to_string(&json!(true), &options), "true");
+        assert_eq!(json_value_to_string(&json!(42), &options), "42");
+        assert_eq!(json_value_to_string(&json!("hello"), &options), "hello");
+        assert_eq!(json_value_to_string(&json!({"key": "value"}), &options), "{\"key\":\"value\"}");
+    }
+
+    #[test]
+    fn test_null_representation() {
+        let render = |null_representation| {
+            let options = ExportOptions {
+                null_representation,
+                ..Default::default()
+            };
+            json_value_to_string(&Value::Null, &options)
+        };
+        assert_eq!(render(NullRepresentation::Empty), "");
+        assert_eq!(render(NullRepresentation::Null), "NULL");
+        assert_eq!(render(NullRepresentation::BackslashN), "\\N");
+
+        let parsed: NullRepresentation = serde_json::from_str("\"\\\\N\"").unwrap();
+        assert_eq!(parsed, NullRepresentation::BackslashN);
+    }
+
+    #[test]
+    fn test_binary_encoding() {
+        let hex = ExportOptions::default();
+        let base64 = ExportOptions {
+            binary_encoding: BinaryEncoding::Base64,
+            ..Default::default()
+        };
+        let blob = json!("0x48656c6c6f");
+
+        assert_eq!(json_value_to_string(&blob, &hex), "0x48656c6c6f");
+        assert_eq!(json_value_to_string(&blob, &base64), "SGVsbG8=");
+        assert_eq!(base64.render_json(&blob), json!("SGVsbG8="));
+        // Not valid hex, so not treated as binary
+        assert_eq!(json_value_to_string(&json!("0xyz"), &base64), "0xyz");
+
+        let blob = blob_value(b"Hello");
+        assert_eq!(json_value_to_string(&blob, &hex), "0x48656c6c6f");
+        assert_eq!(base64.render_json(&blob), json!("SGVsbG8="));
+        assert_eq!(sql_literal(&blob, &DbDriver::SqlServer), "0x48656c6c6f");
+        assert_eq!(sql_literal(&blob, &DbDriver::Sqlite), "X'48656c6c6f'");
+    }
+
+    #[test]
+    fn test_date_format() {
+        let options = ExportOptions {
+            date_format: Some("%d/%m/%Y".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(json_value_to_string(&json!("2024-03-09"), &options), "09/03/2024");
+        assert_eq!(
+            json_value_to_string(&json!("2024-03-09 13:45:00"), &options),
+            "09/03/2024"
+        );
+        assert_eq!(
+            json_value_to_string(&json!("2024-03-09T13:45:00+00:00"), &options),
+            "09/03/2024"
+        );
+        assert_eq!(json_value_to_string(&json!("not a date"), &options), "not a date");
+
+        // A time pattern cannot be applied to a plain date
+        let with_time = ExportOptions {
+            date_format: Some("%H:%M".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(json_value_to_string(&json!("2024-03-09"), &with_time), "2024-03-09");
+
+        let invalid = ExportOptions {
+            date_format: Some("%Q".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(invalid.validate(), Err(DbError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_result_format_options() {
+        let raw = ResultFormatOptions::default();
+        assert_eq!(raw.format_value(&Value::Null), Value::Null);
+        assert_eq!(raw.format_value(&json!(1234567.891)), json!("1234567.891"));
+        assert_eq!(raw.format_value(&json!(true)), json!("true"));
+        assert_eq!(raw.format_value(&blob_value(b"Hi")), json!("0x4869"));
+
+        let options = ResultFormatOptions {
+            date_format: Some("%d/%m/%Y".to_string()),
+            decimal_places: Some(2),
+            thousands_separator: true,
+            null_sentinel: Some("(null)".to_string()),
+        };
+        let mut rows = vec![vec![
+            json!(1234567.891),
+            json!(-1234),
+            json!(12),
+            json!("2024-03-09T13:45:00+00:00"),
+            Value::Null,
+        ]];
+        options.format_rows(&mut rows);
+        assert_eq!(
+            rows[0],
+            vec![
+                json!("1,234,567.89"),
+                json!("-1,234"),
+                json!("12"),
+                json!("09/03/2024"),
+                json!("(null)"),
+            ]
+        );
+
+        let invalid = ResultFormatOptions {
+            date_format: Some("%Q".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(invalid.validate(), Err(DbError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_row_to_json_object_keeps_nulls_and_numbers() {
+        let columns = vec!["id".to_string(), "big".to_string(), "note".to_string()];
+        let row = vec![json!(1), json!(u64::MAX), Value::Null];
+
+        let object = row_to_json_object(&columns, &row, &ExportOptions::default());
+        assert_eq!(object["big"], json!(u64::MAX));
+        assert_eq!(object.get("note"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn test_row_to_json_object_self_join() {
+        // SELECT e.id, e.name, m.id, m.name
+        // FROM employees e JOIN employees m ON m.id = e.manager_id
+        let columns: Vec<String> = ["id", "name", "id", "name"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        let row = vec![json!(2), json!("Ann"), json!(1), json!("Bob")];
+
+        let object = row_to_json_object(&columns, &row, &ExportOptions::default());
+        assert_eq!(object.len(), 4);
+        assert_eq!(object["id"], json!(2));
+        assert_eq!(object["name_2"], json!("Bob"));
+
+        // A suffix the result already uses is skipped
+        let columns: Vec<String> = ["id", "id", "id_2"].iter().map(|c| c.to_string()).collect();
+        assert_eq!(unique_column_names(&columns), vec!["id", "id_3", "id_2"]);
     }
 
     #[test]
@@ -1034,7 +1875,7 @@ mod tests {
             vec![json!(2), json!("Bob"), json!(25)],
         ];
 
-        let result = export_to_csv(file_path.clone(), columns, rows);
+        let result = export_to_csv(file_path.clone(), columns, rows, None);
         assert!(result.is_ok());
 
         // Read and verify the file
@@ -1058,7 +1899,7 @@ mod tests {
             vec![json!(2), json!("Bob")],
         ];
 
-        let result = export_to_json(file_path.clone(), columns, rows);
+        let result = export_to_json(file_path.clone(), columns, rows, None);
         assert!(result.is_ok());
 
         // Read and verify the file
@@ -1071,4 +1912,150 @@ mod tests {
         // Cleanup
         let _ = fs::remove_file(temp_file);
     }
+
+    #[test]
+    fn test_created_object_name() {
+        let pg = DbDriver::Postgres;
+        assert_eq!(
+            created_object_name("CREATE TABLE IF NOT EXISTS public.\"Users\" (id int)", &pg),
+            Some("users".to_string())
+        );
+        assert_eq!(
+            created_object_name("CREATE OR REPLACE VIEW active AS SELECT 1", &pg),
+            Some("active".to_string())
+        );
+        assert_eq!(
+            created_object_name("CREATE TABLE `orders` (id int)", &DbDriver::MySql),
+            Some("orders".to_string())
+        );
+        assert_eq!(created_object_name("CREATE INDEX idx ON t (a)", &pg), None);
+    }
+
+    #[test]
+    fn test_dry_run_check_sql() {
+        let pg = DbDriver::Postgres;
+        let created: HashSet<String> = ["orders".to_string()].into_iter().collect();
+        let check = |driver: &DbDriver, sql: &str| {
+            dry_run_check_sql(driver, QueryType::from_sql(sql), sql, &created)
+        };
+
+        assert_eq!(
+            check(&pg, "INSERT INTO users VALUES (1)"),
+            Some("EXPLAIN INSERT INTO users VALUES (1)".to_string())
+        );
+        assert!(check(&DbDriver::Sqlite, "DELETE FROM users").is_some());
+        // DDL and session commands are never sent
+        assert_eq!(check(&pg, "DROP TABLE users"), None);
+        assert_eq!(check(&pg, "SET search_path = public"), None);
+        // Tables created earlier in the file don't exist yet
+        assert_eq!(check(&pg, "INSERT INTO \"orders\" VALUES (1)"), None);
+        // No side-effect-free check for SQL Server
+        assert_eq!(check(&DbDriver::SqlServer, "SELECT 1"), None);
+    }
+
+    #[test]
+    fn test_format_insert_batches() {
+        let rows = [
+            vec![json!(1), json!("O'Hara")],
+            vec![json!(2), Value::Null],
+            vec![json!(3), json!(true)],
+        ];
+        let target = quote_qualified(Some("public"), "users", Quoting::Standard);
+
+        assert_eq!(
+            format_insert(&target, None, &rows[..1], &DbDriver::Postgres),
+            "INSERT INTO \"public\".\"users\" VALUES (1, 'O''Hara');"
+        );
+        assert_eq!(
+            format_insert(
+                "`users`",
+                Some("`id`, `name`"),
+                &rows[1..],
+                &DbDriver::MySql
+            ),
+            "INSERT INTO `users` (`id`, `name`) VALUES\n  (2, NULL),\n  (3, TRUE);"
+        );
+
+        assert_eq!(rows_per_insert(&DbDriver::Postgres, 0), 1);
+        assert_eq!(rows_per_insert(&DbDriver::Postgres, 5000), 5000);
+        assert_eq!(rows_per_insert(&DbDriver::SqlServer, 5000), 1000);
+    }
+
+    #[test]
+    fn test_format_rows_as_insert_statements() {
+        let columns = vec!["id".to_string(), "note".to_string()];
+        let rows = vec![
+            vec![json!(1), json!("it's a C:\\path")],
+            vec![json!(2), Value::Null],
+        ];
+
+        assert_eq!(
+            format_rows_as(
+                columns.clone(),
+                rows.clone(),
+                Some("public.notes".to_string()),
+                RowCopyFormat::InsertStatements,
+                Some(DbDriver::Postgres),
+            )
+            .unwrap(),
+            "INSERT INTO \"public\".\"notes\" (\"id\", \"note\") VALUES (1, 'it''s a C:\\path');\n\
+             INSERT INTO \"public\".\"notes\" (\"id\", \"note\") VALUES (2, NULL);"
+        );
+        // MySQL doubles backslashes and uses backticks
+        assert_eq!(
+            format_rows_as(
+                columns.clone(),
+                rows[..1].to_vec(),
+                Some("notes".to_string()),
+                RowCopyFormat::InsertStatements,
+                Some(DbDriver::MySql),
+            )
+            .unwrap(),
+            "INSERT INTO `notes` (`id`, `note`) VALUES (1, 'it''s a C:\\\\path');"
+        );
+        assert_eq!(sql_literal(&json!(true), &DbDriver::SqlServer), "1");
+        assert_eq!(
+            sql_literal(&json!("line\nbreak"), &DbDriver::Postgres),
+            "E'line\\nbreak'"
+        );
+        assert!(matches!(
+            format_rows_as(columns, rows, None, RowCopyFormat::InsertStatements, None),
+            Err(DbError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_format_rows_as_markdown() {
+        let columns = vec!["id".to_string(), "label".to_string()];
+        let rows = vec![vec![json!(1), json!("a|b")], vec![json!(22), Value::Null]];
+        assert_eq!(
+            format_rows_as(columns, rows, None, RowCopyFormat::Markdown, None).unwrap(),
+            "| id  | label |\n\
+             | --- | ----- |\n\
+             | 1   | a\\|b  |\n\
+             | 22  | NULL  |"
+        );
+    }
+
+    #[test]
+    fn test_format_rows_as_json_and_csv() {
+        let columns = vec!["id".to_string(), "name".to_string()];
+        let rows = vec![
+            vec![json!(1), json!("say \"hi\", bob")],
+            vec![json!(2), Value::Null],
+        ];
+
+        let json_text =
+            format_rows_as(columns.clone(), rows.clone(), None, RowCopyFormat::Json, None).unwrap();
+        let parsed: Value = serde_json::from_str(&json_text).unwrap();
+        assert_eq!(
+            parsed,
+            json!([{ "id": 1, "name": "say \"hi\", bob" }, { "id": 2, "name": null }])
+        );
+
+        assert_eq!(
+            format_rows_as(columns, rows, None, RowCopyFormat::Csv, None).unwrap(),
+            "id,name\n1,\"say \"\"hi\"\", bob\"\n2,"
+        );
+    }
 }
@@ -3,9 +3,15 @@
 //! This module provides Tauri commands for managing query execution history
 //! and saved query snippets. History is automatically saved when queries are
 //! executed, and snippets can be manually created and managed by users.
+//! Saved views are named, connection-bound queries shown in the sidebar tree.
 
-use crate::models::{DbError, QueryHistory, QuerySnippet};
+use super::query::{run_query, QueryExecutionResult};
+use super::settings::load_settings;
+use crate::models::{DbError, QueryHistory, QuerySnippet, SavedView, SnippetVariable};
+use crate::sql::template::{find_template_variables, render_template, to_named_parameters};
 use crate::state::AppState;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::Mutex;
 use tauri::{AppHandle, State};
 
@@ -17,7 +23,7 @@ use tauri::{AppHandle, State};
 ///
 /// Adds a new query execution record to the history. This is typically called
 /// automatically after a query is executed, capturing metadata like execution
-/// time and row count.
+/// time and row count. The history retention settings are applied afterwards.
 ///
 /// # Arguments
 ///
@@ -52,12 +58,15 @@ pub fn save_to_history(
     app: AppHandle,
 ) -> Result<String, DbError> {
     let history_id = history.id.clone();
+    // A broken settings file should not stop history from being recorded
+    let query_settings = load_settings(&app).map(|s| s.query).unwrap_or_default();
 
     // Add the entry and snapshot the history inside the lock, then persist
     // outside the lock so the disk write never blocks other state access
     let snapshot = {
         let mut state = state.lock().unwrap();
         state.add_history(history);
+        state.apply_history_retention(&query_settings);
         state.query_history.clone()
     };
 
@@ -75,6 +84,7 @@ pub fn save_to_history(
 ///
 /// * `connection_id` - Optional filter by connection ID
 /// * `limit` - Optional limit on number of records (most recent first)
+/// * `favorites_only` - Only return favorited entries
 /// * `state` - Application state
 ///
 /// # Returns
@@ -97,11 +107,17 @@ pub fn save_to_history(
 ///   connectionId: 'conn-123',
 ///   limit: 100
 /// });
+///
+/// // Get starred queries only
+/// const favorites = await invoke<QueryHistory[]>('get_query_history', {
+///   favoritesOnly: true
+/// });
 /// ```
 #[tauri::command]
 pub fn get_query_history(
     connection_id: Option<String>,
     limit: Option<usize>,
+    favorites_only: Option<bool>,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<Vec<QueryHistory>, DbError> {
     let state = state.lock().unwrap();
@@ -112,6 +128,10 @@ pub fn get_query_history(
         state.get_all_history()
     };
 
+    if favorites_only.unwrap_or(false) {
+        history.retain(|h| h.favorite);
+    }
+
     // Sort by most recent first (newest executedAt first)
     history.sort_by(|a, b| b.executed_at.cmp(&a.executed_at));
 
@@ -171,6 +191,115 @@ pub fn clear_history(
     Ok(count)
 }
 
+/// Prune query history according to the retention settings
+///
+/// Drops entries older than `historyRetentionDays` and the oldest entries
+/// beyond `maxHistoryEntries`. Favorited entries are always kept. Pruning
+/// also runs on startup and after every `save_to_history`; this command is
+/// for applying changed settings right away.
+///
+/// # Returns
+///
+/// Number of history entries removed
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const removed = await invoke<number>('prune_history');
+/// ```
+#[tauri::command]
+pub fn prune_history(state: State<'_, Mutex<AppState>>, app: AppHandle) -> Result<usize, DbError> {
+    let settings = load_settings(&app)?;
+
+    let (removed, snapshot) = {
+        let mut state = state.lock().unwrap();
+        let removed = state.apply_history_retention(&settings.query);
+        (removed, state.query_history.clone())
+    };
+
+    if removed > 0 {
+        AppState::save_history_to_store(&app, &snapshot)?;
+    }
+
+    Ok(removed)
+}
+
+/// Star or unstar a history entry
+///
+/// Favorited entries are exempt from history pruning.
+///
+/// # Arguments
+///
+/// * `id` - History entry ID
+/// * `state` - Application state
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// Whether the entry is a favorite after toggling
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const isFavorite = await invoke<boolean>('toggle_history_favorite', { id: 'history-123' });
+/// ```
+#[tauri::command]
+pub fn toggle_history_favorite(
+    id: String,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<bool, DbError> {
+    let (favorite, snapshot) = {
+        let mut state = state.lock().unwrap();
+        let entry = state
+            .get_history_mut(&id)
+            .ok_or_else(|| DbError::NotFound(format!("History entry not found: {}", id)))?;
+        entry.favorite = !entry.favorite;
+        let favorite = entry.favorite;
+        (favorite, state.query_history.clone())
+    };
+
+    AppState::save_history_to_store(&app, &snapshot)?;
+
+    Ok(favorite)
+}
+
+/// Add, change or remove the note on a history entry
+///
+/// # Arguments
+///
+/// * `id` - History entry ID
+/// * `note` - Note text; `null` or blank removes the note
+/// * `state` - Application state
+/// * `app` - Tauri application handle
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('set_history_note', {
+///   id: 'history-123',
+///   note: 'Numbers for the monthly churn report'
+/// });
+/// ```
+#[tauri::command]
+pub fn set_history_note(
+    id: String,
+    note: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<(), DbError> {
+    let snapshot = {
+        let mut state = state.lock().unwrap();
+        state
+            .get_history_mut(&id)
+            .ok_or_else(|| DbError::NotFound(format!("History entry not found: {}", id)))?
+            .set_note(note);
+        state.query_history.clone()
+    };
+
+    AppState::save_history_to_store(&app, &snapshot)
+}
+
 // ============================================================================
 // Query Snippet Commands
 // ============================================================================
@@ -247,11 +376,14 @@ pub fn save_snippet(
 
 /// List all saved query snippets
 ///
-/// Retrieves all saved query snippets, optionally filtered by tags.
+/// Retrieves all saved query snippets, optionally filtered by tag and by
+/// connection. With a connection ID only global snippets and those scoped to
+/// that connection (or to its driver) are returned.
 ///
 /// # Arguments
 ///
 /// * `tag` - Optional filter by tag
+/// * `connection_id` - Optional active connection (profile) ID
 /// * `state` - Application state
 ///
 /// # Returns
@@ -268,10 +400,16 @@ pub fn save_snippet(
 /// const backupSnippets = await invoke<QuerySnippet[]>('list_snippets', {
 ///   tag: 'backup'
 /// });
+///
+/// // Get snippets relevant to the active connection
+/// const editorSnippets = await invoke<QuerySnippet[]>('list_snippets', {
+///   connectionId: 'conn-123'
+/// });
 /// ```
 #[tauri::command]
 pub fn list_snippets(
     tag: Option<String>,
+    connection_id: Option<String>,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<Vec<QuerySnippet>, DbError> {
     let state = state.lock().unwrap();
@@ -282,6 +420,12 @@ pub fn list_snippets(
         state.get_all_snippets()
     };
 
+    // Keep global snippets plus those scoped to this connection or its driver
+    if let Some(id) = connection_id {
+        let driver = state.get_profile(&id).map(|p| &p.driver);
+        snippets.retain(|s| s.applies_to(&id, driver));
+    }
+
     // Sort alphabetically by name
     snippets.sort_by(|a, b| a.name.cmp(&b.name));
 
@@ -329,8 +473,34 @@ pub fn delete_snippet(
     Ok(())
 }
 
+/// A snippet with the `{{name}}` variables its query uses
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetDetails {
+    #[serde(flatten)]
+    pub snippet: QuerySnippet,
+
+    /// Placeholders found in the query, with any declared defaults
+    pub detected_variables: Vec<SnippetVariable>,
+}
+
+/// A snippet rewritten for `execute_parameterized_query`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParameterizedSnippet {
+    /// Query with each `{{name}}` replaced by a `:name` parameter
+    pub sql: String,
+
+    /// Values to bind, by parameter name
+    pub params: HashMap<String, String>,
+}
+
 /// Get a specific snippet by ID
 ///
+/// The snippet's fields are returned alongside `detectedVariables`, the
+/// `{{name}}` placeholders in its query, so the UI can prompt for them
+/// before running it.
+///
 /// # Arguments
 ///
 /// * `snippet_id` - ID of snippet to retrieve
@@ -338,12 +508,12 @@ pub fn delete_snippet(
 ///
 /// # Returns
 ///
-/// The snippet if found
+/// The snippet and its variables if found
 ///
 /// # Frontend Usage
 ///
 /// ```typescript
-/// const snippet = await invoke<QuerySnippet>('get_snippet', {
+/// const snippet = await invoke<SnippetDetails>('get_snippet', {
 ///   snippetId: 'snippet-uuid'
 /// });
 /// ```
@@ -351,10 +521,406 @@ pub fn delete_snippet(
 pub fn get_snippet(
     snippet_id: String,
     state: State<'_, Mutex<AppState>>,
-) -> Result<QuerySnippet, DbError> {
+) -> Result<SnippetDetails, DbError> {
     let state = state.lock().unwrap();
-    state
+    let snippet = state
         .get_snippet(&snippet_id)
         .cloned()
-        .ok_or_else(|| DbError::NotFound(format!("Snippet not found: {}", snippet_id)))
+        .ok_or_else(|| DbError::NotFound(format!("Snippet not found: {}", snippet_id)))?;
+
+    Ok(SnippetDetails {
+        detected_variables: snippet.detected_variables(),
+        snippet,
+    })
+}
+
+/// Render a snippet's query with its variables filled in
+///
+/// Each `{{name}}` is replaced by `values[name]`, or by the variable's
+/// declared default. This is plain textual substitution: values are not
+/// quoted or escaped, so a value can inject arbitrary SQL. Use it for
+/// identifiers and fragments the user types themselves; for data values
+/// prefer `render_snippet_parameterized`, which binds them instead.
+///
+/// # Arguments
+///
+/// * `snippet_id` - ID of the snippet to render
+/// * `values` - Variable values by name
+/// * `state` - Application state
+///
+/// # Returns
+///
+/// The final SQL, or `DbError::InvalidInput` naming a variable with
+/// neither a value nor a default
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const sql = await invoke<string>('render_snippet', {
+///   snippetId: 'snippet-uuid',
+///   values: { table: 'orders', since: '2025-01-01' }
+/// });
+/// ```
+#[tauri::command]
+pub fn render_snippet(
+    snippet_id: String,
+    values: HashMap<String, String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<String, DbError> {
+    let state = state.lock().unwrap();
+    let snippet = state
+        .get_snippet(&snippet_id)
+        .ok_or_else(|| DbError::NotFound(format!("Snippet not found: {}", snippet_id)))?;
+
+    render_template(&snippet.query, &snippet.variable_values(&values))
+}
+
+/// Render a snippet as a parameterized query
+///
+/// Each `{{name}}` becomes a `:name` parameter (a quoted `'{{name}}'` drops
+/// its quotes), and the values are returned separately so they can be passed
+/// to `execute_parameterized_query` and bound by the driver. Variables used
+/// as identifiers, such as a table name, can't be bound; render those with
+/// `render_snippet`.
+///
+/// # Arguments
+///
+/// * `snippet_id` - ID of the snippet to render
+/// * `values` - Variable values by name
+/// * `state` - Application state
+///
+/// # Returns
+///
+/// The rewritten SQL and the values to bind, or `DbError::InvalidInput`
+/// naming a variable with neither a value nor a default
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const { sql, params } = await invoke<ParameterizedSnippet>(
+///   'render_snippet_parameterized',
+///   { snippetId: 'snippet-uuid', values: { since: '2025-01-01' } }
+/// );
+/// await invoke('execute_parameterized_query', { connectionId, sql, params });
+/// ```
+#[tauri::command]
+pub fn render_snippet_parameterized(
+    snippet_id: String,
+    values: HashMap<String, String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ParameterizedSnippet, DbError> {
+    let state = state.lock().unwrap();
+    let snippet = state
+        .get_snippet(&snippet_id)
+        .ok_or_else(|| DbError::NotFound(format!("Snippet not found: {}", snippet_id)))?;
+
+    let mut resolved = snippet.variable_values(&values);
+    let mut params = HashMap::new();
+    for name in find_template_variables(&snippet.query) {
+        let value = resolved.remove(&name).ok_or_else(|| {
+            DbError::InvalidInput(format!("Missing value for snippet variable '{}'", name))
+        })?;
+        params.insert(name, value);
+    }
+
+    Ok(ParameterizedSnippet {
+        sql: to_named_parameters(&snippet.query),
+        params,
+    })
+}
+
+/// Copy a snippet into a connection-specific snippet
+///
+/// Creates a new snippet with the same name, query, description and tags,
+/// scoped to the given connection. The original is left untouched so global
+/// snippets keep showing for other connections.
+///
+/// # Arguments
+///
+/// * `snippet_id` - ID of the snippet to copy
+/// * `connection_id` - Connection (profile) ID to scope the copy to
+/// * `state` - Application state
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// The new snippet's ID
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const newId = await invoke<string>('clone_snippet_to_connection', {
+///   snippetId: 'snippet-uuid',
+///   connectionId: 'conn-123'
+/// });
+/// ```
+#[tauri::command]
+pub fn clone_snippet_to_connection(
+    snippet_id: String,
+    connection_id: String,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<String, DbError> {
+    let mut state = state.lock().unwrap();
+
+    if state.get_profile(&connection_id).is_none() {
+        return Err(DbError::NotFound(format!(
+            "Connection profile not found: {}",
+            connection_id
+        )));
+    }
+
+    let source = state
+        .get_snippet(&snippet_id)
+        .ok_or_else(|| DbError::NotFound(format!("Snippet not found: {}", snippet_id)))?;
+
+    let mut copy = QuerySnippet::new(
+        source.name.clone(),
+        source.query.clone(),
+        source.description.clone(),
+        source.tags.clone(),
+    );
+    copy.driver = source.driver.clone();
+    copy.connection_id = Some(connection_id);
+
+    let copy_id = copy.id.clone();
+    state.add_snippet(copy);
+    state.save_snippets_to_store(&app)?;
+
+    Ok(copy_id)
+}
+
+// ============================================================================
+// Saved View Commands
+// ============================================================================
+
+/// Saved views pinned to the tree under one connection
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedViewGroup {
+    /// Connection (profile) ID
+    pub connection_id: String,
+
+    /// Connection name, if the profile still exists
+    pub connection_name: Option<String>,
+
+    /// Pinned views, sorted alphabetically by name
+    pub views: Vec<SavedView>,
+}
+
+/// Save a saved view
+///
+/// Creates or updates a saved view. If a view with the same ID already
+/// exists, it will be updated; otherwise a new view is created.
+///
+/// # Arguments
+///
+/// * `view` - Saved view to save
+/// * `state` - Application state
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// The view ID (UUID)
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const viewId = await invoke<string>('save_saved_view', {
+///   view: {
+///     id: '', // Empty for new view
+///     name: 'Open orders',
+///     connectionId: 'conn-123',
+///     query: "SELECT * FROM orders WHERE status = 'open'",
+///     isPinnedToTree: true,
+///   }
+/// });
+/// ```
+#[tauri::command]
+pub fn save_saved_view(
+    mut view: SavedView,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<String, DbError> {
+    if view.connection_id.is_empty() {
+        return Err(DbError::InvalidInput(
+            "A saved view needs a connection".to_string(),
+        ));
+    }
+
+    // Generate ID if not provided (new view)
+    if view.id.is_empty() {
+        view.id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        view.created_at = now.clone();
+        view.updated_at = now;
+    } else {
+        // Update timestamp for existing view
+        view.updated_at = chrono::Utc::now().to_rfc3339();
+    }
+
+    let view_id = view.id.clone();
+
+    let mut state = state.lock().unwrap();
+    state.add_saved_view(view);
+    state.save_saved_views_to_store(&app)?;
+
+    Ok(view_id)
+}
+
+/// List saved views
+///
+/// # Arguments
+///
+/// * `connection_id` - Optional filter by connection
+/// * `state` - Application state
+///
+/// # Returns
+///
+/// Vector of saved views, sorted alphabetically by name
+#[tauri::command]
+pub fn list_saved_views(
+    connection_id: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<SavedView>, DbError> {
+    let state = state.lock().unwrap();
+
+    let mut views: Vec<SavedView> = state
+        .saved_views
+        .values()
+        .filter(|v| {
+            connection_id
+                .as_ref()
+                .is_none_or(|id| &v.connection_id == id)
+        })
+        .cloned()
+        .collect();
+
+    views.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(views)
+}
+
+/// Get the saved views pinned to the sidebar tree, grouped by connection
+///
+/// # Returns
+///
+/// One group per connection that has pinned views, sorted by connection
+/// name (groups whose profile was deleted come last)
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const groups = await invoke<SavedViewGroup[]>('get_saved_view_tree');
+/// ```
+#[tauri::command]
+pub fn get_saved_view_tree(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<SavedViewGroup>, DbError> {
+    let state = state.lock().unwrap();
+
+    let mut groups: Vec<SavedViewGroup> = Vec::new();
+    for view in state.saved_views.values().filter(|v| v.is_pinned_to_tree) {
+        match groups
+            .iter_mut()
+            .find(|g| g.connection_id == view.connection_id)
+        {
+            Some(group) => group.views.push(view.clone()),
+            None => groups.push(SavedViewGroup {
+                connection_id: view.connection_id.clone(),
+                connection_name: state
+                    .get_profile(&view.connection_id)
+                    .map(|p| p.name.clone()),
+                views: vec![view.clone()],
+            }),
+        }
+    }
+
+    for group in &mut groups {
+        group.views.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    groups.sort_by(|a, b| match (&a.connection_name, &b.connection_name) {
+        (Some(x), Some(y)) => x.cmp(y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.connection_id.cmp(&b.connection_id),
+    });
+
+    Ok(groups)
+}
+
+/// Delete a saved view
+///
+/// # Arguments
+///
+/// * `view_id` - ID of the view to delete
+/// * `state` - Application state
+/// * `app` - Tauri application handle
+#[tauri::command]
+pub fn delete_saved_view(
+    view_id: String,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<(), DbError> {
+    let mut state = state.lock().unwrap();
+    state
+        .remove_saved_view(&view_id)
+        .ok_or_else(|| DbError::NotFound(format!("Saved view not found: {}", view_id)))?;
+    state.save_saved_views_to_store(&app)?;
+
+    Ok(())
+}
+
+/// Get a specific saved view by ID
+#[tauri::command]
+pub fn get_saved_view(
+    view_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<SavedView, DbError> {
+    let state = state.lock().unwrap();
+    state
+        .get_saved_view(&view_id)
+        .cloned()
+        .ok_or_else(|| DbError::NotFound(format!("Saved view not found: {}", view_id)))
+}
+
+/// Run (or refresh) a saved view
+///
+/// Executes the view's SQL on its connection through the same path as
+/// `execute_query`, then records the run time and row count on the view.
+///
+/// # Arguments
+///
+/// * `view_id` - ID of the view to run
+/// * `state` - Application state
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// The query result, as returned by `execute_query`
+#[tauri::command]
+pub async fn run_saved_view(
+    view_id: String,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<QueryExecutionResult, DbError> {
+    let view = {
+        let state = state.lock().unwrap();
+        state
+            .get_saved_view(&view_id)
+            .cloned()
+            .ok_or_else(|| DbError::NotFound(format!("Saved view not found: {}", view_id)))?
+    };
+
+    let result = run_query(view.connection_id, view.query, state.clone(), Some(&app)).await?;
+
+    let row_count = result.rows_affected.unwrap_or(result.rows.len() as u64);
+
+    let mut state = state.lock().unwrap();
+    if let Some(view) = state.saved_views.get_mut(&view_id) {
+        view.record_run(row_count);
+    }
+    state.save_saved_views_to_store(&app)?;
+
+    Ok(result)
 }
@@ -1,8 +1,9 @@
 //! Schema migration Tauri commands.
 //!
-//! Three commands: `compute_schema_diff`, `generate_migration`, and
-//! `apply_migration`. The first two are pure/advisory; the third executes
-//! statements against the live connection inside an optional transaction.
+//! `compare_schemas` (or the older same-schema `compute_schema_diff`) builds
+//! a diff, `generate_migration_ddl` (or `generate_migration`) turns it into
+//! statements, and `apply_migration` executes statements against the live
+//! connection inside an optional transaction. Only the last one writes.
 
 use std::sync::Mutex;
 
@@ -11,8 +12,12 @@ use tauri::State;
 
 use crate::drivers::DatabaseDriver;
 use crate::migrations::diff::TableWithFks;
-use crate::migrations::{compute_diff, generate_migration_sql, SchemaDiff};
-use crate::models::DbError;
+use crate::migrations::type_map::dialect_of;
+use crate::migrations::{
+    compute_diff, generate_migration_ddl as migration_ddl, generate_migration_sql, MigrationDdl,
+    SchemaDiff,
+};
+use crate::models::{DbDriver, DbError};
 use crate::state::AppState;
 use std::sync::Arc;
 
@@ -80,6 +85,86 @@ async fn collect_tables(
     Ok(out)
 }
 
+/// One side of a schema comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaRef {
+    pub connection_id: String,
+    pub schema: String,
+}
+
+/// Compare two schemas, on the same or different connections
+///
+/// The diff describes what the `target` schema needs to match `source`.
+/// Tables are matched by name, so two differently named schemas (say
+/// `staging` and `public`) compare table by table; the diff refers to the
+/// target schema. When the connections use different dialects, types are
+/// compared by meaning (`int4` equals `INTEGER`) and defaults are not
+/// compared, which is noted in `warnings`.
+///
+/// # Example
+///
+/// ```typescript
+/// const diff = await invoke<SchemaDiff>('compare_schemas', {
+///     source: { connectionId: 'dev', schema: 'public' },
+///     target: { connectionId: 'prod', schema: 'public' },
+/// });
+/// ```
+#[tauri::command]
+pub async fn compare_schemas(
+    source: SchemaRef,
+    target: SchemaRef,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<SchemaDiff, DbError> {
+    let src_driver = driver_of(&state, &source.connection_id)?;
+    let tgt_driver = driver_of(&state, &target.connection_id)?;
+    let src_dialect = profile_driver(&state, &source.connection_id)?;
+    let tgt_dialect = profile_driver(&state, &target.connection_id)?;
+
+    let mut src = collect_tables(&src_driver, &source.schema).await?;
+    let mut tgt = collect_tables(&tgt_driver, &target.schema).await?;
+    for table in &mut src {
+        move_to_schema(table, &source.schema, &target.schema);
+    }
+
+    let mut warnings = Vec::new();
+    if dialect_of(&src_dialect) != dialect_of(&tgt_dialect) {
+        // Default expressions are written differently on every server
+        for column in src
+            .iter_mut()
+            .chain(tgt.iter_mut())
+            .flat_map(|t| t.schema.columns.iter_mut())
+        {
+            column.default_value = None;
+        }
+        warnings.push(format!(
+            "Comparing {} with {}: column types are compared by meaning and defaults are ignored",
+            dialect_of(&src_dialect),
+            dialect_of(&tgt_dialect)
+        ));
+    }
+
+    let mut diff = compute_diff(&src, &tgt);
+    diff.source_driver = Some(src_dialect);
+    diff.target_driver = Some(tgt_dialect);
+    diff.warnings = warnings;
+    Ok(diff)
+}
+
+/// Re-home a source table (and FKs within its schema) in the target schema
+fn move_to_schema(table: &mut TableWithFks, from: &str, to: &str) {
+    if from == to {
+        return;
+    }
+    table.schema.table.schema = to.to_string();
+    for fk in &mut table.foreign_keys {
+        fk.schema = to.to_string();
+        if fk.referenced_schema == from {
+            fk.referenced_schema = to.to_string();
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn compute_schema_diff(
     source_connection_id: String,
@@ -93,7 +178,10 @@ pub async fn compute_schema_diff(
     let src = collect_tables(&src_driver, &schema).await?;
     let tgt = collect_tables(&tgt_driver, &schema).await?;
 
-    Ok(compute_diff(&src, &tgt))
+    let mut diff = compute_diff(&src, &tgt);
+    diff.source_driver = profile_driver(&state, &source_connection_id).ok();
+    diff.target_driver = profile_driver(&state, &target_connection_id).ok();
+    Ok(diff)
 }
 
 #[tauri::command]
@@ -106,6 +194,36 @@ pub async fn generate_migration(
     generate_migration_sql(&diff, &driver)
 }
 
+/// Generate migration DDL for a diff, with destructive statements kept apart
+///
+/// `dialect` defaults to the diff's target driver. `statements` never drop a
+/// column or table; those are in `destructiveStatements`, to be run after
+/// `statements` only if the user opts in. Types that do not map exactly
+/// between dialects are listed in `warnings`.
+///
+/// # Example
+///
+/// ```typescript
+/// const ddl = await invoke<MigrationDdl>('generate_migration_ddl', { diff });
+/// await invoke('apply_migration', {
+///     connectionId: 'prod',
+///     statements: ddl.statements,
+///     useTransaction: true,
+/// });
+/// ```
+#[tauri::command]
+pub async fn generate_migration_ddl(
+    diff: SchemaDiff,
+    dialect: Option<DbDriver>,
+) -> Result<MigrationDdl, DbError> {
+    let dialect = dialect
+        .or_else(|| diff.target_driver.clone())
+        .ok_or_else(|| {
+            DbError::InvalidInput("Choose the dialect to generate the migration for".to_string())
+        })?;
+    migration_ddl(&diff, &dialect)
+}
+
 #[tauri::command]
 pub async fn apply_migration(
     connection_id: String,
@@ -9,13 +9,30 @@ pub mod backup;
 pub mod connection;
 pub mod data_import;
 pub mod ddl;
+pub mod erd;
 pub mod export;
 pub mod history;
 pub mod migrations;
 pub mod monitoring;
+pub mod notifications;
 pub mod plugins;
 pub mod procedures;
+pub mod profile_transfer;
+pub mod profiling;
 pub mod query;
+pub mod query_export;
+pub mod query_plan;
+pub mod query_templates;
+pub mod reconnect;
+pub mod result_diff;
+pub mod schedules;
 pub mod schema;
+pub mod sequences;
 pub mod settings;
+pub mod snippet_transfer;
+pub mod sql_tools;
+pub mod sqlite;
+pub mod table_data;
+pub mod triggers;
+pub mod vault;
 pub mod window;
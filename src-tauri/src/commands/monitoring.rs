@@ -4,16 +4,26 @@
 //! querying server system views (pg_stat_activity, SHOW PROCESSLIST, etc).
 //! Drivers that do not expose session metadata return InvalidInput so the
 //! frontend can render a graceful "not supported" message.
+//!
+//! `get_database_stats` backs the database overview dashboard. Each figure
+//! comes from its own query so one denied query only blanks the figures it
+//! covers instead of failing the whole request.
+//!
+//! `get_server_info` reports the server version and settings, plus which
+//! syntax the version supports, so callers can gate features like
+//! `STRING_AGG` (SQL Server 2017+) or `RETURNING` (SQLite 3.35+).
 
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::State;
+use tauri::{AppHandle, State};
 
+use crate::commands::settings::load_settings;
 use crate::drivers::DatabaseDriver;
-use crate::models::{DbDriver, DbError};
-use crate::state::AppState;
+use crate::models::{DatabaseStats, DbDriver, DbError, Environment};
+use crate::sql::literal::escape_literal;
+use crate::state::{AppState, CachedDatabaseStats, QueryPriority, QueryTicket};
 
 /// A snapshot of a single active session/query on the database server.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +94,13 @@ fn as_f64(v: &Value) -> Option<f64> {
     }
 }
 
+fn as_u64(v: &Value) -> Option<u64> {
+    // MySQL returns SUM()/DECIMAL results as strings like "1024" or "1024.0".
+    as_i64(v)
+        .or_else(|| as_f64(v).map(|f| f as i64))
+        .and_then(|n| u64::try_from(n).ok())
+}
+
 fn as_string(v: &Value) -> Option<String> {
     match v {
         Value::Null => None,
@@ -206,6 +223,194 @@ pub async fn kill_query(
     }
 }
 
+/// A client session on the database server, idle or not.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerSession {
+    /// Backend pid (PostgreSQL), connection id (MySQL) or spid (SQL Server)
+    pub session_id: i64,
+    pub user: Option<String>,
+    pub database: Option<String>,
+    pub client_addr: Option<String>,
+    pub state: Option<String>,
+    pub query_text: Option<String>,
+    /// Time since the current (or last) statement started
+    pub duration_ms: Option<i64>,
+    /// The session this listing was read from, which belongs to DB Hive
+    pub is_current: bool,
+}
+
+/// List client sessions on the server, including DB Hive's own.
+///
+/// SQLite and Turso are embedded and have no other sessions, so they return
+/// an empty list.
+#[tauri::command]
+pub async fn get_server_sessions(
+    connection_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<ServerSession>, DbError> {
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+
+    let sql = if driver.is_postgres_compatible() {
+        r#"
+            SELECT pid,
+                   usename,
+                   datname,
+                   client_addr::text,
+                   state,
+                   query,
+                   CASE WHEN query_start IS NOT NULL
+                        THEN (EXTRACT(EPOCH FROM (now() - query_start)) * 1000)::bigint
+                        ELSE NULL END,
+                   pid = pg_backend_pid()
+            FROM pg_stat_activity
+            WHERE backend_type = 'client backend'
+            ORDER BY query_start NULLS LAST
+        "#
+    } else {
+        match driver {
+            DbDriver::MySql => {
+                // Same rows as SHOW PROCESSLIST, but CONNECTION_ID() is
+                // evaluated on the connection that reads them
+                r#"
+                    SELECT ID, USER, DB, HOST, COMMAND, INFO, TIME * 1000,
+                           ID = CONNECTION_ID()
+                    FROM information_schema.PROCESSLIST
+                    ORDER BY TIME DESC
+                "#
+            }
+            DbDriver::SqlServer => {
+                // Idle sessions have no request; fall back to the session's
+                // last request for state and timing. Seconds are cast before
+                // scaling so long-lived sessions don't overflow DATEDIFF.
+                r#"
+                    SELECT s.session_id,
+                           s.login_name,
+                           DB_NAME(COALESCE(r.database_id, s.database_id)),
+                           c.client_net_address,
+                           COALESCE(r.status, s.status),
+                           t.text,
+                           CAST(DATEDIFF(SECOND,
+                                COALESCE(r.start_time, s.last_request_start_time),
+                                GETDATE()) AS BIGINT) * 1000,
+                           CASE WHEN s.session_id = @@SPID THEN 1 ELSE 0 END
+                    FROM sys.dm_exec_sessions s
+                    LEFT JOIN sys.dm_exec_requests r ON r.session_id = s.session_id
+                    LEFT JOIN sys.dm_exec_connections c
+                        ON c.session_id = s.session_id AND c.parent_connection_id IS NULL
+                    OUTER APPLY sys.dm_exec_sql_text(r.sql_handle) t
+                    WHERE s.is_user_process = 1
+                    ORDER BY s.session_id
+                "#
+            }
+            DbDriver::Sqlite | DbDriver::Turso => return Ok(Vec::new()),
+            _ => return Err(not_supported(&driver)),
+        }
+    };
+
+    let res = conn.execute_query(sql).await?;
+    Ok(res
+        .rows
+        .iter()
+        .map(|row| ServerSession {
+            session_id: row.first().and_then(as_i64).unwrap_or(0),
+            user: row.get(1).and_then(as_string),
+            database: row.get(2).and_then(as_string),
+            client_addr: row.get(3).and_then(as_string),
+            state: row.get(4).and_then(as_string),
+            query_text: row.get(5).and_then(as_string).map(|q| truncate(q, 500)),
+            duration_ms: row.get(6).and_then(as_i64),
+            is_current: row.get(7).is_some_and(is_truthy),
+        })
+        .collect())
+}
+
+/// Terminate a server session, rolling back its open transaction.
+///
+/// Unlike `kill_query`, which only cancels the running statement, this
+/// disconnects the session. It is refused with `DbError::ConfirmationRequired`
+/// unless `confirmed` is true when the `confirmDestructive` setting is on or
+/// the connection is tagged as production. The session the statement runs on
+/// is never terminated; since connections are pooled, DB Hive's other pooled
+/// sessions are not protected and are simply reopened if killed.
+#[tauri::command]
+pub async fn kill_session(
+    connection_id: String,
+    session_id: i64,
+    confirmed: Option<bool>,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<(), DbError> {
+    if !confirmed.unwrap_or(false) {
+        let confirm_setting = load_settings(&app)?.query.confirm_destructive;
+        let state = state.lock().unwrap();
+        let production = state
+            .get_profile(&connection_id)
+            .and_then(|p| p.environment.as_ref())
+            .is_some_and(Environment::requires_confirmation);
+        if confirm_setting || production {
+            return Err(DbError::ConfirmationRequired(format!(
+                "Terminate session {}",
+                session_id
+            )));
+        }
+    }
+
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+    let own_session =
+        || DbError::InvalidInput("Cannot terminate DB Hive's own session".to_string());
+
+    if driver.is_postgres_compatible() {
+        // The guard runs on the same backend as the terminate call
+        let sql = format!(
+            "SELECT pg_terminate_backend({0}) WHERE {0} <> pg_backend_pid()",
+            session_id
+        );
+        let res = conn.execute_query(&sql).await?;
+        return match res.rows.first().and_then(|row| row.first()) {
+            None => Err(own_session()),
+            Some(terminated) if is_truthy(terminated) => Ok(()),
+            Some(_) => Err(DbError::NotFound(format!(
+                "Session {} not found",
+                session_id
+            ))),
+        };
+    }
+
+    match driver {
+        DbDriver::MySql => {
+            // KILL can't be made conditional outside a stored program, so the
+            // id is checked beforehand. The pool normally hands both
+            // statements the same idle connection.
+            let res = conn.execute_query("SELECT CONNECTION_ID()").await?;
+            let current = res.rows.first().and_then(|row| row.first()).and_then(as_i64);
+            if current == Some(session_id) {
+                return Err(own_session());
+            }
+            conn.execute_query(&format!("KILL {}", session_id)).await?;
+            Ok(())
+        }
+        DbDriver::SqlServer => {
+            let sql = format!(
+                "IF @@SPID = {0} RAISERROR('Cannot terminate DB Hive''s own session', 16, 1) ELSE KILL {0}",
+                session_id
+            );
+            conn.execute_query(&sql).await?;
+            Ok(())
+        }
+        _ => Err(not_supported(&driver)),
+    }
+}
+
+/// Read a boolean that may come back as `true`, `1` or `"1"`
+fn is_truthy(v: &Value) -> bool {
+    match v {
+        Value::Bool(b) => *b,
+        Value::String(s) => matches!(s.as_str(), "1" | "t" | "true"),
+        other => as_i64(other).is_some_and(|n| n != 0),
+    }
+}
+
 /// Retrieve aggregate server metrics for charting.
 #[tauri::command]
 pub async fn get_server_stats(
@@ -276,3 +481,534 @@ pub async fn get_server_stats(
         _ => Err(not_supported(&driver)),
     }
 }
+
+/// A figure reported by `get_database_stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StatField {
+    SizeBytes,
+    TableCount,
+    ViewCount,
+    IndexCount,
+    EstimatedRows,
+    ActiveConnections,
+}
+
+impl StatField {
+    const ALL: [StatField; 6] = [
+        StatField::SizeBytes,
+        StatField::TableCount,
+        StatField::ViewCount,
+        StatField::IndexCount,
+        StatField::EstimatedRows,
+        StatField::ActiveConnections,
+    ];
+
+    /// Serialized (camelCase) field name, as reported in `unavailable`.
+    fn name(self) -> &'static str {
+        match self {
+            StatField::SizeBytes => "sizeBytes",
+            StatField::TableCount => "tableCount",
+            StatField::ViewCount => "viewCount",
+            StatField::IndexCount => "indexCount",
+            StatField::EstimatedRows => "estimatedRows",
+            StatField::ActiveConnections => "activeConnections",
+        }
+    }
+
+    fn get(self, stats: &DatabaseStats) -> Option<u64> {
+        match self {
+            StatField::SizeBytes => stats.size_bytes,
+            StatField::TableCount => stats.table_count,
+            StatField::ViewCount => stats.view_count,
+            StatField::IndexCount => stats.index_count,
+            StatField::EstimatedRows => stats.estimated_rows,
+            StatField::ActiveConnections => stats.active_connections,
+        }
+    }
+
+    fn set(self, stats: &mut DatabaseStats, value: Option<u64>) {
+        match self {
+            StatField::SizeBytes => stats.size_bytes = value,
+            StatField::TableCount => stats.table_count = value,
+            StatField::ViewCount => stats.view_count = value,
+            StatField::IndexCount => stats.index_count = value,
+            StatField::EstimatedRows => stats.estimated_rows = value,
+            StatField::ActiveConnections => stats.active_connections = value,
+        }
+    }
+}
+
+/// A single-row query whose columns map, in order, onto `fields`.
+type StatQuery = (String, &'static [StatField]);
+
+/// Per-driver stats queries, or `None` if the driver has no notion of them.
+fn database_stats_queries(driver: &DbDriver, database: &str) -> Option<Vec<StatQuery>> {
+    let db = escape_literal(database, driver);
+
+    if driver.is_postgres_compatible() {
+        // Catalog counts cover the connected database only; a Postgres
+        // session cannot see another database's pg_class.
+        return Some(vec![
+            (
+                format!("SELECT pg_database_size({})::bigint", db),
+                &[StatField::SizeBytes],
+            ),
+            (
+                r#"
+                SELECT
+                    count(*) FILTER (WHERE c.relkind IN ('r', 'p'))::bigint,
+                    count(*) FILTER (WHERE c.relkind IN ('v', 'm'))::bigint,
+                    count(*) FILTER (WHERE c.relkind = 'i')::bigint,
+                    COALESCE(SUM(c.reltuples) FILTER (
+                        WHERE c.relkind IN ('r', 'p') AND c.reltuples > 0), 0)::bigint
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+                  AND n.nspname NOT LIKE 'pg_toast%'
+                "#
+                .to_string(),
+                &[
+                    StatField::TableCount,
+                    StatField::ViewCount,
+                    StatField::IndexCount,
+                    StatField::EstimatedRows,
+                ],
+            ),
+            (
+                format!(
+                    "SELECT count(*)::bigint FROM pg_stat_activity WHERE datname = {}",
+                    db
+                ),
+                &[StatField::ActiveConnections],
+            ),
+        ]);
+    }
+
+    match driver {
+        DbDriver::MySql => Some(vec![
+            (
+                format!(
+                    "SELECT COALESCE(SUM(DATA_LENGTH + INDEX_LENGTH), 0), \
+                     COALESCE(SUM(TABLE_TYPE = 'BASE TABLE'), 0), \
+                     COALESCE(SUM(TABLE_TYPE = 'VIEW'), 0), \
+                     COALESCE(SUM(TABLE_ROWS), 0) \
+                     FROM information_schema.TABLES WHERE TABLE_SCHEMA = {}",
+                    db
+                ),
+                &[
+                    StatField::SizeBytes,
+                    StatField::TableCount,
+                    StatField::ViewCount,
+                    StatField::EstimatedRows,
+                ],
+            ),
+            (
+                format!(
+                    "SELECT COUNT(DISTINCT TABLE_NAME, INDEX_NAME) \
+                     FROM information_schema.STATISTICS WHERE TABLE_SCHEMA = {}",
+                    db
+                ),
+                &[StatField::IndexCount],
+            ),
+            (
+                // Without the PROCESS privilege only the caller's own
+                // threads are visible, so this may undercount.
+                format!(
+                    "SELECT COUNT(*) FROM information_schema.PROCESSLIST WHERE DB = {}",
+                    db
+                ),
+                &[StatField::ActiveConnections],
+            ),
+        ]),
+        DbDriver::Sqlite | DbDriver::Turso => Some(vec![
+            (
+                "SELECT p.page_count * s.page_size \
+                 FROM pragma_page_count() p, pragma_page_size() s"
+                    .to_string(),
+                &[StatField::SizeBytes],
+            ),
+            (
+                "SELECT \
+                 COALESCE(SUM(type = 'table' AND name NOT LIKE 'sqlite_%'), 0), \
+                 COALESCE(SUM(type = 'view'), 0), \
+                 COALESCE(SUM(type = 'index'), 0) \
+                 FROM sqlite_master"
+                    .to_string(),
+                &[
+                    StatField::TableCount,
+                    StatField::ViewCount,
+                    StatField::IndexCount,
+                ],
+            ),
+        ]),
+        DbDriver::SqlServer => Some(vec![
+            (
+                // sys.database_files sizes are in 8 KB pages.
+                "SELECT SUM(CAST(size AS bigint)) * 8192 FROM sys.database_files".to_string(),
+                &[StatField::SizeBytes],
+            ),
+            (
+                "SELECT \
+                 (SELECT COUNT_BIG(*) FROM sys.tables), \
+                 (SELECT COUNT_BIG(*) FROM sys.views), \
+                 (SELECT COUNT_BIG(*) FROM sys.indexes i \
+                    JOIN sys.tables t ON t.object_id = i.object_id WHERE i.index_id > 0), \
+                 (SELECT COALESCE(SUM(p.rows), 0) FROM sys.partitions p \
+                    JOIN sys.tables t ON t.object_id = p.object_id WHERE p.index_id IN (0, 1))"
+                    .to_string(),
+                &[
+                    StatField::TableCount,
+                    StatField::ViewCount,
+                    StatField::IndexCount,
+                    StatField::EstimatedRows,
+                ],
+            ),
+            (
+                "SELECT COUNT_BIG(*) FROM sys.dm_exec_sessions WHERE database_id = DB_ID()"
+                    .to_string(),
+                &[StatField::ActiveConnections],
+            ),
+        ]),
+        _ => None,
+    }
+}
+
+/// Run the stats queries, keeping whatever succeeds.
+///
+/// A failed query (typically a permission error) marks the fields it covers
+/// as unavailable; fields no query covers for this engine are too. Each
+/// query waits for the connection's interactive queries first.
+async fn collect_database_stats(
+    conn: &dyn DatabaseDriver,
+    database: &str,
+    queries: Vec<StatQuery>,
+    ticket: &QueryTicket,
+) -> DatabaseStats {
+    let mut stats = DatabaseStats {
+        database: database.to_string(),
+        ..Default::default()
+    };
+
+    for (sql, fields) in &queries {
+        ticket.yield_to_interactive().await;
+        let row = match conn.execute_query(sql).await {
+            Ok(res) => res.rows.into_iter().next(),
+            Err(_) => None,
+        };
+        apply_stat_row(&mut stats, fields, row.as_deref());
+    }
+    mark_unavailable(&mut stats);
+
+    stats
+}
+
+/// Set `fields` from the columns of a stats query's row, in order. A failed
+/// query (no row) or a NULL column leaves its fields unset.
+fn apply_stat_row(stats: &mut DatabaseStats, fields: &[StatField], row: Option<&[Value]>) {
+    for (i, field) in fields.iter().enumerate() {
+        let value = row.and_then(|r| r.get(i)).and_then(as_u64);
+        field.set(stats, value);
+    }
+}
+
+/// List every field that is still unset as unavailable.
+fn mark_unavailable(stats: &mut DatabaseStats) {
+    for field in StatField::ALL {
+        if field.get(stats).is_none() {
+            stats.unavailable.push(field.name().to_string());
+        }
+    }
+}
+
+/// Retrieve database-level stats (size, object counts, connections) for the
+/// overview dashboard.
+///
+/// Results are cached per connection and database for
+/// `DATABASE_STATS_TTL`; pass `force_refresh` to bypass the cache. The stats
+/// queries are background work, run after the connection's interactive
+/// queries.
+#[tauri::command]
+pub async fn get_database_stats(
+    connection_id: String,
+    database: String,
+    force_refresh: Option<bool>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<DatabaseStats, DbError> {
+    let cache_key = format!("{}:{}", connection_id, database);
+    if !force_refresh.unwrap_or(false) {
+        let state = state.lock().unwrap();
+        if let Some(cached) = state.database_stats_cache.get(&cache_key) {
+            if cached.is_fresh() {
+                return Ok(cached.stats.clone());
+            }
+        }
+    }
+
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+    let queries = database_stats_queries(&driver, &database)
+        .ok_or_else(|| not_supported(&driver))?;
+    let scheduler = Arc::clone(&state.lock().unwrap().query_scheduler);
+    let ticket = scheduler
+        .enter(&connection_id, QueryPriority::Background)
+        .await;
+    let stats = collect_database_stats(conn.as_ref(), &database, queries, &ticket).await;
+
+    let mut state = state.lock().unwrap();
+    state
+        .database_stats_cache
+        .insert(cache_key, CachedDatabaseStats::new(stats.clone()));
+
+    Ok(stats)
+}
+
+/// Syntax and features the connected server supports, derived from its
+/// engine and version
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerFeatures {
+    /// `INSERT/UPDATE/DELETE ... RETURNING`
+    pub returning: bool,
+    /// Case-insensitive `ILIKE`
+    pub ilike: bool,
+    /// Window functions (`OVER (...)`)
+    pub window_functions: bool,
+    /// `WITH` common table expressions
+    pub cte: bool,
+    /// `STRING_AGG` aggregate
+    pub string_agg: bool,
+    /// Insert-or-update syntax (`ON CONFLICT`, `ON DUPLICATE KEY UPDATE`)
+    pub upsert: bool,
+    /// JSON functions
+    pub json: bool,
+}
+
+impl ServerFeatures {
+    /// Features of `driver` at `version` (major, minor)
+    ///
+    /// `flavor` is the full version string, used to tell MariaDB from MySQL.
+    fn detect(driver: &DbDriver, flavor: &str, version: (u32, u32)) -> Self {
+        let at_least = |major, minor| version >= (major, minor);
+        if driver.is_postgres_compatible() {
+            return ServerFeatures {
+                returning: true,
+                ilike: true,
+                window_functions: at_least(8, 4),
+                cte: at_least(8, 4),
+                string_agg: at_least(9, 0),
+                upsert: at_least(9, 5),
+                json: at_least(9, 2),
+            };
+        }
+        match driver {
+            DbDriver::MySql if flavor.to_ascii_lowercase().contains("mariadb") => ServerFeatures {
+                returning: at_least(10, 5),
+                window_functions: at_least(10, 2),
+                cte: at_least(10, 2),
+                upsert: true,
+                json: at_least(10, 2),
+                ..Default::default()
+            },
+            DbDriver::MySql => ServerFeatures {
+                window_functions: at_least(8, 0),
+                cte: at_least(8, 0),
+                upsert: true,
+                json: at_least(5, 7),
+                ..Default::default()
+            },
+            DbDriver::Sqlite | DbDriver::Turso => ServerFeatures {
+                returning: at_least(3, 35),
+                window_functions: at_least(3, 25),
+                cte: at_least(3, 8),
+                string_agg: at_least(3, 44),
+                upsert: at_least(3, 24),
+                json: at_least(3, 38),
+                ..Default::default()
+            },
+            // Major versions: 11 = 2012, 13 = 2016, 14 = 2017
+            DbDriver::SqlServer => ServerFeatures {
+                window_functions: at_least(11, 0),
+                cte: true,
+                string_agg: at_least(14, 0),
+                json: at_least(13, 0),
+                ..Default::default()
+            },
+            _ => ServerFeatures::default(),
+        }
+    }
+}
+
+/// Version, settings and supported features of a database server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerInfo {
+    /// Version string as the server reports it
+    pub version: String,
+    pub major_version: Option<u32>,
+    pub minor_version: Option<u32>,
+    /// Server/database character set (encoding for SQLite)
+    pub charset: Option<String>,
+    pub collation: Option<String>,
+    /// Session time zone (UTC offset for SQL Server)
+    pub timezone: Option<String>,
+    pub max_connections: Option<u64>,
+    pub features: ServerFeatures,
+}
+
+/// First `major.minor` in a version string ("PostgreSQL 16.2 on ...",
+/// "10.11.6-MariaDB", "16.0.1000.6")
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    version
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .find_map(|word| {
+            let mut parts = word.split('.');
+            let major = parts.next()?.parse().ok()?;
+            let minor = parts.next()?.parse().ok()?;
+            Some((major, minor))
+        })
+}
+
+/// Query the server version and settings of an open connection
+pub(crate) async fn collect_server_info(
+    conn: &dyn DatabaseDriver,
+    driver: &DbDriver,
+) -> Result<ServerInfo, DbError> {
+    // Columns: version string, parseable version, charset, collation,
+    // timezone, max connections
+    let sql = if driver.is_postgres_compatible() {
+        "SELECT version(), current_setting('server_version'), \
+                pg_encoding_to_char(encoding), datcollate, \
+                current_setting('TimeZone'), current_setting('max_connections') \
+         FROM pg_database WHERE datname = current_database()"
+    } else {
+        match driver {
+            DbDriver::MySql => {
+                "SELECT VERSION(), VERSION(), @@character_set_server, @@collation_server, \
+                        IF(@@time_zone = 'SYSTEM', @@system_time_zone, @@time_zone), \
+                        @@max_connections"
+            }
+            DbDriver::SqlServer => {
+                "SELECT @@VERSION, CAST(SERVERPROPERTY('ProductVersion') AS NVARCHAR(128)), \
+                        NULL, CAST(SERVERPROPERTY('Collation') AS NVARCHAR(128)), \
+                        DATENAME(TZOFFSET, SYSDATETIMEOFFSET()), @@MAX_CONNECTIONS"
+            }
+            DbDriver::Sqlite | DbDriver::Turso => {
+                "SELECT sqlite_version(), sqlite_version(), encoding, NULL, NULL, NULL \
+                 FROM pragma_encoding"
+            }
+            _ => return Err(not_supported(driver)),
+        }
+    };
+
+    let row = conn
+        .execute_query(sql)
+        .await?
+        .rows
+        .into_iter()
+        .next()
+        .ok_or_else(|| DbError::QueryError("Version query returned no rows".to_string()))?;
+    let field = |i: usize| row.get(i).and_then(as_string);
+
+    let version = field(0).unwrap_or_default();
+    // SQL Server's @@VERSION runs over several lines of build details
+    let version = version
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let parsed = field(1).as_deref().and_then(parse_version);
+    Ok(ServerInfo {
+        features: ServerFeatures::detect(driver, &version, parsed.unwrap_or((0, 0))),
+        major_version: parsed.map(|(major, _)| major),
+        minor_version: parsed.map(|(_, minor)| minor),
+        charset: field(2),
+        collation: field(3),
+        timezone: field(4),
+        max_connections: row.get(5).and_then(as_u64),
+        version,
+    })
+}
+
+/// Retrieve the server version, settings and supported features.
+#[tauri::command]
+pub async fn get_server_info(
+    connection_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ServerInfo, DbError> {
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+    collect_server_info(conn.as_ref(), &driver).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            parse_version("16.2 (Debian 16.2-1.pgdg120+2)"),
+            Some((16, 2))
+        );
+        assert_eq!(parse_version("10.11.6-MariaDB-0+deb12u1"), Some((10, 11)));
+        assert_eq!(parse_version("16.0.1000.6"), Some((16, 0)));
+        assert_eq!(parse_version("unknown"), None);
+    }
+
+    #[test]
+    fn test_detect_features_by_version() {
+        let sql_server_2016 = ServerFeatures::detect(&DbDriver::SqlServer, "", (13, 0));
+        assert!(!sql_server_2016.string_agg);
+        assert!(sql_server_2016.json);
+        assert!(ServerFeatures::detect(&DbDriver::SqlServer, "", (14, 0)).string_agg);
+
+        let mariadb = ServerFeatures::detect(&DbDriver::MySql, "10.6.16-MariaDB", (10, 6));
+        assert!(mariadb.returning && mariadb.window_functions);
+        let mysql_57 = ServerFeatures::detect(&DbDriver::MySql, "5.7.44", (5, 7));
+        assert!(!mysql_57.returning && !mysql_57.window_functions && mysql_57.json);
+
+        assert!(!ServerFeatures::detect(&DbDriver::Sqlite, "", (3, 34)).returning);
+        assert!(ServerFeatures::detect(&DbDriver::Neon, "", (16, 2)).ilike);
+    }
+
+    #[test]
+    fn test_mysql_stats_queries_count_empty_schemas_as_zero() {
+        let queries = database_stats_queries(&DbDriver::MySql, "o'brien").unwrap();
+        let (sql, fields) = &queries[0];
+        assert!(sql.contains("COALESCE(SUM(TABLE_TYPE = 'BASE TABLE'), 0)"));
+        assert!(sql.contains("COALESCE(SUM(TABLE_TYPE = 'VIEW'), 0)"));
+        assert!(sql.contains("TABLE_SCHEMA = 'o''brien'"));
+        assert_eq!(fields.len(), 4);
+        assert!(database_stats_queries(&DbDriver::MongoDb, "app").is_none());
+    }
+
+    #[test]
+    fn test_partial_stats_list_unavailable_fields() {
+        let queries = database_stats_queries(&DbDriver::MySql, "shop").unwrap();
+        let mut stats = DatabaseStats::default();
+
+        // Counts come back as DECIMAL strings; a NULL leaves its field unset
+        let tables_row = [json!("1024"), json!("3"), Value::Null, json!(70)];
+        apply_stat_row(&mut stats, queries[0].1, Some(&tables_row[..]));
+        // The index query failed, e.g. for lack of a privilege
+        apply_stat_row(&mut stats, queries[1].1, None);
+        apply_stat_row(&mut stats, queries[2].1, Some(&[json!(5)][..]));
+        mark_unavailable(&mut stats);
+
+        assert_eq!(stats.size_bytes, Some(1024));
+        assert_eq!(stats.table_count, Some(3));
+        assert_eq!(stats.estimated_rows, Some(70));
+        assert_eq!(stats.active_connections, Some(5));
+        assert_eq!(stats.unavailable, vec!["viewCount", "indexCount"]);
+    }
+
+    #[test]
+    fn test_stats_without_queries_are_all_unavailable() {
+        let mut stats = DatabaseStats::default();
+        mark_unavailable(&mut stats);
+        assert_eq!(
+            stats.unavailable,
+            StatField::ALL.map(|f| f.name()).to_vec()
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! PostgreSQL LISTEN/NOTIFY subscriptions
+//!
+//! `listen_channel` subscribes on a dedicated session per connection (see
+//! `drivers::pg_notify`), opened on first use and kept in
+//! `AppState::pg_listeners`. Each notification is emitted to the frontend as
+//! a `pg-notification` event. The session is closed when its last channel is
+//! unlistened or the connection is closed; switching databases closes it
+//! too, since channels are per database.
+
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use super::procedures::take_connection;
+use crate::drivers::pg_notify::{PgListener, PgNotification};
+use crate::models::DbError;
+use crate::state::AppState;
+
+/// Event emitted for every notification received
+pub const PG_NOTIFICATION_EVENT: &str = "pg-notification";
+
+/// Payload of `pg-notification`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NotificationEvent {
+    connection_id: String,
+    #[serde(flatten)]
+    notification: PgNotification,
+}
+
+/// The connection's listener, unless its session has dropped
+fn open_listener(state: &AppState, connection_id: &str) -> Option<Arc<PgListener>> {
+    state
+        .pg_listeners
+        .get(connection_id)
+        .filter(|l| !l.is_closed())
+        .cloned()
+}
+
+/// Subscribe a connection to a notification channel
+///
+/// The first subscription opens the dedicated session. If that session has
+/// dropped since, a new one is opened and earlier subscriptions are lost, so
+/// the returned list is the one to show.
+///
+/// # Returns
+///
+/// The channels the connection is now subscribed to, sorted
+#[tauri::command]
+pub async fn listen_channel(
+    connection_id: String,
+    channel: String,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<Vec<String>, DbError> {
+    if channel.trim().is_empty() {
+        return Err(DbError::InvalidInput(
+            "Channel name cannot be empty".to_string(),
+        ));
+    }
+
+    let existing = open_listener(&state.lock().unwrap(), &connection_id);
+    let listener = match existing {
+        Some(listener) => listener,
+        None => {
+            let (conn, _) = take_connection(&state, &connection_id)?;
+            let id = connection_id.clone();
+            let listener = conn
+                .open_listener(Box::new(move |notification| {
+                    let event = NotificationEvent {
+                        connection_id: id.clone(),
+                        notification,
+                    };
+                    if let Err(e) = app.emit(PG_NOTIFICATION_EVENT, event) {
+                        eprintln!("Failed to emit notification event: {}", e);
+                    }
+                }))
+                .await?;
+
+            let mut state = state.lock().unwrap();
+            if !state.connections.contains_key(&connection_id) {
+                return Err(DbError::ConnectionError(
+                    "Connection was closed".to_string(),
+                ));
+            }
+            // A concurrent call may have opened one first; keep that one
+            match open_listener(&state, &connection_id) {
+                Some(listener) => listener,
+                None => {
+                    let listener = Arc::new(listener);
+                    state
+                        .pg_listeners
+                        .insert(connection_id.clone(), Arc::clone(&listener));
+                    listener
+                }
+            }
+        }
+    };
+
+    listener.listen(&channel).await?;
+    Ok(listener.channels())
+}
+
+/// Unsubscribe a connection from a notification channel
+///
+/// Closes the dedicated session once no channels are left.
+///
+/// # Returns
+///
+/// The channels the connection is still subscribed to, sorted
+#[tauri::command]
+pub async fn unlisten_channel(
+    connection_id: String,
+    channel: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<String>, DbError> {
+    let Some(listener) = state
+        .lock()
+        .unwrap()
+        .pg_listeners
+        .get(&connection_id)
+        .cloned()
+    else {
+        return Ok(Vec::new());
+    };
+
+    if !listener.is_closed() {
+        listener.unlisten(&channel).await?;
+    }
+    let channels = if listener.is_closed() {
+        Vec::new()
+    } else {
+        listener.channels()
+    };
+
+    if channels.is_empty() {
+        let mut state = state.lock().unwrap();
+        if state
+            .pg_listeners
+            .get(&connection_id)
+            .is_some_and(|l| Arc::ptr_eq(l, &listener))
+        {
+            state.pg_listeners.remove(&connection_id);
+        }
+    }
+    Ok(channels)
+}
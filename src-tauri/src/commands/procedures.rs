@@ -5,6 +5,7 @@
 //! (SQLite, MongoDB) simply return an empty list so the UI can render an
 //! empty state without error.
 
+use std::collections::HashMap;
 use std::sync::Mutex;
 
 use serde::{Deserialize, Serialize};
@@ -13,6 +14,8 @@ use tauri::State;
 
 use crate::drivers::{DatabaseDriver, QueryResult};
 use crate::models::{DbDriver, DbError};
+use crate::sql::quote::quote_qualified;
+use crate::sql::tokenizer::Quoting;
 use crate::state::AppState;
 
 /// Metadata describing a single stored procedure or function.
@@ -37,7 +40,7 @@ fn resolve_driver(state: &AppState, connection_id: &str) -> Result<DbDriver, DbE
     Ok(profile.driver.clone())
 }
 
-fn take_connection(
+pub(super) fn take_connection(
     state: &State<'_, Mutex<AppState>>,
     connection_id: &str,
 ) -> Result<(std::sync::Arc<dyn DatabaseDriver>, DbDriver), DbError> {
@@ -50,7 +53,7 @@ fn take_connection(
     Ok((conn, driver))
 }
 
-fn as_string(v: &Value) -> Option<String> {
+pub(super) fn as_string(v: &Value) -> Option<String> {
     match v {
         Value::Null => None,
         Value::String(s) => {
@@ -166,36 +169,81 @@ pub async fn list_procedures(
             Ok(out)
         }
         DbDriver::SqlServer => {
-            let mut sql = String::from(
+            let schema_filter = schema
+                .as_ref()
+                .map(|s| {
+                    format!(
+                        " AND SCHEMA_NAME(o.schema_id) = '{}'",
+                        s.replace('\'', "''")
+                    )
+                })
+                .unwrap_or_default();
+
+            // One row per parameter, folded per routine below (STRING_AGG
+            // needs SQL Server 2017+). parameter_id 0 is a scalar function's
+            // return value.
+            let params_sql = format!(
+                r#"SELECT SCHEMA_NAME(o.schema_id), o.name, p.parameter_id, p.name,
+                          TYPE_NAME(p.user_type_id), p.is_output
+                   FROM sys.parameters p
+                   JOIN sys.objects o ON o.object_id = p.object_id
+                   WHERE o.type IN ('P','FN','IF','TF'){}
+                   ORDER BY o.object_id, p.parameter_id"#,
+                schema_filter
+            );
+            let mut params: HashMap<(String, String), (Vec<String>, Option<String>)> =
+                HashMap::new();
+            for row in conn.execute_query(&params_sql).await?.rows {
+                let key = (
+                    row.first().and_then(as_string).unwrap_or_default(),
+                    row.get(1).and_then(as_string).unwrap_or_default(),
+                );
+                let entry = params.entry(key).or_default();
+                let type_name = row.get(4).and_then(as_string).unwrap_or_default();
+                if row.get(2).and_then(Value::as_i64) == Some(0) {
+                    entry.1 = Some(type_name);
+                } else {
+                    let name = row.get(3).and_then(as_string).unwrap_or_default();
+                    let is_output = row.get(5).is_some_and(is_truthy);
+                    entry
+                        .0
+                        .push(sqlserver_parameter(&name, &type_name, is_output));
+                }
+            }
+
+            let sql = format!(
                 r#"SELECT SCHEMA_NAME(o.schema_id) AS schema_name,
                           o.name,
                           CASE WHEN o.type = 'P' THEN 'procedure' ELSE 'function' END,
-                          NULL,
-                          NULL,
-                          NULL
+                          o.type
                    FROM sys.objects o
-                   WHERE o.type IN ('P','FN','IF','TF')"#,
+                   WHERE o.type IN ('P','FN','IF','TF'){}
+                   ORDER BY schema_name, o.name"#,
+                schema_filter
             );
-            if let Some(s) = &schema {
-                sql.push_str(&format!(
-                    " AND SCHEMA_NAME(o.schema_id) = '{}'",
-                    s.replace('\'', "''")
-                ));
-            }
-            sql.push_str(" ORDER BY schema_name, o.name");
             let res = conn.execute_query(&sql).await?;
             let mut out = Vec::with_capacity(res.rows.len());
             for row in res.rows {
+                let schema = row.first().and_then(as_string).unwrap_or_default();
+                let name = row.get(1).and_then(as_string).unwrap_or_default();
+                let (args, scalar_return) = params
+                    .remove(&(schema.clone(), name.clone()))
+                    .unwrap_or_default();
+                // Inline and multi-statement table-valued functions return TABLE
+                let return_type = match row.get(3).and_then(as_string).as_deref().map(str::trim) {
+                    Some("IF") | Some("TF") => Some("TABLE".to_string()),
+                    _ => scalar_return,
+                };
                 out.push(ProcedureInfo {
-                    schema: row.first().and_then(as_string).unwrap_or_default(),
-                    name: row.get(1).and_then(as_string).unwrap_or_default(),
+                    schema,
+                    name,
                     kind: row
                         .get(2)
                         .and_then(as_string)
                         .unwrap_or_else(|| "procedure".to_string()),
                     language: None,
-                    return_type: None,
-                    argument_signature: "()".to_string(),
+                    return_type,
+                    argument_signature: format!("({})", args.join(", ")),
                 });
             }
             Ok(out)
@@ -244,7 +292,7 @@ pub async fn get_procedure_definition(
     match driver {
         DbDriver::MySql => {
             // Try SHOW CREATE PROCEDURE first, fall back to FUNCTION.
-            let qualified = format!("`{}`.`{}`", schema.replace('`', "``"), name.replace('`', "``"));
+            let qualified = quote_qualified(Some(&schema), &name, Quoting::MySql);
             let proc_sql = format!("SHOW CREATE PROCEDURE {}", qualified);
             if let Ok(res) = conn.execute_query(&proc_sql).await {
                 if let Some(row) = res.rows.into_iter().next() {
@@ -265,6 +313,27 @@ pub async fn get_procedure_definition(
                 .and_then(as_string)
                 .ok_or_else(|| DbError::QueryError("Empty routine definition".to_string()))
         }
+        DbDriver::SqlServer => {
+            // OBJECT_DEFINITION returns NULL both for unknown objects and for
+            // encrypted modules
+            let sql = format!(
+                "SELECT OBJECT_DEFINITION(OBJECT_ID(QUOTENAME('{}') + '.' + QUOTENAME('{}')))",
+                schema.replace('\'', "''"),
+                name.replace('\'', "''"),
+            );
+            let res = conn.execute_query(&sql).await?;
+            res.rows
+                .into_iter()
+                .next()
+                .and_then(|row| row.into_iter().next())
+                .and_then(|v| as_string(&v))
+                .ok_or_else(|| {
+                    DbError::NotFound(format!(
+                        "{}.{} not found or its definition is encrypted",
+                        schema, name
+                    ))
+                })
+        }
         _ => Err(DbError::QueryError(
             "Routine definition not supported for this driver".to_string(),
         )),
@@ -285,15 +354,16 @@ pub async fn execute_procedure(
     let rendered_args: Vec<String> = args.iter().map(render_arg).collect();
     let arg_list = rendered_args.join(", ");
 
+    let routine = quote_qualified(Some(&schema), &name, Quoting::for_driver(&driver));
     let sql = if driver.is_postgres_compatible() {
         // PG: plain functions are invoked via SELECT; procedures use CALL.
         // We default to SELECT here because list_procedures surfaces both and
         // CALL on a function errors with a clear message the user can act on.
-        format!("SELECT \"{}\".\"{}\"({})", schema, name, arg_list)
+        format!("SELECT {}({})", routine, arg_list)
     } else {
         match driver {
-            DbDriver::MySql => format!("CALL `{}`.`{}`({})", schema, name, arg_list),
-            DbDriver::SqlServer => format!("EXEC [{}].[{}] {}", schema, name, arg_list),
+            DbDriver::MySql => format!("CALL {}({})", routine, arg_list),
+            DbDriver::SqlServer => format!("EXEC {} {}", routine, arg_list),
             _ => {
                 return Err(DbError::QueryError(
                     "Executing routines is not supported for this driver".to_string(),
@@ -305,6 +375,24 @@ pub async fn execute_procedure(
     conn.execute_query(&sql).await
 }
 
+/// Render one SQL Server parameter for an argument signature, e.g. `@id int OUTPUT`
+fn sqlserver_parameter(name: &str, type_name: &str, is_output: bool) -> String {
+    let mut rendered = format!("{} {}", name, type_name);
+    if is_output {
+        rendered.push_str(" OUTPUT");
+    }
+    rendered
+}
+
+/// Whether a bit column came back set, as a bool or as 0/1
+fn is_truthy(v: &Value) -> bool {
+    match v {
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_i64().is_some_and(|n| n != 0),
+        _ => false,
+    }
+}
+
 /// Render a JSON value as a SQL literal suitable for inline argument lists.
 fn render_arg(v: &Value) -> String {
     match v {
@@ -316,3 +404,22 @@ fn render_arg(v: &Value) -> String {
         other => format!("'{}'", other.to_string().replace('\'', "''")),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_sqlserver_parameter() {
+        assert_eq!(sqlserver_parameter("@id", "int", false), "@id int");
+        assert_eq!(
+            sqlserver_parameter("@total", "decimal", true),
+            "@total decimal OUTPUT"
+        );
+        assert!(is_truthy(&json!(true)));
+        assert!(is_truthy(&json!(1)));
+        assert!(!is_truthy(&json!(0)));
+        assert!(!is_truthy(&Value::Null));
+    }
+}
@@ -0,0 +1,485 @@
+//! Connection profile import/export commands
+//!
+//! Moves connection profiles between machines as a portable JSON bundle.
+//! Passwords normally stay in the OS keyring; when explicitly requested they
+//! are included encrypted with a passphrase (see `credentials::passphrase`).
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+use crate::credentials::passphrase::{self, EncryptedPayload};
+use crate::credentials::CredentialManager;
+use crate::models::{ConnectionProfile, DbError};
+use crate::state::AppState;
+
+/// `format` value identifying a connection bundle
+const BUNDLE_FORMAT: &str = "dbhive-connections";
+
+/// Bundle layout version written by this build
+const BUNDLE_VERSION: u32 = 1;
+
+/// Shortest passphrase accepted for encrypting exported passwords
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Portable file holding connection profiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileBundle {
+    /// Always `dbhive-connections`
+    pub format: String,
+
+    /// Layout version of the bundle
+    pub version: u32,
+
+    /// Export timestamp (Unix timestamp in seconds)
+    pub exported_at: i64,
+
+    /// Exported profiles, without local keyring references or usage stats
+    pub profiles: Vec<ConnectionProfile>,
+
+    /// Passwords keyed by profile ID, encrypted as one JSON document;
+    /// absent unless passwords were requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passwords: Option<EncryptedPayload>,
+}
+
+/// Passwords of one exported profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileSecrets {
+    password: Option<String>,
+    ssh_password: Option<String>,
+}
+
+/// What to do when an imported profile has the ID of an existing one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictStrategy {
+    /// Keep the existing profile and ignore the imported one
+    Skip,
+    /// Replace the existing profile, keeping its ID
+    Overwrite,
+    /// Import under a new ID, with " (imported)" appended to the name
+    Rename,
+}
+
+/// Imported SSH tunnel whose private key file does not exist on this machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingSshKey {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub path: String,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileImportReport {
+    /// Profiles added as new profiles (including renamed ones)
+    pub imported: usize,
+
+    /// Existing profiles replaced by imported ones
+    pub overwritten: usize,
+
+    /// Imported profiles given a new ID because of a conflict
+    pub renamed: usize,
+
+    /// Imported profiles ignored because of a conflict
+    pub skipped: usize,
+
+    /// Profiles whose password was restored to the OS keyring
+    pub passwords_restored: usize,
+
+    /// The bundle has encrypted passwords but no passphrase was given, so
+    /// they were not imported
+    pub passwords_skipped: bool,
+
+    /// SSH private key paths that need fixing up on this machine
+    pub missing_ssh_keys: Vec<MissingSshKey>,
+}
+
+fn now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// Copy of `profile` fit for another machine
+///
+/// Keyring references only mean something locally, and usage stats belong
+/// to the person who used the connection.
+fn portable_profile(profile: &ConnectionProfile) -> ConnectionProfile {
+    let mut profile = profile.clone();
+    profile.password_keyring_key = None;
+    profile.last_connected_at = None;
+    profile.connection_count = 0;
+    if let Some(tunnel) = profile.ssh_tunnel.as_mut() {
+        tunnel.key_passphrase_keyring_key = None;
+    }
+    profile
+}
+
+/// Parse and validate a bundle
+fn parse_bundle(content: &str) -> Result<ProfileBundle, DbError> {
+    let bundle: ProfileBundle = serde_json::from_str(content)
+        .map_err(|e| DbError::ImportError(format!("Not a valid connection bundle: {}", e)))?;
+
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(DbError::ImportError(format!(
+            "Unexpected bundle format '{}'",
+            bundle.format
+        )));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(DbError::ImportError(format!(
+            "Bundle version {} was created by a newer version of DB Hive",
+            bundle.version
+        )));
+    }
+    if let Some(profile) = bundle
+        .profiles
+        .iter()
+        .find(|p| p.name.trim().is_empty() || p.host.trim().is_empty())
+    {
+        return Err(DbError::ImportError(format!(
+            "Profile '{}' is missing a name or host",
+            profile.id
+        )));
+    }
+
+    Ok(bundle)
+}
+
+/// First of "`name` (imported)", "`name` (imported 2)", ... not in `names`
+fn imported_name(name: &str, names: &HashSet<String>) -> String {
+    let mut candidate = format!("{} (imported)", name);
+    let mut n = 2;
+    while names.contains(&candidate) {
+        candidate = format!("{} (imported {})", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+/// Decide which bundle profiles to add, under which IDs
+///
+/// Returns `(bundle ID, profile to store)` pairs. Profiles repeated within
+/// the bundle conflict with each other just like with existing profiles.
+fn plan_import(
+    existing: &HashMap<String, ConnectionProfile>,
+    profiles: Vec<ConnectionProfile>,
+    strategy: ConflictStrategy,
+    report: &mut ProfileImportReport,
+) -> Vec<(String, ConnectionProfile)> {
+    let mut ids: HashSet<String> = existing.keys().cloned().collect();
+    let mut names: HashSet<String> = existing.values().map(|p| p.name.clone()).collect();
+    let mut planned: Vec<(String, ConnectionProfile)> = Vec::new();
+
+    for mut profile in profiles {
+        let bundle_id = profile.id.clone();
+        if profile.id.is_empty() {
+            profile.id = Uuid::new_v4().to_string();
+        }
+
+        if ids.contains(&profile.id) {
+            match strategy {
+                ConflictStrategy::Skip => {
+                    report.skipped += 1;
+                    continue;
+                }
+                ConflictStrategy::Overwrite => {
+                    report.overwritten += 1;
+                    planned.retain(|(_, p)| p.id != profile.id);
+                }
+                ConflictStrategy::Rename => {
+                    profile.id = Uuid::new_v4().to_string();
+                    profile.name = imported_name(&profile.name, &names);
+                    report.renamed += 1;
+                    report.imported += 1;
+                }
+            }
+        } else {
+            report.imported += 1;
+        }
+
+        profile.updated_at = now();
+        ids.insert(profile.id.clone());
+        names.insert(profile.name.clone());
+        planned.push((bundle_id, profile));
+    }
+
+    planned
+}
+
+/// Export connection profiles to a portable file
+///
+/// # Arguments
+///
+/// * `file_path` - Destination path of the JSON bundle
+/// * `profile_ids` - Profiles to export; all profiles when omitted
+/// * `include_passwords` - Include database and SSH passwords, encrypted
+///   with `passphrase`
+/// * `passphrase` - Required with `include_passwords`, at least 8 characters
+/// * `state` - Application state
+///
+/// # Returns
+///
+/// Number of exported profiles
+///
+/// # Example
+///
+/// ```typescript
+/// const count = await invoke<number>('export_connection_profiles', {
+///     filePath: '/home/me/connections.json',
+///     profileIds: ['conn-1', 'conn-2'],
+///     includePasswords: true,
+///     passphrase: 'share this separately'
+/// });
+/// ```
+#[tauri::command]
+pub fn export_connection_profiles(
+    file_path: String,
+    profile_ids: Option<Vec<String>>,
+    include_passwords: bool,
+    passphrase: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<usize, DbError> {
+    let passphrase = if include_passwords {
+        match passphrase {
+            Some(p) if p.chars().count() >= MIN_PASSPHRASE_LEN => Some(p),
+            _ => {
+                return Err(DbError::InvalidInput(format!(
+                    "Exporting passwords requires a passphrase of at least {} characters",
+                    MIN_PASSPHRASE_LEN
+                )))
+            }
+        }
+    } else {
+        None
+    };
+
+    let (profiles, cached_passwords) = {
+        let state_guard = state.lock().unwrap();
+        let profiles: Vec<ConnectionProfile> = match &profile_ids {
+            Some(ids) => ids
+                .iter()
+                .map(|id| {
+                    state_guard.get_profile(id).cloned().ok_or_else(|| {
+                        DbError::NotFound(format!("Profile with ID {} not found", id))
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+            None => state_guard.list_profiles().into_iter().cloned().collect(),
+        };
+        (profiles, state_guard.connection_passwords.clone())
+    };
+
+    let passwords = match passphrase {
+        Some(passphrase) => {
+            let mut secrets = HashMap::new();
+            for profile in &profiles {
+                let password = CredentialManager::get_password(&profile.id)?
+                    .or_else(|| cached_passwords.get(&profile.id).cloned());
+                let ssh_password = CredentialManager::get_ssh_password(&profile.id)?;
+                if password.is_some() || ssh_password.is_some() {
+                    secrets.insert(
+                        profile.id.clone(),
+                        ProfileSecrets {
+                            password,
+                            ssh_password,
+                        },
+                    );
+                }
+            }
+            let json = serde_json::to_vec(&secrets).map_err(|e| {
+                DbError::InternalError(format!("Failed to serialize passwords: {}", e))
+            })?;
+            Some(passphrase::encrypt(&json, &passphrase)?)
+        }
+        None => None,
+    };
+
+    let bundle = ProfileBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: now(),
+        profiles: profiles.iter().map(portable_profile).collect(),
+        passwords,
+    };
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| DbError::InternalError(format!("Failed to serialize profiles: {}", e)))?;
+    std::fs::write(&file_path, json)
+        .map_err(|e| DbError::InternalError(format!("Failed to write {}: {}", file_path, e)))?;
+
+    Ok(bundle.profiles.len())
+}
+
+/// Import connection profiles from a bundle written by
+/// `export_connection_profiles`
+///
+/// Profiles whose ID already exists are handled per `conflict_strategy`.
+/// Encrypted passwords are restored to the OS keyring when `passphrase` is
+/// given. SSH private key paths are kept as-is and reported in
+/// `missingSshKeys` when the file does not exist on this machine.
+///
+/// # Arguments
+///
+/// * `file_path` - Path of the JSON bundle
+/// * `conflict_strategy` - `skip`, `overwrite` or `rename`
+/// * `passphrase` - Passphrase the passwords were exported with
+/// * `state` - Application state
+/// * `app` - Application handle
+///
+/// # Errors
+///
+/// Returns `DbError::ImportError` for an unreadable or invalid bundle and
+/// `DbError::CredentialError` for a wrong passphrase. Nothing is imported in
+/// either case.
+#[tauri::command]
+pub fn import_connection_profiles(
+    file_path: String,
+    conflict_strategy: ConflictStrategy,
+    passphrase: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<ProfileImportReport, DbError> {
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| DbError::ImportError(format!("Failed to read {}: {}", file_path, e)))?;
+    let bundle = parse_bundle(&content)?;
+
+    let mut report = ProfileImportReport::default();
+    let secrets: HashMap<String, ProfileSecrets> = match (&bundle.passwords, passphrase) {
+        (Some(payload), Some(passphrase)) => {
+            let json = passphrase::decrypt(payload, &passphrase)?;
+            serde_json::from_slice(&json)
+                .map_err(|e| DbError::ImportError(format!("Invalid encrypted passwords: {}", e)))?
+        }
+        (Some(_), None) => {
+            report.passwords_skipped = true;
+            HashMap::new()
+        }
+        (None, _) => HashMap::new(),
+    };
+
+    let planned = {
+        let mut state_guard = state.lock().unwrap();
+        let planned = plan_import(
+            &state_guard.connection_profiles,
+            bundle.profiles,
+            conflict_strategy,
+            &mut report,
+        );
+        for (_, profile) in &planned {
+            state_guard.add_profile(profile.clone());
+        }
+        state_guard.save_profiles_to_store(&app)?;
+        planned
+    };
+
+    for (bundle_id, profile) in &planned {
+        let key_path = profile
+            .ssh_tunnel
+            .as_ref()
+            .and_then(|t| t.private_key_path.as_ref());
+        if let Some(path) = key_path.filter(|p| !Path::new(p).exists()) {
+            report.missing_ssh_keys.push(MissingSshKey {
+                profile_id: profile.id.clone(),
+                profile_name: profile.name.clone(),
+                path: path.clone(),
+            });
+        }
+
+        let Some(secret) = secrets.get(bundle_id) else {
+            continue;
+        };
+        if let Some(password) = &secret.password {
+            CredentialManager::save_password(&profile.id, password)?;
+            state
+                .lock()
+                .unwrap()
+                .connection_passwords
+                .insert(profile.id.clone(), password.clone());
+            report.passwords_restored += 1;
+        }
+        if let Some(ssh_password) = &secret.ssh_password {
+            CredentialManager::save_ssh_password(&profile.id, ssh_password)?;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DbDriver;
+
+    fn profile(id: &str, name: &str) -> ConnectionProfile {
+        ConnectionProfile::new(
+            id.to_string(),
+            name.to_string(),
+            DbDriver::Postgres,
+            "localhost".to_string(),
+            5432,
+            "postgres".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_parse_bundle_validates() {
+        let mut bundle = ProfileBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            exported_at: 0,
+            profiles: vec![portable_profile(&profile("a", "Local"))],
+            passwords: None,
+        };
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert_eq!(parse_bundle(&json).unwrap().profiles.len(), 1);
+
+        bundle.version = BUNDLE_VERSION + 1;
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(matches!(parse_bundle(&json), Err(DbError::ImportError(_))));
+        assert!(matches!(
+            parse_bundle(r#"{"profiles": []}"#),
+            Err(DbError::ImportError(_))
+        ));
+    }
+
+    #[test]
+    fn test_plan_import_strategies() {
+        let mut existing = HashMap::new();
+        existing.insert("a".to_string(), profile("a", "Prod"));
+        let incoming = || vec![profile("a", "Prod"), profile("b", "Dev")];
+
+        let mut report = ProfileImportReport::default();
+        let planned = plan_import(&existing, incoming(), ConflictStrategy::Skip, &mut report);
+        assert_eq!(planned.len(), 1);
+        assert_eq!((report.imported, report.skipped), (1, 1));
+
+        let mut report = ProfileImportReport::default();
+        let planned = plan_import(
+            &existing,
+            incoming(),
+            ConflictStrategy::Overwrite,
+            &mut report,
+        );
+        assert_eq!(planned[0].1.id, "a");
+        assert_eq!((report.imported, report.overwritten), (1, 1));
+
+        let mut report = ProfileImportReport::default();
+        let planned = plan_import(&existing, incoming(), ConflictStrategy::Rename, &mut report);
+        let (bundle_id, renamed) = &planned[0];
+        assert_eq!(bundle_id, "a");
+        assert_ne!(renamed.id, "a");
+        assert_eq!(renamed.name, "Prod (imported)");
+        assert_eq!((report.imported, report.renamed), (2, 1));
+    }
+}
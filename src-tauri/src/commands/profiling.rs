@@ -0,0 +1,913 @@
+//! Column profiling
+//!
+//! `profile_column` gives a quick picture of what one column holds: distinct
+//! and NULL counts, its range, the mean of a numeric column, the length range
+//! of a text column and its most frequent values. The aggregates run as one
+//! query over the table (or a random sample of it); if that query fails
+//! because an aggregate doesn't apply to the column's type (`MIN` over
+//! `json`, `COUNT(DISTINCT)` over `ntext`, ...), each statistic is retried on
+//! its own and the ones that still fail are reported as skipped.
+//!
+//! `estimate_cardinality` answers the narrower question indexing advice
+//! needs: roughly how many distinct values each column holds, relative to
+//! the table's rows. It reads the engine's planner statistics where they
+//! exist and only counts distinct values in a small sample otherwise.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::State;
+
+use super::procedures::take_connection;
+use super::schema::cached_table_schema;
+use super::table_data::qualified_table;
+use crate::drivers::DatabaseDriver;
+use crate::models::{DbDriver, DbError};
+use crate::sql::literal::escape_literal;
+use crate::state::AppState;
+
+/// Most frequent values returned when the caller doesn't ask for a number
+const DEFAULT_TOP_N: u32 = 10;
+
+/// Rows read for the `COUNT(DISTINCT)` fallback of `estimate_cardinality`
+const DEFAULT_CARDINALITY_SAMPLE: u64 = 10_000;
+
+/// A value and how many rows hold it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueFrequency {
+    pub value: Value,
+    pub count: u64,
+}
+
+/// A statistic that applies to the column's type but couldn't be computed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedStatistic {
+    /// `distinctCount`, `minMax`, `mean`, `length` or `topValues`
+    pub statistic: String,
+    /// Error the database returned
+    pub reason: String,
+}
+
+/// Profile of one column
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnProfile {
+    /// Rows profiled: all rows of the table, or the sample
+    pub row_count: u64,
+    /// Whether only a random sample of the rows was profiled
+    pub sampled: bool,
+    pub null_count: u64,
+    /// `null_count / row_count`, 0 for an empty table
+    pub null_ratio: f64,
+    pub distinct_count: Option<u64>,
+    pub min: Option<Value>,
+    pub max: Option<Value>,
+    /// Mean, for numeric columns
+    pub mean: Option<f64>,
+    /// Shortest value in characters, for text columns
+    pub min_length: Option<u64>,
+    /// Longest value in characters, for text columns
+    pub max_length: Option<u64>,
+    /// Most frequent non-NULL values, most frequent first
+    pub top_values: Vec<ValueFrequency>,
+    pub skipped: Vec<SkippedStatistic>,
+}
+
+/// Where a distinct-count estimate came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CardinalitySource {
+    /// The engine's statistics: `pg_stats`, MySQL index statistics or SQL
+    /// Server's statistics density vector
+    Statistics,
+    /// `COUNT(DISTINCT)` over a sample of the rows
+    Sample,
+}
+
+/// Estimated number of distinct values in one column
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnCardinality {
+    pub column: String,
+    /// Approximate distinct non-NULL values; `None` if they couldn't be
+    /// counted (e.g. `COUNT(DISTINCT)` over a `text` column on SQL Server)
+    pub distinct_count: Option<u64>,
+    /// `distinct_count / total_rows`: near 1 for a unique column, near 0 for
+    /// one with few values
+    pub selectivity: Option<f64>,
+    pub source: CardinalitySource,
+}
+
+/// Result of `estimate_cardinality`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardinalityEstimate {
+    /// Rows in the table
+    pub total_rows: u64,
+    /// Whether `total_rows` comes from statistics rather than a `COUNT(*)`
+    pub total_rows_estimated: bool,
+    /// One entry per requested column, in request order
+    pub columns: Vec<ColumnCardinality>,
+}
+
+/// Which type-specific statistics apply to a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Numeric,
+    Text,
+    Other,
+}
+
+impl ColumnKind {
+    /// Classify a column from its declared type (`VARCHAR(255)`, `int4`,
+    /// `double precision`, `int unsigned`, ...)
+    fn classify(data_type: &str) -> Self {
+        let data_type = data_type.to_ascii_lowercase();
+        if data_type.starts_with('_') || data_type.ends_with("[]") {
+            return ColumnKind::Other;
+        }
+        let base = data_type
+            .split(['(', ' '])
+            .next()
+            .unwrap_or_default()
+            .trim();
+        let numeric = matches!(
+            base,
+            "int"
+                | "integer"
+                | "int2"
+                | "int4"
+                | "int8"
+                | "tinyint"
+                | "smallint"
+                | "mediumint"
+                | "bigint"
+                | "serial"
+                | "bigserial"
+                | "smallserial"
+                | "numeric"
+                | "decimal"
+                | "dec"
+                | "real"
+                | "double"
+                | "money"
+                | "smallmoney"
+                | "number"
+        ) || base.starts_with("float");
+        let text = matches!(
+            base,
+            "char"
+                | "character"
+                | "varchar"
+                | "varchar2"
+                | "nchar"
+                | "nvarchar"
+                | "bpchar"
+                | "citext"
+                | "name"
+                | "string"
+                | "clob"
+        ) || base.ends_with("text");
+
+        if numeric {
+            ColumnKind::Numeric
+        } else if text {
+            ColumnKind::Text
+        } else {
+            ColumnKind::Other
+        }
+    }
+}
+
+/// Aggregates computed together; `v` is the profiled column
+struct Statistic {
+    name: &'static str,
+    exprs: Vec<String>,
+}
+
+/// The statistics to compute for a column, the row counts first
+fn statistics(driver: &DbDriver, kind: ColumnKind) -> Vec<Statistic> {
+    let stat = |name, exprs: &[&str]| Statistic {
+        name,
+        exprs: exprs.iter().map(|e| e.to_string()).collect(),
+    };
+    let mut statistics = vec![
+        stat("rows", &["COUNT(*)", "COUNT(v)"]),
+        stat("distinctCount", &["COUNT(DISTINCT v)"]),
+        stat("minMax", &["MIN(v)", "MAX(v)"]),
+    ];
+    match kind {
+        ColumnKind::Numeric => statistics.push(stat(
+            "mean",
+            &[match driver {
+                DbDriver::SqlServer => "AVG(CAST(v AS FLOAT))",
+                DbDriver::MySql | DbDriver::Sqlite | DbDriver::Turso => "AVG(v)",
+                _ => "AVG(CAST(v AS DOUBLE PRECISION))",
+            }],
+        )),
+        ColumnKind::Text => {
+            let length = match driver {
+                DbDriver::SqlServer => "LEN(v)",
+                DbDriver::Sqlite | DbDriver::Turso => "LENGTH(v)",
+                _ => "CHAR_LENGTH(v)",
+            };
+            statistics.push(Statistic {
+                name: "length",
+                exprs: vec![format!("MIN({})", length), format!("MAX({})", length)],
+            });
+        }
+        ColumnKind::Other => {}
+    }
+    statistics
+}
+
+/// Derived table `p` with the column as `v`, over a random sample of
+/// `sample_size` rows if one is given
+fn profile_source(
+    driver: &DbDriver,
+    table: &str,
+    column: &str,
+    sample_size: Option<u64>,
+) -> String {
+    match (driver, sample_size) {
+        (_, None) => format!("(SELECT {} AS v FROM {}) p", column, table),
+        (DbDriver::SqlServer, Some(n)) => format!(
+            "(SELECT TOP ({}) {} AS v FROM {} ORDER BY NEWID()) p",
+            n, column, table
+        ),
+        (DbDriver::MySql, Some(n)) => format!(
+            "(SELECT {} AS v FROM {} ORDER BY RAND() LIMIT {}) p",
+            column, table, n
+        ),
+        (_, Some(n)) => format!(
+            "(SELECT {} AS v FROM {} ORDER BY RANDOM() LIMIT {}) p",
+            column, table, n
+        ),
+    }
+}
+
+/// The `top_n` most frequent non-NULL values of the profiled column
+fn top_values_sql(driver: &DbDriver, source: &str, top_n: u32) -> String {
+    match driver {
+        DbDriver::SqlServer => format!(
+            "SELECT TOP ({}) v, COUNT(*) AS freq FROM {} WHERE v IS NOT NULL GROUP BY v ORDER BY COUNT(*) DESC",
+            top_n, source
+        ),
+        _ => format!(
+            "SELECT v, COUNT(*) AS freq FROM {} WHERE v IS NOT NULL GROUP BY v ORDER BY COUNT(*) DESC LIMIT {}",
+            source, top_n
+        ),
+    }
+}
+
+fn as_u64(v: &Value) -> Option<u64> {
+    match v {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_f64(v: &Value) -> Option<f64> {
+    match v {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+async fn first_row(conn: &dyn DatabaseDriver, sql: &str) -> Result<Vec<Value>, DbError> {
+    conn.execute_query(sql)
+        .await?
+        .rows
+        .into_iter()
+        .next()
+        .ok_or_else(|| DbError::QueryError("Profiling query returned no rows".to_string()))
+}
+
+/// Profile one column of a table
+///
+/// `sample_size` limits the profile to that many randomly chosen rows (the
+/// random ordering still reads the whole table, but the aggregates and the
+/// grouping for `top_values` only see the sample). `top_n` defaults to 10.
+#[tauri::command]
+pub async fn profile_column(
+    connection_id: String,
+    schema: String,
+    table: String,
+    column: String,
+    sample_size: Option<u64>,
+    top_n: Option<u32>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ColumnProfile, DbError> {
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+    if matches!(driver, DbDriver::MongoDb | DbDriver::Redis) {
+        return Err(DbError::InvalidInput(
+            "Column profiling is only supported for SQL databases".to_string(),
+        ));
+    }
+
+    let table_schema = conn.get_table_schema(&schema, &table).await?;
+    let info = table_schema
+        .columns
+        .iter()
+        .find(|c| c.name == column)
+        .ok_or_else(|| {
+            DbError::NotFound(format!(
+                "Column {} not found in {}.{}",
+                column, schema, table
+            ))
+        })?;
+
+    let sample_size = sample_size.filter(|n| *n > 0);
+    let source = profile_source(
+        &driver,
+        &qualified_table(conn.as_ref(), &schema, &table),
+        &conn.quote_identifier(&column),
+        sample_size,
+    );
+    let statistics = statistics(&driver, ColumnKind::classify(&info.data_type));
+    let mut profile = ColumnProfile {
+        sampled: sample_size.is_some(),
+        ..Default::default()
+    };
+
+    let exprs: Vec<&str> = statistics
+        .iter()
+        .flat_map(|s| s.exprs.iter().map(String::as_str))
+        .collect();
+    let combined = format!("SELECT {} FROM {}", exprs.join(", "), source);
+    let row = match first_row(conn.as_ref(), &combined).await {
+        Ok(row) => row,
+        Err(_) => {
+            let mut row = Vec::with_capacity(exprs.len());
+            for (i, statistic) in statistics.iter().enumerate() {
+                let sql = format!("SELECT {} FROM {}", statistic.exprs.join(", "), source);
+                match first_row(conn.as_ref(), &sql).await {
+                    Ok(values) => row.extend(values),
+                    // Row counts work for any column type, so this is a
+                    // real error (missing permission, dropped table, ...)
+                    Err(e) if i == 0 => return Err(e),
+                    Err(e) => {
+                        profile.skipped.push(SkippedStatistic {
+                            statistic: statistic.name.to_string(),
+                            reason: e.to_string(),
+                        });
+                        row.extend(std::iter::repeat(Value::Null).take(statistic.exprs.len()));
+                    }
+                }
+            }
+            row
+        }
+    };
+
+    let mut values = row.into_iter();
+    for statistic in &statistics {
+        let v: Vec<Value> = values.by_ref().take(statistic.exprs.len()).collect();
+        let non_null = |i: usize| v.get(i).filter(|v| !v.is_null()).cloned();
+        match statistic.name {
+            "rows" => {
+                profile.row_count = v.first().and_then(as_u64).unwrap_or(0);
+                let non_null_count = v.get(1).and_then(as_u64).unwrap_or(0);
+                profile.null_count = profile.row_count.saturating_sub(non_null_count);
+            }
+            "distinctCount" => profile.distinct_count = v.first().and_then(as_u64),
+            "minMax" => {
+                profile.min = non_null(0);
+                profile.max = non_null(1);
+            }
+            "mean" => profile.mean = v.first().and_then(as_f64),
+            "length" => {
+                profile.min_length = v.first().and_then(as_u64);
+                profile.max_length = v.get(1).and_then(as_u64);
+            }
+            _ => {}
+        }
+    }
+    if profile.row_count > 0 {
+        profile.null_ratio = profile.null_count as f64 / profile.row_count as f64;
+    }
+
+    let top_sql = top_values_sql(&driver, &source, top_n.unwrap_or(DEFAULT_TOP_N));
+    match conn.execute_query(&top_sql).await {
+        Ok(result) => {
+            profile.top_values = result
+                .rows
+                .into_iter()
+                .map(|mut row| ValueFrequency {
+                    count: row.get(1).and_then(as_u64).unwrap_or(0),
+                    value: row.swap_remove(0),
+                })
+                .collect();
+        }
+        Err(e) => profile.skipped.push(SkippedStatistic {
+            statistic: "topValues".to_string(),
+            reason: e.to_string(),
+        }),
+    }
+
+    Ok(profile)
+}
+
+/// Table row count from the engine's statistics, without scanning the table
+///
+/// `None` where there are none: SQLite, or a PostgreSQL table that was never
+/// vacuumed or analyzed (see [`pg_row_estimate`]).
+async fn estimated_row_count(
+    conn: &dyn DatabaseDriver,
+    driver: &DbDriver,
+    schema: &str,
+    table: &str,
+    full_table: &str,
+) -> Option<u64> {
+    let schema_lit = escape_literal(schema, driver);
+    let table_lit = escape_literal(table, driver);
+    let sql = match driver {
+        DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon => format!(
+            "SELECT c.reltuples::bigint, c.relpages FROM pg_class c \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = {} AND c.relname = {}",
+            schema_lit, table_lit
+        ),
+        DbDriver::MySql => format!(
+            "SELECT TABLE_ROWS FROM information_schema.TABLES \
+             WHERE TABLE_SCHEMA = {} AND TABLE_NAME = {}",
+            schema_lit, table_lit
+        ),
+        DbDriver::SqlServer => format!(
+            "SELECT SUM(rows) FROM sys.partitions \
+             WHERE object_id = OBJECT_ID({}) AND index_id IN (0, 1)",
+            escape_literal(full_table, driver)
+        ),
+        _ => return None,
+    };
+    let row = first_row(conn, &sql).await.ok()?;
+    if driver.is_postgres_compatible() {
+        return pg_row_estimate(row.first()?, row.get(1)?);
+    }
+    row.first().and_then(as_u64)
+}
+
+/// Row estimate from `pg_class.reltuples` and `relpages`
+///
+/// A table that was never vacuumed or analyzed has `reltuples = -1` on
+/// PostgreSQL 14 and later, but `0` with no pages before that, which looks
+/// the same as an empty table. Both are taken as no statistics, so the
+/// caller counts the rows instead.
+fn pg_row_estimate(reltuples: &Value, relpages: &Value) -> Option<u64> {
+    if as_f64(reltuples)? <= 0.0 && as_u64(relpages)? == 0 {
+        return None;
+    }
+    as_u64(reltuples)
+}
+
+/// Distinct counts the engine's statistics hold for columns of the table,
+/// by column name
+///
+/// Statistics that can't be read (missing permission, no statistics yet)
+/// leave the column to the sampled fallback.
+async fn statistics_distinct_counts(
+    conn: &dyn DatabaseDriver,
+    driver: &DbDriver,
+    schema: &str,
+    table: &str,
+    full_table: &str,
+    total_rows: u64,
+) -> HashMap<String, u64> {
+    let schema_lit = escape_literal(schema, driver);
+    let table_lit = escape_literal(table, driver);
+    let mut counts = HashMap::new();
+    match driver {
+        DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon => {
+            let sql = format!(
+                "SELECT attname, n_distinct FROM pg_stats \
+                 WHERE schemaname = {} AND tablename = {} AND NOT inherited",
+                schema_lit, table_lit
+            );
+            for row in conn
+                .execute_query(&sql)
+                .await
+                .map(|r| r.rows)
+                .unwrap_or_default()
+            {
+                let name = row.first().and_then(Value::as_str);
+                let n_distinct = row.get(1).and_then(as_f64);
+                if let (Some(name), Some(n_distinct)) = (name, n_distinct) {
+                    counts.insert(name.to_string(), from_n_distinct(n_distinct, total_rows));
+                }
+            }
+        }
+        DbDriver::MySql => {
+            // Only the leading column of an index has its own cardinality
+            let sql = format!(
+                "SELECT COLUMN_NAME, MAX(CARDINALITY) FROM information_schema.STATISTICS \
+                 WHERE TABLE_SCHEMA = {} AND TABLE_NAME = {} AND SEQ_IN_INDEX = 1 \
+                 GROUP BY COLUMN_NAME",
+                schema_lit, table_lit
+            );
+            for row in conn
+                .execute_query(&sql)
+                .await
+                .map(|r| r.rows)
+                .unwrap_or_default()
+            {
+                let name = row.first().and_then(Value::as_str);
+                if let (Some(name), Some(count)) = (name, row.get(1).and_then(as_u64)) {
+                    counts.insert(name.to_string(), count);
+                }
+            }
+        }
+        DbDriver::SqlServer => {
+            let object = escape_literal(full_table, driver);
+            let sql = format!(
+                "SELECT c.name, s.name FROM sys.stats s \
+                 JOIN sys.stats_columns sc ON sc.object_id = s.object_id \
+                 AND sc.stats_id = s.stats_id AND sc.stats_column_id = 1 \
+                 JOIN sys.columns c ON c.object_id = sc.object_id AND c.column_id = sc.column_id \
+                 WHERE s.object_id = OBJECT_ID({})",
+                object
+            );
+            let stats = conn
+                .execute_query(&sql)
+                .await
+                .map(|r| r.rows)
+                .unwrap_or_default();
+            for row in stats {
+                let (Some(column), Some(stat)) = (
+                    row.first().and_then(Value::as_str),
+                    row.get(1).and_then(Value::as_str),
+                ) else {
+                    continue;
+                };
+                if counts.contains_key(column) {
+                    continue;
+                }
+                // The first density row covers the leading column alone
+                let sql = format!(
+                    "DBCC SHOW_STATISTICS ({}, {}) WITH DENSITY_VECTOR, NO_INFOMSGS",
+                    object,
+                    conn.quote_identifier(stat)
+                );
+                let density = first_row(conn, &sql)
+                    .await
+                    .ok()
+                    .and_then(|row| row.first().and_then(as_f64))
+                    .filter(|density| *density > 0.0);
+                if let Some(density) = density {
+                    counts.insert(column.to_string(), (1.0 / density).round() as u64);
+                }
+            }
+        }
+        _ => {}
+    }
+    counts
+}
+
+/// Distinct count from `pg_stats.n_distinct`, which is a count when
+/// positive and minus the fraction of rows when negative
+fn from_n_distinct(n_distinct: f64, total_rows: u64) -> u64 {
+    if n_distinct < 0.0 {
+        (-n_distinct * total_rows as f64).round() as u64
+    } else {
+        n_distinct.round() as u64
+    }
+}
+
+/// `COUNT(*)`, then `COUNT(DISTINCT c), COUNT(c)` for each (quoted) column,
+/// over the first `sample_size` rows of `table`
+///
+/// Taking the first rows rather than a random sample avoids reading the
+/// whole table, at the cost of skew when values are clustered on disk.
+fn sample_distinct_sql(
+    driver: &DbDriver,
+    table: &str,
+    columns: &[String],
+    sample_size: u64,
+) -> String {
+    let source = match driver {
+        DbDriver::SqlServer => format!("(SELECT TOP ({}) * FROM {}) s", sample_size, table),
+        _ => format!("(SELECT * FROM {} LIMIT {}) s", table, sample_size),
+    };
+    let mut exprs = vec!["COUNT(*)".to_string()];
+    for column in columns {
+        exprs.push(format!("COUNT(DISTINCT {})", column));
+        exprs.push(format!("COUNT({})", column));
+    }
+    format!("SELECT {} FROM {}", exprs.join(", "), source)
+}
+
+/// Scale a distinct count found in a sample of `sample_rows` rows to a
+/// table of `total_rows`
+///
+/// A column whose sampled values were all different is taken to be unique
+/// and scaled with the table; otherwise the sample is assumed to have seen
+/// (nearly) every value already.
+fn scale_sample_distinct(distinct: u64, non_null: u64, sample_rows: u64, total_rows: u64) -> u64 {
+    if distinct > 0 && distinct == non_null && sample_rows < total_rows {
+        (distinct as f64 * total_rows as f64 / sample_rows as f64).round() as u64
+    } else {
+        distinct
+    }
+}
+
+/// Estimate how many distinct values columns hold, for indexing advice
+///
+/// Uses statistics the engine already keeps: `pg_stats.n_distinct` on
+/// PostgreSQL, the cardinality of indexes led by the column on MySQL, and
+/// the density vector of `DBCC SHOW_STATISTICS` on SQL Server. Columns
+/// without statistics (and every column on SQLite) fall back to
+/// `COUNT(DISTINCT)` over the first `sample_size` rows, 10,000 by default.
+/// The total row count likewise comes from statistics, with a `COUNT(*)`
+/// only when there are none.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table to estimate
+/// * `columns` - Columns to estimate; every column of the table when empty
+/// * `sample_size` - Rows read by the sampled fallback
+/// * `state` - Application state containing active connections
+///
+/// # Errors
+///
+/// Returns `DbError::NotFound` if a column isn't part of the table, and
+/// `DbError::InvalidInput` for document and key-value stores.
+///
+/// # Example
+///
+/// ```typescript
+/// const estimate = await invoke<CardinalityEstimate>('estimate_cardinality', {
+///     connectionId: 'conn-123',
+///     schema: 'public',
+///     table: 'orders',
+///     columns: ['status', 'customer_id'],
+/// });
+/// // { totalRows: 1200000, totalRowsEstimated: true, columns: [
+/// //     { column: 'status', distinctCount: 5, selectivity: 0.0000042, source: 'statistics' }, ...
+/// // ] }
+/// ```
+#[tauri::command]
+pub async fn estimate_cardinality(
+    connection_id: String,
+    schema: String,
+    table: String,
+    columns: Vec<String>,
+    sample_size: Option<u64>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<CardinalityEstimate, DbError> {
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+    if matches!(driver, DbDriver::MongoDb | DbDriver::Redis) {
+        return Err(DbError::InvalidInput(
+            "Cardinality estimates are only supported for SQL databases".to_string(),
+        ));
+    }
+
+    let table_schema = cached_table_schema(&state, &connection_id, &schema, &table, None).await?;
+    let columns = if columns.is_empty() {
+        table_schema
+            .columns
+            .iter()
+            .map(|c| c.name.clone())
+            .collect()
+    } else {
+        if let Some(missing) = columns
+            .iter()
+            .find(|column| !table_schema.columns.iter().any(|c| &c.name == *column))
+        {
+            return Err(DbError::NotFound(format!(
+                "Column {} not found in {}.{}",
+                missing, schema, table
+            )));
+        }
+        columns
+    };
+
+    let full_table = qualified_table(conn.as_ref(), &schema, &table);
+    let estimated = estimated_row_count(conn.as_ref(), &driver, &schema, &table, &full_table).await;
+    let total_rows = match estimated {
+        Some(rows) => rows,
+        None => {
+            let sql = format!("SELECT COUNT(*) FROM {}", full_table);
+            first_row(conn.as_ref(), &sql)
+                .await?
+                .first()
+                .and_then(as_u64)
+                .unwrap_or(0)
+        }
+    };
+
+    let statistics = statistics_distinct_counts(
+        conn.as_ref(),
+        &driver,
+        &schema,
+        &table,
+        &full_table,
+        total_rows,
+    )
+    .await;
+
+    // Sample the remaining columns together, or one by one if a column's
+    // type can't be counted (COUNT(DISTINCT) over ntext, json, ...)
+    let unsampled: Vec<&String> = columns
+        .iter()
+        .filter(|c| !statistics.contains_key(*c))
+        .collect();
+    let sample_size = sample_size
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_CARDINALITY_SAMPLE);
+    let mut sampled: HashMap<&str, u64> = HashMap::new();
+    if !unsampled.is_empty() {
+        let quoted: Vec<String> = unsampled.iter().map(|c| conn.quote_identifier(c)).collect();
+        let sql = sample_distinct_sql(&driver, &full_table, &quoted, sample_size);
+        let rows = match first_row(conn.as_ref(), &sql).await {
+            Ok(row) => vec![(unsampled.clone(), row)],
+            Err(_) => {
+                let mut rows = Vec::new();
+                for (column, quoted) in unsampled.iter().zip(&quoted) {
+                    let sql = sample_distinct_sql(
+                        &driver,
+                        &full_table,
+                        std::slice::from_ref(quoted),
+                        sample_size,
+                    );
+                    if let Ok(row) = first_row(conn.as_ref(), &sql).await {
+                        rows.push((vec![*column], row));
+                    }
+                }
+                rows
+            }
+        };
+        for (columns, row) in rows {
+            let sample_rows = row.first().and_then(as_u64).unwrap_or(0);
+            for (i, column) in columns.into_iter().enumerate() {
+                let distinct = row.get(1 + 2 * i).and_then(as_u64);
+                let non_null = row.get(2 + 2 * i).and_then(as_u64).unwrap_or(0);
+                if let Some(distinct) = distinct {
+                    let estimate =
+                        scale_sample_distinct(distinct, non_null, sample_rows, total_rows);
+                    sampled.insert(column.as_str(), estimate);
+                }
+            }
+        }
+    }
+
+    let columns = columns
+        .iter()
+        .map(|column| {
+            let (distinct_count, source) = match statistics.get(column) {
+                Some(count) => (Some(*count), CardinalitySource::Statistics),
+                None => (
+                    sampled.get(column.as_str()).copied(),
+                    CardinalitySource::Sample,
+                ),
+            };
+            // Statistics can lag behind the table; never report more
+            // distinct values than rows
+            let distinct_count = distinct_count.map(|n| n.min(total_rows));
+            ColumnCardinality {
+                column: column.clone(),
+                distinct_count,
+                selectivity: distinct_count
+                    .filter(|_| total_rows > 0)
+                    .map(|n| n as f64 / total_rows as f64),
+                source,
+            }
+        })
+        .collect();
+
+    Ok(CardinalityEstimate {
+        total_rows,
+        total_rows_estimated: estimated.is_some(),
+        columns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_classify_column_types() {
+        for numeric in [
+            "integer",
+            "int4",
+            "BIGINT",
+            "int unsigned",
+            "numeric(10,2)",
+            "double precision",
+            "float8",
+            "DECIMAL(18,4)",
+        ] {
+            assert_eq!(
+                ColumnKind::classify(numeric),
+                ColumnKind::Numeric,
+                "{}",
+                numeric
+            );
+        }
+        for text in [
+            "VARCHAR(255)",
+            "character varying",
+            "text",
+            "NVARCHAR(MAX)",
+            "longtext",
+        ] {
+            assert_eq!(ColumnKind::classify(text), ColumnKind::Text, "{}", text);
+        }
+        for other in [
+            "interval",
+            "point",
+            "timestamp",
+            "jsonb",
+            "_int4",
+            "integer[]",
+            "bool",
+        ] {
+            assert_eq!(ColumnKind::classify(other), ColumnKind::Other, "{}", other);
+        }
+    }
+
+    #[test]
+    fn test_profile_sql_per_dialect() {
+        let source = profile_source(&DbDriver::SqlServer, "[dbo].[t]", "[name]", Some(500));
+        assert_eq!(
+            source,
+            "(SELECT TOP (500) [name] AS v FROM [dbo].[t] ORDER BY NEWID()) p"
+        );
+        assert_eq!(
+            top_values_sql(&DbDriver::SqlServer, &source, 5),
+            "SELECT TOP (5) v, COUNT(*) AS freq FROM (SELECT TOP (500) [name] AS v FROM [dbo].[t] ORDER BY NEWID()) p WHERE v IS NOT NULL GROUP BY v ORDER BY COUNT(*) DESC"
+        );
+        assert_eq!(
+            profile_source(&DbDriver::Postgres, "\"public\".\"t\"", "\"n\"", None),
+            "(SELECT \"n\" AS v FROM \"public\".\"t\") p"
+        );
+
+        let names = |driver, kind| -> Vec<String> {
+            statistics(&driver, kind)
+                .into_iter()
+                .flat_map(|s| s.exprs)
+                .collect()
+        };
+        assert_eq!(
+            names(DbDriver::Sqlite, ColumnKind::Text),
+            [
+                "COUNT(*)",
+                "COUNT(v)",
+                "COUNT(DISTINCT v)",
+                "MIN(v)",
+                "MAX(v)",
+                "MIN(LENGTH(v))",
+                "MAX(LENGTH(v))"
+            ]
+        );
+        assert_eq!(
+            names(DbDriver::Postgres, ColumnKind::Numeric)
+                .last()
+                .unwrap(),
+            "AVG(CAST(v AS DOUBLE PRECISION))"
+        );
+    }
+
+    #[test]
+    fn test_pg_row_estimate() {
+        assert_eq!(pg_row_estimate(&json!(1200), &json!(15)), Some(1200));
+        // Never analyzed: -1 on PostgreSQL 14+, 0 with no pages before it
+        assert_eq!(pg_row_estimate(&json!(-1), &json!(0)), None);
+        assert_eq!(pg_row_estimate(&json!(0), &json!(0)), None);
+        // Analyzed and emptied, but its pages not yet reclaimed
+        assert_eq!(pg_row_estimate(&json!(0), &json!(3)), Some(0));
+        assert_eq!(pg_row_estimate(&Value::Null, &json!(3)), None);
+    }
+
+    #[test]
+    fn test_cardinality_helpers() {
+        assert_eq!(from_n_distinct(42.0, 1000), 42);
+        assert_eq!(from_n_distinct(-1.0, 1000), 1000);
+        assert_eq!(from_n_distinct(-0.25, 1000), 250);
+
+        // All sampled values differ: treated as unique and scaled up
+        assert_eq!(scale_sample_distinct(100, 100, 100, 5000), 5000);
+        // Repeats in the sample: the sample is taken as having seen them all
+        assert_eq!(scale_sample_distinct(7, 100, 100, 5000), 7);
+        // The whole table was sampled
+        assert_eq!(scale_sample_distinct(50, 50, 50, 50), 50);
+
+        let columns = vec!["[a]".to_string(), "[b]".to_string()];
+        assert_eq!(
+            sample_distinct_sql(&DbDriver::SqlServer, "[dbo].[t]", &columns, 100),
+            "SELECT COUNT(*), COUNT(DISTINCT [a]), COUNT([a]), COUNT(DISTINCT [b]), COUNT([b]) \
+             FROM (SELECT TOP (100) * FROM [dbo].[t]) s"
+        );
+        assert_eq!(
+            sample_distinct_sql(&DbDriver::Sqlite, "\"main\".\"t\"", &columns[..1], 10),
+            "SELECT COUNT(*), COUNT(DISTINCT [a]), COUNT([a]) FROM (SELECT * FROM \"main\".\"t\" LIMIT 10) s"
+        );
+    }
+}
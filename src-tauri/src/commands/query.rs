@@ -4,14 +4,30 @@
 //! active database connections. It handles query execution, timing, and
 //! result formatting.
 
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use tauri::State;
+use tauri::{AppHandle, State};
 use uuid::Uuid;
 
-use crate::drivers::MAX_RESULT_ROWS;
-use crate::models::{DbError, QueryLog};
-use crate::state::AppState;
+use super::export::ResultFormatOptions;
+use super::reconnect::{
+    is_connection_lost, retry_after_reconnect, track_transaction, transaction_control,
+    TransactionControl,
+};
+use super::settings::load_settings;
+use crate::drivers::blob::{preview_blob, BLOB_PREVIEW_BYTES};
+use crate::drivers::{unique_column_names, AggregateOptions, DatabaseDriver, MAX_RESULT_ROWS};
+use crate::models::{DbDriver, DbError, Environment, QueryLog, RunningQuery};
+use crate::sql::affected::{plan_affected_rows, AffectedRowsPlan};
+use crate::sql::filter::FilterNode;
+use crate::sql::limit::apply_row_limit;
+use crate::sql::literal::escape_literal;
+use crate::sql::normalize::compact_query_with;
+use crate::sql::params::{bind_named_parameters, QueryParam};
+use crate::sql::tables::referenced_tables;
+use crate::sql::tokenizer::{executed_verbs, statement_verbs, tokenize_with, Quoting, TokenKind};
+use crate::state::{AppState, QueryPriority, QueueDepth};
 use serde::{Deserialize, Serialize};
 
 /// Result of a query execution
@@ -26,12 +42,19 @@ use serde::{Deserialize, Serialize};
 /// * `rows_affected` - Number of rows affected (for INSERT/UPDATE/DELETE)
 /// * `execution_time` - Time taken to execute the query in milliseconds
 /// * `query_type` - The type of query derived from the first SQL keyword (e.g. "SELECT", "INSERT")
+/// * `limited` - The default row limit was added to the query and reached
+/// * `from_cache` - The result was served from the query result cache
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryExecutionResult {
-    /// Column names in the result set
+    /// Column names in the result set, made unique: a repeated name gets a
+    /// `_2`, `_3`, ... suffix (see `unique_column_names`)
     pub columns: Vec<String>,
 
+    /// Column names as the database returned them, parallel to `columns`
+    #[serde(default)]
+    pub original_columns: Vec<String>,
+
     /// Rows of data, each row is a vector of JSON values
     pub rows: Vec<Vec<serde_json::Value>>,
 
@@ -47,6 +70,17 @@ pub struct QueryExecutionResult {
     /// `true` when the result set exceeded `MAX_RESULT_ROWS` and `rows` was
     /// truncated. The UI should surface a "add a LIMIT clause" hint.
     pub truncated: bool,
+
+    /// `true` when `execute_query` added the default row limit to the query
+    /// and the result filled it, so more rows may exist. The UI can offer to
+    /// re-run without the limit.
+    #[serde(default)]
+    pub limited: bool,
+
+    /// `true` when the result came from the result cache instead of the
+    /// database (see `execute_query`'s `use_cache`)
+    #[serde(default)]
+    pub from_cache: bool,
 }
 
 impl QueryExecutionResult {
@@ -73,14 +107,22 @@ impl QueryExecutionResult {
         if truncated {
             rows.truncate(MAX_RESULT_ROWS);
         }
+        // The grid only shows the start of large binary values; the whole
+        // value is fetched on demand with `fetch_cell_blob`.
+        for value in rows.iter_mut().flatten() {
+            preview_blob(value, BLOB_PREVIEW_BYTES);
+        }
 
         Self {
-            columns: query_result.columns,
+            columns: unique_column_names(&query_result.columns),
+            original_columns: query_result.columns,
             rows,
             rows_affected: query_result.rows_affected,
             execution_time: execution_time_ms,
             query_type,
             truncated,
+            limited: false,
+            from_cache: false,
         }
     }
 }
@@ -94,8 +136,38 @@ impl QueryExecutionResult {
 ///
 /// * `connection_id` - ID of the active database connection to use
 /// * `sql` - SQL query string to execute
+/// * `confirmed` - The user confirmed a destructive statement (see below)
+/// * `unlimited` - Run a SELECT without the default row limit (see below)
+/// * `use_cache` - Serve a read-only SELECT from the result cache (see below)
+/// * `format` - Format values as display strings (see below)
 /// * `state` - Application state containing active connections
 ///
+/// On a connection whose profile is tagged `Production`, DELETE, DROP and
+/// TRUNCATE statements are refused with `DbError::ConfirmationRequired`
+/// unless `confirmed` is true, whatever the global `confirmDestructive`
+/// setting says. The UI should ask the user and re-send the query with
+/// `confirmed: true`.
+///
+/// A single SELECT that neither limits nor aggregates its rows is run with
+/// the `defaultRowLimit` query setting as a LIMIT (TOP on SQL Server), and
+/// `limited` is set on the result when that limit was reached. Pass
+/// `unlimited: true` to run the query as written.
+///
+/// With `use_cache: true`, a single read-only SELECT is answered from the
+/// result cache when the same query ran on the connection in the last five
+/// minutes, with `fromCache` set on the result; otherwise its result is
+/// cached. Any other statement run on the connection drops the cached
+/// results that read from the tables it names, as do the grid editing,
+/// import and DDL commands for the tables they change. Writes made by other
+/// clients are not seen until the entry expires or `clear_query_cache` is
+/// called.
+///
+/// Rows hold typed JSON values unless `format` is given, in which case every
+/// value is turned into the string to display: dates with its `dateFormat`,
+/// non-integer numbers rounded to `decimalPlaces`, thousands grouped only
+/// when `thousandsSeparator` is set, and NULL shown as `nullSentinel` (or
+/// left `null`). The result cache always holds the raw values.
+///
 /// # Returns
 ///
 /// Returns a `QueryExecutionResult` containing:
@@ -107,6 +179,7 @@ impl QueryExecutionResult {
 ///
 /// Returns `DbError` if:
 /// - The connection ID is not found
+/// - A destructive statement on a production connection is not confirmed
 /// - The query execution fails
 /// - The database driver encounters an error
 ///
@@ -127,14 +200,312 @@ impl QueryExecutionResult {
 pub async fn execute_query(
     connection_id: String,
     sql: String,
+    confirmed: Option<bool>,
+    unlimited: Option<bool>,
+    use_cache: Option<bool>,
+    tab_id: Option<String>,
+    format: Option<ResultFormatOptions>,
     state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<QueryExecutionResult, DbError> {
+    if let Some(format) = &format {
+        format.validate()?;
+    }
+    if !confirmed.unwrap_or(false) {
+        check_destructive_confirmation(&state.lock().unwrap(), &connection_id, &sql)?;
+    }
+
+    let row_limit = if unlimited.unwrap_or(false) {
+        0
+    } else {
+        load_settings(&app)
+            .map(|s| s.query)
+            .unwrap_or_default()
+            .default_row_limit
+    };
+    let driver = state
+        .lock()
+        .unwrap()
+        .connection_profiles
+        .get(&connection_id)
+        .map(|p| p.driver.clone());
+
+    let quoting = driver.as_ref().map(Quoting::for_driver).unwrap_or_default();
+    let limited_sql = driver.and_then(|d| apply_row_limit(&sql, &d, row_limit));
+    let limit_applied = limited_sql.is_some();
+    let sql = limited_sql.unwrap_or(sql);
+
+    let cache_key = (use_cache.unwrap_or(false) && is_read_only_select(&sql, quoting))
+        .then(|| compact_query_with(&sql, quoting));
+    if let Some(key) = &cache_key {
+        let cached = state.lock().unwrap().result_cache.get(&connection_id, key);
+        if let Some(mut result) = cached {
+            result.from_cache = true;
+            if let Some(format) = &format {
+                format.format_rows(&mut result.rows);
+            }
+            return Ok(result);
+        }
+    }
+
+    let mut result = run_logged_query(
+        connection_id.clone(),
+        sql.clone(),
+        None,
+        tab_id,
+        None,
+        state.clone(),
+        Some(&app),
+    )
+    .await?;
+    if limit_applied {
+        result.limited = result.rows.len() >= row_limit as usize;
+    }
+    if let Some(key) = cache_key {
+        state.lock().unwrap().result_cache.insert(
+            connection_id,
+            key,
+            referenced_tables(&sql, quoting),
+            result.clone(),
+        );
+    }
+    if let Some(format) = &format {
+        format.format_rows(&mut result.rows);
+    }
+    Ok(result)
+}
+
+/// Whether `sql` is a single SELECT that only reads: no common table
+/// expression that writes, no `SELECT ... INTO` and no `FOR UPDATE`/`FOR
+/// SHARE` row locks
+fn is_read_only_select(sql: &str, quoting: Quoting) -> bool {
+    if statement_verbs(sql, quoting) != ["SELECT"]
+        || executed_verbs(sql, quoting).iter().any(|v| v != "SELECT")
+    {
+        return false;
+    }
+    let words: Vec<String> = tokenize_with(sql, quoting)
+        .filter(|t| t.kind == TokenKind::Word)
+        .map(|t| t.text.to_ascii_uppercase())
+        .collect();
+    !words.iter().any(|w| w == "INTO")
+        && !words
+            .windows(2)
+            .any(|w| w[0] == "FOR" && (w[1] == "UPDATE" || w[1] == "SHARE"))
+}
+
+/// Statements besides read-only SELECTs that never change data
+const INSPECTION_VERBS: &[&str] = &["SHOW", "DESCRIBE", "DESC"];
+
+/// Statements that change the schema tree
+const DDL_VERBS: &[&str] = &["CREATE", "ALTER", "DROP", "RENAME", "COMMENT"];
+
+/// Drop the cached results a statement may have made stale
+///
+/// Results reading a table `sql` names are dropped; if it names none (a
+/// procedure call, `SET search_path`, ...) every result of the connection is.
+/// DDL also drops the connection's cached schema tree.
+fn invalidate_cached_results(state: &mut AppState, connection_id: &str, sql: &str) {
+    let quoting = state
+        .connection_profiles
+        .get(connection_id)
+        .map(|p| Quoting::for_driver(&p.driver))
+        .unwrap_or_default();
+    let verbs = executed_verbs(sql, quoting);
+    if verbs.iter().any(|v| DDL_VERBS.contains(&v.as_str())) {
+        state.metadata_cache.remove(connection_id);
+    }
+    if state.result_cache.is_empty() {
+        return;
+    }
+    let inspection = verbs.iter().all(|v| INSPECTION_VERBS.contains(&v.as_str()));
+    if inspection || is_read_only_select(sql, quoting) {
+        return;
+    }
+    invalidate_results_of(state, connection_id, sql);
+}
+
+/// Drop the cached results reading a table `sql` names, or every result of
+/// the connection if it names none
+///
+/// For commands that run generated statements themselves rather than
+/// through `execute_query`.
+pub(crate) fn invalidate_results_of(state: &mut AppState, connection_id: &str, sql: &str) {
+    let quoting = state
+        .connection_profiles
+        .get(connection_id)
+        .map(|p| Quoting::for_driver(&p.driver))
+        .unwrap_or_default();
+    let tables = referenced_tables(sql, quoting);
+    state.result_cache.invalidate(connection_id, &tables);
+}
+
+/// Drop cached query results
+///
+/// Clears the results cached for `connection_id`, or for every connection
+/// when it is omitted, so the next `use_cache` run goes to the database.
+///
+/// # Returns
+///
+/// The number of cached results dropped
+#[tauri::command]
+pub fn clear_query_cache(
+    connection_id: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<usize, DbError> {
+    let mut state = state.lock().unwrap();
+    Ok(state.result_cache.clear(connection_id.as_deref()))
+}
+
+/// List the queries this app is running on a connection
+///
+/// Includes queries still waiting for one of the connection's slots
+/// (`queued`), and tells which editor tab ran each one, for callers that
+/// passed `tab_id`. `monitoring::get_active_queries` is the server's view,
+/// across all clients.
+///
+/// # Returns
+///
+/// The queries, oldest first
+#[tauri::command]
+pub fn get_running_queries(
+    connection_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<RunningQuery>, DbError> {
+    let state = state.lock().unwrap();
+    let mut queries: Vec<RunningQuery> = state
+        .running_queries
+        .values()
+        .filter(|q| q.connection_id == connection_id)
+        .cloned()
+        .collect();
+    queries.sort_by_key(|q| q.started_at);
+    Ok(queries)
+}
+
+/// How much work is running and deferred on a connection
+///
+/// `deferred` counts background work (autocomplete metadata, database stats,
+/// file exports) waiting for interactive queries to finish, so the UI can
+/// show why it's slow to arrive.
+#[tauri::command]
+pub fn get_query_queue_depth(
+    connection_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<QueueDepth, DbError> {
+    Ok(state.lock().unwrap().query_scheduler.depth(&connection_id))
+}
+
+/// Statements that need confirmation on production connections
+const DESTRUCTIVE_VERBS: &[&str] = &["DELETE", "DROP", "TRUNCATE"];
+
+/// Refuse destructive statements on a production connection
+///
+/// Returns `DbError::ConfirmationRequired` naming the statement when the
+/// connection's profile is tagged with an environment that requires
+/// confirmation and `sql` runs a DELETE, DROP or TRUNCATE, including in a
+/// common table expression or under `EXPLAIN ANALYZE`.
+pub(crate) fn check_destructive_confirmation(
+    state: &AppState,
+    connection_id: &str,
+    sql: &str,
+) -> Result<(), DbError> {
+    let Some(profile) = state.connection_profiles.get(connection_id) else {
+        return Ok(());
+    };
+    if !profile
+        .environment
+        .as_ref()
+        .is_some_and(Environment::requires_confirmation)
+    {
+        return Ok(());
+    }
+
+    let verbs = executed_verbs(sql, Quoting::for_driver(&profile.driver));
+    match verbs.iter().find(|v| DESTRUCTIVE_VERBS.contains(&v.as_str())) {
+        Some(verb) => Err(DbError::ConfirmationRequired(format!(
+            "{} on production connection '{}'",
+            verb, profile.name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Execute a query with activity logging
+///
+/// Shared body of the `execute_query` command, also used by import/export.
+/// With an `app` handle, a query that fails because the connection dropped
+/// is retried once after an automatic reconnect (see `commands::reconnect`);
+/// internal callers pass `None` to surface the failure instead.
+pub(crate) async fn run_query(
+    connection_id: String,
+    sql: String,
+    state: State<'_, Mutex<AppState>>,
+    app: Option<&AppHandle>,
+) -> Result<QueryExecutionResult, DbError> {
+    run_logged_query(connection_id, sql, None, None, None, state, app).await
+}
+
+/// Like [`run_query`], with `tags` attached to the activity log entry
+///
+/// Used for runs the user didn't start by hand, such as scheduled queries,
+/// so they can be told apart in the activity log.
+pub(crate) async fn run_tagged_query(
+    connection_id: String,
+    sql: String,
+    tags: Vec<String>,
+    state: State<'_, Mutex<AppState>>,
+    app: Option<&AppHandle>,
+) -> Result<QueryExecutionResult, DbError> {
+    run_logged_query(connection_id, sql, None, None, Some(tags), state, app).await
+}
+
+/// Removes a query from `AppState::running_queries` when it finishes, however
+/// it exits
+struct RunningQueryGuard<'a> {
+    state: &'a Mutex<AppState>,
+    id: String,
+}
+
+impl Drop for RunningQueryGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.running_queries.remove(&self.id);
+        }
+    }
+}
+
+/// Execute a query, optionally with named parameters, with activity logging
+///
+/// With `params`, the `:name` / `$name` placeholders in `sql` are rewritten
+/// to the driver's positional placeholders and the values are sent as bound
+/// parameters. Parameterized queries are not retried after a reconnect.
+///
+/// At most `QuerySettings::max_concurrent_queries` queries run on a
+/// connection at once; the rest wait for a slot. A `BEGIN` on a pooled
+/// connection opens a session pinned to one connection, and every statement
+/// up to the matching `COMMIT`/`ROLLBACK` runs there, without taking a slot.
+async fn run_logged_query(
+    connection_id: String,
+    sql: String,
+    params: Option<&HashMap<String, serde_json::Value>>,
+    tab_id: Option<String>,
+    tags: Option<Vec<String>>,
+    state: State<'_, Mutex<AppState>>,
+    app: Option<&AppHandle>,
 ) -> Result<QueryExecutionResult, DbError> {
     // Generate a unique log ID
     let log_id = Uuid::new_v4().to_string();
 
+    let max_concurrent = app
+        .and_then(|app| load_settings(app).ok())
+        .map(|s| s.query)
+        .unwrap_or_default()
+        .max_concurrent_queries;
+
     // Get the connection and connection name from state, and start logging
-    let connection = {
-        let state_guard = state.lock().unwrap();
+    let (connection, session, slots, scheduler) = {
+        let mut state_guard = state.lock().unwrap();
 
         let connection = state_guard
             .get_connection(&connection_id)
@@ -160,31 +531,130 @@ pub async fn execute_query(
             .and_then(|p| p.database.clone());
 
         // Create and log the query start
-        let query_log = QueryLog::new(
+        let mut query_log = QueryLog::new(
             log_id.clone(),
             connection_id.clone(),
             connection_name.clone(),
             database.clone(),
             sql.clone(),
         );
+        query_log.tags = tags;
         state_guard.activity_logger.log_query_start(query_log);
 
-        connection
+        let session = state_guard
+            .transaction_sessions
+            .get(&connection_id)
+            .cloned();
+        let slots = state_guard.query_semaphore(&connection_id, max_concurrent);
+        state_guard.running_queries.insert(
+            log_id.clone(),
+            RunningQuery {
+                id: log_id.clone(),
+                connection_id: connection_id.clone(),
+                tab_id,
+                sql: sql.clone(),
+                started_at: chrono::Utc::now(),
+                queued: session.is_none(),
+                in_transaction: session.is_some(),
+            },
+        );
+
+        let scheduler = Arc::clone(&state_guard.query_scheduler);
+        (connection, session, slots, scheduler)
+    };
+    let _active = RunningQueryGuard {
+        state: state.inner(),
+        id: log_id.clone(),
+    };
+    // Background work on the connection waits while this is registered
+    let _priority = scheduler
+        .enter(&connection_id, QueryPriority::Interactive)
+        .await;
+
+    // The slot is held until the query finishes. The semaphore is never
+    // closed, so acquiring only fails if that changes.
+    let _slot = match session {
+        Some(_) => None,
+        None => {
+            let slot = slots.acquire_owned().await.ok();
+            if let Some(query) = state.lock().unwrap().running_queries.get_mut(&log_id) {
+                query.queued = false;
+            }
+            slot
+        }
     };
 
     // Measure execution time
     let start = Instant::now();
 
-    // Execute the query
-    let query_result = connection.execute_query(&sql).await;
+    let control = transaction_control(&sql);
+    let pinned = session.is_some();
+    let mut opened_session = None;
+    let target: Result<Arc<dyn DatabaseDriver>, DbError> = match session {
+        Some(session) => Ok(session),
+        None if control == Some(TransactionControl::Begin) => {
+            match connection.open_session().await {
+                Ok(Some(session)) => {
+                    opened_session = Some(Arc::clone(&session));
+                    Ok(session)
+                }
+                Ok(None) => Ok(connection),
+                Err(e) => Err(e),
+            }
+        }
+        None => Ok(connection),
+    };
+
+    // Execute the query, transparently reconnecting once if the connection
+    // dropped and the profile opted into auto-reconnect. A dropped session
+    // took its transaction with it, so that isn't retried.
+    let query_result = match (target, params) {
+        (Err(e), _) => Err(e),
+        (Ok(target), Some(params)) => {
+            match bind_named_parameters(&sql, target.placeholder_style(), params) {
+                Ok((bound_sql, values)) => {
+                    target.execute_query_with_params(&bound_sql, &values).await
+                }
+                Err(e) => Err(e),
+            }
+        }
+        (Ok(target), None) => match (target.execute_query(&sql).await, app) {
+            (Err(e), Some(app)) if is_connection_lost(&e) && !pinned => {
+                retry_after_reconnect(app, &connection_id, &sql, e).await
+            }
+            (result, _) => result,
+        },
+    };
 
     // Calculate execution time in milliseconds
     let execution_time_ms = start.elapsed().as_millis() as u64;
 
-    // Update the log based on result
+    // Update the log based on result. A query whose connection was reset
+    // meanwhile ran on the old connection and is already logged as cancelled.
+    let reset = !state.lock().unwrap().running_queries.contains_key(&log_id);
     match &query_result {
+        _ if reset => {}
         Ok(result) => {
-            let state_guard = state.lock().unwrap();
+            let mut state_guard = state.lock().unwrap();
+            track_transaction(&mut state_guard, &connection_id, &sql);
+            match control {
+                Some(TransactionControl::Begin) => {
+                    // Unless the connection was closed while BEGIN ran
+                    if let Some(session) = opened_session
+                        .filter(|_| state_guard.connections.contains_key(&connection_id))
+                    {
+                        state_guard
+                            .transaction_sessions
+                            .insert(connection_id.clone(), session);
+                    }
+                }
+                Some(TransactionControl::End) => {
+                    state_guard.transaction_sessions.remove(&connection_id);
+                }
+                None => {}
+            }
+            state_guard.touch_connection(&connection_id);
+            invalidate_cached_results(&mut state_guard, &connection_id, &sql);
             let row_count = result.rows_affected.or(Some(result.rows.len() as u64));
             state_guard.activity_logger.log_query_complete(
                 &log_id,
@@ -193,7 +663,11 @@ pub async fn execute_query(
             );
         }
         Err(err) => {
-            let state_guard = state.lock().unwrap();
+            let mut state_guard = state.lock().unwrap();
+            if pinned && is_connection_lost(err) {
+                state_guard.transaction_sessions.remove(&connection_id);
+                state_guard.open_transactions.remove(&connection_id);
+            }
             state_guard.activity_logger.log_query_error(
                 &log_id,
                 execution_time_ms,
@@ -216,6 +690,320 @@ pub async fn execute_query(
     Ok(result)
 }
 
+/// Find the named parameters in a query
+///
+/// Detects `:name` and `$name` placeholders, skipping any inside string
+/// literals, quoted identifiers and comments. Each parameter carries a type
+/// inferred from the surrounding SQL (defaulting to text) and the value it
+/// was last run with, so the UI can pre-fill its parameter prompt.
+///
+/// # Example
+///
+/// ```typescript
+/// const params = await invoke<QueryParam[]>('extract_query_parameters', {
+///     sql: 'SELECT * FROM users WHERE id = :id LIMIT :limit'
+/// });
+/// // [{ name: 'id', paramType: 'text', lastValue: '42' },
+/// //  { name: 'limit', paramType: 'integer', lastValue: null }]
+/// ```
+#[tauri::command]
+pub async fn extract_query_parameters(
+    sql: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<QueryParam>, DbError> {
+    let mut found = crate::sql::params::extract_query_parameters(&sql);
+
+    let state_guard = state.lock().unwrap();
+    for param in &mut found {
+        param.last_value = state_guard.parameter_values.get(&param.name).cloned();
+    }
+
+    Ok(found)
+}
+
+/// Execute a query with named parameters
+///
+/// The `:name` / `$name` placeholders in `sql` are bound as real driver
+/// parameters (`$1` for PostgreSQL, `@P1` for SQL Server, `?` elsewhere),
+/// never interpolated into the SQL text. On success the values are
+/// remembered per parameter name for the next `extract_query_parameters`.
+/// Destructive statements on production connections need `confirmed`, as
+/// with `execute_query`.
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` if a placeholder has no value, if the
+/// query mixes named and positional placeholders, or if the driver does not
+/// support parameterized queries (MongoDB, Redis), and
+/// `DbError::ConfirmationRequired` for an unconfirmed destructive statement
+/// on a production connection.
+///
+/// # Example
+///
+/// ```typescript
+/// const result = await invoke<QueryExecutionResult>('execute_parameterized_query', {
+///     connectionId: 'conn-123',
+///     sql: 'SELECT * FROM users WHERE id = :id',
+///     params: { id: 42 }
+/// });
+/// ```
+#[tauri::command]
+pub async fn execute_parameterized_query(
+    connection_id: String,
+    sql: String,
+    params: HashMap<String, serde_json::Value>,
+    confirmed: Option<bool>,
+    tab_id: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<QueryExecutionResult, DbError> {
+    if !confirmed.unwrap_or(false) {
+        check_destructive_confirmation(&state.lock().unwrap(), &connection_id, &sql)?;
+    }
+
+    let result = run_logged_query(
+        connection_id,
+        sql,
+        Some(&params),
+        tab_id,
+        None,
+        state.clone(),
+        Some(&app),
+    )
+    .await?;
+
+    let values = {
+        let mut state_guard = state.lock().unwrap();
+        state_guard.remember_parameter_values(&params);
+        state_guard.parameter_values.clone()
+    };
+    // A failed write only loses the pre-filled values, not the result.
+    if let Err(e) = AppState::save_parameter_values_to_store(&app, &values) {
+        eprintln!("Failed to save query parameter values: {}", e);
+    }
+
+    Ok(result)
+}
+
+/// Run a MongoDB aggregation pipeline and return the output as a table
+///
+/// Nested documents are returned as JSON strings, or expanded into
+/// `parent.child` columns with `options.expandNested`. A `$limit` stage is
+/// appended so at most `options.limit` (and never more than
+/// `MAX_RESULT_ROWS`) documents come back; pipelines ending in `$out` or
+/// `$merge` are left as written.
+///
+/// # Errors
+///
+/// Returns `DbError::QueryError` naming the stage index (0-based) when a
+/// stage is malformed or, where the server's message allows, when it fails,
+/// and `DbError::InvalidInput` for connections other than MongoDB.
+///
+/// # Example
+///
+/// ```typescript
+/// const result = await invoke<QueryExecutionResult>('run_mongo_aggregate', {
+///     connectionId: 'conn-123',
+///     collection: 'orders',
+///     pipeline: [
+///         { $match: { status: 'paid' } },
+///         { $group: { _id: '$customer', total: { $sum: '$amount' } } },
+///     ],
+///     options: { expandNested: true, limit: 500 },
+/// });
+/// ```
+#[tauri::command]
+pub async fn run_mongo_aggregate(
+    connection_id: String,
+    collection: String,
+    pipeline: Vec<mongodb::bson::Document>,
+    options: Option<AggregateOptions>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<QueryExecutionResult, DbError> {
+    if collection.trim().is_empty() {
+        return Err(DbError::InvalidInput("Collection name is required".to_string()));
+    }
+
+    let connection = state
+        .lock()
+        .unwrap()
+        .get_connection(&connection_id)
+        .ok_or_else(|| {
+            DbError::NotFound(format!("Connection with ID {} not found", connection_id))
+        })?
+        .clone();
+
+    let start = Instant::now();
+    let result = connection
+        .aggregate(&collection, pipeline, options.unwrap_or_default())
+        .await?;
+    let execution_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(QueryExecutionResult::from_query_result(
+        result,
+        execution_time_ms,
+        "AGGREGATE".to_string(),
+    ))
+}
+
+/// How `preview_affected_rows` measured a statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewMethod {
+    /// Counted with a rewritten `SELECT COUNT(*)`
+    Count,
+    /// Executed inside a transaction that was rolled back
+    Rollback,
+}
+
+/// Number of rows an UPDATE or DELETE would affect
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AffectedRowsPreview {
+    pub rows: u64,
+    pub method: PreviewMethod,
+    /// The count query, for `PreviewMethod::Count`
+    pub count_sql: Option<String>,
+    /// Why the statement could not be counted, for `PreviewMethod::Rollback`
+    pub reason: Option<String>,
+}
+
+/// Count the rows an UPDATE or DELETE statement would affect
+///
+/// Single-table statements are rewritten into `SELECT COUNT(*)` with the
+/// same table and WHERE clause, so nothing is modified. Statements whose
+/// count cannot be derived that way (joins, ORDER BY/LIMIT, CTEs) are only
+/// run in a transaction that is rolled back when `allow_rollback` is true;
+/// `reason` says why. That run still takes row locks and does not undo
+/// sequence increments, so it is refused on MySQL unless every table the
+/// statement names uses a transactional engine.
+///
+/// # Errors
+///
+/// Returns `DbError::ConfirmationRequired` with the reason if the statement
+/// needs the rollback fallback and `allow_rollback` isn't set, and
+/// `DbError::InvalidInput` if `sql` is not a single UPDATE or DELETE
+/// statement, or if the fallback isn't safe on the connection (a driver
+/// without transactions, or a MySQL table that can't roll back).
+///
+/// # Example
+///
+/// ```typescript
+/// const preview = await invoke<AffectedRowsPreview>('preview_affected_rows', {
+///     connectionId: 'conn-123',
+///     sql: "UPDATE users SET plan = 'pro' WHERE created_at < '2024-01-01'",
+/// });
+/// console.log(`This will affect ${preview.rows.toLocaleString()} rows`);
+/// ```
+#[tauri::command]
+pub async fn preview_affected_rows(
+    connection_id: String,
+    sql: String,
+    allow_rollback: Option<bool>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<AffectedRowsPreview, DbError> {
+    let (connection, driver) = {
+        let state_guard = state.lock().unwrap();
+        let connection = state_guard
+            .get_connection(&connection_id)
+            .ok_or_else(|| {
+                DbError::NotFound(format!("Connection with ID {} not found", connection_id))
+            })?
+            .clone();
+        let driver = state_guard
+            .connection_profiles
+            .get(&connection_id)
+            .map(|p| p.driver.clone());
+        (connection, driver)
+    };
+    let quoting = driver
+        .as_ref()
+        .map(Quoting::for_driver)
+        .unwrap_or_default();
+
+    match plan_affected_rows(&sql, quoting)? {
+        AffectedRowsPlan::Count(count_sql) => {
+            let result = connection.execute_query(&count_sql).await?;
+            let rows = result
+                .rows
+                .first()
+                .and_then(|row| row.first())
+                .and_then(|value| match value {
+                    serde_json::Value::Number(n) => n.as_u64(),
+                    serde_json::Value::String(s) => s.parse().ok(),
+                    _ => None,
+                })
+                .ok_or_else(|| {
+                    DbError::QueryError(format!("Unexpected result for {}", count_sql))
+                })?;
+            Ok(AffectedRowsPreview {
+                rows,
+                method: PreviewMethod::Count,
+                count_sql: Some(count_sql),
+                reason: None,
+            })
+        }
+        AffectedRowsPlan::Rollback(reason) => {
+            if !allow_rollback.unwrap_or(false) {
+                return Err(DbError::ConfirmationRequired(format!(
+                    "Counting these rows means running the statement and rolling it back: {}",
+                    reason
+                )));
+            }
+            if driver == Some(DbDriver::MySql) {
+                let tables = non_transactional_tables(connection.as_ref(), &sql).await?;
+                if !tables.is_empty() {
+                    return Err(DbError::InvalidInput(format!(
+                        "Cannot preview by rolling back: {} can't roll back writes",
+                        tables.join(", ")
+                    )));
+                }
+            }
+            let affected = connection.execute_and_rollback(&[sql]).await?;
+            Ok(AffectedRowsPreview {
+                rows: affected.first().copied().unwrap_or(0),
+                method: PreviewMethod::Rollback,
+                count_sql: None,
+                reason: Some(reason.to_string()),
+            })
+        }
+    }
+}
+
+/// Tables named in a MySQL statement whose storage engine has no
+/// transactions (MyISAM, MEMORY, ...), so a rollback would not undo the
+/// statement's writes to them
+///
+/// Names are matched unqualified, in every database, so a same-named table
+/// elsewhere counts too. A statement whose tables can't be told is refused.
+async fn non_transactional_tables(
+    conn: &dyn DatabaseDriver,
+    sql: &str,
+) -> Result<Vec<String>, DbError> {
+    let tables = referenced_tables(sql, Quoting::MySql);
+    if tables.is_empty() {
+        return Err(DbError::InvalidInput(
+            "Cannot preview by rolling back: the statement's tables are unknown".to_string(),
+        ));
+    }
+    let names: Vec<String> = tables
+        .iter()
+        .map(|t| escape_literal(t, &DbDriver::MySql))
+        .collect();
+    let check = format!(
+        "SELECT DISTINCT t.TABLE_NAME FROM information_schema.TABLES t \
+         JOIN information_schema.ENGINES e ON e.ENGINE = t.ENGINE \
+         WHERE LOWER(t.TABLE_NAME) IN ({}) AND COALESCE(e.TRANSACTIONS, 'NO') <> 'YES'",
+        names.join(", ")
+    );
+    let result = conn.execute_query(&check).await?;
+    Ok(result
+        .rows
+        .iter()
+        .filter_map(|row| row.first().and_then(|v| v.as_str()).map(str::to_string))
+        .collect())
+}
+
 /// Result of a keyset-paginated table data fetch
 ///
 /// Uses keyset (cursor-based) pagination for efficient large table browsing.
@@ -345,21 +1133,29 @@ pub async fn get_table_data_keyset(
     // `filter_clause`: optional extra predicate (the structured column filters
     //   / FK drill-down built by the UI). Accepted with or without a leading
     //   `WHERE`; it is ANDed with the keyset cursor predicate.
+    // `filters`: optional structured column filters, compiled to a
+    //   parameterized predicate (see `sql::filter`) and ANDed with the rest.
     // `sort_direction`: "ASC" (default) or "DESC". Anything else is rejected
     //   to keep the value out of the interpolated ORDER BY.
     filter_clause: Option<String>,
+    filters: Option<Vec<FilterNode>>,
     sort_direction: Option<String>,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<KeysetPageResult, DbError> {
     // Clone the Arc<dyn DatabaseDriver> out of the state before any await points
-    let connection = {
+    let (connection, db_kind) = {
         let state_guard = state.lock().unwrap();
-        state_guard
+        let connection = state_guard
             .get_connection(&connection_id)
             .ok_or_else(|| {
                 DbError::NotFound(format!("Connection with ID {} not found", connection_id))
             })?
-            .clone()
+            .clone();
+        let db_kind = state_guard
+            .connection_profiles
+            .get(&connection_id)
+            .map(|profile| profile.driver.clone());
+        (connection, db_kind)
     };
 
     // Whitelist the sort direction — it is interpolated into ORDER BY, so it
@@ -411,11 +1207,32 @@ pub async fn get_table_data_keyset(
         .and_then(cursor_sql_literal)
         .map(|lit| format!("{} {} {}", quoted_cursor_col, cursor_op, lit));
 
-    // Compose the WHERE clause from (optional) UI filter + (optional) cursor.
+    // Structured filters bind their values as parameters. Their placeholders
+    // are numbered from 1, which holds because nothing else here is bound.
+    let structured = match (&filters, &db_kind) {
+        (Some(filters), Some(db_kind)) => crate::sql::filter::filter_predicate(filters, db_kind)?,
+        (Some(filters), None) if !filters.is_empty() => {
+            return Err(DbError::NotFound(format!(
+                "Connection profile for '{}' not found",
+                connection_id
+            )))
+        }
+        _ => None,
+    };
+
+    // Compose the WHERE clause from (optional) UI filter + (optional)
+    // structured filters + (optional) cursor.
     let mut conditions: Vec<String> = Vec::new();
     if let Some(f) = &filter_predicate {
         conditions.push(format!("({})", f));
     }
+    let params = match structured {
+        Some((predicate, params)) => {
+            conditions.push(format!("({})", predicate));
+            params
+        }
+        None => Vec::new(),
+    };
     if let Some(c) = &cursor_predicate {
         conditions.push(c.clone());
     }
@@ -438,7 +1255,11 @@ pub async fn get_table_data_keyset(
 
     // Measure execution time
     let start = Instant::now();
-    let query_result = connection.execute_query(&sql).await?;
+    let query_result = if params.is_empty() {
+        connection.execute_query(&sql).await?
+    } else {
+        connection.execute_query_with_params(&sql, &params).await?
+    };
     let execution_time_ms = start.elapsed().as_millis() as u64;
 
     let columns = query_result.columns.clone();
@@ -546,6 +1367,108 @@ mod tests {
         assert!(!json_str.contains("query_type"));
     }
 
+    #[test]
+    fn test_query_execution_result_duplicate_columns() {
+        let columns = vec!["id".to_string(), "id".to_string()];
+        let rows = vec![vec![serde_json::json!(2), serde_json::json!(1)]];
+
+        let execution_result = QueryExecutionResult::from_query_result(
+            QueryResult::with_data(columns, rows),
+            1,
+            "SELECT".to_string(),
+        );
+        assert_eq!(execution_result.columns, vec!["id", "id_2"]);
+        assert_eq!(execution_result.original_columns, vec!["id", "id"]);
+    }
+
+    #[test]
+    fn test_destructive_confirmation_on_production() {
+        let mut state = AppState::new();
+        let mut profile = crate::models::ConnectionProfile::new(
+            "prod".to_string(),
+            "Prod DB".to_string(),
+            crate::models::DbDriver::Postgres,
+            "localhost".to_string(),
+            5432,
+            "postgres".to_string(),
+        );
+        profile.environment = Some(Environment::Production);
+        state.add_profile(profile);
+
+        let err = check_destructive_confirmation(&state, "prod", "SELECT 1; DELETE FROM users")
+            .unwrap_err();
+        assert!(matches!(err, DbError::ConfirmationRequired(_)));
+        assert!(check_destructive_confirmation(&state, "prod", "SELECT 'DROP TABLE x'").is_ok());
+        for sql in [
+            "WITH d AS (DELETE FROM t RETURNING *) SELECT * FROM d",
+            "EXPLAIN ANALYZE DELETE FROM t",
+        ] {
+            assert!(matches!(
+                check_destructive_confirmation(&state, "prod", sql),
+                Err(DbError::ConfirmationRequired(_))
+            ));
+        }
+        assert!(check_destructive_confirmation(&state, "prod", "EXPLAIN DELETE FROM t").is_ok());
+
+        state.connection_profiles.get_mut("prod").unwrap().environment = Some(Environment::Staging);
+        assert!(check_destructive_confirmation(&state, "prod", "DROP TABLE users").is_ok());
+    }
+
+    #[test]
+    fn test_writes_invalidate_cached_results() {
+        let q = Quoting::Standard;
+        assert!(is_read_only_select(
+            "WITH t AS (SELECT 1) SELECT * FROM t",
+            q
+        ));
+        assert!(!is_read_only_select("SELECT * INTO backup FROM users", q));
+        assert!(!is_read_only_select("SELECT * FROM users FOR UPDATE", q));
+        assert!(!is_read_only_select("SELECT 1; SELECT 2", q));
+        assert!(!is_read_only_select(
+            "WITH d AS (DELETE FROM users RETURNING *) SELECT * FROM d",
+            q
+        ));
+
+        let mut state = AppState::new();
+        for table in ["users", "orders"] {
+            let result = QueryExecutionResult::from_query_result(
+                QueryResult::with_data(vec![], vec![]),
+                1,
+                "SELECT".to_string(),
+            );
+            state.result_cache.insert(
+                "c1".to_string(),
+                table.to_string(),
+                vec![table.to_string()],
+                result,
+            );
+        }
+
+        invalidate_cached_results(&mut state, "c1", "SELECT * FROM users");
+        invalidate_cached_results(&mut state, "c1", "SHOW search_path");
+        assert!(state.result_cache.get("c1", "users").is_some());
+        invalidate_cached_results(&mut state, "c1", "UPDATE \"Users\" SET name = 'x'");
+        assert!(state.result_cache.get("c1", "users").is_none());
+        assert!(state.result_cache.get("c1", "orders").is_some());
+        invalidate_cached_results(&mut state, "c1", "EXPLAIN ANALYZE DELETE FROM orders");
+        assert!(state.result_cache.get("c1", "orders").is_none());
+        invalidate_cached_results(&mut state, "c1", "CALL rebuild()");
+        assert!(state.result_cache.is_empty());
+    }
+
+    #[test]
+    fn test_ddl_drops_schema_tree_cache() {
+        let mut state = AppState::new();
+        state
+            .metadata_cache
+            .insert("c1".to_string(), crate::state::MetadataCache::new());
+
+        invalidate_cached_results(&mut state, "c1", "UPDATE users SET name = 'x'");
+        assert!(state.metadata_cache.contains_key("c1"));
+        invalidate_cached_results(&mut state, "c1", "ALTER TABLE users ADD COLUMN age int");
+        assert!(!state.metadata_cache.contains_key("c1"));
+    }
+
     // Note: Integration tests for execute_query command would require
     // a real or mock database connection. These are better placed in
     // integration tests with actual database drivers or mocked drivers.
@@ -0,0 +1,594 @@
+//! Server-side query export
+//!
+//! Runs a query on the backend and streams its rows straight into a CSV,
+//! JSON or XLSX file, so exporting a large result never sends it through the
+//! IPC bridge (and is not limited by `MAX_RESULT_ROWS`). Progress is reported
+//! through `query-export-progress` events, and a running export can be
+//! stopped with `cancel_query_export`.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, State};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::export::{csv_record, json_value_to_string, row_to_json_object, ExportOptions};
+use crate::drivers::RowSink;
+use crate::models::DbError;
+use crate::state::{AppState, QueryPriority};
+
+/// Event emitted after every written batch and once when the export ends
+pub const QUERY_EXPORT_PROGRESS_EVENT: &str = "query-export-progress";
+
+/// Rows fetched from the driver and written per batch.
+const EXPORT_BATCH_SIZE: usize = 1_000;
+
+/// Worksheet row limit of the XLSX format (including the header row).
+const XLSX_MAX_ROWS: u64 = 1_048_576;
+
+/// Largest integer a spreadsheet can store exactly (2^53).
+const XLSX_MAX_EXACT_INT: u64 = 1 << 53;
+
+/// Output file format of `export_query_to_file`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryExportFormat {
+    Csv,
+    Json,
+    Xlsx,
+}
+
+/// Payload of the `query-export-progress` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryExportProgress {
+    pub file_path: String,
+    pub rows_written: u64,
+    pub done: bool,
+    pub cancelled: bool,
+}
+
+/// Outcome of `export_query_to_file`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryExportResult {
+    pub file_path: String,
+    pub rows_written: u64,
+    /// The export was stopped early; the file holds the rows written so far
+    pub cancelled: bool,
+}
+
+/// Cancel flags of running exports, keyed by target file path.
+#[derive(Default)]
+pub struct ActiveQueryExports(pub Mutex<HashMap<String, Arc<AtomicBool>>>);
+
+/// Removes the export's cancel flag however `export_query_to_file` exits.
+struct ActiveExportGuard<'a> {
+    exports: &'a ActiveQueryExports,
+    file_path: String,
+}
+
+impl Drop for ActiveExportGuard<'_> {
+    fn drop(&mut self) {
+        self.exports.0.lock().unwrap().remove(&self.file_path);
+    }
+}
+
+fn io_error(e: impl std::fmt::Display) -> DbError {
+    DbError::InternalError(format!("Failed to write export file: {}", e))
+}
+
+/// Escape text for an XML text node, dropping characters XML 1.0 forbids.
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Render one worksheet cell.
+///
+/// Integers a spreadsheet cannot hold exactly (e.g. 64-bit IDs) are written
+/// as text so no digits are lost.
+fn xlsx_cell(value: &Value) -> String {
+    let inline = |text: &str| {
+        format!(
+            "<c t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+            escape_xml(text)
+        )
+    };
+
+    match value {
+        Value::Null => "<c/>".to_string(),
+        Value::Bool(b) => format!("<c t=\"b\"><v>{}</v></c>", u8::from(*b)),
+        Value::Number(n) => {
+            let exact = n
+                .as_i64()
+                .map(|i| i.unsigned_abs() <= XLSX_MAX_EXACT_INT)
+                .or_else(|| n.as_u64().map(|u| u <= XLSX_MAX_EXACT_INT))
+                .unwrap_or(true);
+            if exact {
+                format!("<c><v>{}</v></c>", n)
+            } else {
+                inline(&n.to_string())
+            }
+        }
+        Value::String(s) => inline(s),
+        Value::Array(_) | Value::Object(_) => inline(&value.to_string()),
+    }
+}
+
+/// Minimal single-sheet XLSX writer
+///
+/// The static package parts are written up front; the worksheet is the last
+/// zip entry and is streamed row by row, so memory use does not grow with
+/// the result size.
+struct XlsxWriter {
+    zip: ZipWriter<BufWriter<File>>,
+    rows: u64,
+}
+
+impl XlsxWriter {
+    const CONTENT_TYPES: &'static str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+        r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+        r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+        r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+        r#"<Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+        r#"</Types>"#
+    );
+
+    const ROOT_RELS: &'static str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>"#,
+        r#"</Relationships>"#
+    );
+
+    const WORKBOOK: &'static str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" "#,
+        r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
+        r#"<sheets><sheet name="Results" sheetId="1" r:id="rId1"/></sheets>"#,
+        r#"</workbook>"#
+    );
+
+    const WORKBOOK_RELS: &'static str = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>"#,
+        r#"</Relationships>"#
+    );
+
+    fn create(file: File) -> Result<Self, DbError> {
+        let mut zip = ZipWriter::new(BufWriter::new(file));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        for (name, content) in [
+            ("[Content_Types].xml", Self::CONTENT_TYPES),
+            ("_rels/.rels", Self::ROOT_RELS),
+            ("xl/workbook.xml", Self::WORKBOOK),
+            ("xl/_rels/workbook.xml.rels", Self::WORKBOOK_RELS),
+        ] {
+            zip.start_file(name, options).map_err(io_error)?;
+            zip.write_all(content.as_bytes()).map_err(io_error)?;
+        }
+
+        // The sheet can exceed 4 GiB uncompressed on very large exports.
+        zip.start_file("xl/worksheets/sheet1.xml", options.large_file(true))
+            .map_err(io_error)?;
+        zip.write_all(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+                "<sheetData>"
+            )
+            .as_bytes(),
+        )
+        .map_err(io_error)?;
+
+        Ok(Self { zip, rows: 0 })
+    }
+
+    fn write_row(&mut self, cells: impl Iterator<Item = String>) -> Result<(), DbError> {
+        if self.rows >= XLSX_MAX_ROWS {
+            return Err(DbError::InvalidInput(format!(
+                "XLSX worksheets are limited to {} rows; export to CSV or JSON instead",
+                XLSX_MAX_ROWS
+            )));
+        }
+        self.rows += 1;
+
+        let mut line = String::from("<row>");
+        cells.for_each(|cell| line.push_str(&cell));
+        line.push_str("</row>");
+        self.zip.write_all(line.as_bytes()).map_err(io_error)
+    }
+
+    fn finish(mut self) -> Result<(), DbError> {
+        self.zip
+            .write_all(b"</sheetData></worksheet>")
+            .map_err(io_error)?;
+        self.zip
+            .finish()
+            .map_err(io_error)?
+            .flush()
+            .map_err(io_error)
+    }
+}
+
+/// Incremental writer for one export file
+enum ExportWriter {
+    Csv(BufWriter<File>),
+    Json {
+        out: BufWriter<File>,
+        columns: Vec<String>,
+        first: bool,
+    },
+    Xlsx(XlsxWriter),
+}
+
+impl ExportWriter {
+    fn create(file_path: &str, format: QueryExportFormat) -> Result<Self, DbError> {
+        let file = File::create(file_path)
+            .map_err(|e| DbError::InternalError(format!("Failed to create export file: {}", e)))?;
+
+        Ok(match format {
+            QueryExportFormat::Csv => Self::Csv(BufWriter::new(file)),
+            QueryExportFormat::Json => Self::Json {
+                out: BufWriter::new(file),
+                columns: Vec::new(),
+                first: true,
+            },
+            QueryExportFormat::Xlsx => Self::Xlsx(XlsxWriter::create(file)?),
+        })
+    }
+
+    fn write_header(&mut self, header: &[String]) -> Result<(), DbError> {
+        match self {
+            Self::Csv(out) => {
+                writeln!(out, "{}", csv_record(header.iter().cloned())).map_err(io_error)
+            }
+            Self::Json { out, columns, .. } => {
+                *columns = header.to_vec();
+                out.write_all(b"[").map_err(io_error)
+            }
+            Self::Xlsx(xlsx) => {
+                xlsx.write_row(header.iter().map(|c| xlsx_cell(&Value::from(c.as_str()))))
+            }
+        }
+    }
+
+    fn write_row(&mut self, row: &[Value], options: &ExportOptions) -> Result<(), DbError> {
+        match self {
+            Self::Csv(out) => {
+                let values = row.iter().map(|val| json_value_to_string(val, options));
+                writeln!(out, "{}", csv_record(values)).map_err(io_error)
+            }
+            Self::Json {
+                out,
+                columns,
+                first,
+            } => {
+                let object = serde_json::to_string(&row_to_json_object(columns, row, options))
+                    .map_err(|e| {
+                        DbError::InternalError(format!("Failed to serialize JSON: {}", e))
+                    })?;
+                let separator = if *first { "\n  " } else { ",\n  " };
+                *first = false;
+                write!(out, "{}{}", separator, object).map_err(io_error)
+            }
+            Self::Xlsx(xlsx) => {
+                xlsx.write_row(row.iter().map(|val| xlsx_cell(&options.render_json(val))))
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), DbError> {
+        match self {
+            Self::Csv(mut out) => out.flush().map_err(io_error),
+            Self::Json { mut out, .. } => {
+                out.write_all(b"\n]\n").map_err(io_error)?;
+                out.flush().map_err(io_error)
+            }
+            Self::Xlsx(xlsx) => xlsx.finish(),
+        }
+    }
+}
+
+/// `RowSink` that writes each batch to the export file
+struct FileExportSink {
+    writer: ExportWriter,
+    options: ExportOptions,
+    cancel: Arc<AtomicBool>,
+    app: AppHandle,
+    file_path: String,
+    rows_written: u64,
+}
+
+fn emit_progress(app: &AppHandle, progress: QueryExportProgress) {
+    if let Err(e) = app.emit(QUERY_EXPORT_PROGRESS_EVENT, progress) {
+        eprintln!(
+            "Failed to emit {} event: {}",
+            QUERY_EXPORT_PROGRESS_EVENT, e
+        );
+    }
+}
+
+impl RowSink for FileExportSink {
+    fn columns(&mut self, columns: &[String]) -> Result<(), DbError> {
+        self.writer.write_header(columns)
+    }
+
+    fn rows(&mut self, rows: Vec<Vec<Value>>) -> Result<bool, DbError> {
+        for row in &rows {
+            self.writer.write_row(row, &self.options)?;
+        }
+        self.rows_written += rows.len() as u64;
+
+        let cancelled = self.cancel.load(Ordering::Relaxed);
+        emit_progress(
+            &self.app,
+            QueryExportProgress {
+                file_path: self.file_path.clone(),
+                rows_written: self.rows_written,
+                done: false,
+                cancelled,
+            },
+        );
+        Ok(!cancelled)
+    }
+}
+
+/// Execute a query and stream its result straight to a file
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `sql` - A single row-returning statement
+/// * `file_path` - Absolute path of the file to write
+/// * `format` - `csv`, `json` or `xlsx`
+/// * `options` - NULL, binary and date rendering (defaults if omitted)
+///
+/// # Returns
+///
+/// The number of rows written and whether the export was cancelled. A
+/// cancelled export still leaves a valid file with the rows written so far;
+/// a failed export removes the partial file.
+///
+/// The export is background work: it starts once the connection's
+/// interactive queries have finished. On pooled drivers its rows then
+/// stream over a connection of their own, so queries run meanwhile don't
+/// wait for it.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const unlisten = await listen('query-export-progress', (e) => {
+///   console.log(`${e.payload.rowsWritten} rows written`);
+/// });
+///
+/// const result = await invoke('export_query_to_file', {
+///   connectionId: 'conn-123',
+///   sql: 'SELECT * FROM events ORDER BY id',
+///   filePath,
+///   format: 'csv'
+/// });
+///
+/// // From a "Stop" button:
+/// await invoke('cancel_query_export', { filePath });
+/// ```
+#[tauri::command]
+pub async fn export_query_to_file(
+    connection_id: String,
+    sql: String,
+    file_path: String,
+    format: QueryExportFormat,
+    options: Option<ExportOptions>,
+    state: State<'_, Mutex<AppState>>,
+    exports: State<'_, ActiveQueryExports>,
+    app: AppHandle,
+) -> Result<QueryExportResult, DbError> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
+
+    let (connection, scheduler) = {
+        let state = state.lock().unwrap();
+        let connection = state
+            .get_connection(&connection_id)
+            .cloned()
+            .ok_or_else(|| DbError::NotFound(format!("Connection {} not found", connection_id)))?;
+        (connection, Arc::clone(&state.query_scheduler))
+    };
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut active = exports.0.lock().unwrap();
+        if active.contains_key(&file_path) {
+            return Err(DbError::InvalidInput(format!(
+                "An export to {} is already running",
+                file_path
+            )));
+        }
+        active.insert(file_path.clone(), Arc::clone(&cancel));
+    }
+    let _guard = ActiveExportGuard {
+        exports: &exports,
+        file_path: file_path.clone(),
+    };
+
+    let mut sink = FileExportSink {
+        writer: ExportWriter::create(&file_path, format)?,
+        options,
+        cancel,
+        app,
+        file_path: file_path.clone(),
+        rows_written: 0,
+    };
+
+    let _ticket = scheduler
+        .enter(&connection_id, QueryPriority::Background)
+        .await;
+    let streamed = connection
+        .stream_query(&sql, EXPORT_BATCH_SIZE, &mut sink)
+        .await;
+
+    let FileExportSink {
+        writer,
+        cancel,
+        app,
+        rows_written,
+        ..
+    } = sink;
+    if let Err(e) = streamed.and_then(|_| writer.finish()) {
+        let _ = std::fs::remove_file(&file_path);
+        return Err(e);
+    }
+
+    let cancelled = cancel.load(Ordering::Relaxed);
+    emit_progress(
+        &app,
+        QueryExportProgress {
+            file_path: file_path.clone(),
+            rows_written,
+            done: true,
+            cancelled,
+        },
+    );
+
+    Ok(QueryExportResult {
+        file_path,
+        rows_written,
+        cancelled,
+    })
+}
+
+/// Stop a running `export_query_to_file` after its current batch
+///
+/// # Arguments
+///
+/// * `file_path` - Target path the export was started with
+#[tauri::command]
+pub fn cancel_query_export(
+    file_path: String,
+    exports: State<'_, ActiveQueryExports>,
+) -> Result<(), DbError> {
+    let active = exports.0.lock().unwrap();
+    let cancel = active
+        .get(&file_path)
+        .ok_or_else(|| DbError::NotFound(format!("No export to {} is running", file_path)))?;
+    cancel.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("a<b & \"c\">"), "a&lt;b &amp; &quot;c&quot;&gt;");
+        assert_eq!(escape_xml("tab\there\u{1}"), "tab\there");
+    }
+
+    #[test]
+    fn test_xlsx_cell() {
+        assert_eq!(xlsx_cell(&Value::Null), "<c/>");
+        assert_eq!(xlsx_cell(&json!(true)), "<c t=\"b\"><v>1</v></c>");
+        assert_eq!(xlsx_cell(&json!(1.5)), "<c><v>1.5</v></c>");
+        assert_eq!(
+            xlsx_cell(&json!(9_007_199_254_740_993u64)),
+            "<c t=\"inlineStr\"><is><t xml:space=\"preserve\">9007199254740993</t></is></c>"
+        );
+        assert_eq!(
+            xlsx_cell(&json!("x<y")),
+            "<c t=\"inlineStr\"><is><t xml:space=\"preserve\">x&lt;y</t></is></c>"
+        );
+    }
+
+    fn write_export(name: &str, format: QueryExportFormat) -> String {
+        let path = std::env::temp_dir().join(name);
+        let path = path.to_str().unwrap().to_string();
+
+        let mut writer = ExportWriter::create(&path, format).unwrap();
+        writer
+            .write_header(&["id".to_string(), "name".to_string()])
+            .unwrap();
+        let options = ExportOptions::default();
+        writer
+            .write_row(&[json!(1), json!("a,b")], &options)
+            .unwrap();
+        writer
+            .write_row(&[json!(2), Value::Null], &options)
+            .unwrap();
+        writer.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_streamed_csv_export() {
+        let path = write_export("test_query_export.csv", QueryExportFormat::Csv);
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content, "id,name\n1,\"a,b\"\n2,\n");
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_streamed_json_export() {
+        let path = write_export("test_query_export.json", QueryExportFormat::Json);
+        let parsed: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            parsed,
+            json!([{"id": 1, "name": "a,b"}, {"id": 2, "name": null}])
+        );
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_streamed_xlsx_export() {
+        use calamine::{open_workbook, Data, Reader, Xlsx};
+
+        let path = write_export("test_query_export.xlsx", QueryExportFormat::Xlsx);
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        let range = workbook.worksheet_range("Results").unwrap();
+
+        let rows: Vec<Vec<Data>> = range.rows().map(|r| r.to_vec()).collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][0], Data::String("id".to_string()));
+        assert_eq!(rows[1][0], Data::Float(1.0));
+        assert_eq!(rows[1][1], Data::String("a,b".to_string()));
+        assert_eq!(rows[2][1], Data::Empty);
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_streamed_json_export_without_rows() {
+        let path = std::env::temp_dir().join("test_query_export_empty.json");
+        let path = path.to_str().unwrap().to_string();
+        let mut writer = ExportWriter::create(&path, QueryExportFormat::Json).unwrap();
+        writer.write_header(&["id".to_string()]).unwrap();
+        writer.finish().unwrap();
+
+        let parsed: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(parsed, json!([]));
+        fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,282 @@
+//! Query plan commands
+//!
+//! Runs `EXPLAIN` for a query and reports likely performance problems in the
+//! plan (see `sql::plan`), or records the plan to see how it changes over
+//! time. The query itself is never executed.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde_json::Value;
+use tauri::{AppHandle, State};
+
+use super::procedures::{as_string, take_connection};
+use crate::commands::settings::load_settings;
+use crate::drivers::DatabaseDriver;
+use crate::models::{DbDriver, DbError};
+use crate::sql::literal::escape_literal;
+use crate::sql::normalize::fingerprint_query;
+use crate::sql::plan::{
+    analyze_plan, compare_plans, parse_mysql_plan, parse_postgres_plan, PlanComparison, PlanNode,
+    PlanNodeKind, PlanWarning, RecordedPlan,
+};
+use crate::sql::tokenizer::{count_statements, statement_verbs, Quoting};
+use crate::state::AppState;
+
+/// Analyze a query's plan for common performance red flags
+///
+/// Runs a plain `EXPLAIN` (no ANALYZE) and flags full scans of large tables,
+/// nested loops without an index on the inner side and steps estimated to
+/// produce huge row counts. Thresholds come from the `planAnalysis` section
+/// of the application settings. The warnings are heuristics built on planner
+/// estimates; each suggestion is a starting point, not a verified fix.
+///
+/// Supported on PostgreSQL-compatible drivers and MySQL.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `sql` - A single statement, without `EXPLAIN`
+/// * `app` - Tauri application handle, used to read the thresholds
+///
+/// # Example
+///
+/// ```typescript
+/// const warnings = await invoke<PlanWarning[]>('analyze_query_plan', {
+///     connectionId,
+///     sql: 'SELECT * FROM orders WHERE customer_id = 42'
+/// });
+/// // [{ kind: 'missingIndex', suggestion: 'Consider an index on orders(customer_id)', ... }]
+/// ```
+#[tauri::command]
+pub async fn analyze_query_plan(
+    connection_id: String,
+    sql: String,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<Vec<PlanWarning>, DbError> {
+    let settings = load_settings(&app)?;
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+    let plan = explain_plan(conn.as_ref(), &driver, &sql).await?;
+
+    Ok(analyze_plan(&plan, &settings.plan_analysis))
+}
+
+/// Capture a query's plan for later comparison
+///
+/// Runs a plain `EXPLAIN` (no ANALYZE) and stores the normalized plan under
+/// the query's fingerprint, so runs that differ only in literal values share
+/// a history. The last `MAX_PLANS_PER_FINGERPRINT` plans of each query are
+/// kept across restarts. Supported on PostgreSQL-compatible drivers and
+/// MySQL.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `sql` - A single statement, without `EXPLAIN`
+///
+/// # Returns
+///
+/// The recorded plan, carrying the fingerprint to pass to
+/// `compare_query_plans`
+#[tauri::command]
+pub async fn record_query_plan(
+    connection_id: String,
+    sql: String,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<RecordedPlan, DbError> {
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+    let plan = explain_plan(conn.as_ref(), &driver, &sql).await?;
+
+    let recorded = RecordedPlan {
+        fingerprint: fingerprint_query(&sql, Some(&driver)).fingerprint,
+        connection_id,
+        sql,
+        recorded_at: chrono::Utc::now().to_rfc3339(),
+        plan,
+    };
+    let plans = {
+        let mut state = state.lock().unwrap();
+        state.record_plan(recorded.clone());
+        state.query_plans.clone()
+    };
+    AppState::save_query_plans_to_store(&app, &plans)?;
+
+    Ok(recorded)
+}
+
+/// List the recorded plans of a query, oldest first
+#[tauri::command]
+pub fn get_query_plan_history(
+    fingerprint: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<RecordedPlan>, DbError> {
+    let state = state.lock().unwrap();
+    Ok(state
+        .query_plans
+        .get(&fingerprint)
+        .cloned()
+        .unwrap_or_default())
+}
+
+/// Compare the latest recorded plan of a query with an earlier one
+///
+/// Reports steps whose type or index changed (e.g. a sequential scan that
+/// became an index scan) and the change in estimated cost. Only plans
+/// recorded on the same connection as the latest one are compared.
+///
+/// # Arguments
+///
+/// * `fingerprint` - Fingerprint returned by `record_query_plan`
+/// * `steps_back` - Which earlier plan to compare against: 1 (the default)
+///   is the one recorded just before the latest
+///
+/// # Example
+///
+/// ```typescript
+/// const diff = await invoke<PlanComparison>('compare_query_plans', { fingerprint });
+/// // { planChanged: true, costDelta: -91.5, changes: [{ before: 'Seq Scan', after: 'Index Scan', ... }] }
+/// ```
+#[tauri::command]
+pub fn compare_query_plans(
+    fingerprint: String,
+    steps_back: Option<usize>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<PlanComparison, DbError> {
+    let steps_back = steps_back.unwrap_or(1);
+    if steps_back == 0 {
+        return Err(DbError::InvalidInput(
+            "steps_back must be at least 1".to_string(),
+        ));
+    }
+
+    let state = state.lock().unwrap();
+    let plans = state
+        .query_plans
+        .get(&fingerprint)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let latest = plans
+        .last()
+        .ok_or_else(|| DbError::NotFound(format!("No plans recorded for query {}", fingerprint)))?;
+    let earlier = plans
+        .iter()
+        .rev()
+        .skip(1)
+        .filter(|p| p.connection_id == latest.connection_id)
+        .nth(steps_back - 1)
+        .ok_or_else(|| {
+            DbError::InvalidInput(format!(
+                "Query {} has fewer than {} earlier plan(s) on this connection",
+                fingerprint, steps_back
+            ))
+        })?;
+
+    Ok(compare_plans(earlier, latest))
+}
+
+/// Run `EXPLAIN` for one statement and normalize the plan
+async fn explain_plan(
+    conn: &dyn DatabaseDriver,
+    driver: &DbDriver,
+    sql: &str,
+) -> Result<PlanNode, DbError> {
+    let stmt = sql.trim().trim_end_matches(';').trim_end();
+    if count_statements(stmt) != 1 {
+        return Err(DbError::InvalidInput(
+            "Plan analysis needs exactly one statement".to_string(),
+        ));
+    }
+    let quoting = Quoting::for_driver(driver);
+    if statement_verbs(stmt, quoting).first().map(String::as_str) == Some("EXPLAIN") {
+        return Err(DbError::InvalidInput(
+            "Pass the query without EXPLAIN".to_string(),
+        ));
+    }
+
+    let postgres = driver.is_postgres_compatible();
+    let explain_sql = if postgres {
+        format!("EXPLAIN (FORMAT JSON) {}", stmt)
+    } else if *driver == DbDriver::MySql {
+        format!("EXPLAIN FORMAT=JSON {}", stmt)
+    } else {
+        return Err(DbError::InvalidInput(
+            "Plan analysis is only supported for PostgreSQL and MySQL".to_string(),
+        ));
+    };
+
+    let res = conn.execute_query(&explain_sql).await?;
+    let output = res
+        .rows
+        .first()
+        .and_then(|row| row.first())
+        .ok_or_else(|| DbError::QueryError("EXPLAIN returned no plan".to_string()))?;
+    // PostgreSQL returns the plan as json; MySQL returns it as text
+    let explain = match output {
+        Value::String(text) => serde_json::from_str(text)
+            .map_err(|e| DbError::QueryError(format!("Failed to parse EXPLAIN output: {}", e)))?,
+        other => other.clone(),
+    };
+
+    if postgres {
+        let mut plan = parse_postgres_plan(&explain)?;
+        fill_postgres_table_rows(conn, &mut plan).await;
+        Ok(plan)
+    } else {
+        parse_mysql_plan(&explain)
+    }
+}
+
+/// Set `table_rows` on full scans from `pg_class.reltuples`
+///
+/// A PostgreSQL scan node only estimates the rows left after its filter, which
+/// says nothing about how much of the table was read. Best effort: without
+/// statistics the filtered estimate is used instead.
+async fn fill_postgres_table_rows(conn: &dyn DatabaseDriver, plan: &mut PlanNode) {
+    let mut relations: Vec<String> = Vec::new();
+    plan.for_each_mut(&mut |node| {
+        if let (PlanNodeKind::FullScan, Some(relation)) = (node.kind, &node.relation) {
+            if !relations.contains(relation) {
+                relations.push(relation.clone());
+            }
+        }
+    });
+    if relations.is_empty() {
+        return;
+    }
+
+    // Relation names in the plan are unqualified; resolve them through the
+    // search path like the query itself did
+    let values: Vec<String> = relations
+        .iter()
+        .map(|r| format!("({})", escape_literal(r, &DbDriver::Postgres)))
+        .collect();
+    let sql = format!(
+        "SELECT v.name, c.reltuples::bigint
+         FROM (VALUES {}) AS v(name)
+         JOIN pg_class c ON c.oid = to_regclass(quote_ident(v.name))",
+        values.join(", ")
+    );
+    let Ok(res) = conn.execute_query(&sql).await else {
+        return;
+    };
+
+    // reltuples is -1 for tables that have never been analyzed
+    let sizes: HashMap<String, f64> = res
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let name = row.first().and_then(as_string)?;
+            let rows = row.get(1)?.as_f64().filter(|n| *n >= 0.0)?;
+            Some((name, rows))
+        })
+        .collect();
+    plan.for_each_mut(&mut |node| {
+        if node.kind == PlanNodeKind::FullScan {
+            if let Some(rows) = node.relation.as_ref().and_then(|r| sizes.get(r)) {
+                node.table_rows = Some(*rows);
+            }
+        }
+    });
+}
@@ -0,0 +1,284 @@
+//! Query template commands
+//!
+//! Builds SELECT / INSERT / UPDATE / DELETE scaffolds from a table's
+//! introspected columns so users don't have to type column names by hand.
+//! Values are left as `:name` placeholders, which the query runner prompts
+//! for (see `sql::params`).
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::procedures::take_connection;
+use crate::models::{ColumnInfo, DbDriver, DbError, TableSchema};
+use crate::state::AppState;
+
+/// Statement to scaffold with [`generate_query_template`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueryTemplateKind {
+    /// Every column, no filter
+    Select,
+    /// Every column except auto-increment and generated ones
+    Insert,
+    /// Non-key, non-generated columns, filtered on the primary key
+    Update,
+    /// Filtered on the primary key
+    Delete,
+}
+
+/// Generate a statement template for a table
+///
+/// Identifiers are quoted for the connection's dialect. UPDATE and DELETE
+/// filter on the primary key and fail with `DbError::InvalidInput` for
+/// tables without one, rather than producing a statement that would touch
+/// every row.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table to build the statement for
+/// * `kind` - Statement to generate
+///
+/// # Example
+///
+/// ```typescript
+/// const sql = await invoke<string>('generate_query_template', {
+///     connectionId, schema: 'public', table: 'users', kind: 'Update'
+/// });
+/// // UPDATE "public"."users" SET "name" = :name WHERE "id" = :id;
+/// ```
+#[tauri::command]
+pub async fn generate_query_template(
+    connection_id: String,
+    schema: String,
+    table: String,
+    kind: QueryTemplateKind,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<String, DbError> {
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+    if matches!(driver, DbDriver::MongoDb | DbDriver::Redis) {
+        return Err(DbError::InvalidInput(
+            "Query templates are only available for SQL databases".to_string(),
+        ));
+    }
+
+    let table_schema = conn.get_table_schema(&schema, &table).await?;
+    if table_schema.columns.is_empty() {
+        return Err(DbError::NotFound(format!(
+            "Table '{}.{}' not found",
+            schema, table
+        )));
+    }
+
+    build_query_template(&table_schema, kind, |ident| conn.quote_identifier(ident))
+}
+
+/// Build the template for `table_schema`, quoting identifiers with `quote`
+fn build_query_template(
+    table_schema: &TableSchema,
+    kind: QueryTemplateKind,
+    quote: impl Fn(&str) -> String,
+) -> Result<String, DbError> {
+    let table = &table_schema.table;
+    let target = format!("{}.{}", quote(&table.schema), quote(&table.name));
+    let placeholders = placeholder_names(&table_schema.columns);
+    let assign =
+        |(column, param): (&ColumnInfo, &String)| format!("{} = :{}", quote(&column.name), param);
+    // Generated columns are computed by the database and can't be written
+    let writable = || {
+        table_schema
+            .columns
+            .iter()
+            .zip(&placeholders)
+            .filter(|(c, _)| !c.is_generated)
+    };
+    let key_predicate = || -> Result<String, DbError> {
+        let keys: Vec<String> = table_schema
+            .columns
+            .iter()
+            .zip(&placeholders)
+            .filter(|(c, _)| c.is_primary_key)
+            .map(assign)
+            .collect();
+        if keys.is_empty() {
+            return Err(DbError::InvalidInput(format!(
+                "Table '{}' has no primary key to filter on",
+                table.name
+            )));
+        }
+        Ok(keys.join(" AND "))
+    };
+
+    match kind {
+        QueryTemplateKind::Select => {
+            let columns: Vec<String> = table_schema
+                .columns
+                .iter()
+                .map(|c| quote(&c.name))
+                .collect();
+            Ok(format!("SELECT {} FROM {};", columns.join(", "), target))
+        }
+        QueryTemplateKind::Insert => {
+            let mut insertable: Vec<(&ColumnInfo, &String)> =
+                writable().filter(|(c, _)| !c.is_auto_increment).collect();
+            if insertable.is_empty() {
+                insertable = writable().collect();
+            }
+            let columns: Vec<String> = insertable.iter().map(|(c, _)| quote(&c.name)).collect();
+            let values: Vec<String> = insertable.iter().map(|(_, p)| format!(":{}", p)).collect();
+            Ok(format!(
+                "INSERT INTO {} ({}) VALUES ({});",
+                target,
+                columns.join(", "),
+                values.join(", ")
+            ))
+        }
+        QueryTemplateKind::Update => {
+            let predicate = key_predicate()?;
+            let mut assignments: Vec<String> = writable()
+                .filter(|(c, _)| !c.is_primary_key)
+                .map(assign)
+                .collect();
+            // A table made only of key columns: updating the key is the only option
+            if assignments.is_empty() {
+                assignments = writable().map(assign).collect();
+            }
+            Ok(format!(
+                "UPDATE {} SET {} WHERE {};",
+                target,
+                assignments.join(", "),
+                predicate
+            ))
+        }
+        QueryTemplateKind::Delete => Ok(format!(
+            "DELETE FROM {} WHERE {};",
+            target,
+            key_predicate()?
+        )),
+    }
+}
+
+/// Placeholder name for each column
+///
+/// `:name` placeholders only allow letters, digits and underscores, so other
+/// characters become `_`; names that collide after that get a numeric suffix.
+fn placeholder_names(columns: &[ColumnInfo]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    columns
+        .iter()
+        .map(|column| {
+            let mut base: String = column
+                .name
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            if !base.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+                base.insert_str(0, "p_");
+            }
+            let mut name = base.clone();
+            let mut n = 2;
+            while !seen.insert(name.clone()) {
+                name = format!("{}_{}", base, n);
+                n += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TableInfo;
+
+    fn users() -> TableSchema {
+        let mut id = ColumnInfo::new("id".to_string(), "integer".to_string(), false);
+        id.is_primary_key = true;
+        id.is_auto_increment = true;
+        TableSchema::new(
+            TableInfo::new(
+                "users".to_string(),
+                "public".to_string(),
+                "BASE TABLE".to_string(),
+            ),
+            vec![
+                id,
+                ColumnInfo::new("name".to_string(), "text".to_string(), false),
+                ColumnInfo::new("Created At".to_string(), "timestamp".to_string(), true),
+            ],
+            Vec::new(),
+        )
+    }
+
+    fn quote(ident: &str) -> String {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+
+    #[test]
+    fn test_build_query_template() {
+        let schema = users();
+        assert_eq!(
+            build_query_template(&schema, QueryTemplateKind::Select, quote).unwrap(),
+            r#"SELECT "id", "name", "Created At" FROM "public"."users";"#
+        );
+        assert_eq!(
+            build_query_template(&schema, QueryTemplateKind::Insert, quote).unwrap(),
+            r#"INSERT INTO "public"."users" ("name", "Created At") VALUES (:name, :created_at);"#
+        );
+        assert_eq!(
+            build_query_template(&schema, QueryTemplateKind::Update, quote).unwrap(),
+            r#"UPDATE "public"."users" SET "name" = :name, "Created At" = :created_at WHERE "id" = :id;"#
+        );
+        assert_eq!(
+            build_query_template(&schema, QueryTemplateKind::Delete, quote).unwrap(),
+            r#"DELETE FROM "public"."users" WHERE "id" = :id;"#
+        );
+    }
+
+    #[test]
+    fn test_build_query_template_skips_generated_columns() {
+        let mut schema = users();
+        schema.columns[2].is_generated = true;
+        schema.columns[2].generation_expression = Some("now()".to_string());
+        assert_eq!(
+            build_query_template(&schema, QueryTemplateKind::Insert, quote).unwrap(),
+            r#"INSERT INTO "public"."users" ("name") VALUES (:name);"#
+        );
+        assert_eq!(
+            build_query_template(&schema, QueryTemplateKind::Update, quote).unwrap(),
+            r#"UPDATE "public"."users" SET "name" = :name WHERE "id" = :id;"#
+        );
+    }
+
+    #[test]
+    fn test_build_query_template_without_primary_key() {
+        let mut schema = users();
+        schema.columns[0].is_primary_key = false;
+        assert!(matches!(
+            build_query_template(&schema, QueryTemplateKind::Delete, quote),
+            Err(DbError::InvalidInput(_))
+        ));
+        assert!(build_query_template(&schema, QueryTemplateKind::Select, quote).is_ok());
+    }
+
+    #[test]
+    fn test_placeholder_names() {
+        let columns: Vec<ColumnInfo> = ["user id", "user_id", "2fa", "Email"]
+            .iter()
+            .map(|n| ColumnInfo::new(n.to_string(), "text".to_string(), true))
+            .collect();
+        assert_eq!(
+            placeholder_names(&columns),
+            vec!["user_id", "user_id_2", "p_2fa", "email"]
+        );
+    }
+}
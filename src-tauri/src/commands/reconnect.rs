@@ -0,0 +1,650 @@
+//! Automatic reconnection
+//!
+//! Rebuilds a dropped connection from its profile using the saved
+//! credentials (OS keyring, falling back to the in-memory session cache) and
+//! re-creates its SSH tunnel. Triggered by the health monitor, by a query
+//! that fails with a lost connection, or manually via `reconnect_connection`.
+//! `reset_connection` does the same for a connection that is still up but
+//! wedged, tearing it down first.
+//!
+//! Progress is reported through `reconnecting`, `reconnected` and
+//! `reconnect-failed` events. Attempts back off exponentially up to
+//! `MAX_RECONNECT_ATTEMPTS`.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::connection::{open_connection, resolve_endpoint};
+use crate::credentials::CredentialManager;
+use crate::drivers::{DatabaseDriver, QueryResult};
+use crate::models::{ConnectionProfile, ConnectionStatus, DbError};
+use crate::sql::tokenizer::{tokenize, Token, TokenKind};
+use crate::state::{spawn_keepalive, AppState};
+
+/// Maximum number of attempts before giving up.
+const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+
+/// Delay before the second attempt; doubled after every failure.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound on the delay between attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Longest `reset_connection` waits for the old connection to close
+const RESET_CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Payload of the `reconnecting` / `reconnected` / `reconnect-failed` events
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconnectEvent {
+    pub connection_id: String,
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub error: Option<String>,
+    /// The drop interrupted an open transaction, which was rolled back by
+    /// the server and is not resumed on the new connection
+    pub transaction_aborted: bool,
+}
+
+/// Whether an error means the connection itself is gone (as opposed to a
+/// problem with the statement).
+///
+/// Drivers report some socket failures as `QueryError` (the failure surfaces
+/// while executing), so those are recognised by message.
+pub(crate) fn is_connection_lost(err: &DbError) -> bool {
+    const LOST_MARKERS: [&str; 7] = [
+        "connection closed",
+        "connection reset",
+        "connection refused",
+        "broken pipe",
+        "server has gone away",
+        "lost connection",
+        "unexpected eof",
+    ];
+
+    match err {
+        DbError::ConnectionError(_) => true,
+        DbError::QueryError(msg) => {
+            let msg = msg.to_lowercase();
+            LOST_MARKERS.iter().any(|m| msg.contains(m))
+        }
+        // Connection exceptions, and the server shutting the session down
+        DbError::DatabaseError { code, .. } => code
+            .sqlstate()
+            .is_some_and(|s| s.starts_with("08") || s.starts_with("57P")),
+        _ => false,
+    }
+}
+
+/// Backoff delay before attempt `attempt` (1-based; the first is immediate).
+fn backoff_delay(attempt: u32) -> Duration {
+    if attempt <= 1 {
+        return Duration::ZERO;
+    }
+    INITIAL_BACKOFF
+        .saturating_mul(1 << (attempt - 2).min(16))
+        .min(MAX_BACKOFF)
+}
+
+fn emit(app: &AppHandle, event: &str, payload: ReconnectEvent) {
+    if let Err(e) = app.emit(event, payload) {
+        eprintln!("Failed to emit {} event: {}", event, e);
+    }
+}
+
+/// Clears the in-progress marker however `reconnect` exits.
+struct ReconnectGuard<'a> {
+    app: &'a AppHandle,
+    connection_id: &'a str,
+}
+
+impl Drop for ReconnectGuard<'_> {
+    fn drop(&mut self) {
+        let state = self.app.state::<Mutex<AppState>>();
+        let mut state = state.lock().unwrap();
+        state.reconnecting.remove(self.connection_id);
+    }
+}
+
+/// Rebuild a dropped connection, retrying with exponential backoff
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle (state access and events)
+/// * `connection_id` - Connection to rebuild (same as profile ID)
+/// * `require_opt_in` - Only proceed if the profile has `auto_reconnect` set
+///
+/// # Returns
+///
+/// The new driver, already stored in the state under `connection_id`.
+///
+/// # Notes
+///
+/// A transaction that was open on the dropped connection is marked aborted
+/// (the server rolled it back) and reported via `transaction_aborted`; it is
+/// never resumed on the new connection.
+///
+/// If the user disconnects while the reconnect is in flight, the new
+/// connection is closed instead of stored and an error is returned.
+pub(crate) async fn reconnect(
+    app: &AppHandle,
+    connection_id: &str,
+    require_opt_in: bool,
+) -> Result<Arc<dyn DatabaseDriver>, DbError> {
+    let state = app.state::<Mutex<AppState>>();
+
+    let (profile, cached_password, tunnel_manager, transaction_aborted, was_registered) = {
+        let mut state = state.lock().unwrap();
+        let profile: ConnectionProfile = state
+            .get_profile(connection_id)
+            .ok_or_else(|| {
+                DbError::NotFound(format!(
+                    "Profile for connection {} not found",
+                    connection_id
+                ))
+            })?
+            .clone();
+
+        if require_opt_in && !profile.auto_reconnect {
+            return Err(DbError::ConnectionError(format!(
+                "Connection to {} was lost",
+                profile.name
+            )));
+        }
+        if !state.reconnecting.insert(connection_id.to_string()) {
+            return Err(DbError::ConnectionError(format!(
+                "Reconnect to {} is already in progress",
+                profile.name
+            )));
+        }
+
+        let aborted = state.open_transactions.remove(connection_id);
+        state.transaction_sessions.remove(connection_id);
+        (
+            profile,
+            state.connection_passwords.get(connection_id).cloned(),
+            state.ssh_tunnel_manager.clone(),
+            aborted,
+            state.connections.contains_key(connection_id),
+        )
+    };
+    let _guard = ReconnectGuard { app, connection_id };
+    let (password, ssh_password) = saved_credentials(connection_id, cached_password);
+
+    let event = |attempt: u32, error: Option<String>| ReconnectEvent {
+        connection_id: connection_id.to_string(),
+        attempt,
+        max_attempts: MAX_RECONNECT_ATTEMPTS,
+        error,
+        transaction_aborted,
+    };
+
+    let mut last_error = None;
+    for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+        tokio::time::sleep(backoff_delay(attempt)).await;
+        emit(app, "reconnecting", event(attempt, None));
+
+        // The old tunnel's SSH session is usually dead along with the
+        // database connection, so always rebuild it.
+        if tunnel_manager.has_tunnel(connection_id).await {
+            let _ = tunnel_manager.close_tunnel(connection_id).await;
+        }
+
+        let result = match resolve_endpoint(&tunnel_manager, &profile, ssh_password.clone()).await {
+            Ok((host, port)) => open_connection(app, &profile, host, port, password.clone()).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(connection) => {
+                let stored = {
+                    let mut state = state.lock().unwrap();
+                    // Disconnected while we were reconnecting: don't bring it back
+                    if was_registered && !state.connections.contains_key(connection_id) {
+                        None
+                    } else {
+                        let old = state
+                            .connections
+                            .insert(connection_id.to_string(), Arc::clone(&connection));
+                        state.record_connection_health(connection_id, ConnectionStatus::Connected);
+                        Some(old)
+                    }
+                };
+                let Some(old) = stored else {
+                    let _ = connection.close().await;
+                    if tunnel_manager.has_tunnel(connection_id).await {
+                        let _ = tunnel_manager.close_tunnel(connection_id).await;
+                    }
+                    return Err(DbError::ConnectionError(format!(
+                        "Connection {} was closed during reconnect",
+                        profile.name
+                    )));
+                };
+                if let Some(old) = old {
+                    let _ = old.close().await;
+                }
+
+                emit(app, "reconnected", event(attempt, None));
+                return Ok(connection);
+            }
+            Err(e) => {
+                emit(app, "reconnecting", event(attempt, Some(e.to_string())));
+                last_error = Some(e);
+            }
+        }
+    }
+
+    let error =
+        last_error.unwrap_or_else(|| DbError::ConnectionError("Reconnect failed".to_string()));
+    emit(
+        app,
+        "reconnect-failed",
+        event(MAX_RECONNECT_ATTEMPTS, Some(error.to_string())),
+    );
+    Err(error)
+}
+
+/// Database and SSH passwords of a connection
+///
+/// The database password comes from the OS keyring, falling back to the
+/// in-memory session cache.
+fn saved_credentials(
+    connection_id: &str,
+    cached_password: Option<String>,
+) -> (String, Option<String>) {
+    let password = CredentialManager::get_password(connection_id)
+        .ok()
+        .flatten()
+        .or(cached_password)
+        .unwrap_or_default();
+    let ssh_password = CredentialManager::get_ssh_password(connection_id)
+        .ok()
+        .flatten();
+    (password, ssh_password)
+}
+
+/// Recover from a query that failed because its connection dropped
+///
+/// If the profile opted into `auto_reconnect`, rebuilds the connection and
+/// re-runs `sql` once. When the drop happened inside an open transaction
+/// the statement is *not* retried: the rest of the transaction is gone, so
+/// running it on its own would apply only part of the user's work.
+///
+/// If reconnecting is not possible the original error is returned.
+pub(crate) async fn retry_after_reconnect(
+    app: &AppHandle,
+    connection_id: &str,
+    sql: &str,
+    err: DbError,
+) -> Result<QueryResult, DbError> {
+    let in_transaction = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        state.open_transactions.contains(connection_id)
+    };
+
+    let connection = match reconnect(app, connection_id, true).await {
+        Ok(connection) => connection,
+        Err(_) => return Err(err),
+    };
+
+    if in_transaction {
+        return Err(DbError::ConnectionError(
+            "Connection was lost during an open transaction. The transaction was aborted; \
+             the connection has been re-established but the statement was not retried"
+                .to_string(),
+        ));
+    }
+
+    connection.execute_query(sql).await
+}
+
+/// Explicit transaction control statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TransactionControl {
+    /// `BEGIN` / `START TRANSACTION`
+    Begin,
+    /// `COMMIT` / `ROLLBACK` / `END` / `ABORT`
+    End,
+}
+
+/// Words that may follow `BEGIN` when it starts a transaction; anything else
+/// (`BEGIN TRY`, a SQL Server `BEGIN ... END` block) does not
+const BEGIN_MODIFIERS: &[&str] = &[
+    "TRANSACTION",
+    "TRAN",
+    "WORK",
+    "DISTRIBUTED",
+    "ISOLATION",
+    "READ",
+    "NOT",
+    "DEFERRABLE",
+    "DEFERRED",
+    "IMMEDIATE",
+    "EXCLUSIVE",
+];
+
+/// Whether `sql` leaves a transaction started or ended
+///
+/// Goes by the last transaction control statement of the script, so
+/// `BEGIN; UPDATE ...; COMMIT` is an `End`. `ROLLBACK TO [SAVEPOINT] x` only
+/// undoes part of the transaction, so it is not an `End`.
+pub(crate) fn transaction_control(sql: &str) -> Option<TransactionControl> {
+    let tokens: Vec<Token> = tokenize(sql).filter(|t| !t.is_trivia()).collect();
+    tokens
+        .split(|t| t.kind == TokenKind::Semicolon)
+        .rev()
+        .find_map(|statement| {
+            let words: Vec<String> = statement
+                .iter()
+                .take(3)
+                .map(|t| match t.kind {
+                    TokenKind::Word => t.text.to_ascii_uppercase(),
+                    _ => String::new(),
+                })
+                .collect();
+            statement_control(&words)
+        })
+}
+
+/// Transaction control done by a statement starting with `words`
+fn statement_control(words: &[String]) -> Option<TransactionControl> {
+    let word = |i: usize| words.get(i).map(String::as_str);
+    match word(0)? {
+        "BEGIN" if word(1).is_none_or(|w| BEGIN_MODIFIERS.contains(&w)) => {
+            Some(TransactionControl::Begin)
+        }
+        "START" if word(1) == Some("TRANSACTION") => Some(TransactionControl::Begin),
+        "ROLLBACK" => {
+            let next = match word(1) {
+                Some("TRANSACTION" | "TRAN" | "WORK") => word(2),
+                next => next,
+            };
+            (next != Some("TO")).then_some(TransactionControl::End)
+        }
+        "END" if word(1).is_none_or(|w| matches!(w, "TRANSACTION" | "WORK")) => {
+            Some(TransactionControl::End)
+        }
+        "COMMIT" | "ABORT" => Some(TransactionControl::End),
+        _ => None,
+    }
+}
+
+/// Update the open-transaction marker for `connection_id` after `sql` ran.
+///
+/// Only explicit transaction control statements are tracked; anything else
+/// leaves the marker unchanged.
+pub(crate) fn track_transaction(state: &mut AppState, connection_id: &str, sql: &str) {
+    match transaction_control(sql) {
+        Some(TransactionControl::Begin) => {
+            state.open_transactions.insert(connection_id.to_string());
+        }
+        Some(TransactionControl::End) => {
+            state.open_transactions.remove(connection_id);
+        }
+        None => {}
+    }
+}
+
+/// Manually rebuild a connection with its saved credentials
+///
+/// Works regardless of the profile's `auto_reconnect` setting.
+///
+/// # Arguments
+///
+/// * `connection_id` - Connection to rebuild (same as profile ID)
+/// * `app` - Tauri application handle
+#[tauri::command]
+pub async fn reconnect_connection(
+    connection_id: String,
+    app: AppHandle,
+) -> Result<String, DbError> {
+    reconnect(&app, &connection_id, false).await?;
+    Ok(connection_id)
+}
+
+/// Tear a connection down and build it again from its profile
+///
+/// For a connection that is still up but stuck, e.g. in a broken
+/// transaction, where reconnect-on-failure never kicks in. The connection
+/// and its transaction session are closed, the open transaction is dropped
+/// and queries tracked as running on it are logged as cancelled. The SSH
+/// tunnel is rebuilt and the connection reopened with the saved
+/// credentials under the same ID. Query calls still waiting on the old
+/// connection are abandoned and fail once it closes.
+///
+/// # Arguments
+///
+/// * `connection_id` - Connection to reset (same as profile ID)
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// `Connected` once the new connection is up. If it can't be opened the
+/// error is returned and the connection stays closed.
+#[tauri::command]
+pub async fn reset_connection(
+    connection_id: String,
+    app: AppHandle,
+) -> Result<ConnectionStatus, DbError> {
+    let state = app.state::<Mutex<AppState>>();
+
+    let (profile, cached_password, tunnel_manager, old) = {
+        let mut state = state.lock().unwrap();
+        let profile: ConnectionProfile = state
+            .get_profile(&connection_id)
+            .ok_or_else(|| {
+                DbError::NotFound(format!(
+                    "Profile for connection {} not found",
+                    connection_id
+                ))
+            })?
+            .clone();
+        if !state.reconnecting.insert(connection_id.clone()) {
+            return Err(DbError::ConnectionError(format!(
+                "Reconnect to {} is already in progress",
+                profile.name
+            )));
+        }
+
+        let mut old: Vec<Arc<dyn DatabaseDriver>> = state
+            .transaction_sessions
+            .remove(&connection_id)
+            .into_iter()
+            .collect();
+        old.extend(state.remove_connection(&connection_id));
+        cancel_running_queries(&mut state, &connection_id);
+        (
+            profile,
+            state.connection_passwords.get(&connection_id).cloned(),
+            state.ssh_tunnel_manager.clone(),
+            old,
+        )
+    };
+    let _guard = ReconnectGuard {
+        app: &app,
+        connection_id: &connection_id,
+    };
+
+    // A wedged connection may not close cleanly; don't wait on it forever
+    let closes = futures_util::future::join_all(old.iter().map(|driver| driver.close()));
+    if tokio::time::timeout(RESET_CLOSE_TIMEOUT, closes)
+        .await
+        .is_err()
+    {
+        eprintln!(
+            "Old connection {} did not close within {} seconds",
+            connection_id,
+            RESET_CLOSE_TIMEOUT.as_secs()
+        );
+    }
+    if tunnel_manager.has_tunnel(&connection_id).await {
+        let _ = tunnel_manager.close_tunnel(&connection_id).await;
+    }
+
+    let (password, ssh_password) = saved_credentials(&connection_id, cached_password);
+    let (host, port) = resolve_endpoint(&tunnel_manager, &profile, ssh_password).await?;
+    let connection = open_connection(&app, &profile, host, port, password).await?;
+
+    {
+        let mut state = state.lock().unwrap();
+        state.add_connection(connection_id.clone(), connection);
+        state.record_connection_health(&connection_id, ConnectionStatus::Connected);
+    }
+    spawn_keepalive(&app, &connection_id);
+
+    Ok(ConnectionStatus::Connected)
+}
+
+/// Drop the running-query entries of `connection_id`, logging the queries
+/// as cancelled
+fn cancel_running_queries(state: &mut AppState, connection_id: &str) {
+    let now = chrono::Utc::now();
+    let logger = &state.activity_logger;
+    state.running_queries.retain(|id, query| {
+        if query.connection_id != connection_id {
+            return true;
+        }
+        let elapsed_ms = (now - query.started_at).num_milliseconds().max(0) as u64;
+        logger.log_query_cancel(id, elapsed_ms);
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ErrorCode, QueryLog, QueryStatus, RunningQuery};
+
+    #[test]
+    fn test_is_connection_lost() {
+        assert!(is_connection_lost(&DbError::ConnectionError(
+            "x".to_string()
+        )));
+        assert!(is_connection_lost(&DbError::QueryError(
+            "error communicating with the server: Connection reset by peer".to_string()
+        )));
+        assert!(is_connection_lost(&DbError::QueryError(
+            "MySQL server has gone away".to_string()
+        )));
+        assert!(!is_connection_lost(&DbError::QueryError(
+            "syntax error at or near \"SELEC\"".to_string()
+        )));
+        assert!(!is_connection_lost(&DbError::AuthError("x".to_string())));
+
+        let server_error = |sqlstate: &str| DbError::DatabaseError {
+            message: "x".to_string(),
+            code: ErrorCode::SqlState(sqlstate.to_string()),
+            details: None,
+        };
+        assert!(is_connection_lost(&server_error("57P01")));
+        assert!(!is_connection_lost(&server_error("42601")));
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        assert_eq!(backoff_delay(1), Duration::ZERO);
+        assert_eq!(backoff_delay(2), Duration::from_secs(1));
+        assert_eq!(backoff_delay(3), Duration::from_secs(2));
+        assert_eq!(backoff_delay(4), Duration::from_secs(4));
+        assert_eq!(backoff_delay(20), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_track_transaction() {
+        let mut state = AppState::new();
+
+        track_transaction(&mut state, "c1", "BEGIN;");
+        assert!(state.open_transactions.contains("c1"));
+
+        track_transaction(&mut state, "c1", "select 1");
+        assert!(state.open_transactions.contains("c1"));
+
+        track_transaction(&mut state, "c1", "  commit");
+        assert!(!state.open_transactions.contains("c1"));
+
+        track_transaction(&mut state, "c1", "START TRANSACTION");
+        track_transaction(&mut state, "c1", "ROLLBACK");
+        assert!(!state.open_transactions.contains("c1"));
+    }
+
+    #[test]
+    fn test_cancel_running_queries() {
+        let mut state = AppState::new();
+        for (id, connection_id) in [("q1", "c1"), ("q2", "c2")] {
+            state.activity_logger.log_query_start(QueryLog::new(
+                id.to_string(),
+                connection_id.to_string(),
+                "Local".to_string(),
+                None,
+                "SELECT pg_sleep(60)".to_string(),
+            ));
+            state.running_queries.insert(
+                id.to_string(),
+                RunningQuery {
+                    id: id.to_string(),
+                    connection_id: connection_id.to_string(),
+                    tab_id: None,
+                    sql: "SELECT pg_sleep(60)".to_string(),
+                    started_at: chrono::Utc::now(),
+                    queued: false,
+                    in_transaction: false,
+                },
+            );
+        }
+
+        cancel_running_queries(&mut state, "c1");
+
+        assert!(!state.running_queries.contains_key("q1"));
+        assert!(state.running_queries.contains_key("q2"));
+        let status = |id: &str| state.activity_logger.get_log(id).unwrap().status;
+        assert_eq!(status("q1"), QueryStatus::Cancelled);
+        assert_eq!(status("q2"), QueryStatus::Running);
+    }
+
+    #[test]
+    fn test_transaction_control() {
+        assert_eq!(
+            transaction_control("begin"),
+            Some(TransactionControl::Begin)
+        );
+        assert_eq!(
+            transaction_control("\n  ROLLBACK;"),
+            Some(TransactionControl::End)
+        );
+        assert_eq!(transaction_control("rollback to savepoint a"), None);
+        assert_eq!(transaction_control("ROLLBACK TO a"), None);
+        assert_eq!(transaction_control(""), None);
+        assert_eq!(transaction_control("SELECT 1"), None);
+        assert_eq!(
+            transaction_control("START TRANSACTION READ ONLY"),
+            Some(TransactionControl::Begin)
+        );
+        assert_eq!(transaction_control("START REPLICA"), None);
+        assert_eq!(transaction_control("BEGIN TRY SELECT 1/0; END TRY"), None);
+    }
+
+    #[test]
+    fn test_transaction_control_of_scripts() {
+        // The last control statement decides
+        assert_eq!(
+            transaction_control("BEGIN; UPDATE t SET x = 1; COMMIT;"),
+            Some(TransactionControl::End)
+        );
+        assert_eq!(
+            transaction_control("COMMIT; begin work; DELETE FROM t"),
+            Some(TransactionControl::Begin)
+        );
+        assert_eq!(
+            transaction_control("BEGIN TRANSACTION; SAVEPOINT a; ROLLBACK TRANSACTION TO a"),
+            Some(TransactionControl::Begin)
+        );
+        assert_eq!(
+            transaction_control("SELECT 'BEGIN'; -- COMMIT\nUPDATE t SET x = 1"),
+            None
+        );
+    }
+}
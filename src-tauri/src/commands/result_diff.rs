@@ -0,0 +1,307 @@
+//! Query result diffing
+//!
+//! Compares two result sets the frontend already holds (e.g. the output of a
+//! query before and after a rewrite) and reports added, removed and changed
+//! rows. Everything runs in memory on the supplied `QueryResult`s — no
+//! database round-trip — so it also works against stale or read-only
+//! snapshots.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::drivers::QueryResult;
+use crate::models::DbError;
+
+/// A single column whose value differs between the two sides
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnChange {
+    /// Column name
+    pub column: String,
+
+    /// Value in the left (old) result
+    pub old_value: Value,
+
+    /// Value in the right (new) result
+    pub new_value: Value,
+}
+
+/// A row present on both sides (matched by key) whose values differ
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedRow {
+    /// Key column values identifying the row
+    pub key: Vec<Value>,
+
+    /// Columns that differ, in `ResultDiff::columns` order
+    pub changes: Vec<ColumnChange>,
+}
+
+/// Difference between two query results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultDiff {
+    /// Columns present in both results, in left order; only these are compared
+    pub columns: Vec<String>,
+
+    /// Columns that only exist in the left result
+    pub left_only_columns: Vec<String>,
+
+    /// Columns that only exist in the right result
+    pub right_only_columns: Vec<String>,
+
+    /// Columns used to match rows; empty when rows were matched by content
+    pub key_columns: Vec<String>,
+
+    /// Rows only in the right result (values for `columns`)
+    pub added: Vec<Vec<Value>>,
+
+    /// Rows only in the left result (values for `columns`)
+    pub removed: Vec<Vec<Value>>,
+
+    /// Rows matched by key whose values differ
+    pub changed: Vec<ChangedRow>,
+
+    /// Number of matched rows with identical values
+    pub unchanged_count: usize,
+}
+
+/// Project `row` onto the given column positions.
+fn project(row: &[Value], positions: &[usize]) -> Vec<Value> {
+    positions
+        .iter()
+        .map(|&i| row.get(i).cloned().unwrap_or(Value::Null))
+        .collect()
+}
+
+/// Hashable identity for a list of values.
+fn identity(values: &[Value]) -> String {
+    serde_json::to_string(values).unwrap_or_default()
+}
+
+/// Diff two results by key (or by whole-row content if `key_columns` is empty)
+///
+/// Rows sharing a key are paired in order of appearance, so duplicate keys
+/// (or duplicate rows, for content matching) are handled as a multiset
+/// rather than collapsed.
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` if a key column is missing from either
+/// result.
+pub fn diff_results(
+    left: &QueryResult,
+    right: &QueryResult,
+    key_columns: &[String],
+) -> Result<ResultDiff, DbError> {
+    let right_names: HashSet<&String> = right.columns.iter().collect();
+    let left_names: HashSet<&String> = left.columns.iter().collect();
+
+    let columns: Vec<String> = left
+        .columns
+        .iter()
+        .filter(|c| right_names.contains(c))
+        .cloned()
+        .collect();
+    let left_only_columns: Vec<String> = left
+        .columns
+        .iter()
+        .filter(|c| !right_names.contains(c))
+        .cloned()
+        .collect();
+    let right_only_columns: Vec<String> = right
+        .columns
+        .iter()
+        .filter(|c| !left_names.contains(c))
+        .cloned()
+        .collect();
+
+    for key in key_columns {
+        if !columns.contains(key) {
+            return Err(DbError::InvalidInput(format!(
+                "Key column {} must exist in both results",
+                key
+            )));
+        }
+    }
+
+    let positions = |result: &QueryResult, names: &[String]| -> Vec<usize> {
+        names
+            .iter()
+            .filter_map(|n| result.columns.iter().position(|c| c == n))
+            .collect()
+    };
+    let left_pos = positions(left, &columns);
+    let right_pos = positions(right, &columns);
+
+    // Without explicit keys, every compared column is part of the identity:
+    // rows can then only be added or removed, never "changed".
+    let key_names: &[String] = if key_columns.is_empty() {
+        &columns
+    } else {
+        key_columns
+    };
+    let left_key_pos = positions(left, key_names);
+    let right_key_pos = positions(right, key_names);
+
+    // Queue the right rows per key so duplicates are consumed in order.
+    let mut right_by_key: HashMap<String, VecDeque<usize>> = HashMap::new();
+    for (i, row) in right.rows.iter().enumerate() {
+        right_by_key
+            .entry(identity(&project(row, &right_key_pos)))
+            .or_default()
+            .push_back(i);
+    }
+
+    let mut matched_right = vec![false; right.rows.len()];
+    let mut removed = Vec::new();
+    let mut changed = Vec::new();
+    let mut unchanged_count = 0;
+
+    for row in &left.rows {
+        let key = project(row, &left_key_pos);
+        let partner = right_by_key
+            .get_mut(&identity(&key))
+            .and_then(|queue| queue.pop_front());
+
+        let Some(j) = partner else {
+            removed.push(project(row, &left_pos));
+            continue;
+        };
+        matched_right[j] = true;
+
+        let old_values = project(row, &left_pos);
+        let new_values = project(&right.rows[j], &right_pos);
+        let changes: Vec<ColumnChange> = columns
+            .iter()
+            .zip(old_values.into_iter().zip(new_values))
+            .filter(|(_, (old, new))| old != new)
+            .map(|(column, (old_value, new_value))| ColumnChange {
+                column: column.clone(),
+                old_value,
+                new_value,
+            })
+            .collect();
+
+        if changes.is_empty() {
+            unchanged_count += 1;
+        } else {
+            changed.push(ChangedRow { key, changes });
+        }
+    }
+
+    let added = right
+        .rows
+        .iter()
+        .zip(&matched_right)
+        .filter(|(_, matched)| !**matched)
+        .map(|(row, _)| project(row, &right_pos))
+        .collect();
+
+    Ok(ResultDiff {
+        columns,
+        left_only_columns,
+        right_only_columns,
+        key_columns: key_columns.to_vec(),
+        added,
+        removed,
+        changed,
+        unchanged_count,
+    })
+}
+
+/// Compare two query results
+///
+/// # Arguments
+///
+/// * `left` - The earlier ("before") result
+/// * `right` - The later ("after") result
+/// * `key_columns` - Columns identifying a row; empty to match whole rows
+///
+/// # Returns
+///
+/// A `ResultDiff` listing added, removed and changed rows, plus any columns
+/// present on only one side (those are reported but not compared).
+#[tauri::command]
+pub fn diff_query_results(
+    left: QueryResult,
+    right: QueryResult,
+    key_columns: Vec<String>,
+) -> Result<ResultDiff, DbError> {
+    diff_results(&left, &right, &key_columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn result(columns: &[&str], rows: Vec<Vec<Value>>) -> QueryResult {
+        QueryResult::with_data(columns.iter().map(|c| c.to_string()).collect(), rows)
+    }
+
+    #[test]
+    fn test_diff_by_key() {
+        let left = result(
+            &["id", "name"],
+            vec![
+                vec![json!(1), json!("a")],
+                vec![json!(2), json!("b")],
+                vec![json!(3), json!("c")],
+            ],
+        );
+        let right = result(
+            &["id", "name"],
+            vec![
+                vec![json!(1), json!("a")],
+                vec![json!(2), json!("B")],
+                vec![json!(4), json!("d")],
+            ],
+        );
+
+        let diff = diff_results(&left, &right, &["id".to_string()]).unwrap();
+        assert_eq!(diff.unchanged_count, 1);
+        assert_eq!(diff.removed, vec![vec![json!(3), json!("c")]]);
+        assert_eq!(diff.added, vec![vec![json!(4), json!("d")]]);
+        assert_eq!(
+            diff.changed,
+            vec![ChangedRow {
+                key: vec![json!(2)],
+                changes: vec![ColumnChange {
+                    column: "name".to_string(),
+                    old_value: json!("b"),
+                    new_value: json!("B"),
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_by_content_keeps_duplicates() {
+        let left = result(&["v"], vec![vec![json!(1)], vec![json!(1)]]);
+        let right = result(&["v"], vec![vec![json!(1)], vec![json!(2)]]);
+
+        let diff = diff_results(&left, &right, &[]).unwrap();
+        assert_eq!(diff.unchanged_count, 1);
+        assert_eq!(diff.removed, vec![vec![json!(1)]]);
+        assert_eq!(diff.added, vec![vec![json!(2)]]);
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_column_mismatch() {
+        let left = result(&["id", "old"], vec![vec![json!(1), json!("x")]]);
+        let right = result(&["new", "id"], vec![vec![json!("y"), json!(1)]]);
+
+        let diff = diff_results(&left, &right, &["id".to_string()]).unwrap();
+        assert_eq!(diff.columns, vec!["id".to_string()]);
+        assert_eq!(diff.left_only_columns, vec!["old".to_string()]);
+        assert_eq!(diff.right_only_columns, vec!["new".to_string()]);
+        assert_eq!(diff.unchanged_count, 1);
+
+        let missing = diff_results(&left, &right, &["old".to_string()]);
+        assert!(matches!(missing, Err(DbError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,290 @@
+//! Scheduled query commands
+//!
+//! Create, list and delete query schedules, and run one on demand. The
+//! background loop that fires due schedules lives in `state::schedule_runner`;
+//! both go through [`run_schedule`], which tags the activity log entry
+//! `scheduled`. Schedules only run while the app is open.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use tauri::{AppHandle, Manager, State};
+
+use super::query::{check_destructive_confirmation, run_tagged_query, QueryExecutionResult};
+use crate::models::{parse_cron, DbError, MissedRunPolicy, QuerySchedule};
+use crate::sql::template::render_template;
+use crate::state::AppState;
+
+/// Activity log tag of queries run by a schedule
+pub const SCHEDULED_TAG: &str = "scheduled";
+
+/// SQL a schedule runs: its own, or its snippet's current query
+///
+/// Snippet variables are filled in from their declared defaults, since
+/// there is nobody to prompt.
+fn schedule_sql(state: &AppState, schedule: &QuerySchedule) -> Result<String, DbError> {
+    if let Some(sql) = &schedule.sql {
+        return Ok(sql.clone());
+    }
+    let snippet_id = schedule.snippet_id.as_deref().unwrap_or_default();
+    let snippet = state
+        .get_snippet(snippet_id)
+        .ok_or_else(|| DbError::NotFound(format!("Snippet not found: {}", snippet_id)))?;
+    render_template(&snippet.query, &snippet.variable_values(&HashMap::new()))
+}
+
+/// Run a schedule once and record the outcome on it
+///
+/// Does not move `next_run_at`; the scheduler advances that before firing.
+pub(crate) async fn run_schedule(
+    app: &AppHandle,
+    schedule_id: &str,
+) -> Result<QueryExecutionResult, DbError> {
+    let state = app.state::<Mutex<AppState>>();
+    let (connection_id, sql) = {
+        let state_guard = state.lock().unwrap();
+        let schedule = state_guard.get_schedule(schedule_id).ok_or_else(|| {
+            DbError::NotFound(format!("Schedule not found: {}", schedule_id))
+        })?;
+        // A snippet may have changed since the schedule was confirmed
+        let sql = schedule_sql(&state_guard, schedule).and_then(|sql| {
+            if schedule.confirmed_sql.as_ref() != Some(&sql) {
+                check_destructive_confirmation(&state_guard, &schedule.connection_id, &sql)?;
+            }
+            Ok(sql)
+        });
+        (schedule.connection_id.clone(), sql)
+    };
+
+    let result = match sql {
+        Ok(sql) => {
+            run_tagged_query(
+                connection_id,
+                sql,
+                vec![SCHEDULED_TAG.to_string()],
+                state.clone(),
+                Some(app),
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    let mut state_guard = state.lock().unwrap();
+    if let Some(schedule) = state_guard.query_schedules.get_mut(schedule_id) {
+        schedule.last_run_at = Some(Utc::now());
+        schedule.last_error = result.as_ref().err().map(|e| e.to_string());
+    }
+    if let Err(e) = state_guard.save_schedules_to_store(app) {
+        eprintln!("Failed to save query schedules: {}", e);
+    }
+
+    result
+}
+
+/// Schedule a query
+///
+/// Runs either `sql` or the snippet `snippet_id` (exactly one must be
+/// given) against the connection whenever `cron_expr` fires. Expressions use
+/// UTC and may have five fields (`0 3 * * *`) or six with leading seconds.
+///
+/// Schedules only fire while DB Hive is open and the connection is
+/// connected; there is no background service. Runs that fell due while the
+/// app was closed are skipped, or run once at startup with
+/// `missedRuns: 'runOnce'`. A run on a connection that isn't open fails and
+/// is recorded in the schedule's `lastError`.
+///
+/// Scheduling a DELETE, DROP or TRUNCATE on a production connection needs
+/// `confirmed`, as running it with `execute_query` would. Runs are not asked
+/// again while the query stays the one confirmed; a run whose snippet has
+/// since turned destructive fails instead.
+///
+/// # Arguments
+///
+/// * `connection_id` - Connection (profile) ID to run against
+/// * `name` - User-provided name
+/// * `cron_expr` - When to run
+/// * `sql` - SQL to run
+/// * `snippet_id` - Snippet to run instead of `sql`
+/// * `missed_runs` - `skip` (default) or `runOnce`
+/// * `confirmed` - The user confirmed scheduling a destructive query
+/// * `state` - Application state
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// The new schedule, with its first `nextRunAt`
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const schedule = await invoke<QuerySchedule>('schedule_query', {
+///   connectionId: 'conn-123',
+///   name: 'Nightly cleanup',
+///   cronExpr: '0 3 * * *',
+///   sql: 'DELETE FROM sessions WHERE expires_at < now()',
+///   confirmed: true,
+/// });
+/// ```
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub fn schedule_query(
+    connection_id: String,
+    name: String,
+    cron_expr: String,
+    sql: Option<String>,
+    snippet_id: Option<String>,
+    missed_runs: Option<MissedRunPolicy>,
+    confirmed: Option<bool>,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<QuerySchedule, DbError> {
+    if name.trim().is_empty() {
+        return Err(DbError::InvalidInput(
+            "A schedule needs a name".to_string(),
+        ));
+    }
+    let sql = sql.filter(|s| !s.trim().is_empty());
+    if sql.is_some() == snippet_id.is_some() {
+        return Err(DbError::InvalidInput(
+            "A schedule runs either SQL or a snippet".to_string(),
+        ));
+    }
+    let next_run_at = parse_cron(&cron_expr)?.after(&Utc::now()).next();
+
+    let mut state = state.lock().unwrap();
+    if state.get_profile(&connection_id).is_none() {
+        return Err(DbError::NotFound(format!(
+            "Connection profile not found: {}",
+            connection_id
+        )));
+    }
+    if let Some(id) = &snippet_id {
+        if state.get_snippet(id).is_none() {
+            return Err(DbError::NotFound(format!("Snippet not found: {}", id)));
+        }
+    }
+
+    let mut schedule = QuerySchedule {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.trim().to_string(),
+        connection_id,
+        sql,
+        snippet_id,
+        cron_expr: cron_expr.trim().to_string(),
+        missed_runs: missed_runs.unwrap_or_default(),
+        confirmed_sql: None,
+        created_at: Utc::now().to_rfc3339(),
+        next_run_at,
+        last_run_at: None,
+        last_error: None,
+    };
+    let sql = schedule_sql(&state, &schedule)?;
+    if confirmed.unwrap_or(false) {
+        schedule.confirmed_sql = Some(sql);
+    } else {
+        check_destructive_confirmation(&state, &schedule.connection_id, &sql)?;
+    }
+    state.add_schedule(schedule.clone());
+    state.save_schedules_to_store(&app)?;
+
+    Ok(schedule)
+}
+
+/// List query schedules
+///
+/// # Arguments
+///
+/// * `connection_id` - Only list schedules of this connection
+/// * `state` - Application state
+///
+/// # Returns
+///
+/// Schedules ordered by next run, those with no upcoming run last
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const schedules = await invoke<QuerySchedule[]>('list_schedules', {});
+/// ```
+#[tauri::command]
+pub fn list_schedules(
+    connection_id: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<QuerySchedule>, DbError> {
+    let state = state.lock().unwrap();
+
+    let mut schedules: Vec<QuerySchedule> = state
+        .query_schedules
+        .values()
+        .filter(|s| connection_id.as_ref().is_none_or(|id| &s.connection_id == id))
+        .cloned()
+        .collect();
+
+    schedules.sort_by(|a, b| match (a.next_run_at, b.next_run_at) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.name.cmp(&b.name),
+    });
+
+    Ok(schedules)
+}
+
+/// Delete a query schedule
+///
+/// # Arguments
+///
+/// * `schedule_id` - ID of the schedule to delete
+/// * `state` - Application state
+/// * `app` - Tauri application handle
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// await invoke('delete_schedule', { scheduleId: 'schedule-uuid' });
+/// ```
+#[tauri::command]
+pub fn delete_schedule(
+    schedule_id: String,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<(), DbError> {
+    let mut state = state.lock().unwrap();
+    state
+        .remove_schedule(&schedule_id)
+        .ok_or_else(|| DbError::NotFound(format!("Schedule not found: {}", schedule_id)))?;
+    state.save_schedules_to_store(&app)?;
+
+    Ok(())
+}
+
+/// Run a schedule's query now
+///
+/// The run is logged and recorded like a scheduled one, but the next
+/// scheduled time is left as it was.
+///
+/// # Arguments
+///
+/// * `schedule_id` - ID of the schedule to run
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// The query result
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// const result = await invoke<QueryExecutionResult>('run_schedule_now', {
+///   scheduleId: 'schedule-uuid'
+/// });
+/// ```
+#[tauri::command]
+pub async fn run_schedule_now(
+    schedule_id: String,
+    app: AppHandle,
+) -> Result<QueryExecutionResult, DbError> {
+    run_schedule(&app, &schedule_id).await
+}
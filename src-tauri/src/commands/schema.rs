@@ -3,20 +3,27 @@
 //! This module provides Tauri commands for exploring database schemas,
 //! including listing databases, schemas, tables, and retrieving table details.
 
-use std::sync::Mutex;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
-use crate::models::{DatabaseInfo, DbError, ForeignKeyInfo, SchemaInfo, TableInfo, TableSchema};
-use crate::state::{AppState, MetadataCache};
+use crate::drivers::DatabaseDriver;
+use crate::models::{
+    ColumnInfo, DatabaseInfo, DbError, ForeignKeyInfo, SchemaInfo, TableInfo, TableSchema,
+};
+use crate::state::{AppState, MetadataCache, MetadataLevel, QueryPriority, QueryTicket};
 
 /// Get list of databases for a connection
 ///
 /// Returns all databases available on the connected database server.
-/// This command requires an active connection.
+/// This command requires an active connection. The list is cached for five
+/// minutes (see `get_cache_status`).
 ///
 /// # Arguments
 /// * `connection_id` - UUID of the active connection
+/// * `force_refresh` - If true, bypass the cache
 /// * `state` - Application state containing active connections
 ///
 /// # Returns
@@ -25,19 +32,19 @@ use crate::state::{AppState, MetadataCache};
 #[tauri::command]
 pub async fn get_databases(
     connection_id: String,
+    force_refresh: Option<bool>,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<Vec<DatabaseInfo>, DbError> {
-    // Clone the Arc<dyn DatabaseDriver> before awaiting to avoid holding the lock across await
-    let connection = {
-        let state = state.lock().unwrap();
-        state
-            .get_connection(&connection_id)
-            .ok_or_else(|| DbError::ConnectionError("Connection not found".to_string()))?
-            .clone()
-    };
-
-    // Call the driver method
-    connection.get_databases().await
+    load_level(
+        &state,
+        &connection_id,
+        MetadataLevel::Databases,
+        force_refresh,
+        |cache| Some(cache.databases.clone()),
+        |connection| async move { connection.get_databases().await },
+        |cache, databases| cache.databases = databases.clone(),
+    )
+    .await
 }
 
 /// Get list of schemas for a database
@@ -45,10 +52,12 @@ pub async fn get_databases(
 /// Returns all schemas/namespaces within a specific database.
 /// Some databases (like MySQL) don't have separate schemas, in which case
 /// this may return a single schema with the same name as the database.
+/// The list is cached for five minutes.
 ///
 /// # Arguments
 /// * `connection_id` - UUID of the active connection
 /// * `database` - Name of the database to query
+/// * `force_refresh` - If true, bypass the cache
 /// * `state` - Application state containing active connections
 ///
 /// # Returns
@@ -58,29 +67,34 @@ pub async fn get_databases(
 pub async fn get_schemas(
     connection_id: String,
     database: String,
+    force_refresh: Option<bool>,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<Vec<SchemaInfo>, DbError> {
-    // Clone the Arc<dyn DatabaseDriver> before awaiting to avoid holding the lock across await
-    let connection = {
-        let state = state.lock().unwrap();
-        state
-            .get_connection(&connection_id)
-            .ok_or_else(|| DbError::ConnectionError("Connection not found".to_string()))?
-            .clone()
-    };
-
-    // Call the driver method
-    connection.get_schemas(&database).await
+    let key = database.clone();
+    load_level(
+        &state,
+        &connection_id,
+        MetadataLevel::Schemas(database.clone()),
+        force_refresh,
+        |cache| cache.schemas.get(&key).cloned(),
+        |connection| async move { connection.get_schemas(&database).await },
+        |cache, schemas| {
+            cache.schemas.insert(key.clone(), schemas.clone());
+        },
+    )
+    .await
 }
 
 /// Get list of tables in a schema
 ///
 /// Returns all tables and views within a specific schema.
 /// Includes table type information (TABLE, VIEW, MATERIALIZED VIEW, etc.)
+/// The list is cached for five minutes, row counts included.
 ///
 /// # Arguments
 /// * `connection_id` - UUID of the active connection
 /// * `schema` - Name of the schema to query
+/// * `force_refresh` - If true, bypass the cache
 /// * `state` - Application state containing active connections
 ///
 /// # Returns
@@ -90,30 +104,92 @@ pub async fn get_schemas(
 pub async fn get_tables(
     connection_id: String,
     schema: String,
+    force_refresh: Option<bool>,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<Vec<TableInfo>, DbError> {
-    // Clone the Arc<dyn DatabaseDriver> before awaiting to avoid holding the lock across await
-    let connection = {
-        let state = state.lock().unwrap();
-        state
-            .get_connection(&connection_id)
-            .ok_or_else(|| DbError::ConnectionError("Connection not found".to_string()))?
-            .clone()
-    };
+    load_tables(&state, &connection_id, &schema, force_refresh).await
+}
 
-    // Call the driver method
-    connection.get_tables(&schema).await
+/// Tables of `schema`, from the cache while fresh
+async fn load_tables(
+    state: &Mutex<AppState>,
+    connection_id: &str,
+    schema: &str,
+    force_refresh: Option<bool>,
+) -> Result<Vec<TableInfo>, DbError> {
+    let schema = schema.to_string();
+    let key = schema.clone();
+    load_level(
+        state,
+        connection_id,
+        MetadataLevel::Tables(schema.clone()),
+        force_refresh,
+        |cache| cache.tables.get(&key).cloned(),
+        |connection| async move { connection.get_tables(&schema).await },
+        |cache, tables| {
+            cache.tables.insert(key.clone(), tables.clone());
+        },
+    )
+    .await
+}
+
+/// A table together with its pin status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableWithPin {
+    #[serde(flatten)]
+    pub table: TableInfo,
+
+    /// Whether the user pinned this table on the connection's profile
+    pub pinned: bool,
+}
+
+/// Get list of tables in a schema, with pinned tables first
+///
+/// Same as `get_tables`, but each entry carries its pin status and pinned
+/// tables are floated to the top (otherwise keeping the driver's order).
+///
+/// # Arguments
+/// * `connection_id` - UUID of the active connection
+/// * `schema` - Name of the schema to query
+/// * `force_refresh` - If true, bypass the cache
+/// * `state` - Application state containing active connections
+#[tauri::command]
+pub async fn get_tables_with_pins(
+    connection_id: String,
+    schema: String,
+    force_refresh: Option<bool>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<TableWithPin>, DbError> {
+    let tables = load_tables(&state, &connection_id, &schema, force_refresh).await?;
+    let profile = state.lock().unwrap().get_profile(&connection_id).cloned();
+
+    let mut tables: Vec<TableWithPin> = tables
+        .into_iter()
+        .map(|table| {
+            let pinned = profile
+                .as_ref()
+                .is_some_and(|p| p.is_table_pinned(&schema, &table.name));
+            TableWithPin { table, pinned }
+        })
+        .collect();
+    // Stable sort keeps the driver's ordering within each group.
+    tables.sort_by_key(|t| !t.pinned);
+
+    Ok(tables)
 }
 
 /// Get detailed schema for a specific table
 ///
 /// Returns complete table metadata including columns, indexes, and constraints.
-/// This is used when the user wants to see the full table structure.
+/// This is used when the user wants to see the full table structure. The
+/// result is cached for five minutes.
 ///
 /// # Arguments
 /// * `connection_id` - UUID of the active connection
 /// * `schema` - Name of the schema containing the table
 /// * `table` - Name of the table
+/// * `force_refresh` - If true, bypass the cache
 /// * `state` - Application state containing active connections
 ///
 /// # Returns
@@ -124,19 +200,188 @@ pub async fn get_table_schema(
     connection_id: String,
     schema: String,
     table: String,
+    force_refresh: Option<bool>,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<TableSchema, DbError> {
+    cached_table_schema(&state, &connection_id, &schema, &table, force_refresh).await
+}
+
+/// `get_table_schema` for use by other commands: served from the
+/// connection's `MetadataCache` when fresh, and cached once fetched
+pub(crate) async fn cached_table_schema(
+    state: &Mutex<AppState>,
+    connection_id: &str,
+    schema: &str,
+    table: &str,
+    force_refresh: Option<bool>,
+) -> Result<TableSchema, DbError> {
+    let key = format!("{}.{}", schema, table);
+    load_level(
+        state,
+        connection_id,
+        MetadataLevel::TableSchema(key.clone()),
+        force_refresh,
+        |cache| cache.table_schemas.get(&key).cloned(),
+        |connection| async move { connection.get_table_schema(schema, table).await },
+        |cache, table_schema| {
+            cache
+                .columns
+                .insert(key.clone(), table_schema.columns.clone());
+            cache
+                .table_schemas
+                .insert(key.clone(), table_schema.clone());
+        },
+    )
+    .await
+}
+
+/// Serve one level of the schema tree from the `MetadataCache`, or fetch it
+///
+/// A level fetched within the last five minutes is returned without a
+/// database round-trip unless `force_refresh` is set; `read` takes it from
+/// the cache. Otherwise `fetch` loads it and `write` stores it in the
+/// connection's cache, which is created on first use. Expanding a node
+/// therefore only ever loads that node.
+async fn load_level<T, Fut>(
+    state: &Mutex<AppState>,
+    connection_id: &str,
+    level: MetadataLevel,
+    force_refresh: Option<bool>,
+    read: impl FnOnce(&MetadataCache) -> Option<T>,
+    fetch: impl FnOnce(Arc<dyn DatabaseDriver>) -> Fut,
+    write: impl FnOnce(&mut MetadataCache, &T),
+) -> Result<T, DbError>
+where
+    Fut: Future<Output = Result<T, DbError>>,
+{
     // Clone the Arc<dyn DatabaseDriver> before awaiting to avoid holding the lock across await
     let connection = {
         let state = state.lock().unwrap();
+        if !force_refresh.unwrap_or(false) {
+            let cached = state
+                .metadata_cache
+                .get(connection_id)
+                .filter(|cache| cache.is_level_fresh(&level))
+                .and_then(read);
+            if let Some(value) = cached {
+                return Ok(value);
+            }
+        }
         state
-            .get_connection(&connection_id)
+            .get_connection(connection_id)
             .ok_or_else(|| DbError::ConnectionError("Connection not found".to_string()))?
             .clone()
     };
 
-    // Call the driver method
-    connection.get_table_schema(&schema, &table).await
+    let value = fetch(connection).await?;
+
+    let mut state = state.lock().unwrap();
+    // Skip connections closed while the fetch was in flight
+    if state.connections.contains_key(connection_id) {
+        let cache = state
+            .metadata_cache
+            .entry(connection_id.to_string())
+            .or_insert_with(MetadataCache::new);
+        write(cache, &value);
+        cache.record_level(level);
+    }
+
+    Ok(value)
+}
+
+/// What a connection's `MetadataCache` holds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataCacheStatus {
+    /// Whether the whole database is cached (used by autocomplete and search)
+    pub complete: bool,
+
+    /// Every cached level of the schema tree, oldest first
+    pub levels: Vec<CachedLevelStatus>,
+}
+
+/// One cached level of the schema tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedLevelStatus {
+    /// "databases", "schemas", "tables" or "tableSchema"
+    pub kind: String,
+
+    /// Database, schema or "schema.table" the level belongs to; `None` for
+    /// the database list
+    pub name: Option<String>,
+
+    /// When the level was fetched (RFC 3339)
+    pub fetched_at: String,
+
+    /// Whether the next request will go to the database
+    pub stale: bool,
+
+    /// Number of entries cached (columns, for a table schema)
+    pub item_count: usize,
+}
+
+/// Report which levels of the schema tree are cached for a connection
+///
+/// Returns an empty, incomplete status when nothing is cached yet.
+///
+/// # Arguments
+/// * `connection_id` - UUID of the connection
+/// * `state` - Application state containing the metadata cache
+#[tauri::command]
+pub fn get_cache_status(
+    connection_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<MetadataCacheStatus, DbError> {
+    let state = state.lock().unwrap();
+    let Some(cache) = state.metadata_cache.get(&connection_id) else {
+        return Ok(MetadataCacheStatus {
+            complete: false,
+            levels: Vec::new(),
+        });
+    };
+    Ok(cache_status(cache))
+}
+
+fn cache_status(cache: &MetadataCache) -> MetadataCacheStatus {
+    let mut levels: Vec<(SystemTime, CachedLevelStatus)> = cache
+        .fetched_at
+        .iter()
+        .map(|(level, at)| {
+            let (kind, name, item_count) = match level {
+                MetadataLevel::Databases => ("databases", None, cache.databases.len()),
+                MetadataLevel::Schemas(db) => (
+                    "schemas",
+                    Some(db.clone()),
+                    cache.schemas.get(db).map_or(0, Vec::len),
+                ),
+                MetadataLevel::Tables(schema) => (
+                    "tables",
+                    Some(schema.clone()),
+                    cache.tables.get(schema).map_or(0, Vec::len),
+                ),
+                MetadataLevel::TableSchema(key) => (
+                    "tableSchema",
+                    Some(key.clone()),
+                    cache.columns.get(key).map_or(0, Vec::len),
+                ),
+            };
+            let status = CachedLevelStatus {
+                kind: kind.to_string(),
+                name,
+                fetched_at: chrono::DateTime::<chrono::Utc>::from(*at).to_rfc3339(),
+                stale: !cache.is_level_fresh(level),
+                item_count,
+            };
+            (*at, status)
+        })
+        .collect();
+    levels.sort_by_key(|(at, _)| *at);
+
+    MetadataCacheStatus {
+        complete: cache.complete,
+        levels: levels.into_iter().map(|(_, status)| status).collect(),
+    }
 }
 
 /// Get foreign key relationships for a schema
@@ -247,17 +492,43 @@ pub async fn get_autocomplete_metadata(
     }
 
     // Otherwise, fetch fresh metadata
-    let connection = {
+    let (connection, scheduler) = {
         let state = state.lock().unwrap();
-        state
+        let connection = state
             .get_connection(&connection_id)
             .ok_or_else(|| DbError::ConnectionError("Connection not found".to_string()))?
-            .clone()
+            .clone();
+        (connection, Arc::clone(&state.query_scheduler))
     };
 
+    let ticket = scheduler
+        .enter(&connection_id, QueryPriority::Background)
+        .await;
+    let cache = fetch_metadata_cache(connection, &database, &ticket).await?;
+    let result = flatten_metadata_for_autocomplete(&cache);
+
+    // Store in cache
+    {
+        let mut state = state.lock().unwrap();
+        state.metadata_cache.insert(connection_id, cache);
+    }
+
+    Ok(result)
+}
+
+/// Fetch databases, schemas, tables and columns into a fresh `MetadataCache`
+///
+/// Shared by autocomplete and schema search. The caller decides where to
+/// store the result. This is background work: before each table's columns
+/// are fetched, interactive queries on the connection go first.
+pub(crate) async fn fetch_metadata_cache(
+    connection: Arc<dyn DatabaseDriver>,
+    database: &str,
+    ticket: &QueryTicket,
+) -> Result<MetadataCache, DbError> {
     // Fetch all metadata
     let databases = connection.get_databases().await?;
-    let schemas = connection.get_schemas(&database).await?;
+    let schemas = connection.get_schemas(database).await?;
 
     // Fetch tables and columns for all schemas.
     //
@@ -303,7 +574,7 @@ pub async fn get_autocomplete_metadata(
     let mut pending = pending.into_iter();
 
     let spawn_col = |set: &mut JoinSet<Result<(String, Vec<crate::models::ColumnInfo>), DbError>>,
-                     conn: std::sync::Arc<dyn crate::drivers::DatabaseDriver>,
+                     conn: Arc<dyn DatabaseDriver>,
                      schema_name: String,
                      table_name: String| {
         set.spawn(async move {
@@ -326,31 +597,400 @@ pub async fn get_autocomplete_metadata(
             joined.map_err(|e| DbError::InternalError(format!("Metadata task failed: {}", e)))??;
         all_columns.insert(key, columns);
         if let Some((s, t)) = pending.next() {
+            ticket.yield_to_interactive().await;
             spawn_col(&mut cols_set, connection.clone(), s, t);
         }
     }
 
-    // Build and cache metadata
+    // Build the cache entry
     let mut cache = MetadataCache::new();
     cache.databases = databases;
-    cache.schemas.insert(database.clone(), schemas);
+    cache.record_level(MetadataLevel::Databases);
+    cache.schemas.insert(database.to_string(), schemas);
+    cache.record_level(MetadataLevel::Schemas(database.to_string()));
 
     for (schema_name, tables) in all_tables {
+        cache.record_level(MetadataLevel::Tables(schema_name.clone()));
         cache.tables.insert(schema_name, tables);
     }
 
     cache.columns = all_columns;
+    cache.complete = true;
     cache.touch();
 
-    let result = flatten_metadata_for_autocomplete(&cache);
+    Ok(cache)
+}
 
-    // Store in cache
-    {
-        let mut state = state.lock().unwrap();
-        state.metadata_cache.insert(connection_id, cache);
+/// Kind of object matched by `search_schema_objects`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaObjectType {
+    Table,
+    View,
+    Column,
+}
+
+/// A schema object whose name matched a search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaSearchResult {
+    pub connection_id: String,
+    pub database: String,
+    pub schema: String,
+    pub object_type: SchemaObjectType,
+    pub name: String,
+    /// Owning table for columns; `None` for tables and views
+    pub parent: Option<String>,
+}
+
+/// Score how well `name` matches `query` (both compared case-insensitively)
+///
+/// Lower is better: exact match, then prefix, then substring (earlier is
+/// better), then a fuzzy in-order subsequence (tighter is better). Returns
+/// `None` if the query characters do not all appear in order.
+pub(crate) fn match_score(query: &str, name: &str) -> Option<u32> {
+    let query = query.to_lowercase();
+    let name = name.to_lowercase();
+
+    if name == query {
+        return Some(0);
+    }
+    if name.starts_with(&query) {
+        return Some(1);
+    }
+    if let Some(pos) = name.find(&query) {
+        return Some(100 + pos as u32);
     }
 
-    Ok(result)
+    // Fuzzy: every query char appears in order; penalise the span covered.
+    let mut chars = name.char_indices();
+    let mut first = None;
+    let mut last = 0;
+    for q in query.chars() {
+        let (i, _) = chars.find(|(_, c)| *c == q)?;
+        first.get_or_insert(i);
+        last = i;
+    }
+    let span = last - first.unwrap_or(0);
+    Some(1000 + span as u32)
+}
+
+/// Collect matches from one connection's cache, paired with their score.
+fn search_cache(
+    connection_id: &str,
+    cache: &MetadataCache,
+    query: &str,
+    object_types: &[SchemaObjectType],
+) -> Vec<(u32, SchemaSearchResult)> {
+    let wants = |t: SchemaObjectType| object_types.is_empty() || object_types.contains(&t);
+    let database = cache.schemas.keys().next().cloned().unwrap_or_default();
+    let mut matches = Vec::new();
+
+    for (schema, tables) in &cache.tables {
+        for table in tables {
+            let object_type = if table.table_type.to_uppercase().contains("VIEW") {
+                SchemaObjectType::View
+            } else {
+                SchemaObjectType::Table
+            };
+            if wants(object_type) {
+                if let Some(score) = match_score(query, &table.name) {
+                    matches.push((
+                        score,
+                        SchemaSearchResult {
+                            connection_id: connection_id.to_string(),
+                            database: database.clone(),
+                            schema: schema.clone(),
+                            object_type,
+                            name: table.name.clone(),
+                            parent: None,
+                        },
+                    ));
+                }
+            }
+
+            if !wants(SchemaObjectType::Column) {
+                continue;
+            }
+            let key = format!("{}.{}", schema, table.name);
+            for column in cache.columns.get(&key).into_iter().flatten() {
+                if let Some(score) = match_score(query, &column.name) {
+                    matches.push((
+                        score,
+                        SchemaSearchResult {
+                            connection_id: connection_id.to_string(),
+                            database: database.clone(),
+                            schema: schema.clone(),
+                            object_type: SchemaObjectType::Column,
+                            name: column.name.clone(),
+                            parent: Some(table.name.clone()),
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    matches
+}
+
+/// Search table, view and column names across several connections
+///
+/// Searches each connection's `MetadataCache`. Connections whose cache is
+/// missing or stale are fetched first, in parallel, and their caches
+/// refreshed so later searches and autocomplete reuse them.
+///
+/// # Arguments
+/// * `query` - Case-insensitive substring or fuzzy (in-order) pattern
+/// * `connection_ids` - Active connections to search
+/// * `object_types` - Kinds of objects to include (empty for all)
+/// * `state` - Application state containing active connections and cache
+///
+/// # Returns
+/// Matches sorted by match quality (exact, prefix, substring, fuzzy), then
+/// by name. Connections that fail to load metadata are skipped.
+#[tauri::command]
+pub async fn search_schema_objects(
+    query: String,
+    connection_ids: Vec<String>,
+    object_types: Vec<SchemaObjectType>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<SchemaSearchResult>, DbError> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Find connections whose cache needs (re)building.
+    let (cold, scheduler): (Vec<(String, Arc<dyn DatabaseDriver>, String)>, _) = {
+        let state = state.lock().unwrap();
+        let cold = connection_ids
+            .iter()
+            .filter(|id| match state.metadata_cache.get(*id) {
+                Some(cache) => cache.is_stale(),
+                None => true,
+            })
+            .filter_map(|id| {
+                let connection = state.get_connection(id)?.clone();
+                let database = state
+                    .get_profile(id)
+                    .and_then(|p| p.database.clone())
+                    .unwrap_or_default();
+                Some((id.clone(), connection, database))
+            })
+            .collect();
+        (cold, Arc::clone(&state.query_scheduler))
+    };
+
+    let mut fetches = tokio::task::JoinSet::new();
+    for (id, connection, database) in cold {
+        let scheduler = Arc::clone(&scheduler);
+        fetches.spawn(async move {
+            let ticket = scheduler.enter(&id, QueryPriority::Background).await;
+            let cache = fetch_metadata_cache(connection, &database, &ticket).await;
+            (id, cache)
+        });
+    }
+    while let Some(joined) = fetches.join_next().await {
+        if let Ok((id, Ok(cache))) = joined {
+            let mut state = state.lock().unwrap();
+            state.metadata_cache.insert(id, cache);
+        }
+    }
+
+    let mut matches = {
+        let state = state.lock().unwrap();
+        connection_ids
+            .iter()
+            .filter_map(|id| state.metadata_cache.get(id).map(|cache| (id, cache)))
+            .flat_map(|(id, cache)| search_cache(id, cache, &query, &object_types))
+            .collect::<Vec<_>>()
+    };
+
+    matches.sort_by(|(a_score, a), (b_score, b)| {
+        a_score
+            .cmp(b_score)
+            .then_with(|| a.name.len().cmp(&b.name.len()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(matches.into_iter().map(|(_, result)| result).collect())
+}
+
+/// A column of a table whose name matched `find_columns`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnMatch {
+    pub name: String,
+    pub data_type: String,
+    /// 1-based position of the column in the table
+    pub ordinal_position: usize,
+    pub nullable: bool,
+    pub is_primary_key: bool,
+}
+
+/// Match `query` against a table's `columns`, best match first
+///
+/// An empty query matches every column, in table order.
+fn match_columns(columns: &[ColumnInfo], query: &str) -> Vec<ColumnMatch> {
+    let mut matches: Vec<(u32, ColumnMatch)> = columns
+        .iter()
+        .enumerate()
+        .filter_map(|(i, column)| {
+            let score = if query.is_empty() {
+                0
+            } else {
+                match_score(query, &column.name)?
+            };
+            Some((
+                score,
+                ColumnMatch {
+                    name: column.name.clone(),
+                    data_type: column.data_type.clone(),
+                    ordinal_position: i + 1,
+                    nullable: column.nullable,
+                    is_primary_key: column.is_primary_key,
+                },
+            ))
+        })
+        .collect();
+    matches.sort_by_key(|(score, column)| (*score, column.ordinal_position));
+    matches.into_iter().map(|(_, column)| column).collect()
+}
+
+/// Find columns of one table by name
+///
+/// For navigating wide tables. Uses the columns in the connection's
+/// `MetadataCache`, even when stale, so it works without a round-trip once
+/// autocomplete has loaded. On a miss the table's schema is fetched and
+/// added to the cache.
+///
+/// # Arguments
+/// * `connection_id` - UUID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table to search
+/// * `query` - Case-insensitive substring or fuzzy (in-order) pattern; empty
+///   lists every column
+///
+/// # Returns
+/// Matching columns sorted by match quality, then by position in the table
+#[tauri::command]
+pub async fn find_columns(
+    connection_id: String,
+    schema: String,
+    table: String,
+    query: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<ColumnMatch>, DbError> {
+    let query = query.trim();
+    let key = format!("{}.{}", schema, table);
+
+    let connection = {
+        let state = state.lock().unwrap();
+        let cached = state
+            .metadata_cache
+            .get(&connection_id)
+            .and_then(|cache| cache.columns.get(&key));
+        if let Some(columns) = cached {
+            return Ok(match_columns(columns, query));
+        }
+        state
+            .get_connection(&connection_id)
+            .ok_or_else(|| DbError::ConnectionError("Connection not found".to_string()))?
+            .clone()
+    };
+
+    let columns = connection.get_table_schema(&schema, &table).await?.columns;
+    let matches = match_columns(&columns, query);
+
+    // Only fill in an existing cache: a cache holding just this table would
+    // pass for a complete one in autocomplete.
+    let mut state = state.lock().unwrap();
+    if let Some(cache) = state.metadata_cache.get_mut(&connection_id) {
+        cache.columns.insert(key, columns);
+    }
+
+    Ok(matches)
+}
+
+/// Foreign keys involving one column
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnReferences {
+    /// Foreign keys on the column, pointing to other tables
+    pub references: Vec<ForeignKeyInfo>,
+    /// Foreign keys in other tables (or this one) pointing to the column
+    pub referenced_by: Vec<ForeignKeyInfo>,
+}
+
+/// Sort `foreign_keys` into those from and those to `schema.table.column`
+fn column_references(
+    foreign_keys: Vec<ForeignKeyInfo>,
+    schema: &str,
+    table: &str,
+    column: &str,
+) -> ColumnReferences {
+    let mut refs = ColumnReferences::default();
+    for fk in foreign_keys {
+        let from =
+            fk.schema == schema && fk.table == table && fk.columns.iter().any(|c| c == column);
+        let to = fk.referenced_schema == schema
+            && fk.referenced_table == table
+            && fk.referenced_columns.iter().any(|c| c == column);
+        if to {
+            refs.referenced_by.push(fk.clone());
+        }
+        if from {
+            refs.references.push(fk);
+        }
+    }
+    refs
+}
+
+/// Get the foreign keys pointing to and from a column
+///
+/// Lets users trace a column's relationships without loading the whole
+/// foreign key graph. Foreign keys pointing to the column can live in any
+/// schema, so every schema in the connection's `MetadataCache` is searched
+/// along with the column's own; schemas that fail to load are skipped.
+///
+/// # Arguments
+/// * `connection_id` - UUID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table containing the column
+/// * `column` - Column to trace
+#[tauri::command]
+pub async fn get_column_references(
+    connection_id: String,
+    schema: String,
+    table: String,
+    column: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ColumnReferences, DbError> {
+    let (connection, mut schemas) = {
+        let state = state.lock().unwrap();
+        let connection = state
+            .get_connection(&connection_id)
+            .ok_or_else(|| DbError::ConnectionError("Connection not found".to_string()))?
+            .clone();
+        let schemas: Vec<String> = state
+            .metadata_cache
+            .get(&connection_id)
+            .map(|cache| cache.tables.keys().cloned().collect())
+            .unwrap_or_default();
+        (connection, schemas)
+    };
+    schemas.retain(|s| *s != schema);
+
+    // The column's own schema is required; the others only add incoming keys
+    let mut foreign_keys = connection.get_foreign_keys(&schema).await?;
+    for other in &schemas {
+        if let Ok(mut fks) = connection.get_foreign_keys(other).await {
+            foreign_keys.append(&mut fks);
+        }
+    }
+
+    Ok(column_references(foreign_keys, &schema, &table, &column))
 }
 
 /// Helper function to flatten metadata cache into autocomplete format
@@ -487,10 +1127,134 @@ mod tests {
         app
     }
 
+    #[test]
+    fn test_match_score_ordering() {
+        let exact = match_score("users", "Users").unwrap();
+        let prefix = match_score("user", "users").unwrap();
+        let substring = match_score("ser", "users").unwrap();
+        let fuzzy = match_score("usr", "users").unwrap();
+
+        assert!(exact < prefix);
+        assert!(prefix < substring);
+        assert!(substring < fuzzy);
+        assert_eq!(match_score("xyz", "users"), None);
+    }
+
+    #[test]
+    fn test_search_cache_filters_object_types() {
+        let mut cache = MetadataCache::new();
+        cache.schemas.insert("app".to_string(), vec![]);
+        cache.tables.insert(
+            "public".to_string(),
+            vec![
+                TableInfo::new("orders".to_string(), "public".to_string(), "TABLE".to_string()),
+                TableInfo::new("order_totals".to_string(), "public".to_string(), "VIEW".to_string()),
+            ],
+        );
+        cache.columns.insert(
+            "public.orders".to_string(),
+            vec![crate::models::ColumnInfo::new(
+                "order_id".to_string(),
+                "integer".to_string(),
+                false,
+            )],
+        );
+
+        let all = search_cache("c1", &cache, "order", &[]);
+        assert_eq!(all.len(), 3);
+
+        let columns = search_cache("c1", &cache, "order", &[SchemaObjectType::Column]);
+        assert_eq!(columns.len(), 1);
+        let (_, column) = &columns[0];
+        assert_eq!(column.name, "order_id");
+        assert_eq!(column.parent.as_deref(), Some("orders"));
+        assert_eq!(column.database, "app");
+
+        let views = search_cache("c1", &cache, "order", &[SchemaObjectType::View]);
+        assert_eq!(views.len(), 1);
+        assert_eq!(views[0].1.name, "order_totals");
+    }
+
+    #[test]
+    fn test_match_columns() {
+        let columns: Vec<ColumnInfo> = ["id", "customer_id", "created_at", "status"]
+            .iter()
+            .map(|name| ColumnInfo::new(name.to_string(), "integer".to_string(), true))
+            .collect();
+
+        let names = |query: &str| -> Vec<(String, usize)> {
+            match_columns(&columns, query)
+                .into_iter()
+                .map(|c| (c.name, c.ordinal_position))
+                .collect()
+        };
+        assert_eq!(
+            names("id"),
+            vec![("id".to_string(), 1), ("customer_id".to_string(), 2)]
+        );
+        assert_eq!(names("cat"), vec![("created_at".to_string(), 3)]);
+        assert_eq!(names("").len(), 4);
+        assert!(names("zzz").is_empty());
+    }
+
+    #[test]
+    fn test_column_references() {
+        let fk = |name: &str, table: &str, column: &str, ref_table: &str, ref_column: &str| {
+            ForeignKeyInfo::new(
+                name.to_string(),
+                table.to_string(),
+                "public".to_string(),
+                vec![column.to_string()],
+                ref_table.to_string(),
+                "public".to_string(),
+                vec![ref_column.to_string()],
+            )
+        };
+        let refs = column_references(
+            vec![
+                fk(
+                    "orders_customer_fk",
+                    "orders",
+                    "customer_id",
+                    "customers",
+                    "id",
+                ),
+                fk("payments_order_fk", "payments", "order_id", "orders", "id"),
+                fk("orders_parent_fk", "orders", "parent_id", "orders", "id"),
+                fk("items_product_fk", "items", "product_id", "products", "id"),
+            ],
+            "public",
+            "orders",
+            "id",
+        );
+        assert!(refs.references.is_empty());
+        let names: Vec<&str> = refs
+            .referenced_by
+            .iter()
+            .map(|fk| fk.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["payments_order_fk", "orders_parent_fk"]);
+
+        let refs = column_references(
+            vec![fk(
+                "orders_customer_fk",
+                "orders",
+                "customer_id",
+                "customers",
+                "id",
+            )],
+            "public",
+            "orders",
+            "customer_id",
+        );
+        assert_eq!(refs.references.len(), 1);
+        assert!(refs.referenced_by.is_empty());
+    }
+
     #[tokio::test]
     async fn test_get_databases() {
         let app = create_test_app();
-        let result = get_databases("test-conn-id".to_string(), app.state()).await;
+        let result = get_databases("test-conn-id".to_string(), None, app.state()).await;
 
         assert!(result.is_ok());
         let databases = result.unwrap();
@@ -501,7 +1265,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_databases_invalid_connection() {
         let app = create_test_app();
-        let result = get_databases("invalid-id".to_string(), app.state()).await;
+        let result = get_databases("invalid-id".to_string(), None, app.state()).await;
 
         assert!(result.is_err());
         match result.unwrap_err() {
@@ -516,6 +1280,7 @@ mod tests {
         let result = get_schemas(
             "test-conn-id".to_string(),
             "test_db".to_string(),
+            None,
             app.state(),
         )
         .await;
@@ -532,6 +1297,7 @@ mod tests {
         let result = get_tables(
             "test-conn-id".to_string(),
             "public".to_string(),
+            None,
             app.state(),
         )
         .await;
@@ -549,6 +1315,7 @@ mod tests {
             "test-conn-id".to_string(),
             "public".to_string(),
             "users".to_string(),
+            None,
             app.state(),
         )
         .await;
@@ -557,4 +1324,54 @@ mod tests {
         let schema = result.unwrap();
         assert_eq!(schema.table.name, "users");
     }
+
+    #[tokio::test]
+    async fn test_get_tables_served_from_cache() {
+        let app = create_test_app();
+        {
+            let state = app.state::<Mutex<AppState>>();
+            let mut state = state.lock().unwrap();
+            let mut cache = MetadataCache::new();
+            cache.tables.insert(
+                "public".to_string(),
+                vec![TableInfo::new(
+                    "cached".to_string(),
+                    "public".to_string(),
+                    "TABLE".to_string(),
+                )],
+            );
+            cache.record_level(MetadataLevel::Tables("public".to_string()));
+            state
+                .metadata_cache
+                .insert("test-conn-id".to_string(), cache);
+        }
+
+        let cached = get_tables(
+            "test-conn-id".to_string(),
+            "public".to_string(),
+            None,
+            app.state(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(cached[0].name, "cached");
+
+        let refreshed = get_tables(
+            "test-conn-id".to_string(),
+            "public".to_string(),
+            Some(true),
+            app.state(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(refreshed[0].name, "users");
+
+        let status = get_cache_status("test-conn-id".to_string(), app.state()).unwrap();
+        assert!(!status.complete);
+        assert_eq!(status.levels.len(), 1);
+        assert_eq!(status.levels[0].kind, "tables");
+        assert_eq!(status.levels[0].name.as_deref(), Some("public"));
+        assert_eq!(status.levels[0].item_count, 1);
+        assert!(!status.levels[0].stale);
+    }
 }
@@ -0,0 +1,217 @@
+//! Sequence / auto-increment commands
+//!
+//! Lists the counters that feed generated keys and lets the user move them,
+//! which is mostly needed after a bulk import leaves a sequence behind the
+//! highest key in the table. PostgreSQL-family connections expose real
+//! sequences; MySQL has one AUTO_INCREMENT counter per table, so each such
+//! table is reported as a "sequence" named after the table. Other drivers
+//! return an empty list.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::State;
+
+use super::procedures::{as_string, take_connection};
+use crate::models::{DbDriver, DbError};
+use crate::sql::literal::escape_literal;
+use crate::state::AppState;
+
+/// A sequence, or a MySQL table's AUTO_INCREMENT counter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SequenceInfo {
+    pub schema: String,
+    /// Sequence name (the table name for MySQL)
+    pub name: String,
+    /// Last value handed out; None if the sequence has not been used yet or
+    /// the current user may not read it
+    pub current_value: Option<i64>,
+    pub increment: i64,
+    pub min_value: Option<i64>,
+    pub max_value: Option<i64>,
+    /// Table and column that own the sequence (serial / identity columns)
+    pub owned_by_table: Option<String>,
+    pub owned_by_column: Option<String>,
+}
+
+/// List sequences (or AUTO_INCREMENT counters) in a schema.
+#[tauri::command]
+pub async fn get_sequences(
+    connection_id: String,
+    schema: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<SequenceInfo>, DbError> {
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+    let schema_lit = escape_literal(&schema, &driver);
+
+    if driver.is_postgres_compatible() {
+        // Ownership lives in pg_depend: 'a' for serial columns (OWNED BY),
+        // 'i' for identity columns
+        let sql = format!(
+            r#"SELECT s.schemaname, s.sequencename, s.last_value, s.increment_by,
+                      s.min_value, s.max_value, t.relname, a.attname
+               FROM pg_sequences s
+               JOIN pg_namespace n ON n.nspname = s.schemaname
+               JOIN pg_class c ON c.relnamespace = n.oid AND c.relname = s.sequencename
+               LEFT JOIN pg_depend d
+                   ON d.classid = 'pg_class'::regclass
+                  AND d.objid = c.oid
+                  AND d.refclassid = 'pg_class'::regclass
+                  AND d.deptype IN ('a', 'i')
+               LEFT JOIN pg_class t ON t.oid = d.refobjid
+               LEFT JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+               WHERE s.schemaname = {}
+               ORDER BY s.sequencename"#,
+            schema_lit
+        );
+        let res = conn.execute_query(&sql).await?;
+        return Ok(res
+            .rows
+            .iter()
+            .map(|row| SequenceInfo {
+                schema: row.first().and_then(as_string).unwrap_or_default(),
+                name: row.get(1).and_then(as_string).unwrap_or_default(),
+                current_value: row.get(2).and_then(as_i64),
+                increment: row.get(3).and_then(as_i64).unwrap_or(1),
+                min_value: row.get(4).and_then(as_i64),
+                max_value: row.get(5).and_then(as_i64),
+                owned_by_table: row.get(6).and_then(as_string),
+                owned_by_column: row.get(7).and_then(as_string),
+            })
+            .collect());
+    }
+
+    match driver {
+        DbDriver::MySql => {
+            // TABLES.AUTO_INCREMENT is the next value to be handed out. It can
+            // be stale until ANALYZE TABLE on MySQL 8 with cached statistics.
+            let sql = format!(
+                r#"SELECT t.TABLE_SCHEMA, t.TABLE_NAME, t.AUTO_INCREMENT, c.COLUMN_NAME
+                   FROM information_schema.TABLES t
+                   JOIN information_schema.COLUMNS c
+                       ON c.TABLE_SCHEMA = t.TABLE_SCHEMA
+                      AND c.TABLE_NAME = t.TABLE_NAME
+                      AND c.EXTRA LIKE '%auto_increment%'
+                   WHERE t.TABLE_SCHEMA = {}
+                     AND t.AUTO_INCREMENT IS NOT NULL
+                   ORDER BY t.TABLE_NAME"#,
+                schema_lit
+            );
+            let res = conn.execute_query(&sql).await?;
+            Ok(res
+                .rows
+                .iter()
+                .map(|row| {
+                    let table = row.get(1).and_then(as_string).unwrap_or_default();
+                    SequenceInfo {
+                        schema: row.first().and_then(as_string).unwrap_or_default(),
+                        name: table.clone(),
+                        current_value: row
+                            .get(2)
+                            .and_then(as_i64)
+                            .map(|next| next - 1)
+                            .filter(|last| *last > 0),
+                        increment: 1,
+                        min_value: Some(1),
+                        max_value: None,
+                        owned_by_table: Some(table),
+                        owned_by_column: row.get(3).and_then(as_string),
+                    }
+                })
+                .collect())
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Restart a sequence (or MySQL AUTO_INCREMENT counter) so the next
+/// generated value is `restart_with`. Returns the statement that was run.
+#[tauri::command]
+pub async fn reset_sequence(
+    connection_id: String,
+    schema: String,
+    name: String,
+    restart_with: i64,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<String, DbError> {
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+    let target = format!(
+        "{}.{}",
+        conn.quote_identifier(&schema),
+        conn.quote_identifier(&name)
+    );
+    let sql = reset_sequence_sql(&driver, &target, restart_with)?;
+    conn.execute_query(&sql).await?;
+    Ok(sql)
+}
+
+/// Build the statement that makes `restart_with` the next generated value
+fn reset_sequence_sql(
+    driver: &DbDriver,
+    target: &str,
+    restart_with: i64,
+) -> Result<String, DbError> {
+    if driver.is_postgres_compatible() {
+        return Ok(format!(
+            "ALTER SEQUENCE {} RESTART WITH {}",
+            target, restart_with
+        ));
+    }
+    match driver {
+        DbDriver::MySql => {
+            if restart_with < 1 {
+                return Err(DbError::InvalidInput(
+                    "AUTO_INCREMENT must be at least 1".to_string(),
+                ));
+            }
+            Ok(format!(
+                "ALTER TABLE {} AUTO_INCREMENT = {}",
+                target, restart_with
+            ))
+        }
+        _ => Err(DbError::InvalidInput(
+            "Resetting sequences is not supported for this driver".to_string(),
+        )),
+    }
+}
+
+/// Read an integer that may come back as a number or as text (numeric/bigint)
+fn as_i64(v: &Value) -> Option<i64> {
+    match v {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reset_sequence_sql() {
+        assert_eq!(
+            reset_sequence_sql(&DbDriver::Postgres, "\"public\".\"users_id_seq\"", 42).unwrap(),
+            "ALTER SEQUENCE \"public\".\"users_id_seq\" RESTART WITH 42"
+        );
+        assert_eq!(
+            reset_sequence_sql(&DbDriver::MySql, "`shop`.`orders`", 1001).unwrap(),
+            "ALTER TABLE `shop`.`orders` AUTO_INCREMENT = 1001"
+        );
+        assert!(matches!(
+            reset_sequence_sql(&DbDriver::MySql, "`shop`.`orders`", 0),
+            Err(DbError::InvalidInput(_))
+        ));
+        assert!(reset_sequence_sql(&DbDriver::Sqlite, "\"t\"", 1).is_err());
+    }
+
+    #[test]
+    fn test_as_i64() {
+        assert_eq!(as_i64(&json!(7)), Some(7));
+        assert_eq!(as_i64(&json!("9223372036854775807")), Some(i64::MAX));
+        assert_eq!(as_i64(&Value::Null), None);
+    }
+}
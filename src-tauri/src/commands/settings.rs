@@ -3,7 +3,7 @@
 //! Tauri commands for managing application settings including loading,
 //! updating, and resetting settings to defaults.
 
-use crate::models::{AppSettings, DbError};
+use crate::models::{AppSettings, DbError, CURRENT_SETTINGS_VERSION};
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 
@@ -21,44 +21,52 @@ use tauri_plugin_store::StoreExt;
 /// Current application settings or defaults if not found
 #[tauri::command]
 pub async fn get_settings(app: AppHandle) -> Result<AppSettings, DbError> {
+    load_settings(&app)
+}
+
+/// Load settings from the store, falling back to defaults
+///
+/// Shared by `get_settings` and backend tasks (e.g. the connection health
+/// monitor) that need to honour user preferences. Settings saved by an
+/// older version are migrated and written back; settings that can't be
+/// read at all are logged and replaced by defaults rather than failing.
+pub(crate) fn load_settings(app: &AppHandle) -> Result<AppSettings, DbError> {
     let store = app
         .store("settings.json")
         .map_err(|e| DbError::InternalError(format!("Failed to access settings store: {}", e)))?;
 
-    // Try to load settings from store
-    if let Some(settings_value) = store.get("settings") {
-        // Deserialize settings
-        let settings: AppSettings = serde_json::from_value(settings_value.clone())
-            .map_err(|e| DbError::InternalError(format!("Failed to deserialize settings: {}", e)))?;
+    // No settings found, return defaults
+    let Some(stored) = store.get("settings") else {
+        return Ok(AppSettings::default());
+    };
 
-        Ok(settings)
-    } else {
-        // No settings found, return defaults
-        Ok(AppSettings::default())
+    let stored_version = stored.get("schemaVersion").and_then(|v| v.as_u64());
+    match AppSettings::migrate(stored) {
+        Ok(settings) => {
+            if stored_version.is_none_or(|v| v < u64::from(CURRENT_SETTINGS_VERSION)) {
+                if let Err(e) = save_settings(app, &settings) {
+                    eprintln!("Failed to save migrated settings: {}", e);
+                }
+            }
+            Ok(settings)
+        }
+        Err(e) => {
+            eprintln!("Failed to load settings, using defaults: {}", e);
+            Ok(AppSettings::default())
+        }
     }
 }
 
-/// Update application settings
-///
-/// Saves the provided settings to persistent storage.
-///
-/// # Arguments
-///
-/// * `app` - Tauri application handle
-/// * `settings` - Updated settings to save
-///
-/// # Returns
-///
-/// Ok(()) if settings were saved successfully
-#[tauri::command]
-pub async fn update_settings(app: AppHandle, settings: AppSettings) -> Result<(), DbError> {
+/// Write settings to the store, stamped with the current version
+fn save_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), DbError> {
     let store = app
         .store("settings.json")
         .map_err(|e| DbError::InternalError(format!("Failed to access settings store: {}", e)))?;
 
     // Serialize settings
-    let settings_value = serde_json::to_value(&settings)
+    let mut settings_value = serde_json::to_value(settings)
         .map_err(|e| DbError::InternalError(format!("Failed to serialize settings: {}", e)))?;
+    settings_value["schemaVersion"] = CURRENT_SETTINGS_VERSION.into();
 
     // Save to store
     store.set("settings", settings_value);
@@ -71,6 +79,25 @@ pub async fn update_settings(app: AppHandle, settings: AppSettings) -> Result<()
     Ok(())
 }
 
+/// Update application settings
+///
+/// Validates the provided settings and saves them to persistent storage.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `settings` - Updated settings to save
+///
+/// # Returns
+///
+/// Ok(()) if settings were saved successfully, or `InvalidInput` naming the
+/// first out-of-range field
+#[tauri::command]
+pub async fn update_settings(app: AppHandle, settings: AppSettings) -> Result<(), DbError> {
+    settings.validate()?;
+    save_settings(&app, &settings)
+}
+
 /// Reset settings to defaults
 ///
 /// Replaces current settings with default values and saves them.
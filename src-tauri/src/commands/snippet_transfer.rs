@@ -0,0 +1,356 @@
+//! Query snippet import/export commands
+//!
+//! Shares snippets between people as a JSON bundle that can live in version
+//! control. Connection scopes are dropped on export since profile IDs are
+//! local to one machine; driver scopes, tags and variables are kept.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+use super::profile_transfer::ConflictStrategy;
+use crate::models::{DbError, QuerySnippet};
+use crate::state::AppState;
+
+/// `format` value identifying a snippet bundle
+const BUNDLE_FORMAT: &str = "dbhive-snippets";
+
+/// Bundle layout version written by this build
+const BUNDLE_VERSION: u32 = 1;
+
+/// Portable file holding query snippets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetBundle {
+    /// Always `dbhive-snippets`
+    pub format: String,
+
+    /// Layout version of the bundle
+    pub version: u32,
+
+    /// Export timestamp (ISO 8601)
+    pub exported_at: String,
+
+    /// Exported snippets, without connection scopes
+    pub snippets: Vec<QuerySnippet>,
+}
+
+/// Outcome of a snippet import
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetImportReport {
+    /// Snippets added as new snippets (including renamed ones)
+    pub imported: usize,
+
+    /// Existing snippets updated from imported ones
+    pub overwritten: usize,
+
+    /// Imported snippets given a new name because of a conflict
+    pub renamed: usize,
+
+    /// Imported snippets ignored because of a conflict
+    pub skipped: usize,
+}
+
+/// Parse and validate a bundle
+fn parse_bundle(content: &str) -> Result<SnippetBundle, DbError> {
+    let bundle: SnippetBundle = serde_json::from_str(content)
+        .map_err(|e| DbError::ImportError(format!("Not a valid snippet bundle: {}", e)))?;
+
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(DbError::ImportError(format!(
+            "Unexpected bundle format '{}'",
+            bundle.format
+        )));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(DbError::ImportError(format!(
+            "Bundle version {} was created by a newer version of DB Hive",
+            bundle.version
+        )));
+    }
+    if let Some(snippet) = bundle
+        .snippets
+        .iter()
+        .find(|s| s.name.trim().is_empty() || s.query.trim().is_empty())
+    {
+        return Err(DbError::ImportError(format!(
+            "Snippet '{}' is missing a name or query",
+            snippet.id
+        )));
+    }
+
+    Ok(bundle)
+}
+
+/// First of "`name` (imported)", "`name` (imported 2)", ... not in `names`
+fn imported_name(name: &str, names: &HashSet<String>) -> String {
+    let mut candidate = format!("{} (imported)", name);
+    let mut n = 2;
+    while names.contains(&candidate) {
+        candidate = format!("{} (imported {})", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+/// Append the tags of `incoming` missing from `tags`
+fn merge_tags(tags: Option<Vec<String>>, incoming: Option<Vec<String>>) -> Option<Vec<String>> {
+    match (tags, incoming) {
+        (Some(mut tags), Some(incoming)) => {
+            for tag in incoming {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+            Some(tags)
+        }
+        (tags, incoming) => tags.or(incoming),
+    }
+}
+
+/// Decide which snippets to store
+///
+/// Bundle IDs are never reused; a conflict is an existing snippet with the
+/// same name. Overwriting keeps the existing snippet's ID and connection
+/// scope, takes the imported query, description, variables and driver, and
+/// merges the tags. Snippets repeated within the bundle conflict with each
+/// other just like with existing snippets.
+fn plan_import(
+    existing: &HashMap<String, QuerySnippet>,
+    snippets: Vec<QuerySnippet>,
+    strategy: ConflictStrategy,
+    report: &mut SnippetImportReport,
+) -> Vec<QuerySnippet> {
+    let mut by_name: HashMap<String, QuerySnippet> = existing
+        .values()
+        .map(|s| (s.name.clone(), s.clone()))
+        .collect();
+    let mut names: HashSet<String> = by_name.keys().cloned().collect();
+    let mut planned: Vec<QuerySnippet> = Vec::new();
+    let now = chrono::Utc::now().to_rfc3339();
+
+    for mut snippet in snippets {
+        snippet.connection_id = None;
+
+        let stored = match by_name.get(&snippet.name) {
+            Some(current) => match strategy {
+                ConflictStrategy::Skip => {
+                    report.skipped += 1;
+                    continue;
+                }
+                ConflictStrategy::Overwrite => {
+                    let mut updated = current.clone();
+                    updated.query = snippet.query;
+                    updated.description = snippet.description;
+                    updated.variables = snippet.variables;
+                    updated.driver = snippet.driver;
+                    updated.tags = merge_tags(updated.tags, snippet.tags);
+                    updated.updated_at = now.clone();
+                    planned.retain(|s| s.id != updated.id);
+                    report.overwritten += 1;
+                    updated
+                }
+                ConflictStrategy::Rename => {
+                    snippet.name = imported_name(&snippet.name, &names);
+                    snippet.id = Uuid::new_v4().to_string();
+                    snippet.created_at = now.clone();
+                    snippet.updated_at = now.clone();
+                    report.renamed += 1;
+                    report.imported += 1;
+                    snippet
+                }
+            },
+            None => {
+                snippet.id = Uuid::new_v4().to_string();
+                snippet.created_at = now.clone();
+                snippet.updated_at = now.clone();
+                report.imported += 1;
+                snippet
+            }
+        };
+
+        names.insert(stored.name.clone());
+        by_name.insert(stored.name.clone(), stored.clone());
+        planned.push(stored);
+    }
+
+    planned
+}
+
+/// Export query snippets to a portable file
+///
+/// # Arguments
+///
+/// * `file_path` - Destination path of the JSON bundle
+/// * `snippet_ids` - Snippets to export; all snippets when omitted
+/// * `state` - Application state
+///
+/// # Returns
+///
+/// Number of exported snippets
+///
+/// # Example
+///
+/// ```typescript
+/// const count = await invoke<number>('export_snippets', {
+///     filePath: '/home/me/team-queries/snippets.json',
+///     snippetIds: ['snippet-1', 'snippet-2']
+/// });
+/// ```
+#[tauri::command]
+pub fn export_snippets(
+    file_path: String,
+    snippet_ids: Option<Vec<String>>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<usize, DbError> {
+    let mut snippets: Vec<QuerySnippet> = {
+        let state_guard = state.lock().unwrap();
+        match &snippet_ids {
+            Some(ids) => ids
+                .iter()
+                .map(|id| {
+                    state_guard.get_snippet(id).cloned().ok_or_else(|| {
+                        DbError::NotFound(format!("Snippet not found: {}", id))
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+            None => state_guard.get_all_snippets(),
+        }
+    };
+
+    // Stable order keeps diffs small when the bundle is in version control
+    snippets.sort_by(|a, b| a.name.cmp(&b.name));
+    for snippet in &mut snippets {
+        snippet.connection_id = None;
+    }
+
+    let bundle = SnippetBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        snippets,
+    };
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| DbError::InternalError(format!("Failed to serialize snippets: {}", e)))?;
+    std::fs::write(&file_path, json)
+        .map_err(|e| DbError::InternalError(format!("Failed to write {}: {}", file_path, e)))?;
+
+    Ok(bundle.snippets.len())
+}
+
+/// Import query snippets from a bundle written by `export_snippets`
+///
+/// Imported snippets get new IDs. A snippet named like an existing one is
+/// handled per `conflict_strategy`; `overwrite` updates the existing snippet
+/// in place and merges the tags of both.
+///
+/// # Arguments
+///
+/// * `file_path` - Path of the JSON bundle
+/// * `conflict_strategy` - `skip`, `overwrite` or `rename`
+/// * `state` - Application state
+/// * `app` - Application handle
+///
+/// # Errors
+///
+/// Returns `DbError::ImportError` for an unreadable or invalid bundle,
+/// including one written by a newer version. Nothing is imported then.
+#[tauri::command]
+pub fn import_snippets(
+    file_path: String,
+    conflict_strategy: ConflictStrategy,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<SnippetImportReport, DbError> {
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| DbError::ImportError(format!("Failed to read {}: {}", file_path, e)))?;
+    let bundle = parse_bundle(&content)?;
+
+    let mut report = SnippetImportReport::default();
+    let mut state_guard = state.lock().unwrap();
+    let planned = plan_import(
+        &state_guard.query_snippets,
+        bundle.snippets,
+        conflict_strategy,
+        &mut report,
+    );
+    for snippet in planned {
+        state_guard.add_snippet(snippet);
+    }
+    state_guard.save_snippets_to_store(&app)?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(name: &str, tags: &[&str]) -> QuerySnippet {
+        QuerySnippet::new(
+            name.to_string(),
+            format!("SELECT '{}'", name),
+            None,
+            Some(tags.iter().map(|t| t.to_string()).collect()),
+        )
+    }
+
+    #[test]
+    fn test_parse_bundle_validates() {
+        let mut bundle = SnippetBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            exported_at: "2025-11-19T12:00:00Z".to_string(),
+            snippets: vec![snippet("Recent", &[])],
+        };
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert_eq!(parse_bundle(&json).unwrap().snippets.len(), 1);
+
+        bundle.version = BUNDLE_VERSION + 1;
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(matches!(parse_bundle(&json), Err(DbError::ImportError(_))));
+
+        bundle.version = BUNDLE_VERSION;
+        bundle.format = "dbhive-connections".to_string();
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(matches!(parse_bundle(&json), Err(DbError::ImportError(_))));
+    }
+
+    #[test]
+    fn test_plan_import_strategies() {
+        let current = snippet("Recent", &["reports"]);
+        let current_id = current.id.clone();
+        let mut existing = HashMap::new();
+        existing.insert(current.id.clone(), current);
+        let incoming = || vec![snippet("Recent", &["reports", "daily"]), snippet("New", &[])];
+
+        let mut report = SnippetImportReport::default();
+        let planned = plan_import(&existing, incoming(), ConflictStrategy::Skip, &mut report);
+        assert_eq!(planned.len(), 1);
+        assert_eq!((report.imported, report.skipped), (1, 1));
+
+        let mut report = SnippetImportReport::default();
+        let planned = plan_import(
+            &existing,
+            incoming(),
+            ConflictStrategy::Overwrite,
+            &mut report,
+        );
+        assert_eq!(planned[0].id, current_id);
+        assert_eq!(
+            planned[0].tags,
+            Some(vec!["reports".to_string(), "daily".to_string()])
+        );
+        assert_eq!((report.imported, report.overwritten), (1, 1));
+
+        let mut report = SnippetImportReport::default();
+        let planned = plan_import(&existing, incoming(), ConflictStrategy::Rename, &mut report);
+        assert_ne!(planned[0].id, current_id);
+        assert_eq!(planned[0].name, "Recent (imported)");
+        assert_eq!((report.imported, report.renamed), (2, 1));
+    }
+}
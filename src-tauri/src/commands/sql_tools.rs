@@ -0,0 +1,156 @@
+//! SQL editor tooling commands
+//!
+//! Pure text transformations on SQL for the editor. None of these touch a
+//! database connection, so they work offline and are safe to expose to
+//! plugins.
+
+use crate::commands::settings::load_settings;
+use crate::models::{DbDriver, DbError};
+use crate::sql::filter::FilterNode;
+use crate::sql::format::FormatOptions;
+use crate::sql::lint::LintDiagnostic;
+use crate::sql::normalize::QueryFingerprint;
+use serde_json::Value;
+use tauri::AppHandle;
+
+/// Pretty-print SQL
+///
+/// Normalizes keyword case, puts each clause on its own line and indents
+/// clause bodies and subqueries. Strings, quoted identifiers and comments are
+/// kept verbatim. Formatting is idempotent: formatting the output again
+/// returns it unchanged.
+///
+/// # Arguments
+///
+/// * `sql` - SQL text, possibly several statements
+/// * `dialect` - Driver whose quoting rules apply (MySQL backslash escapes,
+///   SQL Server `[brackets]`); standard SQL quoting when omitted
+/// * `options` - Keyword case, indent width and statement spacing
+///
+/// # Example
+///
+/// ```typescript
+/// const formatted = await invoke<string>('format_sql', {
+///     sql: 'select a,b from t where x=1',
+///     dialect: 'Postgres',
+///     options: { uppercaseKeywords: true, indentWidth: 2, linesBetweenQueries: 2 }
+/// });
+/// ```
+#[tauri::command]
+pub fn format_sql(
+    sql: String,
+    dialect: Option<DbDriver>,
+    options: Option<FormatOptions>,
+) -> Result<String, DbError> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
+
+    Ok(crate::sql::format::format_sql(&sql, dialect.as_ref(), &options))
+}
+
+/// Lint SQL for common anti-patterns
+///
+/// Reports `SELECT *`, implicit cross joins, comparisons with NULL using
+/// `=`/`<>`, functions wrapped around columns in WHERE, and UPDATE/DELETE
+/// without WHERE. Which rules run is controlled by the `lint` section of the
+/// application settings. Spans are byte offsets into `sql`.
+///
+/// # Arguments
+///
+/// * `sql` - SQL text, possibly several statements
+/// * `dialect` - Driver whose quoting rules apply; standard SQL when omitted
+/// * `app` - Tauri application handle, used to read the lint settings
+///
+/// # Example
+///
+/// ```typescript
+/// const diagnostics = await invoke<LintDiagnostic[]>('lint_sql', {
+///     sql: 'DELETE FROM users',
+///     dialect: 'Postgres'
+/// });
+/// // [{ severity: 'warning', span: { start: 0, end: 6 }, rule: 'missingWhere', message: '...' }]
+/// ```
+#[tauri::command]
+pub fn lint_sql(
+    sql: String,
+    dialect: Option<DbDriver>,
+    app: AppHandle,
+) -> Result<Vec<LintDiagnostic>, DbError> {
+    let settings = load_settings(&app)?;
+
+    Ok(crate::sql::lint::lint_sql(
+        &sql,
+        dialect.as_ref(),
+        &settings.lint,
+    ))
+}
+
+/// Compute a query's canonical form and fingerprint
+///
+/// Queries that differ only in literal values, `IN` list length, keyword
+/// case, whitespace or comments get the same fingerprint, which makes it
+/// suitable for grouping and deduplicating queries.
+///
+/// # Arguments
+///
+/// * `sql` - SQL text, possibly several statements
+/// * `dialect` - Driver whose quoting rules apply; standard SQL when omitted
+///
+/// # Example
+///
+/// ```typescript
+/// const { normalized, fingerprint } = await invoke<QueryFingerprint>('fingerprint_query', {
+///     sql: "SELECT * FROM users WHERE id IN (1, 2, 3)"
+/// });
+/// // normalized: 'select * from users where id in (...)'
+/// ```
+#[tauri::command]
+pub fn fingerprint_query(
+    sql: String,
+    dialect: Option<DbDriver>,
+) -> Result<QueryFingerprint, DbError> {
+    Ok(crate::sql::normalize::fingerprint_query(
+        &sql,
+        dialect.as_ref(),
+    ))
+}
+
+/// Compile structured column filters into a parameterized WHERE clause
+///
+/// Lets the data grid's filter UI preview the SQL its filters produce. Column
+/// names are quoted and values become placeholders in the dialect's native
+/// syntax (`$1`, `?`, `@P1`); the values come back separately, in placeholder
+/// order. Top-level filters are combined with AND; nest a group to use OR.
+///
+/// # Arguments
+///
+/// * `filters` - Column filters and nested `{ join, filters }` groups
+/// * `dialect` - Driver whose quoting and placeholder syntax to use
+///
+/// # Returns
+///
+/// `[sql, params]`, where `sql` is empty when there is nothing to filter on.
+///
+/// # Example
+///
+/// ```typescript
+/// const [sql, params] = await invoke<[string, unknown[]]>('build_where_clause', {
+///     filters: [
+///         { column: 'status', operator: 'eq', value: 'active' },
+///         { join: 'or', filters: [
+///             { column: 'age', operator: 'gt', value: 65 },
+///             { column: 'age', operator: 'lt', value: 18 }
+///         ] }
+///     ],
+///     dialect: 'Postgres'
+/// });
+/// // sql: 'WHERE "status" = $1 AND ("age" > $2 OR "age" < $3)'
+/// // params: ['active', 65, 18]
+/// ```
+#[tauri::command]
+pub fn build_where_clause(
+    filters: Vec<FilterNode>,
+    dialect: DbDriver,
+) -> Result<(String, Vec<Value>), DbError> {
+    crate::sql::filter::build_where_clause(&filters, &dialect)
+}
@@ -0,0 +1,443 @@
+//! SQLite maintenance
+//!
+//! VACUUM, integrity checks and the pragmas people otherwise look up every
+//! time: reading the common ones and setting the few that are safe to change
+//! from a GUI. All commands refuse connections that aren't SQLite.
+//!
+//! Pragmas are per connection, and a SQLite connection in DB Hive is a single
+//! shared one, so a pragma set here applies to every later query on it until
+//! the connection is closed (except `journal_mode = WAL`, `user_version` and,
+//! after a VACUUM, `page_size` and `auto_vacuum`, which are stored in the
+//! file).
+//!
+//! `sqlite_attach_database` attaches another database file under an alias,
+//! which then shows up as a schema of the connection. Attachments are
+//! tracked in `AppState::sqlite_attachments` and detached when the
+//! connection is closed.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::State;
+
+use super::procedures::take_connection;
+use crate::drivers::DatabaseDriver;
+use crate::models::{DbDriver, DbError};
+use crate::state::AppState;
+
+/// Values a settable pragma accepts
+#[derive(Debug, Clone, Copy)]
+enum Accepts {
+    /// One of these keywords, case-insensitively
+    Keyword(&'static [&'static str]),
+    /// Any integer
+    Integer,
+    /// An integer of at least 0
+    NonNegative,
+    /// A power of two from 512 to 65536
+    PageSize,
+}
+
+/// Pragmas shown by `sqlite_get_pragmas`, and what `sqlite_set_pragma`
+/// accepts for the settable ones
+const PRAGMAS: &[(&str, Option<Accepts>)] = &[
+    (
+        "journal_mode",
+        Some(Accepts::Keyword(&[
+            "DELETE", "TRUNCATE", "PERSIST", "MEMORY", "WAL", "OFF",
+        ])),
+    ),
+    (
+        "synchronous",
+        Some(Accepts::Keyword(&["OFF", "NORMAL", "FULL", "EXTRA"])),
+    ),
+    (
+        "foreign_keys",
+        Some(Accepts::Keyword(&["ON", "OFF", "TRUE", "FALSE", "1", "0"])),
+    ),
+    ("cache_size", Some(Accepts::Integer)),
+    ("busy_timeout", Some(Accepts::NonNegative)),
+    (
+        "temp_store",
+        Some(Accepts::Keyword(&["DEFAULT", "FILE", "MEMORY"])),
+    ),
+    (
+        "auto_vacuum",
+        Some(Accepts::Keyword(&["NONE", "FULL", "INCREMENTAL"])),
+    ),
+    ("wal_autocheckpoint", Some(Accepts::NonNegative)),
+    ("user_version", Some(Accepts::Integer)),
+    ("page_size", Some(Accepts::PageSize)),
+    ("page_count", None),
+    ("freelist_count", None),
+    ("encoding", None),
+    ("application_id", None),
+];
+
+/// A pragma and its current value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PragmaValue {
+    pub name: String,
+    pub value: Value,
+    /// Whether `sqlite_set_pragma` accepts this pragma
+    pub writable: bool,
+}
+
+/// Result of `sqlite_integrity_check`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityCheckResult {
+    pub ok: bool,
+    /// Problems found; empty when `ok`
+    pub issues: Vec<String>,
+}
+
+/// Result of `sqlite_vacuum`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VacuumResult {
+    pub elapsed_ms: u64,
+    /// Database size in bytes before and after
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+/// A database attached to a SQLite connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachedDatabase {
+    /// Schema name the database is attached as
+    pub alias: String,
+    pub file_path: String,
+}
+
+/// The connection, if it is a SQLite one
+fn sqlite_connection(
+    state: &State<'_, Mutex<AppState>>,
+    connection_id: &str,
+) -> Result<Arc<dyn DatabaseDriver>, DbError> {
+    let (conn, driver) = take_connection(state, connection_id)?;
+    if driver != DbDriver::Sqlite {
+        return Err(DbError::InvalidInput(
+            "This operation is only available on SQLite connections".to_string(),
+        ));
+    }
+    Ok(conn)
+}
+
+/// Value of a pragma (`Null` if it returns no row)
+async fn read_pragma(conn: &dyn DatabaseDriver, name: &str) -> Result<Value, DbError> {
+    let result = conn.execute_query(&format!("PRAGMA {}", name)).await?;
+    Ok(result
+        .rows
+        .into_iter()
+        .next()
+        .and_then(|row| row.into_iter().next())
+        .unwrap_or(Value::Null))
+}
+
+/// Database size in bytes, from its page count and page size
+async fn database_size(conn: &dyn DatabaseDriver) -> Result<u64, DbError> {
+    let pages = read_pragma(conn, "page_count").await?.as_u64().unwrap_or(0);
+    let page_size = read_pragma(conn, "page_size").await?.as_u64().unwrap_or(0);
+    Ok(pages * page_size)
+}
+
+/// Check `value` against what `name` accepts and return it as it goes in
+/// the `PRAGMA` statement
+fn validate_pragma(name: &str, value: &str) -> Result<String, DbError> {
+    let accepts = PRAGMAS
+        .iter()
+        .find(|(pragma, _)| pragma.eq_ignore_ascii_case(name))
+        .and_then(|(_, accepts)| *accepts)
+        .ok_or_else(|| {
+            DbError::InvalidInput(format!("Pragma '{}' cannot be set from DB Hive", name))
+        })?;
+
+    let value = value.trim();
+    let invalid = || DbError::InvalidInput(format!("Invalid value for {}: '{}'", name, value));
+    match accepts {
+        Accepts::Keyword(keywords) => keywords
+            .iter()
+            .find(|k| k.eq_ignore_ascii_case(value))
+            .map(|k| k.to_string())
+            .ok_or_else(invalid),
+        Accepts::Integer => value
+            .parse::<i64>()
+            .map(|n| n.to_string())
+            .map_err(|_| invalid()),
+        Accepts::NonNegative => value
+            .parse::<u64>()
+            .map(|n| n.to_string())
+            .map_err(|_| invalid()),
+        Accepts::PageSize => value
+            .parse::<u32>()
+            .ok()
+            .filter(|n| n.is_power_of_two() && (512..=65536).contains(n))
+            .map(|n| n.to_string())
+            .ok_or_else(invalid),
+    }
+}
+
+/// Check that `alias` can name an attached database: a plain identifier,
+/// other than the reserved `main` and `temp`
+fn validate_alias(alias: &str) -> Result<String, DbError> {
+    let alias = alias.trim();
+    let mut chars = alias.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(DbError::InvalidInput(format!(
+            "Invalid alias '{}': use letters, digits and underscores, not starting with a digit",
+            alias
+        )));
+    }
+    if alias.eq_ignore_ascii_case("main") || alias.eq_ignore_ascii_case("temp") {
+        return Err(DbError::InvalidInput(format!(
+            "'{}' is reserved by SQLite",
+            alias
+        )));
+    }
+    Ok(alias.to_string())
+}
+
+/// Databases attached to a connection, sorted by alias
+fn attachments(state: &AppState, connection_id: &str) -> Vec<AttachedDatabase> {
+    state
+        .sqlite_attachments
+        .get(connection_id)
+        .map(|attached| {
+            attached
+                .iter()
+                .map(|(alias, file_path)| AttachedDatabase {
+                    alias: alias.clone(),
+                    file_path: file_path.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Rebuild the database file, reclaiming free pages
+///
+/// `VACUUM` goes straight to the driver, which runs it on the blocking
+/// thread pool, instead of through the `execute_query` command, so it isn't
+/// logged, cached or row-limited. Other queries on the connection wait until
+/// it finishes, and it fails while a transaction is open.
+#[tauri::command]
+pub async fn sqlite_vacuum(
+    connection_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<VacuumResult, DbError> {
+    let conn = sqlite_connection(&state, &connection_id)?;
+    let size_before = database_size(conn.as_ref()).await?;
+
+    let start = Instant::now();
+    conn.execute_query("VACUUM").await?;
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+
+    Ok(VacuumResult {
+        elapsed_ms,
+        size_before,
+        size_after: database_size(conn.as_ref()).await?,
+    })
+}
+
+/// Run `PRAGMA integrity_check`
+#[tauri::command]
+pub async fn sqlite_integrity_check(
+    connection_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<IntegrityCheckResult, DbError> {
+    let conn = sqlite_connection(&state, &connection_id)?;
+    let result = conn.execute_query("PRAGMA integrity_check").await?;
+    let messages: Vec<String> = result
+        .rows
+        .into_iter()
+        .filter_map(|row| row.into_iter().next())
+        .map(|v| {
+            v.as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| v.to_string())
+        })
+        .collect();
+
+    let ok = messages.len() == 1 && messages[0] == "ok";
+    Ok(IntegrityCheckResult {
+        ok,
+        issues: if ok { Vec::new() } else { messages },
+    })
+}
+
+/// Current values of the common pragmas
+#[tauri::command]
+pub async fn sqlite_get_pragmas(
+    connection_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<PragmaValue>, DbError> {
+    let conn = sqlite_connection(&state, &connection_id)?;
+    let mut pragmas = Vec::with_capacity(PRAGMAS.len());
+    for (name, accepts) in PRAGMAS {
+        pragmas.push(PragmaValue {
+            name: name.to_string(),
+            value: read_pragma(conn.as_ref(), name).await?,
+            writable: accepts.is_some(),
+        });
+    }
+    Ok(pragmas)
+}
+
+/// Set a pragma
+///
+/// Only the pragmas `sqlite_get_pragmas` marks writable are accepted, with
+/// values checked against what each allows.
+///
+/// # Returns
+///
+/// The pragma as read back, which shows whether SQLite applied the change
+/// (`journal_mode` stays `memory` on an in-memory database, for example)
+#[tauri::command]
+pub async fn sqlite_set_pragma(
+    connection_id: String,
+    name: String,
+    value: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<PragmaValue, DbError> {
+    let conn = sqlite_connection(&state, &connection_id)?;
+    let name = name.trim().to_ascii_lowercase();
+    let value = validate_pragma(&name, &value)?;
+
+    conn.execute_query(&format!("PRAGMA {} = {}", name, value))
+        .await?;
+    Ok(PragmaValue {
+        value: read_pragma(conn.as_ref(), &name).await?,
+        name,
+        writable: true,
+    })
+}
+
+/// Attach another database file to a connection under `alias`
+///
+/// Its tables are then listed under the alias as a schema, and queries can
+/// refer to them as `alias.table`. Schema metadata and cached results of the
+/// connection are dropped so they pick the new schema up.
+///
+/// # Returns
+///
+/// The databases now attached to the connection
+#[tauri::command]
+pub async fn sqlite_attach_database(
+    connection_id: String,
+    file_path: String,
+    alias: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<AttachedDatabase>, DbError> {
+    let alias = validate_alias(&alias)?;
+    let file_path = file_path.trim().to_string();
+    if !Path::new(&file_path).is_file() {
+        return Err(DbError::InvalidInput(format!(
+            "Database file not found: {}",
+            file_path
+        )));
+    }
+
+    let conn = sqlite_connection(&state, &connection_id)?;
+    conn.execute_query(&format!(
+        "ATTACH DATABASE '{}' AS {}",
+        file_path.replace('\'', "''"),
+        conn.quote_identifier(&alias)
+    ))
+    .await?;
+
+    let mut state = state.lock().unwrap();
+    if !state.connections.contains_key(&connection_id) {
+        return Err(DbError::ConnectionError(
+            "Connection was closed".to_string(),
+        ));
+    }
+    state
+        .sqlite_attachments
+        .entry(connection_id.clone())
+        .or_default()
+        .insert(alias, file_path);
+    state.metadata_cache.remove(&connection_id);
+    state.result_cache.clear(Some(&connection_id));
+    Ok(attachments(&state, &connection_id))
+}
+
+/// Detach a database attached with `sqlite_attach_database`
+///
+/// # Returns
+///
+/// The databases still attached to the connection
+#[tauri::command]
+pub async fn sqlite_detach_database(
+    connection_id: String,
+    alias: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<AttachedDatabase>, DbError> {
+    let alias = validate_alias(&alias)?;
+    let conn = sqlite_connection(&state, &connection_id)?;
+    conn.execute_query(&format!(
+        "DETACH DATABASE {}",
+        conn.quote_identifier(&alias)
+    ))
+    .await?;
+
+    let mut state = state.lock().unwrap();
+    if let Some(attached) = state.sqlite_attachments.get_mut(&connection_id) {
+        attached.remove(&alias);
+        if attached.is_empty() {
+            state.sqlite_attachments.remove(&connection_id);
+        }
+    }
+    state.metadata_cache.remove(&connection_id);
+    state.result_cache.clear(Some(&connection_id));
+    Ok(attachments(&state, &connection_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_pragma() {
+        assert_eq!(validate_pragma("journal_mode", " wal ").unwrap(), "WAL");
+        assert_eq!(validate_pragma("cache_size", "-2000").unwrap(), "-2000");
+        assert_eq!(validate_pragma("page_size", "8192").unwrap(), "8192");
+
+        for (name, value) in [
+            ("journal_mode", "WAL; DROP TABLE users"),
+            ("busy_timeout", "-1"),
+            ("page_size", "1000"),
+            ("writable_schema", "ON"),
+            ("page_count", "10"),
+        ] {
+            assert!(
+                matches!(validate_pragma(name, value), Err(DbError::InvalidInput(_))),
+                "{} = {}",
+                name,
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_alias() {
+        assert_eq!(validate_alias(" archive_2023 ").unwrap(), "archive_2023");
+        assert_eq!(validate_alias("_old").unwrap(), "_old");
+        for alias in ["", "2023", "my-db", "a b", "x\"; DROP", "main", "TEMP"] {
+            assert!(
+                matches!(validate_alias(alias), Err(DbError::InvalidInput(_))),
+                "{}",
+                alias
+            );
+        }
+    }
+}
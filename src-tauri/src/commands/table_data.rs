@@ -0,0 +1,1402 @@
+//! Table data editing commands
+//!
+//! Tauri commands backing the data grid editor. Rows are identified by their
+//! primary key (looked up via `get_table_schema`), and the commands generate
+//! the UPDATE/DELETE statements so users no longer have to hand-write them.
+//! Values are always sent as bound parameters, never written into the SQL.
+//! Tables without a primary key are rejected: without one there is no way to
+//! guarantee that a statement touches exactly the row the user edited.
+//!
+//! The bulk variants (`insert_table_rows`, `delete_table_rows`) send their
+//! values as bound parameters and run the whole batch in one transaction by
+//! default, or row by row with per-row results when `continue_on_error` is
+//! set. `truncate_table` empties a whole table
+//! with whatever statement the engine offers for it.
+//!
+//! `fetch_table_rows` reads pages of rows for the grid, with structured
+//! filters and multi-column sorting compiled to safe SQL.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, State};
+
+use crate::commands::query::check_destructive_confirmation;
+use crate::commands::schema::cached_table_schema;
+use crate::commands::settings::load_settings;
+use crate::drivers::blob::blob_bytes;
+use crate::drivers::{BoundStatement, DatabaseDriver, MAX_RESULT_ROWS};
+use crate::models::{DbDriver, DbError, TableSchema};
+use crate::sql::filter::{build_where_clause, FilterNode};
+use crate::sql::literal::escape_literal;
+use crate::sql::order::{build_order_by, SortSpec};
+use crate::state::AppState;
+
+/// Maximum number of rows per generated multi-row statement.
+///
+/// Keeps statements well under SQL Server's 1000-row VALUES limit and the
+/// packet/statement size limits of the other engines.
+const BULK_BATCH_SIZE: usize = 500;
+
+/// Maximum number of bound values per generated statement.
+///
+/// SQL Server accepts at most 2100 parameters per request; the other engines
+/// allow far more.
+const BULK_MAX_PARAMS: usize = 2000;
+
+/// Result of a single-row edit
+///
+/// Carries the affected row count and a fresh copy of the row as it exists in
+/// the database after the statement ran, so the UI can confirm the change
+/// (including any values rewritten by triggers or defaults).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowMutationResult {
+    /// Number of rows affected by the generated statement
+    pub rows_affected: u64,
+
+    /// Generated SQL statement that was executed, with placeholders for the
+    /// bound values
+    pub sql: String,
+
+    /// Column names of the re-fetched row
+    pub columns: Vec<String>,
+
+    /// The row after the edit, or `None` if it no longer exists (deletes)
+    pub row: Option<Vec<Value>>,
+}
+
+/// Outcome of one row in a bulk operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowOperationResult {
+    /// Index of the row in the request
+    pub index: usize,
+
+    /// Whether the row's statement succeeded
+    pub success: bool,
+
+    /// Error message if the row failed
+    pub error: Option<String>,
+}
+
+/// Result of a bulk insert/delete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkMutationResult {
+    /// Total number of rows affected across all statements
+    pub rows_affected: u64,
+
+    /// Generated SQL statements, in execution order, with placeholders for
+    /// the bound values
+    pub statements: Vec<String>,
+
+    /// Per-row outcome, in request order
+    pub results: Vec<RowOperationResult>,
+}
+
+/// One page of rows read by `fetch_table_rows`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRowsPage {
+    /// Column names of the table
+    pub columns: Vec<String>,
+
+    /// Rows of this page
+    pub rows: Vec<Vec<Value>>,
+
+    /// Whether rows exist beyond this page
+    pub has_more: bool,
+
+    /// Generated SELECT statement, with placeholders for the filter values
+    pub sql: String,
+
+    /// Query execution time in milliseconds
+    pub execution_time: u64,
+}
+
+/// Result of emptying a table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TruncateResult {
+    /// Statements that were executed
+    pub sql: Vec<String>,
+    /// Rows removed, when the engine reports it (TRUNCATE doesn't)
+    pub rows_deleted: Option<u64>,
+}
+
+/// Clone the driver for `connection_id` out of the state.
+fn get_connection(
+    state: &State<'_, Mutex<AppState>>,
+    connection_id: &str,
+) -> Result<Arc<dyn DatabaseDriver>, DbError> {
+    let state = state.lock().unwrap();
+    state
+        .get_connection(connection_id)
+        .cloned()
+        .ok_or_else(|| DbError::NotFound(format!("Connection with ID {} not found", connection_id)))
+}
+
+/// Driver kind of the profile behind `connection_id`.
+fn get_driver_kind(
+    state: &State<'_, Mutex<AppState>>,
+    connection_id: &str,
+) -> Result<DbDriver, DbError> {
+    let state = state.lock().unwrap();
+    state
+        .connection_profiles
+        .get(connection_id)
+        .map(|profile| profile.driver.clone())
+        .ok_or_else(|| DbError::NotFound(format!("Connection profile {} not found", connection_id)))
+}
+
+/// Fully qualified, dialect-quoted `schema.table` reference.
+pub(crate) fn qualified_table(conn: &dyn DatabaseDriver, schema: &str, table: &str) -> String {
+    format!(
+        "{}.{}",
+        conn.quote_identifier(schema),
+        conn.quote_identifier(table)
+    )
+}
+
+/// Append `value` to `params` and return the placeholder that binds it.
+fn bind(conn: &dyn DatabaseDriver, params: &mut Vec<Value>, value: &Value) -> String {
+    params.push(value.clone());
+    conn.placeholder_style().placeholder(params.len())
+}
+
+/// Primary key columns of the table paired with their values from
+/// `primary_key`, in table order.
+///
+/// Every primary key column must be supplied and non-null, and no other
+/// columns are accepted, so a predicate built from them can never widen to
+/// multiple rows.
+fn primary_key_values<'a>(
+    table_schema: &'a TableSchema,
+    primary_key: &'a HashMap<String, Value>,
+) -> Result<Vec<(&'a str, &'a Value)>, DbError> {
+    let pk_columns = table_schema.primary_key_columns();
+    if pk_columns.is_empty() {
+        return Err(DbError::InvalidInput(format!(
+            "Table {} has no primary key; editing rows requires one",
+            table_schema.table.name
+        )));
+    }
+
+    if let Some(unknown) = primary_key
+        .keys()
+        .find(|k| !pk_columns.iter().any(|c| &c.name == *k))
+    {
+        return Err(DbError::InvalidInput(format!(
+            "Column {} is not part of the primary key",
+            unknown
+        )));
+    }
+
+    let mut values = Vec::with_capacity(pk_columns.len());
+    for column in pk_columns {
+        match primary_key.get(&column.name) {
+            Some(Value::Null) | None => {
+                return Err(DbError::InvalidInput(format!(
+                    "Missing value for primary key column {}",
+                    column.name
+                )))
+            }
+            Some(v) => values.push((column.name.as_str(), v)),
+        }
+    }
+
+    Ok(values)
+}
+
+/// Build a WHERE predicate that matches exactly one row by primary key.
+///
+/// See `primary_key_values` for the checks applied to the key. The key
+/// values are appended to `params`, so placeholders continue the numbering
+/// of any values already there.
+pub(crate) fn primary_key_predicate(
+    conn: &dyn DatabaseDriver,
+    table_schema: &TableSchema,
+    primary_key: &HashMap<String, Value>,
+    params: &mut Vec<Value>,
+) -> Result<String, DbError> {
+    let values = primary_key_values(table_schema, primary_key)?;
+    Ok(bound_key_predicate(conn, &values, params))
+}
+
+/// Check that every column of `row` exists on the table.
+fn validate_row_columns(
+    table_schema: &TableSchema,
+    row: &HashMap<String, Value>,
+) -> Result<(), DbError> {
+    if row.is_empty() {
+        return Err(DbError::InvalidInput("Row has no values".to_string()));
+    }
+    match row
+        .keys()
+        .find(|k| !table_schema.columns.iter().any(|c| &c.name == *k))
+    {
+        Some(unknown) => Err(DbError::InvalidInput(format!(
+            "Column {} does not exist on table {}",
+            unknown, table_schema.table.name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Build multi-row INSERT statements for already-validated rows.
+///
+/// Rows may supply different (partial) column sets, leaving the rest to
+/// column defaults. Consecutive rows with the same column set share one
+/// `INSERT ... VALUES (..), (..)` statement of up to `BULK_BATCH_SIZE` rows
+/// and `BULK_MAX_PARAMS` values; columns are listed in table order so the
+/// generated SQL is stable.
+pub(crate) fn build_insert_statements(
+    conn: &dyn DatabaseDriver,
+    table_schema: &TableSchema,
+    full_table: &str,
+    rows: &[HashMap<String, Value>],
+) -> Vec<BoundStatement> {
+    let column_set = |row: &HashMap<String, Value>| -> Vec<String> {
+        table_schema
+            .columns
+            .iter()
+            .filter(|c| row.contains_key(&c.name))
+            .map(|c| c.name.clone())
+            .collect()
+    };
+
+    let mut statements = Vec::new();
+    let mut start = 0;
+    while start < rows.len() {
+        let columns = column_set(&rows[start]);
+        let batch_size = (BULK_MAX_PARAMS / columns.len().max(1)).clamp(1, BULK_BATCH_SIZE);
+        let mut end = start + 1;
+        while end < rows.len() && end - start < batch_size && column_set(&rows[end]) == columns {
+            end += 1;
+        }
+
+        let mut params = Vec::new();
+        let mut tuples = Vec::with_capacity(end - start);
+        for row in &rows[start..end] {
+            let placeholders: Vec<String> = columns
+                .iter()
+                .map(|c| bind(conn, &mut params, &row[c]))
+                .collect();
+            tuples.push(format!("({})", placeholders.join(", ")));
+        }
+        let column_list: Vec<String> = columns.iter().map(|c| conn.quote_identifier(c)).collect();
+
+        statements.push(BoundStatement::new(
+            format!(
+                "INSERT INTO {} ({}) VALUES {}",
+                full_table,
+                column_list.join(", "),
+                tuples.join(", ")
+            ),
+            params,
+        ));
+        start = end;
+    }
+
+    statements
+}
+
+/// `column = placeholder` conditions for validated key `values`, AND-ed.
+fn bound_key_predicate(
+    conn: &dyn DatabaseDriver,
+    values: &[(&str, &Value)],
+    params: &mut Vec<Value>,
+) -> String {
+    let conditions: Vec<String> = values
+        .iter()
+        .map(|(column, value)| {
+            format!(
+                "{} = {}",
+                conn.quote_identifier(column),
+                bind(conn, params, value)
+            )
+        })
+        .collect();
+    conditions.join(" AND ")
+}
+
+/// Build a WHERE predicate matching every key in `keys`.
+///
+/// A single-column key becomes `pk IN (...)`. Composite keys are OR-ed
+/// per-row predicates instead of a row-value `(a, b) IN ((..), (..))`, which
+/// SQL Server does not support. Key values are appended to `params`.
+pub(crate) fn primary_keys_predicate(
+    conn: &dyn DatabaseDriver,
+    table_schema: &TableSchema,
+    keys: &[HashMap<String, Value>],
+    params: &mut Vec<Value>,
+) -> Result<String, DbError> {
+    let key_values = keys
+        .iter()
+        .map(|key| primary_key_values(table_schema, key))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let pk_columns = table_schema.primary_key_columns();
+    if pk_columns.len() == 1 {
+        let placeholders: Vec<String> = key_values
+            .iter()
+            .map(|values| bind(conn, params, values[0].1))
+            .collect();
+        Ok(format!(
+            "{} IN ({})",
+            conn.quote_identifier(&pk_columns[0].name),
+            placeholders.join(", ")
+        ))
+    } else {
+        let predicates: Vec<String> = key_values
+            .iter()
+            .map(|values| format!("({})", bound_key_predicate(conn, values, params)))
+            .collect();
+        Ok(predicates.join(" OR "))
+    }
+}
+
+/// Run one statement per row, recording each row's outcome.
+///
+/// Used for `continue_on_error` batches: a failing row is reported and the
+/// remaining rows still run.
+async fn run_per_row(
+    conn: &dyn DatabaseDriver,
+    statements: Vec<Result<BoundStatement, DbError>>,
+) -> BulkMutationResult {
+    let mut rows_affected = 0;
+    let mut executed = Vec::new();
+    let mut results = Vec::with_capacity(statements.len());
+
+    for (index, statement) in statements.into_iter().enumerate() {
+        let outcome = match statement {
+            Ok(statement) => {
+                let outcome = conn
+                    .execute_query_with_params(&statement.sql, &statement.params)
+                    .await;
+                executed.push(statement.sql);
+                outcome.map(|r| r.rows_affected.unwrap_or(0))
+            }
+            Err(e) => Err(e),
+        };
+        match outcome {
+            Ok(affected) => {
+                rows_affected += affected;
+                results.push(RowOperationResult {
+                    index,
+                    success: true,
+                    error: None,
+                });
+            }
+            Err(e) => results.push(RowOperationResult {
+                index,
+                success: false,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+
+    BulkMutationResult {
+        rows_affected,
+        statements: executed,
+        results,
+    }
+}
+
+/// Run batched statements in one transaction; every row succeeds or the
+/// whole batch is rolled back and the error returned.
+async fn run_transactional(
+    conn: &dyn DatabaseDriver,
+    statements: Vec<BoundStatement>,
+    row_count: usize,
+) -> Result<BulkMutationResult, DbError> {
+    let affected = conn.execute_in_transaction_with_params(&statements).await?;
+    Ok(BulkMutationResult {
+        rows_affected: affected.iter().sum(),
+        statements: statements.into_iter().map(|s| s.sql).collect(),
+        results: (0..row_count)
+            .map(|index| RowOperationResult {
+                index,
+                success: true,
+                error: None,
+            })
+            .collect(),
+    })
+}
+
+/// Prefix an error with the index of the row that caused it.
+fn row_error(index: usize, err: DbError) -> DbError {
+    match err {
+        DbError::InvalidInput(msg) => DbError::InvalidInput(format!("Row {}: {}", index, msg)),
+        other => other,
+    }
+}
+
+/// Check that every changed column exists and no primary key column is set
+/// to NULL, before anything is written
+fn validate_changes(
+    table_schema: &TableSchema,
+    changes: &HashMap<String, Value>,
+) -> Result<(), DbError> {
+    for (column, value) in changes {
+        let Some(info) = table_schema.columns.iter().find(|c| &c.name == column) else {
+            return Err(DbError::InvalidInput(format!(
+                "Column {} does not exist on table {}",
+                column, table_schema.table.name
+            )));
+        };
+        if info.is_primary_key && value.is_null() {
+            return Err(DbError::InvalidInput(format!(
+                "Primary key column {} cannot be set to NULL",
+                column
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Re-fetch the row matching `predicate` so the UI can show the stored values.
+async fn fetch_row(
+    conn: &dyn DatabaseDriver,
+    table: &str,
+    predicate: &str,
+    params: &[Value],
+) -> Result<(Vec<String>, Option<Vec<Value>>), DbError> {
+    let sql = format!("SELECT * FROM {} WHERE {}", table, predicate);
+    let result = conn.execute_query_with_params(&sql, params).await?;
+    Ok((result.columns, result.rows.into_iter().next()))
+}
+
+/// Update a single row identified by its primary key
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table to update
+/// * `primary_key` - Map of primary key column → current value
+/// * `changes` - Map of column → new value
+/// * `state` - Application state containing active connections
+///
+/// # Returns
+///
+/// The affected row count and the row as re-read after the update.
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` if the table has no primary key, the key
+/// is incomplete, or a changed column does not exist on the table.
+#[tauri::command]
+pub async fn update_table_row(
+    connection_id: String,
+    schema: String,
+    table: String,
+    primary_key: HashMap<String, Value>,
+    changes: HashMap<String, Value>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<RowMutationResult, DbError> {
+    if changes.is_empty() {
+        return Err(DbError::InvalidInput("No changes to apply".to_string()));
+    }
+
+    let connection = get_connection(&state, &connection_id)?;
+    let table_schema = connection.get_table_schema(&schema, &table).await?;
+    primary_key_values(&table_schema, &primary_key)?;
+    validate_changes(&table_schema, &changes)?;
+
+    // Emit SET clauses in table column order so the generated SQL is stable.
+    let mut assignments = Vec::with_capacity(changes.len());
+    let mut params = Vec::with_capacity(changes.len() + primary_key.len());
+    for column in &table_schema.columns {
+        if let Some(value) = changes.get(&column.name) {
+            assignments.push(format!(
+                "{} = {}",
+                connection.quote_identifier(&column.name),
+                bind(connection.as_ref(), &mut params, value)
+            ));
+        }
+    }
+    let predicate =
+        primary_key_predicate(connection.as_ref(), &table_schema, &primary_key, &mut params)?;
+
+    let full_table = qualified_table(connection.as_ref(), &schema, &table);
+    let sql = format!(
+        "UPDATE {} SET {} WHERE {}",
+        full_table,
+        assignments.join(", "),
+        predicate
+    );
+    let result = connection.execute_query_with_params(&sql, &params).await?;
+    forget_cached_results(&state, &connection_id, &table);
+
+    // If the edit changed key columns, the row now lives under the new key.
+    let mut new_key = primary_key;
+    for (column, value) in &changes {
+        if new_key.contains_key(column) {
+            new_key.insert(column.clone(), value.clone());
+        }
+    }
+    let mut key_params = Vec::new();
+    let new_predicate =
+        primary_key_predicate(connection.as_ref(), &table_schema, &new_key, &mut key_params)?;
+    let (columns, row) =
+        fetch_row(connection.as_ref(), &full_table, &new_predicate, &key_params).await?;
+
+    Ok(RowMutationResult {
+        rows_affected: result.rows_affected.unwrap_or(0),
+        sql,
+        columns,
+        row,
+    })
+}
+
+/// Delete a single row identified by its primary key
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table to delete from
+/// * `primary_key` - Map of primary key column → value
+/// * `state` - Application state containing active connections
+///
+/// # Returns
+///
+/// The affected row count; `row` is re-fetched and is `None` once the delete
+/// has taken effect.
+#[tauri::command]
+pub async fn delete_table_row(
+    connection_id: String,
+    schema: String,
+    table: String,
+    primary_key: HashMap<String, Value>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<RowMutationResult, DbError> {
+    let connection = get_connection(&state, &connection_id)?;
+    let table_schema = connection.get_table_schema(&schema, &table).await?;
+    let mut params = Vec::new();
+    let predicate =
+        primary_key_predicate(connection.as_ref(), &table_schema, &primary_key, &mut params)?;
+
+    let full_table = qualified_table(connection.as_ref(), &schema, &table);
+    let sql = format!("DELETE FROM {} WHERE {}", full_table, predicate);
+    let result = connection.execute_query_with_params(&sql, &params).await?;
+    forget_cached_results(&state, &connection_id, &table);
+
+    let (columns, row) = fetch_row(connection.as_ref(), &full_table, &predicate, &params).await?;
+
+    Ok(RowMutationResult {
+        rows_affected: result.rows_affected.unwrap_or(0),
+        sql,
+        columns,
+        row,
+    })
+}
+
+/// Insert several rows in one operation
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table to insert into
+/// * `rows` - Rows as maps of column → value; omitted columns use defaults
+/// * `continue_on_error` - Run row by row and report failures instead of
+///   rolling back the whole batch (default: false)
+/// * `state` - Application state containing active connections
+///
+/// # Errors
+///
+/// Without `continue_on_error`, returns the first validation or database
+/// error; nothing is inserted in that case.
+#[tauri::command]
+pub async fn insert_table_rows(
+    connection_id: String,
+    schema: String,
+    table: String,
+    rows: Vec<HashMap<String, Value>>,
+    continue_on_error: Option<bool>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<BulkMutationResult, DbError> {
+    if rows.is_empty() {
+        return Err(DbError::InvalidInput("No rows to insert".to_string()));
+    }
+
+    let connection = get_connection(&state, &connection_id)?;
+    let table_schema = cached_table_schema(&state, &connection_id, &schema, &table, None).await?;
+    let full_table = qualified_table(connection.as_ref(), &schema, &table);
+
+    if continue_on_error.unwrap_or(false) {
+        let statements = rows
+            .iter()
+            .map(|row| {
+                validate_row_columns(&table_schema, row)?;
+                let single = build_insert_statements(
+                    connection.as_ref(),
+                    &table_schema,
+                    &full_table,
+                    std::slice::from_ref(row),
+                );
+                single
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| DbError::InvalidInput("Row has no values".to_string()))
+            })
+            .collect();
+        let result = run_per_row(connection.as_ref(), statements).await;
+        forget_cached_results(&state, &connection_id, &table);
+        return Ok(result);
+    }
+
+    for (index, row) in rows.iter().enumerate() {
+        validate_row_columns(&table_schema, row).map_err(|e| row_error(index, e))?;
+    }
+    let statements =
+        build_insert_statements(connection.as_ref(), &table_schema, &full_table, &rows);
+    let result = run_transactional(connection.as_ref(), statements, rows.len()).await?;
+    forget_cached_results(&state, &connection_id, &table);
+    Ok(result)
+}
+
+/// Delete several rows identified by their primary keys
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table to delete from
+/// * `keys` - Primary key tuples as maps of key column → value
+/// * `continue_on_error` - Run row by row and report failures instead of
+///   rolling back the whole batch (default: false)
+/// * `state` - Application state containing active connections
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` if the table has no primary key or a key
+/// is incomplete (per row when `continue_on_error` is set).
+#[tauri::command]
+pub async fn delete_table_rows(
+    connection_id: String,
+    schema: String,
+    table: String,
+    keys: Vec<HashMap<String, Value>>,
+    continue_on_error: Option<bool>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<BulkMutationResult, DbError> {
+    if keys.is_empty() {
+        return Err(DbError::InvalidInput("No rows to delete".to_string()));
+    }
+
+    let connection = get_connection(&state, &connection_id)?;
+    let table_schema = cached_table_schema(&state, &connection_id, &schema, &table, None).await?;
+    let full_table = qualified_table(connection.as_ref(), &schema, &table);
+
+    if continue_on_error.unwrap_or(false) {
+        let statements = keys
+            .iter()
+            .map(|key| {
+                let mut params = Vec::new();
+                let predicate = primary_keys_predicate(
+                    connection.as_ref(),
+                    &table_schema,
+                    std::slice::from_ref(key),
+                    &mut params,
+                )?;
+                Ok(BoundStatement::new(
+                    format!("DELETE FROM {} WHERE {}", full_table, predicate),
+                    params,
+                ))
+            })
+            .collect();
+        let result = run_per_row(connection.as_ref(), statements).await;
+        forget_cached_results(&state, &connection_id, &table);
+        return Ok(result);
+    }
+
+    for (index, key) in keys.iter().enumerate() {
+        primary_key_values(&table_schema, key).map_err(|e| row_error(index, e))?;
+    }
+    let key_columns = table_schema.primary_key_columns().len().max(1);
+    let batch_size = (BULK_MAX_PARAMS / key_columns).clamp(1, BULK_BATCH_SIZE);
+    let mut statements = Vec::new();
+    for chunk in keys.chunks(batch_size) {
+        let mut params = Vec::new();
+        let predicate =
+            primary_keys_predicate(connection.as_ref(), &table_schema, chunk, &mut params)?;
+        statements.push(BoundStatement::new(
+            format!("DELETE FROM {} WHERE {}", full_table, predicate),
+            params,
+        ));
+    }
+    let result = run_transactional(connection.as_ref(), statements, keys.len()).await?;
+    forget_cached_results(&state, &connection_id, &table);
+    Ok(result)
+}
+
+/// Remove every row from a table
+///
+/// Uses the engine's own statement:
+/// - PostgreSQL: `TRUNCATE TABLE`, with `RESTART IDENTITY` and `CASCADE` as
+///   requested
+/// - MySQL and SQL Server: `TRUNCATE TABLE`, which always resets the
+///   auto-increment counter, so `DELETE FROM` is used to keep it
+/// - SQLite: `DELETE FROM`, plus clearing the table's `sqlite_sequence`
+///   entry to restart AUTOINCREMENT
+///
+/// `cascade` (also emptying tables that reference this one) is only
+/// available on PostgreSQL.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table to empty
+/// * `cascade` - Also truncate tables with foreign keys to this one
+/// * `restart_identity` - Reset auto-increment/identity counters
+/// * `confirmed` - The user confirmed the operation; required when the
+///   `confirmDestructive` setting is on or the connection is tagged as
+///   production
+/// * `state` - Application state containing active connections
+/// * `app` - App handle, for reading settings
+#[tauri::command]
+pub async fn truncate_table(
+    connection_id: String,
+    schema: String,
+    table: String,
+    cascade: bool,
+    restart_identity: bool,
+    confirmed: Option<bool>,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<TruncateResult, DbError> {
+    let connection = get_connection(&state, &connection_id)?;
+    let driver = get_driver_kind(&state, &connection_id)?;
+
+    let full_table = qualified_table(connection.as_ref(), &schema, &table);
+    let mut sql = truncate_statements(&driver, &full_table, cascade, restart_identity)?;
+
+    // sqlite_sequence only exists once some table uses AUTOINCREMENT
+    if restart_identity && matches!(driver, DbDriver::Sqlite | DbDriver::Turso) {
+        let has_sequence = connection
+            .execute_query(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'sqlite_sequence'",
+            )
+            .await?;
+        if !has_sequence.rows.is_empty() {
+            sql.push(format!(
+                "DELETE FROM sqlite_sequence WHERE name = {}",
+                escape_literal(&table, &driver)
+            ));
+        }
+    }
+
+    if !confirmed.unwrap_or(false) {
+        if load_settings(&app)?.query.confirm_destructive {
+            return Err(DbError::ConfirmationRequired(format!(
+                "Truncate table {}.{}",
+                schema, table
+            )));
+        }
+        check_destructive_confirmation(&state.lock().unwrap(), &connection_id, &sql.join(";\n"))?;
+    }
+
+    let mut rows_deleted = None;
+    for (i, stmt) in sql.iter().enumerate() {
+        let result = connection.execute_query(stmt).await?;
+        if i == 0 && stmt.starts_with("DELETE") {
+            rows_deleted = result.rows_affected;
+        }
+    }
+    forget_cached_results(&state, &connection_id, &table);
+
+    Ok(TruncateResult { sql, rows_deleted })
+}
+
+/// Drop the cached query results that read `table`, which a command here
+/// has just written to
+fn forget_cached_results(state: &Mutex<AppState>, connection_id: &str, table: &str) {
+    state
+        .lock()
+        .unwrap()
+        .result_cache
+        .invalidate_table(connection_id, table);
+}
+
+/// Statements that empty `full_table` on `driver`
+fn truncate_statements(
+    driver: &DbDriver,
+    full_table: &str,
+    cascade: bool,
+    restart_identity: bool,
+) -> Result<Vec<String>, DbError> {
+    if cascade && !driver.is_postgres_compatible() {
+        return Err(DbError::InvalidInput(
+            "Truncating with CASCADE is only supported on PostgreSQL".to_string(),
+        ));
+    }
+
+    let sql = match driver {
+        DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon => format!(
+            "TRUNCATE TABLE {}{}{}",
+            full_table,
+            if restart_identity { " RESTART IDENTITY" } else { "" },
+            if cascade { " CASCADE" } else { "" }
+        ),
+        DbDriver::MySql | DbDriver::SqlServer if restart_identity => {
+            format!("TRUNCATE TABLE {}", full_table)
+        }
+        DbDriver::MySql | DbDriver::SqlServer | DbDriver::Sqlite | DbDriver::Turso => {
+            format!("DELETE FROM {}", full_table)
+        }
+        DbDriver::MongoDb | DbDriver::Redis => {
+            return Err(DbError::InvalidInput(
+                "Truncate is only supported for SQL databases".to_string(),
+            ))
+        }
+    };
+    Ok(vec![sql])
+}
+
+/// Save one cell's full value to a file
+///
+/// The data grid only holds a preview of large binary values; this fetches
+/// the cell identified by `primary_key` and `column` on its own and writes
+/// its bytes to `file_path`. Text values are written as UTF-8.
+///
+/// # Returns
+///
+/// The number of bytes written.
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` if the table has no primary key, the
+/// column does not exist, or the value is NULL or neither binary nor text,
+/// and `DbError::NotFound` if no row has that key.
+///
+/// # Example
+///
+/// ```typescript
+/// const size = await invoke<number>('fetch_cell_blob', {
+///     connectionId: 'conn-123',
+///     schema: 'public',
+///     table: 'attachments',
+///     primaryKey: { id: 42 },
+///     column: 'content',
+///     filePath: '/home/me/Downloads/attachment-42.pdf',
+/// });
+/// ```
+#[tauri::command]
+pub async fn fetch_cell_blob(
+    connection_id: String,
+    schema: String,
+    table: String,
+    primary_key: HashMap<String, Value>,
+    column: String,
+    file_path: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<u64, DbError> {
+    let connection = get_connection(&state, &connection_id)?;
+    let table_schema = connection.get_table_schema(&schema, &table).await?;
+    if !table_schema.columns.iter().any(|c| c.name == column) {
+        return Err(DbError::InvalidInput(format!(
+            "Column {} does not exist on table {}",
+            column, table
+        )));
+    }
+    let mut params = Vec::new();
+    let predicate =
+        primary_key_predicate(connection.as_ref(), &table_schema, &primary_key, &mut params)?;
+
+    let sql = format!(
+        "SELECT {} FROM {} WHERE {}",
+        connection.quote_identifier(&column),
+        qualified_table(connection.as_ref(), &schema, &table),
+        predicate
+    );
+    let result = connection.execute_query_with_params(&sql, &params).await?;
+    let value = result
+        .rows
+        .into_iter()
+        .next()
+        .and_then(|row| row.into_iter().next())
+        .ok_or_else(|| DbError::NotFound(format!("No row in {} has that key", table)))?;
+
+    let bytes = match value {
+        Value::Null => {
+            return Err(DbError::InvalidInput(format!("{} is NULL", column)));
+        }
+        Value::String(text) => text.into_bytes(),
+        other => blob_bytes(&other).ok_or_else(|| {
+            DbError::InvalidInput(format!("{} does not hold binary or text data", column))
+        })?,
+    };
+
+    tokio::fs::write(&file_path, &bytes)
+        .await
+        .map_err(|e| DbError::InternalError(format!("Failed to write {}: {}", file_path, e)))?;
+    Ok(bytes.len() as u64)
+}
+
+/// Read a page of table rows, filtered and sorted
+///
+/// Backs the data grid's filter UI and column-header sorting. `filters`
+/// compile to a parameterized WHERE clause (see `sql::filter`) and `sort` to
+/// an ORDER BY whose columns are checked against the table's cached schema
+/// (see `sql::order`), so neither can inject SQL. Pages are read with
+/// `LIMIT`/`OFFSET`, or `OFFSET ... FETCH NEXT` on SQL Server.
+///
+/// # Arguments
+///
+/// * `connection_id` - ID of the active connection
+/// * `schema` - Schema containing the table
+/// * `table` - Table to read
+/// * `filters` - Column filters and AND/OR groups; all rows when omitted
+/// * `sort` - Sort keys, most significant first; unordered when omitted
+/// * `limit` - Page size
+/// * `offset` - Rows to skip, for later pages
+/// * `state` - Application state containing active connections
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` for a page size of 0 or above the result
+/// row cap, an invalid filter, or a sort on a column the table doesn't have.
+///
+/// # Example
+///
+/// ```typescript
+/// const page = await invoke<TableRowsPage>('fetch_table_rows', {
+///     connectionId: 'conn-123',
+///     schema: 'public',
+///     table: 'users',
+///     filters: [{ column: 'status', operator: 'eq', value: 'active' }],
+///     sort: [
+///         { column: 'last_login', direction: 'desc', nulls: 'last' },
+///         { column: 'id', direction: 'asc' },
+///     ],
+///     limit: 100,
+///     offset: 0,
+/// });
+/// ```
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn fetch_table_rows(
+    connection_id: String,
+    schema: String,
+    table: String,
+    filters: Option<Vec<FilterNode>>,
+    sort: Option<Vec<SortSpec>>,
+    limit: u64,
+    offset: Option<u64>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<TableRowsPage, DbError> {
+    if limit == 0 || limit >= MAX_RESULT_ROWS as u64 {
+        return Err(DbError::InvalidInput(format!(
+            "Page size must be between 1 and {}",
+            MAX_RESULT_ROWS - 1
+        )));
+    }
+    let connection = get_connection(&state, &connection_id)?;
+    let driver = get_driver_kind(&state, &connection_id)?;
+
+    let filters = filters.unwrap_or_default();
+    let (where_clause, params) = build_where_clause(&filters, &driver)?;
+    let sort = sort.unwrap_or_default();
+    let order_by = if sort.is_empty() {
+        String::new()
+    } else {
+        let table_schema =
+            cached_table_schema(&state, &connection_id, &schema, &table, None).await?;
+        build_order_by(&sort, &table_schema.columns, &driver)?
+    };
+
+    let mut sql = format!(
+        "SELECT * FROM {}",
+        qualified_table(connection.as_ref(), &schema, &table)
+    );
+    for clause in [&where_clause, &order_by] {
+        if !clause.is_empty() {
+            sql.push(' ');
+            sql.push_str(clause);
+        }
+    }
+    // One extra row tells whether another page follows
+    sql.push_str(&page_clause(
+        &driver,
+        !order_by.is_empty(),
+        limit + 1,
+        offset.unwrap_or(0),
+    ));
+
+    let start = Instant::now();
+    let result = if params.is_empty() {
+        connection.execute_query(&sql).await?
+    } else {
+        connection.execute_query_with_params(&sql, &params).await?
+    };
+    let execution_time = start.elapsed().as_millis() as u64;
+
+    let mut rows = result.rows;
+    let has_more = rows.len() as u64 > limit;
+    rows.truncate(limit as usize);
+
+    Ok(TableRowsPage {
+        columns: result.columns,
+        rows,
+        has_more,
+        sql,
+        execution_time,
+    })
+}
+
+/// Clause reading `limit` rows after skipping `offset`
+///
+/// SQL Server can only page an ordered query, so an unordered one gets a
+/// no-op `ORDER BY (SELECT NULL)`.
+fn page_clause(driver: &DbDriver, ordered: bool, limit: u64, offset: u64) -> String {
+    if *driver == DbDriver::SqlServer {
+        let order_by = if ordered {
+            ""
+        } else {
+            " ORDER BY (SELECT NULL)"
+        };
+        return format!(
+            "{} OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+            order_by, offset, limit
+        );
+    }
+    if offset == 0 {
+        format!(" LIMIT {}", limit)
+    } else {
+        format!(" LIMIT {} OFFSET {}", limit, offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ColumnInfo, DatabaseInfo, ForeignKeyInfo, SchemaInfo, TableInfo};
+
+    // Minimal driver: only the default quoting/escaping is exercised.
+    struct MockDriver;
+
+    #[async_trait::async_trait]
+    impl DatabaseDriver for MockDriver {
+        async fn connect(_opts: crate::drivers::ConnectionOptions) -> Result<Self, DbError>
+        where
+            Self: Sized,
+        {
+            Ok(Self)
+        }
+
+        async fn test_connection(&self) -> Result<(), DbError> {
+            Ok(())
+        }
+
+        async fn execute_query(&self, _sql: &str) -> Result<crate::drivers::QueryResult, DbError> {
+            Ok(crate::drivers::QueryResult::empty())
+        }
+
+        async fn get_databases(&self) -> Result<Vec<DatabaseInfo>, DbError> {
+            Ok(vec![])
+        }
+
+        async fn get_schemas(&self, _database: &str) -> Result<Vec<SchemaInfo>, DbError> {
+            Ok(vec![])
+        }
+
+        async fn get_tables(&self, _schema: &str) -> Result<Vec<TableInfo>, DbError> {
+            Ok(vec![])
+        }
+
+        async fn get_table_schema(
+            &self,
+            _schema: &str,
+            _table: &str,
+        ) -> Result<TableSchema, DbError> {
+            Err(DbError::NotFound("unused".to_string()))
+        }
+
+        async fn get_foreign_keys(&self, _schema: &str) -> Result<Vec<ForeignKeyInfo>, DbError> {
+            Ok(vec![])
+        }
+
+        async fn close(&self) -> Result<(), DbError> {
+            Ok(())
+        }
+    }
+
+    fn users_schema(with_pk: bool) -> TableSchema {
+        let table = TableInfo::new(
+            "users".to_string(),
+            "public".to_string(),
+            "TABLE".to_string(),
+        );
+        let columns = vec![
+            ColumnInfo::with_details(
+                "id".to_string(),
+                "integer".to_string(),
+                false,
+                None,
+                with_pk,
+            ),
+            ColumnInfo::new("name".to_string(), "text".to_string(), true),
+        ];
+        TableSchema::new(table, columns, vec![])
+    }
+
+    #[test]
+    fn test_primary_key_predicate() {
+        let conn = MockDriver;
+        let mut pk = HashMap::new();
+        pk.insert("id".to_string(), serde_json::json!(7));
+
+        // Placeholders continue after values already bound (e.g. SET clauses)
+        let mut params = vec![serde_json::json!(true)];
+        let predicate =
+            primary_key_predicate(&conn, &users_schema(true), &pk, &mut params).unwrap();
+        assert_eq!(predicate, "\"id\" = ?");
+        assert_eq!(params, vec![serde_json::json!(true), serde_json::json!(7)]);
+    }
+
+    #[test]
+    fn test_primary_key_predicate_requires_primary_key() {
+        let conn = MockDriver;
+        let mut pk = HashMap::new();
+        pk.insert("id".to_string(), serde_json::json!(7));
+
+        let result = primary_key_predicate(&conn, &users_schema(false), &pk, &mut Vec::new());
+        assert!(matches!(result, Err(DbError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_primary_key_predicate_rejects_incomplete_or_foreign_keys() {
+        let conn = MockDriver;
+        let schema = users_schema(true);
+
+        let empty = HashMap::new();
+        assert!(primary_key_predicate(&conn, &schema, &empty, &mut Vec::new()).is_err());
+
+        let mut wrong = HashMap::new();
+        wrong.insert("id".to_string(), serde_json::json!(1));
+        wrong.insert("name".to_string(), serde_json::json!("x"));
+        assert!(primary_key_predicate(&conn, &schema, &wrong, &mut Vec::new()).is_err());
+
+        let mut null_key = HashMap::new();
+        null_key.insert("id".to_string(), Value::Null);
+        assert!(primary_key_predicate(&conn, &schema, &null_key, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_build_insert_statements_groups_matching_column_sets() {
+        let conn = MockDriver;
+        let schema = users_schema(true);
+        let row = |pairs: &[(&str, Value)]| -> HashMap<String, Value> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect()
+        };
+        let rows = vec![
+            row(&[
+                ("id", serde_json::json!(1)),
+                ("name", serde_json::json!("a")),
+            ]),
+            row(&[
+                ("name", serde_json::json!("b")),
+                ("id", serde_json::json!(2)),
+            ]),
+            row(&[("name", serde_json::json!("c"))]),
+        ];
+
+        let statements = build_insert_statements(&conn, &schema, "\"public\".\"users\"", &rows);
+        assert_eq!(
+            statements,
+            vec![
+                BoundStatement::new(
+                    "INSERT INTO \"public\".\"users\" (\"id\", \"name\") VALUES (?, ?), (?, ?)"
+                        .to_string(),
+                    vec![
+                        serde_json::json!(1),
+                        serde_json::json!("a"),
+                        serde_json::json!(2),
+                        serde_json::json!("b"),
+                    ],
+                ),
+                BoundStatement::new(
+                    "INSERT INTO \"public\".\"users\" (\"name\") VALUES (?)".to_string(),
+                    vec![serde_json::json!("c")],
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_insert_statements_caps_bound_values() {
+        let conn = MockDriver;
+        let names: Vec<String> = (0..10).map(|i| format!("c{}", i)).collect();
+        let columns = names
+            .iter()
+            .map(|name| ColumnInfo::new(name.clone(), "integer".to_string(), true))
+            .collect();
+        let table = TableInfo::new("wide".to_string(), "public".to_string(), "TABLE".to_string());
+        let schema = TableSchema::new(table, columns, vec![]);
+        let rows: Vec<HashMap<String, Value>> = (0..450)
+            .map(|_| {
+                names
+                    .iter()
+                    .map(|name| (name.clone(), serde_json::json!(1)))
+                    .collect()
+            })
+            .collect();
+
+        // 10 values per row: 200 rows fill BULK_MAX_PARAMS
+        let statements = build_insert_statements(&conn, &schema, "\"wide\"", &rows);
+        let per_statement: Vec<usize> = statements.iter().map(|s| s.params.len()).collect();
+        assert_eq!(per_statement, vec![2000, 2000, 500]);
+    }
+
+    #[test]
+    fn test_validate_row_columns() {
+        let schema = users_schema(true);
+        let mut row = HashMap::new();
+        row.insert("name".to_string(), serde_json::json!("x"));
+        assert!(validate_row_columns(&schema, &row).is_ok());
+
+        row.insert("missing".to_string(), serde_json::json!(1));
+        assert!(validate_row_columns(&schema, &row).is_err());
+        assert!(validate_row_columns(&schema, &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_validate_changes() {
+        let schema = users_schema(true);
+        let mut changes = HashMap::new();
+        changes.insert("name".to_string(), Value::Null);
+        changes.insert("id".to_string(), serde_json::json!(2));
+        assert!(validate_changes(&schema, &changes).is_ok());
+
+        changes.insert("id".to_string(), Value::Null);
+        assert!(matches!(
+            validate_changes(&schema, &changes),
+            Err(DbError::InvalidInput(msg)) if msg.contains("cannot be set to NULL")
+        ));
+
+        let mut unknown = HashMap::new();
+        unknown.insert("missing".to_string(), serde_json::json!(1));
+        assert!(validate_changes(&schema, &unknown).is_err());
+    }
+
+    #[test]
+    fn test_primary_keys_predicate_single_column() {
+        let conn = MockDriver;
+        let keys: Vec<HashMap<String, Value>> = [1, 2, 3]
+            .iter()
+            .map(|id| HashMap::from([("id".to_string(), serde_json::json!(id))]))
+            .collect();
+
+        let mut params = Vec::new();
+        let predicate =
+            primary_keys_predicate(&conn, &users_schema(true), &keys, &mut params).unwrap();
+        assert_eq!(predicate, "\"id\" IN (?, ?, ?)");
+        assert_eq!(
+            params,
+            vec![
+                serde_json::json!(1),
+                serde_json::json!(2),
+                serde_json::json!(3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_primary_keys_predicate_composite() {
+        let conn = MockDriver;
+        let table = TableInfo::new(
+            "memberships".to_string(),
+            "public".to_string(),
+            "TABLE".to_string(),
+        );
+        let columns = vec![
+            ColumnInfo::with_details(
+                "user_id".to_string(),
+                "integer".to_string(),
+                false,
+                None,
+                true,
+            ),
+            ColumnInfo::with_details(
+                "team_id".to_string(),
+                "integer".to_string(),
+                false,
+                None,
+                true,
+            ),
+        ];
+        let schema = TableSchema::new(table, columns, vec![]);
+        let keys = vec![
+            HashMap::from([
+                ("user_id".to_string(), serde_json::json!(1)),
+                ("team_id".to_string(), serde_json::json!(2)),
+            ]),
+            HashMap::from([
+                ("user_id".to_string(), serde_json::json!(3)),
+                ("team_id".to_string(), serde_json::json!(4)),
+            ]),
+        ];
+
+        let mut params = Vec::new();
+        let predicate = primary_keys_predicate(&conn, &schema, &keys, &mut params).unwrap();
+        assert_eq!(
+            predicate,
+            "(\"user_id\" = ? AND \"team_id\" = ?) OR (\"user_id\" = ? AND \"team_id\" = ?)"
+        );
+        assert_eq!(params, (1..=4).map(|n| serde_json::json!(n)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_truncate_statements() {
+        let table = "\"public\".\"users\"";
+        assert_eq!(
+            truncate_statements(&DbDriver::Postgres, table, true, true).unwrap(),
+            vec!["TRUNCATE TABLE \"public\".\"users\" RESTART IDENTITY CASCADE"]
+        );
+        assert_eq!(
+            truncate_statements(&DbDriver::MySql, "`shop`.`users`", false, true).unwrap(),
+            vec!["TRUNCATE TABLE `shop`.`users`"]
+        );
+        // TRUNCATE would reset the counter, so keep it with DELETE
+        assert_eq!(
+            truncate_statements(&DbDriver::SqlServer, "[dbo].[users]", false, false).unwrap(),
+            vec!["DELETE FROM [dbo].[users]"]
+        );
+        assert_eq!(
+            truncate_statements(&DbDriver::Sqlite, table, false, true).unwrap(),
+            vec!["DELETE FROM \"public\".\"users\""]
+        );
+        assert!(matches!(
+            truncate_statements(&DbDriver::MySql, "`users`", true, false),
+            Err(DbError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_page_clause() {
+        assert_eq!(page_clause(&DbDriver::Postgres, true, 101, 0), " LIMIT 101");
+        assert_eq!(
+            page_clause(&DbDriver::MySql, false, 101, 200),
+            " LIMIT 101 OFFSET 200"
+        );
+        assert_eq!(
+            page_clause(&DbDriver::SqlServer, true, 101, 200),
+            " OFFSET 200 ROWS FETCH NEXT 101 ROWS ONLY"
+        );
+        assert_eq!(
+            page_clause(&DbDriver::SqlServer, false, 11, 0),
+            " ORDER BY (SELECT NULL) OFFSET 0 ROWS FETCH NEXT 11 ROWS ONLY"
+        );
+    }
+}
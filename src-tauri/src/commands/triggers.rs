@@ -0,0 +1,251 @@
+//! Table trigger commands
+//!
+//! Lists the triggers defined on a table so users can see what side effects
+//! a write will have before they modify data. Drivers without triggers
+//! (MongoDB, Redis) return an empty list.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::State;
+
+use super::procedures::{as_string, take_connection};
+use crate::models::{DbDriver, DbError};
+use crate::sql::literal::escape_literal;
+use crate::sql::tokenizer::{tokenize_with, Quoting, TokenKind};
+use crate::state::AppState;
+
+/// Metadata describing a single trigger.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerInfo {
+    pub name: String,
+    /// "BEFORE" | "AFTER" | "INSTEAD OF"
+    pub timing: String,
+    /// Subset of "INSERT", "UPDATE", "DELETE" (and "TRUNCATE" on PostgreSQL)
+    pub events: Vec<String>,
+    /// CREATE TRIGGER statement, or the trigger body where the server only
+    /// exposes that (MySQL)
+    pub definition: Option<String>,
+    /// Function the trigger executes (PostgreSQL only), schema-qualified
+    pub function_name: Option<String>,
+    /// Source of that function (PostgreSQL only)
+    pub function_definition: Option<String>,
+}
+
+/// List triggers defined on a table.
+#[tauri::command]
+pub async fn get_triggers(
+    connection_id: String,
+    schema: String,
+    table: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<TriggerInfo>, DbError> {
+    let (conn, driver) = take_connection(&state, &connection_id)?;
+    let schema_lit = escape_literal(&schema, &driver);
+    let table_lit = escape_literal(&table, &driver);
+
+    if driver.is_postgres_compatible() {
+        // tgtype bits: 2 = BEFORE, 64 = INSTEAD OF, 4 = INSERT, 8 = DELETE,
+        // 16 = UPDATE, 32 = TRUNCATE. Internal triggers back FK constraints.
+        let sql = format!(
+            r#"SELECT t.tgname,
+                      CASE WHEN t.tgtype & 2 <> 0 THEN 'BEFORE'
+                           WHEN t.tgtype & 64 <> 0 THEN 'INSTEAD OF'
+                           ELSE 'AFTER' END,
+                      concat_ws(',',
+                          CASE WHEN t.tgtype & 4 <> 0 THEN 'INSERT' END,
+                          CASE WHEN t.tgtype & 16 <> 0 THEN 'UPDATE' END,
+                          CASE WHEN t.tgtype & 8 <> 0 THEN 'DELETE' END,
+                          CASE WHEN t.tgtype & 32 <> 0 THEN 'TRUNCATE' END),
+                      pg_get_triggerdef(t.oid, true),
+                      pn.nspname || '.' || p.proname,
+                      pg_get_functiondef(p.oid)
+               FROM pg_trigger t
+               JOIN pg_class c ON c.oid = t.tgrelid
+               JOIN pg_namespace n ON n.oid = c.relnamespace
+               JOIN pg_proc p ON p.oid = t.tgfoid
+               JOIN pg_namespace pn ON pn.oid = p.pronamespace
+               WHERE NOT t.tgisinternal
+                 AND n.nspname = {}
+                 AND c.relname = {}
+               ORDER BY t.tgname"#,
+            schema_lit, table_lit
+        );
+        let res = conn.execute_query(&sql).await?;
+        return Ok(res
+            .rows
+            .iter()
+            .map(|row| TriggerInfo {
+                name: row.first().and_then(as_string).unwrap_or_default(),
+                timing: row.get(1).and_then(as_string).unwrap_or_default(),
+                events: split_events(row.get(2)),
+                definition: row.get(3).and_then(as_string),
+                function_name: row.get(4).and_then(as_string),
+                function_definition: row.get(5).and_then(as_string),
+            })
+            .collect());
+    }
+
+    match driver {
+        DbDriver::MySql => {
+            // MySQL triggers fire on exactly one event
+            let sql = format!(
+                r#"SELECT TRIGGER_NAME, ACTION_TIMING, EVENT_MANIPULATION, ACTION_STATEMENT
+                   FROM information_schema.TRIGGERS
+                   WHERE TRIGGER_SCHEMA = {} AND EVENT_OBJECT_TABLE = {}
+                   ORDER BY ACTION_TIMING, EVENT_MANIPULATION, ACTION_ORDER"#,
+                schema_lit, table_lit
+            );
+            let res = conn.execute_query(&sql).await?;
+            Ok(res
+                .rows
+                .iter()
+                .map(|row| TriggerInfo {
+                    name: row.first().and_then(as_string).unwrap_or_default(),
+                    timing: row.get(1).and_then(as_string).unwrap_or_default(),
+                    events: split_events(row.get(2)),
+                    definition: row.get(3).and_then(as_string),
+                    function_name: None,
+                    function_definition: None,
+                })
+                .collect())
+        }
+        DbDriver::SqlServer => {
+            // SQL Server only has AFTER and INSTEAD OF triggers
+            let sql = format!(
+                r#"SELECT t.name,
+                          CASE WHEN t.is_instead_of_trigger = 1 THEN 'INSTEAD OF' ELSE 'AFTER' END,
+                          STUFF(
+                              CASE WHEN OBJECTPROPERTY(t.object_id, 'ExecIsInsertTrigger') = 1
+                                   THEN ',INSERT' ELSE '' END
+                              + CASE WHEN OBJECTPROPERTY(t.object_id, 'ExecIsUpdateTrigger') = 1
+                                     THEN ',UPDATE' ELSE '' END
+                              + CASE WHEN OBJECTPROPERTY(t.object_id, 'ExecIsDeleteTrigger') = 1
+                                     THEN ',DELETE' ELSE '' END,
+                              1, 1, ''),
+                          OBJECT_DEFINITION(t.object_id)
+                   FROM sys.triggers t
+                   WHERE t.parent_id = OBJECT_ID(QUOTENAME({}) + '.' + QUOTENAME({}))
+                   ORDER BY t.name"#,
+                schema_lit, table_lit
+            );
+            let res = conn.execute_query(&sql).await?;
+            Ok(res
+                .rows
+                .iter()
+                .map(|row| TriggerInfo {
+                    name: row.first().and_then(as_string).unwrap_or_default(),
+                    timing: row.get(1).and_then(as_string).unwrap_or_default(),
+                    events: split_events(row.get(2)),
+                    definition: row.get(3).and_then(as_string),
+                    function_name: None,
+                    function_definition: None,
+                })
+                .collect())
+        }
+        DbDriver::Sqlite | DbDriver::Turso => {
+            // sqlite_master only keeps the original statement, so timing and
+            // events are read back out of it
+            let sql = format!(
+                "SELECT name, sql FROM sqlite_master WHERE type = 'trigger' AND tbl_name = {} ORDER BY name",
+                table_lit
+            );
+            let res = conn.execute_query(&sql).await?;
+            Ok(res
+                .rows
+                .iter()
+                .map(|row| {
+                    let definition = row.get(1).and_then(as_string);
+                    let (timing, events) =
+                        parse_sqlite_trigger(definition.as_deref().unwrap_or_default());
+                    TriggerInfo {
+                        name: row.first().and_then(as_string).unwrap_or_default(),
+                        timing,
+                        events,
+                        definition,
+                        function_name: None,
+                        function_definition: None,
+                    }
+                })
+                .collect())
+        }
+        // MongoDB, Redis: no triggers
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Split a comma-separated event list into its parts
+fn split_events(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(as_string)
+        .map(|s| {
+            s.split(',')
+                .map(|e| e.trim().to_uppercase())
+                .filter(|e| !e.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Read timing and events from a SQLite `CREATE TRIGGER` statement
+///
+/// Only the header up to `ON <table>` is examined. SQLite defaults to BEFORE
+/// when no timing is given.
+fn parse_sqlite_trigger(sql: &str) -> (String, Vec<String>) {
+    let mut timing = None;
+    let mut events = Vec::new();
+
+    let words = tokenize_with(sql, Quoting::for_driver(&DbDriver::Sqlite))
+        .filter(|t| t.kind == TokenKind::Word)
+        .map(|t| t.text.to_ascii_uppercase());
+    for word in words {
+        match word.as_str() {
+            "ON" => break,
+            "BEFORE" | "AFTER" if timing.is_none() => timing = Some(word),
+            "INSTEAD" if timing.is_none() => timing = Some("INSTEAD OF".to_string()),
+            "INSERT" | "UPDATE" | "DELETE" => events.push(word),
+            _ => {}
+        }
+    }
+
+    (timing.unwrap_or_else(|| "BEFORE".to_string()), events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_sqlite_trigger() {
+        assert_eq!(
+            parse_sqlite_trigger(
+                "CREATE TRIGGER audit_orders AFTER UPDATE OF status ON orders BEGIN INSERT INTO log VALUES (1); END"
+            ),
+            ("AFTER".to_string(), vec!["UPDATE".to_string()])
+        );
+        assert_eq!(
+            parse_sqlite_trigger(
+                "create temp trigger if not exists \"after\" instead of delete on v begin select 1; end"
+            ),
+            ("INSTEAD OF".to_string(), vec!["DELETE".to_string()])
+        );
+        // No timing keyword means BEFORE
+        assert_eq!(
+            parse_sqlite_trigger("CREATE TRIGGER t INSERT ON users BEGIN DELETE FROM x; END"),
+            ("BEFORE".to_string(), vec!["INSERT".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_split_events() {
+        assert_eq!(
+            split_events(Some(&json!("INSERT,update"))),
+            vec!["INSERT".to_string(), "UPDATE".to_string()]
+        );
+        assert!(split_events(Some(&json!(""))).is_empty());
+        assert!(split_events(None).is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+//! Master password commands
+//!
+//! Optionally encrypts `profiles.json` and `snippets.json` at rest. The key
+//! is derived from a master password with Argon2id and kept in memory only
+//! while the store is unlocked; see `AppState::seal_store_value`.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::models::DbError;
+use crate::state::AppState;
+
+/// Encryption state of the profile and snippet store
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreLockStatus {
+    /// A master password is set
+    pub encrypted: bool,
+    /// A master password is set and has not been entered this session
+    pub locked: bool,
+    /// No master password is set but profiles or snippets exist on disk,
+    /// so the UI can offer to encrypt them
+    pub unencrypted_data: bool,
+}
+
+/// Report whether the store is encrypted and locked
+#[tauri::command]
+pub fn get_store_lock_status(
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<StoreLockStatus, DbError> {
+    let state = state.lock().unwrap();
+    let unencrypted_data = !state.store_encrypted && state.has_unencrypted_store_data(&app)?;
+    Ok(StoreLockStatus {
+        encrypted: state.store_encrypted,
+        locked: state.is_store_locked(),
+        unencrypted_data,
+    })
+}
+
+/// Set the master password, or change it when one is already set
+///
+/// Setting it for the first time encrypts the existing profiles, snippets
+/// and saved views.
+#[tauri::command]
+pub fn set_master_password(
+    password: String,
+    current_password: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<(), DbError> {
+    let mut state = state.lock().unwrap();
+    state.set_master_password(&app, &password, current_password.as_deref())
+}
+
+/// Unlock the store and load its profiles, snippets and saved views
+///
+/// Returns `DbError::CredentialError` for a wrong password.
+#[tauri::command]
+pub fn unlock_store(
+    password: String,
+    state: State<'_, Mutex<AppState>>,
+    app: AppHandle,
+) -> Result<(), DbError> {
+    let mut state = state.lock().unwrap();
+    state.unlock_store(&app, &password)
+}
+
+/// Lock the store, dropping decrypted profiles and snippets from memory
+#[tauri::command]
+pub fn lock_store(state: State<'_, Mutex<AppState>>) -> Result<(), DbError> {
+    let mut state = state.lock().unwrap();
+    state.lock_store()
+}
@@ -102,3 +102,11 @@ pub fn take_pending_window_profile(
         .ok()
         .and_then(|mut map| map.remove(window.label()))
 }
+
+/// Quit the app after closing connections and SSH tunnels (see
+/// `state::request_shutdown`). Returns at once; the app emits
+/// `shutting-down` and exits when cleanup finishes.
+#[tauri::command]
+pub fn quit_app(app: AppHandle) {
+    crate::state::request_shutdown(&app);
+}
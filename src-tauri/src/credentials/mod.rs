@@ -6,6 +6,8 @@
 //! - Windows: Credential Manager
 //! - Linux: Secret Service API (libsecret)
 
+pub mod passphrase;
+
 use keyring::Entry;
 
 use crate::models::DbError;
@@ -138,6 +140,32 @@ impl CredentialManager {
         let ssh_key = format!("{}-ssh", connection_id);
         Self::delete_password(&ssh_key)
     }
+
+    /// Copy the password and SSH password of one connection to another
+    ///
+    /// Each connection gets its own keyring entries, so deleting either one
+    /// later leaves the other's intact.
+    ///
+    /// # Arguments
+    /// * `from_id` - Connection whose credentials are copied
+    /// * `to_id` - Connection receiving the copies
+    ///
+    /// # Returns
+    /// * `Ok(true)` if a database password was copied
+    /// * `Ok(false)` if `from_id` has no saved database password
+    /// * `Err(DbError)` on errors
+    pub fn copy_credentials(from_id: &str, to_id: &str) -> Result<bool, DbError> {
+        if let Some(ssh_password) = Self::get_ssh_password(from_id)? {
+            Self::save_ssh_password(to_id, &ssh_password)?;
+        }
+        match Self::get_password(from_id)? {
+            Some(password) => {
+                Self::save_password(to_id, &password)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -216,4 +244,39 @@ mod tests {
         // Cleanup
         CredentialManager::delete_password(test_id).unwrap();
     }
+
+    #[test]
+    fn test_copy_credentials_are_independent() {
+        let original = "test_connection_copy_original";
+        let copy = "test_connection_copy_duplicate";
+
+        // Clean up
+        for id in [original, copy] {
+            let _ = CredentialManager::delete_password(id);
+            let _ = CredentialManager::delete_ssh_password(id);
+        }
+
+        CredentialManager::save_password(original, "db_secret").unwrap();
+        CredentialManager::save_ssh_password(original, "ssh_secret").unwrap();
+        assert!(CredentialManager::copy_credentials(original, copy).unwrap());
+
+        // Deleting the original keeps the copy's credentials
+        CredentialManager::delete_password(original).unwrap();
+        CredentialManager::delete_ssh_password(original).unwrap();
+        assert_eq!(
+            CredentialManager::get_password(copy).unwrap(),
+            Some("db_secret".to_string())
+        );
+        assert_eq!(
+            CredentialManager::get_ssh_password(copy).unwrap(),
+            Some("ssh_secret".to_string())
+        );
+
+        // Nothing to copy
+        assert!(!CredentialManager::copy_credentials(original, copy).unwrap());
+
+        // Cleanup
+        CredentialManager::delete_password(copy).unwrap();
+        CredentialManager::delete_ssh_password(copy).unwrap();
+    }
 }
@@ -0,0 +1,188 @@
+//! Passphrase-based encryption
+//!
+//! Protects secrets that have to leave the OS keyring, such as passwords in
+//! an exported connection bundle. The key is derived from a user-supplied
+//! passphrase with Argon2id and the data is sealed with XChaCha20-Poly1305,
+//! so a wrong passphrase or a tampered file fails to decrypt rather than
+//! yielding garbage.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+
+use crate::models::DbError;
+
+/// Length of the random salt fed to Argon2
+const SALT_LEN: usize = 16;
+
+/// Encrypted data together with everything needed to decrypt it, apart from
+/// the passphrase. Binary fields are base64-encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedPayload {
+    /// Key derivation function; always `argon2id`
+    pub kdf: String,
+    /// Argon2 memory cost in KiB
+    pub memory_kib: u32,
+    /// Argon2 iterations
+    pub iterations: u32,
+    /// Argon2 lanes
+    pub parallelism: u32,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: Params) -> Result<[u8; 32], DbError> {
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| DbError::CredentialError(format!("Failed to derive key: {}", e)))?;
+    Ok(key)
+}
+
+fn decode(field: &str, value: &str) -> Result<Vec<u8>, DbError> {
+    BASE64
+        .decode(value)
+        .map_err(|e| DbError::CredentialError(format!("Invalid encrypted {}: {}", field, e)))
+}
+
+/// Key derived from a passphrase, reusable for several payloads
+///
+/// Argon2 is slow on purpose, so callers that encrypt repeatedly (the master
+/// password store) derive once and keep this around. Every payload sealed
+/// with it shares the salt but gets its own random nonce.
+pub struct MasterKey {
+    key: [u8; 32],
+    salt: Vec<u8>,
+    params: Params,
+}
+
+impl MasterKey {
+    /// Derive a key from `passphrase` with a fresh random salt
+    pub fn derive(passphrase: &str) -> Result<Self, DbError> {
+        let params = Params::default();
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        let key = derive_key(passphrase, &salt, params.clone())?;
+        Ok(Self { key, salt, params })
+    }
+
+    /// Re-derive the key that sealed `payload`, using its salt and parameters
+    pub fn derive_for(payload: &EncryptedPayload, passphrase: &str) -> Result<Self, DbError> {
+        if payload.kdf != "argon2id" {
+            return Err(DbError::CredentialError(format!(
+                "Unsupported key derivation function: {}",
+                payload.kdf
+            )));
+        }
+        let params = Params::new(
+            payload.memory_kib,
+            payload.iterations,
+            payload.parallelism,
+            None,
+        )
+        .map_err(|e| {
+            DbError::CredentialError(format!("Invalid key derivation parameters: {}", e))
+        })?;
+
+        let salt = decode("salt", &payload.salt)?;
+        let key = derive_key(passphrase, &salt, params.clone())?;
+        Ok(Self { key, salt, params })
+    }
+
+    /// Encrypt `plaintext` under a new random nonce
+    pub fn seal(&self, plaintext: &[u8]) -> Result<EncryptedPayload, DbError> {
+        let cipher = XChaCha20Poly1305::new(&self.key.into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| DbError::CredentialError("Failed to encrypt data".to_string()))?;
+
+        Ok(EncryptedPayload {
+            kdf: "argon2id".to_string(),
+            memory_kib: self.params.m_cost(),
+            iterations: self.params.t_cost(),
+            parallelism: self.params.p_cost(),
+            salt: BASE64.encode(&self.salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        })
+    }
+
+    /// Decrypt a payload sealed with this key
+    ///
+    /// Fails with `DbError::CredentialError` when the payload was sealed
+    /// with a different key or was modified.
+    pub fn open(&self, payload: &EncryptedPayload) -> Result<Vec<u8>, DbError> {
+        let nonce = decode("nonce", &payload.nonce)?;
+        let ciphertext = decode("data", &payload.ciphertext)?;
+        if nonce.len() != 24 {
+            return Err(DbError::CredentialError(
+                "Invalid encrypted nonce length".to_string(),
+            ));
+        }
+
+        XChaCha20Poly1305::new(&self.key.into())
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                DbError::CredentialError("Wrong passphrase or corrupted encrypted data".to_string())
+            })
+    }
+}
+
+/// Encrypt `plaintext` with a key derived from `passphrase`
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<EncryptedPayload, DbError> {
+    MasterKey::derive(passphrase)?.seal(plaintext)
+}
+
+/// Decrypt a payload produced by [`encrypt`]
+///
+/// Fails with `DbError::CredentialError` when the passphrase is wrong or the
+/// payload was modified.
+pub fn decrypt(payload: &EncryptedPayload, passphrase: &str) -> Result<Vec<u8>, DbError> {
+    MasterKey::derive_for(payload, passphrase)?.open(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_wrong_passphrase() {
+        let payload = encrypt(b"secret", "correct horse").unwrap();
+        assert_eq!(payload.kdf, "argon2id");
+        assert_eq!(decrypt(&payload, "correct horse").unwrap(), b"secret");
+        assert!(matches!(
+            decrypt(&payload, "wrong horse"),
+            Err(DbError::CredentialError(_))
+        ));
+
+        // Fresh salt and nonce every time
+        let again = encrypt(b"secret", "correct horse").unwrap();
+        assert_ne!(payload.salt, again.salt);
+        assert_ne!(payload.ciphertext, again.ciphertext);
+    }
+
+    #[test]
+    fn test_master_key_reuse() {
+        let key = MasterKey::derive("master").unwrap();
+        let first = key.seal(b"profiles").unwrap();
+        let second = key.seal(b"snippets").unwrap();
+        // Same salt, different nonces
+        assert_eq!(first.salt, second.salt);
+        assert_ne!(first.nonce, second.nonce);
+
+        let again = MasterKey::derive_for(&first, "master").unwrap();
+        assert_eq!(again.open(&second).unwrap(), b"snippets");
+        assert_eq!(decrypt(&first, "master").unwrap(), b"profiles");
+
+        let wrong = MasterKey::derive_for(&first, "not it").unwrap();
+        assert!(wrong.open(&first).is_err());
+    }
+}
@@ -10,9 +10,13 @@ pub mod sqlite;
 pub mod sqlserver;
 
 use crate::models::{
-    ddl::{AlterTableDefinition, DdlResult, DropTableDefinition, TableDefinition},
-    DbDriver, DbError,
+    ddl::{
+        AlterTableDefinition, ColumnType, DdlResult, DropTableDefinition, DropViewDefinition,
+        IndexDefinition, TableDefinition, ViewDefinition,
+    },
+    ColumnInfo, DbDriver, DbError,
 };
+use crate::sql::tokenizer::count_statements;
 
 /// DDL generator trait
 ///
@@ -26,6 +30,114 @@ pub trait DdlGenerator {
 
     /// Generate DROP TABLE statement
     fn generate_drop_table(&self, drop: &DropTableDefinition) -> Result<DdlResult, DbError>;
+
+    /// Generate CREATE VIEW statement
+    fn generate_create_view(&self, view: &ViewDefinition) -> Result<DdlResult, DbError>;
+
+    /// Generate DROP VIEW statement
+    fn generate_drop_view(&self, drop: &DropViewDefinition) -> Result<DdlResult, DbError>;
+
+    /// Generate the statement renaming table `schema.old_name` to `new_name`
+    fn generate_rename_table(
+        &self,
+        schema: Option<&str>,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<DdlResult, DbError>;
+
+    /// Generate the statement setting the comment of table `schema.table`;
+    /// `None` removes it
+    fn generate_table_comment(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        comment: Option<&str>,
+    ) -> Result<DdlResult, DbError>;
+
+    /// Generate the statement setting the comment of `column` in
+    /// `schema.table`; `None` removes it
+    ///
+    /// Takes the column's current definition, since MySQL can only change a
+    /// comment by restating the whole column.
+    fn generate_column_comment(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        column: &ColumnInfo,
+        comment: Option<&str>,
+    ) -> Result<DdlResult, DbError>;
+
+    /// Generate CREATE INDEX statement for an index on `schema.table`
+    fn generate_create_index(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        index: &IndexDefinition,
+    ) -> Result<DdlResult, DbError>;
+
+    /// Native type name for `column_type` in this dialect
+    fn column_type_sql(&self, column_type: &ColumnType) -> String;
+}
+
+/// Check an index has a valid name and at least one column
+pub(crate) fn validate_index(index: &IndexDefinition) -> Result<(), DbError> {
+    validate_identifier(&index.name, "Index name")?;
+    if index.columns.is_empty() {
+        return Err(DbError::InvalidInput(
+            "Index must have at least one column".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check a view's name and column names, and return its query ready to
+/// follow `AS`: trimmed, without a trailing semicolon
+pub(crate) fn view_query(view: &ViewDefinition) -> Result<&str, DbError> {
+    validate_identifier(&view.name, "View name")?;
+    if let Some(columns) = &view.columns {
+        if columns.is_empty() {
+            return Err(DbError::InvalidInput(
+                "View column list cannot be empty".to_string(),
+            ));
+        }
+        for column in columns {
+            validate_identifier(column, "View column name")?;
+        }
+    }
+
+    let query = view
+        .query
+        .trim()
+        .trim_end_matches(|c: char| c == ';' || c.is_whitespace());
+    match count_statements(query) {
+        0 => Err(DbError::InvalidInput("View query cannot be empty".to_string())),
+        1 => Ok(query),
+        _ => Err(DbError::InvalidInput(
+            "View query must be a single statement".to_string(),
+        )),
+    }
+}
+
+/// Check that `name` is a plain identifier: a letter or underscore followed
+/// by letters, digits, underscores or `$`
+pub(crate) fn validate_identifier(name: &str, what: &str) -> Result<(), DbError> {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if !valid_start || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$') {
+        return Err(DbError::InvalidInput(format!(
+            "{} '{}' must start with a letter or underscore and contain only letters, digits, underscores and $",
+            what, name
+        )));
+    }
+    if name.len() > 63 {
+        return Err(DbError::InvalidInput(format!(
+            "{} '{}' is too long (max 63 chars)",
+            what, name
+        )));
+    }
+    Ok(())
 }
 
 /// Get DDL generator for a specific database driver
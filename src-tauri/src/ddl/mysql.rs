@@ -3,15 +3,45 @@
 //! Generates MySQL-specific DDL statements for table creation,
 //! alteration, and deletion.
 
-use crate::ddl::DdlGenerator;
+use crate::ddl::{validate_identifier, validate_index, view_query, DdlGenerator};
 use crate::models::{
     ddl::{
         AlterColumnOperation, AlterTableDefinition, CheckConstraint, ColumnDefinition,
-        ColumnType, DdlResult, DropTableDefinition, ForeignKeyAction, ForeignKeyConstraint,
-        TableDefinition, UniqueConstraint,
+        ColumnType, DdlResult, DropTableDefinition, DropViewDefinition, ForeignKeyAction,
+        ForeignKeyConstraint, IndexDefinition, IndexType, TableDefinition, UniqueConstraint,
+        ViewDefinition,
     },
-    DbError,
+    ColumnInfo, DbDriver, DbError,
 };
+use crate::sql::literal::escape_literal;
+use crate::sql::quote::{quote_identifier, quote_list};
+use crate::sql::tokenizer::Quoting;
+
+const DIALECT: Quoting = Quoting::MySql;
+
+/// `comment` as a MySQL string literal; an empty comment removes it
+fn comment_literal(comment: Option<&str>) -> String {
+    escape_literal(comment.unwrap_or_default(), &DbDriver::MySql)
+}
+
+/// `DEFAULT` clause restating a default as information_schema reports it
+///
+/// MySQL reports literal defaults unquoted, so everything but NULL, the
+/// `CURRENT_TIMESTAMP` family and defaults MariaDB already quotes is quoted
+/// again.
+fn default_clause(default: &str) -> String {
+    let upper = default.to_ascii_uppercase();
+    let raw = upper == "NULL"
+        || upper.starts_with("CURRENT_")
+        || upper.starts_with("NOW(")
+        || upper.starts_with("LOCALTIME")
+        || (default.len() > 1 && default.starts_with('\'') && default.ends_with('\''));
+    if raw {
+        format!("DEFAULT {}", default)
+    } else {
+        format!("DEFAULT {}", escape_literal(default, &DbDriver::MySql))
+    }
+}
 
 /// MySQL DDL generator
 pub struct MySqlDdlGenerator;
@@ -53,7 +83,7 @@ impl MySqlDdlGenerator {
         let mut parts = Vec::new();
 
         // Column name
-        parts.push(format!("`{}`", col.name));
+        parts.push(quote_identifier(&col.name, DIALECT));
 
         // Column type
         parts.push(self.column_type_to_sql(&col.column_type));
@@ -83,7 +113,7 @@ impl MySqlDdlGenerator {
 
         // Comment
         if let Some(comment) = &col.comment {
-            parts.push(format!("COMMENT '{}'", comment.replace('\'', "''")));
+            parts.push(format!("COMMENT {}", comment_literal(Some(comment))));
         }
 
         Ok(parts.join(" "))
@@ -107,25 +137,15 @@ impl MySqlDdlGenerator {
             |name| name.clone(),
         );
 
-        let columns = fk
-            .columns
-            .iter()
-            .map(|c| format!("`{}`", c))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let columns = quote_list(&fk.columns, DIALECT);
 
-        let ref_columns = fk
-            .referenced_columns
-            .iter()
-            .map(|c| format!("`{}`", c))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let ref_columns = quote_list(&fk.referenced_columns, DIALECT);
 
         format!(
-            "CONSTRAINT `{}` FOREIGN KEY ({}) REFERENCES `{}` ({}) ON DELETE {} ON UPDATE {}",
-            constraint_name,
+            "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({}) ON DELETE {} ON UPDATE {}",
+            quote_identifier(&constraint_name, DIALECT),
             columns,
-            fk.referenced_table,
+            quote_identifier(&fk.referenced_table, DIALECT),
             ref_columns,
             self.foreign_key_action_to_sql(&fk.on_delete),
             self.foreign_key_action_to_sql(&fk.on_update)
@@ -139,14 +159,13 @@ impl MySqlDdlGenerator {
             |name| name.clone(),
         );
 
-        let columns = unique
-            .columns
-            .iter()
-            .map(|c| format!("`{}`", c))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let columns = quote_list(&unique.columns, DIALECT);
 
-        format!("CONSTRAINT `{}` UNIQUE ({})", constraint_name, columns)
+        format!(
+            "CONSTRAINT {} UNIQUE ({})",
+            quote_identifier(&constraint_name, DIALECT),
+            columns
+        )
     }
 
     /// Generate check constraint SQL (MySQL 8.0.16+)
@@ -156,21 +175,24 @@ impl MySqlDdlGenerator {
             .as_ref()
             .map_or_else(|| format!("check_{}", table_name), |name| name.clone());
 
-        format!("CONSTRAINT `{}` CHECK ({})", constraint_name, check.expression)
+        format!(
+            "CONSTRAINT {} CHECK ({})",
+            quote_identifier(&constraint_name, DIALECT),
+            check.expression
+        )
     }
 
     /// Generate primary key constraint SQL
     fn generate_primary_key_sql(&self, columns: &[String]) -> String {
-        let col_list = columns
-            .iter()
-            .map(|c| format!("`{}`", c))
-            .collect::<Vec<_>>()
-            .join(", ");
-        format!("PRIMARY KEY ({})", col_list)
+        format!("PRIMARY KEY ({})", quote_list(columns, DIALECT))
     }
 }
 
 impl DdlGenerator for MySqlDdlGenerator {
+    fn column_type_sql(&self, column_type: &ColumnType) -> String {
+        self.column_type_to_sql(column_type)
+    }
+
     fn generate_create_table(&self, table: &TableDefinition) -> Result<DdlResult, DbError> {
         if table.columns.is_empty() {
             return Err(DbError::InvalidInput(
@@ -187,7 +209,11 @@ impl DdlGenerator for MySqlDdlGenerator {
             ""
         };
 
-        sql_parts.push(format!("CREATE TABLE {}`{}` (", if_not_exists, table.name));
+        sql_parts.push(format!(
+            "CREATE TABLE {}{} (",
+            if_not_exists,
+            quote_identifier(&table.name, DIALECT)
+        ));
 
         // Column definitions
         let mut table_elements = Vec::new();
@@ -237,7 +263,13 @@ impl DdlGenerator for MySqlDdlGenerator {
         }
 
         sql_parts.push(table_elements.join(",\n"));
-        sql_parts.push(") ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;".to_string());
+        match &table.comment {
+            Some(comment) => sql_parts.push(format!(
+                ") ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT={};",
+                comment_literal(Some(comment))
+            )),
+            None => sql_parts.push(") ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;".to_string()),
+        }
 
         let full_sql = vec![sql_parts.join("\n")];
 
@@ -254,7 +286,7 @@ impl DdlGenerator for MySqlDdlGenerator {
             ));
         }
 
-        let table_name = format!("`{}`", alter.name);
+        let table_name = quote_identifier(&alter.name, DIALECT);
         let mut sql_statements = Vec::new();
 
         for op in &alter.operations {
@@ -268,13 +300,19 @@ impl DdlGenerator for MySqlDdlGenerator {
                 }
                 AlterColumnOperation::DropColumn { column_name, .. } => {
                     // MySQL doesn't support CASCADE in column drop
-                    format!("ALTER TABLE {} DROP COLUMN `{}`;", table_name, column_name)
+                    format!(
+                        "ALTER TABLE {} DROP COLUMN {};",
+                        table_name,
+                        quote_identifier(column_name, DIALECT)
+                    )
                 }
                 AlterColumnOperation::RenameColumn { old_name, new_name } => {
                     // MySQL 8.0+ supports RENAME COLUMN
                     format!(
-                        "ALTER TABLE {} RENAME COLUMN `{}` TO `{}`;",
-                        table_name, old_name, new_name
+                        "ALTER TABLE {} RENAME COLUMN {} TO {};",
+                        table_name,
+                        quote_identifier(old_name, DIALECT),
+                        quote_identifier(new_name, DIALECT)
                     )
                 }
                 AlterColumnOperation::AlterType {
@@ -282,9 +320,9 @@ impl DdlGenerator for MySqlDdlGenerator {
                     new_type,
                 } => {
                     format!(
-                        "ALTER TABLE {} MODIFY COLUMN `{}` {};",
+                        "ALTER TABLE {} MODIFY COLUMN {} {};",
                         table_name,
-                        column_name,
+                        quote_identifier(column_name, DIALECT),
                         self.column_type_to_sql(new_type)
                     )
                 }
@@ -293,9 +331,9 @@ impl DdlGenerator for MySqlDdlGenerator {
                     // This is a simplified version - in production, you'd need to fetch the current column definition
                     let null_clause = if *not_null { "NOT NULL" } else { "NULL" };
                     format!(
-                        "ALTER TABLE {} MODIFY COLUMN `{}` {} {};",
+                        "ALTER TABLE {} MODIFY COLUMN {} {} {};",
                         table_name,
-                        column_name,
+                        quote_identifier(column_name, DIALECT),
                         "/* type needed */",
                         null_clause
                     )
@@ -306,13 +344,16 @@ impl DdlGenerator for MySqlDdlGenerator {
                 } => {
                     if let Some(default_value) = default {
                         format!(
-                            "ALTER TABLE {} ALTER COLUMN `{}` SET DEFAULT {};",
-                            table_name, column_name, default_value
+                            "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};",
+                            table_name,
+                            quote_identifier(column_name, DIALECT),
+                            default_value
                         )
                     } else {
                         format!(
-                            "ALTER TABLE {} ALTER COLUMN `{}` DROP DEFAULT;",
-                            table_name, column_name
+                            "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;",
+                            table_name,
+                            quote_identifier(column_name, DIALECT)
                         )
                     }
                 }
@@ -329,13 +370,183 @@ impl DdlGenerator for MySqlDdlGenerator {
     fn generate_drop_table(&self, drop: &DropTableDefinition) -> Result<DdlResult, DbError> {
         let if_exists = if drop.if_exists { "IF EXISTS " } else { "" };
         // MySQL doesn't support CASCADE in DROP TABLE
-        let sql = format!("DROP TABLE {}`{}`;", if_exists, drop.name);
+        let sql = format!(
+            "DROP TABLE {}{};",
+            if_exists,
+            quote_identifier(&drop.name, DIALECT)
+        );
 
         Ok(DdlResult {
             sql: vec![sql],
             message: format!("Table `{}` dropped successfully", drop.name),
         })
     }
+
+    fn generate_create_view(&self, view: &ViewDefinition) -> Result<DdlResult, DbError> {
+        let query = view_query(view)?;
+        if view.materialized {
+            return Err(DbError::InvalidInput(
+                "MySQL does not support materialized views".to_string(),
+            ));
+        }
+
+        let or_replace = if view.or_replace { "OR REPLACE " } else { "" };
+        let columns = view.columns.as_ref().map_or(String::new(), |cols| {
+            format!(" ({})", quote_list(cols, DIALECT))
+        });
+
+        let sql = format!(
+            "CREATE {}VIEW {}{} AS\n{};",
+            or_replace,
+            quote_identifier(&view.name, DIALECT),
+            columns,
+            query
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("View `{}` created successfully", view.name),
+        })
+    }
+
+    fn generate_drop_view(&self, drop: &DropViewDefinition) -> Result<DdlResult, DbError> {
+        validate_identifier(&drop.name, "View name")?;
+        if drop.materialized {
+            return Err(DbError::InvalidInput(
+                "MySQL does not support materialized views".to_string(),
+            ));
+        }
+
+        let if_exists = if drop.if_exists { "IF EXISTS " } else { "" };
+        // MySQL accepts CASCADE in DROP VIEW but ignores it
+        let sql = format!(
+            "DROP VIEW {}{};",
+            if_exists,
+            quote_identifier(&drop.name, DIALECT)
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("View `{}` dropped successfully", drop.name),
+        })
+    }
+
+    fn generate_rename_table(
+        &self,
+        _schema: Option<&str>,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<DdlResult, DbError> {
+        validate_identifier(new_name, "Table name")?;
+        let sql = format!(
+            "RENAME TABLE {} TO {};",
+            quote_identifier(old_name, DIALECT),
+            quote_identifier(new_name, DIALECT)
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Table `{}` renamed to `{}`", old_name, new_name),
+        })
+    }
+
+    fn generate_table_comment(
+        &self,
+        _schema: Option<&str>,
+        table: &str,
+        comment: Option<&str>,
+    ) -> Result<DdlResult, DbError> {
+        let sql = format!(
+            "ALTER TABLE {} COMMENT = {};",
+            quote_identifier(table, DIALECT),
+            comment_literal(comment)
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Comment on table `{}` updated", table),
+        })
+    }
+
+    /// MySQL has no statement for just the comment, so the column is
+    /// restated with `MODIFY COLUMN` from its reported type, generation
+    /// expression, nullability, default and AUTO_INCREMENT. Attributes
+    /// `ColumnInfo` does not carry, such as `ON UPDATE` or a column
+    /// collation, are not restated.
+    fn generate_column_comment(
+        &self,
+        _schema: Option<&str>,
+        table: &str,
+        column: &ColumnInfo,
+        comment: Option<&str>,
+    ) -> Result<DdlResult, DbError> {
+        let mut parts = vec![
+            quote_identifier(&column.name, DIALECT),
+            column.data_type.clone(),
+        ];
+        if column.is_generated {
+            // Without the expression MODIFY would turn it into a plain column
+            let expression = column.generation_expression.as_deref().ok_or_else(|| {
+                DbError::InvalidInput(format!(
+                    "Generation expression of column `{}` is unknown",
+                    column.name
+                ))
+            })?;
+            parts.push(format!("AS ({})", expression));
+        }
+        parts.push(if column.nullable { "NULL" } else { "NOT NULL" }.to_string());
+        if let Some(default) = &column.default_value {
+            parts.push(default_clause(default));
+        }
+        if column.is_auto_increment {
+            parts.push("AUTO_INCREMENT".to_string());
+        }
+        parts.push(format!("COMMENT {}", comment_literal(comment)));
+
+        let sql = format!(
+            "ALTER TABLE {} MODIFY COLUMN {};",
+            quote_identifier(table, DIALECT),
+            parts.join(" ")
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Comment on column `{}` updated", column.name),
+        })
+    }
+
+    fn generate_create_index(
+        &self,
+        _schema: Option<&str>,
+        table: &str,
+        index: &IndexDefinition,
+    ) -> Result<DdlResult, DbError> {
+        validate_index(index)?;
+
+        let unique = if index.unique { "UNIQUE " } else { "" };
+        let using = match index.index_type {
+            IndexType::BTree => "",
+            IndexType::Hash => " USING HASH",
+            IndexType::Gist | IndexType::Gin => {
+                return Err(DbError::InvalidInput(
+                    "GiST and GIN indexes are not supported in MySQL".to_string(),
+                ))
+            }
+        };
+        let sql = format!(
+            "CREATE {}INDEX {} ON {} ({}){};",
+            unique,
+            quote_identifier(&index.name, DIALECT),
+            quote_identifier(table, DIALECT),
+            quote_list(&index.columns, DIALECT),
+            using
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Index `{}` created successfully", index.name),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -401,4 +612,148 @@ mod tests {
         assert!(result.sql[0].contains("DROP TABLE IF EXISTS"));
         assert!(result.sql[0].contains("`users`"));
     }
+
+
+    fn active_users_view() -> ViewDefinition {
+        ViewDefinition {
+            schema: Some("shop".to_string()),
+            name: "active_users".to_string(),
+            columns: Some(vec!["id".to_string(), "email".to_string()]),
+            query: "SELECT id, email FROM users WHERE active;\n".to_string(),
+            materialized: false,
+            or_replace: true,
+        }
+    }
+
+    #[test]
+    fn test_create_and_drop_view() {
+        let generator = MySqlDdlGenerator;
+
+        let result = generator.generate_create_view(&active_users_view()).unwrap();
+        assert_eq!(
+            result.sql[0],
+            "CREATE OR REPLACE VIEW `active_users` (`id`, `email`) AS\n\
+             SELECT id, email FROM users WHERE active;"
+        );
+
+        let materialized = ViewDefinition {
+            materialized: true,
+            ..active_users_view()
+        };
+        assert!(generator.generate_create_view(&materialized).is_err());
+
+        let drop = DropViewDefinition {
+            schema: None,
+            name: "active_users".to_string(),
+            materialized: false,
+            cascade: true,
+            if_exists: true,
+        };
+        let result = generator.generate_drop_view(&drop).unwrap();
+        assert_eq!(result.sql[0], "DROP VIEW IF EXISTS `active_users`;");
+    }
+
+    #[test]
+    fn test_create_index() {
+        let generator = MySqlDdlGenerator;
+
+        let index = IndexDefinition {
+            name: "users_email_key".to_string(),
+            columns: vec!["email".to_string(), "tenant_id".to_string()],
+            unique: true,
+            index_type: IndexType::BTree,
+        };
+        let result = generator.generate_create_index(None, "users", &index).unwrap();
+        assert_eq!(
+            result.sql[0],
+            "CREATE UNIQUE INDEX `users_email_key` ON `users` (`email`, `tenant_id`);"
+        );
+
+        let gin = IndexDefinition {
+            index_type: IndexType::Gin,
+            ..index
+        };
+        assert!(generator.generate_create_index(None, "users", &gin).is_err());
+    }
+
+    #[test]
+    fn test_create_table_with_comments() {
+        let generator = MySqlDdlGenerator;
+
+        let table = TableDefinition {
+            schema: None,
+            name: "files".to_string(),
+            columns: vec![ColumnDefinition {
+                name: "path".to_string(),
+                column_type: ColumnType::Text,
+                nullable: true,
+                default: None,
+                primary_key: false,
+                auto_increment: false,
+                comment: Some("C:\\data's root".to_string()),
+            }],
+            primary_key: None,
+            foreign_keys: vec![],
+            unique_constraints: vec![],
+            check_constraints: vec![],
+            comment: Some("Uploaded files".to_string()),
+            if_not_exists: false,
+        };
+
+        let result = generator.generate_create_table(&table).unwrap();
+        assert!(result.sql[0].contains("`path` TEXT COMMENT 'C:\\\\data''s root'"));
+        assert!(result.sql[0].ends_with("utf8mb4 COMMENT='Uploaded files';"));
+    }
+
+    #[test]
+    fn test_table_and_column_comments() {
+        let generator = MySqlDdlGenerator;
+        let result = generator
+            .generate_table_comment(None, "orders", None)
+            .unwrap();
+        assert_eq!(result.sql[0], "ALTER TABLE `orders` COMMENT = '';");
+
+        let mut status = ColumnInfo::new("status".to_string(), "varchar(20)".to_string(), false);
+        status.default_value = Some("new".to_string());
+        let result = generator
+            .generate_column_comment(None, "orders", &status, Some("Order state"))
+            .unwrap();
+        assert_eq!(
+            result.sql[0],
+            "ALTER TABLE `orders` MODIFY COLUMN `status` varchar(20) NOT NULL \
+             DEFAULT 'new' COMMENT 'Order state';"
+        );
+
+        let mut id = ColumnInfo::new("id".to_string(), "int".to_string(), false);
+        id.is_auto_increment = true;
+        let created = ColumnInfo::with_details(
+            "created_at".to_string(),
+            "timestamp".to_string(),
+            true,
+            Some("CURRENT_TIMESTAMP".to_string()),
+            false,
+        );
+        let result = generator
+            .generate_column_comment(None, "orders", &id, Some("Key"))
+            .unwrap();
+        assert!(result.sql[0].contains("`id` int NOT NULL AUTO_INCREMENT COMMENT 'Key'"));
+        let result = generator
+            .generate_column_comment(None, "orders", &created, None)
+            .unwrap();
+        assert!(result.sql[0].contains("NULL DEFAULT CURRENT_TIMESTAMP COMMENT ''"));
+
+        let mut total = ColumnInfo::new("total".to_string(), "int".to_string(), true);
+        total.is_generated = true;
+        total.generation_expression = Some("(`price` * `qty`)".to_string());
+        let result = generator
+            .generate_column_comment(None, "orders", &total, Some("Line total"))
+            .unwrap();
+        assert!(
+            result.sql[0].contains("`total` int AS ((`price` * `qty`)) NULL COMMENT 'Line total'")
+        );
+        total.generation_expression = None;
+        assert!(generator
+            .generate_column_comment(None, "orders", &total, None)
+            .is_err());
+    }
 }
@@ -3,15 +3,30 @@
 //! Generates PostgreSQL-specific DDL statements for table creation,
 //! alteration, and deletion.
 
-use crate::ddl::DdlGenerator;
+use crate::ddl::{validate_identifier, validate_index, view_query, DdlGenerator};
 use crate::models::{
     ddl::{
         AlterColumnOperation, AlterTableDefinition, CheckConstraint, ColumnDefinition,
-        ColumnType, DdlResult, DropTableDefinition, ForeignKeyAction, ForeignKeyConstraint,
-        TableDefinition, UniqueConstraint,
+        ColumnType, DdlResult, DropTableDefinition, DropViewDefinition, ForeignKeyAction,
+        ForeignKeyConstraint, IndexDefinition, IndexType, TableDefinition, UniqueConstraint,
+        ViewDefinition,
     },
-    DbError,
+    ColumnInfo, DbDriver, DbError,
 };
+use crate::sql::literal::escape_literal;
+use crate::sql::quote::{quote_identifier, quote_list, quote_qualified};
+use crate::sql::tokenizer::Quoting;
+
+const DIALECT: Quoting = Quoting::Standard;
+
+/// `COMMENT ON <target>` statement; a `None` comment removes it
+fn comment_sql(target: &str, comment: Option<&str>) -> String {
+    let value = comment.map_or_else(
+        || "NULL".to_string(),
+        |c| escape_literal(c, &DbDriver::Postgres),
+    );
+    format!("COMMENT ON {} IS {};", target, value)
+}
 
 /// PostgreSQL DDL generator
 pub struct PostgresDdlGenerator;
@@ -52,7 +67,7 @@ impl PostgresDdlGenerator {
         let mut parts = Vec::new();
 
         // Column name
-        parts.push(format!("\"{}\"", col.name));
+        parts.push(quote_identifier(&col.name, DIALECT));
 
         // Column type (handle auto-increment specially)
         if col.auto_increment {
@@ -108,25 +123,15 @@ impl PostgresDdlGenerator {
             |name| name.clone(),
         );
 
-        let columns = fk
-            .columns
-            .iter()
-            .map(|c| format!("\"{}\"", c))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let columns = quote_list(&fk.columns, DIALECT);
 
-        let ref_columns = fk
-            .referenced_columns
-            .iter()
-            .map(|c| format!("\"{}\"", c))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let ref_columns = quote_list(&fk.referenced_columns, DIALECT);
 
         format!(
-            "CONSTRAINT \"{}\" FOREIGN KEY ({}) REFERENCES \"{}\" ({}) ON DELETE {} ON UPDATE {}",
-            constraint_name,
+            "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({}) ON DELETE {} ON UPDATE {}",
+            quote_identifier(&constraint_name, DIALECT),
             columns,
-            fk.referenced_table,
+            quote_identifier(&fk.referenced_table, DIALECT),
             ref_columns,
             self.foreign_key_action_to_sql(&fk.on_delete),
             self.foreign_key_action_to_sql(&fk.on_update)
@@ -146,14 +151,13 @@ impl PostgresDdlGenerator {
             |name| name.clone(),
         );
 
-        let columns = unique
-            .columns
-            .iter()
-            .map(|c| format!("\"{}\"", c))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let columns = quote_list(&unique.columns, DIALECT);
 
-        format!("CONSTRAINT \"{}\" UNIQUE ({})", constraint_name, columns)
+        format!(
+            "CONSTRAINT {} UNIQUE ({})",
+            quote_identifier(&constraint_name, DIALECT),
+            columns
+        )
     }
 
     /// Generate check constraint SQL
@@ -164,23 +168,23 @@ impl PostgresDdlGenerator {
         );
 
         format!(
-            "CONSTRAINT \"{}\" CHECK ({})",
-            constraint_name, check.expression
+            "CONSTRAINT {} CHECK ({})",
+            quote_identifier(&constraint_name, DIALECT),
+            check.expression
         )
     }
 
     /// Generate primary key constraint SQL
     fn generate_primary_key_sql(&self, columns: &[String]) -> String {
-        let col_list = columns
-            .iter()
-            .map(|c| format!("\"{}\"", c))
-            .collect::<Vec<_>>()
-            .join(", ");
-        format!("PRIMARY KEY ({})", col_list)
+        format!("PRIMARY KEY ({})", quote_list(columns, DIALECT))
     }
 }
 
 impl DdlGenerator for PostgresDdlGenerator {
+    fn column_type_sql(&self, column_type: &ColumnType) -> String {
+        self.column_type_to_sql(column_type)
+    }
+
     fn generate_create_table(&self, table: &TableDefinition) -> Result<DdlResult, DbError> {
         if table.columns.is_empty() {
             return Err(DbError::InvalidInput(
@@ -191,10 +195,7 @@ impl DdlGenerator for PostgresDdlGenerator {
         let mut sql_parts = Vec::new();
 
         // CREATE TABLE clause
-        let schema_prefix = table
-            .schema
-            .as_ref()
-            .map_or(String::new(), |s| format!("\"{}\".", s));
+        let table_name = quote_qualified(table.schema.as_deref(), &table.name, DIALECT);
 
         let if_not_exists = if table.if_not_exists {
             "IF NOT EXISTS "
@@ -202,10 +203,7 @@ impl DdlGenerator for PostgresDdlGenerator {
             ""
         };
 
-        sql_parts.push(format!(
-            "CREATE TABLE {}{}\"{}\" (",
-            if_not_exists, schema_prefix, table.name
-        ));
+        sql_parts.push(format!("CREATE TABLE {}{} (", if_not_exists, table_name));
 
         // Column definitions
         let mut table_elements = Vec::new();
@@ -264,23 +262,19 @@ impl DdlGenerator for PostgresDdlGenerator {
 
         // Add table comment if provided
         if let Some(comment) = &table.comment {
-            full_sql.push(format!(
-                "COMMENT ON TABLE {}\"{}\" IS '{}';",
-                schema_prefix,
-                table.name,
-                comment.replace('\'', "''")
-            ));
+            full_sql.push(comment_sql(&format!("TABLE {}", table_name), Some(comment)));
         }
 
         // Add column comments
         for col in &table.columns {
             if let Some(comment) = &col.comment {
-                full_sql.push(format!(
-                    "COMMENT ON COLUMN {}\"{}\".\"{}\". IS '{}';",
-                    schema_prefix,
-                    table.name,
-                    col.name,
-                    comment.replace('\'', "''")
+                full_sql.push(comment_sql(
+                    &format!(
+                        "COLUMN {}.{}",
+                        table_name,
+                        quote_identifier(&col.name, DIALECT)
+                    ),
+                    Some(comment),
                 ));
             }
         }
@@ -298,12 +292,7 @@ impl DdlGenerator for PostgresDdlGenerator {
             ));
         }
 
-        let schema_prefix = alter
-            .schema
-            .as_ref()
-            .map_or(String::new(), |s| format!("\"{}\".", s));
-
-        let table_name = format!("{}\"{}\"", schema_prefix, alter.name);
+        let table_name = quote_qualified(alter.schema.as_deref(), &alter.name, DIALECT);
         let mut sql_statements = Vec::new();
 
         for op in &alter.operations {
@@ -318,41 +307,50 @@ impl DdlGenerator for PostgresDdlGenerator {
                 AlterColumnOperation::DropColumn { column_name, cascade } => {
                     let cascade_clause = if *cascade { " CASCADE" } else { "" };
                     format!(
-                        "ALTER TABLE {} DROP COLUMN \"{}\"{};",
-                        table_name, column_name, cascade_clause
+                        "ALTER TABLE {} DROP COLUMN {}{};",
+                        table_name,
+                        quote_identifier(column_name, DIALECT),
+                        cascade_clause
                     )
                 }
                 AlterColumnOperation::RenameColumn { old_name, new_name } => {
                     format!(
-                        "ALTER TABLE {} RENAME COLUMN \"{}\" TO \"{}\";",
-                        table_name, old_name, new_name
+                        "ALTER TABLE {} RENAME COLUMN {} TO {};",
+                        table_name,
+                        quote_identifier(old_name, DIALECT),
+                        quote_identifier(new_name, DIALECT)
                     )
                 }
                 AlterColumnOperation::AlterType { column_name, new_type } => {
                     format!(
-                        "ALTER TABLE {} ALTER COLUMN \"{}\" TYPE {};",
+                        "ALTER TABLE {} ALTER COLUMN {} TYPE {};",
                         table_name,
-                        column_name,
+                        quote_identifier(column_name, DIALECT),
                         self.column_type_to_sql(new_type)
                     )
                 }
                 AlterColumnOperation::SetNotNull { column_name, not_null } => {
                     let action = if *not_null { "SET NOT NULL" } else { "DROP NOT NULL" };
                     format!(
-                        "ALTER TABLE {} ALTER COLUMN \"{}\" {};",
-                        table_name, column_name, action
+                        "ALTER TABLE {} ALTER COLUMN {} {};",
+                        table_name,
+                        quote_identifier(column_name, DIALECT),
+                        action
                     )
                 }
                 AlterColumnOperation::SetDefault { column_name, default } => {
                     if let Some(default_value) = default {
                         format!(
-                            "ALTER TABLE {} ALTER COLUMN \"{}\" SET DEFAULT {};",
-                            table_name, column_name, default_value
+                            "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};",
+                            table_name,
+                            quote_identifier(column_name, DIALECT),
+                            default_value
                         )
                     } else {
                         format!(
-                            "ALTER TABLE {} ALTER COLUMN \"{}\" DROP DEFAULT;",
-                            table_name, column_name
+                            "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;",
+                            table_name,
+                            quote_identifier(column_name, DIALECT)
                         )
                     }
                 }
@@ -367,22 +365,147 @@ impl DdlGenerator for PostgresDdlGenerator {
     }
 
     fn generate_drop_table(&self, drop: &DropTableDefinition) -> Result<DdlResult, DbError> {
-        let schema_prefix = drop
-            .schema
-            .as_ref()
-            .map_or(String::new(), |s| format!("\"{}\".", s));
+        let table_name = quote_qualified(drop.schema.as_deref(), &drop.name, DIALECT);
+        let if_exists = if drop.if_exists { "IF EXISTS " } else { "" };
+        let cascade = if drop.cascade { " CASCADE" } else { "" };
+
+        let sql = format!("DROP TABLE {}{}{}; ", if_exists, table_name, cascade);
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Table \"{}\" dropped successfully", drop.name),
+        })
+    }
+
+    fn generate_create_view(&self, view: &ViewDefinition) -> Result<DdlResult, DbError> {
+        let query = view_query(view)?;
+        if view.materialized && view.or_replace {
+            return Err(DbError::InvalidInput(
+                "PostgreSQL cannot replace a materialized view; drop it first".to_string(),
+            ));
+        }
+
+        let view_name = quote_qualified(view.schema.as_deref(), &view.name, DIALECT);
+        let or_replace = if view.or_replace { "OR REPLACE " } else { "" };
+        let kind = if view.materialized { "MATERIALIZED VIEW" } else { "VIEW" };
+        let columns = view.columns.as_ref().map_or(String::new(), |cols| {
+            format!(" ({})", quote_list(cols, DIALECT))
+        });
+
+        let sql = format!(
+            "CREATE {}{} {}{} AS\n{};",
+            or_replace, kind, view_name, columns, query
+        );
 
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("View \"{}\" created successfully", view.name),
+        })
+    }
+
+    fn generate_drop_view(&self, drop: &DropViewDefinition) -> Result<DdlResult, DbError> {
+        validate_identifier(&drop.name, "View name")?;
+        let view_name = quote_qualified(drop.schema.as_deref(), &drop.name, DIALECT);
+        let kind = if drop.materialized { "MATERIALIZED VIEW" } else { "VIEW" };
         let if_exists = if drop.if_exists { "IF EXISTS " } else { "" };
         let cascade = if drop.cascade { " CASCADE" } else { "" };
 
         let sql = format!(
-            "DROP TABLE {}{}\"{}\"{}; ",
-            if_exists, schema_prefix, drop.name, cascade
+            "DROP {} {}{}{};",
+            kind, if_exists, view_name, cascade
         );
 
         Ok(DdlResult {
             sql: vec![sql],
-            message: format!("Table \"{}\" dropped successfully", drop.name),
+            message: format!("View \"{}\" dropped successfully", drop.name),
+        })
+    }
+
+    fn generate_rename_table(
+        &self,
+        schema: Option<&str>,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<DdlResult, DbError> {
+        validate_identifier(new_name, "Table name")?;
+        let sql = format!(
+            "ALTER TABLE {} RENAME TO {};",
+            quote_qualified(schema, old_name, DIALECT),
+            quote_identifier(new_name, DIALECT)
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Table \"{}\" renamed to \"{}\"", old_name, new_name),
+        })
+    }
+
+    fn generate_table_comment(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        comment: Option<&str>,
+    ) -> Result<DdlResult, DbError> {
+        let sql = comment_sql(
+            &format!("TABLE {}", quote_qualified(schema, table, DIALECT)),
+            comment,
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Comment on table \"{}\" updated", table),
+        })
+    }
+
+    fn generate_column_comment(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        column: &ColumnInfo,
+        comment: Option<&str>,
+    ) -> Result<DdlResult, DbError> {
+        let sql = comment_sql(
+            &format!(
+                "COLUMN {}.{}",
+                quote_qualified(schema, table, DIALECT),
+                quote_identifier(&column.name, DIALECT)
+            ),
+            comment,
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Comment on column \"{}\" updated", column.name),
+        })
+    }
+
+    fn generate_create_index(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        index: &IndexDefinition,
+    ) -> Result<DdlResult, DbError> {
+        validate_index(index)?;
+
+        let unique = if index.unique { "UNIQUE " } else { "" };
+        let using = match index.index_type {
+            IndexType::BTree => "",
+            IndexType::Hash => " USING HASH",
+            IndexType::Gist => " USING GIST",
+            IndexType::Gin => " USING GIN",
+        };
+        let sql = format!(
+            "CREATE {}INDEX {} ON {}{} ({});",
+            unique,
+            quote_identifier(&index.name, DIALECT),
+            quote_qualified(schema, table, DIALECT),
+            using,
+            quote_list(&index.columns, DIALECT)
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Index \"{}\" created successfully", index.name),
         })
     }
 }
@@ -482,6 +605,42 @@ mod tests {
         assert!(result.sql[0].contains("ON DELETE CASCADE"));
     }
 
+    #[test]
+    fn test_create_table_with_comments() {
+        let generator = PostgresDdlGenerator;
+
+        let table = TableDefinition {
+            schema: Some("public".to_string()),
+            name: "users".to_string(),
+            columns: vec![ColumnDefinition {
+                name: "email".to_string(),
+                column_type: ColumnType::Text,
+                nullable: true,
+                default: None,
+                primary_key: false,
+                auto_increment: false,
+                comment: Some("User's login".to_string()),
+            }],
+            primary_key: None,
+            foreign_keys: vec![],
+            unique_constraints: vec![],
+            check_constraints: vec![],
+            comment: Some("Registered users".to_string()),
+            if_not_exists: false,
+        };
+
+        let result = generator.generate_create_table(&table).unwrap();
+        assert_eq!(result.sql.len(), 3);
+        assert_eq!(
+            result.sql[1],
+            "COMMENT ON TABLE \"public\".\"users\" IS 'Registered users';"
+        );
+        assert_eq!(
+            result.sql[2],
+            "COMMENT ON COLUMN \"public\".\"users\".\"email\" IS 'User''s login';"
+        );
+    }
+
     #[test]
     fn test_alter_table_add_column() {
         let generator = PostgresDdlGenerator;
@@ -524,4 +683,150 @@ mod tests {
         assert!(result.sql[0].contains("\"public\".\"users\""));
         assert!(result.sql[0].contains("CASCADE"));
     }
+
+
+    fn active_users_view() -> ViewDefinition {
+        ViewDefinition {
+            schema: Some("public".to_string()),
+            name: "active_users".to_string(),
+            columns: Some(vec!["id".to_string(), "email".to_string()]),
+            query: "SELECT id, email FROM users WHERE active;\n".to_string(),
+            materialized: false,
+            or_replace: true,
+        }
+    }
+
+    #[test]
+    fn test_create_view() {
+        let generator = PostgresDdlGenerator;
+
+        let result = generator.generate_create_view(&active_users_view()).unwrap();
+        assert_eq!(
+            result.sql,
+            vec![
+                "CREATE OR REPLACE VIEW \"public\".\"active_users\" (\"id\", \"email\") AS\n\
+                 SELECT id, email FROM users WHERE active;"
+                    .to_string()
+            ]
+        );
+
+        let materialized = ViewDefinition {
+            materialized: true,
+            or_replace: false,
+            columns: None,
+            ..active_users_view()
+        };
+        let result = generator.generate_create_view(&materialized).unwrap();
+        assert!(result.sql[0].starts_with("CREATE MATERIALIZED VIEW \"public\".\"active_users\" AS"));
+
+        // Materialized views can't be replaced in place
+        let replace_materialized = ViewDefinition {
+            materialized: true,
+            ..active_users_view()
+        };
+        assert!(generator.generate_create_view(&replace_materialized).is_err());
+    }
+
+    #[test]
+    fn test_create_view_validation() {
+        let generator = PostgresDdlGenerator;
+
+        let empty = ViewDefinition {
+            query: " ; ".to_string(),
+            ..active_users_view()
+        };
+        assert!(matches!(
+            generator.generate_create_view(&empty),
+            Err(DbError::InvalidInput(_))
+        ));
+
+        let bad_name = ViewDefinition {
+            name: "users\"; DROP TABLE users; --".to_string(),
+            ..active_users_view()
+        };
+        assert!(generator.generate_create_view(&bad_name).is_err());
+
+        let two_statements = ViewDefinition {
+            query: "SELECT 1; DELETE FROM users".to_string(),
+            ..active_users_view()
+        };
+        assert!(generator.generate_create_view(&two_statements).is_err());
+    }
+
+    #[test]
+    fn test_drop_view() {
+        let generator = PostgresDdlGenerator;
+
+        let drop = DropViewDefinition {
+            schema: Some("public".to_string()),
+            name: "active_users".to_string(),
+            materialized: true,
+            cascade: true,
+            if_exists: true,
+        };
+
+        let result = generator.generate_drop_view(&drop).unwrap();
+        assert_eq!(
+            result.sql[0],
+            "DROP MATERIALIZED VIEW IF EXISTS \"public\".\"active_users\" CASCADE;"
+        );
+    }
+
+    #[test]
+    fn test_rename_table() {
+        let generator = PostgresDdlGenerator;
+        let result = generator
+            .generate_rename_table(Some("sales"), "Orders", "orders_2024")
+            .unwrap();
+        assert_eq!(
+            result.sql[0],
+            "ALTER TABLE \"sales\".\"Orders\" RENAME TO \"orders_2024\";"
+        );
+    }
+
+    #[test]
+    fn test_table_and_column_comments() {
+        let generator = PostgresDdlGenerator;
+        let result = generator
+            .generate_table_comment(Some("sales"), "orders", Some("Line 1\nIt's"))
+            .unwrap();
+        assert_eq!(
+            result.sql[0],
+            "COMMENT ON TABLE \"sales\".\"orders\" IS E'Line 1\\nIt''s';"
+        );
+
+        let column = ColumnInfo::new("total".to_string(), "numeric".to_string(), false);
+        let result = generator
+            .generate_column_comment(Some("sales"), "orders", &column, None)
+            .unwrap();
+        assert_eq!(
+            result.sql[0],
+            "COMMENT ON COLUMN \"sales\".\"orders\".\"total\" IS NULL;"
+        );
+    }
+
+    #[test]
+    fn test_create_index() {
+        let generator = PostgresDdlGenerator;
+
+        let index = IndexDefinition {
+            name: "users_tags_idx".to_string(),
+            columns: vec!["tags".to_string()],
+            unique: false,
+            index_type: IndexType::Gin,
+        };
+        let result = generator
+            .generate_create_index(Some("public"), "users", &index)
+            .unwrap();
+        assert_eq!(
+            result.sql[0],
+            "CREATE INDEX \"users_tags_idx\" ON \"public\".\"users\" USING GIN (\"tags\");"
+        );
+
+        let no_columns = IndexDefinition {
+            columns: vec![],
+            ..index
+        };
+        assert!(generator.generate_create_index(None, "users", &no_columns).is_err());
+    }
 }
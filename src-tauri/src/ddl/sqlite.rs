@@ -6,15 +6,25 @@
 //! Note: SQLite has limited ALTER TABLE support. Many operations require
 //! creating a new table and copying data.
 
-use crate::ddl::DdlGenerator;
+use crate::ddl::{validate_identifier, validate_index, view_query, DdlGenerator};
 use crate::models::{
     ddl::{
         AlterColumnOperation, AlterTableDefinition, CheckConstraint, ColumnDefinition,
-        ColumnType, DdlResult, DropTableDefinition, ForeignKeyAction, ForeignKeyConstraint,
-        TableDefinition, UniqueConstraint,
+        ColumnType, DdlResult, DropTableDefinition, DropViewDefinition, ForeignKeyAction,
+        ForeignKeyConstraint, IndexDefinition, IndexType, TableDefinition, UniqueConstraint,
+        ViewDefinition,
     },
-    DbError,
+    ColumnInfo, DbError,
 };
+use crate::sql::quote::{quote_identifier, quote_list};
+use crate::sql::tokenizer::Quoting;
+
+const DIALECT: Quoting = Quoting::Standard;
+
+/// Error for comment statements, which SQLite has no storage for
+fn comments_unsupported() -> DbError {
+    DbError::InvalidInput("SQLite does not support table or column comments".to_string())
+}
 
 /// SQLite DDL generator
 pub struct SqliteDdlGenerator;
@@ -48,7 +58,7 @@ impl SqliteDdlGenerator {
         let mut parts = Vec::new();
 
         // Column name
-        parts.push(format!("\"{}\"", col.name));
+        parts.push(quote_identifier(&col.name, DIALECT));
 
         // Column type
         parts.push(self.column_type_to_sql(&col.column_type));
@@ -95,24 +105,14 @@ impl SqliteDdlGenerator {
 
     /// Generate foreign key constraint SQL
     fn generate_foreign_key_sql(&self, fk: &ForeignKeyConstraint) -> String {
-        let columns = fk
-            .columns
-            .iter()
-            .map(|c| format!("\"{}\"", c))
-            .collect::<Vec<_>>()
-            .join(", ");
-
-        let ref_columns = fk
-            .referenced_columns
-            .iter()
-            .map(|c| format!("\"{}\"", c))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let columns = quote_list(&fk.columns, DIALECT);
+
+        let ref_columns = quote_list(&fk.referenced_columns, DIALECT);
 
         format!(
-            "FOREIGN KEY ({}) REFERENCES \"{}\" ({}) ON DELETE {} ON UPDATE {}",
+            "FOREIGN KEY ({}) REFERENCES {} ({}) ON DELETE {} ON UPDATE {}",
             columns,
-            fk.referenced_table,
+            quote_identifier(&fk.referenced_table, DIALECT),
             ref_columns,
             self.foreign_key_action_to_sql(&fk.on_delete),
             self.foreign_key_action_to_sql(&fk.on_update)
@@ -121,12 +121,7 @@ impl SqliteDdlGenerator {
 
     /// Generate unique constraint SQL
     fn generate_unique_constraint_sql(&self, unique: &UniqueConstraint) -> String {
-        let columns = unique
-            .columns
-            .iter()
-            .map(|c| format!("\"{}\"", c))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let columns = quote_list(&unique.columns, DIALECT);
 
         format!("UNIQUE ({})", columns)
     }
@@ -138,16 +133,15 @@ impl SqliteDdlGenerator {
 
     /// Generate primary key constraint SQL (for composite keys)
     fn generate_primary_key_sql(&self, columns: &[String]) -> String {
-        let col_list = columns
-            .iter()
-            .map(|c| format!("\"{}\"", c))
-            .collect::<Vec<_>>()
-            .join(", ");
-        format!("PRIMARY KEY ({})", col_list)
+        format!("PRIMARY KEY ({})", quote_list(columns, DIALECT))
     }
 }
 
 impl DdlGenerator for SqliteDdlGenerator {
+    fn column_type_sql(&self, column_type: &ColumnType) -> String {
+        self.column_type_to_sql(column_type)
+    }
+
     fn generate_create_table(&self, table: &TableDefinition) -> Result<DdlResult, DbError> {
         if table.columns.is_empty() {
             return Err(DbError::InvalidInput(
@@ -164,7 +158,11 @@ impl DdlGenerator for SqliteDdlGenerator {
             ""
         };
 
-        sql_parts.push(format!("CREATE TABLE {}\"{}\" (", if_not_exists, table.name));
+        sql_parts.push(format!(
+            "CREATE TABLE {}{} (",
+            if_not_exists,
+            quote_identifier(&table.name, DIALECT)
+        ));
 
         // Column definitions
         let mut table_elements = Vec::new();
@@ -222,7 +220,7 @@ impl DdlGenerator for SqliteDdlGenerator {
             ));
         }
 
-        let table_name = format!("\"{}\"", alter.name);
+        let table_name = quote_identifier(&alter.name, DIALECT);
         let mut sql_statements = Vec::new();
 
         for op in &alter.operations {
@@ -238,13 +236,19 @@ impl DdlGenerator for SqliteDdlGenerator {
                 AlterColumnOperation::RenameColumn { old_name, new_name } => {
                     // SQLite 3.25.0+ supports RENAME COLUMN
                     format!(
-                        "ALTER TABLE {} RENAME COLUMN \"{}\" TO \"{}\";",
-                        table_name, old_name, new_name
+                        "ALTER TABLE {} RENAME COLUMN {} TO {};",
+                        table_name,
+                        quote_identifier(old_name, DIALECT),
+                        quote_identifier(new_name, DIALECT)
                     )
                 }
                 AlterColumnOperation::DropColumn { column_name, .. } => {
                     // SQLite 3.35.0+ supports DROP COLUMN
-                    format!("ALTER TABLE {} DROP COLUMN \"{}\";", table_name, column_name)
+                    format!(
+                        "ALTER TABLE {} DROP COLUMN {};",
+                        table_name,
+                        quote_identifier(column_name, DIALECT)
+                    )
                 }
                 AlterColumnOperation::AlterType { .. }
                 | AlterColumnOperation::SetNotNull { .. }
@@ -269,13 +273,134 @@ impl DdlGenerator for SqliteDdlGenerator {
     fn generate_drop_table(&self, drop: &DropTableDefinition) -> Result<DdlResult, DbError> {
         let if_exists = if drop.if_exists { "IF EXISTS " } else { "" };
         // SQLite doesn't support CASCADE
-        let sql = format!("DROP TABLE {}\"{}\"", if_exists, drop.name);
+        let sql = format!(
+            "DROP TABLE {}{}",
+            if_exists,
+            quote_identifier(&drop.name, DIALECT)
+        );
 
         Ok(DdlResult {
             sql: vec![sql],
             message: format!("Table \"{}\" dropped successfully", drop.name),
         })
     }
+
+    fn generate_create_view(&self, view: &ViewDefinition) -> Result<DdlResult, DbError> {
+        let query = view_query(view)?;
+        if view.materialized {
+            return Err(DbError::InvalidInput(
+                "SQLite does not support materialized views".to_string(),
+            ));
+        }
+
+        let columns = view.columns.as_ref().map_or(String::new(), |cols| {
+            format!(" ({})", quote_list(cols, DIALECT))
+        });
+
+        // SQLite has no CREATE OR REPLACE VIEW, so replacing means dropping first
+        let view_name = quote_identifier(&view.name, DIALECT);
+        let mut sql = Vec::new();
+        if view.or_replace {
+            sql.push(format!("DROP VIEW IF EXISTS {}", view_name));
+        }
+        sql.push(format!(
+            "CREATE VIEW {}{} AS\n{};",
+            view_name, columns, query
+        ));
+
+        Ok(DdlResult {
+            sql,
+            message: format!("View \"{}\" created successfully", view.name),
+        })
+    }
+
+    fn generate_drop_view(&self, drop: &DropViewDefinition) -> Result<DdlResult, DbError> {
+        validate_identifier(&drop.name, "View name")?;
+        if drop.materialized {
+            return Err(DbError::InvalidInput(
+                "SQLite does not support materialized views".to_string(),
+            ));
+        }
+
+        let if_exists = if drop.if_exists { "IF EXISTS " } else { "" };
+        // SQLite doesn't support CASCADE
+        let sql = format!(
+            "DROP VIEW {}{}",
+            if_exists,
+            quote_identifier(&drop.name, DIALECT)
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("View \"{}\" dropped successfully", drop.name),
+        })
+    }
+
+    fn generate_rename_table(
+        &self,
+        _schema: Option<&str>,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<DdlResult, DbError> {
+        validate_identifier(new_name, "Table name")?;
+        let sql = format!(
+            "ALTER TABLE {} RENAME TO {}",
+            quote_identifier(old_name, DIALECT),
+            quote_identifier(new_name, DIALECT)
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Table \"{}\" renamed to \"{}\"", old_name, new_name),
+        })
+    }
+
+    fn generate_table_comment(
+        &self,
+        _schema: Option<&str>,
+        _table: &str,
+        _comment: Option<&str>,
+    ) -> Result<DdlResult, DbError> {
+        Err(comments_unsupported())
+    }
+
+    fn generate_column_comment(
+        &self,
+        _schema: Option<&str>,
+        _table: &str,
+        _column: &ColumnInfo,
+        _comment: Option<&str>,
+    ) -> Result<DdlResult, DbError> {
+        Err(comments_unsupported())
+    }
+
+    fn generate_create_index(
+        &self,
+        _schema: Option<&str>,
+        table: &str,
+        index: &IndexDefinition,
+    ) -> Result<DdlResult, DbError> {
+        validate_index(index)?;
+        if index.index_type != IndexType::BTree {
+            return Err(DbError::InvalidInput(
+                "SQLite only supports B-tree indexes".to_string(),
+            ));
+        }
+
+        let unique = if index.unique { "UNIQUE " } else { "" };
+        let sql = format!(
+            "CREATE {}INDEX {} ON {} ({})",
+            unique,
+            quote_identifier(&index.name, DIALECT),
+            quote_identifier(table, DIALECT),
+            quote_list(&index.columns, DIALECT)
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Index \"{}\" created successfully", index.name),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -339,4 +464,38 @@ mod tests {
         assert!(result.sql[0].contains("DROP TABLE IF EXISTS"));
         assert!(result.sql[0].contains("\"users\""));
     }
+
+
+    fn active_users_view() -> ViewDefinition {
+        ViewDefinition {
+            schema: Some("main".to_string()),
+            name: "active_users".to_string(),
+            columns: Some(vec!["id".to_string(), "email".to_string()]),
+            query: "SELECT id, email FROM users WHERE active;\n".to_string(),
+            materialized: false,
+            or_replace: true,
+        }
+    }
+
+    #[test]
+    fn test_create_view_replaces_by_dropping() {
+        let generator = SqliteDdlGenerator;
+
+        let result = generator.generate_create_view(&active_users_view()).unwrap();
+        assert_eq!(
+            result.sql,
+            vec![
+                "DROP VIEW IF EXISTS \"active_users\"".to_string(),
+                "CREATE VIEW \"active_users\" (\"id\", \"email\") AS\n\
+                 SELECT id, email FROM users WHERE active;"
+                    .to_string(),
+            ]
+        );
+
+        let materialized = ViewDefinition {
+            materialized: true,
+            ..active_users_view()
+        };
+        assert!(generator.generate_create_view(&materialized).is_err());
+    }
 }
@@ -3,15 +3,75 @@
 //! Generates Microsoft SQL Server-specific DDL statements for table creation,
 //! alteration, and deletion.
 
-use crate::ddl::DdlGenerator;
+use crate::ddl::{validate_identifier, validate_index, view_query, DdlGenerator};
 use crate::models::{
     ddl::{
         AlterColumnOperation, AlterTableDefinition, ColumnDefinition, ColumnType, DdlResult,
-        DropTableDefinition, ForeignKeyAction, ForeignKeyConstraint, TableDefinition,
-        UniqueConstraint,
+        DropTableDefinition, DropViewDefinition, ForeignKeyAction, ForeignKeyConstraint,
+        IndexDefinition, IndexType, TableDefinition, UniqueConstraint, ViewDefinition,
     },
-    DbError,
+    ColumnInfo, DbError,
 };
+use crate::sql::quote::{quote_identifier, quote_list, quote_qualified};
+use crate::sql::tokenizer::Quoting;
+
+const DIALECT: Quoting = Quoting::SqlServer;
+
+/// `schema.name` quoted, in `dbo` if no schema is given
+fn qualified_name(schema: Option<&str>, name: &str) -> String {
+    quote_qualified(Some(schema.unwrap_or("dbo")), name, DIALECT)
+}
+
+/// `name` as a string literal, for procedures and functions that take an
+/// object name (`OBJECT_ID`, `sp_rename`)
+fn name_literal(name: &str) -> String {
+    format!("'{}'", name.replace('\'', "''"))
+}
+
+/// `value` as an `N'...'` string literal
+fn unicode_literal(value: &str) -> String {
+    format!("N'{}'", value.replace('\'', "''"))
+}
+
+/// Statement setting or removing the `MS_Description` extended property,
+/// which SQL Server tools show as the comment of a table or column
+///
+/// The property has to be added the first time and updated after that, so
+/// the statement checks which applies. A `None` comment drops it if set.
+fn description_sql(
+    schema: Option<&str>,
+    table: &str,
+    column: Option<&str>,
+    comment: Option<&str>,
+) -> String {
+    let levels = format!(
+        "N'SCHEMA', {}, N'TABLE', {}, {}",
+        unicode_literal(schema.unwrap_or("dbo")),
+        unicode_literal(table),
+        column.map_or_else(
+            || "NULL, NULL".to_string(),
+            |c| format!("N'COLUMN', {}", unicode_literal(c))
+        )
+    );
+    let exists = format!(
+        "IF EXISTS (SELECT 1 FROM sys.fn_listextendedproperty(N'MS_Description', {}))",
+        levels
+    );
+    match comment {
+        Some(comment) => {
+            let value = unicode_literal(comment);
+            format!(
+                "{} EXEC sp_updateextendedproperty N'MS_Description', {}, {} \
+                 ELSE EXEC sp_addextendedproperty N'MS_Description', {}, {};",
+                exists, value, levels, value, levels
+            )
+        }
+        None => format!(
+            "{} EXEC sp_dropextendedproperty N'MS_Description', {};",
+            exists, levels
+        ),
+    }
+}
 
 /// SQL Server DDL generator
 pub struct SqlServerDdlGenerator;
@@ -49,7 +109,7 @@ impl SqlServerDdlGenerator {
         let mut parts = Vec::new();
 
         // Column name
-        parts.push(format!("[{}]", col.name));
+        parts.push(quote_identifier(&col.name, DIALECT));
 
         // Column type
         parts.push(self.column_type_to_sql(&col.column_type));
@@ -100,25 +160,15 @@ impl SqlServerDdlGenerator {
             |name| name.clone(),
         );
 
-        let columns = fk
-            .columns
-            .iter()
-            .map(|c| format!("[{}]", c))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let columns = quote_list(&fk.columns, DIALECT);
 
-        let ref_columns = fk
-            .referenced_columns
-            .iter()
-            .map(|c| format!("[{}]", c))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let ref_columns = quote_list(&fk.referenced_columns, DIALECT);
 
         format!(
-            "CONSTRAINT [{}] FOREIGN KEY ({}) REFERENCES [{}] ({}) ON DELETE {} ON UPDATE {}",
-            constraint_name,
+            "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({}) ON DELETE {} ON UPDATE {}",
+            quote_identifier(&constraint_name, DIALECT),
             columns,
-            fk.referenced_table,
+            quote_identifier(&fk.referenced_table, DIALECT),
             ref_columns,
             self.foreign_key_action_to_sql(&fk.on_delete),
             self.foreign_key_action_to_sql(&fk.on_update)
@@ -132,31 +182,30 @@ impl SqlServerDdlGenerator {
             |name| name.clone(),
         );
 
-        let columns = unique
-            .columns
-            .iter()
-            .map(|c| format!("[{}]", c))
-            .collect::<Vec<_>>()
-            .join(", ");
+        let columns = quote_list(&unique.columns, DIALECT);
 
-        format!("CONSTRAINT [{}] UNIQUE ({})", constraint_name, columns)
+        format!(
+            "CONSTRAINT {} UNIQUE ({})",
+            quote_identifier(&constraint_name, DIALECT),
+            columns
+        )
     }
 
     /// Generate primary key constraint SQL
     fn generate_primary_key_sql(&self, columns: &[String], table_name: &str) -> String {
-        let col_list = columns
-            .iter()
-            .map(|c| format!("[{}]", c))
-            .collect::<Vec<_>>()
-            .join(", ");
         format!(
-            "CONSTRAINT [PK_{}] PRIMARY KEY CLUSTERED ({})",
-            table_name, col_list
+            "CONSTRAINT {} PRIMARY KEY CLUSTERED ({})",
+            quote_identifier(&format!("PK_{}", table_name), DIALECT),
+            quote_list(columns, DIALECT)
         )
     }
 }
 
 impl DdlGenerator for SqlServerDdlGenerator {
+    fn column_type_sql(&self, column_type: &ColumnType) -> String {
+        self.column_type_to_sql(column_type)
+    }
+
     fn generate_create_table(&self, table: &TableDefinition) -> Result<DdlResult, DbError> {
         if table.columns.is_empty() {
             return Err(DbError::InvalidInput(
@@ -167,12 +216,10 @@ impl DdlGenerator for SqlServerDdlGenerator {
         let mut sql_parts = Vec::new();
 
         // CREATE TABLE clause
-        let schema_prefix = table
-            .schema
-            .as_ref()
-            .map_or("dbo".to_string(), |s| s.clone());
-
-        sql_parts.push(format!("CREATE TABLE [{}].[{}] (", schema_prefix, table.name));
+        sql_parts.push(format!(
+            "CREATE TABLE {} (",
+            qualified_name(table.schema.as_deref(), &table.name)
+        ));
 
         // Column definitions
         let mut table_elements = Vec::new();
@@ -224,7 +271,23 @@ impl DdlGenerator for SqlServerDdlGenerator {
         sql_parts.push(table_elements.join(",\n"));
         sql_parts.push(");".to_string());
 
-        let full_sql = vec![sql_parts.join("\n")];
+        let mut full_sql = vec![sql_parts.join("\n")];
+
+        // Comments are extended properties, added once the table exists
+        let schema = table.schema.as_deref();
+        if let Some(comment) = &table.comment {
+            full_sql.push(description_sql(schema, &table.name, None, Some(comment)));
+        }
+        for col in &table.columns {
+            if let Some(comment) = &col.comment {
+                full_sql.push(description_sql(
+                    schema,
+                    &table.name,
+                    Some(&col.name),
+                    Some(comment),
+                ));
+            }
+        }
 
         Ok(DdlResult {
             sql: full_sql,
@@ -239,11 +302,7 @@ impl DdlGenerator for SqlServerDdlGenerator {
             ));
         }
 
-        let schema_prefix = alter
-            .schema
-            .as_ref()
-            .map_or("dbo".to_string(), |s| s.clone());
-        let table_name = format!("[{}].[{}]", schema_prefix, alter.name);
+        let table_name = qualified_name(alter.schema.as_deref(), &alter.name);
         let mut sql_statements = Vec::new();
 
         for op in &alter.operations {
@@ -256,25 +315,29 @@ impl DdlGenerator for SqlServerDdlGenerator {
                     )
                 }
                 AlterColumnOperation::DropColumn { column_name, .. } => {
-                    format!("ALTER TABLE {} DROP COLUMN [{}];", table_name, column_name)
+                    format!(
+                        "ALTER TABLE {} DROP COLUMN {};",
+                        table_name,
+                        quote_identifier(column_name, DIALECT)
+                    )
                 }
                 AlterColumnOperation::AlterType {
                     column_name,
                     new_type,
                 } => {
                     format!(
-                        "ALTER TABLE {} ALTER COLUMN [{}] {};",
+                        "ALTER TABLE {} ALTER COLUMN {} {};",
                         table_name,
-                        column_name,
+                        quote_identifier(column_name, DIALECT),
                         self.column_type_to_sql(new_type)
                     )
                 }
                 AlterColumnOperation::SetNotNull { column_name, not_null } => {
                     let null_clause = if *not_null { "NOT NULL" } else { "NULL" };
                     format!(
-                        "ALTER TABLE {} ALTER COLUMN [{}] {} {};",
+                        "ALTER TABLE {} ALTER COLUMN {} {} {};",
                         table_name,
-                        column_name,
+                        quote_identifier(column_name, DIALECT),
                         "/* type needed */",
                         null_clause
                     )
@@ -285,20 +348,29 @@ impl DdlGenerator for SqlServerDdlGenerator {
                 } => {
                     if let Some(default_value) = default {
                         format!(
-                            "ALTER TABLE {} ADD DEFAULT {} FOR [{}];",
-                            table_name, default_value, column_name
+                            "ALTER TABLE {} ADD DEFAULT {} FOR {};",
+                            table_name,
+                            default_value,
+                            quote_identifier(column_name, DIALECT)
                         )
                     } else {
                         format!(
-                            "ALTER TABLE {} DROP CONSTRAINT [DF_{}_{}];",
-                            table_name, alter.name, column_name
+                            "ALTER TABLE {} DROP CONSTRAINT {};",
+                            table_name,
+                            quote_identifier(
+                                &format!("DF_{}_{}", alter.name, column_name),
+                                DIALECT
+                            )
                         )
                     }
                 }
                 AlterColumnOperation::RenameColumn { old_name, new_name } => {
+                    // The column is named as schema.table.column
+                    let column = format!("{}.{}", table_name, quote_identifier(old_name, DIALECT));
                     format!(
-                        "EXEC sp_rename '[{}].[{}]', '{}', 'COLUMN';",
-                        schema_prefix, old_name, new_name
+                        "EXEC sp_rename {}, {}, 'COLUMN';",
+                        name_literal(&column),
+                        name_literal(new_name)
                     )
                 }
             };
@@ -312,22 +384,232 @@ impl DdlGenerator for SqlServerDdlGenerator {
     }
 
     fn generate_drop_table(&self, drop: &DropTableDefinition) -> Result<DdlResult, DbError> {
-        let schema_prefix = drop
-            .schema
-            .as_ref()
-            .map_or("dbo".to_string(), |s| s.clone());
+        let table_name = qualified_name(drop.schema.as_deref(), &drop.name);
 
         let if_exists = if drop.if_exists {
-            format!("IF OBJECT_ID('[{}].[{}]', 'U') IS NOT NULL ", schema_prefix, drop.name)
+            format!(
+                "IF OBJECT_ID({}, 'U') IS NOT NULL ",
+                name_literal(&table_name)
+            )
         } else {
             String::new()
         };
 
-        let sql = format!("{}DROP TABLE [{}].[{}];", if_exists, schema_prefix, drop.name);
+        let sql = format!("{}DROP TABLE {};", if_exists, table_name);
 
         Ok(DdlResult {
             sql: vec![sql],
             message: format!("Table [{}] dropped successfully", drop.name),
         })
     }
+
+    fn generate_create_view(&self, view: &ViewDefinition) -> Result<DdlResult, DbError> {
+        let query = view_query(view)?;
+        if view.materialized {
+            return Err(DbError::InvalidInput(
+                "SQL Server does not support materialized views; use an indexed view instead"
+                    .to_string(),
+            ));
+        }
+
+        let view_name = qualified_name(view.schema.as_deref(), &view.name);
+        let create = if view.or_replace { "CREATE OR ALTER" } else { "CREATE" };
+        let columns = view.columns.as_ref().map_or(String::new(), |cols| {
+            format!(" ({})", quote_list(cols, DIALECT))
+        });
+
+        let sql = format!(
+            "{} VIEW {}{} AS\n{};",
+            create, view_name, columns, query
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("View [{}] created successfully", view.name),
+        })
+    }
+
+    fn generate_drop_view(&self, drop: &DropViewDefinition) -> Result<DdlResult, DbError> {
+        validate_identifier(&drop.name, "View name")?;
+        if drop.materialized {
+            return Err(DbError::InvalidInput(
+                "SQL Server does not support materialized views".to_string(),
+            ));
+        }
+
+        let view_name = qualified_name(drop.schema.as_deref(), &drop.name);
+
+        let if_exists = if drop.if_exists {
+            format!(
+                "IF OBJECT_ID({}, 'V') IS NOT NULL ",
+                name_literal(&view_name)
+            )
+        } else {
+            String::new()
+        };
+
+        let sql = format!("{}DROP VIEW {};", if_exists, view_name);
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("View [{}] dropped successfully", drop.name),
+        })
+    }
+
+    fn generate_rename_table(
+        &self,
+        schema: Option<&str>,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<DdlResult, DbError> {
+        validate_identifier(new_name, "Table name")?;
+        // sp_rename takes the new name unqualified; the table keeps its schema
+        let sql = format!(
+            "EXEC sp_rename {}, {};",
+            name_literal(&qualified_name(schema, old_name)),
+            name_literal(new_name)
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Table [{}] renamed to [{}]", old_name, new_name),
+        })
+    }
+
+    fn generate_table_comment(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        comment: Option<&str>,
+    ) -> Result<DdlResult, DbError> {
+        Ok(DdlResult {
+            sql: vec![description_sql(schema, table, None, comment)],
+            message: format!("Comment on table [{}] updated", table),
+        })
+    }
+
+    fn generate_column_comment(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        column: &ColumnInfo,
+        comment: Option<&str>,
+    ) -> Result<DdlResult, DbError> {
+        Ok(DdlResult {
+            sql: vec![description_sql(schema, table, Some(&column.name), comment)],
+            message: format!("Comment on column [{}] updated", column.name),
+        })
+    }
+
+    fn generate_create_index(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        index: &IndexDefinition,
+    ) -> Result<DdlResult, DbError> {
+        validate_index(index)?;
+        if index.index_type != IndexType::BTree {
+            return Err(DbError::InvalidInput(
+                "Only B-tree (rowstore) indexes are supported for SQL Server".to_string(),
+            ));
+        }
+
+        let unique = if index.unique { "UNIQUE " } else { "" };
+
+        let sql = format!(
+            "CREATE {}INDEX {} ON {} ({});",
+            unique,
+            quote_identifier(&index.name, DIALECT),
+            qualified_name(schema, table),
+            quote_list(&index.columns, DIALECT)
+        );
+
+        Ok(DdlResult {
+            sql: vec![sql],
+            message: format!("Index [{}] created successfully", index.name),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_and_drop_view() {
+        let generator = SqlServerDdlGenerator;
+
+        let view = ViewDefinition {
+            schema: None,
+            name: "active_users".to_string(),
+            columns: Some(vec!["id".to_string(), "email".to_string()]),
+            query: "SELECT id, email FROM users WHERE active = 1".to_string(),
+            materialized: false,
+            or_replace: true,
+        };
+        let result = generator.generate_create_view(&view).unwrap();
+        assert_eq!(
+            result.sql[0],
+            "CREATE OR ALTER VIEW [dbo].[active_users] ([id], [email]) AS\n\
+             SELECT id, email FROM users WHERE active = 1;"
+        );
+
+        let drop = DropViewDefinition {
+            schema: None,
+            name: "active_users".to_string(),
+            materialized: false,
+            cascade: false,
+            if_exists: true,
+        };
+        let result = generator.generate_drop_view(&drop).unwrap();
+        assert_eq!(
+            result.sql[0],
+            "IF OBJECT_ID('[dbo].[active_users]', 'V') IS NOT NULL DROP VIEW [dbo].[active_users];"
+        );
+    }
+
+    #[test]
+    fn test_rename_table() {
+        let generator = SqlServerDdlGenerator;
+        let result = generator
+            .generate_rename_table(Some("sales"), "o'rders", "orders")
+            .unwrap();
+        assert_eq!(
+            result.sql[0],
+            "EXEC sp_rename '[sales].[o''rders]', 'orders';"
+        );
+
+        assert!(generator
+            .generate_rename_table(None, "orders", "bad name")
+            .is_err());
+    }
+
+    #[test]
+    fn test_table_and_column_comments() {
+        let generator = SqlServerDdlGenerator;
+        let result = generator
+            .generate_table_comment(Some("sales"), "orders", Some("Customer's orders"))
+            .unwrap();
+        assert_eq!(
+            result.sql[0],
+            "IF EXISTS (SELECT 1 FROM sys.fn_listextendedproperty(N'MS_Description', \
+             N'SCHEMA', N'sales', N'TABLE', N'orders', NULL, NULL)) \
+             EXEC sp_updateextendedproperty N'MS_Description', N'Customer''s orders', \
+             N'SCHEMA', N'sales', N'TABLE', N'orders', NULL, NULL \
+             ELSE EXEC sp_addextendedproperty N'MS_Description', N'Customer''s orders', \
+             N'SCHEMA', N'sales', N'TABLE', N'orders', NULL, NULL;"
+        );
+
+        let column = ColumnInfo::new("total".to_string(), "money".to_string(), false);
+        let result = generator
+            .generate_column_comment(None, "orders", &column, None)
+            .unwrap();
+        assert_eq!(
+            result.sql[0],
+            "IF EXISTS (SELECT 1 FROM sys.fn_listextendedproperty(N'MS_Description', \
+             N'SCHEMA', N'dbo', N'TABLE', N'orders', N'COLUMN', N'total')) \
+             EXEC sp_dropextendedproperty N'MS_Description', \
+             N'SCHEMA', N'dbo', N'TABLE', N'orders', N'COLUMN', N'total';"
+        );
+    }
 }
@@ -0,0 +1,98 @@
+//! Binary cell values
+//!
+//! Drivers return binary columns (`bytea`, `BLOB`, `varbinary`, ...) as a
+//! tagged object rather than a string, so they can't be mistaken for text
+//! and the UI can offer a download or an image/text preview:
+//!
+//! ```json
+//! { "__blob__": "<base64>", "size": 1048576 }
+//! ```
+//!
+//! Query results keep the whole value (exports need it). The data grid gets
+//! a preview instead: `preview_blob` cuts the payload to
+//! `BLOB_PREVIEW_BYTES` and adds `"truncated": true`, and the full value is
+//! fetched on demand with the `fetch_cell_blob` command.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{Map, Value};
+
+/// Key that marks an object as a binary value; holds the base64 payload
+pub const BLOB_TAG: &str = "__blob__";
+
+/// Bytes of a binary value sent to the data grid
+pub const BLOB_PREVIEW_BYTES: usize = 64 * 1024;
+
+/// Tagged JSON value for binary data
+pub fn blob_value(bytes: &[u8]) -> Value {
+    let mut object = Map::new();
+    object.insert(BLOB_TAG.to_string(), Value::String(BASE64.encode(bytes)));
+    object.insert("size".to_string(), Value::from(bytes.len() as u64));
+    Value::Object(object)
+}
+
+/// Whether `value` is a tagged binary value
+pub fn is_blob(value: &Value) -> bool {
+    value
+        .as_object()
+        .is_some_and(|o| o.get(BLOB_TAG).is_some_and(Value::is_string))
+}
+
+/// Bytes of a tagged binary value
+///
+/// Returns `None` for other values and for previews, whose bytes are only
+/// the start of the value.
+pub fn blob_bytes(value: &Value) -> Option<Vec<u8>> {
+    let object = value.as_object()?;
+    if object.get("truncated").and_then(Value::as_bool) == Some(true) {
+        return None;
+    }
+    BASE64.decode(object.get(BLOB_TAG)?.as_str()?).ok()
+}
+
+/// Cut a tagged binary value down to its first `max_bytes` bytes
+///
+/// `size` keeps the full length and `"truncated": true` is added. Other
+/// values, and binary values that already fit, are left unchanged.
+pub fn preview_blob(value: &mut Value, max_bytes: usize) {
+    let fits = value
+        .get("size")
+        .and_then(Value::as_u64)
+        .is_some_and(|size| size <= max_bytes as u64);
+    if fits {
+        return;
+    }
+    let Some(bytes) = blob_bytes(value) else {
+        return;
+    };
+    if let Some(object) = value.as_object_mut().filter(|_| bytes.len() > max_bytes) {
+        object.insert(
+            BLOB_TAG.to_string(),
+            Value::String(BASE64.encode(&bytes[..max_bytes])),
+        );
+        object.insert("truncated".to_string(), Value::Bool(true));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_blob_round_trip_and_preview() {
+        let mut value = blob_value(b"Hello, world");
+        assert_eq!(value, json!({ "__blob__": "SGVsbG8sIHdvcmxk", "size": 12 }));
+        assert!(is_blob(&value));
+        assert_eq!(blob_bytes(&value).as_deref(), Some(&b"Hello, world"[..]));
+        assert!(!is_blob(&json!("0x48656c6c6f")));
+
+        preview_blob(&mut value, 5);
+        assert_eq!(
+            value,
+            json!({ "__blob__": "SGVsbG8=", "size": 12, "truncated": true })
+        );
+        // A preview is not the value
+        assert_eq!(blob_bytes(&value), None);
+    }
+}
@@ -4,15 +4,25 @@
 //! for various database systems. Each driver handles connection management,
 //! query execution, and metadata retrieval specific to its database type.
 
+use std::collections::HashSet;
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 use crate::models::{
-    DatabaseInfo, DbError, ForeignKeyInfo, SchemaInfo, TableInfo, TableSchema,
+    DatabaseInfo, DbError, ForeignKeyInfo, SchemaInfo, SqliteOptions, TableInfo, TableSchema,
 };
+use crate::sql::params::PlaceholderStyle;
+use crate::sql::quote;
+use crate::sql::tokenizer::Quoting;
 
+pub mod blob;
 pub mod mongodb;
 pub mod mysql;
+pub mod pg_notify;
+pub mod pg_values;
+pub mod postgis;
 pub mod postgres;
 pub mod redis;
 pub mod sqlite;
@@ -58,6 +68,19 @@ pub struct ConnectionOptions {
 
     /// Whether to require TLS/SSL
     pub require_tls: bool,
+
+    /// Client name reported to the server, where the protocol has one
+    /// (Postgres, MySQL, SQL Server)
+    pub application_name: Option<String>,
+
+    /// Schema unqualified names resolve to. Postgres sets `search_path` to
+    /// it followed by `public`; MySQL uses it as the database instead of
+    /// `database`. SQL Server has no per-session default schema (it belongs
+    /// to the login's user), and the other drivers ignore it.
+    pub default_schema: Option<String>,
+
+    /// Pragmas to set on connect; ignored by drivers other than SQLite
+    pub sqlite: SqliteOptions,
 }
 
 /// Result of a query execution
@@ -107,6 +130,85 @@ impl QueryResult {
     }
 }
 
+/// Make result column names unique, for consumers keyed by name
+///
+/// A repeated name gets a `_2`, `_3`, ... suffix (skipping names the result
+/// already has), so `SELECT a.id, b.id FROM a JOIN b` yields `id` and
+/// `id_2`. The first occurrence keeps its name.
+pub fn unique_column_names(columns: &[String]) -> Vec<String> {
+    let mut taken: HashSet<String> = columns.iter().cloned().collect();
+    let mut seen: HashSet<&str> = HashSet::new();
+
+    columns
+        .iter()
+        .map(|name| {
+            if seen.insert(name) {
+                return name.clone();
+            }
+            let unique = (2..)
+                .map(|n| format!("{}_{}", name, n))
+                .find(|candidate| !taken.contains(candidate))
+                .expect("an unused suffix exists");
+            taken.insert(unique.clone());
+            unique
+        })
+        .collect()
+}
+
+/// Options for [`DatabaseDriver::aggregate`]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AggregateOptions {
+    /// Expand nested documents into `parent.child` columns instead of
+    /// returning them as JSON strings
+    pub expand_nested: bool,
+    /// Most documents to return, at most `MAX_RESULT_ROWS`
+    pub limit: Option<usize>,
+}
+
+/// Receives the rows of a [`DatabaseDriver::stream_query`] call batch by batch
+///
+/// Lets callers (e.g. file export) process arbitrarily large result sets
+/// without materializing them or going through the `MAX_RESULT_ROWS` cap.
+pub trait RowSink: Send {
+    /// Called once with the result column names, before any rows
+    fn columns(&mut self, columns: &[String]) -> Result<(), DbError>;
+
+    /// Called with each fetched batch. Return `Ok(false)` to stop streaming
+    /// early (e.g. on cancellation); an error aborts the query.
+    fn rows(&mut self, rows: Vec<Vec<serde_json::Value>>) -> Result<bool, DbError>;
+}
+
+/// Called by [`DatabaseDriver::execute_in_transaction_with`] after each
+/// statement with the number run so far. Return `false` to stop and roll the
+/// transaction back (e.g. on cancellation).
+pub type StatementCallback = Box<dyn FnMut(usize) -> bool + Send>;
+
+/// A statement and the values bound to its placeholders, for
+/// [`DatabaseDriver::execute_in_transaction_with_params`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundStatement {
+    /// SQL using the driver's `placeholder_style`
+    pub sql: String,
+    /// One value per placeholder, in order
+    pub params: Vec<serde_json::Value>,
+}
+
+impl BoundStatement {
+    pub fn new(sql: String, params: Vec<serde_json::Value>) -> Self {
+        Self { sql, params }
+    }
+
+    /// `statements` with no bound values, so drivers can run plain and
+    /// parameterized batches through the same transaction loop
+    pub fn unbound(statements: &[String]) -> Vec<Self> {
+        statements
+            .iter()
+            .map(|sql| Self::new(sql.clone(), Vec::new()))
+            .collect()
+    }
+}
+
 /// Database driver trait
 ///
 /// All database drivers must implement this trait to provide a unified
@@ -227,6 +329,240 @@ pub trait DatabaseDriver: Send + Sync {
     /// This is used for ER diagram generation and understanding table relationships.
     async fn get_foreign_keys(&self, schema: &str) -> Result<Vec<ForeignKeyInfo>, DbError>;
 
+    /// Execute a single row-returning query and stream its rows to `sink`
+    ///
+    /// # Arguments
+    ///
+    /// * `sql` - A single SELECT-like statement
+    /// * `batch_size` - Maximum rows per `RowSink::rows` call
+    /// * `sink` - Receives the columns, then the rows in batches
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of rows handed to the sink.
+    ///
+    /// # Notes
+    ///
+    /// Unlike `execute_query`, the result is not capped at `MAX_RESULT_ROWS`.
+    /// Drivers with a native row stream override this. The default pages
+    /// through the result with `LIMIT`/`OFFSET` on a derived table, so page
+    /// boundaries are only stable if the query has an `ORDER BY`.
+    async fn stream_query(
+        &self,
+        sql: &str,
+        batch_size: usize,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64, DbError> {
+        let base = sql.trim().trim_end_matches(';');
+        let batch_size = batch_size.clamp(1, MAX_RESULT_ROWS);
+
+        let mut offset: u64 = 0;
+        let mut columns_sent = false;
+        loop {
+            let page = self
+                .execute_query(&format!(
+                    "SELECT * FROM ({}) AS stream_page LIMIT {} OFFSET {}",
+                    base, batch_size, offset
+                ))
+                .await?;
+
+            if !columns_sent {
+                sink.columns(&page.columns)?;
+                columns_sent = true;
+            }
+
+            let fetched = page.rows.len();
+            if fetched == 0 {
+                break;
+            }
+            offset += fetched as u64;
+
+            if !sink.rows(page.rows)? || fetched < batch_size {
+                break;
+            }
+        }
+
+        Ok(offset)
+    }
+
+    /// Execute a single statement with bound positional parameters
+    ///
+    /// # Arguments
+    ///
+    /// * `sql` - A statement using this driver's `placeholder_style`
+    /// * `params` - One value per placeholder, in order
+    ///
+    /// # Notes
+    ///
+    /// Values are sent as real bound parameters, never interpolated into the
+    /// SQL text. Results are capped at `MAX_RESULT_ROWS` like `execute_query`.
+    /// The default reports the operation as unsupported (document and
+    /// key-value stores).
+    async fn execute_query_with_params(
+        &self,
+        _sql: &str,
+        _params: &[serde_json::Value],
+    ) -> Result<QueryResult, DbError> {
+        Err(DbError::InvalidInput(
+            "Parameterized queries are not supported by this driver".to_string(),
+        ))
+    }
+
+    /// Positional placeholder syntax `execute_query_with_params` expects
+    fn placeholder_style(&self) -> PlaceholderStyle {
+        PlaceholderStyle::Question
+    }
+
+    /// Execute several statements atomically in a single transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `statements` - SQL statements to run, in order
+    ///
+    /// # Returns
+    ///
+    /// Returns the affected row count of each statement, in order.
+    ///
+    /// # Notes
+    ///
+    /// If any statement fails the transaction is rolled back and the error is
+    /// returned, so either every statement takes effect or none does. Pooled
+    /// drivers must run the whole batch on a single checked-out connection —
+    /// issuing `BEGIN`/`COMMIT` through `execute_query` would not work, as each
+    /// call may land on a different connection. The default reports the
+    /// operation as unsupported (document and key-value stores).
+    async fn execute_in_transaction(&self, _statements: &[String]) -> Result<Vec<u64>, DbError> {
+        Err(DbError::InvalidInput(
+            "Transactions are not supported by this driver".to_string(),
+        ))
+    }
+
+    /// Execute statements in a single transaction, reporting each one
+    ///
+    /// # Returns
+    ///
+    /// The affected row count of each statement, or `None` if
+    /// `after_statement` returned `false` and the transaction was rolled
+    /// back.
+    ///
+    /// # Notes
+    ///
+    /// Lets long batches (file imports) show progress and be cancelled
+    /// without committing part of the batch. The default runs
+    /// `execute_in_transaction` without calling `after_statement`.
+    async fn execute_in_transaction_with(
+        &self,
+        statements: &[String],
+        _after_statement: StatementCallback,
+    ) -> Result<Option<Vec<u64>>, DbError> {
+        self.execute_in_transaction(statements).await.map(Some)
+    }
+
+    /// Execute parameterized statements atomically in a single transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `statements` - Statements to run, in order, each with its values
+    ///
+    /// # Returns
+    ///
+    /// Returns the affected row count of each statement, in order.
+    ///
+    /// # Notes
+    ///
+    /// Like `execute_in_transaction`, but values are bound as in
+    /// `execute_query_with_params` instead of being written into the SQL.
+    /// The default reports the operation as unsupported.
+    async fn execute_in_transaction_with_params(
+        &self,
+        _statements: &[BoundStatement],
+    ) -> Result<Vec<u64>, DbError> {
+        Err(DbError::InvalidInput(
+            "Transactions are not supported by this driver".to_string(),
+        ))
+    }
+
+    /// Execute statements in a transaction that is always rolled back
+    ///
+    /// # Returns
+    ///
+    /// Returns the affected row count of each statement, in order.
+    ///
+    /// # Notes
+    ///
+    /// Used to measure a statement without keeping its effects. Rolling back
+    /// does not undo everything: sequence increments, changes to
+    /// non-transactional tables (MySQL MyISAM) and side effects of triggers
+    /// outside the database remain. The default reports the operation as
+    /// unsupported.
+    async fn execute_and_rollback(&self, _statements: &[String]) -> Result<Vec<u64>, DbError> {
+        Err(DbError::InvalidInput(
+            "Transactions are not supported by this driver".to_string(),
+        ))
+    }
+
+    /// Run an aggregation pipeline and return its output as a table
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - Collection the pipeline starts from
+    /// * `pipeline` - Pipeline stages, in order
+    /// * `options` - Row cap and how nested documents become columns
+    ///
+    /// # Notes
+    ///
+    /// A `$limit` stage is appended to cap the result, so `truncated` is set
+    /// as for `execute_query` when the `MAX_RESULT_ROWS` cap is hit. Columns
+    /// are the union of the fields of all returned documents. The default
+    /// reports the operation as unsupported (everything but MongoDB).
+    async fn aggregate(
+        &self,
+        _collection: &str,
+        _pipeline: Vec<::mongodb::bson::Document>,
+        _options: AggregateOptions,
+    ) -> Result<QueryResult, DbError> {
+        Err(DbError::InvalidInput(
+            "Aggregation pipelines are only supported on MongoDB connections".to_string(),
+        ))
+    }
+
+    /// Open a dedicated session for `LISTEN`/`NOTIFY`
+    ///
+    /// # Arguments
+    ///
+    /// * `on_notification` - Called with every notification the session receives
+    ///
+    /// # Notes
+    ///
+    /// The session is a connection of its own, outside any pool, so it keeps
+    /// receiving notifications between queries. The default reports the
+    /// operation as unsupported (everything but PostgreSQL).
+    async fn open_listener(
+        &self,
+        _on_notification: pg_notify::NotificationCallback,
+    ) -> Result<pg_notify::PgListener, DbError> {
+        Err(DbError::InvalidInput(
+            "LISTEN/NOTIFY is only supported on PostgreSQL connections".to_string(),
+        ))
+    }
+
+    /// Open a session pinned to one connection, for an explicit transaction
+    ///
+    /// # Returns
+    ///
+    /// A driver whose calls all run on the same new connection, or `None`
+    /// when this driver already runs everything on a single connection.
+    ///
+    /// # Notes
+    ///
+    /// Pooled drivers hand each call any idle connection, so a `BEGIN` and
+    /// the statements after it could land on different sessions. Statements
+    /// between `BEGIN` and `COMMIT`/`ROLLBACK` go through the session
+    /// instead; it's closed when dropped.
+    async fn open_session(&self) -> Result<Option<Arc<dyn DatabaseDriver>>, DbError> {
+        Ok(None)
+    }
+
     /// Close the database connection
     ///
     /// # Returns
@@ -246,18 +582,16 @@ pub trait DatabaseDriver: Send + Sync {
     /// so the result is always a single safe identifier token. Used when the
     /// app interpolates identifiers into generated SQL (e.g. keyset pagination).
     fn quote_identifier(&self, ident: &str) -> String {
-        format!("\"{}\"", ident.replace('"', "\"\""))
+        quote::quote_identifier(ident, Quoting::Standard)
     }
 
-    /// Escape a string value for safe inclusion inside a single-quoted SQL
-    /// string literal in this dialect.
-    ///
-    /// The default doubles single quotes (standard SQL, used by Postgres,
-    /// SQLite and SQL Server). MySQL additionally treats backslash as an
-    /// escape character inside string literals, so its driver overrides
-    /// this to escape backslashes as well. Used when the app interpolates
-    /// values into generated SQL (e.g. data import).
-    fn escape_string_literal(&self, value: &str) -> String {
-        value.replace('\'', "''")
+    /// SQL literal for binary data in this dialect.
+    ///
+    /// The default is the `X'...'` hex literal understood by MySQL and
+    /// SQLite. PostgreSQL overrides it with `decode(..., 'hex')` and SQL
+    /// Server with a bare `0x...` literal. Used when a binary value from a
+    /// result (see `drivers::blob`) is written back, e.g. as a row key.
+    fn binary_literal(&self, bytes: &[u8]) -> String {
+        format!("X'{}'", hex::encode(bytes))
     }
 }
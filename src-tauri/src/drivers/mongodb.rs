@@ -4,15 +4,17 @@
 //! MongoDB Rust driver. Note that MongoDB is a NoSQL database, so some SQL-specific
 //! concepts like schemas don't apply directly.
 
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use futures_util::StreamExt;
 use mongodb::{
-    bson::{doc, Document},
+    bson::{doc, Bson, Document},
     Client, Database,
 };
 use serde_json::Value as JsonValue;
 
-use super::{ConnectionOptions, DatabaseDriver, QueryResult, MAX_RESULT_ROWS};
+use super::{AggregateOptions, ConnectionOptions, DatabaseDriver, QueryResult, MAX_RESULT_ROWS};
 use crate::models::{ColumnInfo, DatabaseInfo, DbError, ForeignKeyInfo, IndexInfo, SchemaInfo, TableInfo, TableSchema};
 
 /// MongoDB database driver
@@ -105,6 +107,145 @@ impl MongoDbDriver {
     }
 }
 
+/// Operator of a pipeline stage, e.g. `$match`
+fn stage_operator(stage: &Document) -> Option<&str> {
+    match stage.keys().collect::<Vec<_>>().as_slice() {
+        [key] if key.starts_with('$') => Some(key.as_str()),
+        _ => None,
+    }
+}
+
+/// Check that every stage is a single `{ $operator: ... }` document
+fn validate_pipeline(pipeline: &[Document]) -> Result<(), DbError> {
+    for (i, stage) in pipeline.iter().enumerate() {
+        if stage_operator(stage).is_none() {
+            return Err(DbError::QueryError(format!(
+                "Pipeline stage {} is invalid: a stage must have exactly one field, the stage operator (e.g. $match)",
+                i
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Append a `$limit` so at most `cap` documents come back
+///
+/// Pipelines ending in `$out` or `$merge` return no documents and cannot
+/// have a stage after them, so they are left alone.
+fn inject_limit(pipeline: &mut Vec<Document>, cap: usize) {
+    let writes = pipeline
+        .last()
+        .and_then(stage_operator)
+        .is_some_and(|op| op == "$out" || op == "$merge");
+    if !writes {
+        pipeline.push(doc! { "$limit": cap as i64 });
+    }
+}
+
+/// Turn a server error into a `QueryError` naming the failing stage
+///
+/// The server rarely says which stage failed; the first stage whose operator
+/// appears in the message is taken to be the culprit.
+fn pipeline_error(pipeline: &[Document], message: &str) -> DbError {
+    let failed = pipeline.iter().enumerate().find_map(|(i, stage)| {
+        let op = stage_operator(stage)?;
+        let mentioned = message.match_indices(op).any(|(pos, _)| {
+            !message[pos + op.len()..].starts_with(|c: char| c.is_ascii_alphanumeric())
+        });
+        mentioned.then_some((i, op))
+    });
+    match failed {
+        Some((i, op)) => DbError::QueryError(format!(
+            "Aggregation failed at stage {} ({}): {}",
+            i, op, message
+        )),
+        None => DbError::QueryError(format!("Aggregation failed: {}", message)),
+    }
+}
+
+/// Whether `map` is an extended JSON wrapper such as `{"$oid": "..."}`
+fn is_extended_json(map: &serde_json::Map<String, JsonValue>) -> bool {
+    !map.is_empty() && map.keys().all(|k| k.starts_with('$'))
+}
+
+/// Cell value of a field that is not expanded further
+///
+/// Wrapped scalars (`$oid`, `$date`, `$numberDecimal`, ...) become their
+/// string; other documents and arrays become JSON text.
+fn leaf_value(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) if map.len() == 1 && is_extended_json(map) => {
+            match map.values().next() {
+                Some(JsonValue::String(s)) => JsonValue::String(s.clone()),
+                _ => JsonValue::String(value.to_string()),
+            }
+        }
+        JsonValue::Object(_) | JsonValue::Array(_) => JsonValue::String(value.to_string()),
+        other => other.clone(),
+    }
+}
+
+fn flatten_fields(
+    map: &serde_json::Map<String, JsonValue>,
+    prefix: &str,
+    expand_nested: bool,
+    out: &mut Vec<(String, JsonValue)>,
+) {
+    for (key, value) in map {
+        let name = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            JsonValue::Object(inner)
+                if expand_nested && !inner.is_empty() && !is_extended_json(inner) =>
+            {
+                flatten_fields(inner, &name, expand_nested, out)
+            }
+            _ => out.push((name, leaf_value(value))),
+        }
+    }
+}
+
+/// Lay out documents (as relaxed extended JSON) as rows
+///
+/// Columns are the fields of all documents in order of first appearance;
+/// a document without a field gets `null` there. With `expand_nested`,
+/// nested documents become `parent.child` columns.
+fn flatten_documents(docs: &[JsonValue], expand_nested: bool) -> QueryResult {
+    let mut columns: Vec<String> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut flattened = Vec::with_capacity(docs.len());
+
+    for doc in docs {
+        let mut fields = Vec::new();
+        match doc {
+            JsonValue::Object(map) => flatten_fields(map, "", expand_nested, &mut fields),
+            other => fields.push(("value".to_string(), leaf_value(other))),
+        }
+        for (name, _) in &fields {
+            if !positions.contains_key(name) {
+                positions.insert(name.clone(), columns.len());
+                columns.push(name.clone());
+            }
+        }
+        flattened.push(fields);
+    }
+
+    let rows = flattened
+        .into_iter()
+        .map(|fields| {
+            let mut row = vec![JsonValue::Null; columns.len()];
+            for (name, value) in fields {
+                row[positions[&name]] = value;
+            }
+            row
+        })
+        .collect();
+    QueryResult::with_data(columns, rows)
+}
+
 #[async_trait]
 impl DatabaseDriver for MongoDbDriver {
     async fn connect(opts: ConnectionOptions) -> Result<Self, DbError>
@@ -355,6 +496,41 @@ impl DatabaseDriver for MongoDbDriver {
         }
     }
 
+    async fn aggregate(
+        &self,
+        collection: &str,
+        mut pipeline: Vec<Document>,
+        options: AggregateOptions,
+    ) -> Result<QueryResult, DbError> {
+        validate_pipeline(&pipeline)?;
+
+        // One document past MAX_RESULT_ROWS marks the result as truncated;
+        // a smaller user limit is simply the size asked for
+        let cap = match options.limit {
+            Some(limit) if limit < MAX_RESULT_ROWS => limit.max(1),
+            _ => MAX_RESULT_ROWS + 1,
+        };
+        inject_limit(&mut pipeline, cap);
+
+        let mut cursor = self
+            .database
+            .collection::<Document>(collection)
+            .aggregate(pipeline.clone())
+            .await
+            .map_err(|e| pipeline_error(&pipeline, &e.to_string()))?;
+
+        let mut docs = Vec::new();
+        while let Some(result) = cursor.next().await {
+            let doc = result.map_err(|e| pipeline_error(&pipeline, &e.to_string()))?;
+            docs.push(Bson::Document(doc).into_relaxed_extjson());
+            if docs.len() >= cap {
+                break;
+            }
+        }
+
+        Ok(flatten_documents(&docs, options.expand_nested))
+    }
+
     async fn get_databases(&self) -> Result<Vec<DatabaseInfo>, DbError> {
         // List all databases
         let db_names = self
@@ -430,6 +606,9 @@ impl DatabaseDriver for MongoDbDriver {
                     default_value: None,
                     is_primary_key: key == "_id",
                     is_auto_increment: key == "_id", // MongoDB _id is auto-generated
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 })
                 .collect()
         } else {
@@ -441,6 +620,9 @@ impl DatabaseDriver for MongoDbDriver {
                 default_value: None,
                 is_primary_key: true,
                 is_auto_increment: true, // MongoDB _id is auto-generated
+                comment: None,
+                is_generated: false,
+                generation_expression: None,
             }]
         };
 
@@ -461,6 +643,8 @@ impl DatabaseDriver for MongoDbDriver {
             },
             columns,
             indexes,
+            constraints: Vec::new(),
+            comment: None,
         })
     }
 
@@ -479,3 +663,65 @@ impl DatabaseDriver for MongoDbDriver {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_flatten_documents() {
+        let docs = vec![
+            json!({
+                "_id": { "$oid": "65f1c0ffee0000000000abcd" },
+                "address": { "city": "London", "geo": { "lat": 51.5 } },
+                "name": "Ada",
+                "tags": ["a", "b"]
+            }),
+            json!({ "_id": 2, "total": 10 }),
+        ];
+
+        let kept = flatten_documents(&docs, false);
+        assert_eq!(kept.columns, vec!["_id", "address", "name", "tags", "total"]);
+        assert_eq!(kept.rows[0][0], json!("65f1c0ffee0000000000abcd"));
+        assert_eq!(
+            kept.rows[0][1],
+            json!(r#"{"city":"London","geo":{"lat":51.5}}"#)
+        );
+        assert_eq!(kept.rows[0][3], json!(r#"["a","b"]"#));
+        assert_eq!(kept.rows[1], vec![json!(2), json!(null), json!(null), json!(null), json!(10)]);
+
+        let expanded = flatten_documents(&docs, true);
+        assert_eq!(
+            expanded.columns,
+            vec!["_id", "address.city", "address.geo.lat", "name", "tags", "total"]
+        );
+        assert_eq!(expanded.rows[0][2], json!(51.5));
+    }
+
+    #[test]
+    fn test_pipeline_limit_and_errors() {
+        let mut pipeline = vec![doc! { "$match": { "x": 1 } }, doc! { "$group": { "_id": "$x" } }];
+        assert!(validate_pipeline(&pipeline).is_ok());
+        inject_limit(&mut pipeline, 100);
+        assert_eq!(pipeline.last(), Some(&doc! { "$limit": 100_i64 }));
+
+        let mut writes = vec![doc! { "$out": "copy" }];
+        inject_limit(&mut writes, 100);
+        assert_eq!(writes.len(), 1);
+
+        let bad = vec![doc! { "$match": {} }, doc! { "$sort": { "a": 1 }, "$limit": 5 }];
+        assert!(validate_pipeline(&bad)
+            .unwrap_err()
+            .to_string()
+            .contains("stage 1"));
+
+        let err = pipeline_error(
+            &pipeline,
+            "Command failed: The field 'total' must be an accumulator object, in $group",
+        );
+        assert!(err.to_string().contains("stage 1 ($group)"));
+        let err = pipeline_error(&pipeline, "Command failed: connection reset");
+        assert!(err.to_string().contains("Aggregation failed: Command failed"));
+    }
+}
@@ -5,14 +5,21 @@
 
 use async_trait::async_trait;
 use mysql_async::prelude::*;
-use mysql_async::{Conn, OptsBuilder, Pool};
+use mysql_async::{Conn, OptsBuilder, Pool, PoolConstraints, PoolOpts, TxOpts};
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::drivers::{ConnectionOptions, DatabaseDriver, QueryResult, MAX_RESULT_ROWS};
+use crate::drivers::blob::{blob_bytes, blob_value};
+use crate::drivers::{
+    BoundStatement, ConnectionOptions, DatabaseDriver, QueryResult, RowSink, StatementCallback,
+    MAX_RESULT_ROWS,
+};
 use crate::models::{
-    ColumnInfo, DatabaseInfo, DbError, ForeignKeyInfo, IndexInfo, SchemaInfo, TableInfo,
+    ColumnInfo, DatabaseInfo, DbError, ErrorCode, ForeignKeyInfo, IndexInfo, SchemaInfo, TableInfo,
     TableSchema,
 };
+use crate::sql::quote;
+use crate::sql::tokenizer::Quoting;
 
 pub struct MysqlDriver {
     /// Connection pool backing all queries (PERF-07).
@@ -26,11 +33,26 @@ pub struct MysqlDriver {
     /// not depend on per-connection session state.
     pool: Arc<Pool>,
     current_database: String,
+
+    /// Connection settings, kept to open transaction sessions
+    opts: OptsBuilder,
 }
 
 impl MysqlDriver {
+    /// Errors the server reported keep their error number and SQLSTATE
     fn map_mysql_error(err: mysql_async::Error) -> DbError {
-        DbError::QueryError(err.to_string())
+        let message = err.to_string();
+        match err {
+            mysql_async::Error::Server(server) => DbError::DatabaseError {
+                message,
+                code: ErrorCode::MySql {
+                    number: server.code,
+                    sqlstate: server.state,
+                },
+                details: None,
+            },
+            _ => DbError::QueryError(message),
+        }
     }
 
     /// Check a connection out of the pool for one call (PERF-07).
@@ -40,20 +62,143 @@ impl MysqlDriver {
             .await
             .map_err(|e| DbError::ConnectionError(format!("Failed to get connection: {}", e)))
     }
+
+    /// Collect a text- or binary-protocol result into a `QueryResult`,
+    /// capped at `MAX_RESULT_ROWS + 1` rows.
+    async fn collect_result<P: Protocol>(
+        mut result: mysql_async::QueryResult<'_, '_, P>,
+    ) -> Result<QueryResult, DbError> {
+        // Capture columns before consuming rows (must be read before iteration)
+        let maybe_columns = result.columns();
+
+        if let Some(columns) = maybe_columns {
+            let column_names: Vec<String> = columns
+                .iter()
+                .map(|col| col.name_str().to_string())
+                .collect();
+            let binary: Vec<bool> = columns.iter().map(Self::is_binary_column).collect();
+
+            let mut rows_data = Vec::new();
+
+            while let Some(row) = result.next().await.map_err(Self::map_mysql_error)? {
+                let mut values = Vec::new();
+                for (i, &is_binary) in binary.iter().enumerate() {
+                    let value: mysql_async::Value = row.get(i).unwrap_or(mysql_async::Value::NULL);
+                    values.push(Self::mysql_value_to_json(value, is_binary));
+                }
+                rows_data.push(values);
+
+                // Enforce the row cap inside the fetch loop so an unbounded
+                // SELECT never materializes the full result set (PERF-03).
+                // One extra row past the cap lets the caller flag truncation;
+                // drop_result() below skips the remaining rows on the wire.
+                if rows_data.len() > MAX_RESULT_ROWS {
+                    break;
+                }
+            }
+
+            // REQUIRED: release the connection back to a clean protocol state.
+            // Without this, mysql_async leaves the connection's state machine mid-stream
+            // and every subsequent query on this connection fails with "Connection closed".
+            result.drop_result().await.map_err(Self::map_mysql_error)?;
+
+            Ok(QueryResult::with_data(column_names, rows_data))
+        } else {
+            // DML statement (INSERT, UPDATE, DELETE, SET, etc.)
+            let affected_rows = result.affected_rows();
+
+            // REQUIRED: same reason as above — must always call drop_result()
+            result.drop_result().await.map_err(Self::map_mysql_error)?;
+
+            Ok(QueryResult::with_affected(affected_rows))
+        }
+    }
+
+    /// Convert a JSON parameter value to a MySQL value for binding
+    fn json_to_mysql_value(value: &serde_json::Value) -> mysql_async::Value {
+        use serde_json::Value;
+
+        if let Some(bytes) = blob_bytes(value) {
+            return mysql_async::Value::Bytes(bytes);
+        }
+
+        match value {
+            Value::Null => mysql_async::Value::NULL,
+            Value::Bool(b) => mysql_async::Value::Int(i64::from(*b)),
+            Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    mysql_async::Value::Int(i)
+                } else if let Some(u) = n.as_u64() {
+                    mysql_async::Value::UInt(u)
+                } else {
+                    mysql_async::Value::Double(n.as_f64().unwrap_or_default())
+                }
+            }
+            Value::String(s) => mysql_async::Value::Bytes(s.as_bytes().to_vec()),
+            other => mysql_async::Value::Bytes(other.to_string().into_bytes()),
+        }
+    }
+
+    /// Run statements in one transaction, committing only if `commit` is set
+    ///
+    /// Rolling back only undoes changes to transactional (InnoDB) tables.
+    /// Returns `None` if `after_statement` stopped the transaction.
+    async fn run_in_transaction(
+        &self,
+        statements: &[BoundStatement],
+        commit: bool,
+        mut after_statement: Option<StatementCallback>,
+    ) -> Result<Option<Vec<u64>>, DbError> {
+        let mut conn = self.get_conn().await?;
+
+        // A `Transaction` dropped without commit is rolled back when the
+        // connection is returned to the pool.
+        let mut tx = conn
+            .start_transaction(TxOpts::default())
+            .await
+            .map_err(Self::map_mysql_error)?;
+
+        let mut affected = Vec::with_capacity(statements.len());
+        for statement in statements {
+            if statement.params.is_empty() {
+                tx.query_drop(statement.sql.as_str())
+                    .await
+                    .map_err(Self::map_mysql_error)?;
+            } else {
+                let values: Vec<mysql_async::Value> = statement
+                    .params
+                    .iter()
+                    .map(Self::json_to_mysql_value)
+                    .collect();
+                tx.exec_drop(
+                    statement.sql.as_str(),
+                    mysql_async::Params::Positional(values),
+                )
+                .await
+                .map_err(Self::map_mysql_error)?;
+            }
+            affected.push(tx.affected_rows());
+            if after_statement.as_mut().is_some_and(|f| !f(affected.len())) {
+                tx.rollback().await.map_err(Self::map_mysql_error)?;
+                return Ok(None);
+            }
+        }
+
+        if commit {
+            tx.commit().await.map_err(Self::map_mysql_error)?;
+        } else {
+            tx.rollback().await.map_err(Self::map_mysql_error)?;
+        }
+
+        Ok(Some(affected))
+    }
 }
 
 #[async_trait]
 impl DatabaseDriver for MysqlDriver {
     fn quote_identifier(&self, ident: &str) -> String {
-        // MySQL/MariaDB quote identifiers with backticks; an embedded backtick
-        // is escaped by doubling it.
-        format!("`{}`", ident.replace('`', "``"))
-    }
-
-    fn escape_string_literal(&self, value: &str) -> String {
-        // MySQL treats backslash as an escape character inside string
-        // literals, so escape it in addition to doubling single quotes.
-        value.replace('\\', "\\\\").replace('\'', "''")
+        // MySQL/MariaDB quote identifiers with backticks
+        quote::quote_identifier(ident, Quoting::MySql)
     }
 
     async fn connect(opts: ConnectionOptions) -> Result<Self, DbError>
@@ -64,7 +209,19 @@ impl DatabaseDriver for MysqlDriver {
         let port = opts.port;
         let user = &opts.username;
         let password = opts.password.as_deref().unwrap_or("");
-        let database = opts.database.as_deref().unwrap_or("mysql");
+        // A MySQL schema is a database, so the default schema replaces it
+        let database = opts
+            .default_schema
+            .as_deref()
+            .filter(|s| !s.is_empty())
+            .or(opts.database.as_deref())
+            .unwrap_or("mysql");
+
+        // Shown as `program_name` in performance_schema.session_connect_attrs
+        let connect_attributes = opts
+            .application_name
+            .as_ref()
+            .map(|name| HashMap::from([("program_name".to_string(), name.clone())]));
 
         let opts_builder = OptsBuilder::default()
             .ip_or_hostname(host)
@@ -72,6 +229,7 @@ impl DatabaseDriver for MysqlDriver {
             .user(Some(user))
             .pass(Some(password))
             .db_name(Some(database))
+            .connect_attributes(connect_attributes)
             .max_allowed_packet(Some(1073741824)); // 1GB — needed for large mysqldump imports
 
         let pool = Pool::new(opts_builder.clone());
@@ -84,57 +242,125 @@ impl DatabaseDriver for MysqlDriver {
         Ok(Self {
             pool: Arc::new(pool),
             current_database: database.to_string(),
+            opts: opts_builder,
         })
     }
 
     async fn execute_query(&self, sql: &str) -> Result<QueryResult, DbError> {
         let mut conn = self.get_conn().await?;
 
-        let mut result = conn.query_iter(sql).await.map_err(Self::map_mysql_error)?;
+        let result = conn.query_iter(sql).await.map_err(Self::map_mysql_error)?;
+        Self::collect_result(result).await
+    }
 
-        // Capture columns before consuming rows (must be read before iteration)
-        let maybe_columns = result.columns();
+    async fn execute_query_with_params(
+        &self,
+        sql: &str,
+        params: &[serde_json::Value],
+    ) -> Result<QueryResult, DbError> {
+        let mut conn = self.get_conn().await?;
 
-        if let Some(columns) = maybe_columns {
-            let column_names: Vec<String> = columns
+        let values: Vec<mysql_async::Value> =
+            params.iter().map(Self::json_to_mysql_value).collect();
+        let result = conn
+            .exec_iter(sql, mysql_async::Params::Positional(values))
+            .await
+            .map_err(Self::map_mysql_error)?;
+        Self::collect_result(result).await
+    }
+
+    async fn stream_query(
+        &self,
+        sql: &str,
+        batch_size: usize,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64, DbError> {
+        let mut conn = self.get_conn().await?;
+
+        let mut result = conn.query_iter(sql).await.map_err(Self::map_mysql_error)?;
+
+        let Some(columns) = result.columns() else {
+            result.drop_result().await.map_err(Self::map_mysql_error)?;
+            return Err(DbError::InvalidInput(
+                "Query does not return any rows".to_string(),
+            ));
+        };
+        let column_names: Vec<String> = columns
+            .iter()
+            .map(|col| col.name_str().to_string())
+            .collect();
+        let binary: Vec<bool> = columns.iter().map(Self::is_binary_column).collect();
+
+        let batch_size = batch_size.max(1);
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut total: u64 = 0;
+        let mut outcome = sink.columns(&column_names).map(|_| true);
+
+        while matches!(outcome, Ok(true)) {
+            let Some(row) = result.next().await.map_err(Self::map_mysql_error)? else {
+                break;
+            };
+            let values = binary
                 .iter()
-                .map(|col| col.name_str().to_string())
+                .enumerate()
+                .map(|(i, &is_binary)| {
+                    let value = row.get(i).unwrap_or(mysql_async::Value::NULL);
+                    Self::mysql_value_to_json(value, is_binary)
+                })
                 .collect();
+            batch.push(values);
 
-            let mut rows_data = Vec::new();
+            if batch.len() >= batch_size {
+                let full = std::mem::replace(&mut batch, Vec::with_capacity(batch_size));
+                total += full.len() as u64;
+                outcome = sink.rows(full);
+            }
+        }
 
-            while let Some(row) = result.next().await.map_err(Self::map_mysql_error)? {
-                let mut values = Vec::new();
-                for i in 0..column_names.len() {
-                    let value: mysql_async::Value = row.get(i).unwrap_or(mysql_async::Value::NULL);
-                    values.push(Self::mysql_value_to_json(value));
-                }
-                rows_data.push(values);
+        if matches!(outcome, Ok(true)) && !batch.is_empty() {
+            total += batch.len() as u64;
+            outcome = sink.rows(batch);
+        }
 
-                // Enforce the row cap inside the fetch loop so an unbounded
-                // SELECT never materializes the full result set (PERF-03).
-                // One extra row past the cap lets the caller flag truncation;
-                // drop_result() below skips the remaining rows on the wire.
-                if rows_data.len() > MAX_RESULT_ROWS {
-                    break;
-                }
-            }
+        // REQUIRED (see execute_query): also skips any rows left unread when
+        // the sink stopped early.
+        result.drop_result().await.map_err(Self::map_mysql_error)?;
 
-            // REQUIRED: release the connection back to a clean protocol state.
-            // Without this, mysql_async leaves the connection's state machine mid-stream
-            // and every subsequent query on this connection fails with "Connection closed".
-            result.drop_result().await.map_err(Self::map_mysql_error)?;
+        outcome.map(|_| total)
+    }
 
-            Ok(QueryResult::with_data(column_names, rows_data))
-        } else {
-            // DML statement (INSERT, UPDATE, DELETE, SET, etc.)
-            let affected_rows = result.affected_rows();
+    async fn execute_in_transaction(&self, statements: &[String]) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(&BoundStatement::unbound(statements), true, None)
+            .await
+            .map(Option::unwrap_or_default)
+    }
 
-            // REQUIRED: same reason as above — must always call drop_result()
-            result.drop_result().await.map_err(Self::map_mysql_error)?;
+    async fn execute_in_transaction_with(
+        &self,
+        statements: &[String],
+        after_statement: StatementCallback,
+    ) -> Result<Option<Vec<u64>>, DbError> {
+        self.run_in_transaction(
+            &BoundStatement::unbound(statements),
+            true,
+            Some(after_statement),
+        )
+        .await
+    }
 
-            Ok(QueryResult::with_affected(affected_rows))
-        }
+    async fn execute_in_transaction_with_params(
+        &self,
+        statements: &[BoundStatement],
+    ) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(statements, true, None)
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    async fn execute_and_rollback(&self, statements: &[String]) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(&BoundStatement::unbound(statements), false, None)
+            .await
+            .map(Option::unwrap_or_default)
     }
 
     async fn get_databases(&self) -> Result<Vec<DatabaseInfo>, DbError> {
@@ -213,13 +439,25 @@ impl DatabaseDriver for MysqlDriver {
                 IS_NULLABLE,
                 COLUMN_DEFAULT,
                 COLUMN_KEY,
-                EXTRA
+                EXTRA,
+                COLUMN_COMMENT,
+                GENERATION_EXPRESSION
             FROM information_schema.COLUMNS
             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
             ORDER BY ORDINAL_POSITION
         "#;
 
-        let column_rows: Vec<(String, String, String, Option<String>, String, String)> = conn
+        type ColumnRow = (
+            String,
+            String,
+            String,
+            Option<String>,
+            String,
+            String,
+            String,
+            Option<String>,
+        );
+        let column_rows: Vec<ColumnRow> = conn
             .exec(column_query, (&self.current_database, table_name))
             .await
             .map_err(Self::map_mysql_error)?;
@@ -227,8 +465,23 @@ impl DatabaseDriver for MysqlDriver {
         let columns: Vec<ColumnInfo> = column_rows
             .into_iter()
             .map(
-                |(name, column_type, is_nullable, default_value, column_key, extra)| {
-                    let is_auto_increment = extra.to_lowercase().contains("auto_increment");
+                |(
+                    name,
+                    column_type,
+                    is_nullable,
+                    default_value,
+                    column_key,
+                    extra,
+                    comment,
+                    generation_expression,
+                )| {
+                    let extra = extra.to_lowercase();
+                    let is_auto_increment = extra.contains("auto_increment");
+                    // "VIRTUAL GENERATED" / "STORED GENERATED"; MySQL 8 also
+                    // reports "DEFAULT_GENERATED" for expression defaults,
+                    // which are ordinary writable columns
+                    let is_generated =
+                        extra.contains("virtual generated") || extra.contains("stored generated");
 
                     ColumnInfo {
                         name,
@@ -237,11 +490,27 @@ impl DatabaseDriver for MysqlDriver {
                         default_value,
                         is_primary_key: column_key == "PRI",
                         is_auto_increment,
+                        // An empty COLUMN_COMMENT means no comment
+                        comment: Some(comment).filter(|c| !c.is_empty()),
+                        is_generated,
+                        // Empty rather than NULL for ordinary columns on MySQL
+                        generation_expression: generation_expression
+                            .filter(|e| is_generated && !e.is_empty()),
                     }
                 },
             )
             .collect();
 
+        // TABLE_COMMENT is NULL for some system views
+        let table_comment: Option<Option<String>> = conn
+            .exec_first(
+                "SELECT TABLE_COMMENT FROM information_schema.TABLES \
+                 WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+                (&self.current_database, table_name),
+            )
+            .await
+            .map_err(Self::map_mysql_error)?;
+
         // Get index information using parameterized query
         let index_query = r#"
             SELECT
@@ -253,36 +522,32 @@ impl DatabaseDriver for MysqlDriver {
             ORDER BY INDEX_NAME, SEQ_IN_INDEX
         "#;
 
-        let index_rows: Vec<(String, i64, String)> = conn
+        // COLUMN_NAME is NULL for functional key parts (MySQL 8.0.13+).
+        let index_rows: Vec<(String, i64, Option<String>)> = conn
             .exec(index_query, (&self.current_database, table_name))
             .await
             .map_err(Self::map_mysql_error)?;
 
-        // Group columns by index name
-        let mut indexes_map: std::collections::HashMap<String, (bool, Vec<String>)> =
-            std::collections::HashMap::new();
+        // Group columns by index name. Rows are ordered by index and
+        // SEQ_IN_INDEX, so each index's columns arrive contiguously and in
+        // key order.
+        let mut indexes: Vec<IndexInfo> = Vec::new();
 
         for (index_name, non_unique, column_name) in index_rows {
-            let is_unique = non_unique == 0;
-            indexes_map
-                .entry(index_name)
-                .or_insert((is_unique, Vec::new()))
-                .1
-                .push(column_name);
-        }
-
-        let indexes: Vec<IndexInfo> = indexes_map
-            .into_iter()
-            .map(|(name, (is_unique, columns))| {
-                let is_primary = name == "PRIMARY";
-                IndexInfo {
-                    name,
-                    columns,
-                    is_unique,
-                    is_primary,
+            let column = column_name.unwrap_or_else(|| "(expression)".to_string());
+            match indexes.last_mut() {
+                Some(index) if index.name == index_name => index.columns.push(column),
+                _ => {
+                    let is_primary = index_name == "PRIMARY";
+                    indexes.push(IndexInfo {
+                        name: index_name,
+                        columns: vec![column],
+                        is_unique: non_unique == 0,
+                        is_primary,
+                    });
                 }
-            })
-            .collect();
+            }
+        }
 
         Ok(TableSchema {
             table: TableInfo {
@@ -293,6 +558,8 @@ impl DatabaseDriver for MysqlDriver {
             },
             columns,
             indexes,
+            constraints: Vec::new(),
+            comment: table_comment.flatten().filter(|c| !c.is_empty()),
         })
     }
 
@@ -334,10 +601,11 @@ impl DatabaseDriver for MysqlDriver {
             .await
             .map_err(|e| DbError::QueryError(format!("Failed to fetch foreign keys: {}", e)))?;
 
-        // Group foreign keys by constraint name (for composite foreign keys)
-        use std::collections::HashMap;
-        let mut fk_map: HashMap<String, (ForeignKeyInfo, Vec<String>, Vec<String>)> =
-            HashMap::new();
+        // Group foreign keys by constraint name (for composite foreign keys).
+        // Rows are ordered by table, constraint and ordinal position, so the
+        // columns of one constraint arrive contiguously; folding them in
+        // order keeps the result stable instead of HashMap-ordered.
+        let mut foreign_keys: Vec<ForeignKeyInfo> = Vec::new();
 
         for (
             fk_name,
@@ -351,41 +619,45 @@ impl DatabaseDriver for MysqlDriver {
             on_delete,
         ) in rows
         {
-            fk_map
-                .entry(fk_name.clone())
-                .and_modify(|(_, cols, ref_cols)| {
-                    cols.push(column.clone());
-                    ref_cols.push(ref_column.clone());
-                })
-                .or_insert_with(|| {
-                    let fk = ForeignKeyInfo {
-                        name: fk_name,
-                        table,
-                        schema,
-                        columns: vec![],
-                        referenced_table: ref_table,
-                        referenced_schema: ref_schema,
-                        referenced_columns: vec![],
-                        on_delete: Some(on_delete),
-                        on_update: Some(on_update),
-                    };
-                    (fk, vec![column], vec![ref_column])
-                });
+            match foreign_keys.last_mut() {
+                Some(fk) if fk.name == fk_name && fk.table == table => {
+                    fk.columns.push(column);
+                    fk.referenced_columns.push(ref_column);
+                }
+                _ => foreign_keys.push(ForeignKeyInfo {
+                    name: fk_name,
+                    table,
+                    schema,
+                    columns: vec![column],
+                    referenced_table: ref_table,
+                    referenced_schema: ref_schema,
+                    referenced_columns: vec![ref_column],
+                    on_delete: Some(on_delete),
+                    on_update: Some(on_update),
+                }),
+            }
         }
 
-        // Convert HashMap to Vec, filling in the columns
-        let foreign_keys: Vec<ForeignKeyInfo> = fk_map
-            .into_iter()
-            .map(|(_, (mut fk, cols, ref_cols))| {
-                fk.columns = cols;
-                fk.referenced_columns = ref_cols;
-                fk
-            })
-            .collect();
-
         Ok(foreign_keys)
     }
 
+    async fn open_session(&self) -> Result<Option<Arc<dyn DatabaseDriver>>, DbError> {
+        // A pool of exactly one connection. It must not be reset when a call
+        // hands it back, since that would roll back the open transaction.
+        let constraints = PoolConstraints::new(1, 1).expect("1..=1 is a valid pool size");
+        let pool_opts = PoolOpts::default()
+            .with_constraints(constraints)
+            .with_reset_connection(false);
+        let pool = Pool::new(self.opts.clone().pool_opts(pool_opts));
+        let _ = pool.get_conn().await.map_err(Self::map_mysql_error)?;
+
+        Ok(Some(Arc::new(Self {
+            pool: Arc::new(pool),
+            current_database: self.current_database.clone(),
+            opts: self.opts.clone(),
+        })))
+    }
+
     async fn close(&self) -> Result<(), DbError> {
         // MySQL connection pool will clean up automatically on drop
         Ok(())
@@ -393,18 +665,36 @@ impl DatabaseDriver for MysqlDriver {
 }
 
 impl MysqlDriver {
-    fn mysql_value_to_json(value: mysql_async::Value) -> serde_json::Value {
+    /// Whether a result column holds binary data (BLOB, BINARY, VARBINARY)
+    ///
+    /// Those are string/blob columns with the `binary` character set (63).
+    /// Numbers and dates also report charset 63, so the type is checked too.
+    fn is_binary_column(column: &mysql_async::Column) -> bool {
+        use mysql_async::consts::ColumnType::*;
+        column.character_set() == 63
+            && matches!(
+                column.column_type(),
+                MYSQL_TYPE_TINY_BLOB
+                    | MYSQL_TYPE_MEDIUM_BLOB
+                    | MYSQL_TYPE_LONG_BLOB
+                    | MYSQL_TYPE_BLOB
+                    | MYSQL_TYPE_VAR_STRING
+                    | MYSQL_TYPE_STRING
+                    | MYSQL_TYPE_VARCHAR
+            )
+    }
+
+    fn mysql_value_to_json(value: mysql_async::Value, is_binary: bool) -> serde_json::Value {
         use mysql_async::Value;
         match value {
             Value::NULL => serde_json::Value::Null,
+            Value::Bytes(b) if is_binary => blob_value(&b),
             Value::Bytes(b) => {
                 // Try to convert bytes to UTF-8 string
-                match String::from_utf8(b.clone()) {
+                match String::from_utf8(b) {
                     Ok(s) => serde_json::Value::String(s),
-                    Err(_) => {
-                        // If not valid UTF-8, encode as base64 or hex
-                        serde_json::Value::String(format!("0x{}", hex::encode(b)))
-                    }
+                    // Not text after all (e.g. an expression over a BLOB)
+                    Err(e) => blob_value(e.as_bytes()),
                 }
             }
             Value::Int(i) => serde_json::json!(i),
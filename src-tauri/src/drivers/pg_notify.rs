@@ -0,0 +1,151 @@
+//! PostgreSQL `LISTEN`/`NOTIFY`
+//!
+//! Notifications are delivered to the session that ran `LISTEN`, between
+//! queries, so they can't go through the query pool: a pooled connection
+//! only reads from the socket while a query is running, and the next query
+//! may run on another connection anyway. `PgListener` holds a dedicated
+//! connection instead, with a task that keeps polling it and hands each
+//! notification to a callback.
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use futures_util::future::poll_fn;
+use serde::Serialize;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::task::JoinHandle;
+use tokio_postgres::{AsyncMessage, Client, Config, Connection, NoTls};
+
+use crate::models::DbError;
+
+/// A notification received on a listened channel
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PgNotification {
+    pub channel: String,
+    pub payload: String,
+    /// Backend process that sent the notification
+    pub process_id: i32,
+}
+
+/// Called with each notification, from the listener's task
+pub type NotificationCallback = Box<dyn Fn(PgNotification) + Send + Sync>;
+
+/// Dedicated session for `LISTEN`
+///
+/// Dropping the listener closes its connection, which ends every
+/// subscription.
+pub struct PgListener {
+    client: Client,
+    task: JoinHandle<()>,
+    channels: Mutex<BTreeSet<String>>,
+}
+
+impl PgListener {
+    /// Open the dedicated connection and start polling it
+    pub async fn connect(
+        config: &Config,
+        require_tls: bool,
+        on_notification: NotificationCallback,
+    ) -> Result<Self, DbError> {
+        let connect_error = |e: tokio_postgres::Error| {
+            DbError::ConnectionError(format!("Failed to connect: {}", e))
+        };
+        let (client, task) = if require_tls {
+            let connector = native_tls::TlsConnector::builder()
+                .build()
+                .map_err(|e| DbError::ConnectionError(format!("TLS init failed: {}", e)))?;
+            let tls = postgres_native_tls::MakeTlsConnector::new(connector);
+            let (client, connection) = config.connect(tls).await.map_err(connect_error)?;
+            (client, spawn_poller(connection, on_notification))
+        } else {
+            let (client, connection) = config.connect(NoTls).await.map_err(connect_error)?;
+            (client, spawn_poller(connection, on_notification))
+        };
+
+        Ok(Self {
+            client,
+            task,
+            channels: Mutex::new(BTreeSet::new()),
+        })
+    }
+
+    /// Subscribe to `channel`
+    pub async fn listen(&self, channel: &str) -> Result<(), DbError> {
+        self.client
+            .batch_execute(&format!("LISTEN {}", quote_channel(channel)))
+            .await
+            .map_err(|e| DbError::QueryError(format!("LISTEN failed: {}", e)))?;
+        self.channels.lock().unwrap().insert(channel.to_string());
+        Ok(())
+    }
+
+    /// Unsubscribe from `channel`
+    pub async fn unlisten(&self, channel: &str) -> Result<(), DbError> {
+        self.client
+            .batch_execute(&format!("UNLISTEN {}", quote_channel(channel)))
+            .await
+            .map_err(|e| DbError::QueryError(format!("UNLISTEN failed: {}", e)))?;
+        self.channels.lock().unwrap().remove(channel);
+        Ok(())
+    }
+
+    /// Channels currently subscribed to, sorted
+    pub fn channels(&self) -> Vec<String> {
+        self.channels.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Whether the dedicated connection has been closed (by the server or a
+    /// network failure)
+    pub fn is_closed(&self) -> bool {
+        self.client.is_closed()
+    }
+}
+
+impl Drop for PgListener {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Drive `connection` until it closes, passing notifications on
+fn spawn_poller<S, T>(
+    mut connection: Connection<S, T>,
+    on_notification: NotificationCallback,
+) -> JoinHandle<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        while let Some(message) = poll_fn(|cx| connection.poll_message(cx)).await {
+            match message {
+                Ok(AsyncMessage::Notification(n)) => on_notification(PgNotification {
+                    channel: n.channel().to_string(),
+                    payload: n.payload().to_string(),
+                    process_id: n.process_id(),
+                }),
+                // Notices (RAISE NOTICE, ...) aren't interesting here
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+    })
+}
+
+/// Channel names are identifiers; quoting keeps their case and allows any
+/// character
+fn quote_channel(channel: &str) -> String {
+    format!("\"{}\"", channel.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_channel() {
+        assert_eq!(quote_channel("orders"), "\"orders\"");
+        assert_eq!(quote_channel("a\"; DROP"), "\"a\"\"; DROP\"");
+    }
+}
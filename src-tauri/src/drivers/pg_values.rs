@@ -0,0 +1,408 @@
+//! PostgreSQL values without a direct JSON mapping
+//!
+//! `numeric`, `money` and `interval` are formatted as exact text: decimal
+//! strings for the first two (a `numeric` only becomes a JSON number when
+//! the user opts into that, since f64 can't hold every value) and an
+//! ISO-8601 duration such as `P1Y2M3DT4H5M6.5S` for intervals.
+//!
+//! Arrays become (nested, for multi-dimensional arrays) JSON arrays with
+//! `null` for NULL elements, and composite values become objects keyed by
+//! field name. Anonymous `record` values carry no field names on the wire,
+//! so their fields are named `f1`, `f2`, ... like `row_to_json` does.
+//!
+//! Both are decoded from the binary wire format, element by element, so the
+//! element type doesn't need a `FromSql` impl of its own: types without a
+//! JSON mapping here come through as binary values.
+
+use std::error::Error;
+
+use serde_json::{Map, Number, Value};
+use tokio_postgres::types::{FromSql, Kind, Type};
+
+use super::blob::blob_value;
+use super::postgis::geometry_value;
+
+type BoxError = Box<dyn Error + Sync + Send>;
+
+/// Text of a `numeric`, `money` or `interval` value
+pub struct Formatted(pub String);
+
+impl<'a> FromSql<'a> for Formatted {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        let text = match ty.name() {
+            "numeric" => numeric_to_string(raw)?,
+            "money" => money_to_string(i64::from_sql(&Type::INT8, raw)?),
+            _ => interval_to_iso(raw)?,
+        };
+        Ok(Formatted(text))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(*ty, Type::NUMERIC | Type::MONEY | Type::INTERVAL)
+    }
+}
+
+/// JSON value for the decimal text of a `numeric`
+///
+/// With `as_number`, finite values become JSON numbers rounded to f64;
+/// `NaN` and the infinities stay strings either way.
+pub fn numeric_value(text: String, as_number: bool) -> Value {
+    let number = as_number
+        .then(|| text.parse::<f64>().ok())
+        .flatten()
+        .and_then(Number::from_f64);
+    number.map_or(Value::String(text), Value::Number)
+}
+
+/// An array, composite or `record` value decoded into JSON
+pub struct Structured(pub Value);
+
+impl<'a> FromSql<'a> for Structured {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        decode(ty, raw).map(Structured)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty.kind(), Kind::Array(_) | Kind::Composite(_)) || *ty == Type::RECORD
+    }
+}
+
+fn decode(ty: &Type, raw: &[u8]) -> Result<Value, BoxError> {
+    match ty.kind() {
+        Kind::Array(element) => decode_array(element, raw),
+        Kind::Composite(fields) => {
+            let names: Vec<&str> = fields.iter().map(|f| f.name()).collect();
+            decode_composite(&names, fields.iter().map(|f| f.type_()), raw)
+        }
+        Kind::Domain(base) => decode(base, raw),
+        Kind::Enum(_) => Ok(Value::String(std::str::from_utf8(raw)?.to_string())),
+        _ if *ty == Type::RECORD => decode_composite(&[], std::iter::empty(), raw),
+        _ => decode_scalar(ty, raw),
+    }
+}
+
+/// Reads big-endian integers off the front of a wire value
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], BoxError> {
+        if self.0.len() < len {
+            return Err("unexpected end of value".into());
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn i32(&mut self) -> Result<i32, BoxError> {
+        let bytes = self.take(4)?;
+        Ok(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// A length-prefixed value; a length of -1 is NULL
+    fn value(&mut self) -> Result<Option<&'a [u8]>, BoxError> {
+        match self.i32()? {
+            -1 => Ok(None),
+            len if len < 0 => Err("invalid value length".into()),
+            len => self.take(len as usize).map(Some),
+        }
+    }
+}
+
+fn decode_array(element: &Type, raw: &[u8]) -> Result<Value, BoxError> {
+    let mut cursor = Cursor(raw);
+    let ndim = cursor.i32()?;
+    let _has_nulls = cursor.i32()?;
+    let _element_oid = cursor.i32()?;
+    let mut dims = Vec::new();
+    for _ in 0..ndim {
+        let len = cursor.i32()?;
+        let _lower_bound = cursor.i32()?;
+        dims.push(usize::try_from(len).map_err(|_| "invalid array dimension")?);
+    }
+
+    let count = if dims.is_empty() {
+        0
+    } else {
+        dims.iter().product()
+    };
+    let mut values = Vec::with_capacity(count.min(cursor.0.len() / 4));
+    for _ in 0..count {
+        values.push(match cursor.value()? {
+            Some(raw) => decode(element, raw)?,
+            None => Value::Null,
+        });
+    }
+    Ok(nest(&dims, &mut values.into_iter()))
+}
+
+/// Shape a flat, row-major list of elements into nested arrays
+fn nest(dims: &[usize], values: &mut impl Iterator<Item = Value>) -> Value {
+    match dims.split_first() {
+        None => Value::Array(Vec::new()),
+        Some((&len, [])) => Value::Array(values.take(len).collect()),
+        Some((&len, rest)) => Value::Array((0..len).map(|_| nest(rest, values)).collect()),
+    }
+}
+
+/// Decode a composite value; fields past the end of `names` (all of them,
+/// for a `record`) are named `f1`, `f2`, ... and typed by their OID
+fn decode_composite<'t>(
+    names: &[&str],
+    mut types: impl Iterator<Item = &'t Type>,
+    raw: &[u8],
+) -> Result<Value, BoxError> {
+    let mut cursor = Cursor(raw);
+    let count = cursor.i32()?;
+    let mut object = Map::new();
+    for i in 0..count.max(0) as usize {
+        let oid = cursor.i32()? as u32;
+        let ty = types
+            .next()
+            .cloned()
+            .or_else(|| Type::from_oid(oid))
+            .unwrap_or(Type::BYTEA);
+        let value = match cursor.value()? {
+            Some(raw) => decode(&ty, raw)?,
+            None => Value::Null,
+        };
+        let name = names
+            .get(i)
+            .map_or_else(|| format!("f{}", i + 1), |name| name.to_string());
+        object.insert(name, value);
+    }
+    Ok(Value::Object(object))
+}
+
+/// Decode one array element or composite field
+///
+/// Mirrors the column mapping of the Postgres driver; anything else that
+/// isn't text comes through as a binary value.
+fn decode_scalar(ty: &Type, raw: &[u8]) -> Result<Value, BoxError> {
+    let float = |f: f64| Number::from_f64(f).map_or(Value::Null, Value::Number);
+    Ok(match ty.name() {
+        "bool" => Value::Bool(bool::from_sql(ty, raw)?),
+        "int2" => Value::from(i16::from_sql(ty, raw)?),
+        "int4" => Value::from(i32::from_sql(ty, raw)?),
+        "int8" => Value::from(i64::from_sql(ty, raw)?),
+        "oid" => Value::from(u32::from_sql(ty, raw)?),
+        "float4" => float(f32::from_sql(ty, raw)? as f64),
+        "float8" => float(f64::from_sql(ty, raw)?),
+        "numeric" | "money" | "interval" => Value::String(Formatted::from_sql(ty, raw)?.0),
+        "uuid" => Value::String(uuid::Uuid::from_sql(ty, raw)?.to_string()),
+        "timestamp" => Value::String(chrono::NaiveDateTime::from_sql(ty, raw)?.to_string()),
+        "timestamptz" => {
+            Value::String(chrono::DateTime::<chrono::Utc>::from_sql(ty, raw)?.to_rfc3339())
+        }
+        "date" => Value::String(chrono::NaiveDate::from_sql(ty, raw)?.to_string()),
+        "time" => Value::String(chrono::NaiveTime::from_sql(ty, raw)?.to_string()),
+        "json" | "jsonb" => Value::from_sql(ty, raw)?,
+        "bytea" => blob_value(raw),
+        "geometry" | "geography" => geometry_value(raw),
+        _ if <&str as FromSql>::accepts(ty) => Value::String(<&str>::from_sql(ty, raw)?.into()),
+        _ => blob_value(raw),
+    })
+}
+
+/// Decimal text of a binary `numeric`, digits and scale kept exactly
+fn numeric_to_string(raw: &[u8]) -> Result<String, BoxError> {
+    let mut cursor = Cursor(raw);
+    let mut word = || -> Result<u16, BoxError> {
+        let bytes = cursor.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    };
+    let ndigits = word()? as usize;
+    let weight = word()? as i16 as isize;
+    let sign = match word()? {
+        0x0000 => "",
+        0x4000 => "-",
+        0xC000 => return Ok("NaN".to_string()),
+        0xD000 => return Ok("Infinity".to_string()),
+        0xF000 => return Ok("-Infinity".to_string()),
+        _ => return Err("invalid numeric sign".into()),
+    };
+    let scale = word()? as usize;
+    // Base-10000 digits; digit `k` has weight `weight - k`
+    let digits = (0..ndigits)
+        .map(|_| word())
+        .collect::<Result<Vec<_>, _>>()?;
+    let digit = |k: isize| {
+        usize::try_from(k)
+            .ok()
+            .and_then(|k| digits.get(k))
+            .map_or(0, |&d| d)
+    };
+
+    let mut text = sign.to_string();
+    if weight < 0 {
+        text.push('0');
+    } else {
+        text.push_str(&digit(0).to_string());
+        for k in 1..=weight {
+            text.push_str(&format!("{:04}", digit(k)));
+        }
+    }
+    if scale > 0 {
+        let mut fraction = String::new();
+        let mut k = weight + 1;
+        while fraction.len() < scale {
+            fraction.push_str(&format!("{:04}", digit(k)));
+            k += 1;
+        }
+        fraction.truncate(scale);
+        text.push('.');
+        text.push_str(&fraction);
+    }
+    Ok(text)
+}
+
+/// A `money` amount, stored as cents
+///
+/// The currency symbol and separators depend on the server's `lc_monetary`
+/// and aren't sent in binary results, so this is the plain amount.
+fn money_to_string(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    format!("{}{}.{:02}", sign, cents / 100, cents % 100)
+}
+
+/// ISO-8601 duration of a binary `interval`, as `IntervalStyle =
+/// iso_8601` prints it
+fn interval_to_iso(raw: &[u8]) -> Result<String, BoxError> {
+    let mut cursor = Cursor(raw);
+    let micros = i64::from_be_bytes(cursor.take(8)?.try_into()?);
+    let days = cursor.i32()?;
+    let months = cursor.i32()?;
+
+    let mut text = String::from("P");
+    for (n, unit) in [(months / 12, 'Y'), (months % 12, 'M'), (days, 'D')] {
+        if n != 0 {
+            text.push_str(&format!("{}{}", n, unit));
+        }
+    }
+    let hours = micros / 3_600_000_000;
+    let minutes = micros % 3_600_000_000 / 60_000_000;
+    let seconds = micros % 60_000_000;
+    if micros != 0 {
+        text.push('T');
+        for (n, unit) in [(hours, 'H'), (minutes, 'M')] {
+            if n != 0 {
+                text.push_str(&format!("{}{}", n, unit));
+            }
+        }
+    }
+    if seconds != 0 {
+        let sign = if seconds < 0 { "-" } else { "" };
+        let (whole, fraction) = (seconds.abs() / 1_000_000, seconds.abs() % 1_000_000);
+        text.push_str(&format!("{}{}", sign, whole));
+        if fraction != 0 {
+            let fraction = format!("{:06}", fraction);
+            text.push('.');
+            text.push_str(fraction.trim_end_matches('0'));
+        }
+        text.push('S');
+    }
+    if text == "P" {
+        text.push_str("T0S");
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio_postgres::types::Field;
+
+    fn be(values: &[i32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn test_decodes_arrays() {
+        // '{{1,NULL},{3,4}}'::int4[]
+        let mut raw = be(&[2, 1, 23, 2, 1, 2, 1]);
+        for element in [Some(1), None, Some(3), Some(4)] {
+            match element {
+                Some(n) => raw.extend(be(&[4, n])),
+                None => raw.extend(be(&[-1])),
+            }
+        }
+        let Structured(value) = Structured::from_sql(&Type::INT4_ARRAY, &raw).unwrap();
+        assert_eq!(value, json!([[1, null], [3, 4]]));
+
+        // '{}'::text[]
+        let Structured(value) = Structured::from_sql(&Type::TEXT_ARRAY, &be(&[0, 0, 25])).unwrap();
+        assert_eq!(value, json!([]));
+
+        // '{12.50,-0.001}'::numeric[]
+        let mut raw = be(&[1, 0, 1700, 2, 1]);
+        for numeric in [[2, 0, 0, 2, 12, 5000], [1, -1, 0x4000, 3, 10, 0]] {
+            let words: Vec<u8> = numeric[..4 + numeric[0] as usize]
+                .iter()
+                .flat_map(|w| (*w as u16).to_be_bytes())
+                .collect();
+            raw.extend(be(&[words.len() as i32]));
+            raw.extend(words);
+        }
+        let Structured(value) = Structured::from_sql(&Type::NUMERIC_ARRAY, &raw).unwrap();
+        assert_eq!(value, json!(["12.50", "-0.001"]));
+    }
+
+    #[test]
+    fn test_decodes_composites() {
+        let address = Type::new(
+            "address".to_string(),
+            90001,
+            Kind::Composite(vec![
+                Field::new("street".to_string(), Type::TEXT),
+                Field::new("zip".to_string(), Type::INT4),
+            ]),
+            "public".to_string(),
+        );
+        // ROW('Main St', NULL)::address
+        let mut raw = be(&[2, 25, 7]);
+        raw.extend(b"Main St");
+        raw.extend(be(&[23, -1]));
+        let Structured(value) = Structured::from_sql(&address, &raw).unwrap();
+        assert_eq!(value, json!({ "street": "Main St", "zip": null }));
+
+        // ROW(1, true) as an anonymous record
+        let mut raw = be(&[2, 23, 4, 1, 16, 1]);
+        raw.push(1);
+        let Structured(value) = Structured::from_sql(&Type::RECORD, &raw).unwrap();
+        assert_eq!(value, json!({ "f1": 1, "f2": true }));
+    }
+
+    #[test]
+    fn test_formats_numeric_money_and_interval() {
+        // 12345678901234567890.000000000000000001 has more digits than f64
+        let words = [
+            10u16, 4, 0, 18, 1234, 5678, 9012, 3456, 7890, 0, 0, 0, 0, 100,
+        ];
+        let raw: Vec<u8> = words.iter().flat_map(|w| w.to_be_bytes()).collect();
+        let Formatted(text) = Formatted::from_sql(&Type::NUMERIC, &raw).unwrap();
+        assert_eq!(text, "12345678901234567890.000000000000000001");
+        assert_eq!(numeric_value(text.clone(), false), json!(text));
+        assert_eq!(numeric_value("12.50".into(), true), json!(12.5));
+        assert_eq!(numeric_value("NaN".into(), true), json!("NaN"));
+
+        let Formatted(money) =
+            Formatted::from_sql(&Type::MONEY, &(-123456i64).to_be_bytes()).unwrap();
+        assert_eq!(money, "-1234.56");
+
+        // 1 year 2 mons 3 days 04:05:06.5
+        let micros: i64 = ((4 * 60 + 5) * 60 + 6) * 1_000_000 + 500_000;
+        let mut raw = micros.to_be_bytes().to_vec();
+        raw.extend(be(&[3, 14]));
+        let Formatted(interval) = Formatted::from_sql(&Type::INTERVAL, &raw).unwrap();
+        assert_eq!(interval, "P1Y2M3DT4H5M6.5S");
+
+        let mut raw = (-90_000_000i64).to_be_bytes().to_vec();
+        raw.extend(be(&[0, 0]));
+        let Formatted(interval) = Formatted::from_sql(&Type::INTERVAL, &raw).unwrap();
+        assert_eq!(interval, "PT-1M-30S");
+        let Formatted(zero) = Formatted::from_sql(&Type::INTERVAL, &[0; 16]).unwrap();
+        assert_eq!(zero, "PT0S");
+    }
+}
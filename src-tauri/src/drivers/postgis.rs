@@ -0,0 +1,234 @@
+//! PostGIS `geometry` and `geography` values
+//!
+//! Both types are sent over the binary protocol as EWKB, so they are decoded
+//! here rather than with `ST_AsGeoJSON`, which would need the query rewritten
+//! and PostGIS functions on the search path. Decoded values are GeoJSON
+//! geometries, which the grid can show on a map and which PostGIS accepts
+//! back as input in SQL exports:
+//!
+//! ```json
+//! { "type": "Point", "coordinates": [-0.19, 5.6] }
+//! ```
+//!
+//! An SRID other than 4326 is kept as a `crs` member, as `ST_AsGeoJSON`
+//! does. Geometries GeoJSON can't represent (curves, TINs, ...) fall back to
+//! hex EWKB, the same text PostGIS prints for a geometry.
+
+use serde_json::{json, Value};
+
+const WKB_Z: u32 = 0x8000_0000;
+const WKB_M: u32 = 0x4000_0000;
+const WKB_SRID: u32 = 0x2000_0000;
+
+/// JSON value for a `geometry` or `geography` column
+pub fn geometry_value(ewkb: &[u8]) -> Value {
+    ewkb_to_geojson(ewkb).unwrap_or_else(|| Value::String(hex::encode_upper(ewkb)))
+}
+
+/// Decode EWKB (or plain WKB) into a GeoJSON geometry
+///
+/// Returns `None` if the bytes are malformed or hold a geometry type GeoJSON
+/// has no equivalent for.
+pub fn ewkb_to_geojson(ewkb: &[u8]) -> Option<Value> {
+    let mut reader = Reader {
+        bytes: ewkb,
+        pos: 0,
+    };
+    let mut srid = 0;
+    let (_, mut geometry) = reader.geometry(&mut srid)?;
+    if reader.pos != ewkb.len() {
+        return None;
+    }
+    if srid != 0 && srid != 4326 {
+        geometry["crs"] = json!({
+            "type": "name",
+            "properties": { "name": format!("EPSG:{}", srid) }
+        });
+    }
+    Some(geometry)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.bytes.get(self.pos..self.pos + N)?.try_into().ok()?;
+        self.pos += N;
+        Some(bytes)
+    }
+
+    fn u32(&mut self, little_endian: bool) -> Option<u32> {
+        let bytes = self.take::<4>()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn f64(&mut self, little_endian: bool) -> Option<f64> {
+        let bytes = self.take::<8>()?;
+        Some(if little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    /// Length prefix of a list, checked against the bytes left so a corrupt
+    /// value can't ask for a huge allocation
+    fn count(&mut self, little_endian: bool, min_item_len: usize) -> Option<usize> {
+        let count = self.u32(little_endian)? as usize;
+        let left = self.bytes.len() - self.pos;
+        (count.checked_mul(min_item_len)? <= left).then_some(count)
+    }
+
+    /// One geometry with its header, and its base type code (1 = Point,
+    /// ...); `srid` is set from the header if it has one
+    fn geometry(&mut self, srid: &mut u32) -> Option<(u32, Value)> {
+        let little_endian = match self.take::<1>()? {
+            [0] => false,
+            [1] => true,
+            _ => return None,
+        };
+        let code = self.u32(little_endian)?;
+        if code & WKB_SRID != 0 {
+            *srid = self.u32(little_endian)?;
+        }
+        // EWKB flags, or ISO WKB's 1000/2000/3000 offsets
+        let iso = (code & 0x0fff_ffff) / 1000;
+        let has_z = code & WKB_Z != 0 || iso == 1 || iso == 3;
+        let has_m = code & WKB_M != 0 || iso == 2 || iso == 3;
+        let layout = Layout {
+            little_endian,
+            dims: 2 + has_z as usize + has_m as usize,
+            has_z,
+        };
+
+        let base = (code & 0x0fff_ffff) % 1000;
+        let (kind, coordinates) = match base {
+            1 => ("Point", self.point(&layout)?),
+            2 => ("LineString", self.points(&layout)?),
+            3 => ("Polygon", self.rings(&layout)?),
+            4 => ("MultiPoint", self.members(little_endian, 1)?),
+            5 => ("MultiLineString", self.members(little_endian, 2)?),
+            6 => ("MultiPolygon", self.members(little_endian, 3)?),
+            7 => {
+                let count = self.count(little_endian, 5)?;
+                let geometries = (0..count)
+                    .map(|_| self.geometry(&mut 0).map(|(_, g)| g))
+                    .collect::<Option<Vec<_>>>()?;
+                let collection = json!({ "type": "GeometryCollection", "geometries": geometries });
+                return Some((base, collection));
+            }
+            _ => return None,
+        };
+        Some((base, json!({ "type": kind, "coordinates": coordinates })))
+    }
+
+    /// A position; an empty point (all NaN) becomes `[]`
+    fn point(&mut self, layout: &Layout) -> Option<Value> {
+        let mut ordinates = Vec::with_capacity(layout.dims);
+        for _ in 0..layout.dims {
+            ordinates.push(self.f64(layout.little_endian)?);
+        }
+        if ordinates.iter().all(|o| o.is_nan()) {
+            return Some(json!([]));
+        }
+        // GeoJSON positions have no M
+        ordinates.truncate(2 + layout.has_z as usize);
+        ordinates
+            .into_iter()
+            .map(|o| serde_json::Number::from_f64(o).map(Value::Number))
+            .collect()
+    }
+
+    fn points(&mut self, layout: &Layout) -> Option<Value> {
+        let count = self.count(layout.little_endian, layout.dims * 8)?;
+        (0..count).map(|_| self.point(layout)).collect()
+    }
+
+    fn rings(&mut self, layout: &Layout) -> Option<Value> {
+        let count = self.count(layout.little_endian, 4)?;
+        (0..count).map(|_| self.points(layout)).collect()
+    }
+
+    /// Coordinates of the members of a multi-geometry, which must all have
+    /// base type `member_type`
+    fn members(&mut self, little_endian: bool, member_type: u32) -> Option<Value> {
+        let count = self.count(little_endian, 5)?;
+        (0..count)
+            .map(|_| match self.geometry(&mut 0)? {
+                (base, mut member) if base == member_type => Some(member["coordinates"].take()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// How the coordinates of one geometry are laid out
+struct Layout {
+    little_endian: bool,
+    dims: usize,
+    has_z: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_ewkb() {
+        // SRID=4326;POINT(1 2)
+        let point = hex::decode("0101000020E6100000000000000000F03F0000000000000040").unwrap();
+        assert_eq!(
+            geometry_value(&point),
+            json!({ "type": "Point", "coordinates": [1.0, 2.0] })
+        );
+
+        // SRID=3857;LINESTRING Z (0 0 1, 1 1 2), big-endian
+        let line = hex::decode(concat!(
+            "00A000000200000F1100000002",
+            "000000000000000000000000000000003FF0000000000000",
+            "3FF00000000000003FF00000000000004000000000000000"
+        ))
+        .unwrap();
+        assert_eq!(
+            geometry_value(&line),
+            json!({
+                "type": "LineString",
+                "coordinates": [[0.0, 0.0, 1.0], [1.0, 1.0, 2.0]],
+                "crs": { "type": "name", "properties": { "name": "EPSG:3857" } }
+            })
+        );
+
+        // MULTIPOINT((1 2)), POINT EMPTY
+        let multi = hex::decode(concat!(
+            "010400000001000000",
+            "0101000000000000000000F03F0000000000000040"
+        ))
+        .unwrap();
+        assert_eq!(
+            geometry_value(&multi),
+            json!({ "type": "MultiPoint", "coordinates": [[1.0, 2.0]] })
+        );
+        let empty = hex::decode("0101000000000000000000F87F000000000000F87F").unwrap();
+        assert_eq!(
+            geometry_value(&empty),
+            json!({ "type": "Point", "coordinates": [] })
+        );
+    }
+
+    #[test]
+    fn test_unsupported_geometry_falls_back_to_hex() {
+        // CIRCULARSTRING EMPTY
+        let curve = hex::decode("010800000000000000").unwrap();
+        assert_eq!(geometry_value(&curve), json!("010800000000000000"));
+        // Truncated
+        let short = hex::decode("0101000000000000").unwrap();
+        assert_eq!(geometry_value(&short), json!("0101000000000000"));
+    }
+}
@@ -3,146 +3,105 @@
 //! This module provides the PostgreSQL implementation of the DatabaseDriver trait
 //! using tokio-postgres for async database operations.
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
 use futures_util::TryStreamExt;
+use tokio_postgres::types::{FromSql, ToSql, Type};
 use tokio_postgres::NoTls;
 
-use super::{ConnectionOptions, DatabaseDriver, QueryResult, MAX_RESULT_ROWS};
+use super::blob::{blob_bytes, blob_value};
+use super::pg_notify::{NotificationCallback, PgListener};
+use super::pg_values::{numeric_value, Formatted, Structured};
+use super::postgis::geometry_value;
+use super::{
+    BoundStatement, ConnectionOptions, DatabaseDriver, QueryResult, RowSink, StatementCallback,
+    MAX_RESULT_ROWS,
+};
 use crate::models::{
-    ColumnInfo, DatabaseInfo, DbError, ForeignKeyInfo, IndexInfo, SchemaInfo, TableInfo, TableSchema,
+    ColumnInfo, ConstraintInfo, ConstraintKind, DatabaseInfo, DbError, ErrorCode, ForeignKeyInfo,
+    IndexInfo, SchemaInfo, TableInfo, TableSchema,
 };
+use crate::sql::params::PlaceholderStyle;
+use crate::sql::quote::{quote_identifier, quote_qualified};
+use crate::sql::tokenizer::{count_statements, tokenize, Quoting, TokenKind};
+
+/// Wire bytes of a value of any type
+///
+/// Used for PostGIS types and as the fallback for types with no text or
+/// JSON conversion, so they show up as binary values instead of NULL.
+struct RawBytes(Vec<u8>);
+
+impl<'a> FromSql<'a> for RawBytes {
+    fn from_sql(
+        _ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(RawBytes(raw.to_vec()))
+    }
 
-/// Quote a PostgreSQL identifier to prevent SQL injection.
-/// Doubles any embedded double-quotes, then wraps in double-quotes.
-fn quote_ident(ident: &str) -> String {
-    format!("\"{}\"", ident.replace('"', "\"\""))
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
 }
 
-/// Count the top-level SQL statements in `sql` (PERF-11).
-///
-/// The previous heuristic was `sql.matches(';').count() > 1`, which misrouted
-/// a single statement containing semicolons inside string literals (or
-/// comments, or dollar-quoted bodies) to `batch_execute` — silently returning
-/// no rows. This scanner ignores semicolons inside:
+/// `DbError` for a failed statement
 ///
-/// - single-quoted string literals (`'it''s'` — doubled-quote escape)
-/// - double-quoted identifiers (`"weird;name"`)
-/// - dollar-quoted strings (`$$ ... $$`, `$tag$ ... $tag$`)
-/// - line comments (`-- ...`) and nested block comments (`/* ... */`)
-///
-/// A statement is only counted if it contains non-whitespace content, so a
-/// trailing semicolon (`SELECT 1;`) still counts as one statement.
-fn count_statements(sql: &str) -> usize {
-    let bytes = sql.as_bytes();
-    let mut i = 0;
-    let mut statements = 0;
-    // Whether the current segment (since the last top-level ';') has content.
-    let mut has_content = false;
-
-    while i < bytes.len() {
-        match bytes[i] {
-            b';' => {
-                if has_content {
-                    statements += 1;
-                    has_content = false;
-                }
-                i += 1;
-            }
-            // Line comment: skip to end of line.
-            b'-' if bytes.get(i + 1) == Some(&b'-') => {
-                while i < bytes.len() && bytes[i] != b'\n' {
-                    i += 1;
-                }
-            }
-            // Block comment: skip to matching close, honoring nesting
-            // (PostgreSQL block comments nest).
-            b'/' if bytes.get(i + 1) == Some(&b'*') => {
-                i += 2;
-                let mut depth = 1;
-                while i < bytes.len() && depth > 0 {
-                    if bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'*') {
-                        depth += 1;
-                        i += 2;
-                    } else if bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/') {
-                        depth -= 1;
-                        i += 2;
-                    } else {
-                        i += 1;
-                    }
-                }
-            }
-            // Single-quoted string literal; '' is an escaped quote.
-            b'\'' => {
-                has_content = true;
-                i += 1;
-                while i < bytes.len() {
-                    if bytes[i] == b'\'' {
-                        if bytes.get(i + 1) == Some(&b'\'') {
-                            i += 2; // escaped quote, stay in string
-                        } else {
-                            i += 1; // closing quote
-                            break;
-                        }
-                    } else {
-                        i += 1;
-                    }
-                }
-            }
-            // Double-quoted identifier; "" is an escaped quote.
-            b'"' => {
-                has_content = true;
-                i += 1;
-                while i < bytes.len() {
-                    if bytes[i] == b'"' {
-                        if bytes.get(i + 1) == Some(&b'"') {
-                            i += 2;
-                        } else {
-                            i += 1;
-                            break;
-                        }
-                    } else {
-                        i += 1;
-                    }
-                }
-            }
-            // Possible dollar-quote opener: `$tag$` where tag is empty or
-            // alphanumeric/underscore. If it doesn't parse as a delimiter
-            // (e.g. `$1` positional parameter), treat the `$` as plain text.
-            b'$' => {
-                let tag_start = i + 1;
-                let mut j = tag_start;
-                while j < bytes.len() && (bytes[j].is_ascii_alphanumeric() || bytes[j] == b'_') {
-                    j += 1;
-                }
-                if j < bytes.len() && bytes[j] == b'$' {
-                    has_content = true;
-                    let delim = &sql[i..=j];
-                    // Scan for the matching closing delimiter.
-                    let body_start = j + 1;
-                    match sql[body_start..].find(delim) {
-                        Some(pos) => i = body_start + pos + delim.len(),
-                        None => i = bytes.len(), // unterminated: consume the rest
-                    }
-                } else {
-                    has_content = true;
-                    i += 1;
-                }
-            }
-            c => {
-                if !c.is_ascii_whitespace() {
-                    has_content = true;
-                }
-                i += 1;
+/// Errors the server reported keep their SQLSTATE, and DETAIL and HINT as
+/// `details`; anything else (I/O, protocol) is a plain `QueryError`.
+fn query_error(e: tokio_postgres::Error) -> DbError {
+    let message = e.to_string();
+    match e.as_db_error() {
+        Some(db) => {
+            let details: Vec<String> = db
+                .detail()
+                .map(str::to_string)
+                .into_iter()
+                .chain(db.hint().map(|hint| format!("Hint: {}", hint)))
+                .collect();
+            DbError::DatabaseError {
+                message,
+                code: ErrorCode::SqlState(db.code().code().to_string()),
+                details: (!details.is_empty()).then(|| details.join("\n")),
             }
         }
+        None => DbError::QueryError(message),
     }
+}
 
-    if has_content {
-        statements += 1;
+/// `query_error` with `context` in front of the message
+fn query_error_with(context: &str) -> impl Fn(tokio_postgres::Error) -> DbError + '_ {
+    move |e| match query_error(e) {
+        DbError::DatabaseError {
+            message,
+            code,
+            details,
+        } => DbError::DatabaseError {
+            message: format!("{}: {}", context, message),
+            code,
+            details,
+        },
+        DbError::QueryError(message) => DbError::QueryError(format!("{}: {}", context, message)),
+        other => other,
     }
+}
 
-    statements
+/// `DbError` for a failure to get a pooled connection
+///
+/// A rejected login (SQLSTATE class 28) is an `AuthError`, so the UI can ask
+/// for the password again.
+fn pool_error(context: &str, e: deadpool_postgres::PoolError) -> DbError {
+    let rejected_login = matches!(
+        &e,
+        deadpool_postgres::PoolError::Backend(e)
+            if e.code().is_some_and(|code| code.code().starts_with("28"))
+    );
+    if rejected_login {
+        DbError::AuthError(format!("{}: {}", context, e))
+    } else {
+        DbError::ConnectionError(format!("{}: {}", context, e))
+    }
 }
 
 /// PostgreSQL database driver
@@ -157,12 +116,27 @@ pub struct PostgresDriver {
     /// single dropped connection no longer kills the whole driver: deadpool
     /// recycles/recreates connections transparently.
     pool: Pool,
+
+    /// Return `numeric` values as JSON numbers instead of exact strings
+    numeric_as_number: bool,
+
+    /// Connection settings, kept to open dedicated `LISTEN` and transaction
+    /// sessions
+    config: tokio_postgres::Config,
+    require_tls: bool,
 }
 
 /// Default maximum number of pooled connections.
 const POOL_MAX_SIZE: usize = 8;
 
 impl PostgresDriver {
+    /// Return `numeric` values as JSON numbers (rounded to f64) instead of
+    /// exact decimal strings
+    pub fn with_numeric_as_number(mut self, numeric_as_number: bool) -> Self {
+        self.numeric_as_number = numeric_as_number;
+        self
+    }
+
     /// Build PostgreSQL connection string from options
     fn build_connection_string(opts: &ConnectionOptions) -> String {
         let mut parts = vec![
@@ -189,22 +163,311 @@ impl PostgresDriver {
             parts.push(format!("connect_timeout={}", timeout));
         }
 
+        if let Some(name) = &opts.application_name {
+            let escaped = name.replace('\\', "\\\\").replace('\'', "\\'");
+            parts.push(format!("application_name='{}'", escaped));
+        }
+
+        if let Some(schema) = opts.default_schema.as_deref().filter(|s| !s.is_empty()) {
+            let escaped = Self::search_path_option(schema)
+                .replace('\\', "\\\\")
+                .replace('\'', "\\'");
+            parts.push(format!("options='{}'", escaped));
+        }
+
         parts.join(" ")
     }
 
+    /// Server `options` setting `search_path` to `schema`, then `public`
+    ///
+    /// Passed at startup so every pooled connection gets it. The server
+    /// splits `options` on whitespace, so spaces in the quoted schema name
+    /// are backslash-escaped.
+    fn search_path_option(schema: &str) -> String {
+        let mut path = quote_identifier(schema, Quoting::Standard);
+        if schema != "public" {
+            path.push_str(",public");
+        }
+        let escaped = path.replace('\\', "\\\\").replace(' ', "\\ ");
+        format!("-c search_path={}", escaped)
+    }
+
+    /// Build a pool of up to `max_size` connections
+    ///
+    /// The deadpool `Manager` is generic over the TLS connector, so each
+    /// branch produces a differently-typed `Pool::builder` chain; both arms
+    /// still yield a `deadpool_postgres::Pool`.
+    fn build_pool(
+        config: &tokio_postgres::Config,
+        require_tls: bool,
+        max_size: usize,
+    ) -> Result<Pool, DbError> {
+        let mgr_config = ManagerConfig {
+            recycling_method: RecyclingMethod::Fast,
+        };
+
+        let builder = if require_tls {
+            let connector = native_tls::TlsConnector::builder()
+                .build()
+                .map_err(|e| DbError::ConnectionError(format!("TLS init failed: {}", e)))?;
+            let tls = postgres_native_tls::MakeTlsConnector::new(connector);
+            Pool::builder(Manager::from_config(config.clone(), tls, mgr_config))
+        } else {
+            Pool::builder(Manager::from_config(config.clone(), NoTls, mgr_config))
+        };
+        builder.max_size(max_size).build().map_err(|e| {
+            DbError::ConnectionError(format!("Failed to build connection pool: {}", e))
+        })
+    }
+
     /// Acquire a pooled client.
     ///
-    /// Pool acquisition failures are mapped to `DbError::ConnectionError`
-    /// (matching the variants already used elsewhere in this file).
+    /// Pool acquisition failures are mapped to `DbError::ConnectionError`,
+    /// or `AuthError` for a rejected login (see `pool_error`).
     async fn client(&self) -> Result<deadpool_postgres::Client, DbError> {
         self.pool
             .get()
             .await
-            .map_err(|e| DbError::ConnectionError(format!("Failed to acquire connection: {}", e)))
+            .map_err(|e| pool_error("Failed to acquire connection", e))
+    }
+
+    /// Run an already prepared single statement with bound parameters
+    ///
+    /// Statements without result columns (DML/DDL) report their affected-row
+    /// count; everything else is fetched up to `MAX_RESULT_ROWS + 1` rows.
+    async fn run_prepared(
+        &self,
+        client: &tokio_postgres::Client,
+        statement: &tokio_postgres::Statement,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<QueryResult, DbError> {
+        let columns: Vec<String> = statement
+            .columns()
+            .iter()
+            .map(|col| col.name().to_string())
+            .collect();
+
+        if columns.is_empty() {
+            // No result columns: DML/DDL (INSERT/UPDATE/DELETE/CREATE/...).
+            // Execute the prepared handle to get the affected-row count.
+            let rows_affected = client
+                .execute(statement, params)
+                .await
+                .map_err(query_error)?;
+
+            return Ok(QueryResult::with_affected(rows_affected));
+        }
+
+        // Data-returning statement (SELECT, or DML with RETURNING).
+        //
+        // `query_raw` returns a `RowStream` instead of a fully materialized
+        // `Vec<Row>`, which lets us stop pulling rows at MAX_RESULT_ROWS — an
+        // unbounded `SELECT *` on a huge table would otherwise buffer every
+        // row in memory before any cap could apply (PERF-03). We fetch one
+        // extra row past the cap so the caller can detect truncation.
+        let stream = client
+            .query_raw(statement, params.iter().copied())
+            .await
+            .map_err(query_error)?;
+        futures_util::pin_mut!(stream);
+
+        let mut rows: Vec<tokio_postgres::Row> = Vec::new();
+        while let Some(row) = stream.try_next().await.map_err(query_error)? {
+            rows.push(row);
+            if rows.len() > MAX_RESULT_ROWS {
+                // Cap reached: stop fetching. Dropping the stream
+                // discards the remainder of the result set.
+                break;
+            }
+        }
+
+        // Convert rows to JSON
+        let data: Vec<Vec<serde_json::Value>> =
+            rows.iter().map(|row| self.row_to_json_vec(row)).collect();
+
+        Ok(QueryResult::with_data(columns, data))
+    }
+
+    /// Whether `to_sql_param` converts values for `ty` directly; other
+    /// parameter types are bound as text and cast back on the server.
+    fn has_native_param(ty: &Type) -> bool {
+        matches!(
+            ty.name(),
+            "bool"
+                | "int2"
+                | "int4"
+                | "int8"
+                | "float4"
+                | "float8"
+                | "text"
+                | "varchar"
+                | "bpchar"
+                | "name"
+                | "unknown"
+                | "json"
+                | "jsonb"
+                | "bytea"
+        )
+    }
+
+    /// `sql` with the parameters of `statement` that have no native
+    /// conversion cast from text, or `None` when every parameter has one
+    fn with_text_casts(sql: &str, statement: &tokio_postgres::Statement) -> Option<String> {
+        let text_cast: Vec<usize> = statement
+            .params()
+            .iter()
+            .enumerate()
+            .filter(|(_, ty)| !Self::has_native_param(ty))
+            .map(|(i, _)| i)
+            .collect();
+        if text_cast.is_empty() {
+            return None;
+        }
+        Some(Self::cast_params_from_text(
+            sql,
+            statement.params(),
+            &text_cast,
+        ))
+    }
+
+    /// Convert `params` to the parameter types of `statement`
+    fn bind_params(
+        statement: &tokio_postgres::Statement,
+        params: &[serde_json::Value],
+    ) -> Result<Vec<Box<dyn ToSql + Send + Sync>>, DbError> {
+        if statement.params().len() != params.len() {
+            return Err(DbError::InvalidInput(format!(
+                "Query expects {} parameters but {} were given",
+                statement.params().len(),
+                params.len()
+            )));
+        }
+
+        statement
+            .params()
+            .iter()
+            .zip(params)
+            .enumerate()
+            .map(|(i, (ty, value))| Self::to_sql_param(value, ty, i + 1))
+            .collect()
+    }
+
+    /// Rewrite the `$n` placeholders listed in `text_cast` (0-based) to
+    /// `$n::text::<type>`, so e.g. a `numeric` or `date` parameter can be
+    /// sent as text and parsed by PostgreSQL itself.
+    fn cast_params_from_text(sql: &str, types: &[Type], text_cast: &[usize]) -> String {
+        tokenize(sql)
+            .map(|token| {
+                let index = token
+                    .text
+                    .strip_prefix('$')
+                    .filter(|_| token.kind == TokenKind::Parameter)
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .and_then(|n| n.checked_sub(1));
+                match index {
+                    Some(i) if text_cast.contains(&i) => format!(
+                        "{}::text::{}",
+                        token.text,
+                        quote_qualified(
+                            Some(types[i].schema()),
+                            types[i].name(),
+                            Quoting::Standard
+                        )
+                    ),
+                    _ => token.text.to_string(),
+                }
+            })
+            .collect()
+    }
+
+    /// Convert a JSON value to a parameter of PostgreSQL type `ty`
+    ///
+    /// Strings are parsed for numeric and boolean types, since parameter
+    /// values usually arrive as text from the UI.
+    fn to_sql_param(
+        value: &serde_json::Value,
+        ty: &Type,
+        position: usize,
+    ) -> Result<Box<dyn ToSql + Send + Sync>, DbError> {
+        use serde_json::Value;
+
+        let invalid = || {
+            DbError::InvalidInput(format!(
+                "Parameter ${} expects a {} value, got {}",
+                position,
+                ty.name(),
+                value
+            ))
+        };
+        let as_i64 = || match value {
+            Value::Number(n) => n.as_i64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        };
+        let as_f64 = || match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        };
+
+        if value.is_null() {
+            let null: Box<dyn ToSql + Send + Sync> = match ty.name() {
+                "bool" => Box::new(None::<bool>),
+                "int2" => Box::new(None::<i16>),
+                "int4" => Box::new(None::<i32>),
+                "int8" => Box::new(None::<i64>),
+                "float4" => Box::new(None::<f32>),
+                "float8" => Box::new(None::<f64>),
+                "json" | "jsonb" => Box::new(None::<Value>),
+                "bytea" => Box::new(None::<Vec<u8>>),
+                _ => Box::new(None::<String>),
+            };
+            return Ok(null);
+        }
+
+        let param: Box<dyn ToSql + Send + Sync> = match ty.name() {
+            "bool" => {
+                let b = match value {
+                    Value::Bool(b) => Some(*b),
+                    Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+                        "true" | "t" | "yes" | "1" => Some(true),
+                        "false" | "f" | "no" | "0" => Some(false),
+                        _ => None,
+                    },
+                    Value::Number(n) => n.as_i64().map(|n| n != 0),
+                    _ => None,
+                };
+                Box::new(b.ok_or_else(invalid)?)
+            }
+            "int2" => Box::new(
+                as_i64()
+                    .and_then(|n| i16::try_from(n).ok())
+                    .ok_or_else(invalid)?,
+            ),
+            "int4" => Box::new(
+                as_i64()
+                    .and_then(|n| i32::try_from(n).ok())
+                    .ok_or_else(invalid)?,
+            ),
+            "int8" => Box::new(as_i64().ok_or_else(invalid)?),
+            "float4" => Box::new(as_f64().ok_or_else(invalid)? as f32),
+            "float8" => Box::new(as_f64().ok_or_else(invalid)?),
+            "json" | "jsonb" => Box::new(value.clone()),
+            "bytea" => Box::new(match (blob_bytes(value), value) {
+                (Some(bytes), _) => bytes,
+                (None, Value::String(s)) => s.as_bytes().to_vec(),
+                _ => return Err(invalid()),
+            }),
+            _ => Box::new(match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            }),
+        };
+        Ok(param)
     }
 
     /// Convert a postgres::Row to a Vec of JSON values
-    fn row_to_json_vec(row: &tokio_postgres::Row) -> Vec<serde_json::Value> {
+    fn row_to_json_vec(&self, row: &tokio_postgres::Row) -> Vec<serde_json::Value> {
         let mut values = Vec::new();
 
         for i in 0..row.len() {
@@ -283,22 +546,26 @@ impl PostgresDriver {
                     .flatten()
                     .map(|v| serde_json::Value::String(v.to_string()))
                     .unwrap_or(serde_json::Value::Null),
-                "numeric" | "decimal" => {
-                    // Try as f64 first for numeric types
-                    row.try_get::<_, Option<f64>>(i)
-                        .ok()
-                        .flatten()
-                        .and_then(|v| serde_json::Number::from_f64(v))
-                        .map(serde_json::Value::Number)
-                        .or_else(|| {
-                            // Fallback to string for very large or precise decimals
-                            row.try_get::<_, Option<String>>(i)
-                                .ok()
-                                .flatten()
-                                .map(serde_json::Value::String)
-                        })
-                        .unwrap_or(serde_json::Value::Null)
-                },
+                // Exact decimal text; f64 would corrupt large or precise
+                // values unless the user asked for numbers
+                "numeric" | "decimal" => row
+                    .try_get::<_, Option<Formatted>>(i)
+                    .ok()
+                    .flatten()
+                    .map(|Formatted(v)| numeric_value(v, self.numeric_as_number))
+                    .unwrap_or(serde_json::Value::Null),
+                "money" | "interval" => row
+                    .try_get::<_, Option<Formatted>>(i)
+                    .ok()
+                    .flatten()
+                    .map(|Formatted(v)| serde_json::Value::String(v))
+                    .unwrap_or(serde_json::Value::Null),
+                "bytea" => row
+                    .try_get::<_, Option<Vec<u8>>>(i)
+                    .ok()
+                    .flatten()
+                    .map(|v| blob_value(&v))
+                    .unwrap_or(serde_json::Value::Null),
                 "json" | "jsonb" => {
                     // With the with-serde_json-1 feature, tokio-postgres can deserialize JSON directly
                     row.try_get::<_, Option<serde_json::Value>>(i)
@@ -325,102 +592,33 @@ impl PostgresDriver {
                         })
                         .unwrap_or(serde_json::Value::Null)
                 },
-                // PostgreSQL array types: type names start with '_'
-                t if t.starts_with('_') => {
-                    let inner = &t[1..];
-                    match inner {
-                        "float4" => row
-                            .try_get::<_, Option<Vec<f32>>>(i)
-                            .ok()
-                            .flatten()
-                            .map(|v| {
-                                serde_json::Value::Array(
-                                    v.iter()
-                                        .map(|&f| {
-                                            serde_json::Number::from_f64(f as f64)
-                                                .map(serde_json::Value::Number)
-                                                .unwrap_or(serde_json::Value::Null)
-                                        })
-                                        .collect(),
-                                )
-                            })
-                            .unwrap_or(serde_json::Value::Null),
-                        "float8" => row
-                            .try_get::<_, Option<Vec<f64>>>(i)
-                            .ok()
-                            .flatten()
-                            .map(|v| {
-                                serde_json::Value::Array(
-                                    v.iter()
-                                        .map(|&f| {
-                                            serde_json::Number::from_f64(f)
-                                                .map(serde_json::Value::Number)
-                                                .unwrap_or(serde_json::Value::Null)
-                                        })
-                                        .collect(),
-                                )
-                            })
-                            .unwrap_or(serde_json::Value::Null),
-                        "int2" => row
-                            .try_get::<_, Option<Vec<i16>>>(i)
-                            .ok()
-                            .flatten()
-                            .map(|v| {
-                                serde_json::Value::Array(
-                                    v.iter().map(|&n| serde_json::Value::Number(n.into())).collect(),
-                                )
-                            })
-                            .unwrap_or(serde_json::Value::Null),
-                        "int4" => row
-                            .try_get::<_, Option<Vec<i32>>>(i)
-                            .ok()
-                            .flatten()
-                            .map(|v| {
-                                serde_json::Value::Array(
-                                    v.iter().map(|&n| serde_json::Value::Number(n.into())).collect(),
-                                )
-                            })
-                            .unwrap_or(serde_json::Value::Null),
-                        "int8" => row
-                            .try_get::<_, Option<Vec<i64>>>(i)
-                            .ok()
-                            .flatten()
-                            .map(|v| {
-                                serde_json::Value::Array(
-                                    v.iter().map(|&n| serde_json::Value::Number(n.into())).collect(),
-                                )
-                            })
-                            .unwrap_or(serde_json::Value::Null),
-                        "bool" => row
-                            .try_get::<_, Option<Vec<bool>>>(i)
-                            .ok()
-                            .flatten()
-                            .map(|v| {
-                                serde_json::Value::Array(
-                                    v.iter().map(|&b| serde_json::Value::Bool(b)).collect(),
-                                )
-                            })
-                            .unwrap_or(serde_json::Value::Null),
-                        // text[], varchar[], and other text array types
-                        _ => row
-                            .try_get::<_, Option<Vec<String>>>(i)
-                            .ok()
-                            .flatten()
-                            .map(|v| {
-                                serde_json::Value::Array(
-                                    v.into_iter().map(serde_json::Value::String).collect(),
-                                )
-                            })
-                            .unwrap_or(serde_json::Value::Null),
-                    }
-                },
-                // For unknown/custom types, try to get as string
-                _ => row
-                    .try_get::<_, Option<String>>(i)
+                // Arrays (any element type or dimension), composite types
+                // and records
+                _ if Structured::accepts(column.type_()) => row
+                    .try_get::<_, Option<Structured>>(i)
                     .ok()
                     .flatten()
-                    .map(serde_json::Value::String)
+                    .map(|Structured(v)| v)
+                    .unwrap_or(serde_json::Value::Null),
+                // PostGIS types, decoded from EWKB so PostGIS functions
+                // aren't needed
+                "geometry" | "geography" => row
+                    .try_get::<_, Option<RawBytes>>(i)
+                    .ok()
+                    .flatten()
+                    .map(|RawBytes(v)| geometry_value(&v))
                     .unwrap_or(serde_json::Value::Null),
+                // For unknown/custom types, try to get as string, then as
+                // raw binary
+                _ => match row.try_get::<_, Option<String>>(i) {
+                    Ok(v) => v.map(serde_json::Value::String),
+                    Err(_) => row
+                        .try_get::<_, Option<RawBytes>>(i)
+                        .ok()
+                        .flatten()
+                        .map(|RawBytes(v)| blob_value(&v)),
+                }
+                .unwrap_or(serde_json::Value::Null),
             };
 
             values.push(value);
@@ -428,6 +626,64 @@ impl PostgresDriver {
 
         values
     }
+
+    /// Run statements in one transaction, committing only if `commit` is set
+    ///
+    /// Returns `None` if `after_statement` stopped the transaction.
+    async fn run_in_transaction(
+        &self,
+        statements: &[BoundStatement],
+        commit: bool,
+        mut after_statement: Option<StatementCallback>,
+    ) -> Result<Option<Vec<u64>>, DbError> {
+        let mut client = self.client().await?;
+
+        // The transaction rolls back automatically if it is dropped without
+        // being committed, so an early `?` return leaves nothing applied.
+        let tx = client
+            .transaction()
+            .await
+            .map_err(query_error_with("Failed to begin transaction"))?;
+
+        let mut affected = Vec::with_capacity(statements.len());
+        for statement in statements {
+            let rows = if statement.params.is_empty() {
+                tx.execute(statement.sql.as_str(), &[])
+                    .await
+                    .map_err(query_error)?
+            } else {
+                let mut prepared = tx.prepare(&statement.sql).await.map_err(query_error)?;
+                if let Some(rewritten) = Self::with_text_casts(&statement.sql, &prepared) {
+                    prepared = tx.prepare(&rewritten).await.map_err(query_error)?;
+                }
+                let values = Self::bind_params(&prepared, &statement.params)?;
+                let refs: Vec<&(dyn ToSql + Sync)> = values
+                    .iter()
+                    .map(|v| v.as_ref() as &(dyn ToSql + Sync))
+                    .collect();
+                tx.execute(&prepared, &refs).await.map_err(query_error)?
+            };
+            affected.push(rows);
+            if after_statement.as_mut().is_some_and(|f| !f(affected.len())) {
+                tx.rollback()
+                    .await
+                    .map_err(query_error_with("Failed to roll back transaction"))?;
+                return Ok(None);
+            }
+        }
+
+        if commit {
+            tx.commit()
+                .await
+                .map_err(query_error_with("Failed to commit transaction"))?;
+        } else {
+            tx.rollback()
+                .await
+                .map_err(query_error_with("Failed to roll back transaction"))?;
+        }
+
+        Ok(Some(affected))
+    }
 }
 
 #[async_trait]
@@ -442,44 +698,21 @@ impl DatabaseDriver for PostgresDriver {
             .parse()
             .map_err(|e| DbError::ConnectionError(format!("Failed to parse config: {}", e)))?;
 
-        let mgr_config = ManagerConfig {
-            recycling_method: RecyclingMethod::Fast,
-        };
-
-        // Build the connection manager, preserving the original TLS vs NoTls
-        // branching. The deadpool `Manager` is generic over the TLS connector,
-        // so each branch produces a differently-typed `Pool::builder` chain;
-        // both arms still yield a `deadpool_postgres::Pool`.
-        let pool = if opts.require_tls {
-            let connector = native_tls::TlsConnector::builder()
-                .build()
-                .map_err(|e| DbError::ConnectionError(format!("TLS init failed: {}", e)))?;
-            let tls = postgres_native_tls::MakeTlsConnector::new(connector);
-            let manager = Manager::from_config(pg_config, tls, mgr_config);
-            Pool::builder(manager)
-                .max_size(POOL_MAX_SIZE)
-                .build()
-                .map_err(|e| {
-                    DbError::ConnectionError(format!("Failed to build connection pool: {}", e))
-                })?
-        } else {
-            let manager = Manager::from_config(pg_config, NoTls, mgr_config);
-            Pool::builder(manager)
-                .max_size(POOL_MAX_SIZE)
-                .build()
-                .map_err(|e| {
-                    DbError::ConnectionError(format!("Failed to build connection pool: {}", e))
-                })?
-        };
+        let pool = Self::build_pool(&pg_config, opts.require_tls, POOL_MAX_SIZE)?;
 
         // Validate that we can actually establish a connection now, preserving
         // the original behaviour where `connect()` failed fast on bad creds.
         let _ = pool
             .get()
             .await
-            .map_err(|e| DbError::ConnectionError(format!("Failed to connect: {}", e)))?;
+            .map_err(|e| pool_error("Failed to connect", e))?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            numeric_as_number: false,
+            config: pg_config,
+            require_tls: opts.require_tls,
+        })
     }
 
     async fn test_connection(&self) -> Result<(), DbError> {
@@ -504,7 +737,7 @@ impl DatabaseDriver for PostgresDriver {
             client
                 .batch_execute(sql)
                 .await
-                .map_err(|e| DbError::QueryError(format!("Transaction execution failed: {}", e)))?;
+                .map_err(query_error_with("Transaction execution failed"))?;
 
             // Return empty result for batch execution (no way to get affected rows count for all statements)
             return Ok(QueryResult::empty());
@@ -517,10 +750,55 @@ impl DatabaseDriver for PostgresDriver {
         // - SELECT vs DML is decided from the statement metadata instead of
         //   the old retry-on-error pattern that re-executed failing SQL via
         //   client.execute (a failed query must never run twice).
-        let statement = client
-            .prepare(sql)
-            .await
-            .map_err(|e| DbError::QueryError(format!("{}", e)))?;
+        let statement = client.prepare(sql).await.map_err(query_error)?;
+
+        self.run_prepared(&client, &statement, &[]).await
+    }
+
+    async fn execute_query_with_params(
+        &self,
+        sql: &str,
+        params: &[serde_json::Value],
+    ) -> Result<QueryResult, DbError> {
+        let client = self.client().await?;
+
+        let mut statement = client.prepare(sql).await.map_err(query_error)?;
+        if let Some(rewritten) = Self::with_text_casts(sql, &statement) {
+            statement = client.prepare(&rewritten).await.map_err(query_error)?;
+        }
+
+        let values = Self::bind_params(&statement, params)?;
+        let refs: Vec<&(dyn ToSql + Sync)> = values
+            .iter()
+            .map(|v| v.as_ref() as &(dyn ToSql + Sync))
+            .collect();
+
+        self.run_prepared(&client, &statement, &refs).await
+    }
+
+    fn placeholder_style(&self) -> PlaceholderStyle {
+        PlaceholderStyle::Dollar
+    }
+
+    fn binary_literal(&self, bytes: &[u8]) -> String {
+        format!("decode('{}', 'hex')", hex::encode(bytes))
+    }
+
+    async fn stream_query(
+        &self,
+        sql: &str,
+        batch_size: usize,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64, DbError> {
+        let client = self.client().await?;
+
+        if count_statements(sql) > 1 {
+            return Err(DbError::InvalidInput(
+                "Only a single statement can be streamed".to_string(),
+            ));
+        }
+
+        let statement = client.prepare(sql).await.map_err(query_error)?;
 
         let columns: Vec<String> = statement
             .columns()
@@ -528,50 +806,100 @@ impl DatabaseDriver for PostgresDriver {
             .map(|col| col.name().to_string())
             .collect();
 
+        // Checked before executing, so a DML statement is never run here.
         if columns.is_empty() {
-            // No result columns: DML/DDL (INSERT/UPDATE/DELETE/CREATE/...).
-            // Execute the prepared handle to get the affected-row count.
-            let rows_affected = client
-                .execute(&statement, &[])
-                .await
-                .map_err(|e| DbError::QueryError(format!("{}", e)))?;
-
-            return Ok(QueryResult::with_affected(rows_affected));
+            return Err(DbError::InvalidInput(
+                "Query does not return any rows".to_string(),
+            ));
         }
+        sink.columns(&columns)?;
 
-        // Data-returning statement (SELECT, or DML with RETURNING).
-        //
-        // `query_raw` returns a `RowStream` instead of a fully materialized
-        // `Vec<Row>`, which lets us stop pulling rows at MAX_RESULT_ROWS — an
-        // unbounded `SELECT *` on a huge table would otherwise buffer every
-        // row in memory before any cap could apply (PERF-03). We fetch one
-        // extra row past the cap so the caller can detect truncation.
         let params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = Vec::new();
         let stream = client
             .query_raw(&statement, params)
             .await
-            .map_err(|e| DbError::QueryError(format!("{}", e)))?;
+            .map_err(query_error)?;
         futures_util::pin_mut!(stream);
 
-        let mut rows: Vec<tokio_postgres::Row> = Vec::new();
-        while let Some(row) = stream
-            .try_next()
-            .await
-            .map_err(|e| DbError::QueryError(format!("{}", e)))?
-        {
-            rows.push(row);
-            if rows.len() > MAX_RESULT_ROWS {
-                // Cap reached: stop fetching. Dropping the stream
-                // discards the remainder of the result set.
-                break;
+        let batch_size = batch_size.max(1);
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut total: u64 = 0;
+        while let Some(row) = stream.try_next().await.map_err(query_error)? {
+            batch.push(self.row_to_json_vec(&row));
+            if batch.len() >= batch_size {
+                let full = std::mem::replace(&mut batch, Vec::with_capacity(batch_size));
+                total += full.len() as u64;
+                // Dropping the stream discards the rest of the result set.
+                if !sink.rows(full)? {
+                    return Ok(total);
+                }
             }
         }
 
-        // Convert rows to JSON
-        let data: Vec<Vec<serde_json::Value>> =
-            rows.iter().map(|row| Self::row_to_json_vec(row)).collect();
+        if !batch.is_empty() {
+            total += batch.len() as u64;
+            sink.rows(batch)?;
+        }
 
-        Ok(QueryResult::with_data(columns, data))
+        Ok(total)
+    }
+
+    async fn execute_in_transaction(&self, statements: &[String]) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(&BoundStatement::unbound(statements), true, None)
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    async fn execute_in_transaction_with(
+        &self,
+        statements: &[String],
+        after_statement: StatementCallback,
+    ) -> Result<Option<Vec<u64>>, DbError> {
+        self.run_in_transaction(
+            &BoundStatement::unbound(statements),
+            true,
+            Some(after_statement),
+        )
+        .await
+    }
+
+    async fn execute_in_transaction_with_params(
+        &self,
+        statements: &[BoundStatement],
+    ) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(statements, true, None)
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    async fn execute_and_rollback(&self, statements: &[String]) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(&BoundStatement::unbound(statements), false, None)
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    async fn open_listener(
+        &self,
+        on_notification: NotificationCallback,
+    ) -> Result<PgListener, DbError> {
+        PgListener::connect(&self.config, self.require_tls, on_notification).await
+    }
+
+    async fn open_session(&self) -> Result<Option<Arc<dyn DatabaseDriver>>, DbError> {
+        let pool = Self::build_pool(&self.config, self.require_tls, 1)?;
+        // Connect now, so a failure shows up on the statement that opened
+        // the session
+        let _ = pool
+            .get()
+            .await
+            .map_err(|e| pool_error("Failed to open session", e))?;
+
+        Ok(Some(Arc::new(Self {
+            pool,
+            numeric_as_number: self.numeric_as_number,
+            config: self.config.clone(),
+            require_tls: self.require_tls,
+        })))
     }
 
     async fn get_databases(&self) -> Result<Vec<DatabaseInfo>, DbError> {
@@ -589,7 +917,7 @@ impl DatabaseDriver for PostgresDriver {
         let rows = client
             .query(query, &[])
             .await
-            .map_err(|e| DbError::QueryError(format!("Failed to fetch databases: {}", e)))?;
+            .map_err(query_error_with("Failed to fetch databases"))?;
 
         let databases: Vec<DatabaseInfo> = rows
             .iter()
@@ -627,7 +955,7 @@ impl DatabaseDriver for PostgresDriver {
         let rows = client
             .query(query, &[])
             .await
-            .map_err(|e| DbError::QueryError(format!("Failed to fetch schemas: {}", e)))?;
+            .map_err(query_error_with("Failed to fetch schemas"))?;
 
         let schemas: Vec<SchemaInfo> = rows
             .iter()
@@ -644,60 +972,42 @@ impl DatabaseDriver for PostgresDriver {
     }
 
     async fn get_tables(&self, schema: &str) -> Result<Vec<TableInfo>, DbError> {
-        // Single query that fetches tables, views, and materialized views along with
-        // row count estimates. Uses GREATEST(n_live_tup, reltuples, 0) to pick the
-        // best available estimate:
-        //   - pg_stat_user_tables.n_live_tup: updated by INSERT/UPDATE/DELETE operations
-        //   - pg_class.reltuples: updated by VACUUM/ANALYZE (can be -1 if never analyzed)
+        // Single pass over pg_class that fetches tables, views, and
+        // materialized views of the schema along with row count estimates.
+        // Uses GREATEST(live tuples, reltuples, 0) to pick the best available
+        // estimate:
+        //   - pg_stat_get_live_tuples(): updated by INSERT/UPDATE/DELETE operations
+        //     (the n_live_tup of pg_stat_user_tables)
+        //   - pg_class.reltuples: updated by VACUUM/ANALYZE (-1 if never analyzed)
         // Views and materialized views get NULL row counts.
         let query = r#"
             SELECT
-                t.schemaname AS schema,
-                t.tablename AS name,
-                'TABLE' AS table_type,
-                GREATEST(
-                    COALESCE(s.n_live_tup, 0),
-                    COALESCE(c.reltuples::bigint, 0),
-                    0
-                ) AS row_count
-            FROM pg_tables t
-            LEFT JOIN pg_stat_user_tables s
-                ON s.schemaname = t.schemaname AND s.relname = t.tablename
-            LEFT JOIN pg_class c
-                ON c.relname = t.tablename
-                AND c.relnamespace = (
-                    SELECT oid FROM pg_namespace WHERE nspname = t.schemaname
-                )
-            WHERE t.schemaname = $1
-
-            UNION ALL
-
-            SELECT
-                schemaname AS schema,
-                viewname AS name,
-                'VIEW' AS table_type,
-                NULL::bigint AS row_count
-            FROM pg_views
-            WHERE schemaname = $1
-
-            UNION ALL
-
-            SELECT
-                schemaname AS schema,
-                matviewname AS name,
-                'MATERIALIZED VIEW' AS table_type,
-                NULL::bigint AS row_count
-            FROM pg_matviews
-            WHERE schemaname = $1
-
-            ORDER BY name
+                n.nspname AS schema,
+                c.relname AS name,
+                CASE c.relkind
+                    WHEN 'v' THEN 'VIEW'
+                    WHEN 'm' THEN 'MATERIALIZED VIEW'
+                    ELSE 'TABLE'
+                END AS table_type,
+                CASE WHEN c.relkind IN ('r', 'p') THEN
+                    GREATEST(
+                        COALESCE(pg_stat_get_live_tuples(c.oid), 0),
+                        c.reltuples::bigint,
+                        0
+                    )
+                END AS row_count
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1
+              AND c.relkind IN ('r', 'p', 'v', 'm')
+            ORDER BY c.relname
         "#;
 
         let client = self.client().await?;
         let rows = client
             .query(query, &[&schema])
             .await
-            .map_err(|e| DbError::QueryError(format!("Failed to fetch tables: {}", e)))?;
+            .map_err(query_error_with("Failed to fetch tables"))?;
 
         let mut tables: Vec<TableInfo> = rows
             .iter()
@@ -729,16 +1039,15 @@ impl DatabaseDriver for PostgresDriver {
         // Collapse the per-table COUNT(*) into a single round-trip: one
         // UNION ALL of `SELECT 'name', COUNT(*)` instead of N sequential
         // query_one() calls. `'...'` escapes the name as a string literal;
-        // quote_ident() handles the FROM identifier.
+        // quote_qualified() handles the FROM identifier.
         if !zero_count_tables.is_empty() {
             let union = zero_count_tables
                 .iter()
                 .map(|name| {
                     format!(
-                        "SELECT '{}' AS n, COUNT(*) AS c FROM {}.{}",
+                        "SELECT '{}' AS n, COUNT(*) AS c FROM {}",
                         name.replace('\'', "''"),
-                        quote_ident(schema),
-                        quote_ident(name)
+                        quote_qualified(Some(schema), name, Quoting::Standard)
                     )
                 })
                 .collect::<Vec<_>>()
@@ -766,7 +1075,13 @@ impl DatabaseDriver for PostgresDriver {
                 c.data_type,
                 c.is_nullable = 'YES' as nullable,
                 c.column_default,
-                CASE WHEN pk.column_name IS NOT NULL THEN true ELSE false END as is_primary_key
+                CASE WHEN pk.column_name IS NOT NULL THEN true ELSE false END as is_primary_key,
+                col_description(
+                    format('%I.%I', c.table_schema, c.table_name)::regclass,
+                    c.ordinal_position::int
+                ) as comment,
+                c.is_generated = 'ALWAYS' as is_generated,
+                c.generation_expression::text
             FROM information_schema.columns c
             LEFT JOIN (
                 SELECT ku.column_name
@@ -787,7 +1102,7 @@ impl DatabaseDriver for PostgresDriver {
         let column_rows = client
             .query(column_query, &[&schema, &table])
             .await
-            .map_err(|e| DbError::QueryError(format!("Failed to fetch columns: {}", e)))?;
+            .map_err(query_error_with("Failed to fetch columns"))?;
 
         let columns: Vec<ColumnInfo> = column_rows
             .iter()
@@ -797,6 +1112,9 @@ impl DatabaseDriver for PostgresDriver {
                 let nullable: bool = row.get(2);
                 let default_value: Option<String> = row.get(3);
                 let is_primary_key: bool = row.get(4);
+                let comment: Option<String> = row.get(5);
+                let is_generated: Option<bool> = row.get(6);
+                let generation_expression: Option<String> = row.get(7);
 
                 // Check if column is auto-increment (serial types or nextval in default)
                 // Note: SERIAL types appear as "integer" or "bigint" with a nextval() default
@@ -817,10 +1135,24 @@ impl DatabaseDriver for PostgresDriver {
                     default_value,
                     is_primary_key,
                     is_auto_increment,
+                    comment,
+                    is_generated: is_generated.unwrap_or(false),
+                    generation_expression,
                 }
             })
             .collect();
 
+        // Get the table comment; to_regclass yields NULL rather than an
+        // error when the table does not exist
+        let comment: Option<String> = client
+            .query_one(
+                "SELECT obj_description(to_regclass(format('%I.%I', $1::text, $2::text)), 'pg_class')",
+                &[&schema, &table],
+            )
+            .await
+            .map_err(query_error_with("Failed to fetch table comment"))?
+            .get(0);
+
         // Get index information
         let index_query = r#"
             SELECT
@@ -843,7 +1175,7 @@ impl DatabaseDriver for PostgresDriver {
         let index_rows = client
             .query(index_query, &[&schema, &table])
             .await
-            .map_err(|e| DbError::QueryError(format!("Failed to fetch indexes: {}", e)))?;
+            .map_err(query_error_with("Failed to fetch indexes"))?;
 
         let indexes: Vec<IndexInfo> = index_rows
             .iter()
@@ -862,6 +1194,55 @@ impl DatabaseDriver for PostgresDriver {
             })
             .collect();
 
+        // Get primary key, unique and check constraints. Columns follow the
+        // constraint's key order; conindid points at the enforcing index.
+        let constraint_query = r#"
+            SELECT
+                c.conname::text as name,
+                c.contype::text as kind,
+                ARRAY(
+                    SELECT a.attname::text
+                    FROM unnest(c.conkey) WITH ORDINALITY AS k(attnum, ord)
+                    JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum
+                    ORDER BY k.ord
+                ) as columns,
+                CASE WHEN c.contype = 'c' THEN pg_get_constraintdef(c.oid) END as definition,
+                i.relname::text as index_name
+            FROM pg_constraint c
+            JOIN pg_class t ON t.oid = c.conrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            LEFT JOIN pg_class i ON i.oid = c.conindid
+            WHERE n.nspname = $1
+                AND t.relname = $2
+                AND c.contype IN ('p', 'u', 'c')
+            ORDER BY c.contype, c.conname
+        "#;
+
+        let constraint_rows = client
+            .query(constraint_query, &[&schema, &table])
+            .await
+            .map_err(query_error_with("Failed to fetch constraints"))?;
+
+        let constraints: Vec<ConstraintInfo> = constraint_rows
+            .iter()
+            .filter_map(|row| {
+                let kind = match row.get::<_, String>(1).as_str() {
+                    "p" => ConstraintKind::PrimaryKey,
+                    "u" => ConstraintKind::Unique,
+                    "c" => ConstraintKind::Check,
+                    _ => return None,
+                };
+
+                Some(ConstraintInfo {
+                    name: row.get(0),
+                    kind,
+                    columns: row.get(2),
+                    definition: row.get(3),
+                    index_name: row.get(4),
+                })
+            })
+            .collect();
+
         // Create TableInfo
         let table_info = TableInfo {
             name: table.to_string(),
@@ -874,6 +1255,8 @@ impl DatabaseDriver for PostgresDriver {
             table: table_info,
             columns,
             indexes,
+            constraints,
+            comment,
         })
     }
 
@@ -908,7 +1291,7 @@ impl DatabaseDriver for PostgresDriver {
         let rows = client
             .query(query, &[&schema])
             .await
-            .map_err(|e| DbError::QueryError(format!("Failed to fetch foreign keys: {}", e)))?;
+            .map_err(query_error_with("Failed to fetch foreign keys"))?;
 
         // Group foreign keys by constraint name (for composite foreign keys)
         use std::collections::HashMap;
@@ -981,6 +1364,9 @@ mod tests {
             database: Some("testdb".to_string()),
             timeout: Some(30),
             require_tls: false,
+            application_name: Some("DB-Hive [3f2a9c1e]".to_string()),
+            default_schema: Some("sales ops".to_string()),
+            sqlite: Default::default(),
         };
 
         let conn_str = PostgresDriver::build_connection_string(&opts);
@@ -991,6 +1377,8 @@ mod tests {
         assert!(conn_str.contains("password='secret'"));
         assert!(conn_str.contains("dbname=testdb"));
         assert!(conn_str.contains("connect_timeout=30"));
+        assert!(conn_str.contains("application_name='DB-Hive [3f2a9c1e]'"));
+        assert!(conn_str.contains(r#"options='-c search_path="sales\\ ops",public'"#));
     }
 
     #[test]
@@ -1003,6 +1391,9 @@ mod tests {
             database: Some("testdb".to_string()),
             timeout: None,
             require_tls: false,
+            application_name: None,
+            default_schema: None,
+            sqlite: Default::default(),
         };
 
         let conn_str = PostgresDriver::build_connection_string(&opts);
@@ -1023,6 +1414,9 @@ mod tests {
             database: None,
             timeout: None,
             require_tls: false,
+            application_name: None,
+            default_schema: None,
+            sqlite: Default::default(),
         };
 
         let conn_str = PostgresDriver::build_connection_string(&opts);
@@ -1088,4 +1482,13 @@ mod tests {
         assert_eq!(count_statements("SELECT 1 /* a /* b; */ c; */;"), 1);
         assert_eq!(count_statements("/* only a comment; */"), 0);
     }
+
+    #[test]
+    fn test_cast_params_from_text() {
+        let sql = "SELECT $1, '$2' WHERE price > $2 AND id = $1";
+        assert_eq!(
+            PostgresDriver::cast_params_from_text(sql, &[Type::INT4, Type::NUMERIC], &[1]),
+            "SELECT $1, '$2' WHERE price > $2::text::\"pg_catalog\".\"numeric\" AND id = $1"
+        );
+    }
 }
@@ -196,6 +196,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: true,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
                 ColumnInfo {
                     name: "field".to_string(),
@@ -204,6 +207,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: false,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
                 ColumnInfo {
                     name: "value".to_string(),
@@ -212,6 +218,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: false,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
             ],
             "strings" => vec![
@@ -222,6 +231,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: true,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
                 ColumnInfo {
                     name: "value".to_string(),
@@ -230,6 +242,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: false,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
             ],
             "lists" => vec![
@@ -240,6 +255,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: true,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
                 ColumnInfo {
                     name: "index".to_string(),
@@ -248,6 +266,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: false,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
                 ColumnInfo {
                     name: "value".to_string(),
@@ -256,6 +277,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: false,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
             ],
             "sets" => vec![
@@ -266,6 +290,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: true,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
                 ColumnInfo {
                     name: "member".to_string(),
@@ -274,6 +301,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: false,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
             ],
             // "zsets" | "sorted_sets" | anything else
@@ -285,6 +315,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: true,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
                 ColumnInfo {
                     name: "score".to_string(),
@@ -293,6 +326,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: false,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
                 ColumnInfo {
                     name: "member".to_string(),
@@ -301,6 +337,9 @@ impl DatabaseDriver for RedisDriver {
                     default_value: None,
                     is_primary_key: false,
                     is_auto_increment: false,
+                    comment: None,
+                    is_generated: false,
+                    generation_expression: None,
                 },
             ],
         };
@@ -309,6 +348,8 @@ impl DatabaseDriver for RedisDriver {
             table: table_info,
             columns,
             indexes: vec![],
+            constraints: Vec::new(),
+            comment: None,
         })
     }
 
@@ -4,13 +4,81 @@
 //! using rusqlite for database operations.
 
 use async_trait::async_trait;
-use rusqlite::{Connection, OpenFlags, Row};
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, Row, Transaction, TransactionBehavior};
 use std::sync::{Arc, Mutex as StdMutex};
 
-use super::{ConnectionOptions, DatabaseDriver, QueryResult, MAX_RESULT_ROWS};
+use super::blob::{blob_bytes, blob_value};
+use super::{
+    BoundStatement, ConnectionOptions, DatabaseDriver, QueryResult, StatementCallback,
+    MAX_RESULT_ROWS,
+};
 use crate::models::{
-    ColumnInfo, DatabaseInfo, DbError, ForeignKeyInfo, IndexInfo, SchemaInfo, TableInfo, TableSchema,
+    ColumnInfo, DatabaseInfo, DbError, ErrorCode, ForeignKeyInfo, IndexInfo, SchemaInfo, TableInfo,
+    TableSchema,
 };
+use crate::sql::quote::quote_identifier;
+use crate::sql::tokenizer::Quoting;
+
+/// `DbError` for a failed statement, with `context` in front of the
+/// message; SQLite failures keep their extended result code
+fn query_error_with(context: &str) -> impl Fn(rusqlite::Error) -> DbError + '_ {
+    move |e| {
+        let message = format!("{}: {}", context, e);
+        match e.sqlite_error() {
+            Some(error) => DbError::DatabaseError {
+                message,
+                code: ErrorCode::Sqlite(error.extended_code),
+                details: None,
+            },
+            None => DbError::QueryError(message),
+        }
+    }
+}
+
+/// JSON value of a SQLite value, going by its storage class
+///
+/// - REAL infinities, which JSON can't hold, become `"Infinity"` and
+///   `"-Infinity"` (SQLite stores NaN as NULL).
+/// - TEXT that isn't valid UTF-8 is returned as a blob rather than dropped.
+/// - BLOBs are tagged base64 objects (see `drivers::blob`).
+fn sqlite_value_to_json(value: ValueRef<'_>) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(n) => serde_json::Value::from(n),
+        ValueRef::Real(f) => match serde_json::Number::from_f64(f) {
+            Some(n) => serde_json::Value::Number(n),
+            None if f > 0.0 => serde_json::Value::from("Infinity"),
+            None if f < 0.0 => serde_json::Value::from("-Infinity"),
+            None => serde_json::Value::Null,
+        },
+        ValueRef::Text(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) => serde_json::Value::from(text),
+            Err(_) => blob_value(bytes),
+        },
+        ValueRef::Blob(bytes) => blob_value(bytes),
+    }
+}
+
+/// Type affinity of a column declared as `declared`
+///
+/// Follows SQLite's rules in order, so any type name maps to one of
+/// INTEGER, TEXT, BLOB, REAL or NUMERIC: `VARCHAR(255)` is TEXT, `DATETIME`
+/// and `BOOLEAN` are NUMERIC, and a column without a type is BLOB.
+pub(crate) fn type_affinity(declared: &str) -> &'static str {
+    let upper = declared.to_ascii_uppercase();
+    if upper.contains("INT") {
+        "INTEGER"
+    } else if ["CHAR", "CLOB", "TEXT"].iter().any(|t| upper.contains(t)) {
+        "TEXT"
+    } else if upper.contains("BLOB") || upper.trim().is_empty() {
+        "BLOB"
+    } else if ["REAL", "FLOA", "DOUB"].iter().any(|t| upper.contains(t)) {
+        "REAL"
+    } else {
+        "NUMERIC"
+    }
+}
 
 /// SQLite database driver
 ///
@@ -34,28 +102,89 @@ impl SqliteDriver {
         let mut values = Vec::new();
 
         for i in 0..column_count {
-            let value = match row.get_ref(i)? {
-                rusqlite::types::ValueRef::Null => serde_json::Value::Null,
-                rusqlite::types::ValueRef::Integer(n) => serde_json::Value::Number(n.into()),
-                rusqlite::types::ValueRef::Real(f) => {
-                    serde_json::Number::from_f64(f)
-                        .map(serde_json::Value::Number)
-                        .unwrap_or(serde_json::Value::Null)
-                }
-                rusqlite::types::ValueRef::Text(s) => {
-                    let text = std::str::from_utf8(s).unwrap_or("");
-                    serde_json::Value::String(text.to_string())
-                }
-                rusqlite::types::ValueRef::Blob(b) => {
-                    // Convert blob to base64 string
-                    serde_json::Value::String(format!("<BLOB {} bytes>", b.len()))
+            values.push(sqlite_value_to_json(row.get_ref(i)?));
+        }
+
+        Ok(values)
+    }
+
+    /// Run a single statement with bound parameters on `conn`
+    ///
+    /// Statements that return columns are read up to `MAX_RESULT_ROWS + 1`
+    /// rows; anything else reports its affected-row count.
+    fn run_statement(
+        conn: &Connection,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<QueryResult, DbError> {
+        // Try to prepare the statement to determine if it returns rows
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(query_error_with("Failed to prepare statement"))?;
+
+        let column_count = stmt.column_count();
+
+        if column_count > 0 {
+            // This is a SELECT query
+            let column_names: Vec<String> = stmt
+                .column_names()
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+
+            let mut rows_data = Vec::new();
+
+            let mut query_rows = stmt
+                .query(rusqlite::params_from_iter(params))
+                .map_err(query_error_with("Failed to execute query"))?;
+
+            while let Some(row) = query_rows
+                .next()
+                .map_err(query_error_with("Failed to fetch row"))?
+            {
+                let row_values = Self::row_to_json_vec(row, column_count)
+                    .map_err(|e| DbError::QueryError(format!("Failed to convert row: {}", e)))?;
+                rows_data.push(row_values);
+
+                // Enforce the row cap inside the step loop so an unbounded
+                // SELECT never materializes the full result set (PERF-03).
+                // One extra row past the cap lets the caller flag truncation;
+                // dropping `query_rows` resets the statement.
+                if rows_data.len() > MAX_RESULT_ROWS {
+                    break;
                 }
-            };
+            }
 
-            values.push(value);
+            Ok(QueryResult::with_data(column_names, rows_data))
+        } else {
+            // This is an INSERT/UPDATE/DELETE/CREATE/etc.
+            drop(stmt); // Drop statement before executing
+            let rows_affected = conn
+                .execute(sql, rusqlite::params_from_iter(params))
+                .map_err(query_error_with("Failed to execute statement"))?;
+
+            Ok(QueryResult::with_affected(rows_affected as u64))
         }
+    }
 
-        Ok(values)
+    /// Convert a JSON parameter value to a SQLite value for binding
+    ///
+    /// Tagged binary values are bound as BLOBs.
+    fn json_to_sqlite_value(value: &serde_json::Value) -> Value {
+        if let Some(bytes) = blob_bytes(value) {
+            return Value::Blob(bytes);
+        }
+
+        match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Integer(i64::from(*b)),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => Value::Integer(i),
+                None => Value::Real(n.as_f64().unwrap_or_default()),
+            },
+            serde_json::Value::String(s) => Value::Text(s.clone()),
+            other => Value::Text(other.to_string()),
+        }
     }
 
     /// Run blocking rusqlite work on the Tokio blocking thread pool (PERF-08).
@@ -81,6 +210,77 @@ impl SqliteDriver {
         .await
         .map_err(|e| DbError::InternalError(format!("Blocking task failed: {}", e)))?
     }
+
+    /// Run statements in one transaction, committing only if `commit` is set
+    ///
+    /// Returns `None` if `after_statement` stopped the transaction.
+    async fn run_in_transaction(
+        &self,
+        statements: Vec<BoundStatement>,
+        commit: bool,
+        mut after_statement: Option<StatementCallback>,
+    ) -> Result<Option<Vec<u64>>, DbError> {
+        self.run_blocking(move |conn| {
+            // `new_unchecked` works through the shared `&Connection`;
+            // dropping it without `commit` rolls everything back. IMMEDIATE
+            // takes the write lock up front, so another process writing to
+            // the file makes BEGIN wait or fail instead of a statement
+            // halfway through the batch.
+            let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)
+                .map_err(query_error_with("Failed to begin transaction"))?;
+
+            let mut affected = Vec::with_capacity(statements.len());
+            for statement in &statements {
+                let values = statement.params.iter().map(Self::json_to_sqlite_value);
+                let rows = tx
+                    .execute(&statement.sql, rusqlite::params_from_iter(values))
+                    .map_err(query_error_with("Failed to execute statement"))?;
+                affected.push(rows as u64);
+                if after_statement.as_mut().is_some_and(|f| !f(affected.len())) {
+                    tx.rollback().map_err(|e| {
+                        DbError::QueryError(format!("Failed to roll back transaction: {}", e))
+                    })?;
+                    return Ok(None);
+                }
+            }
+
+            if commit {
+                tx.commit()
+                    .map_err(query_error_with("Failed to commit transaction"))?;
+            } else {
+                tx.rollback().map_err(|e| {
+                    DbError::QueryError(format!("Failed to roll back transaction: {}", e))
+                })?;
+            }
+
+            Ok(Some(affected))
+        })
+        .await
+    }
+}
+
+/// Database a `schema` argument refers to: an attached database's alias, or
+/// `main` for anything else (the frontend may pass "" or a Postgres-style
+/// "public" for SQLite)
+fn database_name(conn: &Connection, schema: &str) -> Result<String, DbError> {
+    let attached = conn
+        .query_row(
+            "SELECT 1 FROM pragma_database_list WHERE name = ?1",
+            [schema],
+            |_| Ok(()),
+        )
+        .optional()
+        .map_err(|e| DbError::QueryError(format!("Failed to list databases: {}", e)))?
+        .is_some();
+    Ok(if attached { schema } else { "main" }.to_string())
+}
+
+/// `database_name`, quoted for `<db>.sqlite_master` and `PRAGMA <db>.x`
+fn database_ident(conn: &Connection, schema: &str) -> Result<String, DbError> {
+    Ok(format!(
+        "\"{}\"",
+        database_name(conn, schema)?.replace('"', "\"\"")
+    ))
 }
 
 #[async_trait]
@@ -101,9 +301,26 @@ impl DatabaseDriver for SqliteDriver {
         )
         .map_err(|e| DbError::ConnectionError(format!("Failed to open SQLite database: {}", e)))?;
 
-        // Enable foreign keys (disabled by default in SQLite)
-        conn.execute("PRAGMA foreign_keys = ON", [])
-            .map_err(|e| DbError::ConnectionError(format!("Failed to enable foreign keys: {}", e)))?;
+        let pragma_error = |e: rusqlite::Error| {
+            DbError::ConnectionError(format!("Failed to configure SQLite: {}", e))
+        };
+        let sqlite = &opts.sqlite;
+
+        // Wait for other connections' locks instead of failing at once
+        conn.pragma_update(None, "busy_timeout", sqlite.busy_timeout_ms)
+            .map_err(pragma_error)?;
+        conn.pragma_update(None, "foreign_keys", sqlite.foreign_keys)
+            .map_err(pragma_error)?;
+        if sqlite.wal {
+            // Returns the mode in effect, which stays as it was where WAL
+            // isn't possible (in-memory databases)
+            let mode: String = conn
+                .pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))
+                .map_err(pragma_error)?;
+            if !mode.eq_ignore_ascii_case("wal") {
+                eprintln!("SQLite kept journal mode {} for {}", mode, db_path);
+            }
+        }
 
         Ok(Self {
             conn: Arc::new(StdMutex::new(conn)),
@@ -120,57 +337,53 @@ impl DatabaseDriver for SqliteDriver {
 
     async fn execute_query(&self, sql: &str) -> Result<QueryResult, DbError> {
         let sql = sql.to_string();
-        self.run_blocking(move |conn| {
-            // Try to prepare the statement to determine if it returns rows
-            let mut stmt = conn
-                .prepare(&sql)
-                .map_err(|e| DbError::QueryError(format!("Failed to prepare statement: {}", e)))?;
+        self.run_blocking(move |conn| Self::run_statement(conn, &sql, &[]))
+            .await
+    }
 
-            let column_count = stmt.column_count();
+    async fn execute_query_with_params(
+        &self,
+        sql: &str,
+        params: &[serde_json::Value],
+    ) -> Result<QueryResult, DbError> {
+        let sql = sql.to_string();
+        let values: Vec<Value> = params.iter().map(Self::json_to_sqlite_value).collect();
+        self.run_blocking(move |conn| Self::run_statement(conn, &sql, &values))
+            .await
+    }
 
-            if column_count > 0 {
-                // This is a SELECT query
-                let column_names: Vec<String> = stmt
-                    .column_names()
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect();
+    async fn execute_in_transaction(&self, statements: &[String]) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(BoundStatement::unbound(statements), true, None)
+            .await
+            .map(Option::unwrap_or_default)
+    }
 
-                let mut rows_data = Vec::new();
-
-                let mut query_rows = stmt
-                    .query([])
-                    .map_err(|e| DbError::QueryError(format!("Failed to execute query: {}", e)))?;
-
-                while let Some(row) = query_rows
-                    .next()
-                    .map_err(|e| DbError::QueryError(format!("Failed to fetch row: {}", e)))?
-                {
-                    let row_values = Self::row_to_json_vec(row, column_count)
-                        .map_err(|e| DbError::QueryError(format!("Failed to convert row: {}", e)))?;
-                    rows_data.push(row_values);
-
-                    // Enforce the row cap inside the step loop so an unbounded
-                    // SELECT never materializes the full result set (PERF-03).
-                    // One extra row past the cap lets the caller flag truncation;
-                    // dropping `query_rows` resets the statement.
-                    if rows_data.len() > MAX_RESULT_ROWS {
-                        break;
-                    }
-                }
+    async fn execute_in_transaction_with(
+        &self,
+        statements: &[String],
+        after_statement: StatementCallback,
+    ) -> Result<Option<Vec<u64>>, DbError> {
+        self.run_in_transaction(
+            BoundStatement::unbound(statements),
+            true,
+            Some(after_statement),
+        )
+        .await
+    }
 
-                Ok(QueryResult::with_data(column_names, rows_data))
-            } else {
-                // This is an INSERT/UPDATE/DELETE/CREATE/etc.
-                drop(stmt); // Drop statement before executing
-                let rows_affected = conn
-                    .execute(&sql, [])
-                    .map_err(|e| DbError::QueryError(format!("Failed to execute statement: {}", e)))?;
+    async fn execute_in_transaction_with_params(
+        &self,
+        statements: &[BoundStatement],
+    ) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(statements.to_vec(), true, None)
+            .await
+            .map(Option::unwrap_or_default)
+    }
 
-                Ok(QueryResult::with_affected(rows_affected as u64))
-            }
-        })
-        .await
+    async fn execute_and_rollback(&self, statements: &[String]) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(BoundStatement::unbound(statements), false, None)
+            .await
+            .map(Option::unwrap_or_default)
     }
 
     async fn get_databases(&self) -> Result<Vec<DatabaseInfo>, DbError> {
@@ -190,34 +403,64 @@ impl DatabaseDriver for SqliteDriver {
     }
 
     async fn get_schemas(&self, _database: &str) -> Result<Vec<SchemaInfo>, DbError> {
-        // SQLite has a simple schema model - typically just "main"
-        // Attached databases would show up here too, but for now we just return main
-        Ok(vec![SchemaInfo {
-            name: "main".to_string(),
-            database: std::path::Path::new(&self.db_path)
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("database.db")
-                .to_string(),
-        }])
+        // "main" plus one schema per attached database; "temp" only holds the
+        // connection's temporary tables and is left out
+        let main_file = self.db_path.clone();
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare("PRAGMA database_list")
+                .map_err(|e| DbError::QueryError(format!("Failed to list databases: {}", e)))?;
+            let databases = stmt
+                .query_map([], |row| {
+                    Ok((row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+                })
+                .map_err(|e| DbError::QueryError(format!("Failed to list databases: {}", e)))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| DbError::QueryError(format!("Failed to read database: {}", e)))?;
+
+            Ok(databases
+                .into_iter()
+                .filter(|(name, _)| name != "temp")
+                .map(|(name, file)| {
+                    let file = if name == "main" {
+                        main_file.as_str()
+                    } else {
+                        file.as_str()
+                    };
+                    SchemaInfo {
+                        database: std::path::Path::new(file)
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or("database.db")
+                            .to_string(),
+                        name,
+                    }
+                })
+                .collect())
+        })
+        .await
     }
 
     async fn get_tables(&self, schema: &str) -> Result<Vec<TableInfo>, DbError> {
         let schema = schema.to_string();
         self.run_blocking(move |conn| {
+            let db = database_ident(conn, &schema)?;
             // Get tables from sqlite_master
-            let query = r#"
+            let query = format!(
+                r#"
                 SELECT
                     name,
                     type
-                FROM sqlite_master
+                FROM {}.sqlite_master
                 WHERE type IN ('table', 'view')
                     AND name NOT LIKE 'sqlite_%'
                 ORDER BY name
-            "#;
+            "#,
+                db
+            );
 
             let mut stmt = conn
-                .prepare(query)
+                .prepare(&query)
                 .map_err(|e| DbError::QueryError(format!("Failed to fetch tables: {}", e)))?;
 
             let table_iter = stmt
@@ -251,7 +494,10 @@ impl DatabaseDriver for SqliteDriver {
                     .map(|name| {
                         let ident = name.replace('"', "\"\"");
                         let lit = name.replace('\'', "''");
-                        format!("SELECT '{}' AS n, (SELECT COUNT(*) FROM \"{}\") AS c", lit, ident)
+                        format!(
+                            "SELECT '{}' AS n, (SELECT COUNT(*) FROM {}.\"{}\") AS c",
+                            lit, db, ident
+                        )
                     })
                     .collect::<Vec<_>>()
                     .join(" UNION ALL ");
@@ -293,9 +539,14 @@ impl DatabaseDriver for SqliteDriver {
         let schema = schema.to_string();
         let table = table.to_string();
         self.run_blocking(move |conn| {
+            let db = database_ident(conn, &schema)?;
             // Get column information using PRAGMA table_info
             let mut stmt = conn
-                .prepare(&format!("PRAGMA table_info(\"{}\")", table))
+                .prepare(&format!(
+                    "PRAGMA {}.table_info({})",
+                    db,
+                    quote_identifier(&table, Quoting::Standard)
+                ))
                 .map_err(|e| DbError::QueryError(format!("Failed to get table info: {}", e)))?;
 
             let column_iter = stmt
@@ -306,10 +557,12 @@ impl DatabaseDriver for SqliteDriver {
                     let default_value: Option<String> = row.get(4)?; // dflt_value
                     let is_primary_key: i32 = row.get(5)?; // pk
 
-                    // SQLite auto-increment: INTEGER PRIMARY KEY or has AUTOINCREMENT in type
-                    let is_auto_increment = (is_primary_key > 0
-                        && data_type.to_uppercase() == "INTEGER")
-                        || data_type.to_uppercase().contains("AUTOINCREMENT");
+                    // A column declared without a type has BLOB affinity
+                    let data_type = if data_type.trim().is_empty() {
+                        type_affinity(&data_type).to_string()
+                    } else {
+                        data_type
+                    };
 
                     Ok(ColumnInfo {
                         name,
@@ -317,7 +570,11 @@ impl DatabaseDriver for SqliteDriver {
                         nullable: not_null == 0,
                         default_value,
                         is_primary_key: is_primary_key > 0,
-                        is_auto_increment,
+                        // Set below, once the indexes are known
+                        is_auto_increment: false,
+                        comment: None,
+                        is_generated: false,
+                        generation_expression: None,
                     })
                 })
                 .map_err(|e| DbError::QueryError(format!("Failed to query columns: {}", e)))?;
@@ -331,7 +588,11 @@ impl DatabaseDriver for SqliteDriver {
 
             // Get index information using PRAGMA index_list
             let mut index_stmt = conn
-                .prepare(&format!("PRAGMA index_list(\"{}\")", table))
+                .prepare(&format!(
+                    "PRAGMA {}.index_list({})",
+                    db,
+                    quote_identifier(&table, Quoting::Standard)
+                ))
                 .map_err(|e| DbError::QueryError(format!("Failed to get index list: {}", e)))?;
 
             let index_list_iter = index_stmt
@@ -351,7 +612,11 @@ impl DatabaseDriver for SqliteDriver {
 
                 // Get columns for this index using PRAGMA index_info
                 let mut col_stmt = conn
-                    .prepare(&format!("PRAGMA index_info(\"{}\")", index_name))
+                    .prepare(&format!(
+                        "PRAGMA {}.index_info({})",
+                        db,
+                        quote_identifier(&index_name, Quoting::Standard)
+                    ))
                     .map_err(|e| DbError::QueryError(format!("Failed to get index info: {}", e)))?;
 
                 let index_columns: Result<Vec<String>, rusqlite::Error> = col_stmt
@@ -373,11 +638,23 @@ impl DatabaseDriver for SqliteDriver {
                 });
             }
 
+            // A lone INTEGER PRIMARY KEY column aliases the rowid and is
+            // filled in automatically (AUTOINCREMENT only changes how). In a
+            // WITHOUT ROWID table it doesn't, and the key has its own index.
+            let has_pk_index = indexes.iter().any(|i| i.is_primary);
+            if !has_pk_index && columns.iter().filter(|c| c.is_primary_key).count() == 1 {
+                for column in columns.iter_mut().filter(|c| c.is_primary_key) {
+                    column.is_auto_increment = column.data_type.eq_ignore_ascii_case("INTEGER");
+                }
+            }
+
             // Get row count
             let row_count = conn
-                .query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table), [], |row| {
-                    row.get::<_, i64>(0)
-                })
+                .query_row(
+                    &format!("SELECT COUNT(*) FROM {}.\"{}\"", db, table),
+                    [],
+                    |row| row.get::<_, i64>(0),
+                )
                 .ok()
                 .map(|c| c as u64);
 
@@ -392,6 +669,8 @@ impl DatabaseDriver for SqliteDriver {
                 table: table_info,
                 columns,
                 indexes,
+                constraints: Vec::new(),
+                comment: None,
             })
         })
         .await
@@ -400,6 +679,7 @@ impl DatabaseDriver for SqliteDriver {
     async fn get_foreign_keys(&self, schema: &str) -> Result<Vec<ForeignKeyInfo>, DbError> {
         let schema = schema.to_string();
         self.run_blocking(move |conn| {
+            let db = database_name(conn, &schema)?;
             // This used to be N+1: one `SELECT name FROM sqlite_master` followed
             // by a `PRAGMA foreign_key_list("<table>")` prepared per table. The
             // `pragma_foreign_key_list` table-valued function (SQLite >= 3.16,
@@ -407,18 +687,22 @@ impl DatabaseDriver for SqliteDriver {
             // sqlite_master and pull every FK for the whole schema in one query.
             // Rows are ordered so composite-key columns arrive contiguously and
             // in `seq` order.
-            let query = r#"
+            let query = format!(
+                r#"
                 SELECT m.name AS tbl, f.id AS id, f.seq AS seq,
                        f."table" AS ref_table, f."from" AS from_col,
                        f."to" AS to_col, f.on_update AS on_update,
                        f.on_delete AS on_delete
-                FROM sqlite_master m
-                JOIN pragma_foreign_key_list(m.name) f
+                FROM "{}".sqlite_master m
+                JOIN pragma_foreign_key_list(m.name, '{}') f
                 WHERE m.type = 'table'
                 ORDER BY m.name, f.id, f.seq
-            "#;
+            "#,
+                db.replace('"', "\"\""),
+                db.replace('\'', "''")
+            );
 
-            let mut stmt = conn.prepare(query).map_err(|e| {
+            let mut stmt = conn.prepare(&query).map_err(|e| {
                 DbError::QueryError(format!("Failed to prepare foreign key query: {}", e))
             })?;
 
@@ -463,7 +747,7 @@ impl DatabaseDriver for SqliteDriver {
                         schema: schema.to_string(),
                         columns: vec![from_col],
                         referenced_table: ref_table,
-                        referenced_schema: schema.to_string(), // SQLite has no schemas like PostgreSQL
+                        referenced_schema: schema.to_string(), // can't reference another attached database
                         referenced_columns: vec![to_col],
                         on_delete: Some(on_delete),
                         on_update: Some(on_update),
@@ -478,8 +762,27 @@ impl DatabaseDriver for SqliteDriver {
     }
 
     async fn close(&self) -> Result<(), DbError> {
-        // Connection will be automatically closed when dropped
-        Ok(())
+        // The connection closes when the driver is dropped, which can be after
+        // a command still using it finishes; detach attached databases now so
+        // their files are released on disconnect
+        self.run_blocking(|conn| {
+            let attached = conn
+                .prepare("SELECT name FROM pragma_database_list WHERE seq > 1")
+                .and_then(|mut stmt| {
+                    stmt.query_map([], |row| row.get::<_, String>(0))?
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .map_err(|e| DbError::QueryError(format!("Failed to list databases: {}", e)))?;
+            for name in attached {
+                conn.execute(
+                    &format!("DETACH DATABASE \"{}\"", name.replace('"', "\"\"")),
+                    [],
+                )
+                .map_err(|e| DbError::QueryError(format!("Failed to detach {}: {}", name, e)))?;
+            }
+            Ok(())
+        })
+        .await
     }
 }
 
@@ -501,6 +804,9 @@ mod tests {
             database: Some(db_path.to_str().unwrap().to_string()),
             timeout: None,
             require_tls: false,
+            application_name: None,
+            default_schema: None,
+            sqlite: Default::default(),
         };
 
         let driver = SqliteDriver::connect(opts).await.unwrap();
@@ -535,6 +841,51 @@ mod tests {
         std::fs::remove_file(db_path).ok();
     }
 
+    #[tokio::test]
+    async fn test_sqlite_connect_pragmas() {
+        let db_path = std::env::temp_dir().join("test_pragmas.sqlite");
+
+        let opts = ConnectionOptions {
+            host: String::new(),
+            port: 0,
+            username: String::new(),
+            password: None,
+            database: Some(db_path.to_str().unwrap().to_string()),
+            timeout: None,
+            require_tls: false,
+            application_name: None,
+            default_schema: None,
+            sqlite: crate::models::SqliteOptions {
+                busy_timeout_ms: 1234,
+                wal: true,
+                foreign_keys: false,
+            },
+        };
+
+        let driver = SqliteDriver::connect(opts).await.unwrap();
+        {
+            let conn = driver.conn.lock().unwrap();
+            let busy_timeout: i64 = conn
+                .query_row("PRAGMA busy_timeout", [], |row| row.get(0))
+                .unwrap();
+            let journal_mode: String = conn
+                .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+                .unwrap();
+            let foreign_keys: i64 = conn
+                .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(busy_timeout, 1234);
+            assert_eq!(journal_mode, "wal");
+            assert_eq!(foreign_keys, 0);
+        }
+        drop(driver);
+
+        // Cleanup, WAL files included
+        for suffix in ["", "-wal", "-shm"] {
+            std::fs::remove_file(format!("{}{}", db_path.display(), suffix)).ok();
+        }
+    }
+
     #[tokio::test]
     async fn test_sqlite_metadata() {
         let temp_dir = std::env::temp_dir();
@@ -548,6 +899,9 @@ mod tests {
             database: Some(db_path.to_str().unwrap().to_string()),
             timeout: None,
             require_tls: false,
+            application_name: None,
+            default_schema: None,
+            sqlite: Default::default(),
         };
 
         let driver = SqliteDriver::connect(opts).await.unwrap();
@@ -589,4 +943,224 @@ mod tests {
         // Cleanup
         std::fs::remove_file(db_path).ok();
     }
+
+    #[tokio::test]
+    async fn test_sqlite_execute_query_with_params() {
+        let db_path = std::env::temp_dir().join("test_db_params.sqlite");
+        std::fs::remove_file(&db_path).ok();
+
+        let opts = ConnectionOptions {
+            host: String::new(),
+            port: 0,
+            username: String::new(),
+            password: None,
+            database: Some(db_path.to_str().unwrap().to_string()),
+            timeout: None,
+            require_tls: false,
+            application_name: None,
+            default_schema: None,
+            sqlite: Default::default(),
+        };
+        let driver = SqliteDriver::connect(opts).await.unwrap();
+
+        driver
+            .execute_query("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+            .await
+            .unwrap();
+        let insert = driver
+            .execute_query_with_params(
+                "INSERT INTO items (id, name) VALUES (?, ?)",
+                &[serde_json::json!(1), serde_json::json!("it's")],
+            )
+            .await
+            .unwrap();
+        assert_eq!(insert.rows_affected, Some(1));
+
+        let result = driver
+            .execute_query_with_params(
+                "SELECT name FROM items WHERE id = ?",
+                &[serde_json::json!(1)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![serde_json::json!("it's")]]);
+
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[test]
+    fn test_type_affinity() {
+        assert_eq!(type_affinity("INTEGER"), "INTEGER");
+        assert_eq!(type_affinity("unsigned big int"), "INTEGER");
+        assert_eq!(type_affinity("VARCHAR(255)"), "TEXT");
+        assert_eq!(type_affinity("NCLOB"), "TEXT");
+        assert_eq!(type_affinity("BLOB"), "BLOB");
+        assert_eq!(type_affinity(""), "BLOB");
+        assert_eq!(type_affinity("DOUBLE PRECISION"), "REAL");
+        assert_eq!(type_affinity("DATETIME"), "NUMERIC");
+        assert_eq!(type_affinity("DECIMAL(10,5)"), "NUMERIC");
+        // "INT" wins over "CHAR" and "FLOATING POINT" contains "INT"
+        assert_eq!(type_affinity("CHARINT"), "INTEGER");
+        assert_eq!(type_affinity("FLOATING POINT"), "INTEGER");
+    }
+
+    #[test]
+    fn test_sqlite_value_to_json() {
+        use serde_json::json;
+
+        assert_eq!(sqlite_value_to_json(ValueRef::Null), json!(null));
+        assert_eq!(
+            sqlite_value_to_json(ValueRef::Integer(i64::MAX)),
+            json!(i64::MAX)
+        );
+        assert_eq!(sqlite_value_to_json(ValueRef::Real(1.5)), json!(1.5));
+        assert_eq!(
+            sqlite_value_to_json(ValueRef::Real(f64::NEG_INFINITY)),
+            json!("-Infinity")
+        );
+        assert_eq!(
+            sqlite_value_to_json(ValueRef::Text(b"caf\xc3\xa9")),
+            json!("café")
+        );
+        assert_eq!(
+            sqlite_value_to_json(ValueRef::Text(b"\xff\x00")),
+            blob_value(b"\xff\x00")
+        );
+        assert_eq!(
+            sqlite_value_to_json(ValueRef::Blob(b"\x00\x01")),
+            blob_value(b"\x00\x01")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_blobs_and_rowid_alias() {
+        let db_path = std::env::temp_dir().join("test_db_types.sqlite");
+        std::fs::remove_file(&db_path).ok();
+
+        let opts = ConnectionOptions {
+            host: String::new(),
+            port: 0,
+            username: String::new(),
+            password: None,
+            database: Some(db_path.to_str().unwrap().to_string()),
+            timeout: None,
+            require_tls: false,
+            application_name: None,
+            default_schema: None,
+            sqlite: Default::default(),
+        };
+        let driver = SqliteDriver::connect(opts).await.unwrap();
+
+        driver
+            .execute_query("CREATE TABLE files (id INTEGER PRIMARY KEY, data, name VARCHAR(20))")
+            .await
+            .unwrap();
+        driver
+            .execute_query("CREATE TABLE codes (id INT PRIMARY KEY)")
+            .await
+            .unwrap();
+        driver
+            .execute_query("CREATE TABLE tags (id INTEGER PRIMARY KEY) WITHOUT ROWID")
+            .await
+            .unwrap();
+
+        // Tagged binary parameters are stored as BLOBs and read back tagged
+        let bytes = blob_value(b"\x89PNG\x00");
+        driver
+            .execute_query_with_params(
+                "INSERT INTO files (data, name) VALUES (?, ?)",
+                &[bytes.clone(), serde_json::json!("logo")],
+            )
+            .await
+            .unwrap();
+        let result = driver
+            .execute_query("SELECT id, data, typeof(data), CAST(X'FF' AS TEXT) FROM files")
+            .await
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![vec![
+                serde_json::json!(1),
+                bytes,
+                serde_json::json!("blob"),
+                blob_value(b"\xff"),
+            ]]
+        );
+
+        let files = driver.get_table_schema("main", "files").await.unwrap();
+        assert!(files.columns[0].is_auto_increment);
+        assert_eq!(files.columns[1].data_type, "BLOB");
+        assert_eq!(files.columns[2].data_type, "VARCHAR(20)");
+        let codes = driver.get_table_schema("main", "codes").await.unwrap();
+        assert!(!codes.columns[0].is_auto_increment);
+        let tags = driver.get_table_schema("main", "tags").await.unwrap();
+        assert!(!tags.columns[0].is_auto_increment);
+
+        std::fs::remove_file(db_path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_attached_database() {
+        let temp_dir = std::env::temp_dir();
+        let db_path = temp_dir.join("test_attach_main.sqlite");
+        let other_path = temp_dir.join("test_attach_other.sqlite");
+        std::fs::remove_file(&db_path).ok();
+        std::fs::remove_file(&other_path).ok();
+
+        let opts = ConnectionOptions {
+            host: String::new(),
+            port: 0,
+            username: String::new(),
+            password: None,
+            database: Some(db_path.to_str().unwrap().to_string()),
+            timeout: None,
+            require_tls: false,
+            application_name: None,
+            default_schema: None,
+            sqlite: Default::default(),
+        };
+        let driver = SqliteDriver::connect(opts).await.unwrap();
+        driver
+            .execute_query("CREATE TABLE users (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+        driver
+            .execute_query(&format!(
+                "ATTACH DATABASE '{}' AS archive",
+                other_path.to_str().unwrap()
+            ))
+            .await
+            .unwrap();
+        driver
+            .execute_query("CREATE TABLE archive.orders (id INTEGER PRIMARY KEY, total REAL)")
+            .await
+            .unwrap();
+        driver
+            .execute_query("INSERT INTO archive.orders (total) VALUES (1.5), (2.5)")
+            .await
+            .unwrap();
+
+        let schemas = driver.get_schemas("").await.unwrap();
+        let names: Vec<&str> = schemas.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["main", "archive"]);
+        assert_eq!(schemas[1].database, "test_attach_other.sqlite");
+
+        let tables = driver.get_tables("archive").await.unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].name, "orders");
+        assert_eq!(tables[0].row_count, Some(2));
+        let schema = driver.get_table_schema("archive", "orders").await.unwrap();
+        assert_eq!(schema.columns.len(), 2);
+
+        // Unknown schema names fall back to main
+        let tables = driver.get_tables("public").await.unwrap();
+        assert_eq!(tables[0].name, "users");
+
+        driver.close().await.unwrap();
+        let schemas = driver.get_schemas("").await.unwrap();
+        assert_eq!(schemas.len(), 1);
+
+        std::fs::remove_file(db_path).ok();
+        std::fs::remove_file(other_path).ok();
+    }
 }
@@ -12,11 +12,18 @@ use tiberius::{AuthMethod, Client, Config, EncryptionLevel, QueryItem};
 use tokio::net::TcpStream;
 use tokio_util::compat::TokioAsyncWriteCompatExt;
 
-use super::{ConnectionOptions, DatabaseDriver, QueryResult, MAX_RESULT_ROWS};
+use super::blob::{blob_bytes, blob_value};
+use super::{
+    BoundStatement, ConnectionOptions, DatabaseDriver, QueryResult, RowSink, StatementCallback,
+    MAX_RESULT_ROWS,
+};
 use crate::models::{
-    ColumnInfo, DatabaseInfo, DbError, ForeignKeyInfo, IndexInfo, SchemaInfo, TableInfo,
+    ColumnInfo, DatabaseInfo, DbError, ErrorCode, ForeignKeyInfo, IndexInfo, SchemaInfo, TableInfo,
     TableSchema,
 };
+use crate::sql::params::PlaceholderStyle;
+use crate::sql::quote;
+use crate::sql::tokenizer::Quoting;
 
 /// A tiberius client over a compat-wrapped Tokio TCP stream.
 type SqlServerClient = Client<tokio_util::compat::Compat<TcpStream>>;
@@ -31,6 +38,22 @@ type SqlServerClient = Client<tokio_util::compat::Compat<TcpStream>>;
 /// activity poll behind it.
 const POOL_SIZE: usize = 4;
 
+/// `DbError` for a failed statement, with `context` in front of the
+/// message; errors the server reported keep their error number
+fn query_error_with(context: &str) -> impl Fn(tiberius::error::Error) -> DbError + '_ {
+    move |e| {
+        let message = format!("{}: {}", context, e);
+        match e.code() {
+            Some(number) => DbError::DatabaseError {
+                message,
+                code: ErrorCode::SqlServer(number),
+                details: None,
+            },
+            None => DbError::QueryError(message),
+        }
+    }
+}
+
 /// SQL Server database driver
 ///
 /// Manages connections to Microsoft SQL Server databases and provides query execution
@@ -41,6 +64,9 @@ pub struct SqlServerDriver {
 
     /// Round-robin cursor into `clients`.
     next: AtomicUsize,
+
+    /// Connection settings, kept to open transaction sessions
+    opts: ConnectionOptions,
 }
 
 impl SqlServerDriver {
@@ -53,6 +79,94 @@ impl SqlServerDriver {
         Arc::clone(&self.clients[idx])
     }
 
+    /// Run a query with `@P1`-style bound parameters on the next pooled
+    /// client and collect the first result set.
+    async fn run_query(
+        &self,
+        sql: &str,
+        params: &[&dyn tiberius::ToSql],
+    ) -> Result<QueryResult, DbError> {
+        let client = self.client();
+        let mut client = client.lock().await;
+
+        // Execute query
+        let mut stream = client
+            .query(sql, params)
+            .await
+            .map_err(query_error_with("Query execution failed"))?;
+
+        // Get column names
+        let columns = stream
+            .columns()
+            .await
+            .map_err(|e| DbError::QueryError(format!("Failed to get columns: {}", e)))?
+            .unwrap_or(&[]);
+
+        let column_names: Vec<String> = columns
+            .iter()
+            .map(|col| col.name().to_string())
+            .collect();
+
+        // Stream rows from the first result set instead of materializing the
+        // entire response via `into_first_result()` (PERF-03). Conversion
+        // stops at MAX_RESULT_ROWS + 1 (the extra row lets the caller flag
+        // truncation), but the stream is still drained to completion because
+        // tiberius requires the result stream to be fully consumed before the
+        // connection can execute another query. Drained rows are dropped
+        // without JSON conversion, so memory stays bounded.
+        let mut rows: Vec<Vec<serde_json::Value>> = Vec::new();
+        let mut capped = false;
+        while let Some(item) = stream
+            .try_next()
+            .await
+            .map_err(query_error_with("Failed to read query results"))?
+        {
+            if let QueryItem::Row(row) = item {
+                // Match the previous `into_first_result()` behavior: only
+                // rows from the first result set are returned.
+                if row.result_index() == 0 && !capped {
+                    rows.push(Self::row_to_json_vec(&row));
+                    if rows.len() > MAX_RESULT_ROWS {
+                        capped = true;
+                    }
+                }
+            }
+        }
+
+        // For DML statements, get rows affected
+        let rows_affected = if column_names.is_empty() {
+            Some(rows.len() as u64)
+        } else {
+            None
+        };
+
+        Ok(QueryResult {
+            columns: column_names,
+            rows,
+            rows_affected,
+        })
+    }
+
+    /// Convert a JSON parameter value to a tiberius parameter
+    fn json_to_sql_param(value: &serde_json::Value) -> Box<dyn tiberius::ToSql> {
+        use serde_json::Value;
+
+        if let Some(bytes) = blob_bytes(value) {
+            return Box::new(bytes);
+        }
+
+        match value {
+            Value::Null => Box::new(None::<String>),
+            Value::Bool(b) => Box::new(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => Box::new(i),
+                None => Box::new(n.as_f64().unwrap_or_default()),
+            },
+            Value::String(s) => Box::new(s.clone()),
+            other => Box::new(other.to_string()),
+        }
+    }
+
     /// Establish a single client connection from connection options.
     async fn connect_client(opts: &ConnectionOptions) -> Result<SqlServerClient, DbError> {
         let config = Self::build_config(opts)?;
@@ -100,6 +214,10 @@ impl SqlServerDriver {
             config.database(database);
         }
 
+        if let Some(name) = &opts.application_name {
+            config.application_name(name);
+        }
+
         // Set encryption level (not supported to avoid TLS issues)
         config.encryption(EncryptionLevel::NotSupported);
 
@@ -133,6 +251,8 @@ impl SqlServerDriver {
                         serde_json::Value::String(v.to_string())
                     } else if let Ok(Some(v)) = row.try_get::<chrono::NaiveDateTime, usize>(i) {
                         serde_json::Value::String(v.to_string())
+                    } else if let Ok(Some(v)) = row.try_get::<&[u8], usize>(i) {
+                        blob_value(v)
                     } else {
                         // Fallback to null for unknown types
                         serde_json::Value::Null
@@ -145,14 +265,83 @@ impl SqlServerDriver {
 
         values
     }
+
+    /// Run statements in one transaction, committing only if `commit` is set
+    ///
+    /// Returns `None` if `after_statement` stopped the transaction.
+    async fn run_in_transaction(
+        &self,
+        statements: &[BoundStatement],
+        commit: bool,
+        mut after_statement: Option<StatementCallback>,
+    ) -> Result<Option<Vec<u64>>, DbError> {
+        let client = self.client();
+        let mut client = client.lock().await;
+
+        // BEGIN/COMMIT go through simple batches rather than `execute`, which
+        // wraps the SQL in sp_executesql and would flag the open transaction
+        // as a BEGIN/COMMIT count mismatch.
+        client
+            .simple_query("BEGIN TRANSACTION")
+            .await
+            .map_err(|e| DbError::QueryError(format!("Failed to begin transaction: {}", e)))?
+            .into_results()
+            .await
+            .map_err(|e| DbError::QueryError(format!("Failed to begin transaction: {}", e)))?;
+
+        let mut affected = Vec::with_capacity(statements.len());
+        let mut failure = None;
+        let mut stopped = false;
+        for statement in statements {
+            let values: Vec<Box<dyn tiberius::ToSql>> = statement
+                .params
+                .iter()
+                .map(Self::json_to_sql_param)
+                .collect();
+            let refs: Vec<&dyn tiberius::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+            match client.execute(statement.sql.as_str(), &refs).await {
+                Ok(result) => affected.push(result.total()),
+                Err(e) => {
+                    failure = Some(query_error_with("Query execution failed")(e));
+                    break;
+                }
+            }
+            if after_statement.as_mut().is_some_and(|f| !f(affected.len())) {
+                stopped = true;
+                break;
+            }
+        }
+
+        let finish = if failure.is_some() || stopped || !commit {
+            "IF @@TRANCOUNT > 0 ROLLBACK TRANSACTION"
+        } else {
+            "COMMIT TRANSACTION"
+        };
+        client
+            .simple_query(finish)
+            .await
+            .map_err(|e| DbError::QueryError(format!("Failed to end transaction: {}", e)))?
+            .into_results()
+            .await
+            .map_err(|e| DbError::QueryError(format!("Failed to end transaction: {}", e)))?;
+
+        match failure {
+            Some(e) => Err(e),
+            None if stopped => Ok(None),
+            None => Ok(Some(affected)),
+        }
+    }
 }
 
 #[async_trait]
 impl DatabaseDriver for SqlServerDriver {
     fn quote_identifier(&self, ident: &str) -> String {
-        // SQL Server uses bracket-delimited identifiers; a closing bracket is
-        // escaped by doubling it.
-        format!("[{}]", ident.replace(']', "]]"))
+        // SQL Server uses bracket-delimited identifiers
+        quote::quote_identifier(ident, Quoting::SqlServer)
+    }
+
+    fn binary_literal(&self, bytes: &[u8]) -> String {
+        format!("0x{}", hex::encode(bytes))
     }
 
     async fn connect(opts: ConnectionOptions) -> Result<Self, DbError>
@@ -170,6 +359,7 @@ impl DatabaseDriver for SqlServerDriver {
         Ok(Self {
             clients,
             next: AtomicUsize::new(0),
+            opts,
         })
     }
 
@@ -186,65 +376,118 @@ impl DatabaseDriver for SqlServerDriver {
     }
 
     async fn execute_query(&self, sql: &str) -> Result<QueryResult, DbError> {
+        self.run_query(sql, &[]).await
+    }
+
+    async fn execute_query_with_params(
+        &self,
+        sql: &str,
+        params: &[serde_json::Value],
+    ) -> Result<QueryResult, DbError> {
+        let values: Vec<Box<dyn tiberius::ToSql>> =
+            params.iter().map(Self::json_to_sql_param).collect();
+        let refs: Vec<&dyn tiberius::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+        self.run_query(sql, &refs).await
+    }
+
+    fn placeholder_style(&self) -> PlaceholderStyle {
+        PlaceholderStyle::AtP
+    }
+
+    async fn stream_query(
+        &self,
+        sql: &str,
+        batch_size: usize,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64, DbError> {
         let client = self.client();
         let mut client = client.lock().await;
 
-        // Execute query
         let mut stream = client
             .query(sql, &[])
             .await
-            .map_err(|e| DbError::QueryError(format!("Query execution failed: {}", e)))?;
+            .map_err(query_error_with("Query execution failed"))?;
 
-        // Get column names
-        let columns = stream
+        let column_names: Vec<String> = stream
             .columns()
             .await
             .map_err(|e| DbError::QueryError(format!("Failed to get columns: {}", e)))?
-            .unwrap_or(&[]);
-
-        let column_names: Vec<String> = columns
+            .unwrap_or(&[])
             .iter()
             .map(|col| col.name().to_string())
             .collect();
 
-        // Stream rows from the first result set instead of materializing the
-        // entire response via `into_first_result()` (PERF-03). Conversion
-        // stops at MAX_RESULT_ROWS + 1 (the extra row lets the caller flag
-        // truncation), but the stream is still drained to completion because
-        // tiberius requires the result stream to be fully consumed before the
-        // connection can execute another query. Drained rows are dropped
-        // without JSON conversion, so memory stays bounded.
-        let mut rows: Vec<Vec<serde_json::Value>> = Vec::new();
-        let mut capped = false;
+        let batch_size = batch_size.max(1);
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut total: u64 = 0;
+        let mut outcome = if column_names.is_empty() {
+            Err(DbError::InvalidInput(
+                "Query does not return any rows".to_string(),
+            ))
+        } else {
+            sink.columns(&column_names).map(|_| true)
+        };
+
+        // As in execute_query, the stream is always drained to the end so the
+        // connection stays usable; once the sink has stopped (or failed) the
+        // remaining rows are skipped without conversion.
         while let Some(item) = stream
             .try_next()
             .await
-            .map_err(|e| DbError::QueryError(format!("Failed to read query results: {}", e)))?
+            .map_err(query_error_with("Failed to read query results"))?
         {
             if let QueryItem::Row(row) = item {
-                // Match the previous `into_first_result()` behavior: only
-                // rows from the first result set are returned.
-                if row.result_index() == 0 && !capped {
-                    rows.push(Self::row_to_json_vec(&row));
-                    if rows.len() > MAX_RESULT_ROWS {
-                        capped = true;
+                if row.result_index() == 0 && matches!(outcome, Ok(true)) {
+                    batch.push(Self::row_to_json_vec(&row));
+                    if batch.len() >= batch_size {
+                        let full = std::mem::replace(&mut batch, Vec::with_capacity(batch_size));
+                        total += full.len() as u64;
+                        outcome = sink.rows(full);
                     }
                 }
             }
         }
 
-        // For DML statements, get rows affected
-        let rows_affected = if column_names.is_empty() {
-            Some(rows.len() as u64)
-        } else {
-            None
-        };
+        if matches!(outcome, Ok(true)) && !batch.is_empty() {
+            total += batch.len() as u64;
+            outcome = sink.rows(batch);
+        }
 
-        Ok(QueryResult {
-            columns: column_names,
-            rows,
-            rows_affected,
-        })
+        outcome.map(|_| total)
+    }
+
+    async fn execute_in_transaction(&self, statements: &[String]) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(&BoundStatement::unbound(statements), true, None)
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    async fn execute_in_transaction_with(
+        &self,
+        statements: &[String],
+        after_statement: StatementCallback,
+    ) -> Result<Option<Vec<u64>>, DbError> {
+        self.run_in_transaction(
+            &BoundStatement::unbound(statements),
+            true,
+            Some(after_statement),
+        )
+        .await
+    }
+
+    async fn execute_in_transaction_with_params(
+        &self,
+        statements: &[BoundStatement],
+    ) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(statements, true, None)
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    async fn execute_and_rollback(&self, statements: &[String]) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(&BoundStatement::unbound(statements), false, None)
+            .await
+            .map(Option::unwrap_or_default)
     }
 
     async fn get_databases(&self) -> Result<Vec<DatabaseInfo>, DbError> {
@@ -368,10 +611,18 @@ impl DatabaseDriver for SqlServerDriver {
                 c.is_nullable,
                 dc.definition AS default_value,
                 CASE WHEN pk.column_id IS NOT NULL THEN 1 ELSE 0 END AS is_primary_key,
-                c.is_identity AS is_auto_increment
+                c.is_identity AS is_auto_increment,
+                CAST(ep.value AS NVARCHAR(MAX)) AS comment,
+                c.is_computed,
+                cc.definition AS generation_expression
              FROM sys.columns c
              INNER JOIN sys.types t ON c.user_type_id = t.user_type_id
              LEFT JOIN sys.default_constraints dc ON c.default_object_id = dc.object_id
+             LEFT JOIN sys.computed_columns cc
+                ON cc.object_id = c.object_id AND cc.column_id = c.column_id
+             LEFT JOIN sys.extended_properties ep
+                ON ep.class = 1 AND ep.major_id = c.object_id
+                AND ep.minor_id = c.column_id AND ep.name = 'MS_Description'
              LEFT JOIN (
                  SELECT ic.object_id, ic.column_id
                  FROM sys.index_columns ic
@@ -427,6 +678,19 @@ impl DatabaseDriver for SqlServerDriver {
                 .map_err(|e| DbError::QueryError(format!("Failed to parse is_identity: {}", e)))?
                 .ok_or_else(|| DbError::QueryError("is_identity is null".to_string()))?;
 
+            let comment: Option<&str> = row
+                .try_get(6)
+                .map_err(|e| DbError::QueryError(format!("Failed to parse comment: {}", e)))?;
+
+            let is_computed: bool = row
+                .try_get(7)
+                .map_err(|e| DbError::QueryError(format!("Failed to parse is_computed: {}", e)))?
+                .unwrap_or(false);
+
+            let generation_expression: Option<&str> = row.try_get(8).map_err(|e| {
+                DbError::QueryError(format!("Failed to parse computed definition: {}", e))
+            })?;
+
             columns.push(ColumnInfo {
                 name: name.to_string(),
                 data_type: data_type.to_string(),
@@ -434,20 +698,57 @@ impl DatabaseDriver for SqlServerDriver {
                 default_value: default_value.map(|s| s.to_string()),
                 is_primary_key: is_primary_key == 1,
                 is_auto_increment,
+                comment: comment.map(|s| s.to_string()),
+                is_generated: is_computed,
+                generation_expression: generation_expression.map(|s| s.to_string()),
             });
         }
 
-        // Get indexes - Note: STRING_AGG requires SQL Server 2017+
-        // For compatibility, we'll use a simpler query
+        // The table comment is the MS_Description property of the table
+        // itself (minor_id 0)
+        let comment_sql = format!(
+            "SELECT CAST(value AS NVARCHAR(MAX))
+             FROM sys.extended_properties
+             WHERE class = 1 AND major_id = OBJECT_ID('{}.{}')
+             AND minor_id = 0 AND name = 'MS_Description'",
+            schema, table
+        );
+
+        let comment_row = client
+            .query(&comment_sql, &[])
+            .await
+            .map_err(|e| DbError::QueryError(format!("Failed to get table comment: {}", e)))?
+            .into_row()
+            .await
+            .map_err(|e| DbError::QueryError(format!("Failed to read table comment: {}", e)))?;
+
+        let table_comment = match comment_row {
+            Some(row) => row
+                .try_get::<&str, _>(0)
+                .map_err(|e| DbError::QueryError(format!("Failed to parse comment: {}", e)))?
+                .map(|s| s.to_string()),
+            None => None,
+        };
+
+        // Get indexes with their key columns. STRING_AGG would need SQL
+        // Server 2017+, so fetch one row per (index, column) ordered by
+        // key_ordinal and fold them here. key_ordinal = 0 marks INCLUDE
+        // columns, which are not part of the key and are skipped.
         let indexes_sql = format!(
-            "SELECT DISTINCT
+            "SELECT
                 i.name,
                 i.is_unique,
-                i.is_primary_key
+                i.is_primary_key,
+                c.name AS column_name
              FROM sys.indexes i
+             INNER JOIN sys.index_columns ic
+                ON ic.object_id = i.object_id AND ic.index_id = i.index_id
+             INNER JOIN sys.columns c
+                ON c.object_id = ic.object_id AND c.column_id = ic.column_id
              WHERE i.object_id = OBJECT_ID('{}.{}')
              AND i.name IS NOT NULL
-             ORDER BY i.name",
+             AND ic.key_ordinal > 0
+             ORDER BY i.name, ic.key_ordinal",
             schema, table
         );
 
@@ -461,7 +762,7 @@ impl DatabaseDriver for SqlServerDriver {
             .await
             .map_err(|e| DbError::QueryError(format!("Failed to read indexes: {}", e)))?;
 
-        let mut indexes = Vec::new();
+        let mut indexes: Vec<IndexInfo> = Vec::new();
         for row in row_stream {
             let name: &str = row
                 .try_get(0)
@@ -478,13 +779,21 @@ impl DatabaseDriver for SqlServerDriver {
                 .map_err(|e| DbError::QueryError(format!("Failed to parse is_primary_key: {}", e)))?
                 .ok_or_else(|| DbError::QueryError("is_primary_key is null".to_string()))?;
 
-            // For now, we'll leave columns empty as getting them requires a more complex query
-            indexes.push(IndexInfo {
-                name: name.to_string(),
-                columns: Vec::new(),
-                is_unique,
-                is_primary,
-            });
+            let column: &str = row
+                .try_get(3)
+                .map_err(|e| DbError::QueryError(format!("Failed to parse index column: {}", e)))?
+                .ok_or_else(|| DbError::QueryError("Index column is null".to_string()))?;
+
+            // Rows of one index are contiguous thanks to the ORDER BY.
+            match indexes.last_mut() {
+                Some(index) if index.name == name => index.columns.push(column.to_string()),
+                _ => indexes.push(IndexInfo {
+                    name: name.to_string(),
+                    columns: vec![column.to_string()],
+                    is_unique,
+                    is_primary,
+                }),
+            }
         }
 
         Ok(TableSchema {
@@ -496,6 +805,8 @@ impl DatabaseDriver for SqlServerDriver {
             },
             columns,
             indexes,
+            constraints: Vec::new(),
+            comment: table_comment,
         })
     }
 
@@ -612,6 +923,15 @@ impl DatabaseDriver for SqlServerDriver {
         Ok(fk_map.into_values().collect())
     }
 
+    async fn open_session(&self) -> Result<Option<Arc<dyn DatabaseDriver>>, DbError> {
+        let client = Self::connect_client(&self.opts).await?;
+        Ok(Some(Arc::new(Self {
+            clients: vec![Arc::new(Mutex::new(client))],
+            next: AtomicUsize::new(0),
+            opts: self.opts.clone(),
+        })))
+    }
+
     async fn close(&self) -> Result<(), DbError> {
         // Tiberius client doesn't need explicit close
         // Connection will be closed when dropped
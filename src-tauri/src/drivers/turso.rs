@@ -7,7 +7,11 @@
 use async_trait::async_trait;
 use libsql::{Builder, Connection, Value};
 
-use super::{ConnectionOptions, DatabaseDriver, QueryResult, MAX_RESULT_ROWS};
+use super::blob::{blob_bytes, blob_value};
+use super::{
+    BoundStatement, ConnectionOptions, DatabaseDriver, QueryResult, StatementCallback,
+    MAX_RESULT_ROWS,
+};
 use crate::models::{
     ColumnInfo, DatabaseInfo, DbError, ForeignKeyInfo, IndexInfo, SchemaInfo, TableInfo, TableSchema,
 };
@@ -26,7 +30,78 @@ impl TursoDriver {
                 .map(serde_json::Value::Number)
                 .unwrap_or(serde_json::Value::Null),
             Value::Text(s) => serde_json::Value::String(s),
-            Value::Blob(b) => serde_json::Value::String(format!("<BLOB {} bytes>", b.len())),
+            Value::Blob(b) => blob_value(&b),
+        }
+    }
+
+    fn json_to_value(value: &serde_json::Value) -> Value {
+        if let Some(bytes) = blob_bytes(value) {
+            return Value::Blob(bytes);
+        }
+
+        match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Integer(i64::from(*b)),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => Value::Integer(i),
+                None => Value::Real(n.as_f64().unwrap_or_default()),
+            },
+            serde_json::Value::String(s) => Value::Text(s.clone()),
+            other => Value::Text(other.to_string()),
+        }
+    }
+
+    /// Run a single statement with positional (`?`) parameters
+    async fn run_statement(&self, sql: &str, params: Vec<Value>) -> Result<QueryResult, DbError> {
+        if Self::is_select(sql) {
+            let mut rows = self
+                .conn
+                .query(sql, params)
+                .await
+                .map_err(|e| DbError::QueryError(format!("Failed to execute query: {}", e)))?;
+
+            let col_count = rows.column_count();
+            let mut columns = Vec::with_capacity(col_count as usize);
+            for i in 0..col_count {
+                columns.push(
+                    rows.column_name(i)
+                        .map(|s| s.to_string())
+                        .unwrap_or_else(|| format!("column_{}", i)),
+                );
+            }
+
+            let mut data = Vec::new();
+            while let Some(row) = rows
+                .next()
+                .await
+                .map_err(|e| DbError::QueryError(format!("Failed to fetch row: {}", e)))?
+            {
+                let mut json_row = Vec::with_capacity(col_count as usize);
+                for i in 0..col_count {
+                    let v = row.get_value(i).map_err(|e| {
+                        DbError::QueryError(format!("Failed to read column: {}", e))
+                    })?;
+                    json_row.push(Self::value_to_json(v));
+                }
+                data.push(json_row);
+
+                // Enforce the row cap inside the fetch loop so an unbounded
+                // SELECT never materializes the full result set (PERF-03).
+                // One extra row past the cap lets the caller flag truncation;
+                // dropping `rows` discards the remainder.
+                if data.len() > MAX_RESULT_ROWS {
+                    break;
+                }
+            }
+
+            Ok(QueryResult::with_data(columns, data))
+        } else {
+            let affected = self
+                .conn
+                .execute(sql, params)
+                .await
+                .map_err(|e| DbError::QueryError(format!("Failed to execute statement: {}", e)))?;
+            Ok(QueryResult::with_affected(affected))
         }
     }
 
@@ -56,6 +131,61 @@ impl TursoDriver {
         Ok(out)
     }
 
+    /// Run statements in one transaction, committing only if `commit` is set
+    ///
+    /// Returns `None` if `after_statement` stopped the transaction.
+    async fn run_in_transaction(
+        &self,
+        statements: &[BoundStatement],
+        commit: bool,
+        mut after_statement: Option<StatementCallback>,
+    ) -> Result<Option<Vec<u64>>, DbError> {
+        let tx = self
+            .conn
+            .transaction()
+            .await
+            .map_err(|e| DbError::QueryError(format!("Failed to begin transaction: {}", e)))?;
+
+        let mut affected = Vec::with_capacity(statements.len());
+        let mut failure = None;
+        let mut stopped = false;
+        for statement in statements {
+            let values: Vec<Value> = statement.params.iter().map(Self::json_to_value).collect();
+            match tx.execute(&statement.sql, values).await {
+                Ok(rows) => affected.push(rows),
+                Err(e) => {
+                    failure = Some(DbError::QueryError(format!(
+                        "Failed to execute statement: {}",
+                        e
+                    )));
+                    break;
+                }
+            }
+            if after_statement.as_mut().is_some_and(|f| !f(affected.len())) {
+                stopped = true;
+                break;
+            }
+        }
+
+        // Roll back explicitly rather than relying on drop, so the remote
+        // stream is left without an open transaction
+        if failure.is_some() || stopped || !commit {
+            tx.rollback().await.map_err(|e| {
+                DbError::QueryError(format!("Failed to roll back transaction: {}", e))
+            })?;
+        } else {
+            tx.commit()
+                .await
+                .map_err(|e| DbError::QueryError(format!("Failed to commit transaction: {}", e)))?;
+        }
+
+        match failure {
+            Some(e) => Err(e),
+            None if stopped => Ok(None),
+            None => Ok(Some(affected)),
+        }
+    }
+
     fn is_select(sql: &str) -> bool {
         let trimmed = sql.trim_start().to_ascii_lowercase();
         trimmed.starts_with("select")
@@ -100,56 +230,50 @@ impl DatabaseDriver for TursoDriver {
     }
 
     async fn execute_query(&self, sql: &str) -> Result<QueryResult, DbError> {
-        if Self::is_select(sql) {
-            let mut rows = self
-                .conn
-                .query(sql, ())
-                .await
-                .map_err(|e| DbError::QueryError(format!("Failed to execute query: {}", e)))?;
+        self.run_statement(sql, Vec::new()).await
+    }
 
-            let col_count = rows.column_count();
-            let mut columns = Vec::with_capacity(col_count as usize);
-            for i in 0..col_count {
-                columns.push(
-                    rows.column_name(i)
-                        .map(|s| s.to_string())
-                        .unwrap_or_else(|| format!("column_{}", i)),
-                );
-            }
+    async fn execute_query_with_params(
+        &self,
+        sql: &str,
+        params: &[serde_json::Value],
+    ) -> Result<QueryResult, DbError> {
+        let values = params.iter().map(Self::json_to_value).collect();
+        self.run_statement(sql, values).await
+    }
 
-            let mut data = Vec::new();
-            while let Some(row) = rows
-                .next()
-                .await
-                .map_err(|e| DbError::QueryError(format!("Failed to fetch row: {}", e)))?
-            {
-                let mut json_row = Vec::with_capacity(col_count as usize);
-                for i in 0..col_count {
-                    let v = row.get_value(i).map_err(|e| {
-                        DbError::QueryError(format!("Failed to read column: {}", e))
-                    })?;
-                    json_row.push(Self::value_to_json(v));
-                }
-                data.push(json_row);
+    async fn execute_in_transaction(&self, statements: &[String]) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(&BoundStatement::unbound(statements), true, None)
+            .await
+            .map(Option::unwrap_or_default)
+    }
 
-                // Enforce the row cap inside the fetch loop so an unbounded
-                // SELECT never materializes the full result set (PERF-03).
-                // One extra row past the cap lets the caller flag truncation;
-                // dropping `rows` discards the remainder.
-                if data.len() > MAX_RESULT_ROWS {
-                    break;
-                }
-            }
+    async fn execute_in_transaction_with(
+        &self,
+        statements: &[String],
+        after_statement: StatementCallback,
+    ) -> Result<Option<Vec<u64>>, DbError> {
+        self.run_in_transaction(
+            &BoundStatement::unbound(statements),
+            true,
+            Some(after_statement),
+        )
+        .await
+    }
 
-            Ok(QueryResult::with_data(columns, data))
-        } else {
-            let affected = self
-                .conn
-                .execute(sql, ())
-                .await
-                .map_err(|e| DbError::QueryError(format!("Failed to execute statement: {}", e)))?;
-            Ok(QueryResult::with_affected(affected))
-        }
+    async fn execute_in_transaction_with_params(
+        &self,
+        statements: &[BoundStatement],
+    ) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(statements, true, None)
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    async fn execute_and_rollback(&self, statements: &[String]) -> Result<Vec<u64>, DbError> {
+        self.run_in_transaction(&BoundStatement::unbound(statements), false, None)
+            .await
+            .map(Option::unwrap_or_default)
     }
 
     async fn get_databases(&self) -> Result<Vec<DatabaseInfo>, DbError> {
@@ -247,6 +371,9 @@ impl DatabaseDriver for TursoDriver {
                 default_value,
                 is_primary_key: pk,
                 is_auto_increment,
+                comment: None,
+                is_generated: false,
+                generation_expression: None,
             });
         }
 
@@ -305,6 +432,8 @@ impl DatabaseDriver for TursoDriver {
             },
             columns,
             indexes,
+            constraints: Vec::new(),
+            comment: None,
         })
     }
 
@@ -7,6 +7,7 @@ mod drivers;
 mod migrations;
 mod models;
 mod plugins;
+mod sql;
 mod ssh;
 mod state;
 
@@ -16,7 +17,7 @@ use plugins::{loader::PluginLoader, PluginManager};
 use state::AppState;
 use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, WindowEvent};
 
 // Helper function to toggle window visibility
 fn toggle_window_visibility(app: &AppHandle) {
@@ -89,15 +90,25 @@ pub fn run() {
             // Initialize application state
             let mut state = AppState::default();
 
+            // With a master password set, profiles and snippets stay encrypted
+            // on disk until the user unlocks the store
+            match state.load_store_encryption(&app.handle()) {
+                Ok(true) => println!("Profile store is encrypted; waiting for unlock"),
+                Ok(false) => {}
+                Err(e) => eprintln!("Failed to read store encryption state: {}", e),
+            }
+
             // Load saved profiles from persistent storage
-            match state.load_profiles_from_store(&app.handle()) {
-                Ok(count) => {
-                    if count > 0 {
-                        println!("Loaded {} connection profile(s) from storage", count);
+            if !state.is_store_locked() {
+                match state.load_profiles_from_store(&app.handle()) {
+                    Ok(count) => {
+                        if count > 0 {
+                            println!("Loaded {} connection profile(s) from storage", count);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load profiles from storage: {}", e);
                     }
-                }
-                Err(e) => {
-                    eprintln!("Failed to load profiles from storage: {}", e);
                 }
             }
 
@@ -125,29 +136,100 @@ pub fn run() {
                 }
             }
 
-            // Load query snippets from persistent storage
-            match state.load_snippets_from_store(&app.handle()) {
+            // Drop history outside the retention window before it sits in memory
+            let query_settings = commands::settings::load_settings(&app.handle())
+                .map(|s| s.query)
+                .unwrap_or_default();
+            let pruned = state.apply_history_retention(&query_settings);
+            if pruned > 0 {
+                match AppState::save_history_to_store(&app.handle(), &state.query_history) {
+                    Ok(()) => println!("Pruned {} query history record(s)", pruned),
+                    Err(e) => eprintln!("Failed to save pruned query history: {}", e),
+                }
+            }
+
+            // Load last-used query parameter values from persistent storage
+            match state.load_parameter_values_from_store(&app.handle()) {
+                Ok(count) => {
+                    if count > 0 {
+                        println!("Loaded {} query parameter value(s) from storage", count);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Failed to load query parameter values from storage: {}", e);
+                }
+            }
+
+            // Load recorded query plans from persistent storage
+            match state.load_query_plans_from_store(&app.handle()) {
+                Ok(count) => {
+                    if count > 0 {
+                        println!("Loaded {} recorded query plan(s) from storage", count);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Failed to load recorded query plans from storage: {}", e);
+                }
+            }
+
+            if !state.is_store_locked() {
+                // Load query snippets from persistent storage
+                match state.load_snippets_from_store(&app.handle()) {
+                    Ok(count) => {
+                        if count > 0 {
+                            println!("Loaded {} query snippet(s) from storage", count);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load query snippets from storage: {}", e);
+                    }
+                }
+
+                // Load saved views from persistent storage
+                match state.load_saved_views_from_store(&app.handle()) {
+                    Ok(count) => {
+                        if count > 0 {
+                            println!("Loaded {} saved view(s) from storage", count);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load saved views from storage: {}", e);
+                    }
+                }
+            }
+
+            // Load query schedules from persistent storage
+            match state.load_schedules_from_store(&app.handle()) {
                 Ok(count) => {
                     if count > 0 {
-                        println!("Loaded {} query snippet(s) from storage", count);
+                        println!("Loaded {} query schedule(s) from storage", count);
                     }
                 }
                 Err(e) => {
-                    eprintln!("Failed to load query snippets from storage: {}", e);
+                    eprintln!("Failed to load query schedules from storage: {}", e);
                 }
             }
 
             // Manage the state
             app.manage(Mutex::new(state));
 
+            // Periodically ping active connections (interval from settings)
+            state::spawn_health_monitor(app.handle().clone());
+
+            // Fire due query schedules while the app is open
+            state::spawn_schedule_runner(app.handle().clone());
+
             // Per-window pending profile map (multi-window auto-connect)
             app.manage(commands::window::PendingWindowProfiles::default());
 
             // Shared cancel flag for long-running import operations
             app.manage(Arc::new(AtomicBool::new(false)));
 
-            // Initialize AI state
-            app.manage(commands::ai::AiState::default());
+            // Cancel flags of running query-to-file exports
+            app.manage(commands::query_export::ActiveQueryExports::default());
+
+            // Initialize AI state with the saved prompt templates
+            app.manage(commands::ai::AiState::load(app.handle()));
 
             // Initialize plugin manager
             let plugin_manager = PluginManager::new(app.handle().clone());
@@ -196,7 +278,7 @@ pub fn run() {
                         }
                     }
                     "quit" => {
-                        app.exit(0);
+                        state::request_shutdown(app);
                     }
                     _ => {}
                 })
@@ -225,6 +307,16 @@ pub fn run() {
 
             Ok(())
         })
+        .on_window_event(|window, event| {
+            // Closing the last window quits; clean up first
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                let app = window.app_handle();
+                if app.webview_windows().len() <= 1 && !state::is_shutting_down() {
+                    api.prevent_close();
+                    state::request_shutdown(app);
+                }
+            }
+        })
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
@@ -234,6 +326,7 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             greet,
             commands::connection::test_connection_command,
+            commands::connection::test_all_connections,
             commands::connection::create_connection_profile,
             commands::connection::update_connection_profile,
             commands::connection::delete_connection_profile,
@@ -244,38 +337,109 @@ pub fn run() {
             commands::connection::get_ssh_password,
             commands::connection::connect_to_database,
             commands::connection::disconnect_from_database,
+            commands::connection::get_connection_health,
+            commands::connection::ping_connection,
+            commands::connection::get_connection_latency,
+            commands::reconnect::reconnect_connection,
+            commands::reconnect::reset_connection,
             commands::connection::switch_database,
             commands::connection::record_connection,
             commands::connection::toggle_favorite,
+            commands::connection::pin_table,
+            commands::connection::unpin_table,
             commands::connection::update_connection_folder,
+            commands::connection::set_connection_environment,
             commands::connection::get_connection_stats,
+            commands::connection::find_duplicate_profiles,
             commands::connection::get_recent_connections,
             commands::connection::duplicate_connection,
+            commands::connection::parse_connection_uri,
+            commands::connection::profile_to_uri,
+            commands::profile_transfer::export_connection_profiles,
+            commands::profile_transfer::import_connection_profiles,
+            commands::vault::get_store_lock_status,
+            commands::vault::set_master_password,
+            commands::vault::unlock_store,
+            commands::vault::lock_store,
+            commands::query::execute_parameterized_query,
+            commands::query::run_mongo_aggregate,
+            commands::query::preview_affected_rows,
             commands::query::execute_query,
+            commands::query::clear_query_cache,
+            commands::query::get_running_queries,
+            commands::query::get_query_queue_depth,
+            commands::query::extract_query_parameters,
             commands::query::get_table_data_keyset,
+            commands::table_data::fetch_table_rows,
+            commands::table_data::update_table_row,
+            commands::table_data::delete_table_row,
+            commands::table_data::insert_table_rows,
+            commands::table_data::delete_table_rows,
+            commands::table_data::truncate_table,
+            commands::table_data::fetch_cell_blob,
+            commands::profiling::profile_column,
+            commands::profiling::estimate_cardinality,
+            commands::result_diff::diff_query_results,
             commands::schema::get_databases,
             commands::schema::get_schemas,
             commands::schema::get_tables,
+            commands::schema::get_tables_with_pins,
+            commands::schema::search_schema_objects,
+            commands::schema::find_columns,
+            commands::schema::get_column_references,
             commands::schema::get_table_schema,
             commands::schema::get_foreign_keys,
+            commands::schema::get_cache_status,
+            commands::erd::get_schema_graph,
             commands::schema::get_autocomplete_metadata,
             commands::history::save_to_history,
             commands::history::get_query_history,
             commands::history::clear_history,
+            commands::history::prune_history,
+            commands::history::toggle_history_favorite,
+            commands::history::set_history_note,
             commands::history::save_snippet,
             commands::history::list_snippets,
             commands::history::delete_snippet,
             commands::history::get_snippet,
+            commands::history::clone_snippet_to_connection,
+            commands::history::render_snippet,
+            commands::history::render_snippet_parameterized,
+            commands::snippet_transfer::export_snippets,
+            commands::snippet_transfer::import_snippets,
+            commands::schedules::schedule_query,
+            commands::schedules::list_schedules,
+            commands::schedules::delete_schedule,
+            commands::schedules::run_schedule_now,
+            commands::history::save_saved_view,
+            commands::history::list_saved_views,
+            commands::history::get_saved_view_tree,
+            commands::history::delete_saved_view,
+            commands::history::get_saved_view,
+            commands::history::run_saved_view,
             commands::export::export_to_csv,
             commands::export::export_to_json,
             commands::export::export_to_sql,
             commands::export::import_from_sql,
             commands::export::cancel_import,
+            commands::export::format_rows_as,
+            commands::query_export::export_query_to_file,
+            commands::query_export::cancel_query_export,
+            commands::query_templates::generate_query_template,
+            commands::query_plan::analyze_query_plan,
+            commands::query_plan::record_query_plan,
+            commands::query_plan::get_query_plan_history,
+            commands::query_plan::compare_query_plans,
             commands::settings::get_settings,
             commands::settings::update_settings,
             commands::settings::reset_settings,
+            commands::sql_tools::format_sql,
+            commands::sql_tools::lint_sql,
+            commands::sql_tools::fingerprint_query,
+            commands::sql_tools::build_where_clause,
             commands::window::open_database_window,
             commands::window::take_pending_window_profile,
+            commands::window::quit_app,
             commands::backup::get_backup_directory,
             commands::backup::list_backups,
             commands::backup::create_backup,
@@ -284,6 +448,8 @@ pub fn run() {
             commands::backup::open_backup_directory,
             commands::activity::get_query_logs,
             commands::activity::get_activity_stats,
+            commands::activity::get_activity_timeseries,
+            commands::activity::get_slow_queries,
             commands::activity::clear_query_logs,
             commands::activity::clear_old_query_logs,
             commands::activity::export_query_logs,
@@ -291,19 +457,45 @@ pub fn run() {
             commands::activity::get_query_logs_count,
             commands::monitoring::get_active_queries,
             commands::monitoring::kill_query,
+            commands::monitoring::get_server_sessions,
+            commands::monitoring::kill_session,
             commands::monitoring::get_server_stats,
+            commands::monitoring::get_database_stats,
+            commands::monitoring::get_server_info,
+            commands::notifications::listen_channel,
+            commands::notifications::unlisten_channel,
+            commands::sqlite::sqlite_vacuum,
+            commands::sqlite::sqlite_integrity_check,
+            commands::sqlite::sqlite_get_pragmas,
+            commands::sqlite::sqlite_set_pragma,
+            commands::sqlite::sqlite_attach_database,
+            commands::sqlite::sqlite_detach_database,
             commands::procedures::list_procedures,
             commands::procedures::get_procedure_definition,
             commands::procedures::execute_procedure,
+            commands::triggers::get_triggers,
+            commands::sequences::get_sequences,
+            commands::sequences::reset_sequence,
             commands::ddl::preview_create_table,
             commands::ddl::create_table,
             commands::ddl::preview_alter_table,
             commands::ddl::alter_table,
             commands::ddl::preview_drop_table,
             commands::ddl::drop_table,
+            commands::ddl::preview_create_view,
+            commands::ddl::create_view,
+            commands::ddl::preview_drop_view,
+            commands::ddl::drop_view,
+            commands::ddl::duplicate_table_structure,
+            commands::ddl::preview_rename_table,
+            commands::ddl::rename_table,
+            commands::ddl::set_table_comment,
+            commands::ddl::set_column_comment,
             commands::ddl::create_database,
+            commands::migrations::compare_schemas,
             commands::migrations::compute_schema_diff,
             commands::migrations::generate_migration,
+            commands::migrations::generate_migration_ddl,
             commands::migrations::apply_migration,
             commands::plugins::get_installed_plugins,
             commands::plugins::get_plugin,
@@ -320,6 +512,7 @@ pub fn run() {
             commands::plugins::is_plugin_loaded,
             commands::data_import::preview_import_file,
             commands::data_import::import_data_to_table,
+            commands::data_import::validate_import_mapping,
             commands::data_import::get_tables_for_import,
             commands::data_import::get_table_columns_for_import,
             commands::ai::check_ollama_status,
@@ -329,11 +522,16 @@ pub fn run() {
             commands::ai::set_active_ai_provider,
             commands::ai::set_ai_api_key,
             commands::ai::list_ai_models,
+            commands::ai::get_ai_prompts,
+            commands::ai::set_ai_prompt,
+            commands::ai::reset_ai_prompts,
             commands::ai::ai_generate_sql,
             commands::ai::ai_explain_query,
             commands::ai::ai_optimize_query,
             commands::ai::ai_fix_query,
+            commands::ai::ai_explain_error,
             commands::ai::ai_chat,
+            commands::ai::summarize_schema_for_ai,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
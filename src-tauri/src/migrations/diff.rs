@@ -5,7 +5,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::migrations::type_map::types_equivalent;
 use crate::models::metadata::{ColumnInfo, ForeignKeyInfo, IndexInfo, TableSchema};
+use crate::models::DbDriver;
 
 /// Column-level modification reported in `TableDiff::modified_columns`.
 ///
@@ -65,6 +67,16 @@ pub struct SchemaDiff {
     pub added_tables: Vec<TableSchema>,
     pub removed_tables: Vec<TableSchema>,
     pub modified_tables: Vec<TableDiff>,
+    /// Driver of the desired side, when known; used to map types when the
+    /// migration is generated for a different dialect
+    #[serde(default)]
+    pub source_driver: Option<DbDriver>,
+    /// Driver of the side being migrated, when known
+    #[serde(default)]
+    pub target_driver: Option<DbDriver>,
+    /// Caveats found while comparing, e.g. across dialects
+    #[serde(default)]
+    pub warnings: Vec<String>,
 }
 
 /// Pair of schemas passed into `compute_diff`. Each element corresponds to one
@@ -176,7 +188,7 @@ fn diff_table(src: &TableWithFks, tgt: &TableWithFks) -> TableDiff {
 }
 
 fn columns_equal(a: &ColumnInfo, b: &ColumnInfo) -> bool {
-    a.data_type.eq_ignore_ascii_case(&b.data_type)
+    types_equivalent(&a.data_type, &b.data_type)
         && a.nullable == b.nullable
         && a.default_value == b.default_value
         && a.is_primary_key == b.is_primary_key
@@ -7,8 +7,9 @@
 
 pub mod diff;
 pub mod sql_gen;
+pub mod type_map;
 
 pub use diff::{
     compute_diff, ColumnChange, ForeignKeyDiff, IndexDiff, SchemaDiff, TableDiff,
 };
-pub use sql_gen::generate_migration_sql;
+pub use sql_gen::{generate_migration_ddl, generate_migration_sql, MigrationDdl};
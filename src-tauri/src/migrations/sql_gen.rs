@@ -12,49 +12,130 @@
 //!   8. DROP removed columns
 //!   9. DROP removed tables
 //!
-//! Column type strings come directly from metadata (`ColumnInfo::data_type`),
-//! which each driver already formats in its native dialect. FK definitions on
-//! newly-created tables are deferred to the ADD-FK pass so referenced tables
-//! created in the same migration exist first.
+//! Steps 8 and 9 lose data and are returned separately, so a migration can be
+//! run without them. Tables, columns and indexes are rendered by the target's
+//! `DdlGenerator`; column types go through `type_map`, which keeps them as
+//! written within one dialect and maps them, with warnings where the mapping
+//! is inexact, across dialects. FK definitions on newly-created tables are
+//! deferred to the ADD-FK pass so referenced tables created in the same
+//! migration exist first.
 
+use serde::{Deserialize, Serialize};
+
+use crate::ddl::{get_ddl_generator, DdlGenerator};
 use crate::migrations::diff::SchemaDiff;
+use crate::migrations::type_map::{map_column_type, map_default, types_equivalent};
+use crate::models::ddl::{
+    AlterColumnOperation, AlterTableDefinition, ColumnDefinition, ColumnType, DropTableDefinition,
+    IndexDefinition, IndexType, TableDefinition,
+};
 use crate::models::metadata::{ColumnInfo, ForeignKeyInfo, IndexInfo, TableSchema};
 use crate::models::{DbDriver, DbError};
 
+/// Migration statements, with the ones that lose data kept apart
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationDdl {
+    /// Statements that keep existing data, in execution order
+    pub statements: Vec<String>,
+    /// DROP COLUMN and DROP TABLE statements, to run after `statements` if wanted
+    pub destructive_statements: Vec<String>,
+    /// Type mappings and changes that could not be expressed exactly
+    pub warnings: Vec<String>,
+}
+
+/// All statements of [`generate_migration_ddl`], destructive ones last
 pub fn generate_migration_sql(
     diff: &SchemaDiff,
     driver: &DbDriver,
 ) -> Result<Vec<String>, DbError> {
+    let ddl = generate_migration_ddl(diff, driver)?;
+    let mut out = ddl.statements;
+    out.extend(ddl.destructive_statements);
+    Ok(out)
+}
+
+/// Generate the statements that turn the diff's target into its source
+///
+/// `driver` is the dialect to write. When `diff.source_driver` is another
+/// dialect, column types and defaults are mapped and every inexact mapping
+/// is reported in `warnings`.
+pub fn generate_migration_ddl(
+    diff: &SchemaDiff,
+    driver: &DbDriver,
+) -> Result<MigrationDdl, DbError> {
+    let generator = get_ddl_generator(driver)?;
     let q = Quoter::for_driver(driver)?;
+    let source = diff.source_driver.as_ref().unwrap_or(driver);
+    let mut warnings = diff.warnings.clone();
     let mut out: Vec<String> = Vec::new();
 
     // 1. CREATE tables (columns + PK only; FKs deferred)
     for t in &diff.added_tables {
-        out.push(create_table_sql(t, &q));
+        let definition = table_definition(t, source, driver, &mut warnings);
+        out.extend(generator.generate_create_table(&definition)?.sql);
         for idx in &t.indexes {
             if idx.is_primary {
                 continue;
             }
-            out.push(create_index_sql(&t.table.schema, &t.table.name, idx, &q));
+            out.push(create_index(
+                generator.as_ref(),
+                &t.table.schema,
+                &t.table.name,
+                idx,
+                &q,
+            ));
         }
     }
 
     // 2. ADD columns
     for td in &diff.modified_tables {
-        for col in &td.added_columns {
-            out.push(format!(
-                "ALTER TABLE {} ADD COLUMN {}",
-                q.qualified(&td.schema, &td.name),
-                column_sql(col, &q),
-            ));
+        let operations: Vec<AlterColumnOperation> = td
+            .added_columns
+            .iter()
+            .map(|col| {
+                let label = format!("{}.{}", td.name, col.name);
+                if !col.nullable && col.default_value.is_none() {
+                    warnings.push(format!(
+                        "{}: adding a NOT NULL column without a default fails if the table has rows",
+                        label
+                    ));
+                }
+                let mut column = column_definition(col, source, driver, &label, &mut warnings);
+                column.primary_key = false;
+                AlterColumnOperation::AddColumn { column }
+            })
+            .collect();
+        if !operations.is_empty() {
+            out.extend(
+                generator
+                    .generate_alter_table(&alter_table(&td.schema, &td.name, operations))?
+                    .sql,
+            );
         }
     }
 
-    // 3. MODIFY columns (best-effort; driver-specific syntax)
+    // 3. MODIFY columns (driver-specific syntax)
     for td in &diff.modified_tables {
         for ch in &td.modified_columns {
+            let label = format!("{}.{}", td.name, ch.name);
+            let new_type =
+                map_column_type(&ch.source.data_type, source, driver, &label, &mut warnings);
+            let default = ch
+                .source
+                .default_value
+                .as_deref()
+                .and_then(|d| map_default(d, source, driver, &label, &mut warnings));
             out.extend(alter_column_sql(
-                &td.schema, &td.name, &ch.source, &ch.target, &q, driver,
+                &td.schema,
+                &td.name,
+                &ch.source,
+                &ch.target,
+                &generator.column_type_sql(&new_type),
+                default.as_deref(),
+                &q,
+                driver,
+                &mut warnings,
             ));
         }
     }
@@ -62,17 +143,18 @@ pub fn generate_migration_sql(
     // 4. ADD indexes on existing tables
     for td in &diff.modified_tables {
         for idx in &td.added_indexes {
-            out.push(create_index_sql(&td.schema, &td.name, idx, &q));
+            out.push(create_index(
+                generator.as_ref(),
+                &td.schema,
+                &td.name,
+                idx,
+                &q,
+            ));
         }
     }
 
-    // 5. ADD foreign keys — both for new tables and modified tables
-    for t in &diff.added_tables {
-        // FKs for added tables come via diff.modified_tables only when both
-        // sides exist; for brand-new tables we have no FK info in SchemaDiff,
-        // so the caller should include them separately if desired.
-        let _ = t;
-    }
+    // 5. ADD foreign keys. Brand-new tables carry no FK info in SchemaDiff,
+    // so only FKs between tables that exist on both sides are added here.
     for td in &diff.modified_tables {
         for fk in &td.added_fks {
             out.push(add_fk_sql(&td.schema, &td.name, fk, &q));
@@ -93,69 +175,165 @@ pub fn generate_migration_sql(
     // 7. DROP indexes
     for td in &diff.modified_tables {
         for idx in &td.removed_indexes {
-            out.push(drop_index_sql(&td.schema, &idx.name, &q, driver));
+            out.push(drop_index_sql(&td.schema, &td.name, &idx.name, &q, driver));
         }
     }
 
     // 8. DROP removed columns
+    let mut destructive = Vec::new();
     for td in &diff.modified_tables {
-        for col in &td.removed_columns {
-            out.push(format!(
-                "ALTER TABLE {} DROP COLUMN {}",
-                q.qualified(&td.schema, &td.name),
-                q.ident(&col.name),
-            ));
+        let operations: Vec<AlterColumnOperation> = td
+            .removed_columns
+            .iter()
+            .map(|col| AlterColumnOperation::DropColumn {
+                column_name: col.name.clone(),
+                cascade: false,
+            })
+            .collect();
+        if !operations.is_empty() {
+            destructive.extend(
+                generator
+                    .generate_alter_table(&alter_table(&td.schema, &td.name, operations))?
+                    .sql,
+            );
         }
     }
 
     // 9. DROP removed tables
     for t in &diff.removed_tables {
-        out.push(format!(
-            "DROP TABLE {}",
-            q.qualified(&t.table.schema, &t.table.name)
-        ));
+        let drop = DropTableDefinition {
+            schema: schema_name(&t.table.schema),
+            name: t.table.name.clone(),
+            cascade: false,
+            if_exists: false,
+        };
+        destructive.extend(generator.generate_drop_table(&drop)?.sql);
     }
 
-    Ok(out)
+    // Generators end statements with `;` and sometimes a space
+    let tidy = |v: Vec<String>| v.into_iter().map(|s| s.trim().to_string()).collect();
+    Ok(MigrationDdl {
+        statements: tidy(out),
+        destructive_statements: tidy(destructive),
+        warnings,
+    })
 }
 
-// ── SQL fragment builders ──
+// ── DDL definitions from metadata ──
 
-fn create_table_sql(t: &TableSchema, q: &Quoter) -> String {
-    let mut cols: Vec<String> = t.columns.iter().map(|c| column_sql(c, q)).collect();
-    let pks: Vec<&str> = t
+fn schema_name(schema: &str) -> Option<String> {
+    (!schema.is_empty()).then(|| schema.to_string())
+}
+
+fn alter_table(
+    schema: &str,
+    table: &str,
+    operations: Vec<AlterColumnOperation>,
+) -> AlterTableDefinition {
+    AlterTableDefinition {
+        schema: schema_name(schema),
+        name: table.to_string(),
+        operations,
+    }
+}
+
+fn table_definition(
+    t: &TableSchema,
+    source: &DbDriver,
+    target: &DbDriver,
+    warnings: &mut Vec<String>,
+) -> TableDefinition {
+    let pk: Vec<String> = t
         .columns
         .iter()
         .filter(|c| c.is_primary_key)
-        .map(|c| c.name.as_str())
+        .map(|c| c.name.clone())
         .collect();
-    if pks.len() > 1 {
-        let quoted: Vec<String> = pks.iter().map(|n| q.ident(n)).collect();
-        cols.push(format!("PRIMARY KEY ({})", quoted.join(", ")));
+    let composite = pk.len() > 1;
+
+    let columns = t
+        .columns
+        .iter()
+        .map(|c| {
+            let label = format!("{}.{}", t.table.name, c.name);
+            let mut column = column_definition(c, source, target, &label, warnings);
+            // Composite keys go in a table-level constraint
+            column.primary_key &= !composite;
+            column
+        })
+        .collect();
+
+    TableDefinition {
+        schema: schema_name(&t.table.schema),
+        name: t.table.name.clone(),
+        columns,
+        primary_key: composite.then_some(pk),
+        foreign_keys: Vec::new(),
+        unique_constraints: Vec::new(),
+        check_constraints: Vec::new(),
+        comment: None,
+        if_not_exists: false,
     }
-    format!(
-        "CREATE TABLE {} (\n  {}\n)",
-        q.qualified(&t.table.schema, &t.table.name),
-        cols.join(",\n  ")
-    )
 }
 
-fn column_sql(c: &ColumnInfo, q: &Quoter) -> String {
-    let mut parts = vec![q.ident(&c.name), c.data_type.clone()];
-    if !c.nullable {
-        parts.push("NOT NULL".to_string());
-    }
-    if let Some(d) = &c.default_value {
-        parts.push(format!("DEFAULT {}", d));
+/// Column definition for `target`; sequence defaults become auto-increment
+fn column_definition(
+    c: &ColumnInfo,
+    source: &DbDriver,
+    target: &DbDriver,
+    label: &str,
+    warnings: &mut Vec<String>,
+) -> ColumnDefinition {
+    let column_type = map_column_type(&c.data_type, source, target, label, warnings);
+    let integer = matches!(
+        column_type,
+        ColumnType::SmallInt | ColumnType::Integer | ColumnType::BigInt
+    );
+    let sequence_default = c
+        .default_value
+        .as_deref()
+        .is_some_and(|d| d.trim_start().to_lowercase().starts_with("nextval("));
+    let auto_increment = integer && (c.is_auto_increment || sequence_default);
+
+    let default = match &c.default_value {
+        Some(_) if auto_increment && sequence_default => None,
+        Some(d) => map_default(d, source, target, label, warnings),
+        None => None,
+    };
+
+    ColumnDefinition {
+        name: c.name.clone(),
+        column_type,
+        nullable: c.nullable,
+        default,
+        primary_key: c.is_primary_key,
+        auto_increment,
+        comment: None,
     }
-    // Inline PK only when the column is solo-PK; composite PKs are emitted
-    // separately by create_table_sql.
-    if c.is_primary_key {
-        parts.push("PRIMARY KEY".to_string());
+}
+
+/// CREATE INDEX through the generator, or directly if it rejects the name
+fn create_index(
+    generator: &dyn DdlGenerator,
+    schema: &str,
+    table: &str,
+    idx: &IndexInfo,
+    q: &Quoter,
+) -> String {
+    let definition = IndexDefinition {
+        name: idx.name.clone(),
+        columns: idx.columns.clone(),
+        unique: idx.is_unique,
+        index_type: IndexType::BTree,
+    };
+    match generator.generate_create_index(schema_name(schema).as_deref(), table, &definition) {
+        Ok(result) if !result.sql.is_empty() => result.sql.join("\n"),
+        _ => create_index_sql(schema, table, idx, q),
     }
-    parts.join(" ")
 }
 
+// ── SQL fragment builders ──
+
 fn create_index_sql(schema: &str, table: &str, idx: &IndexInfo, q: &Quoter) -> String {
     let unique = if idx.is_unique { "UNIQUE " } else { "" };
     let cols: Vec<String> = idx.columns.iter().map(|c| q.ident(c)).collect();
@@ -168,12 +346,19 @@ fn create_index_sql(schema: &str, table: &str, idx: &IndexInfo, q: &Quoter) -> S
     )
 }
 
-fn drop_index_sql(schema: &str, index_name: &str, q: &Quoter, driver: &DbDriver) -> String {
+fn drop_index_sql(
+    schema: &str,
+    table: &str,
+    index_name: &str,
+    q: &Quoter,
+    driver: &DbDriver,
+) -> String {
     match driver {
-        DbDriver::MySql => format!(
+        // Index names are per table in MySQL and SQL Server
+        DbDriver::MySql | DbDriver::SqlServer => format!(
             "DROP INDEX {} ON {}",
             q.ident(index_name),
-            q.qualified(schema, "")
+            q.qualified(schema, table)
         ),
         _ => format!("DROP INDEX {}", q.qualified(schema, index_name)),
     }
@@ -199,89 +384,126 @@ fn add_fk_sql(schema: &str, table: &str, fk: &ForeignKeyInfo, q: &Quoter) -> Str
     s
 }
 
+/// Statements changing a column's type, nullability and default
+///
+/// `new_type` is the source type rendered for `driver` and `default` the
+/// source default mapped to it.
+#[allow(clippy::too_many_arguments)]
 fn alter_column_sql(
     schema: &str,
     table: &str,
     source: &ColumnInfo,
     target: &ColumnInfo,
+    new_type: &str,
+    default: Option<&str>,
     q: &Quoter,
     driver: &DbDriver,
+    warnings: &mut Vec<String>,
 ) -> Vec<String> {
     let qualified = q.qualified(schema, table);
     let col = q.ident(&source.name);
+    let label = format!("{}.{}", table, source.name);
+    let type_changed = !types_equivalent(&source.data_type, &target.data_type);
+    let null_changed = source.nullable != target.nullable;
+    let default_changed = source.default_value != target.default_value;
     let mut out = Vec::new();
 
-    if !source.data_type.eq_ignore_ascii_case(&target.data_type) {
-        match driver {
-            DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon => {
+    if type_changed {
+        warnings.push(format!(
+            "{}: changing the type from {} to {} fails or truncates if existing values do not convert",
+            label, target.data_type, source.data_type
+        ));
+    }
+    if null_changed && !source.nullable {
+        warnings.push(format!(
+            "{}: SET NOT NULL fails if the column contains NULLs",
+            label
+        ));
+    }
+
+    match driver {
+        DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon => {
+            if type_changed {
                 out.push(format!(
                     "ALTER TABLE {} ALTER COLUMN {} TYPE {}",
-                    qualified, col, source.data_type
+                    qualified, col, new_type
                 ));
             }
-            DbDriver::MySql => {
+            if null_changed {
+                let action = if source.nullable {
+                    "DROP NOT NULL"
+                } else {
+                    "SET NOT NULL"
+                };
+                out.push(format!(
+                    "ALTER TABLE {} ALTER COLUMN {} {}",
+                    qualified, col, action
+                ));
+            }
+        }
+        // Both restate the whole column, so type and nullability change together
+        DbDriver::MySql => {
+            if type_changed || null_changed {
                 out.push(format!(
                     "ALTER TABLE {} MODIFY COLUMN {} {}{}",
                     qualified,
                     col,
-                    source.data_type,
-                    if source.nullable { "" } else { " NOT NULL" }
+                    new_type,
+                    if source.nullable {
+                        " NULL"
+                    } else {
+                        " NOT NULL"
+                    }
                 ));
             }
-            DbDriver::SqlServer => {
+        }
+        DbDriver::SqlServer => {
+            if type_changed || null_changed {
                 out.push(format!(
                     "ALTER TABLE {} ALTER COLUMN {} {}{}",
                     qualified,
                     col,
-                    source.data_type,
-                    if source.nullable { " NULL" } else { " NOT NULL" }
+                    new_type,
+                    if source.nullable {
+                        " NULL"
+                    } else {
+                        " NOT NULL"
+                    }
                 ));
             }
-            DbDriver::Sqlite | DbDriver::Turso => {
+        }
+        DbDriver::Sqlite | DbDriver::Turso => {
+            if type_changed || null_changed || default_changed {
+                warnings.push(format!(
+                    "{}: SQLite cannot alter columns; recreate the table to apply this change",
+                    label
+                ));
                 out.push(format!(
-                    "-- SQLite does not support ALTER COLUMN TYPE for {}.{}",
+                    "-- SQLite does not support ALTER COLUMN for {}.{}",
                     qualified, col
                 ));
             }
-            _ => {}
+            return out;
         }
+        DbDriver::MongoDb | DbDriver::Redis => {}
     }
 
-    if source.nullable != target.nullable {
+    if default_changed {
         match driver {
-            DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon => {
-                if source.nullable {
-                    out.push(format!(
-                        "ALTER TABLE {} ALTER COLUMN {} DROP NOT NULL",
-                        qualified, col
-                    ));
-                } else {
-                    out.push(format!(
-                        "ALTER TABLE {} ALTER COLUMN {} SET NOT NULL",
-                        qualified, col
-                    ));
-                }
-            }
-            _ => {}
-        }
-    }
-
-    if source.default_value != target.default_value {
-        match driver {
-            DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon => {
-                if let Some(d) = &source.default_value {
-                    out.push(format!(
-                        "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {}",
-                        qualified, col, d
-                    ));
-                } else {
-                    out.push(format!(
-                        "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT",
-                        qualified, col
-                    ));
-                }
-            }
-            _ => {}
+            DbDriver::SqlServer => warnings.push(format!(
+                "{}: SQL Server defaults are named constraints; change the default by hand",
+                label
+            )),
+            _ => out.push(match default {
+                Some(d) => format!(
+                    "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {}",
+                    qualified, col, d
+                ),
+                None => format!(
+                    "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT",
+                    qualified, col
+                ),
+            }),
         }
     }
 
@@ -298,11 +520,22 @@ struct Quoter {
 impl Quoter {
     fn for_driver(driver: &DbDriver) -> Result<Self, DbError> {
         match driver {
-            DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon | DbDriver::Sqlite | DbDriver::Turso => {
-                Ok(Self { open: '"', close: '"' })
-            }
-            DbDriver::MySql => Ok(Self { open: '`', close: '`' }),
-            DbDriver::SqlServer => Ok(Self { open: '[', close: ']' }),
+            DbDriver::Postgres
+            | DbDriver::Supabase
+            | DbDriver::Neon
+            | DbDriver::Sqlite
+            | DbDriver::Turso => Ok(Self {
+                open: '"',
+                close: '"',
+            }),
+            DbDriver::MySql => Ok(Self {
+                open: '`',
+                close: '`',
+            }),
+            DbDriver::SqlServer => Ok(Self {
+                open: '[',
+                close: ']',
+            }),
             DbDriver::MongoDb | DbDriver::Redis => Err(DbError::InvalidInput(
                 "Schema migrations are not supported for this driver".to_string(),
             )),
@@ -363,4 +596,98 @@ mod tests {
         let sql = generate_migration_sql(&d, &DbDriver::Postgres).unwrap();
         assert!(sql.iter().any(|s| s.starts_with("DROP TABLE")));
     }
+
+    #[test]
+    fn separates_destructive_statements() {
+        let src = vec![tbl(
+            "users",
+            vec![
+                ColumnInfo::new("id".into(), "integer".into(), false),
+                ColumnInfo::new("email".into(), "text".into(), true),
+            ],
+        )];
+        let tgt = vec![
+            tbl(
+                "users",
+                vec![
+                    ColumnInfo::new("id".into(), "integer".into(), false),
+                    ColumnInfo::new("legacy".into(), "text".into(), true),
+                ],
+            ),
+            tbl(
+                "old",
+                vec![ColumnInfo::new("id".into(), "integer".into(), false)],
+            ),
+        ];
+        let ddl = generate_migration_ddl(&compute_diff(&src, &tgt), &DbDriver::Postgres).unwrap();
+        assert_eq!(
+            ddl.statements,
+            vec![r#"ALTER TABLE "public"."users" ADD COLUMN "email" text;"#]
+        );
+        assert_eq!(
+            ddl.destructive_statements,
+            vec![
+                r#"ALTER TABLE "public"."users" DROP COLUMN "legacy";"#,
+                r#"DROP TABLE "public"."old";"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_types_across_dialects() {
+        let mut id = ColumnInfo::new("id".into(), "uuid".into(), false);
+        id.is_primary_key = true;
+        let src = vec![tbl(
+            "events",
+            vec![
+                id,
+                ColumnInfo::new("at".into(), "timestamp with time zone".into(), false),
+                ColumnInfo::new("name".into(), "character varying(80)".into(), true),
+            ],
+        )];
+        let mut diff = compute_diff(&src, &[]);
+        diff.source_driver = Some(DbDriver::Postgres);
+
+        let ddl = generate_migration_ddl(&diff, &DbDriver::MySql).unwrap();
+        let create = &ddl.statements[0];
+        assert!(create.contains("`id` CHAR(36) NOT NULL"), "{}", create);
+        assert!(create.contains("`name` VARCHAR(80)"), "{}", create);
+        assert_eq!(ddl.warnings.len(), 2, "{:?}", ddl.warnings);
+        assert!(ddl.warnings[1].contains("time zone is not stored"));
+    }
+
+    #[test]
+    fn alters_type_and_nullability() {
+        let src = vec![tbl(
+            "t",
+            vec![ColumnInfo::new("n".into(), "bigint".into(), false)],
+        )];
+        let tgt = vec![tbl(
+            "t",
+            vec![ColumnInfo::new("n".into(), "int4".into(), true)],
+        )];
+        let diff = compute_diff(&src, &tgt);
+
+        let pg = generate_migration_ddl(&diff, &DbDriver::Postgres).unwrap();
+        assert_eq!(
+            pg.statements,
+            vec![
+                r#"ALTER TABLE "public"."t" ALTER COLUMN "n" TYPE BIGINT"#,
+                r#"ALTER TABLE "public"."t" ALTER COLUMN "n" SET NOT NULL"#,
+            ]
+        );
+        let mysql = generate_migration_ddl(&diff, &DbDriver::MySql).unwrap();
+        assert_eq!(
+            mysql.statements,
+            vec!["ALTER TABLE `public`.`t` MODIFY COLUMN `n` BIGINT NOT NULL"]
+        );
+        assert_eq!(mysql.warnings.len(), 2);
+
+        // Same type under another spelling is not a change
+        let tgt = vec![tbl(
+            "t",
+            vec![ColumnInfo::new("n".into(), "INT8".into(), false)],
+        )];
+        assert!(compute_diff(&src, &tgt).modified_tables.is_empty());
+    }
 }
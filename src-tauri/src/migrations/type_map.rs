@@ -0,0 +1,346 @@
+//! Column type mapping between SQL dialects.
+//!
+//! Metadata reports column types as the server spells them (`int4`,
+//! `character varying(255)`, `datetime2`, `tinyint(1)`). To compare schemas
+//! from different servers, or to recreate a column on another server, those
+//! spellings are read into a portable `ColumnType` that each `DdlGenerator`
+//! renders natively. Where the mapping can change behaviour (lost time zone,
+//! arrays stored as JSON, ...) a warning is produced instead of guessing
+//! silently.
+
+use crate::models::ddl::ColumnType;
+use crate::models::DbDriver;
+
+/// Dialect family of a driver, for messages and same-dialect checks
+pub fn dialect_of(driver: &DbDriver) -> &'static str {
+    match driver {
+        DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon => "PostgreSQL",
+        DbDriver::MySql => "MySQL",
+        DbDriver::Sqlite | DbDriver::Turso => "SQLite",
+        DbDriver::SqlServer => "SQL Server",
+        DbDriver::MongoDb => "MongoDB",
+        DbDriver::Redis => "Redis",
+    }
+}
+
+/// Read a metadata type name into a portable type
+///
+/// Returns `None` for types without a portable equivalent. The second value
+/// notes when the source spelling itself is ambiguous (e.g. `float`, whose
+/// precision differs between servers).
+pub fn parse_column_type(data_type: &str) -> Option<(ColumnType, Option<&'static str>)> {
+    let normalized = data_type
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+
+    if let Some(element) = normalized.strip_suffix("[]") {
+        let (element, _) = parse_column_type(element)?;
+        return Some((
+            ColumnType::Array {
+                element_type: Box::new(element),
+            },
+            None,
+        ));
+    }
+
+    let (name, args) = match normalized.split_once('(') {
+        Some((name, rest)) => {
+            let (args, suffix) = rest.split_once(')')?;
+            // `timestamp(3) with time zone` keeps its suffix
+            let name = format!("{} {}", name.trim(), suffix.trim());
+            (name.trim().to_string(), Some(args.replace(' ', "")))
+        }
+        None => (normalized, None),
+    };
+    let length = || args.as_deref().and_then(|a| a.parse::<u32>().ok());
+
+    let parsed = match (name.as_str(), args.as_deref()) {
+        ("smallint" | "int2" | "smallserial", _) => (ColumnType::SmallInt, None),
+        ("integer" | "int" | "int4" | "serial" | "mediumint", _) => (ColumnType::Integer, None),
+        ("bigint" | "int8" | "bigserial", _) => (ColumnType::BigInt, None),
+        ("tinyint", Some("1")) => (ColumnType::Boolean, Some("TINYINT(1) is read as BOOLEAN")),
+        ("tinyint", _) => (ColumnType::SmallInt, None),
+        ("numeric" | "decimal", Some(args)) => {
+            let (precision, scale) = args.split_once(',').unwrap_or((args, "0"));
+            (
+                ColumnType::Decimal {
+                    precision: precision.parse().ok()?,
+                    scale: scale.parse().ok()?,
+                },
+                None,
+            )
+        }
+        ("real" | "float4", _) => (ColumnType::Real, None),
+        ("double precision" | "double" | "float8", _) => (ColumnType::DoublePrecision, None),
+        ("float", _) => (
+            ColumnType::DoublePrecision,
+            Some("FLOAT precision differs between servers; read as DOUBLE PRECISION"),
+        ),
+        ("varchar" | "character varying" | "nvarchar", Some("max"))
+        | ("character varying", None) => (ColumnType::Text, None),
+        ("varchar" | "character varying" | "nvarchar", Some(_)) => {
+            (ColumnType::Varchar { length: length()? }, None)
+        }
+        ("char" | "character" | "nchar" | "bpchar", None) => (ColumnType::Char { length: 1 }, None),
+        ("char" | "character" | "nchar" | "bpchar", Some(_)) => {
+            (ColumnType::Char { length: length()? }, None)
+        }
+        ("text" | "tinytext" | "mediumtext" | "longtext" | "ntext" | "clob", None) => {
+            (ColumnType::Text, None)
+        }
+        ("bytea" | "blob" | "tinyblob" | "mediumblob" | "longblob" | "image", None)
+        | ("varbinary", Some("max")) => (ColumnType::Bytea, None),
+        ("boolean" | "bool", None) | ("bit", None | Some("1")) => (ColumnType::Boolean, None),
+        ("date", None) => (ColumnType::Date, None),
+        ("time" | "time without time zone", _) => (ColumnType::Time, None),
+        (
+            "timestamp"
+            | "timestamp without time zone"
+            | "datetime"
+            | "datetime2"
+            | "smalldatetime",
+            _,
+        ) => (ColumnType::Timestamp, None),
+        ("timestamptz" | "timestamp with time zone" | "datetimeoffset", _) => {
+            (ColumnType::TimestampTz, None)
+        }
+        ("json", None) => (ColumnType::Json, None),
+        ("jsonb", None) => (ColumnType::JsonB, None),
+        ("uuid" | "uniqueidentifier", None) => (ColumnType::Uuid, None),
+        _ => return None,
+    };
+    Some(parsed)
+}
+
+fn is_integer(column_type: &ColumnType) -> bool {
+    matches!(
+        column_type,
+        ColumnType::SmallInt | ColumnType::Integer | ColumnType::BigInt
+    )
+}
+
+/// Whether two metadata type names describe the same type
+///
+/// Spellings are compared through [`parse_column_type`], so `int4` and
+/// `INTEGER` match; ambiguous spellings only match themselves.
+pub fn types_equivalent(a: &str, b: &str) -> bool {
+    if a.trim().eq_ignore_ascii_case(b.trim()) {
+        return true;
+    }
+    matches!(
+        (parse_column_type(a), parse_column_type(b)),
+        (Some((x, None)), Some((y, None))) if x == y
+    )
+}
+
+/// What `target` does with a portable type it has no native form of
+fn target_caveat(column_type: &ColumnType, target: &DbDriver) -> Option<&'static str> {
+    match (dialect_of(target), column_type) {
+        ("MySQL" | "SQLite", ColumnType::TimestampTz) => Some("the time zone is not stored"),
+        ("MySQL", ColumnType::Uuid) => Some("stored as CHAR(36)"),
+        ("MySQL", ColumnType::Array { .. }) => Some("the array is stored as JSON"),
+        ("SQLite", ColumnType::Uuid) => Some("stored as TEXT"),
+        ("SQLite", ColumnType::Array { .. }) => Some("the array is stored as JSON text"),
+        ("SQL Server", ColumnType::Json | ColumnType::JsonB) => {
+            Some("stored as NVARCHAR(MAX) without JSON validation")
+        }
+        ("SQL Server", ColumnType::Array { .. }) => Some("the array is stored as NVARCHAR(MAX)"),
+        _ => None,
+    }
+}
+
+/// Type to create a column with on `target`, given its `source` metadata type
+///
+/// Within one dialect the type is kept as written (integer types are read so
+/// generators can make them auto-increment). Across dialects it is mapped
+/// through [`parse_column_type`]; `column` names the column in warnings.
+pub fn map_column_type(
+    data_type: &str,
+    source: &DbDriver,
+    target: &DbDriver,
+    column: &str,
+    warnings: &mut Vec<String>,
+) -> ColumnType {
+    let parsed = parse_column_type(data_type);
+    let custom = ColumnType::Custom {
+        type_name: data_type.to_string(),
+    };
+
+    if dialect_of(source) == dialect_of(target) {
+        return match parsed {
+            Some((column_type, None)) if is_integer(&column_type) => column_type,
+            _ => custom,
+        };
+    }
+
+    match parsed {
+        None => {
+            warnings.push(format!(
+                "{}: type {} has no known {} equivalent and is copied as written",
+                column,
+                data_type,
+                dialect_of(target)
+            ));
+            custom
+        }
+        Some((column_type, note)) => {
+            if let Some(note) = note {
+                warnings.push(format!("{}: {}", column, note));
+            }
+            if let Some(caveat) = target_caveat(&column_type, target) {
+                warnings.push(format!(
+                    "{}: {} has no exact {} type; {}",
+                    column,
+                    data_type,
+                    dialect_of(target),
+                    caveat
+                ));
+            }
+            column_type
+        }
+    }
+}
+
+/// Default expression to use on `target`, if it can be carried over
+///
+/// Across dialects only literals and the current timestamp are kept; other
+/// expressions (sequence calls, functions) are dropped with a warning.
+pub fn map_default(
+    default: &str,
+    source: &DbDriver,
+    target: &DbDriver,
+    column: &str,
+    warnings: &mut Vec<String>,
+) -> Option<String> {
+    if dialect_of(source) == dialect_of(target) {
+        return Some(default.to_string());
+    }
+
+    let trimmed = default.trim();
+    // PostgreSQL casts literals: 'draft'::character varying
+    let literal = match trimmed.rfind("::") {
+        Some(pos) if trimmed.starts_with('\'') => &trimmed[..pos],
+        _ => trimmed,
+    };
+    let lower = literal.to_lowercase();
+
+    let is_string = literal.len() >= 2 && literal.starts_with('\'') && literal.ends_with('\'');
+    if is_string || lower == "null" || literal.parse::<f64>().is_ok() {
+        return Some(literal.to_string());
+    }
+    if matches!(
+        lower.as_str(),
+        "current_timestamp" | "now()" | "current_timestamp()" | "getdate()" | "sysdatetime()"
+    ) {
+        return Some("CURRENT_TIMESTAMP".to_string());
+    }
+
+    warnings.push(format!(
+        "{}: default {} is specific to {} and was left out",
+        column,
+        default,
+        dialect_of(source)
+    ));
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_column_type() {
+        assert_eq!(parse_column_type("INT4"), Some((ColumnType::Integer, None)));
+        assert_eq!(
+            parse_column_type("character varying(255)"),
+            Some((ColumnType::Varchar { length: 255 }, None))
+        );
+        assert_eq!(
+            parse_column_type("numeric(10, 2)"),
+            Some((
+                ColumnType::Decimal {
+                    precision: 10,
+                    scale: 2
+                },
+                None
+            ))
+        );
+        assert_eq!(
+            parse_column_type("timestamp(3) with time zone"),
+            Some((ColumnType::TimestampTz, None))
+        );
+        assert_eq!(
+            parse_column_type("text[]"),
+            Some((
+                ColumnType::Array {
+                    element_type: Box::new(ColumnType::Text)
+                },
+                None
+            ))
+        );
+        assert!(matches!(
+            parse_column_type("tinyint(1)"),
+            Some((ColumnType::Boolean, Some(_)))
+        ));
+        assert_eq!(parse_column_type("geometry"), None);
+
+        assert!(types_equivalent("int4", "INTEGER"));
+        assert!(!types_equivalent("float", "double"));
+        assert!(!types_equivalent("varchar(10)", "varchar(20)"));
+    }
+
+    #[test]
+    fn test_map_column_type_across_dialects() {
+        let mut warnings = Vec::new();
+        let pg = DbDriver::Postgres;
+        let mysql = DbDriver::MySql;
+
+        assert_eq!(
+            map_column_type("character varying(40)", &pg, &pg, "t.c", &mut warnings),
+            ColumnType::Custom {
+                type_name: "character varying(40)".to_string()
+            }
+        );
+        assert_eq!(
+            map_column_type("uuid", &pg, &mysql, "t.id", &mut warnings),
+            ColumnType::Uuid
+        );
+        assert_eq!(
+            map_column_type("tsvector", &pg, &mysql, "t.doc", &mut warnings),
+            ColumnType::Custom {
+                type_name: "tsvector".to_string()
+            }
+        );
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("t.id: uuid has no exact MySQL type"));
+
+        let mut warnings = Vec::new();
+        assert_eq!(
+            map_default(
+                "'draft'::character varying",
+                &pg,
+                &mysql,
+                "t.s",
+                &mut warnings
+            ),
+            Some("'draft'".to_string())
+        );
+        assert_eq!(
+            map_default("now()", &pg, &mysql, "t.at", &mut warnings),
+            Some("CURRENT_TIMESTAMP".to_string())
+        );
+        assert_eq!(
+            map_default(
+                "nextval('t_id_seq'::regclass)",
+                &pg,
+                &mysql,
+                "t.id",
+                &mut warnings
+            ),
+            None
+        );
+        assert_eq!(warnings.len(), 1);
+    }
+}
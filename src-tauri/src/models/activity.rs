@@ -244,6 +244,34 @@ impl QueryLog {
     }
 }
 
+/// A query running, or waiting for a free slot, on a connection
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningQuery {
+    /// Same as the ID of the query's log entry
+    pub id: String,
+
+    /// Connection ID the query runs on
+    pub connection_id: String,
+
+    /// Editor tab that ran the query, when the caller passed one
+    pub tab_id: Option<String>,
+
+    /// SQL query text
+    pub sql: String,
+
+    /// When the query was submitted
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub started_at: DateTime<Utc>,
+
+    /// Waiting for another query on the connection to finish (see
+    /// `QuerySettings::max_concurrent_queries`)
+    pub queued: bool,
+
+    /// Running in the connection's open transaction session
+    pub in_transaction: bool,
+}
+
 /// Query log filter options
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -447,6 +475,90 @@ pub struct ActivityStats {
     pub queries_by_status: std::collections::HashMap<String, usize>,
 }
 
+/// Bucket size for activity time series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeBucket {
+    /// One bucket per hour
+    Hourly,
+    /// One bucket per day, starting at local midnight
+    Daily,
+    /// One bucket per week, starting at local midnight on Monday
+    Weekly,
+}
+
+impl TimeBucket {
+    /// Length of one bucket in seconds
+    pub fn seconds(&self) -> i64 {
+        match self {
+            TimeBucket::Hourly => 3_600,
+            TimeBucket::Daily => 86_400,
+            TimeBucket::Weekly => 7 * 86_400,
+        }
+    }
+
+    /// Start of the bucket containing `timestamp`
+    ///
+    /// Buckets are aligned to local time, so `utc_offset_secs` shifts the
+    /// boundaries (e.g. days start at local rather than UTC midnight).
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - Unix timestamp in seconds
+    /// * `utc_offset_secs` - Local offset from UTC in seconds (east positive)
+    ///
+    /// # Returns
+    ///
+    /// Unix timestamp of the bucket start
+    pub fn bucket_start(&self, timestamp: i64, utc_offset_secs: i64) -> i64 {
+        // The Unix epoch fell on a Thursday; weeks start four days later
+        let anchor = match self {
+            TimeBucket::Weekly => 4 * 86_400,
+            _ => 0,
+        };
+        let local = timestamp + utc_offset_secs - anchor;
+        local - local.rem_euclid(self.seconds()) + anchor - utc_offset_secs
+    }
+}
+
+/// Aggregated activity for one time bucket
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityBucket {
+    /// Bucket start timestamp
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub bucket_start: DateTime<Utc>,
+    /// Queries started in this bucket
+    pub total_queries: usize,
+    /// Failed queries started in this bucket
+    pub failed_queries: usize,
+    /// Average query duration (ms), 0 when no query finished
+    pub avg_duration: f64,
+}
+
+/// Executions of one normalized query that exceeded the slow-query threshold
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowQueryGroup {
+    /// Query with literals replaced by placeholders
+    pub normalized_sql: String,
+    /// SQL of the most recent slow execution
+    pub sample_sql: String,
+    /// Number of slow executions
+    pub count: usize,
+    /// Median duration (ms)
+    pub p50_duration: u64,
+    /// 95th percentile duration (ms)
+    pub p95_duration: u64,
+    /// Longest duration (ms)
+    pub max_duration: u64,
+    /// Start of the most recent slow execution
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub last_occurrence: DateTime<Utc>,
+    /// Connection of the most recent slow execution
+    pub connection_name: String,
+}
+
 /// Export format options
 #[derive(Debug, Clone, Copy, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -578,6 +690,33 @@ mod tests {
         assert_eq!(log.error, Some("Table does not exist".to_string()));
     }
 
+    #[test]
+    fn test_time_bucket_start() {
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().timestamp();
+        // A Wednesday
+        let ts = at("2025-11-19T23:30:00Z");
+
+        assert_eq!(TimeBucket::Hourly.bucket_start(ts, 0), at("2025-11-19T23:00:00Z"));
+        assert_eq!(TimeBucket::Daily.bucket_start(ts, 0), at("2025-11-19T00:00:00Z"));
+        assert_eq!(TimeBucket::Weekly.bucket_start(ts, 0), at("2025-11-17T00:00:00Z"));
+
+        // At UTC+2 it is already Thursday, and the day starts at 22:00 UTC
+        assert_eq!(
+            TimeBucket::Daily.bucket_start(ts, 2 * 3_600),
+            at("2025-11-19T22:00:00Z")
+        );
+        // Half-hour offsets shift hourly buckets too
+        assert_eq!(
+            TimeBucket::Hourly.bucket_start(ts, 5 * 3_600 + 1_800),
+            at("2025-11-19T23:30:00Z")
+        );
+        // At UTC-8 it is Wednesday 15:30, in the week starting Monday 08:00 UTC
+        assert_eq!(
+            TimeBucket::Weekly.bucket_start(ts, -8 * 3_600),
+            at("2025-11-17T08:00:00Z")
+        );
+    }
+
     #[test]
     fn test_query_log_filter_matches() {
         let log = QueryLog::new(
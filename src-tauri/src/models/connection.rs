@@ -37,6 +37,27 @@ impl DbDriver {
     }
 }
 
+/// Schema a driver's objects live in when none is named
+///
+/// `public` for the Postgres family, `dbo` for SQL Server and `main` for
+/// SQLite and Turso. MySQL schemas are databases, so it's the connected
+/// `database` (`mysql` when none is set, as the driver does). MongoDB and
+/// Redis report a single placeholder schema.
+pub fn default_schema_for(driver: &DbDriver, database: Option<&str>) -> String {
+    match driver {
+        DbDriver::Postgres | DbDriver::Supabase | DbDriver::Neon | DbDriver::MongoDb => {
+            "public".to_string()
+        }
+        DbDriver::MySql => database
+            .filter(|db| !db.is_empty())
+            .unwrap_or("mysql")
+            .to_string(),
+        DbDriver::SqlServer => "dbo".to_string(),
+        DbDriver::Sqlite | DbDriver::Turso => "main".to_string(),
+        DbDriver::Redis => "keys".to_string(),
+    }
+}
+
 /// SSL/TLS connection mode
 ///
 /// Defines how SSL/TLS should be handled for database connections.
@@ -79,6 +100,14 @@ pub enum Environment {
     Production,
 }
 
+impl Environment {
+    /// Whether destructive statements against this environment must always be
+    /// confirmed, regardless of the global `confirm_destructive` setting
+    pub fn requires_confirmation(&self) -> bool {
+        matches!(self, Environment::Production)
+    }
+}
+
 /// SSH tunnel configuration
 ///
 /// Configuration for establishing an SSH tunnel to access a remote database.
@@ -109,6 +138,44 @@ pub struct SshConfig {
     pub local_port: u16,
 }
 
+/// SQLite pragmas applied when the database file is opened
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SqliteOptions {
+    /// Milliseconds a statement waits for a lock held by another connection
+    /// before failing with `database is locked` (`PRAGMA busy_timeout`)
+    pub busy_timeout_ms: u32,
+
+    /// Use write-ahead logging (`PRAGMA journal_mode = WAL`), so readers and
+    /// a writer don't block each other. The file must be on a local disk:
+    /// WAL needs shared memory, which network file systems don't provide.
+    pub wal: bool,
+
+    /// Enforce foreign key constraints (`PRAGMA foreign_keys`); SQLite
+    /// leaves them off unless asked
+    pub foreign_keys: bool,
+}
+
+impl Default for SqliteOptions {
+    fn default() -> Self {
+        Self {
+            busy_timeout_ms: 5000,
+            wal: false,
+            foreign_keys: true,
+        }
+    }
+}
+
+/// Connect timeout of profiles that don't set one, in seconds
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 30;
+
+/// Client name of profiles that don't set one
+pub const DEFAULT_APPLICATION_NAME: &str = "DB-Hive";
+
+/// Longest client name Postgres keeps (`NAMEDATALEN - 1`); it silently cuts
+/// longer ones
+const MAX_APPLICATION_NAME_LEN: usize = 63;
+
 /// Connection profile
 ///
 /// Represents a saved database connection with all necessary configuration.
@@ -161,7 +228,7 @@ pub struct ConnectionProfile {
 
     /// Total connection count (how many times this connection was used)
     #[serde(default)]
-    pub connection_count: u32,
+    pub connection_count: u64,
 
     /// Favorite/starred status for quick access
     #[serde(default)]
@@ -175,6 +242,41 @@ pub struct ConnectionProfile {
     #[serde(default)]
     pub description: Option<String>,
 
+    /// Transparently re-establish the connection (and SSH tunnel) with the
+    /// saved credentials when it drops
+    #[serde(default)]
+    pub auto_reconnect: bool,
+
+    /// Tables pinned to the top of the schema tree, as qualified
+    /// `schema.table` names
+    #[serde(default)]
+    pub pinned_tables: Vec<String>,
+
+    /// Seconds a connection may sit idle before a keepalive ping is sent;
+    /// `None` (or 0) disables keepalive for this connection
+    #[serde(default)]
+    pub keepalive_interval_secs: Option<u32>,
+
+    /// Seconds to wait for the server when connecting; `None` uses the
+    /// default of 30
+    #[serde(default)]
+    pub connect_timeout_secs: Option<u32>,
+
+    /// Client name shown in the server's session list; `None` uses
+    /// "DB-Hive"
+    #[serde(default)]
+    pub application_name: Option<String>,
+
+    /// Schema unqualified names resolve to: Postgres puts it first on the
+    /// `search_path`, MySQL makes it the current database. Schema browsing
+    /// commands also fall back to it when no schema is given.
+    #[serde(default)]
+    pub default_schema: Option<String>,
+
+    /// Pragmas set on connect; only used by SQLite profiles
+    #[serde(default)]
+    pub sqlite: SqliteOptions,
+
     /// Created timestamp (Unix timestamp in seconds)
     #[serde(default = "current_timestamp")]
     pub created_at: i64,
@@ -230,11 +332,135 @@ impl ConnectionProfile {
             is_favorite: false,
             color: None,
             description: None,
+            auto_reconnect: false,
+            pinned_tables: Vec::new(),
+            keepalive_interval_secs: None,
+            connect_timeout_secs: None,
+            application_name: None,
+            default_schema: None,
+            sqlite: SqliteOptions::default(),
             created_at: now,
             updated_at: now,
         }
     }
 
+    /// Seconds to wait for the server when connecting
+    pub fn connect_timeout(&self) -> u64 {
+        self.connect_timeout_secs
+            .filter(|secs| *secs > 0)
+            .map_or(DEFAULT_CONNECT_TIMEOUT_SECS, u64::from)
+    }
+
+    /// The configured default schema, ignoring a blank value
+    pub fn default_schema_name(&self) -> Option<&str> {
+        self.default_schema
+            .as_deref()
+            .map(str::trim)
+            .filter(|schema| !schema.is_empty())
+    }
+
+    /// Client name to report to the server for this profile's sessions
+    ///
+    /// The profile's `application_name` (or "DB-Hive") followed by the
+    /// start of the connection ID, e.g. `DB-Hive [3f2a9c1e]`, so sessions in
+    /// `pg_stat_activity` or `SHOW PROCESSLIST` can be traced back to a
+    /// connection. Cut to 63 bytes so the ID survives on Postgres.
+    pub fn session_application_name(&self) -> String {
+        let base = self
+            .application_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(DEFAULT_APPLICATION_NAME);
+        let short_id: String = self.id.chars().take(8).collect();
+        if short_id.is_empty() {
+            return base.chars().take(MAX_APPLICATION_NAME_LEN).collect();
+        }
+
+        let suffix = format!(" [{}]", short_id);
+        let mut name = String::new();
+        for c in base.chars() {
+            if name.len() + c.len_utf8() + suffix.len() > MAX_APPLICATION_NAME_LEN {
+                break;
+            }
+            name.push(c);
+        }
+        name.push_str(&suffix);
+        name
+    }
+
+    /// Record a successful connect at `now` (Unix timestamp in seconds)
+    pub fn record_connect(&mut self, now: i64) {
+        self.last_connected_at = Some(now);
+        self.connection_count += 1;
+    }
+
+    /// Copy usage stats from the stored version of this profile
+    ///
+    /// Profile edits come from the frontend with whatever stats it loaded,
+    /// which may be older than connects recorded since.
+    pub fn keep_usage_from(&mut self, stored: &ConnectionProfile) {
+        self.last_connected_at = stored.last_connected_at;
+        self.connection_count = stored.connection_count;
+    }
+
+    /// Whether `other` connects to the same database as the same user
+    ///
+    /// Compares driver, host, port, database and username, ignoring SSH,
+    /// SSL and cosmetic settings. Hosts compare case-insensitively, with
+    /// `127.0.0.1` and `::1` counting as `localhost`; a missing database
+    /// equals an empty one. SQLite profiles compare by file path only.
+    pub fn same_target_as(&self, other: &ConnectionProfile) -> bool {
+        fn host_key(host: &str) -> String {
+            match host.trim().to_ascii_lowercase().as_str() {
+                "127.0.0.1" | "::1" | "[::1]" => "localhost".to_string(),
+                h => h.to_string(),
+            }
+        }
+        fn database_key(database: &Option<String>) -> &str {
+            database.as_deref().map(str::trim).unwrap_or_default()
+        }
+
+        if self.driver != other.driver {
+            return false;
+        }
+        if self.driver == DbDriver::Sqlite {
+            return self.host.trim() == other.host.trim();
+        }
+        host_key(&self.host) == host_key(&other.host)
+            && self.port == other.port
+            && database_key(&self.database) == database_key(&other.database)
+            && self.username.trim() == other.username.trim()
+    }
+
+    /// Qualified `schema.table` name used as the pin key
+    pub fn pinned_table_key(schema: &str, table: &str) -> String {
+        format!("{}.{}", schema, table)
+    }
+
+    /// Whether `schema.table` is pinned
+    pub fn is_table_pinned(&self, schema: &str, table: &str) -> bool {
+        let key = Self::pinned_table_key(schema, table);
+        self.pinned_tables.iter().any(|t| t == &key)
+    }
+
+    /// Pin `schema.table`; returns `false` if it was already pinned
+    pub fn pin_table(&mut self, schema: &str, table: &str) -> bool {
+        if self.is_table_pinned(schema, table) {
+            return false;
+        }
+        self.pinned_tables.push(Self::pinned_table_key(schema, table));
+        true
+    }
+
+    /// Unpin `schema.table`; returns `false` if it was not pinned
+    pub fn unpin_table(&mut self, schema: &str, table: &str) -> bool {
+        let key = Self::pinned_table_key(schema, table);
+        let before = self.pinned_tables.len();
+        self.pinned_tables.retain(|t| t != &key);
+        self.pinned_tables.len() != before
+    }
+
     /// Get the default port for a given database driver
     pub fn default_port_for_driver(driver: &DbDriver) -> u16 {
         match driver {
@@ -266,10 +492,211 @@ pub enum ConnectionStatus {
     Error(String),
 }
 
+/// Health of an active connection, as last observed by the health monitor
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionHealth {
+    /// Connection ID (same as profile ID)
+    pub connection_id: String,
+
+    /// Result of the most recent check
+    pub status: ConnectionStatus,
+
+    /// When the connection last passed a check (RFC 3339)
+    pub last_healthy_at: Option<String>,
+
+    /// When the connection was last checked (RFC 3339)
+    pub last_checked_at: String,
+}
+
+/// Round-trip latency of an active connection
+///
+/// Built from the most recent ping times, oldest first, recorded by
+/// `ping_connection` and the health monitor.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionLatency {
+    /// Connection ID (same as profile ID)
+    pub connection_id: String,
+
+    /// Latest ping, in milliseconds
+    pub latency_ms: u64,
+
+    /// Mean of the recorded pings, in milliseconds
+    pub average_ms: u64,
+
+    /// Recorded pings, oldest first, for a sparkline
+    pub samples: Vec<u64>,
+
+    /// The latest ping took well over the average of the ones before it
+    pub degraded: bool,
+}
+
+impl ConnectionLatency {
+    /// A ping this many times slower than the previous average is degraded
+    const DEGRADED_FACTOR: u64 = 3;
+
+    /// Pings at or under this many milliseconds never count as degraded
+    const DEGRADED_FLOOR_MS: u64 = 50;
+
+    /// Summarize `samples` (oldest first); `None` if there are none
+    pub fn from_samples(connection_id: &str, samples: &[u64]) -> Option<Self> {
+        let (&latency_ms, previous) = samples.split_last()?;
+        let average_ms = samples.iter().sum::<u64>() / samples.len() as u64;
+        let degraded = !previous.is_empty()
+            && latency_ms > Self::DEGRADED_FLOOR_MS
+            && latency_ms
+                > Self::DEGRADED_FACTOR * previous.iter().sum::<u64>() / previous.len() as u64;
+
+        Some(Self {
+            connection_id: connection_id.to_string(),
+            latency_ms,
+            average_ms,
+            samples: samples.to_vec(),
+            degraded,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_pin_and_unpin_table() {
+        let mut profile = ConnectionProfile::new(
+            "test-id".to_string(),
+            "Test DB".to_string(),
+            DbDriver::Postgres,
+            "localhost".to_string(),
+            5432,
+            "postgres".to_string(),
+        );
+
+        assert!(profile.pin_table("public", "users"));
+        assert!(!profile.pin_table("public", "users"));
+        assert!(profile.is_table_pinned("public", "users"));
+        assert!(!profile.is_table_pinned("audit", "users"));
+        assert_eq!(profile.pinned_tables, vec!["public.users".to_string()]);
+
+        assert!(profile.unpin_table("public", "users"));
+        assert!(!profile.unpin_table("public", "users"));
+        assert!(profile.pinned_tables.is_empty());
+    }
+
+    #[test]
+    fn test_record_connect_and_keep_usage() {
+        let mut stored = ConnectionProfile::new(
+            "test-id".to_string(),
+            "Test DB".to_string(),
+            DbDriver::Postgres,
+            "localhost".to_string(),
+            5432,
+            "postgres".to_string(),
+        );
+        let mut edited = stored.clone();
+
+        stored.record_connect(1_700_000_000);
+        stored.record_connect(1_700_000_100);
+        assert_eq!(stored.last_connected_at, Some(1_700_000_100));
+        assert_eq!(stored.connection_count, 2);
+
+        edited.name = "Renamed".to_string();
+        edited.keep_usage_from(&stored);
+        assert_eq!(edited.connection_count, 2);
+        assert_eq!(edited.last_connected_at, Some(1_700_000_100));
+    }
+
+    #[test]
+    fn test_same_target_as() {
+        let profile = |host: &str, database: Option<&str>| {
+            let mut p = ConnectionProfile::new(
+                uuid::Uuid::new_v4().to_string(),
+                "Test DB".to_string(),
+                DbDriver::Postgres,
+                host.to_string(),
+                5432,
+                "postgres".to_string(),
+            );
+            p.database = database.map(str::to_string);
+            p
+        };
+
+        let local = profile("localhost", Some("app"));
+        assert!(local.same_target_as(&profile(" 127.0.0.1", Some("app "))));
+        assert!(!local.same_target_as(&profile("localhost", Some("other"))));
+        assert!(profile("db.example.com", None).same_target_as(&profile("DB.example.com", Some(""))));
+
+        let mut other_user = profile("localhost", Some("app"));
+        other_user.username = "readonly".to_string();
+        assert!(!local.same_target_as(&other_user));
+
+        let mut with_ssl = profile("localhost", Some("app"));
+        with_ssl.ssl_mode = SslMode::Require;
+        assert!(local.same_target_as(&with_ssl));
+    }
+
+    #[test]
+    fn test_session_application_name() {
+        let mut profile = ConnectionProfile::new(
+            "3f2a9c1e-0000-4000-8000-000000000000".to_string(),
+            "Prod".to_string(),
+            DbDriver::Postgres,
+            "localhost".to_string(),
+            5432,
+            "postgres".to_string(),
+        );
+        assert_eq!(profile.session_application_name(), "DB-Hive [3f2a9c1e]");
+
+        profile.application_name = Some("x".repeat(100));
+        let name = profile.session_application_name();
+        assert_eq!(name.len(), 63);
+        assert!(name.ends_with(" [3f2a9c1e]"));
+    }
+
+    #[test]
+    fn test_default_schema_for_driver() {
+        assert_eq!(default_schema_for(&DbDriver::Postgres, None), "public");
+        assert_eq!(default_schema_for(&DbDriver::MySql, Some("shop")), "shop");
+        assert_eq!(default_schema_for(&DbDriver::MySql, None), "mysql");
+        assert_eq!(default_schema_for(&DbDriver::SqlServer, Some("app")), "dbo");
+        assert_eq!(default_schema_for(&DbDriver::Sqlite, None), "main");
+    }
+
+    #[test]
+    fn test_default_schema_name_ignores_blank() {
+        let mut profile = ConnectionProfile::new(
+            "1".to_string(),
+            "Sales".to_string(),
+            DbDriver::Postgres,
+            "localhost".to_string(),
+            5432,
+            "postgres".to_string(),
+        );
+        assert_eq!(profile.default_schema_name(), None);
+
+        profile.default_schema = Some("  ".to_string());
+        assert_eq!(profile.default_schema_name(), None);
+
+        profile.default_schema = Some(" sales ".to_string());
+        assert_eq!(profile.default_schema_name(), Some("sales"));
+    }
+
+    #[test]
+    fn test_connection_latency_from_samples() {
+        assert!(ConnectionLatency::from_samples("c1", &[]).is_none());
+
+        let steady = ConnectionLatency::from_samples("c1", &[20, 30, 25]).unwrap();
+        assert_eq!((steady.latency_ms, steady.average_ms), (25, 25));
+        assert!(!steady.degraded);
+
+        let slow = ConnectionLatency::from_samples("c1", &[20, 30, 250]).unwrap();
+        assert!(slow.degraded);
+
+        // Fast connections aren't flagged over a few milliseconds of jitter
+        assert!(!ConnectionLatency::from_samples("c1", &[1, 1, 9]).unwrap().degraded);
+    }
+
     #[test]
     fn test_connection_profile_creation() {
         let profile = ConnectionProfile::new(
@@ -301,6 +301,52 @@ pub struct DropTableDefinition {
     pub if_exists: bool,
 }
 
+/// View definition for creation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewDefinition {
+    /// Schema name
+    pub schema: Option<String>,
+
+    /// View name
+    pub name: String,
+
+    /// Column names for the view; `None` takes them from the query
+    pub columns: Option<Vec<String>>,
+
+    /// The SELECT statement the view is defined by
+    pub query: String,
+
+    /// Create a materialized view (PostgreSQL only)
+    #[serde(default)]
+    pub materialized: bool,
+
+    /// Replace the view if it already exists
+    #[serde(default)]
+    pub or_replace: bool,
+}
+
+/// Request to drop a view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DropViewDefinition {
+    /// Schema name
+    pub schema: Option<String>,
+
+    /// View name
+    pub name: String,
+
+    /// The view is materialized (PostgreSQL only)
+    #[serde(default)]
+    pub materialized: bool,
+
+    /// If true, also drop dependent objects (CASCADE)
+    pub cascade: bool,
+
+    /// If true, add "IF EXISTS" clause
+    pub if_exists: bool,
+}
+
 /// DDL operation result with generated SQL
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
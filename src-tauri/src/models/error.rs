@@ -4,7 +4,7 @@
 //! The `DbError` enum represents all possible errors that can occur during
 //! database operations and is serializable for transmission over Tauri's IPC.
 
-use serde::Serializer;
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
 /// Main error type for all database operations
@@ -53,6 +53,164 @@ pub enum DbError {
     /// Error occurred during AI operations
     #[error("AI error: {0}")]
     AiError(String),
+
+    /// The AI provider kept rejecting requests with HTTP 429
+    #[error("AI rate limit exceeded: {0}")]
+    AiRateLimited(String),
+
+    /// The AI provider API key is missing or was rejected
+    #[error("AI authentication failed: {0}")]
+    AiAuthError(String),
+
+    /// The AI provider could not be reached
+    #[error("AI provider unreachable: {0}")]
+    AiNetworkError(String),
+
+    /// A destructive statement was refused until the user confirms it
+    #[error("Confirmation required: {0}")]
+    ConfirmationRequired(String),
+
+    /// Profiles and snippets are encrypted and the master password has not
+    /// been entered yet
+    #[error("Store locked: {0}")]
+    StoreLocked(String),
+
+    /// A new profile connects to the same database as an existing one
+    ///
+    /// Only returned when the caller asked for duplicate checking; the UI
+    /// can offer the existing profile or create the new one anyway.
+    #[error("Profile '{existing_name}' already connects to this database")]
+    DuplicateProfile {
+        existing_id: String,
+        existing_name: String,
+    },
+
+    /// Error reported by the database, with its error code
+    ///
+    /// Displays like `QueryError`; serialized with the kind its code implies,
+    /// so a rejected login is `auth` wherever it surfaces.
+    #[error("Query execution failed: {message}")]
+    DatabaseError {
+        message: String,
+        code: ErrorCode,
+        /// The server's DETAIL and HINT text
+        details: Option<String>,
+    },
+}
+
+/// Machine-readable code of an error reported by the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// Five-character SQLSTATE (PostgreSQL), e.g. `42P01`
+    SqlState(String),
+    /// MySQL error number and the SQLSTATE it maps to
+    MySql { number: u16, sqlstate: String },
+    /// SQL Server error number
+    SqlServer(u32),
+    /// SQLite extended result code
+    Sqlite(i32),
+}
+
+impl ErrorCode {
+    pub fn sqlstate(&self) -> Option<&str> {
+        match self {
+            ErrorCode::SqlState(sqlstate) | ErrorCode::MySql { sqlstate, .. } => Some(sqlstate),
+            ErrorCode::SqlServer(_) | ErrorCode::Sqlite(_) => None,
+        }
+    }
+
+    /// The vendor's numeric code, where it has one
+    pub fn number(&self) -> Option<i64> {
+        match self {
+            ErrorCode::SqlState(_) => None,
+            ErrorCode::MySql { number, .. } => Some(i64::from(*number)),
+            ErrorCode::SqlServer(number) => Some(i64::from(*number)),
+            ErrorCode::Sqlite(code) => Some(i64::from(*code)),
+        }
+    }
+}
+
+/// Common kinds of failed statement, for errors the UI handles specifically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorCategory {
+    UniqueViolation,
+    ForeignKeyViolation,
+    SyntaxError,
+    PermissionDenied,
+}
+
+/// Category of a database error code, if it is one of the common ones
+///
+/// SQL Server's 547 covers CHECK constraints as well as foreign keys; it is
+/// reported as a foreign key violation, by far the usual cause. SQLite has
+/// no code of its own for syntax errors.
+pub fn classify_db_error(code: &ErrorCode) -> Option<ErrorCategory> {
+    use ErrorCategory::*;
+
+    match code {
+        ErrorCode::SqlState(sqlstate) => match sqlstate.as_str() {
+            "23505" => Some(UniqueViolation),
+            "23503" => Some(ForeignKeyViolation),
+            "42601" => Some(SyntaxError),
+            "42501" => Some(PermissionDenied),
+            _ => None,
+        },
+        ErrorCode::MySql { number, .. } => match number {
+            1062 | 1586 => Some(UniqueViolation),
+            1216 | 1217 | 1451 | 1452 => Some(ForeignKeyViolation),
+            1064 | 1149 => Some(SyntaxError),
+            1044 | 1142 | 1143 | 1227 | 1370 => Some(PermissionDenied),
+            _ => None,
+        },
+        ErrorCode::SqlServer(number) => match number {
+            2601 | 2627 => Some(UniqueViolation),
+            547 => Some(ForeignKeyViolation),
+            102 | 156 => Some(SyntaxError),
+            229 | 230 | 262 | 297 | 300 => Some(PermissionDenied),
+            _ => None,
+        },
+        // SQLITE_CONSTRAINT_UNIQUE, _PRIMARYKEY, _FOREIGNKEY, SQLITE_AUTH
+        ErrorCode::Sqlite(code) => match code {
+            2067 | 1555 => Some(UniqueViolation),
+            787 => Some(ForeignKeyViolation),
+            23 => Some(PermissionDenied),
+            _ => None,
+        },
+    }
+}
+
+impl DbError {
+    /// Discriminant the frontend branches on
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DbError::ConnectionError(_) => "connection",
+            DbError::QueryError(_) => "query",
+            DbError::AuthError(_) => "auth",
+            DbError::TimeoutError(_) => "timeout",
+            DbError::InvalidInput(_) => "invalidInput",
+            DbError::NotFound(_) => "notFound",
+            DbError::InternalError(_) => "internal",
+            DbError::CredentialError(_) => "credential",
+            DbError::ImportError(_) => "import",
+            DbError::AiError(_) => "ai",
+            DbError::AiRateLimited(_) => "aiRateLimited",
+            DbError::AiAuthError(_) => "aiAuth",
+            DbError::AiNetworkError(_) => "aiNetwork",
+            DbError::ConfirmationRequired(_) => "confirmationRequired",
+            DbError::StoreLocked(_) => "storeLocked",
+            DbError::DuplicateProfile { .. } => "duplicateProfile",
+            // SQLSTATE class 28 is invalid authorization, class 08
+            // connection exceptions; SQL Server's 18456 is a failed login
+            DbError::DatabaseError { code, .. } => {
+                match (code.sqlstate().and_then(|s| s.get(..2)), code) {
+                    (Some("28"), _) | (_, ErrorCode::SqlServer(18456)) => "auth",
+                    (Some("08"), _) => "connection",
+                    _ => "query",
+                }
+            }
+        }
+    }
 }
 
 impl serde::Serialize for DbError {
@@ -65,30 +223,55 @@ impl serde::Serialize for DbError {
     ///   "message": "Connection failed: timeout"
     /// }
     /// ```
+    ///
+    /// Errors reported by the database also have, where they apply,
+    /// `sqlstate`, `errorCode` (the vendor's error number), `category` (see
+    /// `classify_db_error`) and `details`. A duplicate profile error has
+    /// `existingProfileId`.
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
         use serde::ser::SerializeStruct;
+        use serde_json::{json, Value};
 
-        let mut state = serializer.serialize_struct("DbError", 2)?;
-
-        // Determine error kind
-        let kind = match self {
-            DbError::ConnectionError(_) => "connection",
-            DbError::QueryError(_) => "query",
-            DbError::AuthError(_) => "auth",
-            DbError::TimeoutError(_) => "timeout",
-            DbError::InvalidInput(_) => "invalid_input",
-            DbError::NotFound(_) => "not_found",
-            DbError::InternalError(_) => "internal",
-            DbError::CredentialError(_) => "credential",
-            DbError::ImportError(_) => "import",
-            DbError::AiError(_) => "ai",
-        };
-
-        state.serialize_field("kind", kind)?;
+        let mut state = serializer.serialize_struct("DbError", 7)?;
+        state.serialize_field("kind", self.kind())?;
         state.serialize_field("message", &self.to_string())?;
+        let (code, details) = match self {
+            DbError::DatabaseError { code, details, .. } => (Some(code), details.as_ref()),
+            _ => (None, None),
+        };
+        let fields = [
+            (
+                "sqlstate",
+                code.and_then(ErrorCode::sqlstate).map(Value::from),
+            ),
+            (
+                "errorCode",
+                code.and_then(ErrorCode::number).map(Value::from),
+            ),
+            (
+                "category",
+                code.and_then(classify_db_error).map(|c| json!(c)),
+            ),
+            ("details", details.map(|d| Value::from(d.as_str()))),
+            (
+                "existingProfileId",
+                match self {
+                    DbError::DuplicateProfile { existing_id, .. } => {
+                        Some(Value::from(existing_id.as_str()))
+                    }
+                    _ => None,
+                },
+            ),
+        ];
+        for (name, value) in fields {
+            match value {
+                Some(value) => state.serialize_field(name, &value)?,
+                None => state.skip_field(name)?,
+            }
+        }
         state.end()
     }
 }
@@ -103,6 +286,84 @@ mod tests {
         let json = serde_json::to_string(&error).unwrap();
         assert!(json.contains("\"kind\":\"connection\""));
         assert!(json.contains("\"message\":"));
+
+        let json = serde_json::to_value(DbError::NotFound("t".to_string())).unwrap();
+        assert_eq!(json["kind"], "notFound");
+        assert!(json.get("sqlstate").is_none());
+        assert!(json.get("existingProfileId").is_none());
+
+        let json = serde_json::to_value(DbError::DuplicateProfile {
+            existing_id: "conn-1".to_string(),
+            existing_name: "Prod".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json["kind"], "duplicateProfile");
+        assert_eq!(json["existingProfileId"], "conn-1");
+    }
+
+    #[test]
+    fn test_database_error_serialization() {
+        let error = DbError::DatabaseError {
+            message: "db error: ERROR: relation \"t\" does not exist".to_string(),
+            code: ErrorCode::SqlState("42P01".to_string()),
+            details: None,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Query execution failed: db error: ERROR: relation \"t\" does not exist"
+        );
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["kind"], "query");
+        assert_eq!(json["sqlstate"], "42P01");
+        assert!(json.get("details").is_none());
+        assert!(json.get("errorCode").is_none());
+        assert!(json.get("category").is_none());
+
+        let error = DbError::DatabaseError {
+            message: "db error: FATAL: password authentication failed".to_string(),
+            code: ErrorCode::SqlState("28P01".to_string()),
+            details: Some("Hint: check the password".to_string()),
+        };
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["kind"], "auth");
+        assert_eq!(json["details"], "Hint: check the password");
+
+        let error = DbError::DatabaseError {
+            message: "Duplicate entry '1' for key 'PRIMARY'".to_string(),
+            code: ErrorCode::MySql {
+                number: 1062,
+                sqlstate: "23000".to_string(),
+            },
+            details: None,
+        };
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["kind"], "query");
+        assert_eq!(json["sqlstate"], "23000");
+        assert_eq!(json["errorCode"], 1062);
+        assert_eq!(json["category"], "uniqueViolation");
+    }
+
+    #[test]
+    fn test_classify_db_error() {
+        use ErrorCategory::*;
+
+        let sqlstate = |s: &str| ErrorCode::SqlState(s.to_string());
+        assert_eq!(classify_db_error(&sqlstate("23505")), Some(UniqueViolation));
+        assert_eq!(classify_db_error(&sqlstate("42601")), Some(SyntaxError));
+        assert_eq!(classify_db_error(&sqlstate("42P01")), None);
+        assert_eq!(
+            classify_db_error(&ErrorCode::SqlServer(547)),
+            Some(ForeignKeyViolation)
+        );
+        assert_eq!(
+            classify_db_error(&ErrorCode::SqlServer(229)),
+            Some(PermissionDenied)
+        );
+        assert_eq!(
+            classify_db_error(&ErrorCode::Sqlite(1555)),
+            Some(UniqueViolation)
+        );
+        assert_eq!(classify_db_error(&ErrorCode::Sqlite(1)), None);
     }
 
     #[test]
@@ -4,8 +4,12 @@
 //! history and saved query snippets. These provide users with the ability to
 //! review past queries and save frequently-used SQL for quick access.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use super::DbDriver;
+
 /// Query history record
 ///
 /// Represents a single executed query with metadata about the execution.
@@ -24,6 +28,8 @@ use serde::{Deserialize, Serialize};
 /// - **row_count**: Number of rows returned/affected (if available)
 /// - **success**: Whether the query executed successfully
 /// - **error_message**: Error message if query failed (None if successful)
+/// - **favorite**: Starred by the user; exempt from history pruning
+/// - **note**: User annotation explaining what the query was for
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryHistory {
@@ -59,6 +65,14 @@ pub struct QueryHistory {
     /// Error message if query failed
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_message: Option<String>,
+
+    /// Starred by the user; favorites are never pruned
+    #[serde(default)]
+    pub favorite: bool,
+
+    /// User note explaining what the query was for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
 }
 
 impl QueryHistory {
@@ -93,6 +107,8 @@ impl QueryHistory {
             row_count: None,
             success: true,
             error_message: None,
+            favorite: false,
+            note: None,
         }
     }
 
@@ -111,6 +127,13 @@ impl QueryHistory {
         self.execution_time_ms = execution_time_ms;
         self
     }
+
+    /// Set or clear the note; a blank note clears it
+    pub fn set_note(&mut self, note: Option<String>) {
+        self.note = note
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty());
+    }
 }
 
 /// Saved query snippet
@@ -126,6 +149,9 @@ impl QueryHistory {
 /// - **description**: Optional description of what the snippet does
 /// - **query**: The SQL query text
 /// - **tags**: Optional array of tags for categorization (e.g., ["backup", "maintenance"])
+/// - **variables**: Declared defaults and descriptions for `{{name}}` placeholders
+/// - **connection_id**: Connection (profile ID) the snippet is scoped to (None = global)
+/// - **driver**: Database driver the snippet is scoped to (None = any driver)
 /// - **created_at**: ISO 8601 timestamp of when snippet was created
 /// - **updated_at**: ISO 8601 timestamp of last update
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,6 +174,18 @@ pub struct QuerySnippet {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
 
+    /// Declared `{{name}}` placeholders; the query may use names not listed here
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<SnippetVariable>,
+
+    /// Connection (profile) ID the snippet belongs to; None for global snippets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_id: Option<String>,
+
+    /// Driver the snippet is limited to; None for any driver
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub driver: Option<DbDriver>,
+
     /// ISO 8601 timestamp of creation
     pub created_at: String,
 
@@ -181,11 +219,65 @@ impl QuerySnippet {
             description,
             query,
             tags,
+            variables: Vec::new(),
+            connection_id: None,
+            driver: None,
             created_at: now.clone(),
             updated_at: now,
         }
     }
 
+    /// Whether the snippet should be offered for a connection
+    ///
+    /// Global snippets apply everywhere. A connection scope must match the
+    /// connection ID, and a driver scope must match the connection's driver;
+    /// when both are set, both must match.
+    pub fn applies_to(&self, connection_id: &str, driver: Option<&DbDriver>) -> bool {
+        let connection_matches = match &self.connection_id {
+            None => true,
+            Some(id) => id == connection_id,
+        };
+        let driver_matches = match (&self.driver, driver) {
+            (None, _) => true,
+            (Some(scope), Some(driver)) => scope == driver,
+            (Some(_), None) => false,
+        };
+        connection_matches && driver_matches
+    }
+
+    /// The `{{name}}` placeholders used in the query, in order of appearance
+    ///
+    /// Each carries the default and description declared in `variables`, if
+    /// any. Declared variables the query no longer uses are left out.
+    pub fn detected_variables(&self) -> Vec<SnippetVariable> {
+        crate::sql::template::find_template_variables(&self.query)
+            .into_iter()
+            .map(|name| {
+                self.variables
+                    .iter()
+                    .find(|v| v.name == name)
+                    .cloned()
+                    .unwrap_or(SnippetVariable {
+                        name,
+                        default_value: None,
+                        description: None,
+                    })
+            })
+            .collect()
+    }
+
+    /// Fill in the query's placeholders from `values`, falling back to the
+    /// declared defaults
+    pub fn variable_values(&self, values: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut resolved: HashMap<String, String> = self
+            .variables
+            .iter()
+            .filter_map(|v| Some((v.name.clone(), v.default_value.clone()?)))
+            .collect();
+        resolved.extend(values.iter().map(|(k, v)| (k.clone(), v.clone())));
+        resolved
+    }
+
     /// Update the snippet with new values
     pub fn update(
         &mut self,
@@ -210,6 +302,83 @@ impl QuerySnippet {
     }
 }
 
+/// A `{{name}}` placeholder in a snippet's query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetVariable {
+    /// Placeholder name, without braces
+    pub name: String,
+
+    /// Value pre-filled in the prompt and used when none is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<String>,
+
+    /// What the value is for, shown in the prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Saved view (query bookmark)
+///
+/// A named query bound to a connection that the sidebar can show like a
+/// virtual table under that connection and re-run with one click. The last
+/// run time and row count let the UI show how stale the displayed data is.
+///
+/// # Fields
+///
+/// - **id**: Unique identifier (UUID)
+/// - **name**: User-provided name shown in the tree
+/// - **connection_id**: Connection (profile ID) the view belongs to
+/// - **query**: The SQL query text
+/// - **is_pinned_to_tree**: Whether the view is listed in the sidebar tree
+/// - **last_run_at**: ISO 8601 timestamp of the last run (None if never run)
+/// - **last_row_count**: Rows returned/affected by the last run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedView {
+    /// Unique identifier for this view
+    pub id: String,
+
+    /// User-provided name
+    pub name: String,
+
+    /// Connection (profile) ID the query runs against
+    pub connection_id: String,
+
+    /// Optional description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The SQL query text
+    pub query: String,
+
+    /// Show the view in the sidebar tree under its connection
+    #[serde(default)]
+    pub is_pinned_to_tree: bool,
+
+    /// ISO 8601 timestamp of the last successful run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<String>,
+
+    /// Number of rows returned or affected by the last run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_row_count: Option<u64>,
+
+    /// ISO 8601 timestamp of creation
+    pub created_at: String,
+
+    /// ISO 8601 timestamp of last update
+    pub updated_at: String,
+}
+
+impl SavedView {
+    /// Record a successful run
+    pub fn record_run(&mut self, row_count: u64) {
+        self.last_run_at = Some(chrono::Utc::now().to_rfc3339());
+        self.last_row_count = Some(row_count);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -262,6 +431,21 @@ mod tests {
         assert_eq!(history.execution_time_ms, Some(50));
     }
 
+    #[test]
+    fn test_query_history_note_and_legacy_entries() {
+        // Entries saved before favorites and notes existed still load
+        let json = r#"{"id":"h1","connectionId":"c","connectionName":"C","database":"db",
+            "query":"SELECT 1","executedAt":"2025-11-19T12:00:00Z","success":true}"#;
+        let mut history: QueryHistory = serde_json::from_str(json).unwrap();
+        assert!(!history.favorite);
+        assert!(history.note.is_none());
+
+        history.set_note(Some("  monthly report  ".to_string()));
+        assert_eq!(history.note.as_deref(), Some("monthly report"));
+        history.set_note(Some("   ".to_string()));
+        assert!(history.note.is_none());
+    }
+
     #[test]
     fn test_query_snippet_new() {
         let snippet = QuerySnippet::new(
@@ -307,4 +491,80 @@ mod tests {
         assert_eq!(snippet.created_at, original_created);
         assert_ne!(snippet.updated_at, original_created);
     }
+
+    #[test]
+    fn test_query_snippet_scope() {
+        // Snippets saved before scoping existed load as global
+        let json = r#"{"id":"s1","name":"N","query":"SELECT 1",
+            "createdAt":"2025-11-19T12:00:00Z","updatedAt":"2025-11-19T12:00:00Z"}"#;
+        let mut snippet: QuerySnippet = serde_json::from_str(json).unwrap();
+        assert!(snippet.connection_id.is_none() && snippet.driver.is_none());
+        assert!(snippet.applies_to("conn-1", None));
+
+        snippet.driver = Some(DbDriver::Postgres);
+        assert!(snippet.applies_to("conn-1", Some(&DbDriver::Postgres)));
+        assert!(!snippet.applies_to("conn-1", Some(&DbDriver::MySql)));
+        assert!(!snippet.applies_to("conn-1", None));
+
+        snippet.driver = None;
+        snippet.connection_id = Some("conn-1".to_string());
+        assert!(snippet.applies_to("conn-1", Some(&DbDriver::Sqlite)));
+        assert!(!snippet.applies_to("conn-2", Some(&DbDriver::Sqlite)));
+    }
+
+    #[test]
+    fn test_query_snippet_variables() {
+        let mut snippet = QuerySnippet::new(
+            "Recent".to_string(),
+            "SELECT * FROM {{table}} WHERE created_at > '{{since}}'".to_string(),
+            None,
+            None,
+        );
+        snippet.variables = vec![
+            SnippetVariable {
+                name: "since".to_string(),
+                default_value: Some("2025-01-01".to_string()),
+                description: Some("Earliest creation date".to_string()),
+            },
+            SnippetVariable {
+                name: "unused".to_string(),
+                default_value: Some("x".to_string()),
+                description: None,
+            },
+        ];
+
+        let detected = snippet.detected_variables();
+        assert_eq!(detected.len(), 2);
+        assert_eq!(detected[0].name, "table");
+        assert!(detected[0].default_value.is_none());
+        assert_eq!(detected[1].default_value.as_deref(), Some("2025-01-01"));
+
+        let mut given = HashMap::new();
+        given.insert("table".to_string(), "orders".to_string());
+        let values = snippet.variable_values(&given);
+        assert_eq!(values.get("table").map(String::as_str), Some("orders"));
+        assert_eq!(values.get("since").map(String::as_str), Some("2025-01-01"));
+    }
+
+    #[test]
+    fn test_saved_view_defaults_and_run() {
+        let mut view: SavedView = serde_json::from_str(
+            r#"{
+                "id": "view-1",
+                "name": "Open orders",
+                "connectionId": "conn-123",
+                "query": "SELECT * FROM orders WHERE status = 'open'",
+                "createdAt": "2025-11-19T12:00:00Z",
+                "updatedAt": "2025-11-19T12:00:00Z"
+            }"#,
+        )
+        .unwrap();
+
+        assert!(!view.is_pinned_to_tree);
+        assert!(view.last_run_at.is_none());
+
+        view.record_run(42);
+        assert_eq!(view.last_row_count, Some(42));
+        assert!(view.last_run_at.is_some());
+    }
 }
@@ -133,6 +133,19 @@ pub struct ColumnInfo {
 
     /// Whether this column is auto-increment/serial (MySQL AUTO_INCREMENT, PostgreSQL SERIAL)
     pub is_auto_increment: bool,
+
+    /// Column comment, for drivers that support comments
+    #[serde(default)]
+    pub comment: Option<String>,
+
+    /// Whether the column is generated/computed from other columns; such
+    /// columns can't be written to
+    #[serde(default)]
+    pub is_generated: bool,
+
+    /// Expression a generated column is computed from
+    #[serde(default)]
+    pub generation_expression: Option<String>,
 }
 
 impl ColumnInfo {
@@ -145,6 +158,9 @@ impl ColumnInfo {
             default_value: None,
             is_primary_key: false,
             is_auto_increment: false,
+            comment: None,
+            is_generated: false,
+            generation_expression: None,
         }
     }
 
@@ -163,6 +179,9 @@ impl ColumnInfo {
             default_value,
             is_primary_key,
             is_auto_increment: false,
+            comment: None,
+            is_generated: false,
+            generation_expression: None,
         }
     }
 }
@@ -284,6 +303,38 @@ impl ForeignKeyInfo {
     }
 }
 
+/// Kind of table constraint
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConstraintKind {
+    PrimaryKey,
+    Unique,
+    Check,
+}
+
+/// Logical constraint on a table
+///
+/// Listed separately from `IndexInfo`: a primary key or unique constraint is
+/// enforced through an index, but the constraint is what the user declared.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintInfo {
+    /// Constraint name
+    pub name: String,
+
+    /// Primary key, unique or check
+    pub kind: ConstraintKind,
+
+    /// Constrained columns (in order); may be empty for checks on expressions
+    pub columns: Vec<String>,
+
+    /// Constraint definition for checks, e.g. `CHECK ((price > 0))`
+    pub definition: Option<String>,
+
+    /// Index enforcing a primary key or unique constraint
+    pub index_name: Option<String>,
+}
+
 /// Complete table schema
 ///
 /// Contains all metadata about a table, including columns and indexes.
@@ -298,6 +349,15 @@ pub struct TableSchema {
 
     /// Indexes defined on the table
     pub indexes: Vec<IndexInfo>,
+
+    /// Primary key, unique and check constraints, for drivers that report
+    /// them (currently PostgreSQL)
+    #[serde(default)]
+    pub constraints: Vec<ConstraintInfo>,
+
+    /// Table comment, for drivers that support comments
+    #[serde(default)]
+    pub comment: Option<String>,
 }
 
 impl TableSchema {
@@ -307,6 +367,8 @@ impl TableSchema {
             table,
             columns,
             indexes,
+            constraints: Vec::new(),
+            comment: None,
         }
     }
 
@@ -324,6 +386,40 @@ impl TableSchema {
     }
 }
 
+/// Database-level statistics for the overview dashboard
+///
+/// Every figure is optional: collecting one may need privileges the user does
+/// not have (e.g. other sessions in `pg_stat_activity`) or may simply not
+/// exist for the engine (connection counts for SQLite). Fields that could not
+/// be collected are `None` and listed by name in `unavailable`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStats {
+    /// Database the stats were collected for
+    pub database: String,
+
+    /// Total size on disk in bytes (data + indexes)
+    pub size_bytes: Option<u64>,
+
+    /// Number of user tables
+    pub table_count: Option<u64>,
+
+    /// Number of views
+    pub view_count: Option<u64>,
+
+    /// Number of indexes
+    pub index_count: Option<u64>,
+
+    /// Estimated total row count across all tables (from planner statistics)
+    pub estimated_rows: Option<u64>,
+
+    /// Number of sessions connected to the database
+    pub active_connections: Option<u64>,
+
+    /// camelCase names of the fields that could not be collected
+    pub unavailable: Vec<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -407,6 +503,29 @@ mod tests {
         assert_eq!(pk_idx.unwrap().name, "users_pkey");
     }
 
+    #[test]
+    fn test_table_schema_constraints() {
+        let table = TableInfo::new("users".to_string(), "public".to_string(), "TABLE".to_string());
+        let mut schema = TableSchema::new(table, vec![], vec![]);
+        schema.constraints.push(ConstraintInfo {
+            name: "users_age_check".to_string(),
+            kind: ConstraintKind::Check,
+            columns: vec!["age".to_string()],
+            definition: Some("CHECK ((age >= 0))".to_string()),
+            index_name: None,
+        });
+
+        let json = serde_json::to_value(&schema).unwrap();
+        assert_eq!(json["constraints"][0]["kind"], "check");
+        assert_eq!(json["constraints"][0]["indexName"], serde_json::Value::Null);
+
+        // Schemas serialized before constraints existed still load
+        let mut legacy = json;
+        legacy.as_object_mut().unwrap().remove("constraints");
+        let schema: TableSchema = serde_json::from_value(legacy).unwrap();
+        assert!(schema.constraints.is_empty());
+    }
+
     #[test]
     fn test_serialization() {
         let db = DatabaseInfo::new("test_db".to_string());
@@ -6,27 +6,37 @@
 pub mod activity;
 pub mod backup;
 pub mod connection;
+pub mod connection_uri;
 pub mod ddl;
 pub mod error;
 pub mod history;
 pub mod metadata;
+pub mod schedule;
 pub mod settings;
 
 // Re-export commonly used types for convenience
 pub use activity::{
-    ActivityStats, ExportFormat, QueryLog, QueryLogFilter, QueryLogResponse, QueryLogSort,
-    QueryLogSortField, QueryStatus, QueryType, SortDirection,
+    ActivityBucket, ActivityStats, ExportFormat, QueryLog, QueryLogFilter, QueryLogResponse,
+    QueryLogSort, QueryLogSortField, QueryStatus, QueryType, RunningQuery, SlowQueryGroup,
+    SortDirection, TimeBucket,
+};
+pub use connection::{
+    default_schema_for, ConnectionHealth, ConnectionLatency, ConnectionProfile, ConnectionStatus,
+    DbDriver, Environment, SqliteOptions, SslMode,
 };
-pub use connection::{ConnectionProfile, ConnectionStatus, DbDriver, SslMode};
 pub use ddl::{
     AlterColumnOperation, AlterTableDefinition, CheckConstraint, ColumnDefinition, ColumnType,
     DdlResult, DropTableDefinition, ForeignKeyAction, ForeignKeyConstraint, IndexDefinition,
     IndexType, TableDefinition, UniqueConstraint,
 };
-pub use error::DbError;
-pub use history::{QueryHistory, QuerySnippet};
+pub use error::{classify_db_error, DbError, ErrorCategory, ErrorCode};
+pub use history::{QueryHistory, QuerySnippet, SavedView, SnippetVariable};
 pub use metadata::{
-    ColumnInfo, DatabaseInfo, ForeignKeyInfo, IndexInfo, SchemaInfo, TableInfo, TableSchema,
+    ColumnInfo, ConstraintInfo, ConstraintKind, DatabaseInfo, DatabaseStats, ForeignKeyInfo,
+    IndexInfo, SchemaInfo, TableInfo, TableSchema,
 };
+pub use schedule::{parse_cron, MissedRunPolicy, QuerySchedule};
 pub use backup::{BackupEntry, BackupOptions, BackupProgress, BackupStatus, RestoreOptions};
-pub use settings::AppSettings;
+pub use settings::{
+    AppSettings, LintSettings, PlanAnalysisSettings, QuerySettings, CURRENT_SETTINGS_VERSION,
+};
@@ -0,0 +1,216 @@
+//! Scheduled query models
+//!
+//! A schedule runs a SQL statement or a saved snippet against a connection
+//! on a cron expression. Schedules only fire while the app is open; there is
+//! no background service, so `missed_runs` decides what happens to runs that
+//! fell due while it was closed.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::DbError;
+
+/// What to do with runs that fell due while the app was closed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MissedRunPolicy {
+    /// Drop them and wait for the next scheduled time
+    #[default]
+    Skip,
+    /// Run once at startup, however many runs were missed
+    RunOnce,
+}
+
+/// A query run on a cron schedule
+///
+/// # Fields
+///
+/// - **id**: Unique identifier (UUID)
+/// - **name**: User-provided name
+/// - **connection_id**: Connection (profile ID) the query runs against
+/// - **sql** / **snippet_id**: What to run; exactly one is set
+/// - **cron_expr**: Cron expression, with or without a leading seconds field
+/// - **missed_runs**: Handling of runs missed while the app was closed
+/// - **confirmed_sql**: SQL the user confirmed running on production
+/// - **next_run_at**: When the schedule fires next
+/// - **last_run_at** / **last_error**: Outcome of the most recent run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuerySchedule {
+    /// Unique identifier for this schedule
+    pub id: String,
+
+    /// User-provided name
+    pub name: String,
+
+    /// Connection (profile) ID the query runs against
+    pub connection_id: String,
+
+    /// SQL to run, when not running a snippet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sql: Option<String>,
+
+    /// Snippet to run; its current query is used at each run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet_id: Option<String>,
+
+    /// Cron expression (e.g. `0 3 * * *` for 03:00 daily, UTC)
+    pub cron_expr: String,
+
+    /// Handling of runs missed while the app was closed
+    #[serde(default)]
+    pub missed_runs: MissedRunPolicy,
+
+    /// SQL confirmed when the schedule was created; a destructive query on a
+    /// production connection only runs unattended while it matches this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmed_sql: Option<String>,
+
+    /// ISO 8601 timestamp of creation
+    pub created_at: String,
+
+    /// Next time the schedule fires; None if the expression has no future times
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_run_at: Option<DateTime<Utc>>,
+
+    /// When the schedule last ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<DateTime<Utc>>,
+
+    /// Error of the last run, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Parse a cron expression
+///
+/// Accepts the usual five fields (minute to day-of-week) as well as the
+/// six or seven field form with seconds (and year). Times are in UTC.
+///
+/// Numeric weekdays follow the form: the five-field (Unix) form counts
+/// 0-6 from Sunday, with 7 also Sunday, while the longer forms count 1-7
+/// from Sunday. Names (`MON-FRI`) mean the same in both.
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` for an expression that does not parse.
+pub fn parse_cron(expr: &str) -> Result<cron::Schedule, DbError> {
+    let expr = expr.trim();
+    let invalid = |e: &dyn std::fmt::Display| {
+        DbError::InvalidInput(format!("Invalid cron expression '{}': {}", expr, e))
+    };
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    let normalized = if let [minute, hour, day, month, weekday] = fields[..] {
+        let weekday = unix_weekdays(weekday).map_err(|e| invalid(&e))?;
+        format!("0 {} {} {} {} {}", minute, hour, day, month, weekday)
+    } else {
+        expr.to_string()
+    };
+    cron::Schedule::from_str(&normalized).map_err(|e| invalid(&e))
+}
+
+/// A Unix day-of-week field in the `cron` crate's numbering, which counts
+/// 1-7 from Sunday instead of 0-6
+///
+/// Ranges ending in Sunday as 7 (`5-7`) are split, since Sunday comes first
+/// in the crate's week; names and `*` are left as they are.
+fn unix_weekdays(field: &str) -> Result<String, &'static str> {
+    let day = |n: &str| match n.parse::<u8>() {
+        Ok(n @ 0..=7) => (n % 7 + 1).to_string(),
+        _ => n.to_string(),
+    };
+    let mut items = Vec::new();
+    for item in field.split(',') {
+        let (base, step) = match item.split_once('/') {
+            Some((base, step)) => (base, Some(step)),
+            None => (item, None),
+        };
+        let base = match base.split_once('-') {
+            Some((first, "7")) => {
+                if step.is_some() {
+                    return Err("a stepped weekday range can't end in 7; use 0-6 or names");
+                }
+                items.push("1".to_string());
+                format!("{}-7", day(first))
+            }
+            Some((first, last)) => format!("{}-{}", day(first), day(last)),
+            None => day(base),
+        };
+        items.push(match step {
+            Some(step) => format!("{}/{}", base, step),
+            None => base,
+        });
+    }
+    Ok(items.join(","))
+}
+
+impl QuerySchedule {
+    /// First time after `after` the schedule fires
+    pub fn next_after(&self, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, DbError> {
+        Ok(parse_cron(&self.cron_expr)?.after(&after).next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cron_field_counts() {
+        assert!(parse_cron("0 3 * * *").is_ok());
+        assert!(parse_cron("30 0 3 * * *").is_ok());
+        assert!(matches!(
+            parse_cron("every day"),
+            Err(DbError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_cron_unix_weekdays() {
+        // Wednesday 2025-11-19, noon
+        let after: DateTime<Utc> = "2025-11-19T12:00:00Z".parse().unwrap();
+        let next = |expr: &str| {
+            parse_cron(expr)
+                .unwrap()
+                .after(&after)
+                .next()
+                .unwrap()
+                .to_rfc3339()
+        };
+        assert_eq!(next("0 9 * * 1"), "2025-11-24T09:00:00+00:00");
+        assert_eq!(next("0 9 * * 0"), "2025-11-23T09:00:00+00:00");
+        assert_eq!(next("0 9 * * 7"), "2025-11-23T09:00:00+00:00");
+        assert_eq!(next("0 9 * * 6-7"), "2025-11-22T09:00:00+00:00");
+        assert_eq!(next("0 9 * * 4,5"), "2025-11-20T09:00:00+00:00");
+        assert_eq!(next("0 9 * * MON-FRI"), "2025-11-20T09:00:00+00:00");
+        // Six fields keep the crate's own numbering, where 2 is Monday
+        assert_eq!(next("0 0 9 * * 2"), "2025-11-24T09:00:00+00:00");
+
+        assert_eq!(unix_weekdays("1-5/2").unwrap(), "2-6/2");
+        assert_eq!(unix_weekdays("*/2").unwrap(), "*/2");
+        assert!(parse_cron("0 9 * * 1-7/2").is_err());
+    }
+
+    #[test]
+    fn test_next_after() {
+        let schedule = QuerySchedule {
+            id: "s1".to_string(),
+            name: "Cleanup".to_string(),
+            connection_id: "conn-1".to_string(),
+            sql: Some("DELETE FROM sessions WHERE expired".to_string()),
+            snippet_id: None,
+            cron_expr: "0 3 * * *".to_string(),
+            missed_runs: MissedRunPolicy::Skip,
+            confirmed_sql: None,
+            created_at: "2025-11-19T12:00:00Z".to_string(),
+            next_run_at: None,
+            last_run_at: None,
+            last_error: None,
+        };
+        let after: DateTime<Utc> = "2025-11-19T12:00:00Z".parse().unwrap();
+        let next = schedule.next_after(after).unwrap().unwrap();
+        assert_eq!(next.to_rfc3339(), "2025-11-20T03:00:00+00:00");
+    }
+}
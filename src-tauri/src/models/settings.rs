@@ -5,6 +5,18 @@
 //! and keyboard shortcuts.
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::DbError;
+
+/// Version of the settings layout written by this build
+///
+/// Bump it and add a step to [`AppSettings::migrate`] when a field is renamed
+/// or its meaning changes; new fields with defaults need no step.
+pub const CURRENT_SETTINGS_VERSION: u32 = 1;
+
+/// Longest query timeout accepted (one day)
+const MAX_TIMEOUT_SECONDS: u32 = 86_400;
 
 /// Application settings
 ///
@@ -13,6 +25,11 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppSettings {
+    /// Layout version the settings were saved with; files from before
+    /// versioning have none and count as version 0
+    #[serde(default)]
+    pub schema_version: u32,
+
     /// General application settings
     pub general: GeneralSettings,
 
@@ -24,16 +41,165 @@ pub struct AppSettings {
 
     /// Keyboard shortcuts configuration
     pub shortcuts: ShortcutsSettings,
+
+    /// Connection health settings
+    ///
+    /// Defaulted so settings files saved before this section existed still load.
+    #[serde(default)]
+    pub connection: ConnectionSettings,
+
+    /// SQL linter rules
+    #[serde(default)]
+    pub lint: LintSettings,
+
+    /// Thresholds for query plan warnings
+    #[serde(default)]
+    pub plan_analysis: PlanAnalysisSettings,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
+            schema_version: CURRENT_SETTINGS_VERSION,
             general: GeneralSettings::default(),
             theme: ThemeSettings::default(),
             query: QuerySettings::default(),
             shortcuts: ShortcutsSettings::default(),
+            connection: ConnectionSettings::default(),
+            lint: LintSettings::default(),
+            plan_analysis: PlanAnalysisSettings::default(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// Upgrade stored settings JSON to the current version
+    ///
+    /// Fields missing from the stored value, at any depth, are filled from
+    /// the defaults, so a file written before a field existed still loads.
+    /// Values of the wrong type are left alone and fail deserialization.
+    pub fn migrate(stored: Value) -> Result<AppSettings, DbError> {
+        let version = stored
+            .get("schemaVersion")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        if version > u64::from(CURRENT_SETTINGS_VERSION) {
+            eprintln!(
+                "Settings were saved by a newer version ({}); unknown fields are ignored",
+                version
+            );
+        }
+
+        let mut merged = serde_json::to_value(AppSettings::default())
+            .map_err(|e| DbError::InternalError(format!("Failed to serialize settings: {}", e)))?;
+        merge_json(&mut merged, stored);
+
+        let mut settings: AppSettings = serde_json::from_value(merged).map_err(|e| {
+            DbError::InternalError(format!("Failed to deserialize settings: {}", e))
+        })?;
+        settings.schema_version = CURRENT_SETTINGS_VERSION;
+        Ok(settings)
+    }
+
+    /// Check every value is in range
+    ///
+    /// The error names the offending field by its camelCase path, e.g.
+    /// `query.maxConcurrentQueries`.
+    pub fn validate(&self) -> Result<(), DbError> {
+        let general = &self.general;
+        if general.language.trim().is_empty() {
+            return Err(invalid("general.language must not be empty"));
+        }
+        if general.update_check_interval_hours < 1 {
+            return Err(invalid(
+                "general.updateCheckIntervalHours must be at least 1",
+            ));
+        }
+
+        let theme = &self.theme;
+        if !is_hex_color(&theme.accent_color) {
+            return Err(invalid(&format!(
+                "theme.accentColor must be a hex color like #f59e0b, got '{}'",
+                theme.accent_color
+            )));
+        }
+        if !(6..=72).contains(&theme.editor_font_size) {
+            return Err(invalid(&format!(
+                "theme.editorFontSize must be between 6 and 72, got {}",
+                theme.editor_font_size
+            )));
+        }
+        if theme.editor_font_family.trim().is_empty() {
+            return Err(invalid("theme.editorFontFamily must not be empty"));
+        }
+
+        let query = &self.query;
+        if query.timeout_seconds > MAX_TIMEOUT_SECONDS {
+            return Err(invalid(&format!(
+                "query.timeoutSeconds must be at most {} (0 disables the timeout), got {}",
+                MAX_TIMEOUT_SECONDS, query.timeout_seconds
+            )));
+        }
+        if query.max_rows < 1 {
+            return Err(invalid("query.maxRows must be at least 1"));
+        }
+        if query.max_history_entries < 1 {
+            return Err(invalid("query.maxHistoryEntries must be at least 1"));
+        }
+        if query.max_concurrent_queries < 1 {
+            return Err(invalid("query.maxConcurrentQueries must be at least 1"));
+        }
+
+        if self.connection.health_check_interval_seconds < 5 {
+            return Err(invalid(&format!(
+                "connection.healthCheckIntervalSeconds must be at least 5, got {}",
+                self.connection.health_check_interval_seconds
+            )));
         }
+
+        let plan = &self.plan_analysis;
+        for (name, value) in [
+            ("largeTableRows", plan.large_table_rows),
+            ("nestedLoopRows", plan.nested_loop_rows),
+            ("hugeRowEstimate", plan.huge_row_estimate),
+        ] {
+            if value < 1 {
+                return Err(invalid(&format!(
+                    "planAnalysis.{} must be at least 1",
+                    name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid(message: &str) -> DbError {
+    DbError::InvalidInput(format!("Invalid setting: {}", message))
+}
+
+/// `#rgb` or `#rrggbb`
+fn is_hex_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Overlay `overlay` onto `base`, recursing into objects present in both
+fn merge_json(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
     }
 }
 
@@ -175,11 +341,46 @@ pub struct QuerySettings {
     /// Save query to history automatically
     pub auto_save_history: bool,
 
-    /// Maximum number of history entries to keep
+    /// Maximum number of history entries to keep (favorites not counted)
     pub max_history_entries: u32,
 
+    /// Days to keep history entries; 0 keeps them regardless of age
+    #[serde(default)]
+    pub history_retention_days: u32,
+
     /// Format SQL automatically before execution
     pub auto_format_sql: bool,
+
+    /// Queries running longer than this many milliseconds count as slow
+    #[serde(default = "default_slow_query_threshold_ms")]
+    pub slow_query_threshold_ms: u64,
+
+    /// LIMIT added to plain SELECTs that don't limit themselves (0 = unlimited)
+    #[serde(default = "default_row_limit")]
+    pub default_row_limit: u32,
+
+    /// Return Postgres `numeric` values as JSON numbers instead of exact
+    /// decimal strings, rounding what f64 can't hold; applies to
+    /// connections opened after the change
+    #[serde(default)]
+    pub numeric_as_number: bool,
+
+    /// Queries one connection runs at once; further queries wait for a
+    /// free slot. Statements inside an open transaction don't count.
+    #[serde(default = "default_max_concurrent_queries")]
+    pub max_concurrent_queries: usize,
+}
+
+fn default_slow_query_threshold_ms() -> u64 {
+    1000
+}
+
+fn default_row_limit() -> u32 {
+    1000
+}
+
+fn default_max_concurrent_queries() -> usize {
+    4
 }
 
 impl Default for QuerySettings {
@@ -191,7 +392,109 @@ impl Default for QuerySettings {
             confirm_destructive: true,
             auto_save_history: true,
             max_history_entries: 500,
+            history_retention_days: 0,
             auto_format_sql: false,
+            slow_query_threshold_ms: default_slow_query_threshold_ms(),
+            default_row_limit: default_row_limit(),
+            numeric_as_number: false,
+            max_concurrent_queries: default_max_concurrent_queries(),
+        }
+    }
+}
+
+/// Connection health settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionSettings {
+    /// Periodically ping active connections to detect drops
+    pub health_check_enabled: bool,
+
+    /// Seconds between health checks (minimum 5)
+    pub health_check_interval_seconds: u32,
+
+    /// Send keepalive pings on idle connections whose profile sets
+    /// `keepalive_interval_secs`
+    #[serde(default = "default_keepalive_enabled")]
+    pub keepalive_enabled: bool,
+}
+
+fn default_keepalive_enabled() -> bool {
+    true
+}
+
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        Self {
+            health_check_enabled: true,
+            health_check_interval_seconds: 60,
+            keepalive_enabled: default_keepalive_enabled(),
+        }
+    }
+}
+
+/// SQL linter settings
+///
+/// One switch per rule; rules added later default to on for settings files
+/// that predate them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LintSettings {
+    /// Show lint diagnostics in the editor
+    pub enabled: bool,
+
+    /// Flag `SELECT *`
+    pub select_star: bool,
+
+    /// Flag comma joins without WHERE and JOINs without ON/USING
+    pub implicit_cross_join: bool,
+
+    /// Flag `= NULL` and `<> NULL` comparisons
+    pub null_comparison: bool,
+
+    /// Flag functions wrapped around columns in WHERE comparisons
+    pub non_sargable_predicate: bool,
+
+    /// Flag UPDATE and DELETE statements without WHERE
+    pub missing_where: bool,
+}
+
+impl Default for LintSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            select_star: true,
+            implicit_cross_join: true,
+            null_comparison: true,
+            non_sargable_predicate: true,
+            missing_where: true,
+        }
+    }
+}
+
+/// Query plan analyzer thresholds
+///
+/// All counts are planner estimates, compared against the figures in
+/// `EXPLAIN` output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlanAnalysisSettings {
+    /// Full scans of tables with at least this many rows are flagged
+    pub large_table_rows: u64,
+
+    /// Nested loops probing at least this many outer x inner row pairs
+    /// without an index on the inner side are flagged
+    pub nested_loop_rows: u64,
+
+    /// Plan steps estimated to produce at least this many rows are flagged
+    pub huge_row_estimate: u64,
+}
+
+impl Default for PlanAnalysisSettings {
+    fn default() -> Self {
+        Self {
+            large_table_rows: 10_000,
+            nested_loop_rows: 100_000,
+            huge_row_estimate: 1_000_000,
         }
     }
 }
@@ -288,6 +591,9 @@ mod tests {
         assert!(!query.auto_commit);
         assert!(query.confirm_destructive);
         assert!(query.auto_save_history);
+        assert_eq!(query.default_row_limit, 1000);
+        assert!(!query.numeric_as_number);
+        assert_eq!(query.max_concurrent_queries, 4);
     }
 
     #[test]
@@ -298,6 +604,84 @@ mod tests {
         assert_eq!(shortcuts.save_snippet, "Ctrl+S");
     }
 
+    #[test]
+    fn test_connection_settings_default_when_missing() {
+        let mut json = serde_json::to_value(AppSettings::default()).unwrap();
+        json.as_object_mut().unwrap().remove("connection");
+
+        let settings: AppSettings = serde_json::from_value(json).unwrap();
+        assert!(settings.connection.health_check_enabled);
+        assert_eq!(settings.connection.health_check_interval_seconds, 60);
+        assert!(settings.connection.keepalive_enabled);
+    }
+
+    #[test]
+    fn test_keepalive_enabled_when_missing() {
+        let json = serde_json::json!({
+            "healthCheckEnabled": false,
+            "healthCheckIntervalSeconds": 30
+        });
+        let connection: ConnectionSettings = serde_json::from_value(json).unwrap();
+        assert!(connection.keepalive_enabled);
+    }
+
+    #[test]
+    fn test_lint_settings_partial() {
+        let json = serde_json::json!({ "enabled": true, "selectStar": false });
+        let lint: LintSettings = serde_json::from_value(json).unwrap();
+        assert!(!lint.select_star);
+        assert!(lint.missing_where);
+    }
+
+    #[test]
+    fn test_migrate_fills_missing_fields() {
+        let stored = serde_json::json!({
+            "general": { "language": "fr" },
+            "theme": { "editorFontSize": 16 },
+            "query": { "timeoutSeconds": 60 }
+        });
+
+        let settings = AppSettings::migrate(stored).unwrap();
+        assert_eq!(settings.schema_version, CURRENT_SETTINGS_VERSION);
+        assert_eq!(settings.general.language, "fr");
+        assert_eq!(settings.general.update_check_interval_hours, 24);
+        assert_eq!(settings.theme.editor_font_size, 16);
+        assert_eq!(settings.theme.accent_color, "#f59e0b");
+        assert_eq!(settings.query.timeout_seconds, 60);
+        assert_eq!(settings.query.max_rows, 1000);
+        assert_eq!(settings.shortcuts.execute_query, "Ctrl+Enter");
+    }
+
+    #[test]
+    fn test_migrate_rejects_wrong_types() {
+        let stored = serde_json::json!({ "query": { "timeoutSeconds": -5 } });
+        assert!(AppSettings::migrate(stored).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(AppSettings::default().validate().is_ok());
+
+        let mut settings = AppSettings::default();
+        settings.query.max_concurrent_queries = 0;
+        match settings.validate() {
+            Err(DbError::InvalidInput(msg)) => assert!(msg.contains("query.maxConcurrentQueries")),
+            other => panic!("expected InvalidInput, got {:?}", other),
+        }
+
+        let mut settings = AppSettings::default();
+        settings.query.timeout_seconds = 90_000;
+        assert!(settings.validate().is_err());
+
+        let mut settings = AppSettings::default();
+        settings.theme.accent_color = "amber".to_string();
+        assert!(settings.validate().is_err());
+
+        let mut settings = AppSettings::default();
+        settings.connection.health_check_interval_seconds = 1;
+        assert!(settings.validate().is_err());
+    }
+
     #[test]
     fn test_serialization() {
         let settings = AppSettings::default();
@@ -39,6 +39,38 @@ const DBHive = {
         };
     },
 
+    // ========== SQL API ==========
+
+    /**
+     * Format SQL text (no database access, no permission required)
+     * @param {string} sql - SQL to format
+     * @param {string} [dialect] - Driver name whose quoting rules apply, e.g. 'MySql'
+     * @param {Object} [options] - Formatting options
+     * @param {boolean} [options.uppercaseKeywords=true] - Uppercase keywords
+     * @param {number} [options.indentWidth=2] - Spaces per indentation level
+     * @param {number} [options.linesBetweenQueries=2] - Line breaks between statements
+     * @returns {Promise<string>} Formatted SQL
+     */
+    formatSql: async (sql, dialect, options) => {
+        return await __dbhive_internal__.formatSql(
+            sql,
+            dialect ?? null,
+            options ? JSON.stringify(options) : null
+        );
+    },
+
+    /**
+     * Lint SQL for common anti-patterns using the user's lint settings
+     * (no database access, no permission required). Intended for analyzer plugins.
+     * @param {string} sql - SQL to lint
+     * @param {string} [dialect] - Driver name whose quoting rules apply, e.g. 'SqlServer'
+     * @returns {Promise<Array<{severity: string, span: {start: number, end: number}, rule: string, message: string}>>}
+     *   Diagnostics ordered by position; spans are byte offsets into `sql`
+     */
+    lintSql: async (sql, dialect) => {
+        return JSON.parse(await __dbhive_internal__.lintSql(sql, dialect ?? null));
+    },
+
     // ========== UI API ==========
 
     /**
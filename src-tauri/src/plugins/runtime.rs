@@ -32,6 +32,26 @@ fn permission_to_string(p: &PluginPermission) -> String {
     }
 }
 
+/// Parse an optional SQL dialect (driver name such as `"MySql"`) argument
+fn dialect_arg(
+    args: &[JsValue],
+    index: usize,
+    ctx: &mut Context,
+) -> boa_engine::JsResult<Option<crate::models::DbDriver>> {
+    let value = args.get_or_undefined(index);
+    if value.is_null_or_undefined() {
+        return Ok(None);
+    }
+    let name = value.to_string(ctx)?.to_std_string_escaped();
+    serde_json::from_value(JsonValue::String(name.clone()))
+        .map(Some)
+        .map_err(|_| {
+            JsNativeError::error()
+                .with_message(format!("Unknown SQL dialect: {}", name))
+                .into()
+        })
+}
+
 /// Enforce a manifest-declared permission at an API call site.
 /// `granted` is derived from the plugin's manifest permissions at runtime setup.
 fn require_permission(granted: bool, permission: &str) -> boa_engine::JsResult<()> {
@@ -123,6 +143,8 @@ pub struct PluginRuntimeSync {
     data_dir: PathBuf,
     /// Plugin configuration as JSON string
     config_str: Option<String>,
+    /// User's SQL lint settings as JSON string, applied by `lintSql`
+    lint_settings_str: String,
 }
 
 impl PluginRuntimeSync {
@@ -152,12 +174,19 @@ impl PluginRuntimeSync {
         // Convert config to string
         let config_str = plugin.config.as_ref().map(|c| c.to_string());
 
+        let lint_settings = crate::commands::settings::load_settings(app_handle)
+            .map(|s| s.lint)
+            .unwrap_or_default();
+        let lint_settings_str = serde_json::to_string(&lint_settings)
+            .map_err(|e| PluginError::Other(e.to_string()))?;
+
         Ok(Self {
             context,
             plugin_id: plugin.manifest.id.clone(),
             permissions,
             data_dir,
             config_str,
+            lint_settings_str,
         })
     }
 
@@ -264,6 +293,7 @@ impl PluginRuntimeSync {
 
         // Config as string
         let config_str = self.config_str.clone();
+        let lint_settings_str = self.lint_settings_str.clone();
 
         // Create internal API object
         let internal = ObjectInitializer::new(&mut self.context)
@@ -464,6 +494,62 @@ impl PluginRuntimeSync {
                 js_string!("getConfig"),
                 0,
             )
+            // formatSql - pure text transformation, no permission needed
+            .function(
+                NativeFunction::from_copy_closure(move |_this, args, ctx| {
+                    let sql = args
+                        .get_or_undefined(0)
+                        .to_string(ctx)?
+                        .to_std_string_escaped();
+
+                    let dialect = dialect_arg(args, 1, ctx)?;
+
+                    let options = if args.get_or_undefined(2).is_null_or_undefined() {
+                        crate::sql::format::FormatOptions::default()
+                    } else {
+                        let options = args
+                            .get_or_undefined(2)
+                            .to_string(ctx)?
+                            .to_std_string_escaped();
+                        serde_json::from_str(&options).map_err(|e| {
+                            JsNativeError::error()
+                                .with_message(format!("Invalid format options: {}", e))
+                        })?
+                    };
+                    options
+                        .validate()
+                        .map_err(|e| JsNativeError::error().with_message(e.to_string()))?;
+
+                    let formatted =
+                        crate::sql::format::format_sql(&sql, dialect.as_ref(), &options);
+                    Ok(JsValue::String(js_string!(formatted)))
+                }),
+                js_string!("formatSql"),
+                3,
+            )
+            // lintSql - pure text analysis using the user's lint settings
+            .function(
+                NativeFunction::from_copy_closure_with_captures(
+                    move |_this, args, lint_settings_str, ctx| {
+                        let sql = args
+                            .get_or_undefined(0)
+                            .to_string(ctx)?
+                            .to_std_string_escaped();
+                        let dialect = dialect_arg(args, 1, ctx)?;
+                        let settings: crate::models::LintSettings =
+                            serde_json::from_str(lint_settings_str).unwrap_or_default();
+
+                        let diagnostics =
+                            crate::sql::lint::lint_sql(&sql, dialect.as_ref(), &settings);
+                        let json = serde_json::to_string(&diagnostics)
+                            .map_err(|e| JsNativeError::error().with_message(e.to_string()))?;
+                        Ok(JsValue::String(js_string!(json)))
+                    },
+                    lint_settings_str,
+                ),
+                js_string!("lintSql"),
+                2,
+            )
             // registerUiComponent - stub
             .function(
                 NativeFunction::from_copy_closure_with_captures(
@@ -0,0 +1,327 @@
+//! Affected-row previews for UPDATE and DELETE
+//!
+//! A single-table `UPDATE ... WHERE` or `DELETE FROM ... WHERE` touches
+//! exactly the rows `SELECT COUNT(*) FROM <table> WHERE <condition>` counts,
+//! so those are rewritten and counted without running the mutation. Anything
+//! where that equivalence breaks (joins, `LIMIT`, CTEs, `UPDATE OR IGNORE`)
+//! is reported as needing a run inside a rolled-back transaction instead.
+
+use super::tokenizer::{statement_verbs, tokenize_with, Quoting, Token, TokenKind};
+use crate::models::DbError;
+
+/// How to find out how many rows a statement affects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AffectedRowsPlan {
+    /// Run this `SELECT COUNT(*)` query
+    Count(String),
+    /// Run the statement in a transaction and roll it back, for this reason
+    Rollback(&'static str),
+}
+
+/// Significant tokens of one statement and the paren depth of each
+struct Statement<'a> {
+    sql: &'a str,
+    tokens: Vec<Token<'a>>,
+    depths: Vec<usize>,
+}
+
+impl<'a> Statement<'a> {
+    fn parse(sql: &'a str, quoting: Quoting) -> Self {
+        let mut tokens: Vec<Token> = tokenize_with(sql, quoting)
+            .filter(|t| !t.is_trivia())
+            .collect();
+        while tokens
+            .last()
+            .is_some_and(|t| t.kind == TokenKind::Semicolon)
+        {
+            tokens.pop();
+        }
+
+        let mut depths = Vec::with_capacity(tokens.len());
+        let mut depth = 0usize;
+        for token in &tokens {
+            if token.kind == TokenKind::Punct && token.text == ")" {
+                depth = depth.saturating_sub(1);
+            }
+            depths.push(depth);
+            if token.kind == TokenKind::Punct && token.text == "(" {
+                depth += 1;
+            }
+        }
+        Self {
+            sql,
+            tokens,
+            depths,
+        }
+    }
+
+    /// Whether token `i` is the top-level keyword `word`
+    fn is_keyword(&self, i: usize, word: &str) -> bool {
+        self.depths[i] == 0
+            && self.tokens[i].kind == TokenKind::Word
+            && self.tokens[i].text.eq_ignore_ascii_case(word)
+    }
+
+    /// First top-level keyword out of `words` at or after `from`
+    fn find_keyword(&self, from: usize, words: &[&str]) -> Option<(usize, String)> {
+        (from..self.tokens.len()).find_map(|i| {
+            words
+                .iter()
+                .find(|w| self.is_keyword(i, w))
+                .map(|w| (i, w.to_string()))
+        })
+    }
+
+    /// Source text of tokens `start..end`, comments inside included
+    fn text(&self, start: usize, end: usize) -> &'a str {
+        let last = &self.tokens[end - 1];
+        &self.sql[self.tokens[start].start..last.start + last.text.len()]
+    }
+
+    /// Whether tokens `start..end` name more than one table
+    fn is_join(&self, start: usize, end: usize) -> bool {
+        (start..end).any(|i| {
+            self.is_keyword(i, "JOIN") || (self.depths[i] == 0 && self.tokens[i].text == ",")
+        })
+    }
+
+    /// The WHERE condition starting at `start`, up to RETURNING or the end
+    fn condition(&self, start: usize) -> Result<(usize, usize), &'static str> {
+        if self.is_keyword(start, "CURRENT") {
+            return Err("the statement updates the current row of a cursor");
+        }
+        match self.find_keyword(start, &["RETURNING", "ORDER", "LIMIT"]) {
+            Some((_, kw)) if kw != "RETURNING" => Err("the statement has ORDER BY or LIMIT"),
+            Some((end, _)) => Ok((start, end)),
+            None => Ok((start, self.tokens.len())),
+        }
+    }
+}
+
+/// Plan how to count the rows an UPDATE or DELETE statement affects
+///
+/// For an UPDATE this counts the rows it matches, which on MySQL can be more
+/// than the rows it reports as changed (rows already holding the new values
+/// are not counted there).
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` if `sql` is not exactly one UPDATE or
+/// DELETE statement.
+pub fn plan_affected_rows(sql: &str, quoting: Quoting) -> Result<AffectedRowsPlan, DbError> {
+    let verbs = statement_verbs(sql, quoting);
+    if verbs.len() != 1 {
+        return Err(DbError::InvalidInput(
+            "Preview one statement at a time".to_string(),
+        ));
+    }
+    if !matches!(verbs[0].as_str(), "UPDATE" | "DELETE") {
+        return Err(DbError::InvalidInput(
+            "Only UPDATE and DELETE statements can be previewed".to_string(),
+        ));
+    }
+
+    let statement = Statement::parse(sql, quoting);
+    if statement.is_keyword(0, "WITH") {
+        return Ok(AffectedRowsPlan::Rollback(
+            "the statement has a WITH clause",
+        ));
+    }
+    let plan = if verbs[0] == "UPDATE" {
+        plan_update(&statement)?
+    } else {
+        plan_delete(&statement)?
+    };
+    Ok(plan)
+}
+
+fn missing(what: &str) -> DbError {
+    DbError::InvalidInput(format!("Could not find the {} of the statement", what))
+}
+
+fn count_query(
+    statement: &Statement,
+    table: (usize, usize),
+    condition: Option<(usize, usize)>,
+) -> AffectedRowsPlan {
+    let mut sql = format!("SELECT COUNT(*) FROM {}", statement.text(table.0, table.1));
+    if let Some((start, end)) = condition.filter(|(start, end)| start < end) {
+        sql.push_str(" WHERE ");
+        sql.push_str(statement.text(start, end));
+    }
+    AffectedRowsPlan::Count(sql)
+}
+
+fn plan_delete(statement: &Statement) -> Result<AffectedRowsPlan, DbError> {
+    // `DELETE t1 FROM t1 JOIN ...` (MySQL) or `DELETE TOP (5) ...`
+    if statement.tokens.len() < 2 || !statement.is_keyword(1, "FROM") {
+        return Ok(AffectedRowsPlan::Rollback(
+            "the DELETE names its target before FROM",
+        ));
+    }
+
+    let clause = statement.find_keyword(
+        2,
+        &[
+            "WHERE",
+            "USING",
+            "FROM",
+            "OUTPUT",
+            "RETURNING",
+            "ORDER",
+            "LIMIT",
+        ],
+    );
+    let table_end = clause.as_ref().map_or(statement.tokens.len(), |(i, _)| *i);
+    if table_end == 2 {
+        return Err(missing("table"));
+    }
+    if statement.is_join(2, table_end) {
+        return Ok(AffectedRowsPlan::Rollback("the DELETE joins other tables"));
+    }
+
+    let condition = match clause.as_ref().map(|(i, kw)| (*i, kw.as_str())) {
+        None | Some((_, "RETURNING")) => None,
+        Some((i, "WHERE")) => match statement.condition(i + 1) {
+            Ok(condition) => Some(condition),
+            Err(reason) => return Ok(AffectedRowsPlan::Rollback(reason)),
+        },
+        Some((_, "USING" | "FROM")) => {
+            return Ok(AffectedRowsPlan::Rollback("the DELETE joins other tables"))
+        }
+        Some((_, "OUTPUT")) => {
+            return Ok(AffectedRowsPlan::Rollback(
+                "the DELETE has an OUTPUT clause",
+            ))
+        }
+        Some(_) => {
+            return Ok(AffectedRowsPlan::Rollback(
+                "the statement has ORDER BY or LIMIT",
+            ))
+        }
+    };
+    Ok(count_query(statement, (2, table_end), condition))
+}
+
+fn plan_update(statement: &Statement) -> Result<AffectedRowsPlan, DbError> {
+    // SQLite `UPDATE OR IGNORE`, MySQL `UPDATE IGNORE`, SQL Server `UPDATE TOP (n)`
+    if ["OR", "IGNORE", "LOW_PRIORITY", "TOP"]
+        .iter()
+        .any(|w| statement.tokens.len() > 1 && statement.is_keyword(1, w))
+    {
+        return Ok(AffectedRowsPlan::Rollback(
+            "a modifier of the UPDATE changes which rows it updates",
+        ));
+    }
+
+    let (set, _) = statement
+        .find_keyword(1, &["SET"])
+        .ok_or_else(|| missing("SET clause"))?;
+    if set == 1 {
+        return Err(missing("table"));
+    }
+    if statement.is_join(1, set) {
+        return Ok(AffectedRowsPlan::Rollback("the UPDATE joins other tables"));
+    }
+
+    let clause = statement.find_keyword(
+        set + 1,
+        &["WHERE", "FROM", "OUTPUT", "RETURNING", "ORDER", "LIMIT"],
+    );
+    let condition = match clause.as_ref().map(|(i, kw)| (*i, kw.as_str())) {
+        None | Some((_, "RETURNING")) => None,
+        Some((i, "WHERE")) => match statement.condition(i + 1) {
+            Ok(condition) => Some(condition),
+            Err(reason) => return Ok(AffectedRowsPlan::Rollback(reason)),
+        },
+        Some((_, "FROM")) => {
+            return Ok(AffectedRowsPlan::Rollback("the UPDATE joins other tables"))
+        }
+        Some((_, "OUTPUT")) => {
+            return Ok(AffectedRowsPlan::Rollback(
+                "the UPDATE has an OUTPUT clause",
+            ))
+        }
+        Some(_) => {
+            return Ok(AffectedRowsPlan::Rollback(
+                "the statement has ORDER BY or LIMIT",
+            ))
+        }
+    };
+    Ok(count_query(statement, (1, set), condition))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(sql: &str) -> String {
+        match plan_affected_rows(sql, Quoting::Standard).unwrap() {
+            AffectedRowsPlan::Count(sql) => sql,
+            other => panic!("expected a count for {}, got {:?}", sql, other),
+        }
+    }
+
+    fn is_rollback(sql: &str, quoting: Quoting) -> bool {
+        matches!(
+            plan_affected_rows(sql, quoting),
+            Ok(AffectedRowsPlan::Rollback(_))
+        )
+    }
+
+    #[test]
+    fn test_rewrites_single_table_statements() {
+        assert_eq!(
+            count("UPDATE users u SET name = 'x', updated_at = now() WHERE u.id IN (SELECT user_id FROM orders WHERE total > 10) RETURNING id;"),
+            "SELECT COUNT(*) FROM users u WHERE u.id IN (SELECT user_id FROM orders WHERE total > 10)"
+        );
+        assert_eq!(
+            count("update t set x = extract(year from d) where y = 'where'"),
+            "SELECT COUNT(*) FROM t WHERE y = 'where'"
+        );
+        assert_eq!(
+            count("DELETE FROM ONLY logs -- old rows\nWHERE at < now() - interval '30 days'"),
+            "SELECT COUNT(*) FROM ONLY logs WHERE at < now() - interval '30 days'"
+        );
+        assert_eq!(
+            count("DELETE FROM sessions"),
+            "SELECT COUNT(*) FROM sessions"
+        );
+    }
+
+    #[test]
+    fn test_falls_back_to_rollback() {
+        let mysql = Quoting::MySql;
+        assert!(is_rollback(
+            "DELETE t1 FROM t1 JOIN t2 ON t1.id = t2.id",
+            mysql
+        ));
+        assert!(is_rollback(
+            "DELETE FROM t USING u WHERE t.id = u.id",
+            Quoting::Standard
+        ));
+        assert!(is_rollback(
+            "UPDATE a JOIN b ON a.id = b.id SET a.x = 1",
+            mysql
+        ));
+        assert!(is_rollback(
+            "UPDATE a SET x = b.x FROM b WHERE a.id = b.id",
+            Quoting::Standard
+        ));
+        assert!(is_rollback(
+            "DELETE FROM t WHERE x = 1 ORDER BY id LIMIT 10",
+            mysql
+        ));
+        assert!(is_rollback(
+            "UPDATE OR IGNORE t SET x = 1",
+            Quoting::Standard
+        ));
+        assert!(is_rollback(
+            "WITH old AS (SELECT id FROM t) DELETE FROM t WHERE id IN (SELECT id FROM old)",
+            Quoting::Standard
+        ));
+
+        assert!(plan_affected_rows("SELECT 1", Quoting::Standard).is_err());
+        assert!(plan_affected_rows("DELETE FROM a; DELETE FROM b", Quoting::Standard).is_err());
+    }
+}
@@ -0,0 +1,362 @@
+//! Structured row filters
+//!
+//! Compiles the column filters built in the data grid's filter UI into a
+//! parameterized WHERE clause. Column names are quoted for the target
+//! dialect and every value becomes a bound parameter in the driver's native
+//! placeholder syntax, so nothing the user types is interpolated into SQL.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::params::PlaceholderStyle;
+use super::quote::quote_identifier;
+use super::tokenizer::Quoting;
+use crate::models::{DbDriver, DbError};
+
+/// Comparison applied by a column filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOperator {
+    /// `column = value`
+    Eq,
+    /// `column <> value`
+    Neq,
+    /// `column > value`
+    Gt,
+    /// `column < value`
+    Lt,
+    /// `column LIKE value`; `%` and `_` in the value are wildcards
+    Like,
+    /// `column IN (values...)`
+    In,
+    /// `column IS NULL`; takes no value
+    IsNull,
+    /// `column BETWEEN values[0] AND values[1]`
+    Between,
+}
+
+/// A single condition on one column
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnFilter {
+    /// Column name, unquoted
+    pub column: String,
+    pub operator: FilterOperator,
+    /// Operand of the single-value operators (`eq`, `neq`, `gt`, `lt`, `like`)
+    #[serde(default)]
+    pub value: Option<Value>,
+    /// Operands of `in` (one or more) and `between` (exactly two)
+    #[serde(default)]
+    pub values: Vec<Value>,
+}
+
+/// How the members of a filter group are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterJoin {
+    #[default]
+    And,
+    Or,
+}
+
+/// Filters combined with AND or OR
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterGroup {
+    #[serde(default)]
+    pub join: FilterJoin,
+    pub filters: Vec<FilterNode>,
+}
+
+/// A column filter or a nested group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FilterNode {
+    Group(FilterGroup),
+    Column(ColumnFilter),
+}
+
+/// Build a parameterized WHERE clause from `filters`, combined with AND
+///
+/// Returns `WHERE ...` and the values to bind, in placeholder order, or an
+/// empty string and no values when there is nothing to filter on. Empty
+/// groups are ignored.
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` for a blank column name or an operator
+/// given the wrong number of values. Null operands are rejected too, since
+/// `= NULL` never matches; use `is_null` instead.
+pub fn build_where_clause(
+    filters: &[FilterNode],
+    driver: &DbDriver,
+) -> Result<(String, Vec<Value>), DbError> {
+    Ok(match filter_predicate(filters, driver)? {
+        Some((predicate, params)) => (format!("WHERE {}", predicate), params),
+        None => (String::new(), Vec::new()),
+    })
+}
+
+/// Like [`build_where_clause`], but without the `WHERE` keyword so the
+/// predicate can be combined with other conditions
+///
+/// Placeholders are numbered from 1, so any further parameters must follow
+/// the predicate in the final statement.
+pub fn filter_predicate(
+    filters: &[FilterNode],
+    driver: &DbDriver,
+) -> Result<Option<(String, Vec<Value>)>, DbError> {
+    let mut builder = WhereBuilder {
+        quoting: Quoting::for_driver(driver),
+        style: PlaceholderStyle::for_driver(driver),
+        params: Vec::new(),
+    };
+    let terms = builder.terms(filters)?;
+    if terms.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some((terms.join(" AND "), builder.params)))
+}
+
+/// Accumulates bound values while rendering a filter tree
+struct WhereBuilder {
+    quoting: Quoting,
+    style: PlaceholderStyle,
+    params: Vec<Value>,
+}
+
+impl WhereBuilder {
+    /// One rendered term per member of `nodes`; nested groups with more than
+    /// one member are parenthesized and empty ones are dropped
+    fn terms(&mut self, nodes: &[FilterNode]) -> Result<Vec<String>, DbError> {
+        let mut terms = Vec::new();
+        for node in nodes {
+            match node {
+                FilterNode::Column(filter) => terms.push(self.column(filter)?),
+                FilterNode::Group(group) => {
+                    let mut inner = self.terms(&group.filters)?;
+                    let separator = match group.join {
+                        FilterJoin::And => " AND ",
+                        FilterJoin::Or => " OR ",
+                    };
+                    match inner.len() {
+                        0 => {}
+                        1 => terms.append(&mut inner),
+                        _ => terms.push(format!("({})", inner.join(separator))),
+                    }
+                }
+            }
+        }
+        Ok(terms)
+    }
+
+    fn column(&mut self, filter: &ColumnFilter) -> Result<String, DbError> {
+        if filter.column.trim().is_empty() {
+            return Err(DbError::InvalidInput(
+                "Filter column name must not be empty".to_string(),
+            ));
+        }
+        let column = quote_identifier(&filter.column, self.quoting);
+
+        let comparison = match filter.operator {
+            FilterOperator::Eq => "=",
+            FilterOperator::Neq => "<>",
+            FilterOperator::Gt => ">",
+            FilterOperator::Lt => "<",
+            FilterOperator::Like => "LIKE",
+            FilterOperator::IsNull => return Ok(format!("{} IS NULL", column)),
+            FilterOperator::In => {
+                let values = operands(filter, 1, usize::MAX, "at least one value")?;
+                let placeholders: Vec<String> = values.iter().map(|v| self.bind(v)).collect();
+                return Ok(format!("{} IN ({})", column, placeholders.join(", ")));
+            }
+            FilterOperator::Between => {
+                let values = operands(filter, 2, 2, "exactly two values")?;
+                let low = self.bind(&values[0]);
+                let high = self.bind(&values[1]);
+                return Ok(format!("{} BETWEEN {} AND {}", column, low, high));
+            }
+        };
+
+        let value = match &filter.value {
+            Some(value) if !value.is_null() => value,
+            _ => {
+                return Err(DbError::InvalidInput(format!(
+                    "Filter on column '{}' needs a non-null value",
+                    filter.column
+                )))
+            }
+        };
+        Ok(format!("{} {} {}", column, comparison, self.bind(value)))
+    }
+
+    /// Record `value` as the next parameter and return its placeholder
+    fn bind(&mut self, value: &Value) -> String {
+        self.params.push(value.clone());
+        self.style.placeholder(self.params.len())
+    }
+}
+
+/// The `values` of a multi-value filter, which must number `min..=max`
+fn operands<'a>(
+    filter: &'a ColumnFilter,
+    min: usize,
+    max: usize,
+    expected: &str,
+) -> Result<&'a [Value], DbError> {
+    let count = filter.values.len();
+    if !(min..=max).contains(&count) || filter.values.iter().any(Value::is_null) {
+        return Err(DbError::InvalidInput(format!(
+            "Filter on column '{}' needs {}, none of them null",
+            filter.column, expected
+        )));
+    }
+    Ok(&filter.values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn filter(column: &str, operator: FilterOperator, value: Option<Value>) -> FilterNode {
+        FilterNode::Column(ColumnFilter {
+            column: column.to_string(),
+            operator,
+            value,
+            values: Vec::new(),
+        })
+    }
+
+    fn multi(column: &str, operator: FilterOperator, values: Vec<Value>) -> FilterNode {
+        FilterNode::Column(ColumnFilter {
+            column: column.to_string(),
+            operator,
+            value: None,
+            values,
+        })
+    }
+
+    #[test]
+    fn test_build_where_clause_operators() {
+        let filters = vec![
+            filter("name", FilterOperator::Like, Some(json!("a%"))),
+            filter("age", FilterOperator::Gt, Some(json!(18))),
+            filter("deleted_at", FilterOperator::IsNull, None),
+            multi(
+                "role",
+                FilterOperator::In,
+                vec![json!("admin"), json!("owner")],
+            ),
+            multi("score", FilterOperator::Between, vec![json!(1), json!(10)]),
+        ];
+
+        let (sql, params) = build_where_clause(&filters, &DbDriver::Postgres).unwrap();
+        assert_eq!(
+            sql,
+            "WHERE \"name\" LIKE $1 AND \"age\" > $2 AND \"deleted_at\" IS NULL \
+             AND \"role\" IN ($3, $4) AND \"score\" BETWEEN $5 AND $6"
+        );
+        assert_eq!(
+            params,
+            vec![
+                json!("a%"),
+                json!(18),
+                json!("admin"),
+                json!("owner"),
+                json!(1),
+                json!(10)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_where_clause_dialects() {
+        let filters = vec![filter("order", FilterOperator::Neq, Some(json!(1)))];
+
+        let (mysql, _) = build_where_clause(&filters, &DbDriver::MySql).unwrap();
+        assert_eq!(mysql, "WHERE `order` <> ?");
+        let (mssql, _) = build_where_clause(&filters, &DbDriver::SqlServer).unwrap();
+        assert_eq!(mssql, "WHERE [order] <> @P1");
+        let (sqlite, _) = build_where_clause(&filters, &DbDriver::Sqlite).unwrap();
+        assert_eq!(sqlite, "WHERE \"order\" <> ?");
+    }
+
+    #[test]
+    fn test_build_where_clause_groups() {
+        let filters = vec![
+            filter("active", FilterOperator::Eq, Some(json!(true))),
+            FilterNode::Group(FilterGroup {
+                join: FilterJoin::Or,
+                filters: vec![
+                    filter("a", FilterOperator::Eq, Some(json!(1))),
+                    filter("b", FilterOperator::Lt, Some(json!(2))),
+                ],
+            }),
+            FilterNode::Group(FilterGroup {
+                join: FilterJoin::Or,
+                filters: Vec::new(),
+            }),
+        ];
+
+        let (sql, params) = build_where_clause(&filters, &DbDriver::Postgres).unwrap();
+        assert_eq!(sql, "WHERE \"active\" = $1 AND (\"a\" = $2 OR \"b\" < $3)");
+        assert_eq!(params, vec![json!(true), json!(1), json!(2)]);
+
+        assert_eq!(
+            build_where_clause(&[], &DbDriver::Postgres).unwrap(),
+            (String::new(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_build_where_clause_keeps_values_out_of_sql() {
+        let filters = vec![filter(
+            "name\"; DROP TABLE users; --",
+            FilterOperator::Eq,
+            Some(json!("x' OR '1'='1")),
+        )];
+
+        let (sql, params) = build_where_clause(&filters, &DbDriver::Postgres).unwrap();
+        assert_eq!(sql, "WHERE \"name\"\"; DROP TABLE users; --\" = $1");
+        assert_eq!(params, vec![json!("x' OR '1'='1")]);
+    }
+
+    #[test]
+    fn test_build_where_clause_rejects_bad_operands() {
+        let invalid = [
+            filter("a", FilterOperator::Eq, None),
+            filter("a", FilterOperator::Eq, Some(Value::Null)),
+            filter(" ", FilterOperator::IsNull, None),
+            multi("a", FilterOperator::In, Vec::new()),
+            multi("a", FilterOperator::Between, vec![json!(1)]),
+            multi("a", FilterOperator::Between, vec![json!(1), Value::Null]),
+        ];
+        for node in invalid {
+            assert!(matches!(
+                build_where_clause(&[node], &DbDriver::Postgres),
+                Err(DbError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_filter_node_deserialize() {
+        let nodes: Vec<FilterNode> = serde_json::from_value(json!([
+            { "column": "id", "operator": "is_null" },
+            { "join": "or", "filters": [
+                { "column": "id", "operator": "in", "values": [1, 2] }
+            ] }
+        ]))
+        .unwrap();
+
+        assert_eq!(nodes[0], filter("id", FilterOperator::IsNull, None));
+        assert_eq!(
+            nodes[1],
+            FilterNode::Group(FilterGroup {
+                join: FilterJoin::Or,
+                filters: vec![multi("id", FilterOperator::In, vec![json!(1), json!(2)])],
+            })
+        );
+    }
+}
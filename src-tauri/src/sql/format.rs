@@ -0,0 +1,630 @@
+//! SQL pretty-printer
+//!
+//! Re-lays out SQL from its token stream: keywords get a consistent case,
+//! each major clause starts on its own line with its body indented below
+//! it, and subqueries are indented one level deeper. Strings, quoted
+//! identifiers and comments are copied verbatim, so formatting never
+//! changes what a query means. Layout depends only on the tokens, which
+//! makes formatting idempotent.
+
+use serde::{Deserialize, Serialize};
+
+use super::tokenizer::{tokenize_with, Quoting, Token, TokenKind};
+use crate::models::{DbDriver, DbError};
+
+/// Options for `format_sql`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormatOptions {
+    /// Uppercase keywords (`SELECT`); lowercase them when false
+    pub uppercase_keywords: bool,
+    /// Spaces per indentation level
+    pub indent_width: usize,
+    /// Line breaks between statements (1 puts the next statement on the
+    /// following line, 2 leaves a blank line between them)
+    pub lines_between_queries: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            uppercase_keywords: true,
+            indent_width: 2,
+            lines_between_queries: 2,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Reject option values that would produce unusable output
+    pub fn validate(&self) -> Result<(), DbError> {
+        if self.indent_width > 16 {
+            return Err(DbError::InvalidInput(
+                "Indent width must be at most 16".to_string(),
+            ));
+        }
+        if self.lines_between_queries > 10 {
+            return Err(DbError::InvalidInput(
+                "Lines between queries must be at most 10".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Words whose case is normalized. Function names are left as written.
+const KEYWORDS: &[&str] = &[
+    "ADD",
+    "ALL",
+    "ALTER",
+    "ANALYZE",
+    "AND",
+    "ANY",
+    "AS",
+    "ASC",
+    "BEGIN",
+    "BETWEEN",
+    "BY",
+    "CASCADE",
+    "CASE",
+    "CAST",
+    "CHECK",
+    "COLUMN",
+    "COMMIT",
+    "CONFLICT",
+    "CONSTRAINT",
+    "CREATE",
+    "CROSS",
+    "DEFAULT",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "DO",
+    "DROP",
+    "ELSE",
+    "END",
+    "EXCEPT",
+    "EXISTS",
+    "EXPLAIN",
+    "FALSE",
+    "FETCH",
+    "FILTER",
+    "FIRST",
+    "FOR",
+    "FOREIGN",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "IF",
+    "ILIKE",
+    "IN",
+    "INDEX",
+    "INNER",
+    "INSERT",
+    "INTERSECT",
+    "INTO",
+    "IS",
+    "JOIN",
+    "KEY",
+    "LAST",
+    "LATERAL",
+    "LEFT",
+    "LIKE",
+    "LIMIT",
+    "NATURAL",
+    "NEXT",
+    "NOT",
+    "NOTHING",
+    "NULL",
+    "NULLS",
+    "OFFSET",
+    "ON",
+    "ONLY",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "OVER",
+    "PARTITION",
+    "PRIMARY",
+    "RECURSIVE",
+    "REFERENCES",
+    "RETURNING",
+    "RIGHT",
+    "ROLLBACK",
+    "ROW",
+    "ROWS",
+    "SELECT",
+    "SET",
+    "TABLE",
+    "THEN",
+    "TOP",
+    "TRANSACTION",
+    "TRUE",
+    "TRUNCATE",
+    "UNION",
+    "UNIQUE",
+    "UPDATE",
+    "USING",
+    "VALUES",
+    "VIEW",
+    "WHEN",
+    "WHERE",
+    "WINDOW",
+    "WITH",
+];
+
+/// Clauses that go on their own line with their body indented below
+const BLOCK_CLAUSES: &[&str] = &[
+    "SELECT",
+    "FROM",
+    "WHERE",
+    "HAVING",
+    "SET",
+    "VALUES",
+    "RETURNING",
+    "LIMIT",
+    "OFFSET",
+];
+
+/// Clauses that start a new line but keep their body on the same line
+const LINE_CLAUSES: &[&str] = &[
+    "WITH",
+    "UNION",
+    "INTERSECT",
+    "EXCEPT",
+    "FETCH",
+    "INSERT",
+    "UPDATE",
+    "DELETE",
+];
+
+/// Words that may start a join (`LEFT JOIN`, `LEFT OUTER JOIN`, ...)
+const JOIN_MODIFIERS: &[&str] = &["INNER", "LEFT", "RIGHT", "FULL", "CROSS", "NATURAL"];
+
+/// Multi-character operators, longest first. The tokenizer emits operator
+/// characters one at a time; adjacent ones are glued back together.
+const OPERATORS: &[&str] = &[
+    "->>", "#>>", "!~*", "<=>", "->", "#>", "@>", "<@", "<=", ">=", "<>", "!=", "||", "&&", "<<",
+    ">>", "=>", "!~", "~*", "@@",
+];
+
+/// One unit of output: a token, or several adjacent operator characters
+struct Item<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    /// Uppercased text for words, for keyword matching
+    upper: String,
+    /// Whether the item directly follows the previous one in the input
+    touches_previous: bool,
+}
+
+impl Item<'_> {
+    fn is_keyword(&self) -> bool {
+        self.kind == TokenKind::Word && KEYWORDS.contains(&self.upper.as_str())
+    }
+
+    fn is(&self, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.upper == word
+    }
+}
+
+/// Split `sql` into items, dropping whitespace and gluing operators
+fn items(sql: &str, quoting: Quoting) -> Vec<Item<'_>> {
+    let tokens: Vec<Token<'_>> = tokenize_with(sql, quoting).collect();
+    let mut items: Vec<Item<'_>> = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i];
+        let touches_previous = i > 0 && tokens[i - 1].kind != TokenKind::Whitespace;
+        if token.kind == TokenKind::Whitespace {
+            i += 1;
+            continue;
+        }
+
+        let mut end = token.start + token.text.len();
+        let mut consumed = 1;
+        if token.kind == TokenKind::Punct {
+            let rest = &sql[token.start..];
+            if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+                // Only glue if every character came from its own Punct token
+                // (i.e. the operator is not partly inside a comment or string)
+                let mut len = 0;
+                let mut n = 0;
+                while len < op.len()
+                    && tokens
+                        .get(i + n)
+                        .is_some_and(|t| t.kind == TokenKind::Punct)
+                {
+                    len += tokens[i + n].text.len();
+                    n += 1;
+                }
+                if len == op.len() {
+                    end = token.start + op.len();
+                    consumed = n;
+                }
+            }
+        }
+
+        let text = &sql[token.start..end];
+        items.push(Item {
+            kind: token.kind,
+            text,
+            upper: if token.kind == TokenKind::Word {
+                text.to_ascii_uppercase()
+            } else {
+                String::new()
+            },
+            touches_previous,
+        });
+        i += consumed;
+    }
+
+    items
+}
+
+/// How an open parenthesis lays out its contents
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Paren {
+    /// Function call, column list, tuple: stays on one line
+    Inline,
+    /// Subquery: contents start on a new, deeper-indented line
+    Block {
+        /// Clause level to restore at the closing parenthesis
+        saved_base: usize,
+        /// Indent level of the line holding the opening parenthesis
+        line_level: usize,
+        /// `CASE` nesting to restore at the closing parenthesis
+        saved_case_depth: usize,
+    },
+}
+
+/// Output buffer that tracks indentation
+struct Writer<'o> {
+    out: String,
+    options: &'o FormatOptions,
+    /// Indent level of the current line
+    line_level: usize,
+    at_line_start: bool,
+}
+
+impl Writer<'_> {
+    /// Start a new line at `level`, or re-indent the current one if nothing
+    /// has been written on it yet
+    fn newline(&mut self, level: usize) {
+        if self.out.is_empty() {
+            self.line_level = level;
+            return;
+        }
+        if self.at_line_start {
+            let line_start = self.out.rfind('\n').map_or(0, |p| p + 1);
+            self.out.truncate(line_start);
+        } else {
+            self.out.truncate(self.out.trim_end_matches(' ').len());
+            self.out.push('\n');
+        }
+        self.out
+            .push_str(&" ".repeat(level * self.options.indent_width));
+        self.line_level = level;
+        self.at_line_start = true;
+    }
+
+    fn write(&mut self, text: &str, space_before: bool) {
+        if space_before && !self.at_line_start && !self.out.is_empty() {
+            self.out.push(' ');
+        }
+        self.out.push_str(text);
+        self.at_line_start = false;
+    }
+}
+
+/// Whether a space goes between `prev` and `item`
+fn space_between(
+    prev: &Item<'_>,
+    item: &Item<'_>,
+    prev_unary: bool,
+    before_prev: Option<&Item<'_>>,
+) -> bool {
+    if prev_unary {
+        return false;
+    }
+    if matches!(prev.text, "(" | "[" | "." | "::" | "@") {
+        return false;
+    }
+    if matches!(item.text, "," | ";" | ")" | "]" | "." | "::") {
+        return false;
+    }
+    match item.text {
+        "(" => {
+            let callable = matches!(prev.kind, TokenKind::Word | TokenKind::QuotedIdentifier)
+                && (!prev.is_keyword()
+                    || matches!(
+                        prev.upper.as_str(),
+                        "CAST" | "LEFT" | "RIGHT" | "IF" | "EXISTS"
+                    ));
+            // `INSERT INTO t (a, b)` and `CREATE TABLE t (...)` keep a space
+            let names_table = before_prev.is_some_and(|b| {
+                matches!(
+                    b.upper.as_str(),
+                    "INTO" | "TABLE" | "VIEW" | "INDEX" | "REFERENCES" | "EXISTS"
+                ) && b.kind == TokenKind::Word
+            });
+            !callable || names_table
+        }
+        "[" => {
+            !matches!(
+                prev.kind,
+                TokenKind::Word | TokenKind::QuotedIdentifier | TokenKind::Parameter
+            ) && !matches!(prev.text, ")" | "]")
+        }
+        // Prefixed strings: N'...', E'...', _utf8'...'
+        _ if item.kind == TokenKind::String
+            && item.touches_previous
+            && prev.kind == TokenKind::Word =>
+        {
+            false
+        }
+        _ => true,
+    }
+}
+
+/// Whether `-`/`+` at `item` is a sign rather than a binary operator
+fn is_unary(prev: Option<&Item<'_>>, item: &Item<'_>) -> bool {
+    if !matches!(item.text, "-" | "+") {
+        return false;
+    }
+    match prev {
+        None => true,
+        Some(prev) => {
+            (prev.kind == TokenKind::Punct && !matches!(prev.text, ")" | "]"))
+                || prev.is_keyword()
+                || prev.kind == TokenKind::Semicolon
+        }
+    }
+}
+
+/// Format `sql`
+///
+/// `dialect` selects the quoting rules used to find strings, quoted
+/// identifiers and comments (backslash escapes for MySQL, `[brackets]` for
+/// SQL Server); `None` uses standard SQL quoting.
+pub fn format_sql(sql: &str, dialect: Option<&DbDriver>, options: &FormatOptions) -> String {
+    let quoting = dialect.map(Quoting::for_driver).unwrap_or_default();
+    let items = items(sql, quoting);
+
+    let mut w = Writer {
+        out: String::with_capacity(sql.len() + sql.len() / 4),
+        options,
+        line_level: 0,
+        at_line_start: true,
+    };
+
+    // Indent level of the current clause keywords; bodies go one deeper
+    let mut base = 0;
+    let mut parens: Vec<Paren> = Vec::new();
+    let mut case_depth = 0usize;
+    let mut between_pending = false;
+    let mut pending_newline: Option<usize> = None;
+    let mut statement_ended = false;
+    let mut prev_unary = false;
+
+    for (i, item) in items.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|p| &items[p]);
+        let before_prev = i.checked_sub(2).map(|p| &items[p]);
+        let next = items.get(i + 1);
+        let inline = matches!(parens.last(), Some(Paren::Inline)) || case_depth > 0;
+        let body = base + 1;
+
+        if statement_ended {
+            statement_ended = false;
+            for _ in 1..options.lines_between_queries.max(1) {
+                w.out.push('\n');
+            }
+            w.newline(0);
+            pending_newline = None;
+        }
+
+        // Keywords never apply to qualified names (`t.order`)
+        let keyword = item.is_keyword() && prev.is_none_or(|p| p.text != ".");
+        let upper = if keyword { item.upper.as_str() } else { "" };
+
+        // Line breaks before the item
+        let mut space = prev.is_some_and(|p| space_between(p, item, prev_unary, before_prev));
+        if let Some(level) = pending_newline.take() {
+            w.newline(level);
+        }
+        if !inline && keyword {
+            let prev_word = prev
+                .filter(|p| p.kind == TokenKind::Word)
+                .map(|p| p.upper.as_str());
+            let next_word = next
+                .filter(|n| n.kind == TokenKind::Word)
+                .map(|n| n.upper.as_str());
+            let breaks = match upper {
+                // `IS DISTINCT FROM`
+                "FROM" => prev_word != Some("DISTINCT"),
+                // `ON DELETE SET NULL`
+                "SET" => !matches!(prev_word, Some("DELETE" | "UPDATE")),
+                // Only at the start of a statement or after a CTE, not in
+                // `ON DELETE`, `FOR UPDATE` or `DO UPDATE`
+                "INSERT" | "UPDATE" | "DELETE" => {
+                    prev.is_none_or(|p| matches!(p.kind, TokenKind::Semicolon) || p.text == ")")
+                }
+                "GROUP" | "ORDER" => next_word == Some("BY"),
+                "JOIN" => !prev_word.is_some_and(|w| JOIN_MODIFIERS.contains(&w) || w == "OUTER"),
+                w if JOIN_MODIFIERS.contains(&w) => {
+                    matches!(next_word, Some("JOIN" | "OUTER"))
+                        && !prev_word.is_some_and(|p| JOIN_MODIFIERS.contains(&p))
+                }
+                w => BLOCK_CLAUSES.contains(&w) || LINE_CLAUSES.contains(&w),
+            };
+            if breaks {
+                let is_join = upper == "JOIN" || JOIN_MODIFIERS.contains(&upper);
+                w.newline(if is_join { body } else { base });
+                if BLOCK_CLAUSES.contains(&upper) {
+                    pending_newline = Some(body);
+                }
+            }
+            if upper == "BY" && matches!(prev_word, Some("GROUP" | "ORDER")) {
+                pending_newline = Some(body);
+            }
+        }
+        if upper == "AND" && between_pending {
+            between_pending = false;
+        } else if !inline && matches!(upper, "AND" | "OR") {
+            w.newline(body);
+        }
+        if item.text == ")" {
+            if let Some(Paren::Block { line_level, .. }) = parens.last() {
+                w.newline(*line_level);
+                space = false;
+            }
+        }
+        if item.kind == TokenKind::LineComment && !w.at_line_start {
+            space = true;
+        }
+
+        // The item itself
+        if keyword {
+            if options.uppercase_keywords {
+                w.write(&item.upper, space);
+            } else {
+                w.write(&item.upper.to_ascii_lowercase(), space);
+            }
+        } else {
+            w.write(item.text, space);
+        }
+        prev_unary = is_unary(prev, item);
+
+        // Bookkeeping and line breaks after the item
+        match upper {
+            "CASE" => case_depth += 1,
+            "END" if case_depth > 0 => case_depth -= 1,
+            "BETWEEN" => between_pending = true,
+            _ => {}
+        }
+        match item.kind {
+            TokenKind::LineComment => pending_newline = Some(w.line_level),
+            TokenKind::Semicolon => {
+                parens.clear();
+                base = 0;
+                case_depth = 0;
+                between_pending = false;
+                pending_newline = None;
+                statement_ended = true;
+            }
+            _ => match item.text {
+                "(" => {
+                    let subquery = next.is_some_and(|n| n.is("SELECT") || n.is("WITH"));
+                    if subquery {
+                        parens.push(Paren::Block {
+                            saved_base: base,
+                            line_level: w.line_level,
+                            saved_case_depth: case_depth,
+                        });
+                        base = w.line_level + 1;
+                        case_depth = 0;
+                    } else {
+                        parens.push(Paren::Inline);
+                    }
+                }
+                ")" => {
+                    if let Some(Paren::Block {
+                        saved_base,
+                        saved_case_depth,
+                        ..
+                    }) = parens.pop()
+                    {
+                        base = saved_base;
+                        case_depth = saved_case_depth;
+                    }
+                }
+                "," if !inline => pending_newline = Some(body),
+                _ => {}
+            },
+        }
+    }
+
+    w.out.truncate(w.out.trim_end().len());
+    w.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(sql: &str) -> String {
+        format_sql(sql, None, &FormatOptions::default())
+    }
+
+    #[test]
+    fn test_format_select() {
+        assert_eq!(
+            format(
+                "select a, count(*) from t left join u on t.id=u.id where x between 1 and 2 \
+                 and y is not null group by a order by a desc limit 10"
+            ),
+            "SELECT\n  a,\n  count(*)\nFROM\n  t\n  LEFT JOIN u ON t.id = u.id\nWHERE\n  \
+             x BETWEEN 1 AND 2\n  AND y IS NOT NULL\nGROUP BY\n  a\nORDER BY\n  a DESC\nLIMIT\n  10"
+        );
+    }
+
+    #[test]
+    fn test_format_subquery_and_statements() {
+        assert_eq!(
+            format("SELECT * FROM t WHERE id IN (SELECT id FROM u);update t set a=-1"),
+            "SELECT\n  *\nFROM\n  t\nWHERE\n  id IN (\n    SELECT\n      id\n    FROM\n      u\n  \
+             );\n\nUPDATE t\nSET\n  a = -1"
+        );
+    }
+
+    #[test]
+    fn test_format_preserves_literals_and_operators() {
+        let formatted =
+            format("select data->>'k', \"Order\".x::int from \"Order\" where s = 'a  b' -- c\n");
+        assert_eq!(
+            formatted,
+            "SELECT\n  data ->> 'k',\n  \"Order\".x::int\nFROM\n  \"Order\"\nWHERE\n  s = 'a  b' -- c"
+        );
+
+        let mysql = format_sql(
+            r"select `from` from t where s = 'it\'s;' # note",
+            Some(&DbDriver::MySql),
+            &FormatOptions::default(),
+        );
+        assert_eq!(
+            mysql,
+            "SELECT\n  `from`\nFROM\n  t\nWHERE\n  s = 'it\\'s;' # note"
+        );
+    }
+
+    #[test]
+    fn test_format_options() {
+        let options = FormatOptions {
+            uppercase_keywords: false,
+            indent_width: 4,
+            lines_between_queries: 1,
+        };
+        assert_eq!(
+            format_sql("SELECT a FROM t; SELECT b FROM u", None, &options),
+            "select\n    a\nfrom\n    t;\nselect\n    b\nfrom\n    u"
+        );
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let queries = [
+            "select a, count(*) from t left outer join u on t.id=u.id and u.x>0 where x between 1 and 2",
+            "WITH x AS (SELECT 1 AS n) SELECT * FROM x; INSERT INTO t (a, b) VALUES (1, 'x'), (2, 'y')",
+            "select case when a=1 or b=2 then 'y' else 'n' end, coalesce((select max(v) from w), 0) from t",
+            "-- leading comment\nselect /* inline */ a -- trailing\nfrom t where a is distinct from b",
+            "create table if not exists t (id int primary key, ref int references u(id) on delete set null)",
+            "select * from t order by a nulls last offset 5 rows fetch next 10 rows only",
+        ];
+        for sql in queries {
+            let once = format(sql);
+            assert_eq!(format(&once), once, "not idempotent for {:?}", sql);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Default row limit for ad-hoc queries
+//!
+//! A `SELECT * FROM huge_table` typed into the editor should come back with
+//! the first rows rather than stall while millions are fetched. Plain SELECTs
+//! that don't already bound their result get the dialect's limit clause;
+//! anything else (aggregates, `SELECT ... INTO`, `FOR UPDATE`, an existing
+//! LIMIT/TOP/FETCH) is left untouched. Only top-level tokens are considered,
+//! so a LIMIT inside a subquery does not count as limiting the query.
+
+use super::tokenizer::{statement_verbs, tokenize_with, Quoting, Token, TokenKind};
+use crate::models::DbDriver;
+
+/// Top-level keywords that mean the query already limits itself, or that
+/// a limit clause can't simply be added
+const SKIP_KEYWORDS: &[&str] = &[
+    "limit", "top", "fetch", "offset", "into", "for", "lock", "group", "having",
+];
+
+/// Aggregate functions; a top-level call means one row per group, not a scan
+const AGGREGATES: &[&str] = &[
+    "count",
+    "sum",
+    "avg",
+    "min",
+    "max",
+    "array_agg",
+    "string_agg",
+    "group_concat",
+    "json_agg",
+    "jsonb_agg",
+    "json_arrayagg",
+    "json_objectagg",
+    "bool_and",
+    "bool_or",
+    "every",
+];
+
+/// Set operators; on SQL Server `TOP` would only apply to the first branch
+const SET_OPERATORS: &[&str] = &["union", "intersect", "except"];
+
+/// Rewrite `sql` to return at most `limit` rows, if it should be limited
+///
+/// Returns `None` when `limit` is 0, when `sql` is not a single SELECT, or
+/// when the query already limits or aggregates its rows. PostgreSQL, MySQL
+/// and SQLite get a trailing `LIMIT`; SQL Server gets `TOP (n)` after the
+/// outermost SELECT.
+pub fn apply_row_limit(sql: &str, driver: &DbDriver, limit: u32) -> Option<String> {
+    if limit == 0 || matches!(driver, DbDriver::MongoDb | DbDriver::Redis) {
+        return None;
+    }
+    let quoting = Quoting::for_driver(driver);
+    if statement_verbs(sql, quoting) != ["SELECT"] {
+        return None;
+    }
+
+    // Significant top-level tokens; `(` and `)` themselves are included
+    let mut top_level: Vec<Token> = Vec::new();
+    let mut last_end = 0;
+    let mut depth = 0usize;
+    for token in tokenize_with(sql, quoting) {
+        if token.is_trivia() || token.kind == TokenKind::Semicolon {
+            continue;
+        }
+        if token.kind == TokenKind::Punct && token.text == ")" {
+            depth = depth.saturating_sub(1);
+        }
+        if depth == 0 {
+            top_level.push(token);
+        }
+        if token.kind == TokenKind::Punct && token.text == "(" {
+            depth += 1;
+        }
+        last_end = token.start + token.text.len();
+    }
+
+    let is_word = |token: &Token, words: &[&str]| {
+        token.kind == TokenKind::Word && words.iter().any(|w| token.text.eq_ignore_ascii_case(w))
+    };
+    for (i, token) in top_level.iter().enumerate() {
+        if is_word(token, SKIP_KEYWORDS) {
+            return None;
+        }
+        let is_call = top_level.get(i + 1).is_some_and(|next| next.text == "(");
+        if is_call && is_word(token, AGGREGATES) {
+            return None;
+        }
+    }
+
+    if *driver == DbDriver::SqlServer {
+        if top_level.iter().any(|t| is_word(t, SET_OPERATORS)) {
+            return None;
+        }
+        let select = top_level.iter().position(|t| is_word(t, &["select"]))?;
+        let after = match top_level.get(select + 1) {
+            Some(next) if is_word(next, &["distinct", "all"]) => next,
+            _ => &top_level[select],
+        };
+        let at = after.start + after.text.len();
+        return Some(format!("{} TOP ({}){}", &sql[..at], limit, &sql[at..]));
+    }
+
+    // Before any trailing semicolon or comment
+    Some(format!(
+        "{} LIMIT {}{}",
+        &sql[..last_end],
+        limit,
+        &sql[last_end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_injects_dialect_limit() {
+        let pg = DbDriver::Postgres;
+        assert_eq!(
+            apply_row_limit("SELECT * FROM orders; -- all of them", &pg, 1000).as_deref(),
+            Some("SELECT * FROM orders LIMIT 1000; -- all of them")
+        );
+        assert_eq!(
+            apply_row_limit(
+                "SELECT * FROM t WHERE id IN (SELECT id FROM u LIMIT 5)",
+                &DbDriver::MySql,
+                50
+            )
+            .as_deref(),
+            Some("SELECT * FROM t WHERE id IN (SELECT id FROM u LIMIT 5) LIMIT 50")
+        );
+        assert_eq!(
+            apply_row_limit(
+                "WITH x AS (SELECT TOP 5 * FROM a) SELECT DISTINCT name FROM x",
+                &DbDriver::SqlServer,
+                100
+            )
+            .as_deref(),
+            Some("WITH x AS (SELECT TOP 5 * FROM a) SELECT DISTINCT TOP (100) name FROM x")
+        );
+    }
+
+    #[test]
+    fn test_leaves_bounded_queries_alone() {
+        let pg = DbDriver::Postgres;
+        for sql in [
+            "SELECT * FROM t LIMIT 10",
+            "SELECT * FROM t ORDER BY id FETCH FIRST 5 ROWS ONLY",
+            "SELECT count(*) FROM t",
+            "SELECT status, sum(total) FROM t GROUP BY status",
+            "SELECT * INTO backup FROM t",
+            "SELECT * FROM t FOR UPDATE",
+            "UPDATE t SET x = 1",
+            "SELECT 1; SELECT 2",
+        ] {
+            assert_eq!(apply_row_limit(sql, &pg, 1000), None, "{}", sql);
+        }
+        assert_eq!(apply_row_limit("SELECT * FROM t", &pg, 0), None);
+        assert_eq!(
+            apply_row_limit(
+                "SELECT a FROM t UNION SELECT a FROM u",
+                &DbDriver::SqlServer,
+                10
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,665 @@
+//! SQL linter
+//!
+//! Flags common SQL anti-patterns straight from the token stream. There is
+//! no parser behind it, so every rule is a heuristic that errs on the side of
+//! staying quiet: when a pattern is ambiguous, nothing is reported.
+//!
+//! Statements are linted one at a time. Each `(` opens a frame that tracks
+//! which clause (SELECT list, FROM, WHERE, ...) the tokens inside belong to,
+//! which is enough to tell `SELECT *` from `COUNT(*)` or a comma join from a
+//! comma between function arguments.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{DbDriver, LintSettings};
+use crate::sql::tokenizer::{tokenize_with, Quoting, Token, TokenKind};
+
+/// A lint rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LintRule {
+    /// `SELECT *` or `SELECT t.*`
+    SelectStar,
+    /// Comma join without a WHERE clause, or a JOIN without ON/USING
+    ImplicitCrossJoin,
+    /// `= NULL`, `<> NULL` and friends, which are never true
+    NullComparison,
+    /// A function wrapped around a column on the left of a WHERE comparison
+    NonSargablePredicate,
+    /// UPDATE or DELETE without a WHERE clause
+    MissingWhere,
+}
+
+impl LintRule {
+    /// Severity diagnostics of this rule are reported with
+    pub fn severity(self) -> LintSeverity {
+        match self {
+            LintRule::SelectStar | LintRule::NonSargablePredicate => LintSeverity::Info,
+            LintRule::ImplicitCrossJoin | LintRule::NullComparison | LintRule::MissingWhere => {
+                LintSeverity::Warning
+            }
+        }
+    }
+
+    fn is_enabled(self, settings: &LintSettings) -> bool {
+        match self {
+            LintRule::SelectStar => settings.select_star,
+            LintRule::ImplicitCrossJoin => settings.implicit_cross_join,
+            LintRule::NullComparison => settings.null_comparison,
+            LintRule::NonSargablePredicate => settings.non_sargable_predicate,
+            LintRule::MissingWhere => settings.missing_where,
+        }
+    }
+}
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// Style or performance hint
+    Info,
+    /// Likely bug or dangerous statement
+    Warning,
+}
+
+/// Byte range in the linted SQL text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A problem found by the linter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintDiagnostic {
+    pub severity: LintSeverity,
+    pub span: Span,
+    pub rule: LintRule,
+    pub message: String,
+}
+
+impl LintDiagnostic {
+    fn new(rule: LintRule, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            severity: rule.severity(),
+            span,
+            rule,
+            message: message.into(),
+        }
+    }
+}
+
+/// Words that may precede `(` without being a function call
+const NOT_FUNCTIONS: &[&str] = &[
+    "ALL", "AND", "ANY", "BETWEEN", "ELSE", "EXISTS", "ILIKE", "IN", "IS", "LIKE", "NOT", "ON",
+    "OR", "ROW", "SOME", "THEN", "WHEN", "WHERE",
+];
+
+/// Words that can appear among function arguments without naming a column
+const NOT_COLUMNS: &[&str] = &[
+    "AND",
+    "AS",
+    "AT",
+    "BOTH",
+    "CASE",
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "DATE",
+    "DAY",
+    "DISTINCT",
+    "DOW",
+    "ELSE",
+    "END",
+    "EPOCH",
+    "FALSE",
+    "FOR",
+    "FROM",
+    "HOUR",
+    "IN",
+    "INTERVAL",
+    "IS",
+    "LEADING",
+    "LIKE",
+    "LOCALTIMESTAMP",
+    "MINUTE",
+    "MONTH",
+    "NOT",
+    "NULL",
+    "OR",
+    "QUARTER",
+    "SECOND",
+    "THEN",
+    "TIME",
+    "TIMESTAMP",
+    "TRAILING",
+    "TRUE",
+    "WEEK",
+    "WHEN",
+    "YEAR",
+    "ZONE",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Clause {
+    #[default]
+    None,
+    Select,
+    From,
+    Join,
+    On,
+    Where,
+    Set,
+    Other,
+}
+
+/// State of one parenthesis level (or the statement itself)
+#[derive(Default)]
+struct Frame {
+    clause: Clause,
+    /// The frame is the body of `EXISTS (...)`, where `SELECT *` is idiomatic
+    exists_subquery: bool,
+    /// Start of the function name when the frame is a call inside WHERE
+    where_function: Option<usize>,
+    /// Tokens after `AS` name a type (`CAST(x AS int)`), not columns
+    after_as: bool,
+    has_column_ref: bool,
+    has_where: bool,
+    /// Comma-separated tables in FROM
+    comma_joins: Vec<Span>,
+    /// Last JOIN that has not been followed by ON or USING yet
+    open_join: Option<Span>,
+}
+
+impl Frame {
+    /// Report cross joins of the query block that just ended and reset it
+    fn finish_block(&mut self, out: &mut Vec<LintDiagnostic>) {
+        if !self.has_where {
+            for span in self.comma_joins.drain(..) {
+                out.push(LintDiagnostic::new(
+                    LintRule::ImplicitCrossJoin,
+                    span,
+                    "Comma-joined table without a WHERE clause produces a cross join; \
+                     use JOIN ... ON",
+                ));
+            }
+        }
+        self.close_join(out);
+        self.comma_joins.clear();
+        self.has_where = false;
+    }
+
+    fn close_join(&mut self, out: &mut Vec<LintDiagnostic>) {
+        if let Some(span) = self.open_join.take() {
+            out.push(LintDiagnostic::new(
+                LintRule::ImplicitCrossJoin,
+                span,
+                "JOIN without ON or USING is a cross join; add a join condition or write CROSS JOIN",
+            ));
+        }
+    }
+}
+
+fn end(token: &Token) -> usize {
+    token.start + token.text.len()
+}
+
+fn is_word(token: Option<&Token>, words: &[&str]) -> bool {
+    token.is_some_and(|t| {
+        t.kind == TokenKind::Word && words.iter().any(|w| t.text.eq_ignore_ascii_case(w))
+    })
+}
+
+fn is_punct(token: Option<&Token>, text: &str) -> bool {
+    token.is_some_and(|t| t.kind == TokenKind::Punct && t.text == text)
+}
+
+/// Innermost clause that applies at the current position
+fn current_clause(frames: &[Frame]) -> Clause {
+    frames
+        .iter()
+        .rev()
+        .map(|f| f.clause)
+        .find(|c| *c != Clause::None)
+        .unwrap_or_default()
+}
+
+/// Whether the token starting at `i` begins a comparison operator
+fn starts_comparison(tokens: &[Token], i: usize) -> bool {
+    match tokens.get(i) {
+        Some(t) if t.kind == TokenKind::Punct => {
+            matches!(t.text, "=" | "<" | ">") || (t.text == "!" && is_punct(tokens.get(i + 1), "="))
+        }
+        t => is_word(t, &["BETWEEN", "ILIKE", "IN", "LIKE"]),
+    }
+}
+
+/// Operator made of adjacent `<`, `>`, `=` and `!` tokens just before
+/// (or, when `forward`, just after) index `i`
+fn adjacent_operator(tokens: &[Token], i: usize, forward: bool) -> Option<(String, Span)> {
+    let mut parts: Vec<&Token> = Vec::new();
+    let mut j = i;
+    loop {
+        let next = if forward {
+            j.checked_add(1)
+        } else {
+            j.checked_sub(1)
+        };
+        let Some(token) = next
+            .and_then(|n| tokens.get(n))
+            .filter(|t| t.kind == TokenKind::Punct && matches!(t.text, "<" | ">" | "=" | "!"))
+        else {
+            break;
+        };
+        // Operator characters must touch each other: `< >` is not `<>`.
+        if let Some(last) = parts.last() {
+            let touches = if forward {
+                end(last) == token.start
+            } else {
+                end(token) == last.start
+            };
+            if !touches {
+                break;
+            }
+        }
+        parts.push(token);
+        j = next?;
+    }
+    if !forward {
+        parts.reverse();
+    }
+
+    let first = parts.first()?;
+    let last = parts.last()?;
+    let op = parts.iter().map(|t| t.text).collect();
+    Some((
+        op,
+        Span {
+            start: first.start,
+            end: end(last),
+        },
+    ))
+}
+
+/// Diagnostic for a `NULL` at index `i` that is compared with an operator
+fn null_comparison(tokens: &[Token], i: usize) -> Option<LintDiagnostic> {
+    let null = &tokens[i];
+    let (op, span) = match adjacent_operator(tokens, i, false) {
+        Some((op, span)) => (
+            op,
+            Span {
+                start: span.start,
+                end: end(null),
+            },
+        ),
+        None => {
+            let (op, span) = adjacent_operator(tokens, i, true)?;
+            // `NULL = NULL` is reported once, for the right-hand NULL.
+            let rhs = tokens.iter().skip(i + 1).find(|t| t.start >= span.end);
+            if is_word(rhs, &["NULL"]) {
+                return None;
+            }
+            (
+                op,
+                Span {
+                    start: null.start,
+                    end: span.end,
+                },
+            )
+        }
+    };
+
+    let message = match op.as_str() {
+        "=" => "Comparison with NULL using = is never true; use IS NULL",
+        "<>" | "!=" => "Comparison with NULL using <> is never true; use IS NOT NULL",
+        "<" | ">" | "<=" | ">=" => "Comparison with NULL is never true; use IS NULL or IS NOT NULL",
+        _ => return None,
+    };
+    Some(LintDiagnostic::new(LintRule::NullComparison, span, message))
+}
+
+/// Whether a word or quoted identifier inside a function call names a column
+fn is_column_ref(tokens: &[Token], i: usize) -> bool {
+    let token = &tokens[i];
+    let prev = i.checked_sub(1).map(|p| &tokens[p]);
+    let next = tokens.get(i + 1);
+
+    if is_punct(next, "(") || is_punct(prev, "::") || is_word(prev, &["AS"]) {
+        return false;
+    }
+    match token.kind {
+        TokenKind::QuotedIdentifier => true,
+        // `DATE '2024-01-01'` is a typed literal.
+        TokenKind::Word => {
+            !is_word(Some(token), NOT_COLUMNS) && next.is_none_or(|n| n.kind != TokenKind::String)
+        }
+        _ => false,
+    }
+}
+
+/// Span of a comma join: the comma and the table name after it. `None`
+/// when the item after the comma is a function or LATERAL subquery, which
+/// usually correlates with the preceding tables instead of crossing them.
+fn comma_join_span(tokens: &[Token], comma: usize) -> Option<Span> {
+    let is_name = |t: Option<&Token>| {
+        t.is_some_and(|t| matches!(t.kind, TokenKind::Word | TokenKind::QuotedIdentifier))
+    };
+    let first = comma + 1;
+    if !is_name(tokens.get(first)) || is_word(tokens.get(first), &["LATERAL"]) {
+        return None;
+    }
+    let mut last = first;
+    while is_punct(tokens.get(last + 1), ".") && is_name(tokens.get(last + 2)) {
+        last += 2;
+    }
+    if is_punct(tokens.get(last + 1), "(") {
+        return None;
+    }
+    Some(Span {
+        start: tokens[comma].start,
+        end: end(&tokens[last]),
+    })
+}
+
+/// Lint one statement given its non-trivia tokens
+fn lint_statement(tokens: &[Token], out: &mut Vec<LintDiagnostic>) {
+    let mut frames = vec![Frame::default()];
+    // UPDATE or DELETE keyword of the statement, and whether a top-level
+    // WHERE follows it
+    let mut dml: Option<&Token> = None;
+    let mut dml_has_where = false;
+
+    for (i, token) in tokens.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|p| &tokens[p]);
+
+        match token.kind {
+            TokenKind::Punct if token.text == "(" => {
+                let opener = prev.filter(|p| p.kind == TokenKind::Word);
+                let where_function = opener
+                    .filter(|p| {
+                        current_clause(&frames) == Clause::Where && !is_word(Some(p), NOT_FUNCTIONS)
+                    })
+                    .map(|p| p.start);
+                frames.push(Frame {
+                    exists_subquery: is_word(opener, &["EXISTS"]),
+                    where_function,
+                    ..Frame::default()
+                });
+            }
+            TokenKind::Punct if token.text == ")" => {
+                if frames.len() == 1 {
+                    continue;
+                }
+                let mut frame = frames.pop().expect("nested frame");
+                frame.finish_block(out);
+
+                if let Some(start) = frame.where_function {
+                    if frame.has_column_ref && starts_comparison(tokens, i + 1) {
+                        out.push(LintDiagnostic::new(
+                            LintRule::NonSargablePredicate,
+                            Span {
+                                start,
+                                end: end(token),
+                            },
+                            "Function applied to a column in WHERE prevents index use; \
+                             compare the bare column instead",
+                        ));
+                    }
+                }
+                let parent = frames.last_mut().expect("statement frame");
+                parent.has_column_ref |= frame.has_column_ref;
+            }
+            TokenKind::Punct if token.text == "," => {
+                let frame = frames.last_mut().expect("statement frame");
+                if matches!(frame.clause, Clause::From | Clause::Join | Clause::On) {
+                    frame.clause = Clause::From;
+                    frame.comma_joins.extend(comma_join_span(tokens, i));
+                }
+            }
+            TokenKind::Punct if token.text == "*" => {
+                let frame = frames.last().expect("statement frame");
+                let pp = i.checked_sub(2).map(|p| &tokens[p]);
+                let follows_select_item = is_word(prev, &["SELECT", "DISTINCT", "ALL"])
+                    || is_punct(prev, ",")
+                    || is_punct(prev, ".")
+                    || (prev.is_some_and(|p| p.kind == TokenKind::Number) && is_word(pp, &["TOP"]));
+                if frame.clause == Clause::Select && !frame.exists_subquery && follows_select_item {
+                    let start = match pp {
+                        Some(q) if is_punct(prev, ".") => q.start,
+                        _ => token.start,
+                    };
+                    out.push(LintDiagnostic::new(
+                        LintRule::SelectStar,
+                        Span {
+                            start,
+                            end: end(token),
+                        },
+                        "SELECT * fetches every column; list the columns you need",
+                    ));
+                }
+            }
+            TokenKind::Word => {
+                let at_top = frames.len() == 1;
+                let starts_statement = prev.is_none() || is_punct(prev, ")");
+                let frame = frames.last_mut().expect("statement frame");
+
+                match token.text.to_ascii_uppercase().as_str() {
+                    "SELECT" => {
+                        // A new query block, e.g. after UNION
+                        frame.finish_block(out);
+                        frame.clause = Clause::Select;
+                    }
+                    // `IS [NOT] DISTINCT FROM` is an operator, not a clause
+                    "FROM" if !is_word(prev, &["DISTINCT"]) => frame.clause = Clause::From,
+                    "WHERE" => {
+                        frame.clause = Clause::Where;
+                        frame.has_where = true;
+                        if at_top && dml.is_some() {
+                            dml_has_where = true;
+                        }
+                    }
+                    "JOIN" => {
+                        frame.close_join(out);
+                        if !is_word(prev, &["CROSS", "NATURAL"]) {
+                            frame.open_join = Some(Span {
+                                start: token.start,
+                                end: end(token),
+                            });
+                        }
+                        frame.clause = Clause::Join;
+                    }
+                    "ON" | "USING" => {
+                        frame.open_join = None;
+                        frame.clause = Clause::On;
+                    }
+                    "SET" => frame.clause = Clause::Set,
+                    // MySQL `ON DUPLICATE KEY UPDATE a = ...` assigns like SET
+                    "UPDATE" if is_word(prev, &["KEY"]) => frame.clause = Clause::Set,
+                    "UPDATE" | "DELETE" => {
+                        if at_top && starts_statement && dml.is_none() {
+                            dml = Some(token);
+                        }
+                        frame.clause = Clause::Other;
+                    }
+                    "GROUP" | "ORDER" | "HAVING" | "LIMIT" | "OFFSET" | "FETCH" | "WINDOW"
+                    | "RETURNING" | "UNION" | "INTERSECT" | "EXCEPT" | "VALUES" | "INTO"
+                    | "INSERT" => frame.clause = Clause::Other,
+                    "AS" => frame.after_as = true,
+                    "NULL" => {
+                        if !matches!(current_clause(&frames), Clause::None | Clause::Set) {
+                            out.extend(null_comparison(tokens, i));
+                        }
+                    }
+                    _ => {
+                        if !frame.after_as && is_column_ref(tokens, i) {
+                            frame.has_column_ref = true;
+                        }
+                    }
+                }
+            }
+            TokenKind::QuotedIdentifier => {
+                let frame = frames.last_mut().expect("statement frame");
+                if !frame.after_as && is_column_ref(tokens, i) {
+                    frame.has_column_ref = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Unbalanced parentheses: close whatever is still open.
+    while let Some(mut frame) = frames.pop() {
+        frame.finish_block(out);
+    }
+
+    if let Some(verb) = dml.filter(|_| !dml_has_where) {
+        out.push(LintDiagnostic::new(
+            LintRule::MissingWhere,
+            Span {
+                start: verb.start,
+                end: end(verb),
+            },
+            format!(
+                "{} without a WHERE clause affects every row in the table",
+                verb.text.to_ascii_uppercase()
+            ),
+        ));
+    }
+}
+
+/// Lint `sql`, which may contain several statements
+///
+/// Only rules enabled in `settings` are reported; nothing is reported when
+/// linting is disabled. Diagnostics are ordered by position.
+pub fn lint_sql(
+    sql: &str,
+    dialect: Option<&DbDriver>,
+    settings: &LintSettings,
+) -> Vec<LintDiagnostic> {
+    if !settings.enabled {
+        return Vec::new();
+    }
+
+    let quoting = dialect.map(Quoting::for_driver).unwrap_or_default();
+    let mut diagnostics = Vec::new();
+    let mut statement = Vec::new();
+
+    for token in tokenize_with(sql, quoting) {
+        match token.kind {
+            TokenKind::Semicolon => {
+                lint_statement(&statement, &mut diagnostics);
+                statement.clear();
+            }
+            _ if token.is_trivia() => {}
+            _ => statement.push(token),
+        }
+    }
+    lint_statement(&statement, &mut diagnostics);
+
+    diagnostics.retain(|d| d.rule.is_enabled(settings));
+    diagnostics.sort_by_key(|d| (d.span.start, d.span.end));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(sql: &str) -> Vec<(LintRule, &str)> {
+        lint_sql(sql, None, &LintSettings::default())
+            .into_iter()
+            .map(|d| (d.rule, &sql[d.span.start..d.span.end]))
+            .collect()
+    }
+
+    #[test]
+    fn test_select_star() {
+        assert_eq!(rules("SELECT * FROM t"), vec![(LintRule::SelectStar, "*")]);
+        assert_eq!(
+            rules("SELECT a, t.* FROM t"),
+            vec![(LintRule::SelectStar, "t.*")]
+        );
+        assert!(rules("SELECT COUNT(*), a * 2 FROM t").is_empty());
+        assert!(
+            rules("SELECT a FROM t WHERE EXISTS (SELECT * FROM u WHERE u.id = t.id)").is_empty()
+        );
+    }
+
+    #[test]
+    fn test_implicit_cross_join() {
+        assert_eq!(
+            rules("SELECT a FROM t, u"),
+            vec![(LintRule::ImplicitCrossJoin, ", u")]
+        );
+        assert!(rules("SELECT a FROM t, u WHERE t.id = u.t_id").is_empty());
+        assert!(rules("SELECT a FROM t, LATERAL (SELECT 1) x, jsonb_each(t.data)").is_empty());
+        assert_eq!(
+            rules("SELECT a FROM t JOIN u WHERE t.x = 1"),
+            vec![(LintRule::ImplicitCrossJoin, "JOIN")]
+        );
+        assert!(rules("SELECT a FROM t JOIN u ON t.id = u.id CROSS JOIN v").is_empty());
+        assert!(rules("SELECT substring(a FROM 1 FOR 2), f(a, b) FROM t").is_empty());
+    }
+
+    #[test]
+    fn test_null_comparison() {
+        assert_eq!(
+            rules("SELECT a FROM t WHERE b = NULL OR c <> NULL OR NULL != d"),
+            vec![
+                (LintRule::NullComparison, "= NULL"),
+                (LintRule::NullComparison, "<> NULL"),
+                (LintRule::NullComparison, "NULL !="),
+            ]
+        );
+        assert!(rules("UPDATE t SET a = NULL WHERE b IS NULL").is_empty());
+        assert!(rules("SELECT f(a => NULL), 'x = NULL' FROM t").is_empty());
+    }
+
+    #[test]
+    fn test_non_sargable_predicate() {
+        assert_eq!(
+            rules("SELECT a FROM t WHERE LOWER(email) = 'x' AND (DATE(created_at) >= :d)"),
+            vec![
+                (LintRule::NonSargablePredicate, "LOWER(email)"),
+                (LintRule::NonSargablePredicate, "DATE(created_at)"),
+            ]
+        );
+        assert!(rules(
+            "SELECT a FROM t WHERE email = LOWER(:e) AND created_at > NOW() \
+             AND id IN (1, 2) AND CAST(:p AS int) = b"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_missing_where() {
+        assert_eq!(
+            rules("delete from t; UPDATE t SET a = 1 WHERE id = 2; UPDATE u SET b = 2"),
+            vec![
+                (LintRule::MissingWhere, "delete"),
+                (LintRule::MissingWhere, "UPDATE"),
+            ]
+        );
+        assert!(
+            rules("WITH x AS (SELECT 1) DELETE FROM t WHERE id IN (SELECT 1 FROM x)").is_empty()
+        );
+        assert!(rules("SELECT a FROM t FOR UPDATE").is_empty());
+    }
+
+    #[test]
+    fn test_settings_filter_rules() {
+        let sql = "DELETE FROM t WHERE a = NULL; SELECT * FROM u";
+        let mut settings = LintSettings {
+            select_star: false,
+            ..LintSettings::default()
+        };
+        let diagnostics = lint_sql(sql, None, &settings);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, LintRule::NullComparison);
+        assert_eq!(diagnostics[0].severity, LintSeverity::Warning);
+
+        settings.enabled = false;
+        assert!(lint_sql(sql, None, &settings).is_empty());
+    }
+}
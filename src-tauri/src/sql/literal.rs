@@ -0,0 +1,128 @@
+//! String and binary literals
+//!
+//! For the places that have to inline values into SQL text: SQL dumps,
+//! import batches run through `execute_in_transaction`, and the catalog
+//! queries built for schema and statistics lookups. Statements run one at a
+//! time should bind their values with `execute_query_with_params` instead.
+
+use crate::models::DbDriver;
+
+/// `value` as a string literal of `dialect`, quotes included
+///
+/// - MySQL escapes backslashes, and writes NUL, CR, LF and Ctrl-Z as
+///   `\0`, `\r`, `\n` and `\Z` (the default mode, without
+///   `NO_BACKSLASH_ESCAPES`).
+/// - PostgreSQL uses an `E'...'` string when the value has control
+///   characters. Its text type can't hold NUL, so NULs are dropped.
+/// - SQL Server uses an `N'...'` string when the value isn't ASCII, so it
+///   doesn't go through the database's code page.
+/// - SQLite stops reading SQL at a NUL, so a value with NULs is written as a
+///   hex blob cast to text.
+pub fn escape_literal(value: &str, dialect: &DbDriver) -> String {
+    match dialect {
+        DbDriver::MySql => {
+            let mut out = String::with_capacity(value.len() + 2);
+            out.push('\'');
+            for c in value.chars() {
+                match c {
+                    '\\' => out.push_str("\\\\"),
+                    '\'' => out.push_str("''"),
+                    '\0' => out.push_str("\\0"),
+                    '\n' => out.push_str("\\n"),
+                    '\r' => out.push_str("\\r"),
+                    '\x1a' => out.push_str("\\Z"),
+                    c => out.push(c),
+                }
+            }
+            out.push('\'');
+            out
+        }
+        d if d.is_postgres_compatible() && value.chars().any(|c| c.is_ascii_control()) => {
+            let mut out = String::with_capacity(value.len() + 3);
+            out.push_str("E'");
+            for c in value.chars() {
+                match c {
+                    '\0' => {}
+                    '\\' => out.push_str("\\\\"),
+                    '\'' => out.push_str("''"),
+                    '\n' => out.push_str("\\n"),
+                    '\r' => out.push_str("\\r"),
+                    '\t' => out.push_str("\\t"),
+                    c if c.is_ascii_control() => out.push_str(&format!("\\x{:02X}", c as u8)),
+                    c => out.push(c),
+                }
+            }
+            out.push('\'');
+            out
+        }
+        DbDriver::SqlServer if !value.is_ascii() => format!("N'{}'", value.replace('\'', "''")),
+        DbDriver::Sqlite | DbDriver::Turso if value.contains('\0') => {
+            format!(
+                "CAST({} AS TEXT)",
+                binary_literal(value.as_bytes(), dialect)
+            )
+        }
+        _ => format!("'{}'", value.replace('\'', "''")),
+    }
+}
+
+/// `bytes` as a binary literal of `dialect`
+pub fn binary_literal(bytes: &[u8], dialect: &DbDriver) -> String {
+    let digits = hex::encode(bytes);
+    match dialect {
+        DbDriver::SqlServer => format!("0x{}", digits),
+        d if d.is_postgres_compatible() => format!("decode('{}', 'hex')", digits),
+        _ => format!("X'{}'", digits),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_literal() {
+        let value = "it's C:\\dir\n";
+        assert_eq!(
+            escape_literal(value, &DbDriver::MySql),
+            "'it''s C:\\\\dir\\n'"
+        );
+        assert_eq!(
+            escape_literal(value, &DbDriver::Postgres),
+            "E'it''s C:\\\\dir\\n'"
+        );
+        assert_eq!(
+            escape_literal(value, &DbDriver::Sqlite),
+            "'it''s C:\\dir\n'"
+        );
+
+        // Without control characters Postgres keeps a standard string, where
+        // a backslash is literal
+        assert_eq!(escape_literal("a\\b", &DbDriver::Neon), "'a\\b'");
+        assert_eq!(
+            escape_literal("a\0b\x01", &DbDriver::Postgres),
+            "E'ab\\x01'"
+        );
+        assert_eq!(escape_literal("a\0\x1a", &DbDriver::MySql), "'a\\0\\Z'");
+        assert_eq!(
+            escape_literal("a\0", &DbDriver::Sqlite),
+            "CAST(X'6100' AS TEXT)"
+        );
+
+        assert_eq!(
+            escape_literal("O'Brien", &DbDriver::SqlServer),
+            "'O''Brien'"
+        );
+        assert_eq!(escape_literal("Zoë", &DbDriver::SqlServer), "N'Zoë'");
+    }
+
+    #[test]
+    fn test_binary_literal() {
+        assert_eq!(binary_literal(b"Hi", &DbDriver::SqlServer), "0x4869");
+        assert_eq!(
+            binary_literal(b"Hi", &DbDriver::Supabase),
+            "decode('4869', 'hex')"
+        );
+        assert_eq!(binary_literal(b"Hi", &DbDriver::MySql), "X'4869'");
+    }
+}
@@ -0,0 +1,25 @@
+//! SQL text utilities
+//!
+//! Helpers that work on raw SQL text without touching a database:
+//! tokenizing, formatting, linting, normalizing and fingerprinting queries,
+//! finding and binding named query parameters, analyzing EXPLAIN output,
+//! rewriting UPDATE/DELETE statements to count the rows they would affect,
+//! adding a default row limit to unbounded SELECTs, listing the tables a
+//! query refers to, quoting identifiers and literals per dialect, compiling
+//! structured row filters and sorts into WHERE and ORDER BY clauses, and
+//! filling in `{{variable}}` placeholders in snippet templates.
+
+pub mod affected;
+pub mod filter;
+pub mod format;
+pub mod limit;
+pub mod lint;
+pub mod literal;
+pub mod normalize;
+pub mod order;
+pub mod params;
+pub mod plan;
+pub mod quote;
+pub mod tables;
+pub mod template;
+pub mod tokenizer;
@@ -0,0 +1,261 @@
+//! Query normalization and fingerprinting
+//!
+//! Reduces a query to a canonical shape so executions that differ only in
+//! literal values, keyword case, spacing or comments can be grouped together
+//! (e.g. the slow-query report treats `WHERE id = 1` and `where id=2` as one
+//! query). The fingerprint is a short hash of that canonical text. A lighter
+//! compaction that keeps literals identifies one exact query, for caching.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::tokenizer::{tokenize_with, Quoting, TokenKind};
+use crate::models::DbDriver;
+
+/// Placeholder that replaces literal values and bind parameters
+const PLACEHOLDER: &str = "?";
+
+/// Replacement for the contents of an `IN (...)` list of literals
+const LIST_PLACEHOLDER: &str = "...";
+
+/// Keywords that may directly precede a negative number, which makes the
+/// `-` a sign rather than subtraction
+const SIGN_CONTEXT_KEYWORDS: &[&str] = &[
+    "and", "between", "else", "limit", "offset", "or", "return", "select", "then", "when", "where",
+];
+
+/// Keywords that keep a space before `(`; any other word followed by `(` is
+/// treated as a function call and the parenthesis stays attached
+const SPACED_BEFORE_PAREN: &[&str] = &[
+    "all", "and", "any", "as", "exists", "from", "in", "join", "not", "on", "or", "over", "select",
+    "some", "then", "using", "values", "when", "where",
+];
+
+/// Canonical form of a query and its hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryFingerprint {
+    /// Normalized query text
+    pub normalized: String,
+    /// First 16 hex digits of the SHA-256 of `normalized`
+    pub fingerprint: String,
+}
+
+/// Normalize `sql` using standard SQL quoting rules
+pub fn normalize_query(sql: &str) -> String {
+    normalize_query_with(sql, Quoting::Standard)
+}
+
+/// Normalize `sql` for grouping
+///
+/// - unquoted words are lowercased; quoted identifiers are kept as written
+/// - string, numeric (including signed) and dollar-quoted literals and bind
+///   parameters become `?`
+/// - `IN` lists made only of literals become `in (...)`, so lists of
+///   different lengths match
+/// - comments are dropped and tokens are re-joined with canonical spacing
+/// - empty statements and a trailing `;` are dropped; remaining statements
+///   are joined with `; `
+pub fn normalize_query_with(sql: &str, quoting: Quoting) -> String {
+    let mut tokens: Vec<(TokenKind, String)> = Vec::new();
+
+    for token in tokenize_with(sql, quoting).filter(|t| !t.is_trivia()) {
+        match token.kind {
+            TokenKind::String
+            | TokenKind::Number
+            | TokenKind::DollarQuoted
+            | TokenKind::Parameter => {
+                if token.kind == TokenKind::Number && ends_with_sign(&tokens) {
+                    tokens.pop();
+                }
+                tokens.push((TokenKind::Parameter, PLACEHOLDER.to_string()));
+            }
+            TokenKind::Word => tokens.push((token.kind, token.text.to_ascii_lowercase())),
+            TokenKind::Semicolon => {
+                if tokens
+                    .last()
+                    .is_some_and(|(kind, _)| *kind != TokenKind::Semicolon)
+                {
+                    tokens.push((token.kind, ";".to_string()));
+                }
+            }
+            _ => tokens.push((token.kind, token.text.to_string())),
+        }
+        collapse_in_list(&mut tokens);
+    }
+    if tokens
+        .last()
+        .is_some_and(|(kind, _)| *kind == TokenKind::Semicolon)
+    {
+        tokens.pop();
+    }
+
+    let mut out = String::with_capacity(sql.len());
+    for (i, (_, text)) in tokens.iter().enumerate() {
+        if i > 0 && needs_space(&tokens[i - 1], text) {
+            out.push(' ');
+        }
+        out.push_str(text);
+    }
+    out
+}
+
+/// Normalize `sql` and hash the result
+pub fn fingerprint_query(sql: &str, dialect: Option<&DbDriver>) -> QueryFingerprint {
+    let normalized =
+        normalize_query_with(sql, dialect.map(Quoting::for_driver).unwrap_or_default());
+    let digest = Sha256::digest(normalized.as_bytes());
+    QueryFingerprint {
+        fingerprint: hex::encode(&digest[..8]),
+        normalized,
+    }
+}
+
+/// `sql` without comments, with canonical spacing, literals kept
+///
+/// Unlike `normalize_query_with` this keeps queries that differ in a literal
+/// (or in the case of a word) apart, so the text identifies what the query
+/// returns and can key cached results.
+pub fn compact_query_with(sql: &str, quoting: Quoting) -> String {
+    let mut tokens: Vec<&str> = tokenize_with(sql, quoting)
+        .filter(|t| !t.is_trivia())
+        .map(|t| t.text)
+        .collect();
+    while tokens.last() == Some(&";") {
+        tokens.pop();
+    }
+    tokens.join(" ")
+}
+
+/// Whether the last token is a `+`/`-` sign (as opposed to an operator)
+fn ends_with_sign(tokens: &[(TokenKind, String)]) -> bool {
+    let Some((TokenKind::Punct, sign)) = tokens.last() else {
+        return false;
+    };
+    if sign != "-" && sign != "+" {
+        return false;
+    }
+    match tokens.len().checked_sub(2).map(|i| &tokens[i]) {
+        None | Some((TokenKind::Semicolon, _)) => true,
+        Some((TokenKind::Punct, text)) => text != ")",
+        Some((TokenKind::Word, word)) => SIGN_CONTEXT_KEYWORDS.contains(&word.as_str()),
+        Some(_) => false,
+    }
+}
+
+/// Replace `in (?, ?, ...)` at the end of `tokens` with `in (...)`
+fn collapse_in_list(tokens: &mut Vec<(TokenKind, String)>) {
+    let is_punct =
+        |token: &(TokenKind, String), text: &str| token.0 == TokenKind::Punct && token.1 == text;
+    if !tokens.last().is_some_and(|t| is_punct(t, ")")) {
+        return;
+    }
+
+    // Walk back over `?, ?, ... ?` to the opening parenthesis
+    let mut i = tokens.len() - 1;
+    loop {
+        if i < 2 || tokens[i - 1].0 != TokenKind::Parameter {
+            return;
+        }
+        i -= 2;
+        if is_punct(&tokens[i], "(") {
+            break;
+        }
+        if !is_punct(&tokens[i], ",") {
+            return;
+        }
+    }
+
+    if i > 0 && tokens[i - 1].0 == TokenKind::Word && tokens[i - 1].1 == "in" {
+        tokens.truncate(i + 1);
+        tokens.push((TokenKind::Punct, LIST_PLACEHOLDER.to_string()));
+        tokens.push((TokenKind::Punct, ")".to_string()));
+    }
+}
+
+/// Whether a space goes between the previous token and the next one
+fn needs_space((prev_kind, prev): &(TokenKind, String), next: &str) -> bool {
+    if next == "(" {
+        return match prev_kind {
+            TokenKind::Word => SPACED_BEFORE_PAREN.contains(&prev.as_str()),
+            TokenKind::QuotedIdentifier => false,
+            _ => prev != "(",
+        };
+    }
+    !matches!(prev.as_str(), "(" | "." | "::") && !matches!(next, "," | ")" | ";" | "." | "::")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_variants_share_a_fingerprint() {
+        let a = "SELECT * FROM users WHERE id = 42 AND name = 'alice'";
+        let b = "select *\n  from users -- lookup\n where id=-7 and name = 'o''brien';";
+        let c = "SELECT * FROM users WHERE id = $1 AND name = :name";
+        assert_eq!(
+            normalize_query(a),
+            "select * from users where id = ? and name = ?"
+        );
+        assert_eq!(normalize_query(a), normalize_query(b));
+        assert_eq!(fingerprint_query(a, None), fingerprint_query(c, None));
+        assert_eq!(fingerprint_query(a, None).fingerprint.len(), 16);
+        assert_ne!(
+            fingerprint_query(a, None).fingerprint,
+            fingerprint_query("SELECT * FROM orders", None).fingerprint
+        );
+    }
+
+    #[test]
+    fn test_compact_query_keeps_literals() {
+        assert_eq!(
+            compact_query_with(
+                "SELECT *\n  FROM users -- lookup\n WHERE id=42;",
+                Quoting::Standard
+            ),
+            "SELECT * FROM users WHERE id = 42"
+        );
+        assert_ne!(
+            compact_query_with("SELECT * FROM t WHERE id = 1", Quoting::Standard),
+            compact_query_with("SELECT * FROM t WHERE id = 2", Quoting::Standard)
+        );
+    }
+
+    #[test]
+    fn test_in_lists_and_signs() {
+        assert_eq!(
+            normalize_query("SELECT a FROM t WHERE id IN (1, 2, 3) AND x NOT IN ('a')"),
+            "select a from t where id in (...) and x not in (...)"
+        );
+        // Subqueries and expressions inside IN are kept
+        assert_eq!(
+            normalize_query("DELETE FROM t WHERE id IN (SELECT id FROM u WHERE n > 5)"),
+            "delete from t where id in (select id from u where n > ?)"
+        );
+        // Subtraction stays an operator, signs fold into the literal
+        assert_eq!(
+            normalize_query("SELECT a - 1, b FROM t WHERE c BETWEEN -5 AND +5"),
+            "select a - ?, b from t where c between ? and ?"
+        );
+    }
+
+    #[test]
+    fn test_spacing_comments_and_statements() {
+        assert_eq!(
+            normalize_query("select count( * ),u.id /* total */ from public . users u;;select 1;"),
+            "select count(*), u.id from public.users u; select ?"
+        );
+        assert_eq!(normalize_query("SELECT x :: INT"), "select x::int");
+        assert_eq!(normalize_query("-- only a comment"), "");
+        // Quoted identifiers keep their case; MySQL backslash escapes are honored
+        assert_eq!(
+            fingerprint_query(
+                r#"SELECT "Id" FROM t WHERE s = 'it\'s'"#,
+                Some(&DbDriver::MySql)
+            )
+            .normalized,
+            r#"select "Id" from t where s = ?"#
+        );
+    }
+}
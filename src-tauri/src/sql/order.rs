@@ -0,0 +1,204 @@
+//! ORDER BY clauses for the data grid
+//!
+//! Turns the grid's column-header sorts into an `ORDER BY` clause. Columns
+//! must belong to the table being read and are quoted for the dialect, so a
+//! sort can't smuggle in SQL. Explicit NULL placement is written as
+//! `NULLS FIRST`/`NULLS LAST` where the engine supports it (PostgreSQL,
+//! SQLite); MySQL and SQL Server get an extra leading sort key on whether the
+//! value is NULL instead.
+
+use serde::{Deserialize, Serialize};
+
+use super::quote::quote_identifier;
+use super::tokenizer::Quoting;
+use crate::models::{ColumnInfo, DbDriver, DbError};
+
+/// Sort direction of one column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Where NULLs go relative to other values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+/// One sort key of a table read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SortSpec {
+    /// Column name, unquoted
+    pub column: String,
+    #[serde(default)]
+    pub direction: SortDirection,
+    /// Explicit NULL placement; the engine's default when omitted
+    #[serde(default)]
+    pub nulls: Option<NullsOrder>,
+}
+
+/// Build `ORDER BY ...` for `sort`, or an empty string when there is none
+///
+/// # Arguments
+///
+/// * `sort` - Sort keys, most significant first
+/// * `columns` - Columns of the table being read; every sort column must be
+///   one of them
+/// * `driver` - Dialect to quote for and to pick the NULLS syntax
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` when a sort column isn't a column of the
+/// table or is listed twice.
+pub fn build_order_by(
+    sort: &[SortSpec],
+    columns: &[ColumnInfo],
+    driver: &DbDriver,
+) -> Result<String, DbError> {
+    let quoting = Quoting::for_driver(driver);
+    let mut keys = Vec::new();
+    for (i, spec) in sort.iter().enumerate() {
+        if !columns.iter().any(|c| c.name == spec.column) {
+            return Err(DbError::InvalidInput(format!(
+                "Cannot sort by '{}': no such column",
+                spec.column
+            )));
+        }
+        if sort[..i].iter().any(|s| s.column == spec.column) {
+            return Err(DbError::InvalidInput(format!(
+                "Column '{}' is sorted more than once",
+                spec.column
+            )));
+        }
+
+        let column = quote_identifier(&spec.column, quoting);
+        let direction = match spec.direction {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        match (spec.nulls, quoting) {
+            (None, _) => keys.push(format!("{} {}", column, direction)),
+            (Some(nulls), Quoting::Standard) => {
+                let nulls = match nulls {
+                    NullsOrder::First => "FIRST",
+                    NullsOrder::Last => "LAST",
+                };
+                keys.push(format!("{} {} NULLS {}", column, direction, nulls));
+            }
+            (Some(nulls), _) => {
+                // MySQL and SQL Server treat NULL as the smallest value, so
+                // an extra key is only needed when asking for the opposite
+                let nulls_low = match spec.direction {
+                    SortDirection::Asc => nulls == NullsOrder::First,
+                    SortDirection::Desc => nulls == NullsOrder::Last,
+                };
+                if !nulls_low {
+                    let nulls_last = nulls == NullsOrder::Last;
+                    keys.push(null_sort_key(&column, quoting, nulls_last));
+                }
+                keys.push(format!("{} {}", column, direction));
+            }
+        }
+    }
+
+    if keys.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(format!("ORDER BY {}", keys.join(", ")))
+}
+
+/// Sort key that puts NULLs of `column` last (or first) for dialects
+/// without `NULLS FIRST/LAST`
+fn null_sort_key(column: &str, quoting: Quoting, nulls_last: bool) -> String {
+    let (null, not_null) = if nulls_last { (1, 0) } else { (0, 1) };
+    match quoting {
+        Quoting::MySql if nulls_last => format!("{} IS NULL", column),
+        Quoting::MySql => format!("{} IS NOT NULL", column),
+        _ => format!(
+            "CASE WHEN {} IS NULL THEN {} ELSE {} END",
+            column, null, not_null
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> Vec<ColumnInfo> {
+        ["id", "name", "order"]
+            .iter()
+            .map(|name| ColumnInfo::new(name.to_string(), "text".to_string(), true))
+            .collect()
+    }
+
+    fn spec(column: &str, direction: SortDirection, nulls: Option<NullsOrder>) -> SortSpec {
+        SortSpec {
+            column: column.to_string(),
+            direction,
+            nulls,
+        }
+    }
+
+    #[test]
+    fn test_build_order_by_standard() {
+        let sort = vec![
+            spec("name", SortDirection::Asc, Some(NullsOrder::Last)),
+            spec("order", SortDirection::Desc, None),
+        ];
+        assert_eq!(
+            build_order_by(&sort, &columns(), &DbDriver::Postgres).unwrap(),
+            "ORDER BY \"name\" ASC NULLS LAST, \"order\" DESC"
+        );
+        assert_eq!(
+            build_order_by(&sort, &columns(), &DbDriver::Sqlite).unwrap(),
+            "ORDER BY \"name\" ASC NULLS LAST, \"order\" DESC"
+        );
+        assert_eq!(
+            build_order_by(&[], &columns(), &DbDriver::Postgres).unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_build_order_by_emulated_nulls() {
+        let sort = vec![
+            spec("name", SortDirection::Asc, Some(NullsOrder::Last)),
+            spec("id", SortDirection::Desc, Some(NullsOrder::First)),
+            spec("order", SortDirection::Asc, Some(NullsOrder::First)),
+        ];
+        assert_eq!(
+            build_order_by(&sort, &columns(), &DbDriver::MySql).unwrap(),
+            "ORDER BY `name` IS NULL, `name` ASC, `id` IS NOT NULL, `id` DESC, `order` ASC"
+        );
+        assert_eq!(
+            build_order_by(&sort, &columns(), &DbDriver::SqlServer).unwrap(),
+            "ORDER BY CASE WHEN [name] IS NULL THEN 1 ELSE 0 END, [name] ASC, \
+             CASE WHEN [id] IS NULL THEN 0 ELSE 1 END, [id] DESC, [order] ASC"
+        );
+    }
+
+    #[test]
+    fn test_build_order_by_rejects_unknown_and_repeated_columns() {
+        let unknown = vec![spec("id; DROP TABLE t", SortDirection::Asc, None)];
+        assert!(matches!(
+            build_order_by(&unknown, &columns(), &DbDriver::Postgres),
+            Err(DbError::InvalidInput(_))
+        ));
+
+        let repeated = vec![
+            spec("id", SortDirection::Asc, None),
+            spec("id", SortDirection::Desc, None),
+        ];
+        assert!(matches!(
+            build_order_by(&repeated, &columns(), &DbDriver::Postgres),
+            Err(DbError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,374 @@
+//! Named query parameters
+//!
+//! Finds `:name` / `$name` placeholders in SQL text and rewrites them into
+//! the positional placeholders each driver binds natively (`$1`, `?`,
+//! `@P1`). Placeholders inside strings, quoted identifiers and comments are
+//! left alone because detection runs on the tokenizer output.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::tokenizer::{tokenize, Token, TokenKind};
+use crate::models::{DbDriver, DbError};
+
+/// Value type of a named parameter, inferred from the surrounding SQL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamType {
+    Text,
+    Integer,
+    Number,
+    Boolean,
+    Date,
+    Timestamp,
+    Json,
+}
+
+/// A named parameter found in a query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryParam {
+    /// Parameter name without its `:` / `$` prefix
+    pub name: String,
+    /// Inferred type; `Text` when nothing better is known
+    pub param_type: ParamType,
+    /// Value used for this name the last time a parameterized query ran
+    pub last_value: Option<Value>,
+}
+
+/// Positional placeholder syntax a driver binds natively
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceholderStyle {
+    /// `$1`, `$2`, ... (PostgreSQL); a repeated name reuses its number
+    Dollar,
+    /// `?` for every occurrence (MySQL, SQLite, Turso)
+    Question,
+    /// `@P1`, `@P2`, ... (SQL Server); a repeated name reuses its number
+    AtP,
+}
+
+impl PlaceholderStyle {
+    /// Placeholder syntax the driver for `driver` binds natively
+    pub fn for_driver(driver: &DbDriver) -> Self {
+        if driver.is_postgres_compatible() {
+            PlaceholderStyle::Dollar
+        } else if *driver == DbDriver::SqlServer {
+            PlaceholderStyle::AtP
+        } else {
+            PlaceholderStyle::Question
+        }
+    }
+
+    /// Placeholder for the 1-based parameter `position`
+    pub fn placeholder(self, position: usize) -> String {
+        match self {
+            PlaceholderStyle::Dollar => format!("${}", position),
+            PlaceholderStyle::Question => "?".to_string(),
+            PlaceholderStyle::AtP => format!("@P{}", position),
+        }
+    }
+}
+
+/// Name of a named placeholder token, or `None` for positional ones
+/// (`?`, `$1`) which are not part of the named-parameter feature.
+fn param_name<'a>(token: &Token<'a>) -> Option<&'a str> {
+    if token.kind != TokenKind::Parameter {
+        return None;
+    }
+    let name = token.text.get(1..)?;
+    if name.is_empty() || name.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(name)
+}
+
+/// Map a SQL type name (from a cast) to a parameter type
+fn type_from_name(type_name: &str) -> ParamType {
+    match type_name.to_ascii_lowercase().as_str() {
+        "int" | "int2" | "int4" | "int8" | "integer" | "smallint" | "bigint" | "tinyint"
+        | "serial" | "bigserial" | "signed" | "unsigned" => ParamType::Integer,
+        "numeric" | "decimal" | "real" | "float" | "float4" | "float8" | "double" | "money" => {
+            ParamType::Number
+        }
+        "bool" | "boolean" | "bit" => ParamType::Boolean,
+        "date" => ParamType::Date,
+        "timestamp" | "timestamptz" | "datetime" | "datetime2" | "datetimeoffset" => {
+            ParamType::Timestamp
+        }
+        "json" | "jsonb" => ParamType::Json,
+        _ => ParamType::Text,
+    }
+}
+
+/// Guess a type from the parameter name alone
+fn type_from_param_name(name: &str) -> ParamType {
+    let name = name.to_ascii_lowercase();
+    if name.starts_with("is_") || name.starts_with("has_") {
+        ParamType::Boolean
+    } else if name.ends_with("_at") {
+        ParamType::Timestamp
+    } else if name.ends_with("_date") || name == "date" {
+        ParamType::Date
+    } else if matches!(name.as_str(), "limit" | "offset" | "count") || name.ends_with("_count") {
+        ParamType::Integer
+    } else {
+        ParamType::Text
+    }
+}
+
+/// Infer the type of the placeholder at `index` in `tokens` (trivia removed)
+fn infer_type(tokens: &[Token<'_>], index: usize, name: &str) -> ParamType {
+    let word_at = |i: usize| {
+        tokens
+            .get(i)
+            .filter(|t| t.kind == TokenKind::Word)
+            .map(|t| t.text.to_ascii_uppercase())
+    };
+
+    // `:p::int`
+    if tokens.get(index + 1).is_some_and(|t| t.text == "::") {
+        if let Some(type_name) = tokens.get(index + 2).filter(|t| t.kind == TokenKind::Word) {
+            return type_from_name(type_name.text);
+        }
+    }
+
+    // `CAST(:p AS int)`
+    if index >= 2
+        && tokens[index - 1].text == "("
+        && word_at(index - 2).as_deref() == Some("CAST")
+        && word_at(index + 1).as_deref() == Some("AS")
+    {
+        if let Some(type_name) = tokens.get(index + 2).filter(|t| t.kind == TokenKind::Word) {
+            return type_from_name(type_name.text);
+        }
+    }
+
+    if let Some(previous) = index.checked_sub(1).and_then(word_at) {
+        match previous.as_str() {
+            "LIMIT" | "OFFSET" | "TOP" | "FETCH" => return ParamType::Integer,
+            "LIKE" | "ILIKE" => return ParamType::Text,
+            _ => {}
+        }
+    }
+
+    type_from_param_name(name)
+}
+
+/// Find the named parameters in `sql`
+///
+/// Returns each distinct name once, in order of first appearance. When a
+/// name is used several times, the first occurrence with a more specific
+/// type than `Text` wins. `last_value` is left empty for the caller to fill.
+pub fn extract_query_parameters(sql: &str) -> Vec<QueryParam> {
+    let tokens: Vec<Token<'_>> = tokenize(sql).filter(|t| !t.is_trivia()).collect();
+    let mut params: Vec<QueryParam> = Vec::new();
+
+    for (index, token) in tokens.iter().enumerate() {
+        let Some(name) = param_name(token) else {
+            continue;
+        };
+        let param_type = infer_type(&tokens, index, name);
+
+        match params.iter_mut().find(|p| p.name == name) {
+            Some(existing) => {
+                if existing.param_type == ParamType::Text {
+                    existing.param_type = param_type;
+                }
+            }
+            None => params.push(QueryParam {
+                name: name.to_string(),
+                param_type,
+                last_value: None,
+            }),
+        }
+    }
+
+    params
+}
+
+/// Convert a string value to the parameter's inferred type where it parses
+///
+/// The UI collects every value as text; numbers and booleans must be bound
+/// as such or strict drivers (PostgreSQL) reject them.
+fn coerce_value(value: &Value, param_type: ParamType) -> Value {
+    let Value::String(s) = value else {
+        return value.clone();
+    };
+    let trimmed = s.trim();
+
+    let coerced = match param_type {
+        ParamType::Integer => trimmed.parse::<i64>().ok().map(Value::from),
+        ParamType::Number => trimmed
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number),
+        ParamType::Boolean => match trimmed.to_ascii_lowercase().as_str() {
+            "true" | "t" | "yes" | "1" => Some(Value::Bool(true)),
+            "false" | "f" | "no" | "0" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    coerced.unwrap_or_else(|| value.clone())
+}
+
+/// Rewrite the named placeholders in `sql` for a driver
+///
+/// # Returns
+///
+/// The rewritten SQL and the values to bind, in placeholder order.
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` if a placeholder has no value in
+/// `values`, or if the query already uses the driver's own positional
+/// placeholders (they would clash with the generated ones).
+pub fn bind_named_parameters(
+    sql: &str,
+    style: PlaceholderStyle,
+    values: &HashMap<String, Value>,
+) -> Result<(String, Vec<Value>), DbError> {
+    let types: HashMap<String, ParamType> = extract_query_parameters(sql)
+        .into_iter()
+        .map(|p| (p.name, p.param_type))
+        .collect();
+
+    let mut out = String::with_capacity(sql.len());
+    let mut bound: Vec<Value> = Vec::new();
+    // Position (1-based) assigned to each name, for the numbered styles
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+
+    for token in tokenize(sql) {
+        let Some(name) = param_name(&token) else {
+            let positional = token.kind == TokenKind::Parameter
+                && match style {
+                    PlaceholderStyle::Dollar => token.text.starts_with('$'),
+                    PlaceholderStyle::Question => token.text == "?",
+                    PlaceholderStyle::AtP => false,
+                };
+            if positional {
+                return Err(DbError::InvalidInput(format!(
+                    "Positional placeholder '{}' cannot be mixed with named parameters",
+                    token.text
+                )));
+            }
+            out.push_str(token.text);
+            continue;
+        };
+
+        let value = values.get(name).ok_or_else(|| {
+            DbError::InvalidInput(format!("Missing value for parameter '{}'", name))
+        })?;
+        let param_type = types.get(name).copied().unwrap_or(ParamType::Text);
+
+        match style {
+            PlaceholderStyle::Question => {
+                bound.push(coerce_value(value, param_type));
+                out.push('?');
+            }
+            PlaceholderStyle::Dollar | PlaceholderStyle::AtP => {
+                let position = match positions.get(name) {
+                    Some(&position) => position,
+                    None => {
+                        bound.push(coerce_value(value, param_type));
+                        positions.insert(name, bound.len());
+                        bound.len()
+                    }
+                };
+                out.push_str(&style.placeholder(position));
+            }
+        }
+    }
+
+    Ok((out, bound))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn names_and_types(sql: &str) -> Vec<(String, ParamType)> {
+        extract_query_parameters(sql)
+            .into_iter()
+            .map(|p| (p.name, p.param_type))
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_ignores_strings_comments_and_positional() {
+        let sql = "SELECT ':nope', \":also_nope\" FROM t -- :comment\n\
+                   WHERE a = :a AND b = $b AND c = $1 AND d = ? AND e = :a /* :x */";
+        assert_eq!(
+            names_and_types(sql),
+            vec![
+                ("a".to_string(), ParamType::Text),
+                ("b".to_string(), ParamType::Text),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_infers_types() {
+        let sql = "SELECT * FROM t WHERE id = :id::int AND price > CAST(:min AS numeric) \
+                   AND name LIKE :pattern AND is_active = :is_active \
+                   AND created_at > :created_at LIMIT :page_size";
+        assert_eq!(
+            names_and_types(sql),
+            vec![
+                ("id".to_string(), ParamType::Integer),
+                ("min".to_string(), ParamType::Number),
+                ("pattern".to_string(), ParamType::Text),
+                ("is_active".to_string(), ParamType::Boolean),
+                ("created_at".to_string(), ParamType::Timestamp),
+                ("page_size".to_string(), ParamType::Integer),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bind_numbered_styles_reuse_positions() {
+        let values = HashMap::from([("a".to_string(), json!("x")), ("n".to_string(), json!("5"))]);
+        let sql = "SELECT * FROM t WHERE a = :a OR b = :a LIMIT :n";
+
+        let (pg, pg_values) =
+            bind_named_parameters(sql, PlaceholderStyle::Dollar, &values).unwrap();
+        assert_eq!(pg, "SELECT * FROM t WHERE a = $1 OR b = $1 LIMIT $2");
+        assert_eq!(pg_values, vec![json!("x"), json!(5)]);
+
+        let (ms, _) = bind_named_parameters(sql, PlaceholderStyle::AtP, &values).unwrap();
+        assert_eq!(ms, "SELECT * FROM t WHERE a = @P1 OR b = @P1 LIMIT @P2");
+    }
+
+    #[test]
+    fn test_bind_question_style_repeats_values() {
+        let values = HashMap::from([("a".to_string(), json!(1))]);
+        let (sql, bound) = bind_named_parameters(
+            "SELECT ':a' WHERE x = :a OR y = :a",
+            PlaceholderStyle::Question,
+            &values,
+        )
+        .unwrap();
+        assert_eq!(sql, "SELECT ':a' WHERE x = ? OR y = ?");
+        assert_eq!(bound, vec![json!(1), json!(1)]);
+    }
+
+    #[test]
+    fn test_bind_errors() {
+        let values = HashMap::new();
+        assert!(matches!(
+            bind_named_parameters("SELECT :missing", PlaceholderStyle::Question, &values),
+            Err(DbError::InvalidInput(_))
+        ));
+
+        let values = HashMap::from([("a".to_string(), json!(1))]);
+        assert!(bind_named_parameters("SELECT :a, $1", PlaceholderStyle::Dollar, &values).is_err());
+        assert!(
+            bind_named_parameters("SELECT :a, ?", PlaceholderStyle::Question, &values).is_err()
+        );
+    }
+}
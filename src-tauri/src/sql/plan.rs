@@ -0,0 +1,823 @@
+//! Query plan analysis
+//!
+//! Normalizes `EXPLAIN` output from PostgreSQL (`FORMAT JSON`) and MySQL
+//! (`FORMAT=JSON`) into one tree of [`PlanNode`]s and looks for the usual red
+//! flags in it: full scans of large tables, nested loops without an index on
+//! the inner side and steps that produce huge numbers of rows.
+//!
+//! Everything here works on the planner's estimates, so every warning is a
+//! heuristic. A sequential scan is the right plan for a query that reads most
+//! of a table, and stale statistics make any estimate wrong.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::{DbError, PlanAnalysisSettings};
+use crate::sql::tokenizer::{tokenize, Token, TokenKind};
+
+/// What a plan step does, as far as the analyzer cares
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlanNodeKind {
+    /// Reads every row of a table
+    FullScan,
+    /// Reads a table through an index
+    IndexScan,
+    /// Probes the inner input once per row of the outer input
+    NestedLoop,
+    /// Anything else (sorts, aggregates, hash joins, ...)
+    Other,
+}
+
+/// One step of a normalized query plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanNode {
+    /// Operation name as the server reports it (e.g. "Seq Scan")
+    pub node_type: String,
+    pub kind: PlanNodeKind,
+    /// Table read by this step
+    pub relation: Option<String>,
+    /// Name the query refers to the table by, when different
+    pub alias: Option<String>,
+    pub index_name: Option<String>,
+    /// Condition applied to the rows read
+    pub filter: Option<String>,
+    /// Rows this step produces per execution
+    pub estimated_rows: f64,
+    /// Rows read per execution, when the server reports it separately
+    pub examined_rows: Option<f64>,
+    /// Estimated size of `relation`, filled in where known
+    pub table_rows: Option<f64>,
+    pub total_cost: Option<f64>,
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    fn new(node_type: impl Into<String>, kind: PlanNodeKind) -> Self {
+        Self {
+            node_type: node_type.into(),
+            kind,
+            relation: None,
+            alias: None,
+            index_name: None,
+            filter: None,
+            estimated_rows: 0.0,
+            examined_rows: None,
+            table_rows: None,
+            total_cost: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Call `f` on this node and all its descendants
+    pub fn for_each_mut(&mut self, f: &mut impl FnMut(&mut PlanNode)) {
+        f(self);
+        for child in &mut self.children {
+            child.for_each_mut(f);
+        }
+    }
+}
+
+/// Category of a plan warning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlanWarningKind {
+    /// Full scan of a large table with no filter an index could serve
+    SequentialScan,
+    /// Full scan of a large table whose filter columns could be indexed
+    MissingIndex,
+    /// Nested loop over many row pairs without an index on the inner side
+    NestedLoop,
+    /// A step estimated to produce a huge number of rows
+    LargeRowEstimate,
+}
+
+/// A red flag found in a query plan
+///
+/// `suggestion` is a heuristic hint, not a verified fix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanWarning {
+    pub kind: PlanWarningKind,
+    /// Plan step the warning is about
+    pub node_type: String,
+    pub relation: Option<String>,
+    /// The estimate that triggered the warning
+    pub estimated_rows: f64,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+fn unexpected_output() -> DbError {
+    DbError::QueryError("Unexpected EXPLAIN output".to_string())
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// Read a number that MySQL may report as a string ("12.50")
+fn num_field(value: &Value, key: &str) -> Option<f64> {
+    match value.get(key)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Normalize PostgreSQL `EXPLAIN (FORMAT JSON)` output
+pub fn parse_postgres_plan(explain: &Value) -> Result<PlanNode, DbError> {
+    let root = match explain {
+        Value::Array(items) => items.first().ok_or_else(unexpected_output)?,
+        other => other,
+    };
+    root.get("Plan")
+        .map(postgres_node)
+        .ok_or_else(unexpected_output)
+}
+
+fn postgres_node(plan: &Value) -> PlanNode {
+    let node_type = str_field(plan, "Node Type").unwrap_or_else(|| "Unknown".to_string());
+    let kind = match node_type.as_str() {
+        "Seq Scan" => PlanNodeKind::FullScan,
+        "Index Scan" | "Index Only Scan" | "Bitmap Heap Scan" | "Bitmap Index Scan" => {
+            PlanNodeKind::IndexScan
+        }
+        "Nested Loop" => PlanNodeKind::NestedLoop,
+        _ => PlanNodeKind::Other,
+    };
+
+    let mut node = PlanNode::new(node_type, kind);
+    node.relation = str_field(plan, "Relation Name");
+    node.alias = str_field(plan, "Alias");
+    node.index_name = str_field(plan, "Index Name");
+    node.filter = str_field(plan, "Filter");
+    node.estimated_rows = num_field(plan, "Plan Rows").unwrap_or(0.0);
+    node.total_cost = num_field(plan, "Total Cost");
+    if let Some(children) = plan.get("Plans").and_then(Value::as_array) {
+        node.children = children.iter().map(postgres_node).collect();
+    }
+    node
+}
+
+/// Normalize MySQL `EXPLAIN FORMAT=JSON` output
+///
+/// MySQL reports tables in join order under `nested_loop`; each table's
+/// `rows_produced_per_join` counts the rows of the join so far, which is how
+/// often the next table gets probed.
+pub fn parse_mysql_plan(explain: &Value) -> Result<PlanNode, DbError> {
+    explain
+        .get("query_block")
+        .map(|block| mysql_block(block, "Query Block"))
+        .ok_or_else(unexpected_output)
+}
+
+fn mysql_block(block: &Value, node_type: &str) -> PlanNode {
+    let mut node = PlanNode::new(node_type, PlanNodeKind::Other);
+    node.total_cost = block
+        .get("cost_info")
+        .and_then(|c| num_field(c, "query_cost"));
+    node.children = mysql_children(block);
+    node.estimated_rows = node.children.last().map_or(0.0, |c| c.estimated_rows);
+    node
+}
+
+/// Plan steps nested in a MySQL query block or table entry
+fn mysql_children(value: &Value) -> Vec<PlanNode> {
+    let Some(object) = value.as_object() else {
+        return Vec::new();
+    };
+    let mut children = Vec::new();
+    for (key, child) in object {
+        match key.as_str() {
+            "table" => children.push(mysql_table(child)),
+            "nested_loop" => {
+                let mut node = PlanNode::new("Nested Loop", PlanNodeKind::NestedLoop);
+                node.children = child
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|item| match item.get("table") {
+                        Some(table) => mysql_table(table),
+                        None => mysql_block(item, "Query Block"),
+                    })
+                    .collect();
+                node.estimated_rows = node.children.last().map_or(0.0, |c| c.estimated_rows);
+                children.push(node);
+            }
+            "query_block" => children.push(mysql_block(child, "Query Block")),
+            "ordering_operation" => children.push(mysql_block(child, "Sort")),
+            "grouping_operation" => children.push(mysql_block(child, "Group")),
+            "duplicates_removal" => children.push(mysql_block(child, "Distinct")),
+            "windowing" => children.push(mysql_block(child, "Window")),
+            "union_result" => children.push(mysql_block(child, "Union")),
+            "materialized_from_subquery" => {
+                children.push(mysql_block(child, "Materialized Subquery"))
+            }
+            "query_specifications" | "attached_subqueries" | "optimized_away_subqueries" => {
+                children.extend(
+                    child
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .map(|item| mysql_block(item, "Subquery")),
+                );
+            }
+            _ => {}
+        }
+    }
+    children
+}
+
+fn mysql_table(table: &Value) -> PlanNode {
+    let access_type = str_field(table, "access_type").unwrap_or_default();
+    let (node_type, kind) = match access_type.as_str() {
+        "ALL" => ("Full Table Scan", PlanNodeKind::FullScan),
+        "index" => ("Full Index Scan", PlanNodeKind::IndexScan),
+        "range" => ("Index Range Scan", PlanNodeKind::IndexScan),
+        "const" | "system" => ("Constant Lookup", PlanNodeKind::IndexScan),
+        "" => ("Table", PlanNodeKind::Other),
+        _ => ("Index Lookup", PlanNodeKind::IndexScan),
+    };
+
+    let mut node = PlanNode::new(node_type, kind);
+    node.relation = str_field(table, "table_name");
+    node.index_name = str_field(table, "key");
+    node.filter = str_field(table, "attached_condition");
+    node.examined_rows = num_field(table, "rows_examined_per_scan");
+    node.estimated_rows = num_field(table, "rows_produced_per_join")
+        .or(node.examined_rows)
+        .unwrap_or(0.0);
+    if kind == PlanNodeKind::FullScan {
+        node.table_rows = node.examined_rows;
+    }
+    node.total_cost = table
+        .get("cost_info")
+        .and_then(|c| num_field(c, "prefix_cost"));
+    node.children = mysql_children(table);
+    node
+}
+
+/// Look for red flags in a normalized plan
+pub fn analyze_plan(root: &PlanNode, settings: &PlanAnalysisSettings) -> Vec<PlanWarning> {
+    let mut warnings = Vec::new();
+    analyze_node(root, settings, true, &mut warnings);
+    warnings
+}
+
+fn analyze_node(
+    node: &PlanNode,
+    settings: &PlanAnalysisSettings,
+    mut check_rows: bool,
+    warnings: &mut Vec<PlanWarning>,
+) {
+    // Only the topmost step over the threshold is reported; its inputs are
+    // usually just as large and would repeat the same warning.
+    if check_rows && node.estimated_rows >= settings.huge_row_estimate as f64 {
+        warnings.push(PlanWarning {
+            kind: PlanWarningKind::LargeRowEstimate,
+            node_type: node.node_type.clone(),
+            relation: node.relation.clone(),
+            estimated_rows: node.estimated_rows,
+            message: format!(
+                "{} is estimated to produce {:.0} rows",
+                node.node_type, node.estimated_rows
+            ),
+            suggestion: Some(
+                "Consider a LIMIT or narrower filters, and check for a missing join condition"
+                    .to_string(),
+            ),
+        });
+        check_rows = false;
+    }
+
+    if node.kind == PlanNodeKind::FullScan {
+        let table_rows = node.table_rows.unwrap_or(node.estimated_rows);
+        if table_rows >= settings.large_table_rows as f64 {
+            warnings.push(full_scan_warning(node, table_rows));
+        }
+    }
+
+    if node.kind == PlanNodeKind::NestedLoop {
+        for pair in node.children.windows(2) {
+            let (outer, inner) = (&pair[0], &pair[1]);
+            let probes = outer.estimated_rows * inner.examined_rows.unwrap_or(inner.estimated_rows);
+            if inner.kind != PlanNodeKind::IndexScan && probes >= settings.nested_loop_rows as f64 {
+                let relation = first_relation(inner);
+                warnings.push(PlanWarning {
+                    kind: PlanWarningKind::NestedLoop,
+                    node_type: node.node_type.clone(),
+                    relation: relation.clone(),
+                    estimated_rows: probes,
+                    message: format!(
+                        "Nested loop reads ~{:.0} rows: {:.0} outer rows, each probing {} without an index",
+                        probes,
+                        outer.estimated_rows,
+                        relation.as_deref().unwrap_or("the inner input")
+                    ),
+                    suggestion: Some(match relation {
+                        Some(table) => format!("Consider indexing the join column(s) of {}", table),
+                        None => "Consider indexing the join columns".to_string(),
+                    }),
+                });
+            }
+        }
+    }
+
+    for child in &node.children {
+        analyze_node(child, settings, check_rows, warnings);
+    }
+}
+
+fn full_scan_warning(node: &PlanNode, table_rows: f64) -> PlanWarning {
+    let table = node.relation.as_deref().unwrap_or("table");
+    let names: Vec<&str> = node
+        .relation
+        .iter()
+        .chain(node.alias.iter())
+        .map(String::as_str)
+        .collect();
+    let columns = node
+        .filter
+        .as_deref()
+        .map(|f| filter_columns(f, &names))
+        .unwrap_or_default();
+
+    if columns.is_empty() {
+        PlanWarning {
+            kind: PlanWarningKind::SequentialScan,
+            node_type: node.node_type.clone(),
+            relation: node.relation.clone(),
+            estimated_rows: table_rows,
+            message: format!("Full scan of {} (~{:.0} rows)", table, table_rows),
+            suggestion: Some(
+                "Consider a selective WHERE clause or LIMIT if the whole table isn't needed"
+                    .to_string(),
+            ),
+        }
+    } else {
+        PlanWarning {
+            kind: PlanWarningKind::MissingIndex,
+            node_type: node.node_type.clone(),
+            relation: node.relation.clone(),
+            estimated_rows: table_rows,
+            message: format!(
+                "Full scan of {} (~{:.0} rows) to filter on {}",
+                table,
+                table_rows,
+                columns.join(", ")
+            ),
+            suggestion: Some(format!(
+                "Consider an index on {}({})",
+                table,
+                columns.join(", ")
+            )),
+        }
+    }
+}
+
+/// Table read by `node` or the first of its descendants that reads one
+fn first_relation(node: &PlanNode) -> Option<String> {
+    node.relation
+        .clone()
+        .or_else(|| node.children.iter().find_map(first_relation))
+}
+
+fn is_comparison(token: &Token<'_>) -> bool {
+    match token.kind {
+        TokenKind::Punct => matches!(token.text, "=" | "<" | ">" | "!" | "~"),
+        TokenKind::Word => matches!(
+            token.text.to_ascii_uppercase().as_str(),
+            "LIKE" | "ILIKE" | "IN" | "BETWEEN" | "IS"
+        ),
+        _ => false,
+    }
+}
+
+fn is_connective(token: &Token<'_>) -> bool {
+    token.kind == TokenKind::Word
+        && matches!(
+            token.text.to_ascii_uppercase().as_str(),
+            "AND" | "OR" | "NOT" | "NULL" | "TRUE" | "FALSE"
+        )
+}
+
+fn unquote(token: &Token<'_>) -> String {
+    if token.kind != TokenKind::QuotedIdentifier || token.text.len() < 2 {
+        return token.text.to_string();
+    }
+    let quote = &token.text[..1];
+    token.text[1..token.text.len() - 1].replace(&quote.repeat(2), quote)
+}
+
+/// Columns compared directly in a plan filter, in order of appearance
+///
+/// Only the column on the left of a comparison counts. Columns wrapped in a
+/// function (`lower(email) = ...`) are skipped since a plain index wouldn't
+/// serve them, as are columns qualified with a table other than `tables`.
+fn filter_columns(filter: &str, tables: &[&str]) -> Vec<String> {
+    let tokens: Vec<Token<'_>> = tokenize(filter).filter(|t| !t.is_trivia()).collect();
+    let mut columns: Vec<String> = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        if !is_comparison(token) {
+            continue;
+        }
+
+        // Step left over closing parens and casts (`(status)::text`,
+        // `x::character varying`) to the operand
+        let mut j = i;
+        let mut closed_parens = false;
+        let operand = loop {
+            if j == 0 {
+                break None;
+            }
+            j -= 1;
+            let tok = &tokens[j];
+            if tok.text == ")" {
+                closed_parens = true;
+                continue;
+            }
+            if tok.kind == TokenKind::Word {
+                let mut k = j;
+                while k > 0 && tokens[k - 1].kind == TokenKind::Word {
+                    k -= 1;
+                }
+                if k > 0 && tokens[k - 1].text == "::" {
+                    j = k - 1;
+                    continue;
+                }
+            }
+            break Some(j);
+        };
+        let Some(j) = operand else { continue };
+        let tok = &tokens[j];
+        if !matches!(tok.kind, TokenKind::Word | TokenKind::QuotedIdentifier) || is_connective(tok)
+        {
+            continue;
+        }
+
+        // `f(col)` or `f((col)::text)`: an index on col wouldn't help
+        let mut open = j;
+        while open > 0 && tokens[open - 1].text == "(" {
+            open -= 1;
+        }
+        if closed_parens
+            && open < j
+            && open > 0
+            && tokens[open - 1].kind == TokenKind::Word
+            && !is_connective(&tokens[open - 1])
+        {
+            continue;
+        }
+
+        // `t.col`: only columns of the scanned table
+        if j >= 2 && tokens[j - 1].text == "." {
+            let qualifier = unquote(&tokens[j - 2]);
+            if !tables.iter().any(|t| t.eq_ignore_ascii_case(&qualifier)) {
+                continue;
+            }
+        }
+
+        let column = unquote(tok);
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+
+    columns
+}
+
+/// A plan captured by `record_query_plan`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedPlan {
+    /// Fingerprint of the query (see `sql::normalize`)
+    pub fingerprint: String,
+    pub connection_id: String,
+    /// Query as it was explained
+    pub sql: String,
+    /// When the plan was captured (RFC 3339)
+    pub recorded_at: String,
+    pub plan: PlanNode,
+}
+
+/// A plan step that differs between two plans of the same query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanNodeChange {
+    /// Position of the step: child indexes from the root, which is "0"
+    pub path: String,
+    /// Table read by the step, in either plan
+    pub relation: Option<String>,
+    /// Step in the earlier plan; `None` if it was added
+    pub before: Option<String>,
+    /// Step in the later plan; `None` if it was removed
+    pub after: Option<String>,
+    pub index_before: Option<String>,
+    pub index_after: Option<String>,
+    pub cost_before: Option<f64>,
+    pub cost_after: Option<f64>,
+}
+
+/// Differences between an earlier and a later plan of one query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanComparison {
+    pub fingerprint: String,
+    pub before_recorded_at: String,
+    pub after_recorded_at: String,
+    /// Whether any step changed type or index, or was added or removed
+    pub plan_changed: bool,
+    /// Estimated cost of the whole query
+    pub cost_before: Option<f64>,
+    pub cost_after: Option<f64>,
+    /// `cost_after - cost_before`, when both plans report a cost
+    pub cost_delta: Option<f64>,
+    /// `cost_delta` relative to `cost_before`, in percent
+    pub cost_change_pct: Option<f64>,
+    /// Steps that changed, in tree order
+    pub changes: Vec<PlanNodeChange>,
+}
+
+/// Compare two plans of the same query
+///
+/// The trees are walked side by side, matching steps by position. A step
+/// counts as changed when its type (e.g. "Seq Scan" to "Index Scan") or
+/// index differs; when the plan's shape changes, the steps that no longer
+/// line up are reported as added and removed.
+pub fn compare_plans(before: &RecordedPlan, after: &RecordedPlan) -> PlanComparison {
+    let mut changes = Vec::new();
+    diff_nodes(Some(&before.plan), Some(&after.plan), "0", &mut changes);
+
+    let cost_before = before.plan.total_cost;
+    let cost_after = after.plan.total_cost;
+    let cost_delta = cost_before.zip(cost_after).map(|(b, a)| a - b);
+    let cost_change_pct = cost_before
+        .zip(cost_delta)
+        .filter(|(b, _)| *b > 0.0)
+        .map(|(b, delta)| delta / b * 100.0);
+
+    PlanComparison {
+        fingerprint: after.fingerprint.clone(),
+        before_recorded_at: before.recorded_at.clone(),
+        after_recorded_at: after.recorded_at.clone(),
+        plan_changed: !changes.is_empty(),
+        cost_before,
+        cost_after,
+        cost_delta,
+        cost_change_pct,
+        changes,
+    }
+}
+
+fn diff_nodes(
+    before: Option<&PlanNode>,
+    after: Option<&PlanNode>,
+    path: &str,
+    changes: &mut Vec<PlanNodeChange>,
+) {
+    let changed = match (before, after) {
+        (Some(b), Some(a)) => b.node_type != a.node_type || b.index_name != a.index_name,
+        (None, None) => return,
+        _ => true,
+    };
+    if changed {
+        changes.push(PlanNodeChange {
+            path: path.to_string(),
+            relation: after
+                .and_then(|n| n.relation.clone())
+                .or_else(|| before.and_then(|n| n.relation.clone())),
+            before: before.map(|n| n.node_type.clone()),
+            after: after.map(|n| n.node_type.clone()),
+            index_before: before.and_then(|n| n.index_name.clone()),
+            index_after: after.and_then(|n| n.index_name.clone()),
+            cost_before: before.and_then(|n| n.total_cost),
+            cost_after: after.and_then(|n| n.total_cost),
+        });
+    }
+
+    let before_children = before.map_or(&[][..], |n| &n.children[..]);
+    let after_children = after.map_or(&[][..], |n| &n.children[..]);
+    for i in 0..before_children.len().max(after_children.len()) {
+        diff_nodes(
+            before_children.get(i),
+            after_children.get(i),
+            &format!("{}.{}", path, i),
+            changes,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_filter_columns() {
+        assert_eq!(
+            filter_columns("(customer_id = 42)", &["orders"]),
+            vec!["customer_id"]
+        );
+        assert_eq!(
+            filter_columns(
+                "(((status)::text = 'open'::text) AND (created_at >= '2024-01-01'::date))",
+                &["orders"]
+            ),
+            vec!["status", "created_at"]
+        );
+        // Function-wrapped columns can't use a plain index
+        assert!(filter_columns("(lower((email)::text) = 'a@b.c'::text)", &["users"]).is_empty());
+        // MySQL qualifies columns with schema and table
+        assert_eq!(
+            filter_columns(
+                "((`shop`.`o`.`customer_id` = `shop`.`c`.`id`) and (`shop`.`c`.`country` = 'GH'))",
+                &["c"]
+            ),
+            vec!["country"]
+        );
+    }
+
+    #[test]
+    fn test_postgres_plan_warnings() {
+        let explain = json!([{
+            "Plan": {
+                "Node Type": "Nested Loop",
+                "Plan Rows": 500,
+                "Total Cost": 90000.5,
+                "Plans": [
+                    {
+                        "Node Type": "Seq Scan",
+                        "Relation Name": "orders",
+                        "Alias": "o",
+                        "Plan Rows": 500,
+                        "Filter": "(customer_id = 42)"
+                    },
+                    {
+                        "Node Type": "Seq Scan",
+                        "Relation Name": "items",
+                        "Alias": "i",
+                        "Plan Rows": 1000
+                    }
+                ]
+            }
+        }]);
+        let mut plan = parse_postgres_plan(&explain).unwrap();
+        assert_eq!(plan.kind, PlanNodeKind::NestedLoop);
+        assert_eq!(plan.children.len(), 2);
+
+        // Table sizes come from the catalog, not the filtered estimate
+        plan.for_each_mut(&mut |node| {
+            if node.relation.as_deref() == Some("orders") {
+                node.table_rows = Some(50_000.0);
+            }
+        });
+
+        let warnings = analyze_plan(&plan, &PlanAnalysisSettings::default());
+        let kinds: Vec<PlanWarningKind> = warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![PlanWarningKind::NestedLoop, PlanWarningKind::MissingIndex]
+        );
+        assert_eq!(
+            warnings[1].suggestion.as_deref(),
+            Some("Consider an index on orders(customer_id)")
+        );
+        assert_eq!(warnings[0].relation.as_deref(), Some("items"));
+    }
+
+    #[test]
+    fn test_mysql_plan_warnings() {
+        let explain = json!({
+            "query_block": {
+                "select_id": 1,
+                "cost_info": { "query_cost": "250000.00" },
+                "nested_loop": [
+                    {
+                        "table": {
+                            "table_name": "c",
+                            "access_type": "ALL",
+                            "rows_examined_per_scan": 20000,
+                            "rows_produced_per_join": 2000,
+                            "filtered": "10.00",
+                            "attached_condition": "(`shop`.`c`.`country` = 'GH')"
+                        }
+                    },
+                    {
+                        "table": {
+                            "table_name": "o",
+                            "access_type": "ref",
+                            "key": "idx_customer",
+                            "rows_examined_per_scan": 5,
+                            "rows_produced_per_join": 10000
+                        }
+                    }
+                ]
+            }
+        });
+        let plan = parse_mysql_plan(&explain).unwrap();
+        assert_eq!(plan.estimated_rows, 10000.0);
+
+        let warnings = analyze_plan(&plan, &PlanAnalysisSettings::default());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, PlanWarningKind::MissingIndex);
+        assert_eq!(warnings[0].estimated_rows, 20000.0);
+        assert_eq!(
+            warnings[0].suggestion.as_deref(),
+            Some("Consider an index on c(country)")
+        );
+    }
+
+    #[test]
+    fn test_large_row_estimate_reported_once() {
+        let mut root = PlanNode::new("Hash Join", PlanNodeKind::Other);
+        root.estimated_rows = 5_000_000.0;
+        let mut child = PlanNode::new("Hash", PlanNodeKind::Other);
+        child.estimated_rows = 5_000_000.0;
+        root.children.push(child);
+
+        let warnings = analyze_plan(&root, &PlanAnalysisSettings::default());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, PlanWarningKind::LargeRowEstimate);
+
+        let lenient = PlanAnalysisSettings {
+            huge_row_estimate: 10_000_000,
+            ..Default::default()
+        };
+        assert!(analyze_plan(&root, &lenient).is_empty());
+    }
+
+    #[test]
+    fn test_unexpected_explain_output() {
+        assert!(parse_postgres_plan(&json!([])).is_err());
+        assert!(parse_mysql_plan(&json!({ "rows": 1 })).is_err());
+    }
+
+    #[test]
+    fn test_compare_plans() {
+        let recorded = |explain: Value, at: &str| RecordedPlan {
+            fingerprint: "abc".to_string(),
+            connection_id: "c1".to_string(),
+            sql: "SELECT * FROM orders WHERE customer_id = 42".to_string(),
+            recorded_at: at.to_string(),
+            plan: parse_postgres_plan(&explain).unwrap(),
+        };
+        let before = recorded(
+            json!([{ "Plan": {
+                "Node Type": "Seq Scan",
+                "Relation Name": "orders",
+                "Total Cost": 100.0
+            }}]),
+            "2024-01-01T00:00:00+00:00",
+        );
+        let after = recorded(
+            json!([{ "Plan": {
+                "Node Type": "Index Scan",
+                "Relation Name": "orders",
+                "Index Name": "orders_customer_id_idx",
+                "Total Cost": 25.0
+            }}]),
+            "2024-02-01T00:00:00+00:00",
+        );
+
+        let comparison = compare_plans(&before, &after);
+        assert!(comparison.plan_changed);
+        assert_eq!(comparison.cost_delta, Some(-75.0));
+        assert_eq!(comparison.cost_change_pct, Some(-75.0));
+        assert_eq!(comparison.changes.len(), 1);
+        let change = &comparison.changes[0];
+        assert_eq!(change.path, "0");
+        assert_eq!(change.relation.as_deref(), Some("orders"));
+        assert_eq!(change.before.as_deref(), Some("Seq Scan"));
+        assert_eq!(change.after.as_deref(), Some("Index Scan"));
+        assert_eq!(
+            change.index_after.as_deref(),
+            Some("orders_customer_id_idx")
+        );
+
+        // Same plan, different cost
+        let mut drifted = before.clone();
+        drifted.plan.total_cost = Some(150.0);
+        let comparison = compare_plans(&before, &drifted);
+        assert!(!comparison.plan_changed);
+        assert_eq!(comparison.cost_delta, Some(50.0));
+
+        // A step added under the root
+        let joined = recorded(
+            json!([{ "Plan": {
+                "Node Type": "Seq Scan",
+                "Relation Name": "orders",
+                "Plans": [{ "Node Type": "Seq Scan", "Relation Name": "items" }]
+            }}]),
+            "2024-03-01T00:00:00+00:00",
+        );
+        let comparison = compare_plans(&before, &joined);
+        assert_eq!(comparison.cost_delta, None);
+        assert_eq!(comparison.changes.len(), 1);
+        assert_eq!(comparison.changes[0].path, "0.0");
+        assert_eq!(comparison.changes[0].before, None);
+        assert_eq!(comparison.changes[0].relation.as_deref(), Some("items"));
+    }
+}
@@ -0,0 +1,84 @@
+//! Identifier quoting
+//!
+//! Every table, schema and column name the app interpolates into generated
+//! SQL goes through `quote_identifier`, so reserved words (`order`, `user`)
+//! and names with spaces or quote characters stay a single identifier, and a
+//! name like `x"; DROP TABLE y; --` can't end the quoting early.
+
+use super::tokenizer::Quoting;
+
+/// `name` as a quoted identifier of `dialect`: `"x"` for standard SQL,
+/// `` `x` `` for MySQL and `[x]` for SQL Server. An embedded closing quote
+/// character is doubled.
+pub fn quote_identifier(name: &str, dialect: Quoting) -> String {
+    match dialect {
+        Quoting::Standard => format!("\"{}\"", name.replace('"', "\"\"")),
+        Quoting::MySql => format!("`{}`", name.replace('`', "``")),
+        Quoting::SqlServer => format!("[{}]", name.replace(']', "]]")),
+    }
+}
+
+/// `schema.name` with both parts quoted, or just `name` without a schema
+pub fn quote_qualified(schema: Option<&str>, name: &str, dialect: Quoting) -> String {
+    match schema {
+        Some(schema) => format!(
+            "{}.{}",
+            quote_identifier(schema, dialect),
+            quote_identifier(name, dialect)
+        ),
+        None => quote_identifier(name, dialect),
+    }
+}
+
+/// Quoted, comma-separated list of `names`
+pub fn quote_list<S: AsRef<str>>(names: &[S], dialect: Quoting) -> String {
+    names
+        .iter()
+        .map(|name| quote_identifier(name.as_ref(), dialect))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(quote_identifier("order", Quoting::Standard), "\"order\"");
+        assert_eq!(quote_identifier("order", Quoting::MySql), "`order`");
+        assert_eq!(quote_identifier("order", Quoting::SqlServer), "[order]");
+
+        // Only the dialect's own closing quote needs escaping
+        assert_eq!(
+            quote_identifier("a\"b`c]d", Quoting::Standard),
+            "\"a\"\"b`c]d\""
+        );
+        assert_eq!(quote_identifier("a\"b`c]d", Quoting::MySql), "`a\"b``c]d`");
+        assert_eq!(
+            quote_identifier("a\"b`c]d", Quoting::SqlServer),
+            "[a\"b`c]]d]"
+        );
+        assert_eq!(
+            quote_identifier("x\"; DROP TABLE y; --", Quoting::Standard),
+            "\"x\"\"; DROP TABLE y; --\""
+        );
+    }
+
+    #[test]
+    fn test_quote_qualified() {
+        assert_eq!(
+            quote_qualified(Some("public"), "user", Quoting::Standard),
+            "\"public\".\"user\""
+        );
+        assert_eq!(
+            quote_qualified(Some("my db"), "select", Quoting::MySql),
+            "`my db`.`select`"
+        );
+        assert_eq!(
+            quote_qualified(None, "group", Quoting::SqlServer),
+            "[group]"
+        );
+        assert_eq!(quote_list(&["id", "from"], Quoting::MySql), "`id`, `from`");
+    }
+}
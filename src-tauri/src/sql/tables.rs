@@ -0,0 +1,157 @@
+//! Tables a query refers to
+//!
+//! A token-level scan for the names that follow `FROM`, `JOIN`, `INTO`,
+//! `UPDATE`, `USING`, `TABLE` and `TRUNCATE`, used to drop cached query
+//! results when a statement writes to one of their tables. It errs on the
+//! side of reporting too much: CTE names and `DELETE ... USING` sources are
+//! included, which only costs an extra cache miss.
+
+use super::tokenizer::{tokenize_with, Quoting, Token, TokenKind};
+
+/// Keywords followed by a table name (or a comma-separated list of them)
+const TABLE_KEYWORDS: &[&str] = &[
+    "from", "join", "into", "update", "using", "table", "truncate",
+];
+
+/// Words that may sit between a keyword and the table name
+const NAME_PREFIXES: &[&str] = &["if", "not", "exists", "only", "lateral"];
+
+/// Unqualified, lowercased names of the tables `sql` refers to
+///
+/// `"Sales"."Orders"`, `sales.orders` and `[orders]` all give `orders`.
+/// Function calls in a FROM clause (`generate_series(1, 10)`) and subqueries
+/// are skipped. Each name is listed once, in order of first appearance.
+pub fn referenced_tables(sql: &str, quoting: Quoting) -> Vec<String> {
+    let tokens: Vec<Token> = tokenize_with(sql, quoting)
+        .filter(|t| !t.is_trivia())
+        .collect();
+    let mut tables = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        let is_table_keyword = token.kind == TokenKind::Word
+            && TABLE_KEYWORDS
+                .iter()
+                .any(|k| token.text.eq_ignore_ascii_case(k));
+        if !is_table_keyword {
+            continue;
+        }
+
+        // `FROM f(...)` calls a function, but `INTO t (...)` and
+        // `TABLE t (...)` are followed by a column list
+        let calls_allowed = ["from", "join"]
+            .iter()
+            .any(|k| token.text.eq_ignore_ascii_case(k));
+        let mut pos = i + 1;
+        while let Some((name, next)) = table_name(&tokens, pos, calls_allowed) {
+            if !tables.contains(&name) {
+                tables.push(name);
+            }
+            // `a [AS] alias, b ...` continues the list
+            pos = skip_alias(&tokens, next);
+            if !tokens.get(pos).is_some_and(|t| t.text == ",") {
+                break;
+            }
+            pos += 1;
+        }
+    }
+    tables
+}
+
+/// Table name starting at `pos` (after any `IF NOT EXISTS`, `ONLY`, ...),
+/// and the position after it; `None` for a function call if `calls_allowed`
+fn table_name(tokens: &[Token], mut pos: usize, calls_allowed: bool) -> Option<(String, usize)> {
+    while tokens.get(pos).is_some_and(|t| {
+        t.kind == TokenKind::Word && NAME_PREFIXES.iter().any(|p| t.text.eq_ignore_ascii_case(p))
+    }) {
+        pos += 1;
+    }
+
+    let mut last = None;
+    loop {
+        let token = tokens.get(pos)?;
+        if !matches!(token.kind, TokenKind::Word | TokenKind::QuotedIdentifier) {
+            break;
+        }
+        last = Some(unquote(token));
+        pos += 1;
+        if !tokens.get(pos).is_some_and(|t| t.text == ".") {
+            break;
+        }
+        pos += 1;
+    }
+
+    if calls_allowed && tokens.get(pos).is_some_and(|t| t.text == "(") {
+        return None;
+    }
+    last.map(|name| (name, pos))
+}
+
+/// Position after an optional `[AS] alias` at `pos`
+fn skip_alias(tokens: &[Token], mut pos: usize) -> usize {
+    if tokens
+        .get(pos)
+        .is_some_and(|t| t.text.eq_ignore_ascii_case("as"))
+    {
+        pos += 1;
+    }
+    if tokens
+        .get(pos)
+        .is_some_and(|t| matches!(t.kind, TokenKind::Word | TokenKind::QuotedIdentifier))
+    {
+        pos += 1;
+    }
+    pos
+}
+
+/// Identifier text without its quotes, lowercased
+fn unquote(token: &Token) -> String {
+    let text = token.text;
+    let name = if token.kind == TokenKind::QuotedIdentifier && text.len() >= 2 {
+        let close = &text[text.len() - 1..];
+        text[1..text.len() - 1].replace(&close.repeat(2), close)
+    } else {
+        text.to_string()
+    };
+    name.to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables(sql: &str) -> Vec<String> {
+        referenced_tables(sql, Quoting::Standard)
+    }
+
+    #[test]
+    fn test_referenced_tables() {
+        assert_eq!(
+            tables("SELECT * FROM public.users u JOIN \"Orders\" o ON o.user_id = u.id"),
+            ["users", "orders"]
+        );
+        assert_eq!(
+            tables("select * from a, b as x, c where a.id = 1"),
+            ["a", "b", "c"]
+        );
+        assert_eq!(
+            tables("INSERT INTO audit SELECT * FROM (SELECT * FROM events) e"),
+            ["audit", "events"]
+        );
+        assert_eq!(
+            tables("INSERT INTO logs (id, msg) VALUES (1, 'x')"),
+            ["logs"]
+        );
+        assert_eq!(tables("UPDATE ONLY users SET name = 'x'"), ["users"]);
+        assert_eq!(tables("DROP TABLE IF EXISTS old_users"), ["old_users"]);
+        assert_eq!(tables("TRUNCATE sessions"), ["sessions"]);
+        assert_eq!(
+            tables("SELECT * FROM generate_series(1, 10)"),
+            Vec::<String>::new()
+        );
+        assert_eq!(tables("SELECT 'from users'"), Vec::<String>::new());
+        assert_eq!(
+            referenced_tables("DELETE FROM [dbo].[Users]", Quoting::SqlServer),
+            ["users"]
+        );
+    }
+}
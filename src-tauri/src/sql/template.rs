@@ -0,0 +1,169 @@
+//! Snippet template variables
+//!
+//! Finds `{{name}}` placeholders in snippet text and fills them in. Unlike
+//! the `:name` parameters in `params`, these are plain text substitution:
+//! a value is pasted into the SQL as-is, so it can stand for a table name or
+//! a whole clause, and it can equally inject SQL. Values that come from
+//! outside the user's own hands should go through [`to_named_parameters`]
+//! and be bound by the driver instead.
+
+use std::collections::HashMap;
+
+use crate::models::DbError;
+
+/// A `{{name}}` placeholder found in template text
+struct Placeholder<'a> {
+    /// Byte offset of the opening `{{`
+    start: usize,
+    /// Byte offset just past the closing `}}`
+    end: usize,
+    /// Variable name, whitespace trimmed
+    name: &'a str,
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Every well-formed placeholder in `text`, in order
+///
+/// Braces that don't enclose a valid name (`{{ }}`, `{{a-b}}`) are left as
+/// literal text.
+fn placeholders(text: &str) -> Vec<Placeholder<'_>> {
+    let mut found = Vec::new();
+    let mut pos = 0;
+
+    while let Some(offset) = text[pos..].find("{{") {
+        let start = pos + offset;
+        let Some(close) = text[start + 2..].find("}}") else {
+            break;
+        };
+        let inner_end = start + 2 + close;
+        let name = text[start + 2..inner_end].trim();
+        if is_valid_name(name) {
+            found.push(Placeholder {
+                start,
+                end: inner_end + 2,
+                name,
+            });
+            pos = inner_end + 2;
+        } else {
+            pos = start + 1;
+        }
+    }
+
+    found
+}
+
+/// Distinct variable names in `text`, in order of first appearance
+pub fn find_template_variables(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for placeholder in placeholders(text) {
+        if !names.iter().any(|n| n == placeholder.name) {
+            names.push(placeholder.name.to_string());
+        }
+    }
+    names
+}
+
+/// Substitute each `{{name}}` with its value from `values`
+///
+/// This is textual substitution: values are inserted verbatim, with no
+/// quoting or escaping.
+///
+/// # Errors
+///
+/// Returns `DbError::InvalidInput` naming the first variable without a value.
+pub fn render_template(text: &str, values: &HashMap<String, String>) -> Result<String, DbError> {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for placeholder in placeholders(text) {
+        let value = values.get(placeholder.name).ok_or_else(|| {
+            DbError::InvalidInput(format!(
+                "Missing value for snippet variable '{}'",
+                placeholder.name
+            ))
+        })?;
+        out.push_str(&text[last..placeholder.start]);
+        out.push_str(value);
+        last = placeholder.end;
+    }
+    out.push_str(&text[last..]);
+
+    Ok(out)
+}
+
+/// Rewrite each `{{name}}` as a `:name` query parameter
+///
+/// A placeholder written inside quotes (`'{{since}}'`) takes its quotes
+/// with it, since the bound value is already a string. The result is meant
+/// for `execute_parameterized_query`; variables standing for identifiers or
+/// SQL fragments can't be bound and will fail there.
+pub fn to_named_parameters(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for placeholder in placeholders(text) {
+        let quoted = text[..placeholder.start].ends_with('\'')
+            && text[placeholder.end..].starts_with('\'');
+        let (start, end) = if quoted {
+            (placeholder.start - 1, placeholder.end + 1)
+        } else {
+            (placeholder.start, placeholder.end)
+        };
+        // A previous quoted placeholder may already have consumed this quote
+        let start = start.max(last);
+        out.push_str(&text[last..start]);
+        out.push(':');
+        out.push_str(placeholder.name);
+        last = end;
+    }
+    out.push_str(&text[last..]);
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_template_variables() {
+        let sql = "SELECT * FROM {{table}} WHERE created_at > '{{ since }}' AND a = {{table}}";
+        assert_eq!(find_template_variables(sql), vec!["table", "since"]);
+        assert!(find_template_variables("SELECT '{{}}', '{{a-b}}'").is_empty());
+    }
+
+    #[test]
+    fn test_render_template() {
+        let sql = "SELECT * FROM {{table}} WHERE created_at > '{{since}}'";
+        let rendered =
+            render_template(sql, &values(&[("table", "orders"), ("since", "2025-01-01")]))
+                .unwrap();
+        assert_eq!(
+            rendered,
+            "SELECT * FROM orders WHERE created_at > '2025-01-01'"
+        );
+
+        let err = render_template(sql, &values(&[("table", "orders")])).unwrap_err();
+        assert!(err.to_string().contains("since"));
+    }
+
+    #[test]
+    fn test_to_named_parameters() {
+        assert_eq!(
+            to_named_parameters("SELECT * FROM t WHERE a > '{{since}}' AND b = {{id}}"),
+            "SELECT * FROM t WHERE a > :since AND b = :id"
+        );
+    }
+}
@@ -0,0 +1,609 @@
+//! Lightweight SQL tokenizer
+//!
+//! Splits SQL text into tokens without parsing it. The goal is to reliably
+//! tell code apart from string literals, quoted identifiers, dollar-quoted
+//! bodies and comments, so callers can count statements or find placeholders
+//! without being fooled by a `;` or `:name` inside a string.
+//!
+//! Every byte of the input belongs to exactly one token, so concatenating
+//! the token texts reproduces the input.
+
+use crate::models::DbDriver;
+
+/// Kind of a SQL token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Spaces, tabs and newlines
+    Whitespace,
+    /// `-- ...` up to (not including) the end of the line
+    LineComment,
+    /// `/* ... */`, honoring nesting (PostgreSQL block comments nest)
+    BlockComment,
+    /// `'...'`; `''` is an escaped quote
+    String,
+    /// `"..."` or `` `...` ``; a doubled quote char is an escaped quote
+    QuotedIdentifier,
+    /// `$$ ... $$` or `$tag$ ... $tag$`
+    DollarQuoted,
+    /// Keyword or bare identifier
+    Word,
+    /// Numeric literal
+    Number,
+    /// Placeholder: `:name`, `$name`, `$1` or `?`
+    Parameter,
+    /// Statement terminator
+    Semicolon,
+    /// Any other operator or punctuation (`::` and `:=` are one token)
+    Punct,
+}
+
+/// Quoting rules that differ between SQL dialects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quoting {
+    /// Standard SQL: a doubled quote is the only escape inside quotes
+    #[default]
+    Standard,
+    /// MySQL: backslash also escapes inside quotes; `#` starts a comment
+    MySql,
+    /// SQL Server: `[...]` is a quoted identifier
+    SqlServer,
+}
+
+impl Quoting {
+    /// Quoting rules of a driver's SQL dialect
+    pub fn for_driver(driver: &DbDriver) -> Self {
+        match driver {
+            DbDriver::MySql => Quoting::MySql,
+            DbDriver::SqlServer => Quoting::SqlServer,
+            _ => Quoting::Standard,
+        }
+    }
+}
+
+/// A token and its position in the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    /// Byte offset of the token in the input
+    pub start: usize,
+}
+
+impl Token<'_> {
+    /// Whether the token is whitespace or a comment
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::Whitespace | TokenKind::LineComment | TokenKind::BlockComment
+        )
+    }
+}
+
+/// Bytes that may continue an identifier. Non-ASCII bytes are included so
+/// multi-byte UTF-8 characters are never split.
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80
+}
+
+fn is_ident_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b >= 0x80
+}
+
+/// Iterator over the tokens of a SQL string
+pub struct Tokenizer<'a> {
+    sql: &'a str,
+    pos: usize,
+    quoting: Quoting,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(sql: &'a str) -> Self {
+        Self::with_quoting(sql, Quoting::Standard)
+    }
+
+    pub fn with_quoting(sql: &'a str, quoting: Quoting) -> Self {
+        Self {
+            sql,
+            pos: 0,
+            quoting,
+        }
+    }
+
+    /// End of a quoted section opened at `start` with `quote`; a doubled
+    /// quote stays inside. Unterminated sections run to the end.
+    fn quoted_end(&self, start: usize, quote: u8) -> usize {
+        let bytes = self.sql.as_bytes();
+        let backslash_escapes = self.quoting == Quoting::MySql && quote != b'`';
+        let mut i = start + 1;
+        while i < bytes.len() {
+            if backslash_escapes && bytes[i] == b'\\' {
+                i += 2;
+            } else if bytes[i] == quote {
+                if bytes.get(i + 1) == Some(&quote) {
+                    i += 2;
+                } else {
+                    return i + 1;
+                }
+            } else {
+                i += 1;
+            }
+        }
+        bytes.len()
+    }
+
+    fn scan_token(&self, start: usize) -> (TokenKind, usize) {
+        let bytes = self.sql.as_bytes();
+        let b = bytes[start];
+        let next = bytes.get(start + 1).copied();
+
+        match b {
+            b';' => (TokenKind::Semicolon, start + 1),
+            c if c.is_ascii_whitespace() => {
+                let mut i = start + 1;
+                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                (TokenKind::Whitespace, i)
+            }
+            b'#' if self.quoting == Quoting::MySql => {
+                let end = self.sql[start..]
+                    .find('\n')
+                    .map_or(bytes.len(), |p| start + p);
+                (TokenKind::LineComment, end)
+            }
+            b'[' if self.quoting == Quoting::SqlServer => {
+                // `]]` is an escaped bracket, like a doubled quote
+                (TokenKind::QuotedIdentifier, self.quoted_end(start, b']'))
+            }
+            b'-' if next == Some(b'-') => {
+                let end = self.sql[start..]
+                    .find('\n')
+                    .map_or(bytes.len(), |p| start + p);
+                (TokenKind::LineComment, end)
+            }
+            b'/' if next == Some(b'*') => {
+                let mut i = start + 2;
+                let mut depth = 1;
+                while i < bytes.len() && depth > 0 {
+                    if bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'*') {
+                        depth += 1;
+                        i += 2;
+                    } else if bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/') {
+                        depth -= 1;
+                        i += 2;
+                    } else {
+                        i += 1;
+                    }
+                }
+                (TokenKind::BlockComment, i)
+            }
+            b'\'' => (TokenKind::String, self.quoted_end(start, b'\'')),
+            b'"' | b'`' => (TokenKind::QuotedIdentifier, self.quoted_end(start, b)),
+            b'$' => {
+                let mut j = start + 1;
+                while j < bytes.len() && (bytes[j].is_ascii_alphanumeric() || bytes[j] == b'_') {
+                    j += 1;
+                }
+                let tag_is_valid = j == start + 1 || is_ident_start(bytes[start + 1]);
+                if tag_is_valid && bytes.get(j) == Some(&b'$') {
+                    // `$tag$`: scan for the matching closing delimiter.
+                    let delim = &self.sql[start..=j];
+                    let body = j + 1;
+                    let end = self.sql[body..]
+                        .find(delim)
+                        .map_or(bytes.len(), |p| body + p + delim.len());
+                    (TokenKind::DollarQuoted, end)
+                } else if j > start + 1 {
+                    // `$1` positional or `$name` named placeholder
+                    (TokenKind::Parameter, j)
+                } else {
+                    (TokenKind::Punct, start + 1)
+                }
+            }
+            b':' => match next {
+                Some(b':') | Some(b'=') => (TokenKind::Punct, start + 2),
+                Some(c) if is_ident_start(c) => {
+                    let mut i = start + 2;
+                    while i < bytes.len() && is_ident_byte(bytes[i]) && bytes[i] != b'$' {
+                        i += 1;
+                    }
+                    (TokenKind::Parameter, i)
+                }
+                _ => (TokenKind::Punct, start + 1),
+            },
+            b'?' => (TokenKind::Parameter, start + 1),
+            c if c.is_ascii_digit() || (c == b'.' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let mut i = start + 1;
+                while i < bytes.len() {
+                    match bytes[i] {
+                        d if d.is_ascii_digit() || d == b'.' => i += 1,
+                        b'e' | b'E'
+                            if bytes.get(i + 1).is_some_and(|n| {
+                                n.is_ascii_digit() || *n == b'+' || *n == b'-'
+                            }) =>
+                        {
+                            i += 2
+                        }
+                        _ => break,
+                    }
+                }
+                (TokenKind::Number, i)
+            }
+            c if is_ident_start(c) => {
+                let mut i = start + 1;
+                while i < bytes.len() && is_ident_byte(bytes[i]) {
+                    i += 1;
+                }
+                (TokenKind::Word, i)
+            }
+            _ => {
+                // Advance by a whole character so `text` stays valid UTF-8.
+                let len = self.sql[start..].chars().next().map_or(1, char::len_utf8);
+                (TokenKind::Punct, start + len)
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        if self.pos >= self.sql.len() {
+            return None;
+        }
+
+        let start = self.pos;
+        let (kind, end) = self.scan_token(start);
+        self.pos = end;
+
+        Some(Token {
+            kind,
+            text: &self.sql[start..end],
+            start,
+        })
+    }
+}
+
+/// Tokenize `sql`
+pub fn tokenize(sql: &str) -> Tokenizer<'_> {
+    Tokenizer::new(sql)
+}
+
+/// Tokenize `sql` using a dialect's quoting rules
+pub fn tokenize_with(sql: &str, quoting: Quoting) -> Tokenizer<'_> {
+    Tokenizer::with_quoting(sql, quoting)
+}
+
+/// Count the top-level statements in `sql`
+///
+/// Semicolons inside strings, quoted identifiers, dollar-quoted bodies and
+/// comments are ignored. A statement is only counted if it contains
+/// something other than whitespace and comments, so a trailing semicolon
+/// (`SELECT 1;`) still counts as one statement.
+pub fn count_statements(sql: &str) -> usize {
+    let mut statements = 0;
+    // Whether the current segment (since the last top-level ';') has content.
+    let mut has_content = false;
+
+    for token in tokenize(sql) {
+        match token.kind {
+            TokenKind::Semicolon => {
+                if has_content {
+                    statements += 1;
+                    has_content = false;
+                }
+            }
+            _ if token.is_trivia() => {}
+            _ => has_content = true,
+        }
+    }
+
+    if has_content {
+        statements += 1;
+    }
+
+    statements
+}
+
+/// Leading keyword of each top-level statement, uppercased
+///
+/// For statements that start with a `WITH` clause, the keyword after the
+/// common table expressions is returned (`WITH x AS (...) DELETE ...` gives
+/// `DELETE`).
+pub fn statement_verbs(sql: &str, quoting: Quoting) -> Vec<String> {
+    let mut verbs = Vec::new();
+    // Verb of the current statement, whether it began with WITH, the paren
+    // depth, and whether the previous significant token was a top-level `)`
+    let mut verb: Option<&str> = None;
+    let mut with_clause = false;
+    let mut depth = 0usize;
+    let mut after_close = false;
+
+    for token in tokenize_with(sql, quoting) {
+        if token.is_trivia() {
+            continue;
+        }
+        match token.kind {
+            TokenKind::Semicolon => {
+                verbs.extend(verb.take().map(str::to_ascii_uppercase));
+                with_clause = false;
+                depth = 0;
+            }
+            TokenKind::Punct if token.text == "(" => depth += 1,
+            TokenKind::Punct if token.text == ")" => {
+                depth = depth.saturating_sub(1);
+                after_close = depth == 0;
+                continue;
+            }
+            TokenKind::Word if verb.is_none() && !with_clause => {
+                if token.text.eq_ignore_ascii_case("WITH") {
+                    with_clause = true;
+                } else {
+                    verb = Some(token.text);
+                }
+            }
+            // `WITH x(a) AS (...)`: the AS after a column list is not a verb
+            TokenKind::Word
+                if verb.is_none() && after_close && !token.text.eq_ignore_ascii_case("AS") =>
+            {
+                verb = Some(token.text);
+            }
+            _ => {}
+        }
+        after_close = false;
+    }
+    verbs.extend(verb.map(str::to_ascii_uppercase));
+
+    verbs
+}
+
+/// `EXPLAIN` options that may come before the explained statement
+const EXPLAIN_OPTIONS: &[&str] = &["VERBOSE", "EXTENDED", "PARTITIONS", "FORMAT"];
+
+/// Verb of every statement `sql` runs, uppercased
+///
+/// Like [`statement_verbs`], but a `WITH` clause also gives the verb of each
+/// common table expression (`WITH d AS (DELETE ... RETURNING *) SELECT ...`
+/// gives `DELETE` and `SELECT`), and `EXPLAIN ANALYZE <stmt>`, which runs
+/// the statement, gives the verbs of `<stmt>`.
+pub fn executed_verbs(sql: &str, quoting: Quoting) -> Vec<String> {
+    let tokens: Vec<Token> = tokenize_with(sql, quoting)
+        .filter(|t| !t.is_trivia())
+        .collect();
+    let mut verbs = Vec::new();
+    for statement in tokens.split(|t| t.kind == TokenKind::Semicolon) {
+        push_executed_verbs(statement, &mut verbs);
+    }
+    verbs
+}
+
+/// Push the verbs of one statement or CTE body onto `verbs`
+fn push_executed_verbs(tokens: &[Token], verbs: &mut Vec<String>) {
+    let start = tokens.iter().take_while(|t| is_punct(t, "(")).count();
+    let tokens = &tokens[start..];
+    let Some(first) = tokens.first().filter(|t| t.kind == TokenKind::Word) else {
+        return;
+    };
+    let verb = first.text.to_ascii_uppercase();
+    match verb.as_str() {
+        "WITH" => {
+            // Each body is the group after `AS [NOT] MATERIALIZED`, which a
+            // column list (`x(a, b)`) never contains
+            let mut i = 1;
+            while let Some(open) = (i..tokens.len()).find(|&j| {
+                is_punct(&tokens[j], "(") && is_word_in(&tokens[j - 1], &["AS", "MATERIALIZED"])
+            }) {
+                let close = matching_paren(tokens, open);
+                push_executed_verbs(&tokens[open + 1..close], verbs);
+                i = close + 1;
+                if !tokens.get(i).is_some_and(|t| is_punct(t, ",")) {
+                    break;
+                }
+            }
+            push_executed_verbs(tokens.get(i..).unwrap_or_default(), verbs);
+        }
+        "EXPLAIN" => {
+            let mut i = 1;
+            let mut analyze = false;
+            if tokens.get(1).is_some_and(|t| is_punct(t, "(")) {
+                let close = matching_paren(tokens, 1);
+                analyze = tokens[2..close]
+                    .iter()
+                    .any(|t| is_word_in(t, &["ANALYZE", "ANALYSE"]));
+                i = close + 1;
+            }
+            while let Some(token) = tokens.get(i) {
+                if is_word_in(token, &["ANALYZE", "ANALYSE"]) {
+                    analyze = true;
+                } else if !is_word_in(token, EXPLAIN_OPTIONS)
+                    && !is_punct(token, "=")
+                    && !is_punct(&tokens[i - 1], "=")
+                {
+                    break;
+                }
+                i += 1;
+            }
+            if analyze {
+                push_executed_verbs(tokens.get(i..).unwrap_or_default(), verbs);
+            } else {
+                verbs.push(verb);
+            }
+        }
+        _ => verbs.push(verb),
+    }
+}
+
+/// Index of the `)` closing the `(` at `open`, or the length of `tokens` if
+/// it is never closed
+fn matching_paren(tokens: &[Token], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if is_punct(token, "(") {
+            depth += 1;
+        } else if is_punct(token, ")") {
+            depth -= 1;
+            if depth == 0 {
+                return i;
+            }
+        }
+    }
+    tokens.len()
+}
+
+fn is_punct(token: &Token, text: &str) -> bool {
+    token.kind == TokenKind::Punct && token.text == text
+}
+
+fn is_word_in(token: &Token, words: &[&str]) -> bool {
+    token.kind == TokenKind::Word && words.iter().any(|w| token.text.eq_ignore_ascii_case(w))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(sql: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(sql)
+            .filter(|t| t.kind != TokenKind::Whitespace)
+            .map(|t| (t.kind, t.text))
+            .collect()
+    }
+
+    #[test]
+    fn test_tokens_round_trip() {
+        let sql = "SELECT 'a;b', \"x\"\"y\" FROM t -- note\n/* c /* nested */ */ WHERE é = $$;$$;";
+        let rebuilt: String = tokenize(sql).map(|t| t.text).collect();
+        assert_eq!(rebuilt, sql);
+    }
+
+    #[test]
+    fn test_token_kinds() {
+        assert_eq!(
+            kinds("SELECT a::int, 'it''s' FROM `t` WHERE id = :id AND x = $1 AND y = ?;"),
+            vec![
+                (TokenKind::Word, "SELECT"),
+                (TokenKind::Word, "a"),
+                (TokenKind::Punct, "::"),
+                (TokenKind::Word, "int"),
+                (TokenKind::Punct, ","),
+                (TokenKind::String, "'it''s'"),
+                (TokenKind::Word, "FROM"),
+                (TokenKind::QuotedIdentifier, "`t`"),
+                (TokenKind::Word, "WHERE"),
+                (TokenKind::Word, "id"),
+                (TokenKind::Punct, "="),
+                (TokenKind::Parameter, ":id"),
+                (TokenKind::Word, "AND"),
+                (TokenKind::Word, "x"),
+                (TokenKind::Punct, "="),
+                (TokenKind::Parameter, "$1"),
+                (TokenKind::Word, "AND"),
+                (TokenKind::Word, "y"),
+                (TokenKind::Punct, "="),
+                (TokenKind::Parameter, "?"),
+                (TokenKind::Semicolon, ";"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dollar_quotes_and_numbers() {
+        assert_eq!(
+            kinds("$fn$ body; $x $fn$ 1.5e3 .5"),
+            vec![
+                (TokenKind::DollarQuoted, "$fn$ body; $x $fn$"),
+                (TokenKind::Number, "1.5e3"),
+                (TokenKind::Number, ".5"),
+            ]
+        );
+        assert_eq!(kinds("$name"), vec![(TokenKind::Parameter, "$name")]);
+    }
+
+    #[test]
+    fn test_dialect_quoting() {
+        let mysql: Vec<_> = tokenize_with(r"'it\'s' # note", Quoting::MySql)
+            .map(|t| (t.kind, t.text))
+            .collect();
+        assert_eq!(
+            mysql,
+            vec![
+                (TokenKind::String, r"'it\'s'"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::LineComment, "# note"),
+            ]
+        );
+
+        let mssql: Vec<_> = tokenize_with("[a]]b] [c", Quoting::SqlServer)
+            .map(|t| (t.kind, t.text))
+            .collect();
+        assert_eq!(
+            mssql,
+            vec![
+                (TokenKind::QuotedIdentifier, "[a]]b]"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::QuotedIdentifier, "[c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_statement_verbs() {
+        assert_eq!(
+            statement_verbs("select 1; -- x\n DELETE FROM t;", Quoting::Standard),
+            vec!["SELECT", "DELETE"]
+        );
+        assert_eq!(
+            statement_verbs(
+                "WITH a(x) AS (SELECT 1), b AS (DELETE FROM t RETURNING *) DROP TABLE t",
+                Quoting::Standard
+            ),
+            vec!["DROP"]
+        );
+        assert!(statement_verbs("-- nothing\n;", Quoting::Standard).is_empty());
+    }
+
+    #[test]
+    fn test_executed_verbs() {
+        let q = Quoting::Standard;
+        assert_eq!(
+            executed_verbs(
+                "WITH a(x) AS (SELECT 1), d AS MATERIALIZED (DELETE FROM t RETURNING *) \
+                 SELECT * FROM d; UPDATE t SET x = 1",
+                q
+            ),
+            vec!["SELECT", "DELETE", "SELECT", "UPDATE"]
+        );
+        assert_eq!(
+            executed_verbs(
+                "WITH RECURSIVE r AS (WITH i AS (INSERT INTO t VALUES (1)) SELECT 1) TABLE r",
+                q
+            ),
+            vec!["INSERT", "SELECT", "TABLE"]
+        );
+        assert_eq!(executed_verbs("EXPLAIN ANALYZE DELETE FROM t", q), vec!["DELETE"]);
+        assert_eq!(
+            executed_verbs("explain (analyze, buffers) update t set x = 1", q),
+            vec!["UPDATE"]
+        );
+        assert_eq!(
+            executed_verbs("EXPLAIN ANALYZE FORMAT=TREE DELETE FROM t", Quoting::MySql),
+            vec!["DELETE"]
+        );
+        assert_eq!(
+            executed_verbs("EXPLAIN (FORMAT JSON) DELETE FROM t", q),
+            vec!["EXPLAIN"]
+        );
+        assert_eq!(executed_verbs("EXPLAIN DELETE FROM t", q), vec!["EXPLAIN"]);
+        assert_eq!(executed_verbs("(SELECT 1)", q), vec!["SELECT"]);
+    }
+
+    #[test]
+    fn test_count_statements() {
+        assert_eq!(count_statements("SELECT 1"), 1);
+        assert_eq!(count_statements("SELECT 1;"), 1);
+        assert_eq!(count_statements("SELECT 1; SELECT 2"), 2);
+        assert_eq!(count_statements("SELECT ';' -- ;\n"), 1);
+        assert_eq!(count_statements("-- only a comment\n;"), 0);
+    }
+}
@@ -380,6 +380,18 @@ impl SshTunnelManager {
         Ok(())
     }
 
+    /// Close every tunnel, e.g. on shutdown
+    ///
+    /// Failures are logged; the remaining tunnels are still closed.
+    pub async fn close_all_tunnels(&self) {
+        let ids: Vec<String> = self.tunnels.lock().await.keys().cloned().collect();
+        for id in ids {
+            if let Err(e) = self.close_tunnel(&id).await {
+                eprintln!("Failed to close SSH tunnel {}: {}", id, e);
+            }
+        }
+    }
+
     /// Check if a tunnel exists for a connection
     pub async fn has_tunnel(&self, connection_id: &str) -> bool {
         let tunnels = self.tunnels.lock().await;
@@ -4,13 +4,17 @@
 //! in memory with thread-safe access, filtering, sorting, and statistics.
 
 use crate::models::{
-    ActivityStats, QueryLog, QueryLogFilter, QueryLogResponse, QueryLogSort, QueryLogSortField,
-    QueryStatus, QueryType, SortDirection,
+    ActivityBucket, ActivityStats, QueryLog, QueryLogFilter, QueryLogResponse, QueryLogSort,
+    QueryLogSortField, QueryStatus, QueryType, SlowQueryGroup, SortDirection, TimeBucket,
 };
-use chrono::{Duration, Utc};
-use std::collections::HashMap;
+use crate::sql::normalize::normalize_query;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::RwLock;
 
+/// Upper bound on buckets returned by a single time series request
+const MAX_TIMESERIES_BUCKETS: i64 = 10_000;
+
 /// Activity logger for managing query logs
 ///
 /// Provides thread-safe access to query logs with filtering, sorting, pagination,
@@ -254,6 +258,149 @@ impl ActivityLogger {
         }
     }
 
+    /// Aggregate logs into consecutive time buckets
+    ///
+    /// The range runs from the filter's start date (or the earliest matching
+    /// log) to its end date (or the latest matching log). Buckets without any
+    /// queries are included with zero counts; if the range would exceed
+    /// `MAX_TIMESERIES_BUCKETS`, only the most recent buckets are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Filter criteria (optional)
+    /// * `bucket` - Bucket size
+    /// * `utc_offset_minutes` - Local offset from UTC used to align buckets
+    ///
+    /// # Returns
+    ///
+    /// Buckets in chronological order
+    pub fn get_timeseries(
+        &self,
+        filter: Option<QueryLogFilter>,
+        bucket: TimeBucket,
+        utc_offset_minutes: i32,
+    ) -> Vec<ActivityBucket> {
+        let logs = self.logs.read().unwrap();
+        let filter = filter.unwrap_or_default();
+        let offset = utc_offset_minutes as i64 * 60;
+
+        // Per bucket: (queries, failed, total duration, queries with a duration)
+        let mut totals: BTreeMap<i64, (usize, usize, u64, usize)> = BTreeMap::new();
+        for log in logs.iter().filter(|log| filter.matches(log)) {
+            let entry = totals
+                .entry(bucket.bucket_start(log.started_at.timestamp(), offset))
+                .or_default();
+            entry.0 += 1;
+            if log.status == QueryStatus::Failed {
+                entry.1 += 1;
+            }
+            if let Some(duration) = log.duration_ms {
+                entry.2 += duration;
+                entry.3 += 1;
+            }
+        }
+
+        let range_bound = |date: &Option<String>| {
+            date.as_deref()
+                .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+                .map(|dt| bucket.bucket_start(dt.timestamp(), offset))
+        };
+        let first = range_bound(&filter.start_date).or(totals.keys().next().copied());
+        let last = range_bound(&filter.end_date).or(totals.keys().next_back().copied());
+        let (Some(first), Some(last)) = (first, last) else {
+            return Vec::new();
+        };
+
+        let step = bucket.seconds();
+        let first = first.max(last - (MAX_TIMESERIES_BUCKETS - 1) * step);
+
+        (0..)
+            .map(|i| first + i * step)
+            .take_while(|start| *start <= last)
+            .map(|start| {
+                let (total_queries, failed_queries, duration_sum, durations) =
+                    totals.get(&start).copied().unwrap_or_default();
+                ActivityBucket {
+                    bucket_start: DateTime::from_timestamp(start, 0).unwrap_or_default(),
+                    total_queries,
+                    failed_queries,
+                    avg_duration: if durations > 0 {
+                        duration_sum as f64 / durations as f64
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Group slow queries by their normalized SQL
+    ///
+    /// Only executions that took longer than `threshold_ms` are considered,
+    /// so variants of a query that differ only in literal values end up in
+    /// one group. Groups are ordered worst first by 95th percentile duration.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold_ms` - Minimum duration (exclusive) for a query to count as slow
+    /// * `since` - Only consider queries started at or after this time (optional)
+    /// * `limit` - Maximum number of groups to return
+    ///
+    /// # Returns
+    ///
+    /// The slowest query groups
+    pub fn get_slow_queries(
+        &self,
+        threshold_ms: u64,
+        since: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> Vec<SlowQueryGroup> {
+        let logs = self.logs.read().unwrap();
+
+        let mut groups: HashMap<String, (Vec<u64>, &QueryLog)> = HashMap::new();
+        for log in logs.iter() {
+            let Some(duration) = log.duration_ms.filter(|d| *d > threshold_ms) else {
+                continue;
+            };
+            if since.is_some_and(|since| log.started_at < since) {
+                continue;
+            }
+            let (durations, latest) = groups
+                .entry(normalize_query(&log.sql))
+                .or_insert_with(|| (Vec::new(), log));
+            durations.push(duration);
+            if log.started_at > latest.started_at {
+                *latest = log;
+            }
+        }
+
+        let mut slow: Vec<SlowQueryGroup> = groups
+            .into_iter()
+            .map(|(normalized_sql, (mut durations, latest))| {
+                durations.sort_unstable();
+                SlowQueryGroup {
+                    normalized_sql,
+                    sample_sql: latest.sql.clone(),
+                    count: durations.len(),
+                    p50_duration: percentile(&durations, 50),
+                    p95_duration: percentile(&durations, 95),
+                    max_duration: durations[durations.len() - 1],
+                    last_occurrence: latest.started_at,
+                    connection_name: latest.connection_name.clone(),
+                }
+            })
+            .collect();
+
+        slow.sort_by(|a, b| {
+            b.p95_duration
+                .cmp(&a.p95_duration)
+                .then(b.count.cmp(&a.count))
+                .then_with(|| a.normalized_sql.cmp(&b.normalized_sql))
+        });
+        slow.truncate(limit);
+        slow
+    }
+
     /// Clear logs older than the retention period
     ///
     /// # Returns
@@ -348,6 +495,12 @@ impl ActivityLogger {
     }
 }
 
+/// Nearest-rank percentile of sorted, non-empty `values`
+fn percentile(values: &[u64], pct: usize) -> u64 {
+    let rank = (values.len() * pct).div_ceil(100).max(1);
+    values[rank - 1]
+}
+
 impl Default for ActivityLogger {
     fn default() -> Self {
         Self::new(7) // 7 days retention by default
@@ -479,6 +632,92 @@ mod tests {
         assert!(stats.queries_by_type.contains_key(&QueryType::Insert));
     }
 
+    #[test]
+    fn test_get_timeseries_fills_empty_buckets() {
+        let logger = ActivityLogger::new(7);
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+
+        let mut log1 = create_test_log("log-1", "conn-1", "SELECT * FROM users");
+        log1.complete(100, Some(5));
+        log1.started_at = at("2025-11-17T09:00:00Z");
+        let mut log2 = create_test_log("log-2", "conn-1", "SELECT * FROM orders");
+        log2.fail(300, "Error".to_string());
+        log2.started_at = at("2025-11-17T18:00:00Z");
+        let mut log3 = create_test_log("log-3", "conn-2", "SELECT 1");
+        log3.complete(20, Some(1));
+        log3.started_at = at("2025-11-19T23:30:00Z");
+
+        logger.log_query_start(log1);
+        logger.log_query_start(log2);
+        logger.log_query_start(log3);
+
+        let series = logger.get_timeseries(None, TimeBucket::Daily, 0);
+        assert_eq!(series.len(), 3);
+        assert_eq!(series[0].bucket_start, at("2025-11-17T00:00:00Z"));
+        assert_eq!(series[0].total_queries, 2);
+        assert_eq!(series[0].failed_queries, 1);
+        assert_eq!(series[0].avg_duration, 200.0);
+        assert_eq!(series[1].total_queries, 0);
+        assert_eq!(series[1].avg_duration, 0.0);
+        assert_eq!(series[2].total_queries, 1);
+
+        // At UTC+2 the last query falls on the next local day
+        let series = logger.get_timeseries(None, TimeBucket::Daily, 120);
+        assert_eq!(series.len(), 4);
+        assert_eq!(series[3].bucket_start, at("2025-11-19T22:00:00Z"));
+
+        // The filter's date range extends the series beyond the matching logs
+        let filter = QueryLogFilter {
+            connection_id: Some("conn-1".to_string()),
+            end_date: Some("2025-11-20T12:00:00Z".to_string()),
+            ..Default::default()
+        };
+        let series = logger.get_timeseries(Some(filter), TimeBucket::Daily, 0);
+        assert_eq!(series.len(), 4);
+        assert_eq!(series.iter().map(|b| b.total_queries).sum::<usize>(), 2);
+    }
+
+    #[test]
+    fn test_get_slow_queries_groups_by_normalized_sql() {
+        let logger = ActivityLogger::new(7);
+
+        for (i, (sql, duration)) in [
+            ("SELECT * FROM orders WHERE id = 1", 1_200),
+            ("SELECT * FROM orders WHERE id = 2", 3_000),
+            ("SELECT * FROM orders\n WHERE id = 3", 900),
+            ("SELECT * FROM orders WHERE id = 4", 2_000),
+            ("SELECT * FROM users WHERE name = 'x'", 5_000),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut log = create_test_log(&format!("log-{}", i), "conn-1", sql);
+            log.complete(duration, None);
+            log.started_at = Utc::now() - Duration::minutes(10 - i as i64);
+            logger.log_query_start(log);
+        }
+
+        let slow = logger.get_slow_queries(1_000, None, 10);
+        assert_eq!(slow.len(), 2);
+        assert_eq!(slow[0].normalized_sql, "select * from users where name = ?");
+
+        // The 900ms run is under the threshold
+        let orders = &slow[1];
+        assert_eq!(orders.normalized_sql, "select * from orders where id = ?");
+        assert_eq!(orders.count, 3);
+        assert_eq!(orders.p50_duration, 2_000);
+        assert_eq!(orders.p95_duration, 3_000);
+        assert_eq!(orders.max_duration, 3_000);
+        assert_eq!(orders.sample_sql, "SELECT * FROM orders WHERE id = 4");
+
+        // `since` and `limit` narrow the report
+        let since = Utc::now() - Duration::seconds(450);
+        let slow = logger.get_slow_queries(1_000, Some(since), 10);
+        assert_eq!(slow.len(), 2);
+        assert_eq!(slow[1].count, 1);
+        assert_eq!(logger.get_slow_queries(1_000, Some(since), 1).len(), 1);
+    }
+
     #[test]
     fn test_clear_all_logs() {
         let logger = ActivityLogger::new(7);
@@ -0,0 +1,140 @@
+//! Connection health monitor
+//!
+//! Background task that periodically pings every active connection with
+//! `test_connection`, so a dropped network link or restarted server is
+//! noticed before the user's next query fails. Status transitions are
+//! emitted to the frontend as `connection-status-changed` events; the latest
+//! result per connection is kept in `AppState::connection_health`.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::AppState;
+use crate::commands::reconnect::reconnect;
+use crate::commands::settings::load_settings;
+use crate::drivers::DatabaseDriver;
+use crate::models::{ConnectionStatus, DbError};
+
+/// Event emitted when a connection's health status changes.
+pub const CONNECTION_STATUS_EVENT: &str = "connection-status-changed";
+
+/// How long a single ping may take before the connection counts as down.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Lower bound on the configured interval, to keep pings from flooding.
+const MIN_INTERVAL_SECS: u32 = 5;
+
+/// How often settings are re-read while health checks are disabled.
+const DISABLED_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Payload of `connection-status-changed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectionStatusEvent {
+    connection_id: String,
+    status: ConnectionStatus,
+}
+
+/// Start the health monitor loop on the Tauri async runtime.
+///
+/// Settings are re-read every cycle, so changing the interval or disabling
+/// checks takes effect without a restart.
+pub fn spawn_health_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let settings = load_settings(&app)
+                .map(|s| s.connection)
+                .unwrap_or_default();
+
+            if !settings.health_check_enabled {
+                tokio::time::sleep(DISABLED_POLL_INTERVAL).await;
+                continue;
+            }
+
+            check_connections(&app).await;
+
+            let interval = settings
+                .health_check_interval_seconds
+                .max(MIN_INTERVAL_SECS);
+            tokio::time::sleep(Duration::from_secs(u64::from(interval))).await;
+        }
+    });
+}
+
+/// Ping every active connection concurrently and publish status changes.
+async fn check_connections(app: &AppHandle) {
+    let state = app.state::<Mutex<AppState>>();
+    let connections: Vec<(String, Arc<dyn DatabaseDriver>)> = {
+        let state = state.lock().unwrap();
+        state
+            .connections
+            .iter()
+            .map(|(id, conn)| (id.clone(), Arc::clone(conn)))
+            .collect()
+    };
+
+    let checks = connections.into_iter().map(|(id, conn)| async move {
+        let started = Instant::now();
+        let status = ping(conn.as_ref()).await;
+        (id, status, started.elapsed())
+    });
+    let results = futures_util::future::join_all(checks).await;
+
+    for (id, status, elapsed) in results {
+        let changed = {
+            let mut state = state.lock().unwrap();
+            // Skip connections closed while the ping was in flight.
+            if !state.connections.contains_key(&id) {
+                continue;
+            }
+            if status == ConnectionStatus::Connected {
+                let latency = state.record_ping(&id, elapsed.as_millis() as u64);
+                if let Some(latency) = latency.filter(|l| l.degraded) {
+                    eprintln!(
+                        "Connection {} latency degraded: {} ms (average {} ms)",
+                        id, latency.latency_ms, latency.average_ms
+                    );
+                }
+            }
+            state.record_connection_health(&id, status.clone())
+        };
+
+        if !changed {
+            continue;
+        }
+
+        let dropped = status == ConnectionStatus::Disconnected;
+        let event = ConnectionStatusEvent {
+            connection_id: id.clone(),
+            status,
+        };
+        if let Err(e) = app.emit(CONNECTION_STATUS_EVENT, event) {
+            eprintln!("Failed to emit connection status event: {}", e);
+        }
+
+        // Profiles with auto_reconnect get rebuilt in the background;
+        // `reconnect` is a no-op for the rest.
+        if dropped {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = reconnect(&app, &id, true).await;
+            });
+        }
+    }
+}
+
+/// Classify a single `test_connection` call.
+///
+/// Timeouts and connection-level failures mean the server is unreachable
+/// (`Disconnected`); anything else (e.g. an auth or query error) is reported
+/// as `Error` with its message.
+async fn ping(conn: &dyn DatabaseDriver) -> ConnectionStatus {
+    match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, conn.test_connection()).await {
+        Ok(Ok(())) => ConnectionStatus::Connected,
+        Ok(Err(DbError::ConnectionError(_))) | Err(_) => ConnectionStatus::Disconnected,
+        Ok(Err(e)) => ConnectionStatus::Error(e.to_string()),
+    }
+}
@@ -0,0 +1,95 @@
+//! Connection keepalive
+//!
+//! Servers close sessions that sit idle for too long (MySQL `wait_timeout`,
+//! Postgres `idle_session_timeout`, firewalls dropping quiet TCP flows), and
+//! the user only finds out when their next query fails. Profiles with
+//! `keepalive_interval_secs` get a background task that pings the connection
+//! with `test_connection` once it has been idle for that long.
+//!
+//! A connection with an open transaction is never pinged: the ping would
+//! reset `idle_in_transaction_session_timeout` and keep an abandoned
+//! transaction (and its locks) alive. Failed pings are left to the health
+//! monitor, which reports the drop and reconnects.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use super::AppState;
+use crate::commands::settings::load_settings;
+
+/// Lower bound on a profile's keepalive interval.
+const MIN_INTERVAL_SECS: u32 = 5;
+
+/// How long a ping may take before it is abandoned.
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Start the keepalive task for an active connection.
+///
+/// Does nothing unless the connection's profile sets a keepalive interval.
+/// A task already running for the connection is replaced; the task is
+/// stopped by `AppState::remove_connection`.
+pub fn spawn_keepalive(app: &AppHandle, connection_id: &str) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut state = state.lock().unwrap();
+    let interval = state
+        .connection_profiles
+        .get(connection_id)
+        .and_then(|p| p.keepalive_interval_secs)
+        .filter(|secs| *secs > 0);
+    let Some(interval) = interval else {
+        return;
+    };
+    let interval = Duration::from_secs(u64::from(interval.max(MIN_INTERVAL_SECS)));
+
+    let task =
+        tauri::async_runtime::spawn(keep_alive(app.clone(), connection_id.to_string(), interval));
+    if let Some(previous) = state
+        .keepalive_tasks
+        .insert(connection_id.to_string(), task)
+    {
+        previous.abort();
+    }
+}
+
+/// Ping `connection_id` whenever it has been idle for `interval`.
+async fn keep_alive(app: AppHandle, connection_id: String, interval: Duration) {
+    loop {
+        // Sleep until the connection will have been idle for `interval`
+        let idle_for = {
+            let state = app.state::<Mutex<AppState>>();
+            let state = state.lock().unwrap();
+            state.last_activity.get(&connection_id).map(|t| t.elapsed())
+        };
+        if let Some(idle_for) = idle_for.filter(|d| *d < interval) {
+            tokio::time::sleep(interval - idle_for).await;
+            continue;
+        }
+
+        let enabled = load_settings(&app)
+            .map(|s| s.connection.keepalive_enabled)
+            .unwrap_or(true);
+        let connection = {
+            let state = app.state::<Mutex<AppState>>();
+            let state = state.lock().unwrap();
+            // The driver is looked up each time, since a reconnect replaces it
+            let Some(connection) = state.connections.get(&connection_id) else {
+                return;
+            };
+            let busy = state.open_transactions.contains(&connection_id)
+                || state.reconnecting.contains(&connection_id);
+            (enabled && !busy).then(|| connection.clone())
+        };
+
+        if let Some(connection) = connection {
+            let pinged = tokio::time::timeout(PING_TIMEOUT, connection.test_connection()).await;
+            if matches!(pinged, Ok(Ok(()))) {
+                let state = app.state::<Mutex<AppState>>();
+                state.lock().unwrap().touch_connection(&connection_id);
+                continue;
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
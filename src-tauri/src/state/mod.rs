@@ -6,18 +6,37 @@
 //! across the Tauri application.
 
 mod activity_logger;
+mod health_monitor;
+mod keepalive;
+mod result_cache;
+mod schedule_runner;
+mod scheduler;
+mod shutdown;
 
 pub use activity_logger::ActivityLogger;
-
-use std::collections::HashMap;
+pub use health_monitor::spawn_health_monitor;
+pub use keepalive::spawn_keepalive;
+pub use result_cache::ResultCache;
+pub use schedule_runner::spawn_schedule_runner;
+pub use scheduler::{QueryPriority, QueryScheduler, QueryTicket, QueueDepth};
+pub use shutdown::{is_shutting_down, request_shutdown};
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
+
+use tokio::sync::Semaphore;
 
+use crate::credentials::passphrase::{EncryptedPayload, MasterKey};
+use crate::drivers::pg_notify::PgListener;
 use crate::drivers::DatabaseDriver;
 use crate::models::{
-    ColumnInfo, ConnectionProfile, DatabaseInfo, DbError, QueryHistory, QuerySnippet, SchemaInfo,
-    TableInfo,
+    default_schema_for, ColumnInfo, ConnectionHealth, ConnectionLatency, ConnectionProfile,
+    ConnectionStatus, DatabaseInfo, DatabaseStats, DbError, QueryHistory, QuerySchedule,
+    QuerySettings, QuerySnippet, RunningQuery, SavedView, SchemaInfo, TableInfo, TableSchema,
 };
+use crate::sql::plan::RecordedPlan;
 use crate::ssh::SshTunnelManager;
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
@@ -28,9 +47,40 @@ use tauri_plugin_store::StoreExt;
 /// new entries and when loading a larger history file from a previous version.
 pub const MAX_HISTORY_ENTRIES: usize = 1000;
 
+/// Number of ping round trips kept per connection for latency tracking
+pub const PING_HISTORY_LEN: usize = 30;
+
+/// Number of recorded plans kept per query fingerprint
+pub const MAX_PLANS_PER_FINGERPRINT: usize = 20;
+
+/// Store file holding the master-password verifier
+const VAULT_STORE: &str = "vault.json";
+
+/// Known plaintext sealed under the master key; opening it proves the
+/// password is right before any real data is touched
+const VAULT_VERIFIER: &[u8] = b"db-hive-vault-v1";
+
+/// How long cached metadata is served before it counts as stale
+const METADATA_MAX_AGE: Duration = Duration::from_secs(300); // 5 minutes
+
+/// A level of the schema tree, cached on its own in `MetadataCache`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MetadataLevel {
+    /// Databases of the connection
+    Databases,
+    /// Schemas of a database
+    Schemas(String),
+    /// Tables of a schema
+    Tables(String),
+    /// Full schema of a table, as "schema.table"
+    TableSchema(String),
+}
+
 /// Metadata cache entry for a database connection
 ///
-/// Caches schema metadata to improve autocomplete performance
+/// Caches schema metadata to improve autocomplete performance. It is either
+/// filled all at once by `fetch_metadata_cache` (`complete`), or node by node
+/// as the schema tree is expanded; each level records when it was fetched.
 #[derive(Debug, Clone)]
 pub struct MetadataCache {
     /// List of databases
@@ -45,6 +95,17 @@ pub struct MetadataCache {
     /// Map of "schema.table" to columns
     pub columns: HashMap<String, Vec<ColumnInfo>>,
 
+    /// Map of "schema.table" to full table schema, for tables opened in the
+    /// schema tree
+    pub table_schemas: HashMap<String, TableSchema>,
+
+    /// When each level of the tree was last fetched
+    pub fetched_at: HashMap<MetadataLevel, SystemTime>,
+
+    /// Holds every schema, table and column of the database, not just the
+    /// nodes expanded so far
+    pub complete: bool,
+
     /// When the cache was last updated
     pub last_updated: SystemTime,
 }
@@ -57,17 +118,43 @@ impl MetadataCache {
             schemas: HashMap::new(),
             tables: HashMap::new(),
             columns: HashMap::new(),
+            table_schemas: HashMap::new(),
+            fetched_at: HashMap::new(),
+            complete: false,
             last_updated: SystemTime::now(),
         }
     }
 
     /// Check if the cache is stale (older than 5 minutes)
+    ///
+    /// A cache filled node by node is always stale to callers needing the
+    /// whole database, such as autocomplete.
     pub fn is_stale(&self) -> bool {
-        if let Ok(elapsed) = self.last_updated.elapsed() {
-            elapsed > Duration::from_secs(300) // 5 minutes
-        } else {
-            true
-        }
+        !self.complete || is_older_than(self.last_updated, METADATA_MAX_AGE)
+    }
+
+    /// Whether `level` was fetched within the last 5 minutes
+    pub fn is_level_fresh(&self, level: &MetadataLevel) -> bool {
+        self.fetched_at
+            .get(level)
+            .is_some_and(|at| !is_older_than(*at, METADATA_MAX_AGE))
+    }
+
+    /// Drop what is cached about `schema.table` and the table list of its
+    /// schema, e.g. after the table was renamed
+    pub fn forget_table(&mut self, schema: &str, table: &str) {
+        let key = format!("{}.{}", schema, table);
+        self.columns.remove(&key);
+        self.table_schemas.remove(&key);
+        self.fetched_at.remove(&MetadataLevel::TableSchema(key));
+        self.tables.remove(schema);
+        self.fetched_at.remove(&MetadataLevel::Tables(schema.to_string()));
+        self.complete = false;
+    }
+
+    /// Record that `level` was just fetched
+    pub fn record_level(&mut self, level: MetadataLevel) {
+        self.fetched_at.insert(level, SystemTime::now());
     }
 
     /// Update the timestamp
@@ -76,6 +163,47 @@ impl MetadataCache {
     }
 }
 
+/// Whether `at` is more than `max_age` ago (or in the future, after a clock
+/// change)
+fn is_older_than(at: SystemTime, max_age: Duration) -> bool {
+    at.elapsed().map_or(true, |elapsed| elapsed > max_age)
+}
+
+/// How long collected database stats are served from cache.
+///
+/// The overview dashboard polls; the size and catalog queries are cheap but
+/// not free on large databases, so repeat requests within this window reuse
+/// the previous result.
+pub const DATABASE_STATS_TTL: Duration = Duration::from_secs(30);
+
+/// Cached database stats for one (connection, database) pair
+#[derive(Debug, Clone)]
+pub struct CachedDatabaseStats {
+    /// The collected stats
+    pub stats: DatabaseStats,
+
+    /// When the stats were collected
+    pub collected_at: SystemTime,
+}
+
+impl CachedDatabaseStats {
+    /// Wrap freshly collected stats
+    pub fn new(stats: DatabaseStats) -> Self {
+        Self {
+            stats,
+            collected_at: SystemTime::now(),
+        }
+    }
+
+    /// Check if the entry is still within `DATABASE_STATS_TTL`
+    pub fn is_fresh(&self) -> bool {
+        self.collected_at
+            .elapsed()
+            .map(|elapsed| elapsed < DATABASE_STATS_TTL)
+            .unwrap_or(false)
+    }
+}
+
 /// Application state
 ///
 /// Central state container for the entire application. This is wrapped in a `Mutex`
@@ -119,10 +247,26 @@ pub struct AppState {
     /// Stores records of all executed queries with metadata
     pub query_history: Vec<QueryHistory>,
 
+    /// Last value used for each named query parameter (`:name`), used to
+    /// pre-fill the parameter prompt. Key: Parameter name
+    pub parameter_values: HashMap<String, serde_json::Value>,
+
+    /// Plans captured by `record_query_plan`, oldest first (at most
+    /// `MAX_PLANS_PER_FINGERPRINT`). Key: Query fingerprint
+    pub query_plans: HashMap<String, Vec<RecordedPlan>>,
+
     /// Saved query snippets
     /// Key: Snippet ID (UUID), Value: Query snippet
     pub query_snippets: HashMap<String, QuerySnippet>,
 
+    /// Saved views (named queries shown in the sidebar tree)
+    /// Key: View ID (UUID), Value: Saved view
+    pub saved_views: HashMap<String, SavedView>,
+
+    /// Scheduled queries
+    /// Key: Schedule ID (UUID), Value: Query schedule
+    pub query_schedules: HashMap<String, QuerySchedule>,
+
     /// SSH tunnel manager for managing active SSH tunnels
     pub ssh_tunnel_manager: SshTunnelManager,
 
@@ -130,8 +274,73 @@ pub struct AppState {
     /// Key: Connection ID (UUID), Value: Metadata cache
     pub metadata_cache: HashMap<String, MetadataCache>,
 
+    /// Short-lived database stats cache
+    /// Key: "connection_id:database", Value: Cached stats
+    pub database_stats_cache: HashMap<String, CachedDatabaseStats>,
+
+    /// Results of queries run with `use_cache`, dropped when a statement
+    /// writes to a table they read
+    pub result_cache: ResultCache,
+
+    /// Last observed health of each active connection
+    /// Key: Connection ID (UUID), Value: Health as of the last check
+    pub connection_health: HashMap<String, ConnectionHealth>,
+
+    /// Connections with an explicit transaction open (BEGIN seen, no
+    /// COMMIT/ROLLBACK yet). A dropped connection in this set is reported as
+    /// an aborted transaction instead of being silently retried.
+    pub open_transactions: HashSet<String>,
+
+    /// Sessions pinned by the open transaction of pooled connections (see
+    /// `DatabaseDriver::open_session`). Key: Connection ID
+    pub transaction_sessions: HashMap<String, Arc<dyn DatabaseDriver>>,
+
+    /// Semaphore limiting each connection's concurrent queries, with the
+    /// limit it was created for. Key: Connection ID
+    pub query_slots: HashMap<String, (usize, Arc<Semaphore>)>,
+
+    /// Interactive and background work on each connection
+    pub query_scheduler: Arc<QueryScheduler>,
+
+    /// Queries running or waiting for a slot
+    /// Key: Query log ID, Value: The query
+    pub running_queries: HashMap<String, RunningQuery>,
+
+    /// Connections with a reconnect currently in progress
+    pub reconnecting: HashSet<String>,
+
+    /// When each active connection last ran a query (or keepalive ping)
+    pub last_activity: HashMap<String, Instant>,
+
+    /// Recent ping round-trip times of each active connection, in
+    /// milliseconds, oldest first (at most `PING_HISTORY_LEN`)
+    pub ping_history: HashMap<String, VecDeque<u64>>,
+
+    /// Keepalive task of each connection that has keepalive configured
+    pub keepalive_tasks: HashMap<String, tauri::async_runtime::JoinHandle<()>>,
+
+    /// Dedicated LISTEN sessions of Postgres connections with notification
+    /// subscriptions
+    pub pg_listeners: HashMap<String, Arc<PgListener>>,
+
+    /// Databases attached to SQLite connections with `sqlite_attach_database`
+    /// Key: Connection ID, Value: file path by alias
+    pub sqlite_attachments: HashMap<String, BTreeMap<String, String>>,
+
+    /// Cancel flags of running `import_data_to_table` calls, keyed by
+    /// import ID
+    pub active_imports: HashMap<String, Arc<AtomicBool>>,
+
     /// Activity logger for tracking query execution
     pub activity_logger: ActivityLogger,
+
+    /// Whether profiles, snippets and saved views are encrypted at rest
+    /// with a master password
+    pub store_encrypted: bool,
+
+    /// Key derived from the master password; `None` while the store is
+    /// locked (or not encrypted)
+    pub master_key: Option<MasterKey>,
 }
 
 impl Default for AppState {
@@ -141,10 +350,31 @@ impl Default for AppState {
             connection_profiles: HashMap::new(),
             connection_passwords: HashMap::new(),
             query_history: Vec::new(),
+            parameter_values: HashMap::new(),
+            query_plans: HashMap::new(),
             query_snippets: HashMap::new(),
+            saved_views: HashMap::new(),
+            query_schedules: HashMap::new(),
             ssh_tunnel_manager: SshTunnelManager::new(),
             metadata_cache: HashMap::new(),
+            database_stats_cache: HashMap::new(),
+            result_cache: ResultCache::default(),
+            connection_health: HashMap::new(),
+            open_transactions: HashSet::new(),
+            transaction_sessions: HashMap::new(),
+            query_slots: HashMap::new(),
+            query_scheduler: Arc::default(),
+            running_queries: HashMap::new(),
+            reconnecting: HashSet::new(),
+            last_activity: HashMap::new(),
+            ping_history: HashMap::new(),
+            keepalive_tasks: HashMap::new(),
+            pg_listeners: HashMap::new(),
+            sqlite_attachments: HashMap::new(),
+            active_imports: HashMap::new(),
             activity_logger: ActivityLogger::new(7), // 7 days retention
+            store_encrypted: false,
+            master_key: None,
         }
     }
 }
@@ -161,10 +391,31 @@ impl AppState {
             connection_profiles: HashMap::new(),
             connection_passwords: HashMap::new(),
             query_history: Vec::new(),
+            parameter_values: HashMap::new(),
+            query_plans: HashMap::new(),
             query_snippets: HashMap::new(),
+            saved_views: HashMap::new(),
+            query_schedules: HashMap::new(),
             ssh_tunnel_manager: SshTunnelManager::new(),
             metadata_cache: HashMap::new(),
+            database_stats_cache: HashMap::new(),
+            result_cache: ResultCache::default(),
+            connection_health: HashMap::new(),
+            open_transactions: HashSet::new(),
+            transaction_sessions: HashMap::new(),
+            query_slots: HashMap::new(),
+            query_scheduler: Arc::default(),
+            running_queries: HashMap::new(),
+            reconnecting: HashSet::new(),
+            last_activity: HashMap::new(),
+            ping_history: HashMap::new(),
+            keepalive_tasks: HashMap::new(),
+            pg_listeners: HashMap::new(),
+            sqlite_attachments: HashMap::new(),
+            active_imports: HashMap::new(),
             activity_logger: ActivityLogger::new(7), // 7 days retention
+            store_encrypted: false,
+            master_key: None,
         }
     }
 
@@ -183,6 +434,7 @@ impl AppState {
     ///
     /// If a connection with the same ID already exists, it will be replaced.
     pub fn add_connection(&mut self, id: String, connection: Arc<dyn DatabaseDriver>) {
+        self.last_activity.insert(id.clone(), Instant::now());
         self.connections.insert(id, connection);
     }
 
@@ -196,9 +448,97 @@ impl AppState {
     ///
     /// The removed connection if it existed, `None` otherwise
     pub fn remove_connection(&mut self, id: &str) -> Option<Arc<dyn DatabaseDriver>> {
+        self.connection_health.remove(id);
+        self.open_transactions.remove(id);
+        self.transaction_sessions.remove(id);
+        self.query_slots.remove(id);
+        self.last_activity.remove(id);
+        self.ping_history.remove(id);
+        self.metadata_cache.remove(id);
+        self.result_cache.clear(Some(id));
+        self.pg_listeners.remove(id);
+        self.sqlite_attachments.remove(id);
+        if let Some(task) = self.keepalive_tasks.remove(id) {
+            task.abort();
+        }
         self.connections.remove(id)
     }
 
+    /// The semaphore holding `id`'s query slots, `limit` of them (at least
+    /// one)
+    ///
+    /// A changed limit replaces the semaphore; queries holding a slot of the
+    /// old one keep running.
+    pub fn query_semaphore(&mut self, id: &str, limit: usize) -> Arc<Semaphore> {
+        let limit = limit.max(1);
+        match self.query_slots.get(id) {
+            Some((current, semaphore)) if *current == limit => Arc::clone(semaphore),
+            _ => {
+                let semaphore = Arc::new(Semaphore::new(limit));
+                self.query_slots
+                    .insert(id.to_string(), (limit, Arc::clone(&semaphore)));
+                semaphore
+            }
+        }
+    }
+
+    /// Mark a connection as just used, postponing its next keepalive ping
+    pub fn touch_connection(&mut self, id: &str) {
+        if self.connections.contains_key(id) {
+            self.last_activity.insert(id.to_string(), Instant::now());
+        }
+    }
+
+    /// Record the result of a health check
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Connection identifier
+    /// * `status` - Status observed by the check
+    ///
+    /// # Returns
+    ///
+    /// `true` if the status differs from the previous check. A connection
+    /// without a previous check is assumed to have been `Connected`, since it
+    /// only becomes active after a successful connect.
+    pub fn record_connection_health(&mut self, id: &str, status: ConnectionStatus) -> bool {
+        let now = chrono::Utc::now().to_rfc3339();
+        let previous = self.connection_health.get(id);
+        let changed = previous.map_or(ConnectionStatus::Connected, |h| h.status.clone()) != status;
+        let last_healthy_at = if status == ConnectionStatus::Connected {
+            Some(now.clone())
+        } else {
+            previous.and_then(|h| h.last_healthy_at.clone())
+        };
+
+        self.connection_health.insert(
+            id.to_string(),
+            ConnectionHealth {
+                connection_id: id.to_string(),
+                status,
+                last_healthy_at,
+                last_checked_at: now,
+            },
+        );
+        changed
+    }
+
+    /// Record a ping round trip and return the connection's latency summary
+    pub fn record_ping(&mut self, id: &str, latency_ms: u64) -> Option<ConnectionLatency> {
+        let samples = self.ping_history.entry(id.to_string()).or_default();
+        if samples.len() == PING_HISTORY_LEN {
+            samples.pop_front();
+        }
+        samples.push_back(latency_ms);
+        self.connection_latency(id)
+    }
+
+    /// Latency summary of a connection, if it has been pinged
+    pub fn connection_latency(&self, id: &str) -> Option<ConnectionLatency> {
+        let samples: Vec<u64> = self.ping_history.get(id)?.iter().copied().collect();
+        ConnectionLatency::from_samples(id, &samples)
+    }
+
     /// Get a reference to an active database connection
     ///
     /// # Arguments
@@ -212,6 +552,37 @@ impl AppState {
         self.connections.get(id)
     }
 
+    /// Default schema set on a connection's profile, if any
+    ///
+    /// Used by commands whose schema argument is optional.
+    pub fn default_schema(&self, connection_id: &str) -> Option<String> {
+        self.connection_profiles
+            .get(connection_id)
+            .and_then(|profile| profile.default_schema_name())
+            .map(str::to_string)
+    }
+
+    /// `schema`, or the schema a command should use when given none
+    ///
+    /// Falls back to the profile's default schema, then to the driver's
+    /// (see [`default_schema_for`]).
+    pub fn resolve_schema(
+        &self,
+        connection_id: &str,
+        schema: Option<&str>,
+    ) -> Result<String, DbError> {
+        if let Some(schema) = schema {
+            return Ok(schema.to_string());
+        }
+        let profile = self.connection_profiles.get(connection_id).ok_or_else(|| {
+            DbError::NotFound(format!("Connection profile {} not found", connection_id))
+        })?;
+        Ok(profile.default_schema_name().map_or_else(
+            || default_schema_for(&profile.driver, profile.database.as_deref()),
+            str::to_string,
+        ))
+    }
+
     /// Check if a connection is active
     ///
     /// # Arguments
@@ -350,9 +721,9 @@ impl AppState {
 
         // Get profiles from store
         if let Some(profiles_value) = store.get("profiles") {
-            // Deserialize profiles
+            // Deserialize profiles (decrypting first if the store is encrypted)
             let profiles: Vec<ConnectionProfile> =
-                serde_json::from_value(profiles_value.clone()).map_err(|e| {
+                serde_json::from_value(self.open_store_value(profiles_value)?).map_err(|e| {
                     DbError::InternalError(format!("Failed to deserialize profiles: {}", e))
                 })?;
 
@@ -392,7 +763,7 @@ impl AppState {
             .map_err(|e| DbError::InternalError(format!("Failed to serialize profiles: {}", e)))?;
 
         // Set profiles in store (returns ())
-        store.set("profiles", profiles_value);
+        store.set("profiles", self.seal_store_value(profiles_value)?);
 
         // Save the store to disk
         store
@@ -469,13 +840,72 @@ impl AppState {
     /// Add a query history entry
     ///
     /// History is capped at [`MAX_HISTORY_ENTRIES`]; the oldest entries are
-    /// evicted (FIFO) when the cap is exceeded.
+    /// evicted (FIFO) when the cap is exceeded. Favorites are never evicted.
     pub fn add_history(&mut self, history: QueryHistory) {
         self.query_history.push(history);
         if self.query_history.len() > MAX_HISTORY_ENTRIES {
-            let excess = self.query_history.len() - MAX_HISTORY_ENTRIES;
-            self.query_history.drain(..excess);
+            self.prune_history(0, MAX_HISTORY_ENTRIES, chrono::Utc::now());
+        }
+    }
+
+    /// Drop history entries that fall outside a retention policy
+    ///
+    /// Removes entries executed more than `retention_days` before `now`
+    /// (0 disables the age limit), then the oldest entries beyond
+    /// `max_entries`. Favorited entries are never removed and do not count
+    /// toward `max_entries`. Entries with an unparseable timestamp are only
+    /// subject to the count limit.
+    ///
+    /// # Returns
+    ///
+    /// Number of entries removed
+    pub fn prune_history(
+        &mut self,
+        retention_days: u32,
+        max_entries: usize,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> usize {
+        let original_len = self.query_history.len();
+
+        if retention_days > 0 {
+            let cutoff = now - chrono::Duration::days(i64::from(retention_days));
+            self.query_history.retain(|h| {
+                h.favorite
+                    || chrono::DateTime::parse_from_rfc3339(&h.executed_at)
+                        .map_or(true, |executed_at| executed_at >= cutoff)
+            });
         }
+
+        // History is stored oldest-first, so the first non-favorites go
+        let non_favorites = self.query_history.iter().filter(|h| !h.favorite).count();
+        let mut excess = non_favorites.saturating_sub(max_entries);
+        self.query_history.retain(|h| {
+            if excess > 0 && !h.favorite {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+
+        original_len - self.query_history.len()
+    }
+
+    /// Apply the user's history retention settings
+    ///
+    /// The count limit never exceeds [`MAX_HISTORY_ENTRIES`].
+    pub fn apply_history_retention(&mut self, settings: &QuerySettings) -> usize {
+        let max_entries = (settings.max_history_entries as usize).min(MAX_HISTORY_ENTRIES);
+        self.prune_history(
+            settings.history_retention_days,
+            max_entries,
+            chrono::Utc::now(),
+        )
+    }
+
+    /// Get a mutable reference to a history entry by ID
+    pub fn get_history_mut(&mut self, id: &str) -> Option<&mut QueryHistory> {
+        self.query_history.iter_mut().find(|h| h.id == id)
     }
 
     /// Get all query history
@@ -514,21 +944,19 @@ impl AppState {
             .map_err(|e| DbError::InternalError(format!("Failed to access store: {}", e)))?;
 
         if let Some(history_value) = store.get("history") {
-            let mut history: Vec<QueryHistory> =
+            let history: Vec<QueryHistory> =
                 serde_json::from_value(history_value.clone()).map_err(|e| {
                     DbError::InternalError(format!("Failed to deserialize history: {}", e))
                 })?;
 
             // Truncate oversized files from older versions, keeping the most
             // recent entries (history is stored oldest-first)
-            if history.len() > MAX_HISTORY_ENTRIES {
-                let excess = history.len() - MAX_HISTORY_ENTRIES;
-                history.drain(..excess);
+            self.query_history = history;
+            if self.query_history.len() > MAX_HISTORY_ENTRIES {
+                self.prune_history(0, MAX_HISTORY_ENTRIES, chrono::Utc::now());
             }
 
-            let count = history.len();
-            self.query_history = history;
-            Ok(count)
+            Ok(self.query_history.len())
         } else {
             Ok(0)
         }
@@ -557,6 +985,117 @@ impl AppState {
         Ok(())
     }
 
+    /// Remember the values a parameterized query ran with
+    pub fn remember_parameter_values(&mut self, values: &HashMap<String, serde_json::Value>) {
+        for (name, value) in values {
+            self.parameter_values.insert(name.clone(), value.clone());
+        }
+    }
+
+    /// Load last-used parameter values from persistent storage
+    pub fn load_parameter_values_from_store(&mut self, app: &AppHandle) -> Result<usize, DbError> {
+        let store = app
+            .store("history.json")
+            .map_err(|e| DbError::InternalError(format!("Failed to access store: {}", e)))?;
+
+        if let Some(values) = store.get("parameterValues") {
+            let values: HashMap<String, serde_json::Value> = serde_json::from_value(values.clone())
+                .map_err(|e| {
+                    DbError::InternalError(format!("Failed to deserialize parameter values: {}", e))
+                })?;
+
+            let count = values.len();
+            self.parameter_values = values;
+            Ok(count)
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Save a snapshot of last-used parameter values to persistent storage
+    ///
+    /// Like `save_history_to_store`, this takes a snapshot so the write can
+    /// happen after the `AppState` lock is released.
+    pub fn save_parameter_values_to_store(
+        app: &AppHandle,
+        values: &HashMap<String, serde_json::Value>,
+    ) -> Result<(), DbError> {
+        let store = app
+            .store("history.json")
+            .map_err(|e| DbError::InternalError(format!("Failed to access store: {}", e)))?;
+
+        let values = serde_json::to_value(values).map_err(|e| {
+            DbError::InternalError(format!("Failed to serialize parameter values: {}", e))
+        })?;
+
+        store.set("parameterValues", values);
+
+        store
+            .save()
+            .map_err(|e| DbError::InternalError(format!("Failed to persist store: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Add a recorded plan, dropping the oldest of its fingerprint beyond
+    /// `MAX_PLANS_PER_FINGERPRINT`
+    pub fn record_plan(&mut self, plan: RecordedPlan) {
+        let plans = self
+            .query_plans
+            .entry(plan.fingerprint.clone())
+            .or_default();
+        plans.push(plan);
+        if plans.len() > MAX_PLANS_PER_FINGERPRINT {
+            let excess = plans.len() - MAX_PLANS_PER_FINGERPRINT;
+            plans.drain(..excess);
+        }
+    }
+
+    /// Load recorded query plans from persistent storage
+    pub fn load_query_plans_from_store(&mut self, app: &AppHandle) -> Result<usize, DbError> {
+        let store = app
+            .store("query_plans.json")
+            .map_err(|e| DbError::InternalError(format!("Failed to access store: {}", e)))?;
+
+        if let Some(plans) = store.get("plans") {
+            let plans: HashMap<String, Vec<RecordedPlan>> = serde_json::from_value(plans.clone())
+                .map_err(|e| {
+                DbError::InternalError(format!("Failed to deserialize query plans: {}", e))
+            })?;
+
+            let count = plans.values().map(Vec::len).sum();
+            self.query_plans = plans;
+            Ok(count)
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Save a snapshot of recorded query plans to persistent storage
+    ///
+    /// Like `save_history_to_store`, this takes a snapshot so the write can
+    /// happen after the `AppState` lock is released.
+    pub fn save_query_plans_to_store(
+        app: &AppHandle,
+        plans: &HashMap<String, Vec<RecordedPlan>>,
+    ) -> Result<(), DbError> {
+        let store = app
+            .store("query_plans.json")
+            .map_err(|e| DbError::InternalError(format!("Failed to access store: {}", e)))?;
+
+        let plans = serde_json::to_value(plans).map_err(|e| {
+            DbError::InternalError(format!("Failed to serialize query plans: {}", e))
+        })?;
+
+        store.set("plans", plans);
+
+        store
+            .save()
+            .map_err(|e| DbError::InternalError(format!("Failed to persist store: {}", e)))?;
+
+        Ok(())
+    }
+
     // ========================================================================
     // Query Snippet Management
     // ========================================================================
@@ -603,7 +1142,7 @@ impl AppState {
 
         if let Some(snippets_value) = store.get("snippets") {
             let snippets: Vec<QuerySnippet> =
-                serde_json::from_value(snippets_value.clone()).map_err(|e| {
+                serde_json::from_value(self.open_store_value(snippets_value)?).map_err(|e| {
                     DbError::InternalError(format!("Failed to deserialize snippets: {}", e))
                 })?;
 
@@ -627,7 +1166,7 @@ impl AppState {
         let snippets_value = serde_json::to_value(&snippets)
             .map_err(|e| DbError::InternalError(format!("Failed to serialize snippets: {}", e)))?;
 
-        store.set("snippets", snippets_value);
+        store.set("snippets", self.seal_store_value(snippets_value)?);
 
         store
             .save()
@@ -635,6 +1174,340 @@ impl AppState {
 
         Ok(())
     }
+
+    // ========================================================================
+    // Saved View Management
+    // ========================================================================
+
+    /// Add or update a saved view
+    pub fn add_saved_view(&mut self, view: SavedView) {
+        self.saved_views.insert(view.id.clone(), view);
+    }
+
+    /// Remove a saved view
+    pub fn remove_saved_view(&mut self, id: &str) -> Option<SavedView> {
+        self.saved_views.remove(id)
+    }
+
+    /// Get a saved view by ID
+    pub fn get_saved_view(&self, id: &str) -> Option<&SavedView> {
+        self.saved_views.get(id)
+    }
+
+    /// Load saved views from persistent storage
+    ///
+    /// Views live in the snippets store under their own key.
+    pub fn load_saved_views_from_store(&mut self, app: &AppHandle) -> Result<usize, DbError> {
+        let store = app
+            .store("snippets.json")
+            .map_err(|e| DbError::InternalError(format!("Failed to access store: {}", e)))?;
+
+        if let Some(views_value) = store.get("savedViews") {
+            let views: Vec<SavedView> = serde_json::from_value(self.open_store_value(views_value)?)
+                .map_err(|e| {
+                    DbError::InternalError(format!("Failed to deserialize saved views: {}", e))
+                })?;
+
+            let count = views.len();
+            for view in views {
+                self.saved_views.insert(view.id.clone(), view);
+            }
+            Ok(count)
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Save saved views to persistent storage
+    pub fn save_saved_views_to_store(&self, app: &AppHandle) -> Result<(), DbError> {
+        let store = app
+            .store("snippets.json")
+            .map_err(|e| DbError::InternalError(format!("Failed to access store: {}", e)))?;
+
+        let views: Vec<&SavedView> = self.saved_views.values().collect();
+        let views_value = serde_json::to_value(&views).map_err(|e| {
+            DbError::InternalError(format!("Failed to serialize saved views: {}", e))
+        })?;
+
+        store.set("savedViews", self.seal_store_value(views_value)?);
+
+        store
+            .save()
+            .map_err(|e| DbError::InternalError(format!("Failed to persist store: {}", e)))?;
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Query Schedule Management
+    // ========================================================================
+
+    /// Add or update a query schedule
+    pub fn add_schedule(&mut self, schedule: QuerySchedule) {
+        self.query_schedules.insert(schedule.id.clone(), schedule);
+    }
+
+    /// Remove a query schedule
+    pub fn remove_schedule(&mut self, id: &str) -> Option<QuerySchedule> {
+        self.query_schedules.remove(id)
+    }
+
+    /// Get a query schedule by ID
+    pub fn get_schedule(&self, id: &str) -> Option<&QuerySchedule> {
+        self.query_schedules.get(id)
+    }
+
+    /// Load query schedules from persistent storage
+    pub fn load_schedules_from_store(&mut self, app: &AppHandle) -> Result<usize, DbError> {
+        let store = app
+            .store("schedules.json")
+            .map_err(|e| DbError::InternalError(format!("Failed to access store: {}", e)))?;
+
+        if let Some(schedules_value) = store.get("schedules") {
+            let schedules: Vec<QuerySchedule> = serde_json::from_value(schedules_value.clone())
+                .map_err(|e| {
+                    DbError::InternalError(format!("Failed to deserialize schedules: {}", e))
+                })?;
+
+            let count = schedules.len();
+            for schedule in schedules {
+                self.query_schedules.insert(schedule.id.clone(), schedule);
+            }
+            Ok(count)
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Save query schedules to persistent storage
+    pub fn save_schedules_to_store(&self, app: &AppHandle) -> Result<(), DbError> {
+        let store = app
+            .store("schedules.json")
+            .map_err(|e| DbError::InternalError(format!("Failed to access store: {}", e)))?;
+
+        let schedules: Vec<&QuerySchedule> = self.query_schedules.values().collect();
+        let schedules_value = serde_json::to_value(&schedules)
+            .map_err(|e| DbError::InternalError(format!("Failed to serialize schedules: {}", e)))?;
+
+        store.set("schedules", schedules_value);
+
+        store
+            .save()
+            .map_err(|e| DbError::InternalError(format!("Failed to persist store: {}", e)))?;
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Store Encryption
+    // ========================================================================
+
+    /// Whether the store is encrypted and the master password has not been
+    /// entered yet
+    pub fn is_store_locked(&self) -> bool {
+        self.store_encrypted && self.master_key.is_none()
+    }
+
+    fn ensure_store_unlocked(&self) -> Result<(), DbError> {
+        if self.is_store_locked() {
+            return Err(DbError::StoreLocked(
+                "Enter the master password to unlock profiles and snippets".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Prepare a value for writing under a store key
+    ///
+    /// With a master key the value is serialized and sealed as
+    /// `{"encrypted": <payload>}`; otherwise it is written as is.
+    fn seal_store_value(&self, value: serde_json::Value) -> Result<serde_json::Value, DbError> {
+        self.ensure_store_unlocked()?;
+        let Some(key) = &self.master_key else {
+            return Ok(value);
+        };
+        let plaintext = serde_json::to_vec(&value).map_err(|e| {
+            DbError::InternalError(format!("Failed to serialize store data: {}", e))
+        })?;
+        let payload = key.seal(&plaintext)?;
+        Ok(serde_json::json!({ "encrypted": payload }))
+    }
+
+    /// Read a value written by `seal_store_value`
+    ///
+    /// Plain values (written before encryption was enabled) pass through, so
+    /// an existing unencrypted file keeps loading and is encrypted on its
+    /// next save.
+    fn open_store_value(&self, value: serde_json::Value) -> Result<serde_json::Value, DbError> {
+        let Some(payload) = value.get("encrypted") else {
+            return Ok(value);
+        };
+        let payload: EncryptedPayload = serde_json::from_value(payload.clone())
+            .map_err(|e| DbError::InternalError(format!("Invalid encrypted store data: {}", e)))?;
+
+        self.ensure_store_unlocked()?;
+        let key = self.master_key.as_ref().ok_or_else(|| {
+            DbError::StoreLocked(
+                "Store data is encrypted but no master password is set".to_string(),
+            )
+        })?;
+        let plaintext = key.open(&payload)?;
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| DbError::InternalError(format!("Failed to decode store data: {}", e)))
+    }
+
+    fn read_vault_verifier(app: &AppHandle) -> Result<Option<EncryptedPayload>, DbError> {
+        let store = app
+            .store(VAULT_STORE)
+            .map_err(|e| DbError::InternalError(format!("Failed to access store: {}", e)))?;
+
+        store
+            .get("verifier")
+            .map(|value| {
+                serde_json::from_value(value).map_err(|e| {
+                    DbError::InternalError(format!("Invalid master password verifier: {}", e))
+                })
+            })
+            .transpose()
+    }
+
+    /// Check whether a master password has been set
+    ///
+    /// Call before loading profiles and snippets: when this returns `true`
+    /// the store starts locked and loading waits for `unlock_store`.
+    pub fn load_store_encryption(&mut self, app: &AppHandle) -> Result<bool, DbError> {
+        self.store_encrypted = Self::read_vault_verifier(app)?.is_some();
+        Ok(self.store_encrypted)
+    }
+
+    /// Whether profiles, snippets or saved views are stored unencrypted
+    ///
+    /// Used to offer encrypting existing data when no master password is set.
+    pub fn has_unencrypted_store_data(&self, app: &AppHandle) -> Result<bool, DbError> {
+        for (file, key) in [
+            ("profiles.json", "profiles"),
+            ("snippets.json", "snippets"),
+            ("snippets.json", "savedViews"),
+        ] {
+            let store = app
+                .store(file)
+                .map_err(|e| DbError::InternalError(format!("Failed to access store: {}", e)))?;
+            if store
+                .get(key)
+                .is_some_and(|value| value.as_array().is_some_and(|items| !items.is_empty()))
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Unlock an encrypted store and load its profiles, snippets and views
+    pub fn unlock_store(&mut self, app: &AppHandle, password: &str) -> Result<(), DbError> {
+        if !self.store_encrypted {
+            return Err(DbError::InvalidInput(
+                "No master password is set".to_string(),
+            ));
+        }
+        if self.master_key.is_some() {
+            return Ok(());
+        }
+
+        let verifier = Self::read_vault_verifier(app)?.ok_or_else(|| {
+            DbError::InternalError("Master password verifier is missing".to_string())
+        })?;
+        let key = MasterKey::derive_for(&verifier, password)?;
+        if key.open(&verifier)? != VAULT_VERIFIER {
+            return Err(DbError::CredentialError(
+                "Master password verifier does not match".to_string(),
+            ));
+        }
+        self.master_key = Some(key);
+
+        self.load_profiles_from_store(app)?;
+        self.load_snippets_from_store(app)?;
+        self.load_saved_views_from_store(app)?;
+        Ok(())
+    }
+
+    /// Forget the master key and drop decrypted data from memory
+    ///
+    /// Active connections stay open; only the saved profiles, snippets and
+    /// views disappear until the store is unlocked again.
+    pub fn lock_store(&mut self) -> Result<(), DbError> {
+        if !self.store_encrypted {
+            return Err(DbError::InvalidInput(
+                "No master password is set".to_string(),
+            ));
+        }
+        self.master_key = None;
+        self.connection_profiles.clear();
+        self.query_snippets.clear();
+        self.saved_views.clear();
+        Ok(())
+    }
+
+    /// Set or change the master password and re-encrypt the store with it
+    ///
+    /// Enabling encryption on an unencrypted store encrypts the existing
+    /// profiles, snippets and views. Changing the password requires the
+    /// current one and an unlocked store.
+    pub fn set_master_password(
+        &mut self,
+        app: &AppHandle,
+        password: &str,
+        current_password: Option<&str>,
+    ) -> Result<(), DbError> {
+        if password.is_empty() {
+            return Err(DbError::InvalidInput(
+                "Master password must not be empty".to_string(),
+            ));
+        }
+        if self.store_encrypted {
+            self.ensure_store_unlocked()?;
+            let current = current_password.ok_or_else(|| {
+                DbError::InvalidInput("Current master password is required".to_string())
+            })?;
+            let verifier = Self::read_vault_verifier(app)?.ok_or_else(|| {
+                DbError::InternalError("Master password verifier is missing".to_string())
+            })?;
+            MasterKey::derive_for(&verifier, current)?.open(&verifier)?;
+        }
+
+        let key = MasterKey::derive(password)?;
+        let verifier = key.seal(VAULT_VERIFIER)?;
+        let previous_key = self.master_key.replace(key);
+        let previous_encrypted = std::mem::replace(&mut self.store_encrypted, true);
+
+        // The verifier is written last, so until it is saved the previous
+        // password is still the one that unlocks the store
+        let result = self
+            .save_profiles_to_store(app)
+            .and_then(|_| self.save_snippets_to_store(app))
+            .and_then(|_| self.save_saved_views_to_store(app))
+            .and_then(|_| {
+                let store = app.store(VAULT_STORE).map_err(|e| {
+                    DbError::InternalError(format!("Failed to access store: {}", e))
+                })?;
+                let verifier_value = serde_json::to_value(&verifier).map_err(|e| {
+                    DbError::InternalError(format!("Failed to serialize verifier: {}", e))
+                })?;
+                store.set("verifier", verifier_value);
+                store
+                    .save()
+                    .map_err(|e| DbError::InternalError(format!("Failed to persist store: {}", e)))
+            });
+
+        if result.is_err() {
+            // Rewrite whatever was already re-encrypted with the previous key
+            self.master_key = previous_key;
+            self.store_encrypted = previous_encrypted;
+            let _ = self.save_profiles_to_store(app);
+            let _ = self.save_snippets_to_store(app);
+            let _ = self.save_saved_views_to_store(app);
+        }
+        result
+    }
 }
 
 #[cfg(test)]
@@ -667,6 +1540,22 @@ mod tests {
         assert_eq!(state.connection_profiles.len(), 0);
     }
 
+    #[test]
+    fn test_record_connection_health() {
+        let mut state = AppState::new();
+
+        // First healthy check matches the assumed initial state.
+        assert!(!state.record_connection_health("c1", ConnectionStatus::Connected));
+        let healthy_at = state.connection_health["c1"].last_healthy_at.clone();
+        assert!(healthy_at.is_some());
+
+        assert!(state.record_connection_health("c1", ConnectionStatus::Disconnected));
+        assert!(!state.record_connection_health("c1", ConnectionStatus::Disconnected));
+        assert_eq!(state.connection_health["c1"].last_healthy_at, healthy_at);
+
+        assert!(state.record_connection_health("c1", ConnectionStatus::Connected));
+    }
+
     #[test]
     fn test_add_and_get_profile() {
         let mut state = AppState::new();
@@ -776,4 +1665,114 @@ mod tests {
         let retrieved = state.get_profile("test-1").unwrap();
         assert_eq!(retrieved.name, "Modified Name");
     }
+
+    #[test]
+    fn test_prune_history_keeps_favorites() {
+        let now = chrono::Utc::now();
+        let entry = |query: &str, days_ago: i64, favorite: bool| {
+            let executed_at = (now - chrono::Duration::days(days_ago)).to_rfc3339();
+            let mut h = QueryHistory::new(
+                "conn".to_string(),
+                "Conn".to_string(),
+                "db".to_string(),
+                query.to_string(),
+                executed_at,
+            );
+            h.favorite = favorite;
+            h
+        };
+
+        let mut state = AppState::new();
+        // Oldest first, as history is stored
+        state.query_history = vec![
+            entry("old favorite", 40, true),
+            entry("old", 40, false),
+            entry("a", 3, false),
+            entry("b", 2, false),
+            entry("c", 1, false),
+        ];
+
+        assert_eq!(state.prune_history(30, 2, now), 2);
+        let queries: Vec<&str> = state.query_history.iter().map(|h| h.query.as_str()).collect();
+        assert_eq!(queries, vec!["old favorite", "b", "c"]);
+
+        // Unlimited age, count only
+        assert_eq!(state.prune_history(0, 1, now), 1);
+        assert_eq!(state.query_history.len(), 2);
+    }
+
+    #[test]
+    fn test_store_value_encryption() {
+        let mut state = AppState::new();
+        let profiles = serde_json::json!([{ "id": "a" }]);
+
+        // Without a master password values are stored as is
+        assert_eq!(state.seal_store_value(profiles.clone()).unwrap(), profiles);
+
+        state.store_encrypted = true;
+        state.master_key = Some(MasterKey::derive("master").unwrap());
+        let sealed = state.seal_store_value(profiles.clone()).unwrap();
+        assert!(sealed.get("encrypted").is_some());
+        assert_eq!(state.open_store_value(sealed.clone()).unwrap(), profiles);
+        // Files written before encryption was enabled still load
+        assert_eq!(state.open_store_value(profiles.clone()).unwrap(), profiles);
+
+        state.lock_store().unwrap();
+        assert!(state.is_store_locked());
+        assert!(matches!(
+            state.open_store_value(sealed),
+            Err(DbError::StoreLocked(_))
+        ));
+        assert!(matches!(
+            state.seal_store_value(profiles),
+            Err(DbError::StoreLocked(_))
+        ));
+    }
+
+    #[test]
+    fn test_record_plan_keeps_latest() {
+        let mut state = AppState::new();
+        let plan = crate::sql::plan::parse_postgres_plan(
+            &serde_json::json!([{ "Plan": { "Node Type": "Seq Scan" } }]),
+        )
+        .unwrap();
+        for i in 0..MAX_PLANS_PER_FINGERPRINT + 2 {
+            state.record_plan(RecordedPlan {
+                fingerprint: "abc".to_string(),
+                connection_id: "c1".to_string(),
+                sql: "SELECT 1".to_string(),
+                recorded_at: i.to_string(),
+                plan: plan.clone(),
+            });
+        }
+
+        let plans = &state.query_plans["abc"];
+        assert_eq!(plans.len(), MAX_PLANS_PER_FINGERPRINT);
+        assert_eq!(plans[0].recorded_at, "2");
+        assert_eq!(
+            plans.last().unwrap().recorded_at,
+            (MAX_PLANS_PER_FINGERPRINT + 1).to_string()
+        );
+    }
+
+    #[test]
+    fn test_forget_table() {
+        let mut cache = MetadataCache::new();
+        let table = TableInfo::new("orders".to_string(), "sales".to_string(), "TABLE".to_string());
+        cache.tables.insert("sales".to_string(), vec![table.clone()]);
+        cache.record_level(MetadataLevel::Tables("sales".to_string()));
+        cache
+            .table_schemas
+            .insert("sales.orders".to_string(), TableSchema::new(table, vec![], vec![]));
+        cache.record_level(MetadataLevel::TableSchema("sales.orders".to_string()));
+        cache.tables.insert("public".to_string(), Vec::new());
+        cache.complete = true;
+
+        cache.forget_table("sales", "orders");
+        assert!(!cache.tables.contains_key("sales"));
+        assert!(!cache.table_schemas.contains_key("sales.orders"));
+        assert!(!cache.is_level_fresh(&MetadataLevel::Tables("sales".to_string())));
+        assert!(cache.tables.contains_key("public"));
+        assert!(!cache.complete);
+    }
 }
@@ -0,0 +1,217 @@
+//! Cached query results
+//!
+//! `execute_query` called with `use_cache` keeps the results of read-only
+//! SELECTs here, keyed by connection and compacted query text, so re-opening
+//! a dashboard tab doesn't run its queries again. Entries expire after
+//! `RESULT_CACHE_TTL`; beyond `MAX_ENTRIES` entries or `MAX_ROWS` rows in
+//! total the least recently used ones are evicted. A statement that writes
+//! to a table drops the entries that read from it.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::commands::query::QueryExecutionResult;
+
+/// How long a cached result is served
+const RESULT_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Most results kept across all connections
+const MAX_ENTRIES: usize = 100;
+
+/// Most rows kept across all cached results
+const MAX_ROWS: usize = 100_000;
+
+struct CachedResult {
+    result: QueryExecutionResult,
+    /// Tables the query reads, as given by `referenced_tables`
+    tables: Vec<String>,
+    cached_at: Instant,
+    last_used: Instant,
+}
+
+/// Query results keyed by `(connection_id, compacted SQL)`
+pub struct ResultCache {
+    entries: HashMap<(String, String), CachedResult>,
+    max_entries: usize,
+    max_rows: usize,
+}
+
+impl Default for ResultCache {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            max_entries: MAX_ENTRIES,
+            max_rows: MAX_ROWS,
+        }
+    }
+}
+
+impl ResultCache {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Cached result of `key` on `connection_id`, if there is a fresh one
+    pub fn get(&mut self, connection_id: &str, key: &str) -> Option<QueryExecutionResult> {
+        let id = (connection_id.to_string(), key.to_string());
+        let entry = self.entries.get_mut(&id)?;
+        if entry.cached_at.elapsed() > RESULT_CACHE_TTL {
+            self.entries.remove(&id);
+            return None;
+        }
+        entry.last_used = Instant::now();
+        Some(entry.result.clone())
+    }
+
+    /// Cache `result` for `key` on `connection_id`
+    ///
+    /// A result with more rows than the whole cache may hold is not stored.
+    pub fn insert(
+        &mut self,
+        connection_id: String,
+        key: String,
+        tables: Vec<String>,
+        result: QueryExecutionResult,
+    ) {
+        if result.rows.len() > self.max_rows {
+            return;
+        }
+        let now = Instant::now();
+        self.entries.insert(
+            (connection_id, key),
+            CachedResult {
+                result,
+                tables,
+                cached_at: now,
+                last_used: now,
+            },
+        );
+
+        self.entries
+            .retain(|_, entry| entry.cached_at.elapsed() <= RESULT_CACHE_TTL);
+        let mut rows: usize = self.entries.values().map(|e| e.result.rows.len()).sum();
+        while self.entries.len() > self.max_entries || rows > self.max_rows {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(id, _)| id.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&oldest) {
+                rows -= entry.result.rows.len();
+            }
+        }
+    }
+
+    /// Drop the results of `connection_id` that read any of `tables`, or all
+    /// of its results if `tables` is empty (a statement whose tables aren't
+    /// known). Returns the number of entries dropped.
+    pub fn invalidate(&mut self, connection_id: &str, tables: &[String]) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|(id, _), entry| {
+            id != connection_id
+                || !(tables.is_empty() || entry.tables.iter().any(|t| tables.contains(t)))
+        });
+        before - self.entries.len()
+    }
+
+    /// Drop the results of `connection_id` that read `table`, after a command
+    /// that wrote to it without going through a query. `table` is unquoted
+    /// and unqualified; it is lowercased like the names `referenced_tables`
+    /// gives.
+    pub fn invalidate_table(&mut self, connection_id: &str, table: &str) -> usize {
+        self.invalidate(connection_id, &[table.to_lowercase()])
+    }
+
+    /// Drop every result, or those of one connection; returns the number of
+    /// entries dropped
+    pub fn clear(&mut self, connection_id: Option<&str>) -> usize {
+        match connection_id {
+            Some(connection_id) => self.invalidate(connection_id, &[]),
+            None => {
+                let count = self.entries.len();
+                self.entries.clear();
+                count
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn result(rows: usize) -> QueryExecutionResult {
+        QueryExecutionResult {
+            columns: vec!["n".to_string()],
+            original_columns: vec!["n".to_string()],
+            rows: (0..rows).map(|n| vec![json!(n)]).collect(),
+            rows_affected: None,
+            execution_time: 1,
+            query_type: "SELECT".to_string(),
+            truncated: false,
+            limited: false,
+            from_cache: false,
+        }
+    }
+
+    fn tables(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_invalidate_by_table() {
+        let mut cache = ResultCache::default();
+        cache.insert("c1".into(), "q1".into(), tables(&["users"]), result(1));
+        cache.insert(
+            "c1".into(),
+            "q2".into(),
+            tables(&["orders", "users"]),
+            result(1),
+        );
+        cache.insert("c1".into(), "q3".into(), tables(&["orders"]), result(1));
+        cache.insert("c2".into(), "q1".into(), tables(&["users"]), result(1));
+
+        assert_eq!(cache.invalidate("c1", &tables(&["users"])), 2);
+        assert!(cache.get("c1", "q1").is_none());
+        assert!(cache.get("c1", "q3").is_some());
+        assert!(cache.get("c2", "q1").is_some());
+
+        assert_eq!(cache.invalidate_table("c1", "Orders"), 1);
+        cache.insert("c1".into(), "q3".into(), tables(&["orders"]), result(1));
+
+        // Unknown tables drop everything cached for the connection
+        assert_eq!(cache.invalidate("c1", &[]), 1);
+        assert_eq!(cache.clear(None), 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = ResultCache {
+            max_entries: 2,
+            max_rows: 10,
+            ..Default::default()
+        };
+        let tick = || std::thread::sleep(Duration::from_millis(2));
+        cache.insert("c1".into(), "a".into(), vec![], result(1));
+        tick();
+        cache.insert("c1".into(), "b".into(), vec![], result(1));
+        tick();
+        assert!(cache.get("c1", "a").is_some());
+        tick();
+        cache.insert("c1".into(), "c".into(), vec![], result(1));
+        assert!(cache.get("c1", "b").is_none());
+        tick();
+
+        // Over the row budget: `a` and `c` both go
+        cache.insert("c1".into(), "d".into(), vec![], result(10));
+        assert_eq!(cache.entries.len(), 1);
+        assert!(cache.get("c1", "d").is_some());
+        cache.insert("c1".into(), "e".into(), vec![], result(11));
+        assert!(cache.get("c1", "e").is_none());
+    }
+}
@@ -0,0 +1,142 @@
+//! Query schedule runner
+//!
+//! Background task that fires due query schedules while the app is open.
+//! There is no daemon: at startup, runs that fell due while the app was
+//! closed are dropped, or collapsed into a single run for schedules with
+//! `MissedRunPolicy::RunOnce`. A schedule's `next_run_at` is advanced before
+//! its query starts, so a slow run is never fired twice.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, Manager};
+
+use super::AppState;
+use crate::commands::schedules::run_schedule;
+use crate::models::{MissedRunPolicy, QuerySchedule};
+
+/// How often due schedules are looked for
+const TICK: Duration = Duration::from_secs(15);
+
+/// Start the schedule runner on the Tauri async runtime.
+pub fn spawn_schedule_runner(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        update_schedules(&app, |schedules| {
+            catch_up_missed_runs(schedules, Utc::now());
+        });
+
+        loop {
+            let mut due = Vec::new();
+            update_schedules(&app, |schedules| {
+                due = take_due_schedules(schedules, Utc::now());
+            });
+
+            for schedule_id in due {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = run_schedule(&app, &schedule_id).await {
+                        eprintln!("Scheduled query {} failed: {}", schedule_id, e);
+                    }
+                });
+            }
+
+            tokio::time::sleep(TICK).await;
+        }
+    });
+}
+
+/// Apply `update` to the schedules and persist them if any changed
+fn update_schedules(app: &AppHandle, update: impl FnOnce(&mut HashMap<String, QuerySchedule>)) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut state = state.lock().unwrap();
+    let before: Vec<_> = state
+        .query_schedules
+        .values()
+        .map(|s| (s.id.clone(), s.next_run_at))
+        .collect();
+
+    update(&mut state.query_schedules);
+
+    let changed = before
+        .iter()
+        .any(|(id, next)| state.query_schedules.get(id).map(|s| s.next_run_at) != Some(*next));
+    if changed {
+        if let Err(e) = state.save_schedules_to_store(app) {
+            eprintln!("Failed to save query schedules: {}", e);
+        }
+    }
+}
+
+/// Deal with runs that fell due before `now`, i.e. while the app was closed
+///
+/// `Skip` schedules move on to their next time after `now`; `RunOnce`
+/// schedules stay due so the first tick runs them once.
+fn catch_up_missed_runs(schedules: &mut HashMap<String, QuerySchedule>, now: DateTime<Utc>) {
+    for schedule in schedules.values_mut() {
+        let missed = schedule.next_run_at.is_some_and(|next| next <= now);
+        if missed && schedule.missed_runs == MissedRunPolicy::Skip {
+            schedule.next_run_at = schedule.next_after(now).ok().flatten();
+        }
+    }
+}
+
+/// IDs of the schedules due at `now`, with their next run advanced
+fn take_due_schedules(
+    schedules: &mut HashMap<String, QuerySchedule>,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let mut due = Vec::new();
+    for schedule in schedules.values_mut() {
+        if schedule.next_run_at.is_some_and(|next| next <= now) {
+            schedule.next_run_at = schedule.next_after(now).ok().flatten();
+            due.push(schedule.id.clone());
+        }
+    }
+    due
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(id: &str, next: &str, missed_runs: MissedRunPolicy) -> QuerySchedule {
+        QuerySchedule {
+            id: id.to_string(),
+            name: id.to_string(),
+            connection_id: "conn-1".to_string(),
+            sql: Some("SELECT 1".to_string()),
+            snippet_id: None,
+            cron_expr: "0 3 * * *".to_string(),
+            missed_runs,
+            confirmed_sql: None,
+            created_at: "2025-11-01T00:00:00Z".to_string(),
+            next_run_at: Some(next.parse().unwrap()),
+            last_run_at: None,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn test_missed_runs_then_due() {
+        let now: DateTime<Utc> = "2025-11-19T12:00:00Z".parse().unwrap();
+        let mut schedules = HashMap::new();
+        for s in [
+            schedule("skip", "2025-11-10T03:00:00Z", MissedRunPolicy::Skip),
+            schedule("once", "2025-11-10T03:00:00Z", MissedRunPolicy::RunOnce),
+            schedule("later", "2025-11-20T03:00:00Z", MissedRunPolicy::Skip),
+        ] {
+            schedules.insert(s.id.clone(), s);
+        }
+
+        catch_up_missed_runs(&mut schedules, now);
+        let tomorrow: DateTime<Utc> = "2025-11-20T03:00:00Z".parse().unwrap();
+        assert_eq!(schedules["skip"].next_run_at, Some(tomorrow));
+
+        let due = take_due_schedules(&mut schedules, now);
+        assert_eq!(due, vec!["once".to_string()]);
+        assert_eq!(schedules["once"].next_run_at, Some(tomorrow));
+        assert!(take_due_schedules(&mut schedules, now).is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! Interactive vs background query priority
+//!
+//! Queries typed in the editor are `Interactive`. Autocomplete metadata
+//! refreshes, database stats and file exports are `Background`: they wait
+//! for the connection's interactive queries to finish before starting, and
+//! long-running ones call `QueryTicket::yield_to_interactive` between
+//! batches so a query started meanwhile goes first. A background task stops
+//! deferring once it has waited `MAX_DEFERRAL` in total, so a busy editor
+//! can't starve it.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// Longest a background task waits for interactive queries, over all its
+/// yields
+const MAX_DEFERRAL: Duration = Duration::from_secs(10);
+
+/// Whether a query is waited on by the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryPriority {
+    Interactive,
+    Background,
+}
+
+/// Work queued and running on one connection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueDepth {
+    /// Interactive queries running
+    pub interactive: usize,
+    /// Background tasks running, including deferred ones
+    pub background: usize,
+    /// Background tasks waiting for interactive queries to finish
+    pub deferred: usize,
+}
+
+/// Per-connection counts of interactive and background work
+#[derive(Default)]
+pub struct QueryScheduler {
+    queues: Mutex<HashMap<String, QueueDepth>>,
+    /// Woken whenever an interactive query finishes
+    finished: Notify,
+}
+
+impl QueryScheduler {
+    /// Register work on `connection_id`
+    ///
+    /// Background work first waits for running interactive queries (see
+    /// `yield_to_interactive`). The work counts as running until the ticket
+    /// is dropped.
+    pub async fn enter(
+        self: &Arc<Self>,
+        connection_id: &str,
+        priority: QueryPriority,
+    ) -> QueryTicket {
+        let ticket = QueryTicket {
+            scheduler: Arc::clone(self),
+            connection_id: connection_id.to_string(),
+            priority,
+            defer_until: Instant::now() + MAX_DEFERRAL,
+        };
+        self.update(connection_id, |depth| match priority {
+            QueryPriority::Interactive => depth.interactive += 1,
+            QueryPriority::Background => depth.background += 1,
+        });
+        ticket.yield_to_interactive().await;
+        ticket
+    }
+
+    /// Current work on `connection_id`
+    pub fn depth(&self, connection_id: &str) -> QueueDepth {
+        self.queues
+            .lock()
+            .unwrap()
+            .get(connection_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn update(&self, connection_id: &str, change: impl FnOnce(&mut QueueDepth)) {
+        let mut queues = self.queues.lock().unwrap();
+        let depth = queues.entry(connection_id.to_string()).or_default();
+        change(depth);
+        if *depth == QueueDepth::default() {
+            queues.remove(connection_id);
+        }
+    }
+}
+
+/// A query or background task registered with the `QueryScheduler`
+pub struct QueryTicket {
+    scheduler: Arc<QueryScheduler>,
+    connection_id: String,
+    priority: QueryPriority,
+    /// End of the ticket's `MAX_DEFERRAL` budget
+    defer_until: Instant,
+}
+
+impl QueryTicket {
+    /// Wait until no interactive query runs on the connection
+    ///
+    /// Returns at once for interactive tickets, and for background tickets
+    /// that have used up their `MAX_DEFERRAL`.
+    pub async fn yield_to_interactive(&self) {
+        if self.priority == QueryPriority::Interactive {
+            return;
+        }
+        let scheduler = &self.scheduler;
+        let mut deferred = None;
+        loop {
+            // Created before the check, so a query finishing in between
+            // still wakes it
+            let finished = scheduler.finished.notified();
+            if scheduler.depth(&self.connection_id).interactive == 0 {
+                break;
+            }
+            if deferred.is_none() {
+                scheduler.update(&self.connection_id, |depth| depth.deferred += 1);
+                deferred = Some(Deferred(self));
+            }
+            if tokio::time::timeout_at(self.defer_until, finished)
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+}
+
+impl Drop for QueryTicket {
+    fn drop(&mut self) {
+        let priority = self.priority;
+        self.scheduler
+            .update(&self.connection_id, |depth| match priority {
+                QueryPriority::Interactive => depth.interactive -= 1,
+                QueryPriority::Background => depth.background -= 1,
+            });
+        if priority == QueryPriority::Interactive {
+            self.scheduler.finished.notify_waiters();
+        }
+    }
+}
+
+/// Counts a background ticket as deferred while it waits, even if the wait
+/// is cancelled
+struct Deferred<'a>(&'a QueryTicket);
+
+impl Drop for Deferred<'_> {
+    fn drop(&mut self) {
+        self.0
+            .scheduler
+            .update(&self.0.connection_id, |depth| depth.deferred -= 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_background_waits_for_interactive() {
+        let scheduler = Arc::new(QueryScheduler::default());
+        let interactive = scheduler.enter("c1", QueryPriority::Interactive).await;
+
+        let waiting = tokio::spawn({
+            let scheduler = Arc::clone(&scheduler);
+            async move {
+                let _ticket = scheduler.enter("c1", QueryPriority::Background).await;
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            scheduler.depth("c1"),
+            QueueDepth {
+                interactive: 1,
+                background: 1,
+                deferred: 1,
+            }
+        );
+        // Other connections aren't held up
+        drop(scheduler.enter("c2", QueryPriority::Background).await);
+
+        drop(interactive);
+        waiting.await.unwrap();
+        assert_eq!(scheduler.depth("c1"), QueueDepth::default());
+    }
+}
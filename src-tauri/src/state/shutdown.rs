@@ -0,0 +1,105 @@
+//! Graceful shutdown
+//!
+//! Quitting used to call `app.exit(0)` straight away, leaving server-side
+//! sessions and local SSH tunnel ports open until they timed out. Quitting
+//! from the tray, or closing the last window, now goes through
+//! [`request_shutdown`]: it emits `shutting-down`, closes every connection,
+//! transaction session and SSH tunnel, writes the stores once more and then
+//! exits. Cleanup is bounded by `SHUTDOWN_TIMEOUT` so an unreachable server
+//! can't keep the app from quitting.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::AppState;
+use crate::drivers::DatabaseDriver;
+
+/// Event emitted once when shutdown starts, so the UI can show progress
+pub const SHUTTING_DOWN_EVENT: &str = "shutting-down";
+
+/// Longest cleanup may take before the app exits anyway
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Set when shutdown has started; later quit requests are ignored
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Whether shutdown has started
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// Clean up in the background, then exit the app
+///
+/// Safe to call more than once; only the first call does anything.
+pub fn request_shutdown(app: &AppHandle) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if let Err(e) = app.emit(SHUTTING_DOWN_EVENT, ()) {
+        eprintln!("Failed to emit shutdown event: {}", e);
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if tokio::time::timeout(SHUTDOWN_TIMEOUT, shutdown(&app))
+            .await
+            .is_err()
+        {
+            eprintln!(
+                "Shutdown cleanup did not finish within {} seconds",
+                SHUTDOWN_TIMEOUT.as_secs()
+            );
+        }
+        app.exit(0);
+    });
+}
+
+/// Persist state and close every connection and SSH tunnel
+async fn shutdown(app: &AppHandle) {
+    let state = app.state::<Mutex<AppState>>();
+    let (drivers, tunnel_manager) = {
+        let mut state = state.lock().unwrap();
+        flush_stores(&state, app);
+
+        let mut drivers: Vec<Arc<dyn DatabaseDriver>> =
+            state.transaction_sessions.drain().map(|(_, s)| s).collect();
+        let ids: Vec<String> = state.connections.keys().cloned().collect();
+        drivers.extend(ids.iter().filter_map(|id| state.remove_connection(id)));
+        (drivers, state.ssh_tunnel_manager.clone())
+    };
+
+    let closes = drivers.iter().map(|driver| driver.close());
+    for result in futures_util::future::join_all(closes).await {
+        if let Err(e) = result {
+            eprintln!("Failed to close connection on shutdown: {}", e);
+        }
+    }
+
+    tunnel_manager.close_all_tunnels().await;
+}
+
+/// Write every store from the in-memory state
+///
+/// Stores are normally saved on each change; this catches anything a failed
+/// or interrupted write left behind. Encrypted stores are skipped while
+/// locked, since their contents were never loaded.
+fn flush_stores(state: &AppState, app: &AppHandle) {
+    let mut results = vec![
+        AppState::save_history_to_store(app, &state.query_history),
+        AppState::save_parameter_values_to_store(app, &state.parameter_values),
+        AppState::save_query_plans_to_store(app, &state.query_plans),
+        state.save_schedules_to_store(app),
+    ];
+    if !state.is_store_locked() {
+        results.push(state.save_profiles_to_store(app));
+        results.push(state.save_snippets_to_store(app));
+        results.push(state.save_saved_views_to_store(app));
+    }
+
+    for e in results.into_iter().filter_map(Result::err) {
+        eprintln!("Failed to save state on shutdown: {}", e);
+    }
+}
@@ -6,6 +6,7 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import type { DbDriver } from "../types/database";
 
 // Provider types
 export type AiProviderType = "ollama" | "openai" | "anthropic" | "google" | "openrouter";
@@ -61,6 +62,21 @@ export interface AiConfig {
   anthropic: AnthropicConfig;
   google: GoogleAiConfig;
   openrouter: OpenRouterConfig;
+  /** Retry policy for the hosted providers (not Ollama) */
+  retry?: AiRetryConfig;
+  /** Only use a local Ollama server; hosted providers are refused */
+  offlineMode?: boolean;
+  /** Remove hostnames, addresses and SQL literals from prompts sent to hosted providers */
+  scrubSensitive?: boolean;
+}
+
+export interface AiRetryConfig {
+  /** Retries after the first attempt; 0 disables retrying */
+  maxRetries: number;
+  /** Delay before the first retry, doubled for each further retry */
+  baseDelayMs: number;
+  /** Upper bound for a single delay, including a provider's Retry-After */
+  maxDelayMs: number;
 }
 
 // Model information
@@ -80,12 +96,56 @@ export interface AiChatResponse {
   durationMs: number;
 }
 
+export interface AiErrorExplanation {
+  explanation: string;
+  /** Corrected query, when the fix is a change to the query */
+  suggestedSql: string | null;
+  model: string;
+  provider: AiProviderType;
+  durationMs: number;
+  /** Served from the session cache without calling the provider */
+  cached: boolean;
+}
+
 export interface ProviderStatus {
   provider: AiProviderType;
   available: boolean;
   configured: boolean;
 }
 
+// Prompt templates
+export type PromptOperation =
+  | "generate_sql"
+  | "explain_query"
+  | "optimize_query"
+  | "fix_query"
+  | "explain_error"
+  | "chat";
+
+export interface AiPromptInfo {
+  operation: PromptOperation;
+  /** Template in effect, with `{name}` placeholders */
+  template: string;
+  defaultTemplate: string;
+  /** Whether `template` is a user override */
+  isCustom: boolean;
+  /** Placeholders the template may use */
+  variables: string[];
+  /** Placeholders the template must contain */
+  requiredVariables: string[];
+}
+
+/** Compact schema text for prompts, limited to a token budget */
+export interface SchemaSummary {
+  /** One `schema.table(column type, ...)` line per included table */
+  text: string;
+  /** Included tables (`schema.table`), most relevant first */
+  includedTables: string[];
+  /** Tables left out to stay within the budget */
+  omittedTables: string[];
+  estimatedTokens: number;
+}
+
 // Chat types
 export type ChatRole = "system" | "user" | "assistant";
 
@@ -144,19 +204,65 @@ export async function listAiModels(provider?: AiProviderType): Promise<AiModel[]
 }
 
 /**
- * Generate SQL from natural language
+ * Get the system prompt template of every AI operation
+ */
+export async function getAiPrompts(): Promise<AiPromptInfo[]> {
+  return invoke<AiPromptInfo[]>("get_ai_prompts");
+}
+
+/**
+ * Replace the prompt template of an operation. Rejects templates missing a
+ * required placeholder or using one the operation doesn't provide.
+ */
+export async function setAiPrompt(
+  operation: PromptOperation,
+  template: string
+): Promise<AiPromptInfo> {
+  return invoke<AiPromptInfo>("set_ai_prompt", { operation, template });
+}
+
+/**
+ * Restore the built-in template of one operation, or of all of them
+ */
+export async function resetAiPrompts(operation?: PromptOperation): Promise<AiPromptInfo[]> {
+  return invoke<AiPromptInfo[]>("reset_ai_prompts", { operation });
+}
+
+/**
+ * Summarize a connection's schema for a prompt within `maxTokens`,
+ * starting with `focusTables` and the tables linked to them
+ */
+export async function summarizeSchemaForAi(
+  connectionId: string,
+  maxTokens: number,
+  focusTables?: string[]
+): Promise<SchemaSummary> {
+  return invoke<SchemaSummary>("summarize_schema_for_ai", {
+    connectionId,
+    maxTokens,
+    focusTables,
+  });
+}
+
+/**
+ * Generate SQL from natural language. With `connectionId` the backend builds
+ * the schema context itself instead of using `schemaContext`.
  */
 export async function generateSql(
   prompt: string,
   schemaContext: string,
   model?: string,
-  provider?: AiProviderType
+  provider?: AiProviderType,
+  dialect?: DbDriver,
+  connectionId?: string
 ): Promise<AiChatResponse> {
   return invoke<AiChatResponse>("ai_generate_sql", {
     prompt,
     schemaContext,
     model,
     provider,
+    dialect,
+    connectionId,
   });
 }
 
@@ -166,9 +272,10 @@ export async function generateSql(
 export async function explainQuery(
   sql: string,
   model?: string,
-  provider?: AiProviderType
+  provider?: AiProviderType,
+  dialect?: DbDriver
 ): Promise<AiChatResponse> {
-  return invoke<AiChatResponse>("ai_explain_query", { sql, model, provider });
+  return invoke<AiChatResponse>("ai_explain_query", { sql, model, provider, dialect });
 }
 
 /**
@@ -178,13 +285,17 @@ export async function optimizeQuery(
   sql: string,
   schemaContext: string,
   model?: string,
-  provider?: AiProviderType
+  provider?: AiProviderType,
+  dialect?: DbDriver,
+  connectionId?: string
 ): Promise<AiChatResponse> {
   return invoke<AiChatResponse>("ai_optimize_query", {
     sql,
     schemaContext,
     model,
     provider,
+    dialect,
+    connectionId,
   });
 }
 
@@ -196,7 +307,9 @@ export async function fixQuery(
   errorMessage: string,
   schemaContext: string,
   model?: string,
-  provider?: AiProviderType
+  provider?: AiProviderType,
+  dialect?: DbDriver,
+  connectionId?: string
 ): Promise<AiChatResponse> {
   return invoke<AiChatResponse>("ai_fix_query", {
     sql,
@@ -204,16 +317,43 @@ export async function fixQuery(
     schemaContext,
     model,
     provider,
+    dialect,
+    connectionId,
+  });
+}
+
+/**
+ * Explain why a query failed. Schema context is taken from the
+ * connection's metadata cache; identical failures are answered from a cache.
+ * Pass the error's `sqlstate` or `errorCode` as `errorCode` when it has one.
+ */
+export async function explainError(
+  connectionId: string,
+  sql: string,
+  errorMessage: string,
+  model?: string,
+  provider?: AiProviderType,
+  errorCode?: string
+): Promise<AiErrorExplanation> {
+  return invoke<AiErrorExplanation>("ai_explain_error", {
+    connectionId,
+    sql,
+    errorMessage,
+    errorCode,
+    model,
+    provider,
   });
 }
 
 /**
- * General chat with the AI
+ * General chat with the AI. The chat prompt template is used as the system
+ * message unless `messages` starts with one.
  */
 export async function chat(
   messages: ChatMessage[],
   model?: string,
-  provider?: AiProviderType
+  provider?: AiProviderType,
+  dialect?: DbDriver
 ): Promise<AiChatResponse> {
-  return invoke<AiChatResponse>("ai_chat", { messages, model, provider });
+  return invoke<AiChatResponse>("ai_chat", { messages, model, provider, dialect });
 }
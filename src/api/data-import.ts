@@ -17,30 +17,46 @@ import type {
 export async function previewImportFile(
   filePath: string,
   previewRows: number = 100,
-  sheetName?: string
+  sheetName?: string,
+  csvOverrides?: { delimiter?: string; quote?: string; firstRowIsHeader?: boolean }
 ): Promise<ImportPreview> {
   return invoke<ImportPreview>("preview_import_file", {
     filePath,
     previewRows,
     sheetName: sheetName ?? null,
+    delimiter: csvOverrides?.delimiter ?? null,
+    quote: csvOverrides?.quote ?? null,
+    firstRowIsHeader: csvOverrides?.firstRowIsHeader ?? null,
   });
 }
 
 /**
  * Import data from a file into a database table
+ *
+ * Progress is emitted as "import-progress" events tagged with `importId`,
+ * which `cancelImport` takes to stop the import.
  */
 export async function importDataToTable(
   connectionId: string,
   filePath: string,
-  options: DataImportOptions
+  options: DataImportOptions,
+  importId?: string
 ): Promise<ImportResult> {
   return invoke<ImportResult>("import_data_to_table", {
     connectionId,
     filePath,
     options,
+    importId: importId ?? null,
   });
 }
 
+/**
+ * Stop a running data import, rolling back its current transaction
+ */
+export async function cancelImport(importId: string): Promise<void> {
+  return invoke<void>("cancel_import", { importId });
+}
+
 /**
  * Get list of tables available for import
  */
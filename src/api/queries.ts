@@ -0,0 +1,37 @@
+/**
+ * Query API
+ *
+ * Functions for inspecting the queries running on a connection.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type { QueueDepth, RunningQuery } from "@/types/activity";
+
+/**
+ * List the queries running on a connection
+ *
+ * Queries waiting for a free slot are included with `queued` set.
+ *
+ * @param connectionId - ID of the active connection
+ * @returns The running and queued queries, oldest first
+ */
+export async function getRunningQueries(
+  connectionId: string
+): Promise<RunningQuery[]> {
+  return invoke<RunningQuery[]>("get_running_queries", { connectionId });
+}
+
+/**
+ * Get how much work is running and deferred on a connection
+ *
+ * A non-zero `deferred` means background work (autocomplete metadata,
+ * database stats, exports) is waiting for interactive queries.
+ *
+ * @param connectionId - ID of the active connection
+ * @returns Counts of interactive, background and deferred work
+ */
+export async function getQueryQueueDepth(
+  connectionId: string
+): Promise<QueueDepth> {
+  return invoke<QueueDepth>("get_query_queue_depth", { connectionId });
+}
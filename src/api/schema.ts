@@ -0,0 +1,74 @@
+/**
+ * Schema API
+ *
+ * Functions for navigating the columns and relationships of a schema.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  ColumnMatch,
+  ColumnReferences,
+  SchemaGraph,
+} from "@/types/database";
+
+/**
+ * Find columns of a table by name
+ *
+ * Served from the autocomplete metadata cache when it holds the table.
+ *
+ * @param connectionId - ID of the active connection
+ * @param schema - Schema containing the table
+ * @param table - Table to search
+ * @param query - Substring or fuzzy pattern; empty lists every column
+ * @returns Matching columns, best match first
+ */
+export async function findColumns(
+  connectionId: string,
+  schema: string,
+  table: string,
+  query: string
+): Promise<ColumnMatch[]> {
+  return invoke<ColumnMatch[]>("find_columns", {
+    connectionId,
+    schema,
+    table,
+    query,
+  });
+}
+
+/**
+ * Get the foreign keys pointing to and from a column
+ *
+ * @param connectionId - ID of the active connection
+ * @param schema - Schema containing the table
+ * @param table - Table containing the column
+ * @param column - Column to trace
+ * @returns Foreign keys on the column and those referencing it
+ */
+export async function getColumnReferences(
+  connectionId: string,
+  schema: string,
+  table: string,
+  column: string
+): Promise<ColumnReferences> {
+  return invoke<ColumnReferences>("get_column_references", {
+    connectionId,
+    schema,
+    table,
+    column,
+  });
+}
+
+/**
+ * Get the tables and foreign keys of a schema for an ERD
+ *
+ * @param connectionId - ID of the active connection
+ * @param schema - Schema to graph
+ * @returns Tables with their columns, and foreign key edges
+ */
+export async function getSchemaGraph(
+  connectionId: string,
+  schema: string
+): Promise<SchemaGraph> {
+  return invoke<SchemaGraph>("get_schema_graph", { connectionId, schema });
+}
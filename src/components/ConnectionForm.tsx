@@ -8,7 +8,7 @@ import {
   SslMode,
   SshConfig,
   SshAuthMethod,
-  ConnectionStatus,
+  ConnectionTestResult,
   getDefaultPort,
   getDriverDisplayName,
 } from "../types/database";
@@ -282,14 +282,18 @@ export const ConnectionForm: FC<ConnectionFormProps> = ({
 
     try {
       const testProfile = buildProfile();
-      const status = await invoke<ConnectionStatus>("test_connection_command", {
+      const { status, serverInfo } = await invoke<ConnectionTestResult>("test_connection_command", {
         profile: testProfile,
         password,
         sshPassword: sshMode === "ssh" && sshConfig.authMethod === "Password" ? sshPassword : null,
       });
 
       if (status === "Connected") {
-        setTestStatus({ kind: "success", message: "Connection successful!" });
+        setTestStatus({
+          kind: "success",
+          message: "Connection successful!",
+          detail: serverInfo?.version,
+        });
         if (testProfile.id && password) {
           try {
             await invoke("save_password", { profileId: testProfile.id, password });
@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback } from "react";
 import { open } from "@tauri-apps/plugin-dialog";
+import { listen } from "@tauri-apps/api/event";
 import {
   Upload,
   FileSpreadsheet,
@@ -28,6 +29,7 @@ import {
 import {
   previewImportFile,
   importDataToTable,
+  cancelImport,
   getTablesForImport,
   getTableColumnsForImport,
 } from "../api/data-import";
@@ -36,6 +38,7 @@ import type {
   ColumnMapping,
   DataImportOptions,
   ImportResult,
+  ImportProgressEvent,
   TableColumn,
   ImportStep,
 } from "../types/data-import";
@@ -105,8 +108,13 @@ export function DataImportWizard({
     delimiter: ",",
     first_row_is_header: true,
     sheet_name: null,
+    use_transaction: false,
   });
 
+  // Running import
+  const [importId, setImportId] = useState<string | null>(null);
+  const [importProgress, setImportProgress] = useState<ImportProgressEvent | null>(null);
+
   // Import result
   const [importResult, setImportResult] = useState<ImportResult | null>(null);
 
@@ -200,6 +208,16 @@ export function DataImportWizard({
         options.sheet_name || undefined
       );
       setPreview(previewData);
+      const dialect = previewData.dialect;
+      if (dialect) {
+        setOptions((o) => ({
+          ...o,
+          delimiter: dialect.delimiter,
+          quote: dialect.quote,
+          escape: dialect.escape,
+          first_row_is_header: dialect.has_header,
+        }));
+      }
       setCurrentStep("preview");
     } catch (err) {
       setError(`Failed to preview file: ${err}`);
@@ -215,6 +233,15 @@ export function DataImportWizard({
     setLoading(true);
     setError(null);
 
+    const id = crypto.randomUUID();
+    setImportId(id);
+    setImportProgress(null);
+    const unlisten = await listen<ImportProgressEvent>("import-progress", (event) => {
+      if (event.payload.importId === id) {
+        setImportProgress(event.payload);
+      }
+    });
+
     try {
       const importOptions: DataImportOptions = {
         table_name: selectedTable,
@@ -225,11 +252,15 @@ export function DataImportWizard({
         truncate_before: options.truncate_before || false,
         batch_size: options.batch_size || 1000,
         delimiter: options.delimiter || null,
+        quote: options.quote || null,
+        escape: options.escape || null,
         sheet_name: options.sheet_name || null,
         first_row_is_header: options.first_row_is_header ?? true,
+        use_transaction: options.use_transaction ?? false,
+        commit_each_batch: true,
       };
 
-      const result = await importDataToTable(connectionId, filePath, importOptions);
+      const result = await importDataToTable(connectionId, filePath, importOptions, id);
       setImportResult(result);
       setCurrentStep("complete");
 
@@ -240,10 +271,22 @@ export function DataImportWizard({
       setError(`Import failed: ${err}`);
       setCurrentStep("options");
     } finally {
+      unlisten();
+      setImportId(null);
       setLoading(false);
     }
   };
 
+  const handleCancelImport = async () => {
+    if (!importId) return;
+    try {
+      await cancelImport(importId);
+    } catch (err) {
+      // The import may have just finished
+      console.error("Failed to cancel import:", err);
+    }
+  };
+
   const updateMapping = useCallback(
     (index: number, updates: Partial<ColumnMapping>) => {
       setColumnMappings((prev) => {
@@ -585,6 +628,21 @@ export function DataImportWizard({
             />
           </div>
 
+          <div className="flex items-center justify-between">
+            <div>
+              <Label className="text-foreground">Use transaction</Label>
+              <p className="text-xs text-muted-foreground">
+                Commit after each batch and roll back a batch that fails
+              </p>
+            </div>
+            <Checkbox
+              checked={options.use_transaction}
+              onCheckedChange={(checked) =>
+                setOptions((o) => ({ ...o, use_transaction: !!checked }))
+              }
+            />
+          </div>
+
           <div className="flex items-center justify-between">
             <div>
               <Label className="text-foreground">Truncate table before import</Label>
@@ -686,8 +744,18 @@ export function DataImportWizard({
       <Loader2 className="w-12 h-12 text-primary animate-spin mb-4" />
       <p className="text-lg font-medium text-foreground mb-2">Importing data...</p>
       <p className="text-sm text-muted-foreground">
-        Please wait while your data is being imported
+        {importProgress
+          ? `${importProgress.rowsProcessed.toLocaleString()} of ${importProgress.totalRows.toLocaleString()} rows`
+          : "Please wait while your data is being imported"}
       </p>
+      {importProgress?.etaMs != null && (
+        <p className="text-xs text-muted-foreground mt-1">
+          About {Math.ceil(importProgress.etaMs / 1000)}s left
+        </p>
+      )}
+      <Button variant="outline" size="sm" className="mt-4" onClick={handleCancelImport}>
+        Cancel Import
+      </Button>
     </div>
   );
 
@@ -730,8 +798,14 @@ export function DataImportWizard({
       ) : (
         <>
           <AlertCircle className="w-16 h-16 text-destructive mb-4" />
-          <p className="text-xl font-medium text-foreground mb-2">Import Failed</p>
-          <p className="text-muted-foreground">{error || "An unknown error occurred"}</p>
+          <p className="text-xl font-medium text-foreground mb-2">
+            {importResult?.cancelled ? "Import Cancelled" : "Import Failed"}
+          </p>
+          <p className="text-muted-foreground">
+            {importResult?.cancelled
+              ? `${importResult.rows_imported.toLocaleString()} rows were kept`
+              : error || "An unknown error occurred"}
+          </p>
         </>
       )}
     </div>
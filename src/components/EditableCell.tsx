@@ -1,6 +1,7 @@
 import { FC, useState, useRef, useEffect, KeyboardEvent } from 'react';
 import { Input } from './ui/input';
 import { cn } from '@/lib/utils';
+import { formatBlobLabel, isBlobValue } from '@/types/database';
 
 export interface CellChange {
   rowIndex: number;
@@ -182,6 +183,7 @@ export const EditableCell: FC<EditableCellProps> = ({
 
   // Render display mode
   const cellString = value === null || value === undefined ? 'NULL' :
+                     isBlobValue(value) ? formatBlobLabel(value) :
                      typeof value === "object" ? JSON.stringify(value) :
                      String(value);
   const isTruncated = cellString.length > 100;
@@ -25,6 +25,7 @@ import { InstalledPlugins } from "@/components/InstalledPlugins";
 import { PluginMarketplace } from "@/components/PluginMarketplace";
 import { MigrationsDialog } from "@/components/MigrationsDialog";
 import { BackupManagerDialog } from "@/components/BackupManagerDialog";
+import { StoreUnlockDialog } from "@/components/StoreUnlockDialog";
 import { useAppModal, setAppModal } from "@/store/useAppModal";
 
 export function GlobalModals() {
@@ -89,6 +90,9 @@ export function GlobalModals() {
           </div>
         </DialogContent>
       </Dialog>
+
+      {/* Master password prompt for an encrypted profile store */}
+      <StoreUnlockDialog />
     </>
   );
 }
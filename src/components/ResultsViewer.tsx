@@ -25,6 +25,7 @@ import {
   X,
 } from "lucide-react";
 import { cn } from "@/lib/utils";
+import { formatBlobLabel, isBlobValue } from "@/types/database";
 import { invoke } from "@tauri-apps/api/core";
 import { save } from "@tauri-apps/plugin-dialog";
 import { revealItemInDir } from "@tauri-apps/plugin-opener";
@@ -253,6 +254,13 @@ const ResultsViewerComponent: FC<ResultsViewerProps> = ({
           displayValue = (
             <span className="text-muted-foreground italic">undefined</span>
           );
+        } else if (isBlobValue(value)) {
+          cellString = formatBlobLabel(value);
+          displayValue = (
+            <span className="text-xs font-mono text-muted-foreground">
+              {cellString}
+            </span>
+          );
         } else if (typeof value === "object") {
           cellString = JSON.stringify(value);
           isTruncated = cellString.length > 100;
@@ -8,6 +8,7 @@ import {
   Keyboard,
   Save,
   RotateCcw,
+  Lock,
 } from "lucide-react";
 import { toast } from "sonner";
 import { Button } from "./ui/button";
@@ -27,7 +28,7 @@ import {
   AlertDialogHeader,
   AlertDialogTitle,
 } from "./ui/alert-dialog";
-import type { AppSettings, ShortcutsSettings } from "@/types";
+import type { AppSettings, ShortcutsSettings, StoreLockStatus } from "@/types";
 import { defaultSettings } from "@/types";
 import { useTheme } from "./theme-provider";
 import { broadcastSettingsChanged } from "@/hooks/useSettings";
@@ -92,7 +93,7 @@ function isValidShortcut(combo: string): boolean {
   return true;
 }
 
-type SettingsSection = "general" | "theme" | "query" | "shortcuts";
+type SettingsSection = "general" | "theme" | "query" | "shortcuts" | "security";
 
 export function SettingsPage() {
   const { setTheme } = useTheme();
@@ -198,6 +199,7 @@ export function SettingsPage() {
     { id: "theme" as const, label: "Appearance", icon: Palette },
     { id: "query" as const, label: "Query Execution", icon: Zap },
     { id: "shortcuts" as const, label: "Keyboard Shortcuts", icon: Keyboard },
+    { id: "security" as const, label: "Security", icon: Lock },
   ];
 
   if (isLoading) {
@@ -634,6 +636,8 @@ export function SettingsPage() {
               onResetAll={resetSettings}
             />
           )}
+
+          {activeSection === "security" && <SecuritySection />}
         </div>
       </ScrollArea>
     </div>
@@ -892,3 +896,158 @@ function ShortcutRow({
     </div>
   );
 }
+
+function errorMessage(err: unknown): string {
+  return typeof err === "string" ? err : (err as any)?.message || String(err);
+}
+
+/**
+ * Master password management. Encrypting the store protects profiles,
+ * snippets and saved views on disk; it does not replace the OS keyring used
+ * for connection passwords.
+ */
+function SecuritySection() {
+  const [status, setStatus] = useState<StoreLockStatus | null>(null);
+  const [currentPassword, setCurrentPassword] = useState("");
+  const [newPassword, setNewPassword] = useState("");
+  const [confirmPassword, setConfirmPassword] = useState("");
+  const [busy, setBusy] = useState(false);
+
+  const refreshStatus = useCallback(async () => {
+    try {
+      setStatus(await invoke<StoreLockStatus>("get_store_lock_status"));
+    } catch (err) {
+      toast.error(`Failed to read store status: ${errorMessage(err)}`);
+    }
+  }, []);
+
+  useEffect(() => {
+    refreshStatus();
+  }, [refreshStatus]);
+
+  const mismatch = confirmPassword.length > 0 && newPassword !== confirmPassword;
+  const canSubmit =
+    !busy &&
+    newPassword.length > 0 &&
+    newPassword === confirmPassword &&
+    (!status?.encrypted || currentPassword.length > 0);
+
+  const handleSetPassword = async () => {
+    setBusy(true);
+    try {
+      await invoke("set_master_password", {
+        password: newPassword,
+        currentPassword: status?.encrypted ? currentPassword : null,
+      });
+      toast.success(
+        status?.encrypted
+          ? "Master password changed"
+          : "Profiles and snippets are now encrypted",
+      );
+      setCurrentPassword("");
+      setNewPassword("");
+      setConfirmPassword("");
+      await refreshStatus();
+    } catch (err) {
+      toast.error(errorMessage(err));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleLock = async () => {
+    try {
+      await invoke("lock_store");
+      // Reload so the unlock prompt replaces the now-empty profile list
+      window.location.reload();
+    } catch (err) {
+      toast.error(errorMessage(err));
+    }
+  };
+
+  return (
+    <div className="space-y-6">
+      <div>
+        <h3 className="mb-1 text-2xl font-semibold">Security</h3>
+        <p className="text-muted-foreground text-sm">
+          Encrypt saved connection profiles and snippets with a master password
+        </p>
+      </div>
+
+      {status?.unencryptedData && (
+        <Card className="border-amber-500/50">
+          <CardHeader>
+            <CardTitle>Unencrypted data found</CardTitle>
+            <CardDescription>
+              Your connection profiles and snippets are stored in plain JSON.
+              Set a master password below to encrypt them.
+            </CardDescription>
+          </CardHeader>
+        </Card>
+      )}
+
+      <Card>
+        <CardHeader>
+          <CardTitle>{status?.encrypted ? "Change Master Password" : "Set Master Password"}</CardTitle>
+          <CardDescription>
+            The password is never stored. If you forget it, encrypted profiles
+            and snippets cannot be recovered.
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-3 max-w-sm">
+          {status?.encrypted && (
+            <div className="space-y-1">
+              <Label htmlFor="current-master-password">Current password</Label>
+              <Input
+                id="current-master-password"
+                type="password"
+                value={currentPassword}
+                onChange={(e) => setCurrentPassword(e.target.value)}
+              />
+            </div>
+          )}
+          <div className="space-y-1">
+            <Label htmlFor="new-master-password">New password</Label>
+            <Input
+              id="new-master-password"
+              type="password"
+              value={newPassword}
+              onChange={(e) => setNewPassword(e.target.value)}
+            />
+          </div>
+          <div className="space-y-1">
+            <Label htmlFor="confirm-master-password">Confirm password</Label>
+            <Input
+              id="confirm-master-password"
+              type="password"
+              value={confirmPassword}
+              onChange={(e) => setConfirmPassword(e.target.value)}
+            />
+            {mismatch && <p className="text-xs text-destructive">Passwords do not match</p>}
+          </div>
+          <Button onClick={handleSetPassword} disabled={!canSubmit} size="sm">
+            {busy ? "Encrypting..." : status?.encrypted ? "Change Password" : "Encrypt Store"}
+          </Button>
+        </CardContent>
+      </Card>
+
+      {status?.encrypted && (
+        <Card>
+          <CardHeader>
+            <CardTitle>Lock Now</CardTitle>
+            <CardDescription>
+              Remove decrypted profiles and snippets from memory until the
+              master password is entered again. Open connections stay open.
+            </CardDescription>
+          </CardHeader>
+          <CardContent>
+            <Button variant="outline" size="sm" onClick={handleLock}>
+              <Lock className="mr-2 size-4" />
+              Lock Store
+            </Button>
+          </CardContent>
+        </Card>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,88 @@
+/**
+ * Master password prompt shown at startup when the profile store is
+ * encrypted. Profiles and snippets are only loaded by the backend after a
+ * successful unlock, so the window reloads afterwards to pick them up.
+ */
+
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Lock } from "lucide-react";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import type { StoreLockStatus } from "@/types";
+
+export function StoreUnlockDialog() {
+  const [open, setOpen] = useState(false);
+  const [password, setPassword] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [unlocking, setUnlocking] = useState(false);
+
+  useEffect(() => {
+    invoke<StoreLockStatus>("get_store_lock_status")
+      .then((status) => setOpen(status.locked))
+      .catch(() => setOpen(false));
+  }, []);
+
+  const handleUnlock = async () => {
+    setUnlocking(true);
+    setError(null);
+    try {
+      await invoke("unlock_store", { password });
+      window.location.reload();
+    } catch (err) {
+      setError(typeof err === "string" ? err : (err as any)?.message || String(err));
+      setUnlocking(false);
+    }
+  };
+
+  return (
+    <Dialog open={open}>
+      <DialogContent
+        className="sm:max-w-md"
+        showCloseButton={false}
+        onEscapeKeyDown={(e) => e.preventDefault()}
+        onInteractOutside={(e) => e.preventDefault()}
+      >
+        <DialogHeader>
+          <DialogTitle className="flex items-center gap-2">
+            <Lock className="size-4" />
+            Unlock DB Hive
+          </DialogTitle>
+          <DialogDescription>
+            Your connection profiles and snippets are encrypted. Enter the
+            master password to load them.
+          </DialogDescription>
+        </DialogHeader>
+        <form
+          onSubmit={(e) => {
+            e.preventDefault();
+            if (password) handleUnlock();
+          }}
+          className="space-y-3"
+        >
+          <Input
+            type="password"
+            autoFocus
+            placeholder="Master password"
+            value={password}
+            onChange={(e) => setPassword(e.target.value)}
+          />
+          {error && <p className="text-sm text-destructive">{error}</p>}
+          <DialogFooter>
+            <Button type="submit" disabled={!password || unlocking}>
+              {unlocking ? "Unlocking..." : "Unlock"}
+            </Button>
+          </DialogFooter>
+        </form>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -64,6 +64,7 @@ import {
   QueryExecutionResult,
   ForeignKeyInfo,
   KeysetPageResult,
+  TriggerInfo,
 } from "@/types";
 import { Panel, PanelGroup, PanelResizeHandle } from "react-resizable-panels";
 import { RowJsonViewer } from "./RowJsonViewer";
@@ -225,6 +226,7 @@ export function TableInspector({
   const [filterRows, setFilterRows] = useState<FilterRow[]>([]);
   const [showFilters, setShowFilters] = useState(false);
   const [foreignKeys, setForeignKeys] = useState<ForeignKeyInfo[]>([]);
+  const [triggers, setTriggers] = useState<TriggerInfo[]>([]);
 
   // Map of FK column name -> referenced { schema, table, column }
   const fkByColumn = useMemo(() => {
@@ -460,6 +462,18 @@ export function TableInspector({
       } catch {
         setForeignKeys([]);
       }
+
+      // Best-effort trigger fetch so write side effects are visible
+      try {
+        const trg = await invoke<TriggerInfo[]>("get_triggers", {
+          connectionId,
+          schema,
+          table: tableName,
+        });
+        setTriggers(trg);
+      } catch {
+        setTriggers([]);
+      }
     } catch (err) {
       const errorMessage =
         typeof err === "string" ? err : (err as any)?.message || String(err);
@@ -1677,6 +1691,64 @@ export function TableInspector({
           </ScrollArea>
         </TabsContent>
 
+        {/* Triggers Tab */}
+        <TabsContent value="triggers" className="flex-1 m-0 overflow-hidden">
+          <ScrollArea className="h-full w-full">
+            {triggers.length > 0 ? (
+              <div className="min-w-max">
+                <Table className="[&_th]:border-r [&_th]:border-border [&_td]:border-r [&_td]:border-border">
+                  <TableHeader className="sticky top-0 bg-background z-10">
+                    <TableRow>
+                      <TableHead className="whitespace-nowrap">Trigger Name</TableHead>
+                      <TableHead className="whitespace-nowrap">Timing</TableHead>
+                      <TableHead className="whitespace-nowrap">Events</TableHead>
+                      <TableHead className="whitespace-nowrap">Definition</TableHead>
+                    </TableRow>
+                  </TableHeader>
+                  <TableBody>
+                    {triggers.map((trigger) => (
+                      <TableRow key={trigger.name} className="align-top">
+                        <TableCell className="font-medium">{trigger.name}</TableCell>
+                        <TableCell>
+                          <Badge variant="outline">{trigger.timing}</Badge>
+                        </TableCell>
+                        <TableCell>
+                          <div className="flex gap-1">
+                            {trigger.events.map((event) => (
+                              <Badge key={event} variant="secondary">
+                                {event}
+                              </Badge>
+                            ))}
+                          </div>
+                        </TableCell>
+                        <TableCell>
+                          <pre className="text-xs font-mono whitespace-pre-wrap max-w-[640px]">
+                            {trigger.definition || ""}
+                          </pre>
+                          {trigger.functionDefinition && (
+                            <>
+                              <div className="text-xs text-muted-foreground mt-2 mb-1">
+                                Executes {trigger.functionName}
+                              </div>
+                              <pre className="text-xs font-mono whitespace-pre-wrap max-w-[640px]">
+                                {trigger.functionDefinition}
+                              </pre>
+                            </>
+                          )}
+                        </TableCell>
+                      </TableRow>
+                    ))}
+                  </TableBody>
+                </Table>
+              </div>
+            ) : (
+              <div className="flex items-center justify-center h-full">
+                <p className="text-muted-foreground">No triggers found</p>
+              </div>
+            )}
+          </ScrollArea>
+        </TabsContent>
+
         {/* Footer: Tabs + Pagination in one bar */}
         <div className="flex items-center justify-between px-4 py-1.5 border-t bg-background shrink-0">
           <TabsList>
@@ -1687,6 +1759,9 @@ export function TableInspector({
             <TabsTrigger value="indexes">
               Indexes ({tableSchema.indexes.length})
             </TabsTrigger>
+            <TabsTrigger value="triggers">
+              Triggers ({triggers.length})
+            </TabsTrigger>
           </TabsList>
 
           {/* Pagination (only visible on Data tab) */}
@@ -60,7 +60,7 @@ export const ProcessList: FC<ProcessListProps> = ({
       setError(null);
     } catch (e) {
       const err = e as DbErrorShape;
-      if (err?.kind === "invalid_input") {
+      if (err?.kind === "invalidInput") {
         setUnsupported(err.message ?? "Not supported for this driver");
         setRows([]);
         setError(null);
@@ -86,7 +86,7 @@ export const ServerMetricsChart: FC<ServerMetricsChartProps> = ({
       });
     } catch (e) {
       const err = e as DbErrorShape;
-      if (err?.kind === "invalid_input") {
+      if (err?.kind === "invalidInput") {
         setUnsupported(err.message ?? "Not supported for this driver");
       } else {
         setError(err?.message ?? String(e));
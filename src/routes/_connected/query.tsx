@@ -215,7 +215,10 @@ function QueryPanelRoute() {
     });
   }, [tabIds, getTabState, createTabState, getAllTabStates, removeTabState]);
 
-  const handleExecuteQuery = async (sql: string): Promise<QueryExecutionResult> => {
+  const handleExecuteQuery = async (
+    sql: string,
+    tabId?: string
+  ): Promise<QueryExecutionResult> => {
     const confirmDestructive = settings?.query?.confirmDestructive ?? true;
     if (confirmDestructive) {
       const warnings = analyzeDestructive(sql);
@@ -236,6 +239,7 @@ function QueryPanelRoute() {
       const result = await invoke<QueryExecutionResult>("execute_query", {
         connectionId,
         sql,
+        tabId,
       });
       if (result.truncated) {
         toast.warning(
@@ -634,7 +638,7 @@ function QueryPanelRoute() {
                   connectionId={connectionId}
                   connectionProfile={connectionProfile}
                   currentDatabase={currentDatabase}
-                  onExecuteQuery={handleExecuteQuery}
+                  onExecuteQuery={(sql) => handleExecuteQuery(sql, tabId)}
                   pendingQuery={tabState.sql || null}
                   onPersistSql={(sql) => {
                     const existing = getTabState(tabId);
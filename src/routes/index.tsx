@@ -179,9 +179,11 @@ function HomeRoute() {
   // Handle connection error
   const handleConnectionError = (err: unknown, profile: ConnectionProfile) => {
     const { headline, detail, kind } = formatDbError(err);
+    // A rejected login is shown as such, not as an unreachable server
     const isConnectionError =
       kind === "connection" ||
-      (detail ?? headline).toLowerCase().includes("connection");
+      (kind !== "auth" &&
+        (detail ?? headline).toLowerCase().includes("connection"));
     if (isConnectionError) {
       setConnectionError({
         profileId: profile.id,
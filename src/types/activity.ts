@@ -56,6 +56,38 @@ export interface QueryLog {
   tags?: string[];
 }
 
+/**
+ * A query running, or waiting for a free slot, on a connection
+ */
+export interface RunningQuery {
+  /** Same as the ID of the query's log entry */
+  id: string;
+  /** Connection ID the query runs on */
+  connectionId: string;
+  /** Editor tab that ran the query, when known */
+  tabId: string | null;
+  /** SQL query text */
+  sql: string;
+  /** When the query was submitted (Unix seconds) */
+  startedAt: number;
+  /** Waiting for another query on the connection to finish */
+  queued: boolean;
+  /** Running in the connection's open transaction session */
+  inTransaction: boolean;
+}
+
+/**
+ * Interactive and background work on a connection
+ */
+export interface QueueDepth {
+  /** Interactive queries running */
+  interactive: number;
+  /** Background tasks (metadata, stats, exports) running, including deferred ones */
+  background: number;
+  /** Background tasks waiting for interactive queries to finish */
+  deferred: number;
+}
+
 /**
  * Performance metrics snapshot
  */
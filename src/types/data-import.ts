@@ -10,6 +10,16 @@ export interface ImportPreview {
   detected_types: string[];
   file_type: string;
   sheet_names: string[] | null;
+  /** Dialect the CSV was read with, detected unless overridden */
+  dialect: CsvDialect | null;
+}
+
+export interface CsvDialect {
+  delimiter: string;
+  quote: string;
+  /** Character escaping quotes; null when quotes are doubled */
+  escape: string | null;
+  has_header: boolean;
 }
 
 export interface ColumnMapping {
@@ -29,8 +39,16 @@ export interface DataImportOptions {
   truncate_before: boolean;
   batch_size: number;
   delimiter: string | null;
+  quote?: string | null;
+  escape?: string | null;
   sheet_name: string | null;
   first_row_is_header: boolean;
+  /** Insert inside transactions so a failed batch is rolled back */
+  use_transaction?: boolean;
+  /** With use_transaction, commit after each batch instead of once at the end */
+  commit_each_batch?: boolean;
+  /** Keep importing after a row fails (default true) */
+  continue_on_error?: boolean;
 }
 
 export interface ImportResult {
@@ -38,6 +56,25 @@ export interface ImportResult {
   rows_failed: number;
   errors: string[];
   success: boolean;
+  /** Transactions committed; 0 without use_transaction */
+  batches_committed?: number;
+  /** Whether cancelImport stopped the import */
+  cancelled?: boolean;
+  /** Mapped target columns left out because the database generates them */
+  skippedGenerated?: string[];
+}
+
+/** Payload of the "import-progress" event */
+export interface ImportProgressEvent {
+  importId: string;
+  /** Rows run so far, including failed and not yet committed ones */
+  rowsProcessed: number;
+  rowsFailed: number;
+  totalRows: number;
+  /** Estimated time left in milliseconds */
+  etaMs: number | null;
+  done: boolean;
+  cancelled: boolean;
 }
 
 export interface TableColumn {
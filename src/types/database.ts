@@ -57,6 +57,20 @@ export interface SshConfig {
   localPort: number;
 }
 
+/**
+ * SQLite settings applied on connect
+ */
+export interface SqliteOptions {
+  /** Milliseconds to wait for another connection's lock before failing */
+  busyTimeoutMs: number;
+
+  /** Use write-ahead logging (the file must be on a local disk) */
+  wal: boolean;
+
+  /** Enforce foreign key constraints */
+  foreignKeys: boolean;
+}
+
 /**
  * Connection profile
  *
@@ -115,6 +129,21 @@ export interface ConnectionProfile {
   /** Notes/description about this connection */
   description?: string | null;
 
+  /** Seconds of idleness before a keepalive ping is sent (unset or 0 disables it) */
+  keepaliveIntervalSecs?: number | null;
+
+  /** Seconds to wait for the server when connecting (unset uses 30) */
+  connectTimeoutSecs?: number | null;
+
+  /** Client name shown in the server's session list (unset uses "DB-Hive"); the connection ID is appended */
+  applicationName?: string | null;
+
+  /** Schema unqualified names resolve to (Postgres search_path, MySQL database) */
+  defaultSchema?: string | null;
+
+  /** Pragmas set when opening a SQLite database */
+  sqlite?: SqliteOptions;
+
   /** Created timestamp (Unix timestamp in seconds) */
   createdAt: number;
 
@@ -122,6 +151,14 @@ export interface ConnectionProfile {
   updatedAt: number;
 }
 
+/**
+ * Profile parsed from a connection URI by `parse_connection_uri`; not yet saved
+ */
+export interface ParsedConnectionUri extends ConnectionProfile {
+  /** Password given in the URI, to be saved with `save_password` */
+  password?: string;
+}
+
 /**
  * Connection folder for organization
  */
@@ -148,8 +185,44 @@ export interface ConnectionStats {
   recentCount: number;
   /** Number of folders */
   folderCount: number;
-  /** Most frequently used connection */
+  /** Connects recorded across all connections */
+  totalConnects: number;
+  /** Most frequently used connection, once any has been used */
   mostUsedConnection?: ConnectionProfile;
+  /** Most recently used connection, once any has been used */
+  mostRecentConnection?: ConnectionProfile;
+}
+
+/**
+ * Outcome of testing one saved profile with `test_all_connections`
+ */
+export interface ProfileTestResult {
+  /** Profile ID */
+  id: string;
+  /** Profile name */
+  name: string;
+  /** `Connected`, an `Error`, or `Disconnected` when the profile was skipped */
+  status: ConnectionStatus;
+  /** Time to connect and ping, for reachable profiles */
+  latencyMs?: number | null;
+  /** Why the test failed or was skipped */
+  error?: string | null;
+}
+
+/**
+ * Round-trip latency of an active connection, from `ping_connection`
+ */
+export interface ConnectionLatency {
+  /** Connection ID */
+  connectionId: string;
+  /** Latest ping in milliseconds */
+  latencyMs: number;
+  /** Mean of the recorded pings in milliseconds */
+  averageMs: number;
+  /** Recorded pings, oldest first (up to 30) */
+  samples: number[];
+  /** Latest ping was far slower than the ones before it */
+  degraded: boolean;
 }
 
 /**
@@ -160,6 +233,55 @@ export type ConnectionStatus =
   | 'Disconnected'
   | { Error: string };
 
+/**
+ * Syntax the connected server supports, derived from its engine and version
+ */
+export interface ServerFeatures {
+  returning: boolean;
+  ilike: boolean;
+  windowFunctions: boolean;
+  cte: boolean;
+  stringAgg: boolean;
+  upsert: boolean;
+  json: boolean;
+}
+
+/**
+ * Server version and settings (`get_server_info`)
+ */
+export interface ServerInfo {
+  /** Version string as the server reports it */
+  version: string;
+  majorVersion: number | null;
+  minorVersion: number | null;
+  charset: string | null;
+  collation: string | null;
+  timezone: string | null;
+  maxConnections: number | null;
+  features: ServerFeatures;
+}
+
+/**
+ * Result of `test_connection_command`
+ */
+export interface ConnectionTestResult {
+  status: ConnectionStatus;
+  /** Missing when the server didn't report its version */
+  serverInfo: ServerInfo | null;
+}
+
+/**
+ * Payload of the `pg-notification` event, emitted for each NOTIFY received
+ * on a channel subscribed with `listen_channel`
+ */
+export interface PgNotificationEvent {
+  connectionId: string;
+  channel: string;
+  payload: string;
+  /** Backend process that sent the notification */
+  processId: number;
+}
+
 /**
  * Database error
  *
@@ -171,15 +293,41 @@ export interface DbError {
     | 'query'
     | 'auth'
     | 'timeout'
-    | 'invalid_input'
-    | 'not_found'
+    | 'invalidInput'
+    | 'notFound'
     | 'internal'
     | 'credential'
     | 'import'
-    | 'ai';
+    | 'ai'
+    | 'aiRateLimited'
+    | 'aiAuth'
+    | 'aiNetwork'
+    | 'confirmationRequired'
+    | 'storeLocked'
+    | 'duplicateProfile';
   message: string;
+  /** SQLSTATE code, for errors reported by the database (Postgres, MySQL) */
+  sqlstate?: string;
+  /** Vendor error number (MySQL, SQL Server) or SQLite extended result code */
+  errorCode?: number;
+  /** What the error code means, for the common ones */
+  category?: DbErrorCategory;
+  /** The server's DETAIL and HINT text */
+  details?: string;
+  /** ID of the matching profile, for `duplicateProfile` errors */
+  existingProfileId?: string;
 }
 
+/**
+ * Category of a database error code (see `classify_db_error` in
+ * `models/error.rs`)
+ */
+export type DbErrorCategory =
+  | 'uniqueViolation'
+  | 'foreignKeyViolation'
+  | 'syntaxError'
+  | 'permissionDenied';
+
 /**
  * Get default port for a database driver
  */
@@ -242,9 +390,12 @@ export function getDriverDisplayName(driver: DbDriver): string {
  * Represents the result of executing a SQL query
  */
 export interface QueryExecutionResult {
-  /** Column names in the result set */
+  /** Column names in the result set; a repeated name gets a `_2`, `_3`, ... suffix */
   columns: string[];
 
+  /** Column names as the database returned them, parallel to `columns` */
+  originalColumns?: string[];
+
   /** Row data as array of arrays */
   rows: any[][];
 
@@ -259,6 +410,55 @@ export interface QueryExecutionResult {
 
   /** True when the result set was capped at the backend row limit (50k) and `rows` is partial */
   truncated?: boolean;
+
+  /** True when the default row limit was added to the query and reached; re-run with `unlimited: true` for all rows */
+  limited?: boolean;
+
+  /** True when the result was served from the query result cache (`useCache: true`) */
+  fromCache?: boolean;
+}
+
+/**
+ * Display formatting for `execute_query` (`format`); every value in the
+ * result becomes a string, except NULL without a `nullSentinel`
+ */
+export interface ResultFormatOptions {
+  /** chrono `strftime` pattern for date/time values */
+  dateFormat?: string | null;
+  /** Digits after the decimal point for non-integer numbers */
+  decimalPlaces?: number | null;
+  /** Group thousands with `,` (default false: numbers stay raw) */
+  thousandsSeparator?: boolean;
+  /** Text shown for NULL */
+  nullSentinel?: string | null;
+}
+
+/**
+ * Options for `run_mongo_aggregate`
+ */
+export interface AggregateOptions {
+  /** Expand nested documents into `parent.child` columns instead of JSON strings */
+  expandNested?: boolean;
+
+  /** Most documents to return (capped at the backend row limit) */
+  limit?: number;
+}
+
+/**
+ * Result of `preview_affected_rows`
+ */
+export interface AffectedRowsPreview {
+  /** Rows the UPDATE/DELETE would affect */
+  rows: number;
+
+  /** `count` ran a rewritten SELECT COUNT(*); `rollback` ran the statement and rolled it back */
+  method: 'count' | 'rollback';
+
+  /** The count query, when counted */
+  countSql: string | null;
+
+  /** Why the statement had to be run and rolled back */
+  reason: string | null;
 }
 
 /**
@@ -287,6 +487,90 @@ export interface KeysetPageResult {
   totalFetched: number;
 }
 
+/**
+ * Comparison applied by a structured column filter
+ */
+export type FilterOperator =
+  | 'eq'
+  | 'neq'
+  | 'gt'
+  | 'lt'
+  | 'like'
+  | 'in'
+  | 'is_null'
+  | 'between';
+
+/**
+ * A single condition on one column
+ *
+ * Compiled by the backend into a parameterized predicate; values are always
+ * bound, never interpolated into the SQL.
+ */
+export interface ColumnFilter {
+  /** Column name, unquoted */
+  column: string;
+
+  operator: FilterOperator;
+
+  /** Operand of eq, neq, gt, lt and like */
+  value?: unknown;
+
+  /** Operands of in (one or more) and between (exactly two) */
+  values?: unknown[];
+}
+
+/**
+ * Filters combined with AND or OR; groups can be nested
+ */
+export interface FilterGroup {
+  /** How members are combined (default: 'and') */
+  join?: 'and' | 'or';
+
+  filters: FilterNode[];
+}
+
+/**
+ * A column filter or a nested group
+ */
+export type FilterNode = ColumnFilter | FilterGroup;
+
+/**
+ * One sort key of a table read
+ *
+ * The column must exist in the table; NULL placement is emulated on engines
+ * without `NULLS FIRST/LAST` (MySQL, SQL Server).
+ */
+export interface SortSpec {
+  /** Column name, unquoted */
+  column: string;
+
+  /** Sort direction (default: 'asc') */
+  direction?: 'asc' | 'desc';
+
+  /** Explicit NULL placement; the engine's default when omitted */
+  nulls?: 'first' | 'last' | null;
+}
+
+/**
+ * One page of rows read by `fetch_table_rows`
+ */
+export interface TableRowsPage {
+  /** Column names of the table */
+  columns: string[];
+
+  /** Rows of this page */
+  rows: unknown[][];
+
+  /** Whether rows exist beyond this page */
+  hasMore: boolean;
+
+  /** Generated SELECT statement, with placeholders for the filter values */
+  sql: string;
+
+  /** Query execution time in milliseconds */
+  executionTime: number;
+}
+
 /**
  * Query Plan Node
  *
@@ -468,6 +752,15 @@ export interface ColumnInfo {
 
   /** Whether this column is auto-increment/serial (MySQL AUTO_INCREMENT, PostgreSQL SERIAL) */
   isAutoIncrement: boolean;
+
+  /** Column comment (PostgreSQL, MySQL and SQL Server) */
+  comment?: string | null;
+
+  /** Whether the column is generated/computed and so can't be written */
+  isGenerated?: boolean;
+
+  /** Expression a generated column is computed from */
+  generationExpression?: string | null;
 }
 
 /**
@@ -524,6 +817,116 @@ export interface ForeignKeyInfo {
   onUpdate?: string | null;
 }
 
+/**
+ * Table node of a `SchemaGraph`
+ */
+export interface GraphTable {
+  name: string;
+  /** "TABLE", "VIEW", etc., as reported by the driver */
+  tableType: string;
+  columns: ColumnInfo[];
+  /** Primary key columns, in table order */
+  primaryKey: string[];
+  /** Only foreign key columns, from at least two foreign keys */
+  isJoinTable: boolean;
+}
+
+/**
+ * Foreign key edge of a `SchemaGraph`
+ */
+export interface GraphEdge {
+  /** Constraint name */
+  name: string;
+  sourceTable: string;
+  sourceColumns: string[];
+  /** Differs from the graph's schema for cross-schema references */
+  targetSchema: string;
+  targetTable: string;
+  targetColumns: string[];
+  selfReference: boolean;
+  onDelete?: string | null;
+  onUpdate?: string | null;
+}
+
+/**
+ * Tables and foreign keys of one schema, for the ERD view
+ */
+export interface SchemaGraph {
+  schema: string;
+  tables: GraphTable[];
+  edges: GraphEdge[];
+}
+
+/**
+ * A column matched by `find_columns`
+ */
+export interface ColumnMatch {
+  name: string;
+  dataType: string;
+  /** 1-based position of the column in the table */
+  ordinalPosition: number;
+  nullable: boolean;
+  isPrimaryKey: boolean;
+}
+
+/**
+ * Foreign keys involving one column (`get_column_references`)
+ */
+export interface ColumnReferences {
+  /** Foreign keys on the column, pointing to other tables */
+  references: ForeignKeyInfo[];
+  /** Foreign keys pointing to the column */
+  referencedBy: ForeignKeyInfo[];
+}
+
+/**
+ * One cached level of the schema tree
+ */
+export interface CachedLevelStatus {
+  kind: "databases" | "schemas" | "tables" | "tableSchema";
+  /** Database, schema or "schema.table"; null for the database list */
+  name: string | null;
+  /** When the level was fetched (RFC 3339) */
+  fetchedAt: string;
+  /** Whether the next request will go to the database */
+  stale: boolean;
+  itemCount: number;
+}
+
+/**
+ * What a connection's metadata cache holds (`get_cache_status`)
+ */
+export interface MetadataCacheStatus {
+  /** Whether the whole database is cached (autocomplete, search) */
+  complete: boolean;
+  levels: CachedLevelStatus[];
+}
+
+/**
+ * Kind of table constraint
+ */
+export type ConstraintKind = "primaryKey" | "unique" | "check";
+
+/**
+ * Primary key, unique or check constraint on a table
+ */
+export interface ConstraintInfo {
+  /** Constraint name */
+  name: string;
+
+  /** Constraint kind */
+  kind: ConstraintKind;
+
+  /** Constrained columns (in order) */
+  columns: string[];
+
+  /** Constraint definition for checks, e.g. `CHECK ((price > 0))` */
+  definition?: string | null;
+
+  /** Index enforcing a primary key or unique constraint */
+  indexName?: string | null;
+}
+
 /**
  * Complete table schema
  *
@@ -538,6 +941,12 @@ export interface TableSchema {
 
   /** Indexes defined on the table */
   indexes: IndexInfo[];
+
+  /** Primary key, unique and check constraints (PostgreSQL only for now) */
+  constraints?: ConstraintInfo[];
+
+  /** Table comment (PostgreSQL, MySQL and SQL Server) */
+  comment?: string | null;
 }
 
 /**
@@ -558,6 +967,148 @@ export interface ProcedureInfo {
   argumentSignature: string;
 }
 
+/**
+ * Trigger defined on a table
+ */
+export interface TriggerInfo {
+  /** Trigger name */
+  name: string;
+  /** "BEFORE", "AFTER" or "INSTEAD OF" */
+  timing: string;
+  /** Events that fire the trigger, e.g. ["INSERT", "UPDATE"] */
+  events: string[];
+  /** CREATE TRIGGER statement, or the trigger body on MySQL */
+  definition?: string | null;
+  /** Function the trigger executes (PostgreSQL only) */
+  functionName?: string | null;
+  /** Source of that function (PostgreSQL only) */
+  functionDefinition?: string | null;
+}
+
+/**
+ * Encryption state of the profile and snippet store
+ */
+export interface StoreLockStatus {
+  /** A master password is set */
+  encrypted: boolean;
+  /** A master password is set and has not been entered this session */
+  locked: boolean;
+  /** No master password is set but profiles or snippets exist on disk */
+  unencryptedData: boolean;
+}
+
+/**
+ * Sequence, or a MySQL table's AUTO_INCREMENT counter
+ */
+export interface SequenceInfo {
+  /** Schema (database for MySQL) */
+  schema: string;
+  /** Sequence name (the table name for MySQL) */
+  name: string;
+  /** Last value handed out, if the sequence has been used */
+  currentValue?: number | null;
+  /** Step between generated values */
+  increment: number;
+  /** Lower bound */
+  minValue?: number | null;
+  /** Upper bound */
+  maxValue?: number | null;
+  /** Table owning the sequence (serial / identity / AUTO_INCREMENT column) */
+  ownedByTable?: string | null;
+  /** Column owning the sequence */
+  ownedByColumn?: string | null;
+}
+
+/**
+ * Column profile returned by `profile_column`
+ */
+export interface ColumnProfile {
+  /** Rows profiled: all rows of the table, or the sample */
+  rowCount: number;
+  /** Only a random sample of the rows was profiled */
+  sampled: boolean;
+  nullCount: number;
+  /** nullCount / rowCount, 0 for an empty table */
+  nullRatio: number;
+  distinctCount: number | null;
+  min: unknown;
+  max: unknown;
+  /** Mean, for numeric columns */
+  mean: number | null;
+  /** Shortest value in characters, for text columns */
+  minLength: number | null;
+  /** Longest value in characters, for text columns */
+  maxLength: number | null;
+  /** Most frequent non-NULL values, most frequent first */
+  topValues: { value: unknown; count: number }[];
+  /** Statistics the database couldn't compute for this column type */
+  skipped: {
+    statistic: 'distinctCount' | 'minMax' | 'mean' | 'length' | 'topValues';
+    reason: string;
+  }[];
+}
+
+/**
+ * Estimated distinct values of one column (`estimate_cardinality`)
+ */
+export interface ColumnCardinality {
+  column: string;
+  /** Approximate distinct non-NULL values; null if they couldn't be counted */
+  distinctCount: number | null;
+  /** distinctCount / totalRows: near 1 for a unique column, near 0 for few values */
+  selectivity: number | null;
+  /** Engine statistics, or COUNT(DISTINCT) over a sample of the rows */
+  source: 'statistics' | 'sample';
+}
+
+/**
+ * Result of `estimate_cardinality`
+ */
+export interface CardinalityEstimate {
+  /** Rows in the table */
+  totalRows: number;
+  /** totalRows comes from statistics rather than a COUNT(*) */
+  totalRowsEstimated: boolean;
+  /** One entry per requested column, in request order */
+  columns: ColumnCardinality[];
+}
+
+/**
+ * A SQLite pragma and its value (`sqlite_get_pragmas`, `sqlite_set_pragma`)
+ */
+export interface PragmaValue {
+  name: string;
+  value: unknown;
+  /** Whether `sqlite_set_pragma` accepts this pragma */
+  writable: boolean;
+}
+
+/**
+ * Result of `sqlite_integrity_check`
+ */
+export interface IntegrityCheckResult {
+  ok: boolean;
+  /** Problems found; empty when `ok` */
+  issues: string[];
+}
+
+/**
+ * Result of `sqlite_vacuum`
+ */
+export interface VacuumResult {
+  elapsedMs: number;
+  /** Database size in bytes before and after */
+  sizeBefore: number;
+  sizeAfter: number;
+}
+
+/** A database attached to a SQLite connection */
+export interface AttachedDatabase {
+  /** Schema name the database is attached as */
+  alias: string;
+  filePath: string;
+}
+
 /**
  * SQL Export Options
  *
@@ -576,7 +1127,7 @@ export interface SqlExportOptions {
   /** Filter by specific tables (empty array = all tables) */
   tables: string[];
 
-  /** Schema to export from (PostgreSQL/MySQL). Defaults to "public" if not specified */
+  /** Schema to export from. Defaults to the connection's default schema, else the driver's (public, the MySQL database, dbo or main) */
   schema?: string | null;
 }
 
@@ -603,3 +1154,173 @@ export interface SqlImportResult {
   /** Absolute path to the error log file, or null if there were no errors */
   logFile: string | null;
 }
+
+/**
+ * Output format for copying result rows (see `format_rows_as`)
+ */
+export type RowCopyFormat = 'InsertStatements' | 'Markdown' | 'Json' | 'Csv';
+
+/**
+ * Statement scaffold generated from a table's columns (see `generate_query_template`)
+ */
+export type QueryTemplateKind = 'Select' | 'Insert' | 'Update' | 'Delete';
+
+/**
+ * Category of a query plan warning (see `analyze_query_plan`)
+ */
+export type PlanWarningKind =
+  | 'sequentialScan'
+  | 'missingIndex'
+  | 'nestedLoop'
+  | 'largeRowEstimate';
+
+/**
+ * Red flag found in a query plan. Based on planner estimates, so the
+ * suggestion is a heuristic hint rather than a verified fix.
+ */
+export interface PlanWarning {
+  kind: PlanWarningKind;
+  /** Plan step the warning is about */
+  nodeType: string;
+  /** Table involved, if any */
+  relation?: string | null;
+  /** The estimate that triggered the warning */
+  estimatedRows: number;
+  message: string;
+  suggestion?: string | null;
+}
+
+/**
+ * One step of a normalized plan (PostgreSQL or MySQL)
+ */
+export interface PlanNode {
+  /** Operation name as the server reports it (e.g. "Seq Scan") */
+  nodeType: string;
+  kind: 'fullScan' | 'indexScan' | 'nestedLoop' | 'other';
+  relation: string | null;
+  alias: string | null;
+  indexName: string | null;
+  filter: string | null;
+  estimatedRows: number;
+  examinedRows: number | null;
+  tableRows: number | null;
+  totalCost: number | null;
+  children: PlanNode[];
+}
+
+/**
+ * A plan captured by `record_query_plan`
+ */
+export interface RecordedPlan {
+  fingerprint: string;
+  connectionId: string;
+  sql: string;
+  /** RFC 3339 timestamp */
+  recordedAt: string;
+  plan: PlanNode;
+}
+
+/**
+ * A plan step that differs between two plans (see `compare_query_plans`)
+ */
+export interface PlanNodeChange {
+  /** Child indexes from the root, which is "0" */
+  path: string;
+  relation: string | null;
+  /** Step type before; null if the step was added */
+  before: string | null;
+  /** Step type after; null if the step was removed */
+  after: string | null;
+  indexBefore: string | null;
+  indexAfter: string | null;
+  costBefore: number | null;
+  costAfter: number | null;
+}
+
+/**
+ * Differences between an earlier and the latest plan of a query
+ */
+export interface PlanComparison {
+  fingerprint: string;
+  beforeRecordedAt: string;
+  afterRecordedAt: string;
+  planChanged: boolean;
+  costBefore: number | null;
+  costAfter: number | null;
+  costDelta: number | null;
+  /** Cost change relative to before, in percent */
+  costChangePct: number | null;
+  changes: PlanNodeChange[];
+}
+
+/**
+ * Binary cell value (bytea, BLOB, varbinary, ...)
+ *
+ * Result grids get at most the first 64 KiB (`truncated` is then set);
+ * `fetch_cell_blob` writes the full value to a file.
+ */
+export interface BlobValue {
+  /** Base64-encoded bytes */
+  __blob__: string;
+
+  /** Full length of the value in bytes */
+  size: number;
+
+  /** Only the first bytes of the value are included */
+  truncated?: boolean;
+}
+
+export function isBlobValue(value: unknown): value is BlobValue {
+  return (
+    typeof value === 'object' &&
+    value !== null &&
+    typeof (value as BlobValue).__blob__ === 'string'
+  );
+}
+
+/** Short label for a binary value, e.g. `<binary 12.0 KB>` */
+export function formatBlobLabel(value: BlobValue): string {
+  const { size } = value;
+  if (size < 1024) return `<binary ${size} B>`;
+  if (size < 1024 * 1024) return `<binary ${(size / 1024).toFixed(1)} KB>`;
+  return `<binary ${(size / (1024 * 1024)).toFixed(1)} MB>`;
+}
+
+/**
+ * PostGIS `geometry`/`geography` value, as a GeoJSON geometry
+ *
+ * `crs` is only present for SRIDs other than 4326. Geometries GeoJSON can't
+ * represent (curves, TINs) arrive as a hex EWKB string instead.
+ */
+export interface GeoJsonGeometry {
+  type:
+    | 'Point'
+    | 'LineString'
+    | 'Polygon'
+    | 'MultiPoint'
+    | 'MultiLineString'
+    | 'MultiPolygon'
+    | 'GeometryCollection';
+  coordinates?: unknown[];
+  geometries?: GeoJsonGeometry[];
+  crs?: { type: 'name'; properties: { name: string } };
+}
+
+const GEOJSON_TYPES = new Set([
+  'Point',
+  'LineString',
+  'Polygon',
+  'MultiPoint',
+  'MultiLineString',
+  'MultiPolygon',
+  'GeometryCollection',
+]);
+
+export function isGeoJsonGeometry(value: unknown): value is GeoJsonGeometry {
+  if (typeof value !== 'object' || value === null) return false;
+  const { type, coordinates, geometries } = value as GeoJsonGeometry;
+  return (
+    GEOJSON_TYPES.has(type) &&
+    (Array.isArray(coordinates) || Array.isArray(geometries))
+  );
+}
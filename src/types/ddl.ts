@@ -224,6 +224,32 @@ export interface DdlResult {
   message: string;
 }
 
+/**
+ * Object that refers to a table by name
+ */
+export interface TableDependent {
+  /** "view", "foreignKey" or "sequence" */
+  kind: string;
+  /** Schema of the dependent object */
+  schema: string;
+  /** Name of the dependent object */
+  name: string;
+  /** What the rename does to it */
+  note: string;
+}
+
+/**
+ * Result of renaming a table, or of previewing the rename
+ */
+export interface RenameTableResult {
+  /** SQL statement(s) executed (or to be executed) */
+  sql: string[];
+  /** Views, foreign keys and sequences referring to the old name */
+  dependents: TableDependent[];
+  /** Success message */
+  message: string;
+}
+
 /**
  * Helper functions for creating column types
  */
@@ -6,6 +6,8 @@
  * between frontend and backend.
  */
 
+import type { DbDriver } from './database';
+
 /**
  * Query history record
  *
@@ -64,6 +66,15 @@ export interface QuerySnippet {
   /** Optional tags for categorization */
   tags?: string[];
 
+  /** Declared defaults and descriptions for `{{name}}` placeholders */
+  variables?: SnippetVariable[];
+
+  /** Connection (profile) ID the snippet is scoped to; omitted for global snippets */
+  connectionId?: string;
+
+  /** Driver the snippet is limited to; omitted for any driver */
+  driver?: DbDriver;
+
   /** ISO 8601 timestamp of creation */
   createdAt: string;
 
@@ -71,6 +82,107 @@ export interface QuerySnippet {
   updatedAt: string;
 }
 
+/**
+ * A `{{name}}` placeholder in a snippet's query
+ */
+export interface SnippetVariable {
+  /** Placeholder name, without braces */
+  name: string;
+
+  /** Value pre-filled in the prompt and used when none is given */
+  defaultValue?: string;
+
+  /** What the value is for, shown in the prompt */
+  description?: string;
+}
+
+/**
+ * Snippet returned by `get_snippet`, with the variables its query uses
+ */
+export interface SnippetDetails extends QuerySnippet {
+  /** Placeholders found in the query, with any declared defaults */
+  detectedVariables: SnippetVariable[];
+}
+
+/**
+ * Snippet rewritten for `execute_parameterized_query`
+ */
+export interface ParameterizedSnippet {
+  /** Query with each `{{name}}` replaced by a `:name` parameter */
+  sql: string;
+
+  /** Values to bind, by parameter name */
+  params: Record<string, string>;
+}
+
+/**
+ * What `import_snippets` does with a snippet named like an existing one
+ */
+export type SnippetConflictStrategy = 'skip' | 'overwrite' | 'rename';
+
+/**
+ * Outcome of `import_snippets`
+ */
+export interface SnippetImportReport {
+  /** Snippets added as new snippets (including renamed ones) */
+  imported: number;
+
+  /** Existing snippets updated from imported ones */
+  overwritten: number;
+
+  /** Imported snippets given a new name because of a conflict */
+  renamed: number;
+
+  /** Imported snippets ignored because of a conflict */
+  skipped: number;
+}
+
+/**
+ * What happens to scheduled runs missed while the app was closed
+ */
+export type MissedRunPolicy = 'skip' | 'runOnce';
+
+/**
+ * A query run on a cron schedule while the app is open
+ */
+export interface QuerySchedule {
+  /** Unique identifier (UUID) */
+  id: string;
+
+  /** User-provided name */
+  name: string;
+
+  /** Connection (profile) ID the query runs against */
+  connectionId: string;
+
+  /** SQL to run, when not running a snippet */
+  sql?: string;
+
+  /** Snippet to run; its current query is used at each run */
+  snippetId?: string;
+
+  /** Cron expression in UTC, with five fields (weekdays 0-6 from Sunday) or six with leading seconds (weekdays 1-7) */
+  cronExpr: string;
+
+  /** Handling of runs missed while the app was closed */
+  missedRuns: MissedRunPolicy;
+
+  /** SQL confirmed for a destructive query on production; runs fail if the query no longer matches */
+  confirmedSql?: string;
+
+  /** ISO 8601 timestamp of creation */
+  createdAt: string;
+
+  /** Next time the schedule fires; omitted if it has no future times */
+  nextRunAt?: string;
+
+  /** When the schedule last ran */
+  lastRunAt?: string;
+
+  /** Error of the last run, if it failed */
+  lastError?: string;
+}
+
 /**
  * Folder for organizing query snippets into a tree
  */
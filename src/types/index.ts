@@ -8,9 +8,19 @@ export type {
   SshConfig,
   ConnectionProfile,
   ConnectionStatus,
+  ConnectionTestResult,
+  ServerInfo,
+  ServerFeatures,
+  PgNotificationEvent,
   DbError,
   QueryExecutionResult,
   KeysetPageResult,
+  FilterOperator,
+  ColumnFilter,
+  FilterGroup,
+  FilterNode,
+  SortSpec,
+  TableRowsPage,
   QueryError,
   DatabaseInfo,
   SchemaInfo,
@@ -19,9 +29,23 @@ export type {
   IndexInfo,
   ForeignKeyInfo,
   TableSchema,
+  TriggerInfo,
+  SequenceInfo,
+  ColumnProfile,
+  ColumnCardinality,
+  CardinalityEstimate,
+  PragmaValue,
+  IntegrityCheckResult,
+  VacuumResult,
+  AttachedDatabase,
+  StoreLockStatus,
   SqlExportOptions,
   SqlImportOptions,
   SqlImportResult,
+  RowCopyFormat,
+  QueryTemplateKind,
+  PlanWarning,
+  PlanWarningKind,
 } from './database';
 
 export { getDefaultPort, getDriverDisplayName } from './database';
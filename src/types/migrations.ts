@@ -1,3 +1,5 @@
+import type { DbDriver } from "./database";
+
 /**
  * Schema migration types. Mirrors src-tauri/src/migrations/diff.rs and
  * src-tauri/src/commands/migrations.rs.
@@ -66,6 +68,26 @@ export interface SchemaDiff {
   addedTables: TableSchema[];
   removedTables: TableSchema[];
   modifiedTables: TableDiff[];
+  sourceDriver?: DbDriver | null;
+  targetDriver?: DbDriver | null;
+  /** Caveats of a cross-dialect comparison */
+  warnings?: string[];
+}
+
+/** One side of `compare_schemas` */
+export interface SchemaRef {
+  connectionId: string;
+  schema: string;
+}
+
+/** Result of `generate_migration_ddl` */
+export interface MigrationDdl {
+  /** Non-destructive statements, in execution order */
+  statements: string[];
+  /** Column and table drops, run after `statements` only on opt-in */
+  destructiveStatements: string[];
+  /** Lossy type mappings and other caveats */
+  warnings: string[];
 }
 
 export interface ApplyResult {
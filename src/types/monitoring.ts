@@ -17,6 +17,22 @@ export interface ActiveQuery {
   durationMs: number | null;
 }
 
+/**
+ * Client session returned by `get_server_sessions`; terminate one with
+ * `kill_session`.
+ */
+export interface ServerSession {
+  sessionId: number;
+  user: string | null;
+  database: string | null;
+  clientAddr: string | null;
+  state: string | null;
+  queryText: string | null;
+  durationMs: number | null;
+  /** The session the listing was read from (DB Hive's own) */
+  isCurrent: boolean;
+}
+
 export interface ServerStats {
   numericConnections: number;
   activeConnections: number;
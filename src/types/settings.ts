@@ -9,6 +9,8 @@
  * Complete application settings
  */
 export interface AppSettings {
+  /** Settings layout version; the backend fills it in when saving */
+  schemaVersion?: number;
   general: GeneralSettings;
   theme: ThemeSettings;
   query: QuerySettings;
@@ -111,6 +113,15 @@ export interface QuerySettings {
 
   /** Format SQL automatically before execution */
   autoFormatSql: boolean;
+
+  /** LIMIT added to plain SELECTs that don't limit themselves (0 = unlimited) */
+  defaultRowLimit?: number;
+
+  /** Return Postgres numeric values as JSON numbers instead of exact strings (takes effect on reconnect) */
+  numericAsNumber?: boolean;
+
+  /** Queries one connection runs at once; the rest wait (open transactions don't count) */
+  maxConcurrentQueries?: number;
 }
 
 /**
@@ -180,6 +191,9 @@ export const defaultSettings: AppSettings = {
     autoSaveHistory: true,
     maxHistoryEntries: 500,
     autoFormatSql: false,
+    defaultRowLimit: 1000,
+    numericAsNumber: false,
+    maxConcurrentQueries: 4,
   },
   shortcuts: {
     executeQuery: "Ctrl+Enter",
@@ -4,7 +4,8 @@
  * The Rust backend serializes `DbError` over IPC as `{ kind, message }`
  * (see `src-tauri/src/models/error.rs`), where `message` is the Display
  * output — a human prefix plus the raw driver error, e.g.
- * `"Connection failed: connection refused (os error 61)"`.
+ * `"Connection failed: connection refused (os error 61)"`. Errors reported
+ * by the database server also carry `sqlstate` and optional `details`.
  *
  * Tauri `invoke()` rejections are NOT guaranteed to be that shape: commands
  * returning `Result<T, String>` reject with a plain string, and frontend
@@ -19,12 +20,18 @@ export type DbErrorKind =
   | "query"
   | "auth"
   | "timeout"
-  | "invalid_input"
-  | "not_found"
+  | "invalidInput"
+  | "notFound"
   | "internal"
   | "credential"
   | "import"
-  | "ai";
+  | "ai"
+  | "aiRateLimited"
+  | "aiAuth"
+  | "aiNetwork"
+  | "confirmationRequired"
+  | "storeLocked"
+  | "duplicateProfile";
 
 export interface FormattedDbError {
   /** Short, human-friendly summary suitable for a toast title or heading. */
@@ -36,6 +43,12 @@ export interface FormattedDbError {
   detail?: string;
   /** The structured `DbError.kind` when the error had one. */
   kind?: DbErrorKind | string;
+  /** SQLSTATE code of a database error. */
+  sqlstate?: string;
+  /** Vendor error number of a database error. */
+  errorCode?: number;
+  /** Category of a database error's code, for the common ones. */
+  category?: string;
 }
 
 /** Fixed headlines per structured error kind. */
@@ -43,16 +56,21 @@ const KIND_HEADLINES: Record<string, string> = {
   connection: "Could not connect to the database",
   auth: "Authentication failed — check username and password",
   timeout: "The operation timed out",
-  not_found: "The requested resource was not found",
+  notFound: "The requested resource was not found",
   internal: "An internal error occurred",
   credential: "Could not access saved credentials",
   import: "Import failed",
   ai: "AI request failed",
+  aiRateLimited: "The AI provider is rate limiting requests",
+  aiAuth: "The AI provider rejected the API key",
+  aiNetwork: "Could not reach the AI provider",
+  storeLocked: "Unlock the app with your master password first",
+  duplicateProfile: "A connection to this database already exists",
 };
 
 /**
  * Display prefixes added by `DbError`'s `thiserror` attributes. Stripped
- * when the message itself is used as the headline (query / invalid_input),
+ * when the message itself is used as the headline (query / invalidInput),
  * since the underlying text is the user-actionable part.
  */
 const MESSAGE_PREFIXES = [
@@ -66,6 +84,11 @@ const MESSAGE_PREFIXES = [
   "Credential error: ",
   "Import error: ",
   "AI error: ",
+  "AI rate limit exceeded: ",
+  "AI authentication failed: ",
+  "AI provider unreachable: ",
+  "Confirmation required: ",
+  "Store locked: ",
 ];
 
 function stripKnownPrefix(message: string): string {
@@ -79,7 +102,13 @@ function stripKnownPrefix(message: string): string {
 
 function isStructuredDbError(
   err: unknown
-): err is { kind: string; message: string } {
+): err is {
+  kind: string;
+  message: string;
+  sqlstate?: string;
+  errorCode?: number;
+  category?: string;
+} {
   return (
     typeof err === "object" &&
     err !== null &&
@@ -103,22 +132,32 @@ function withDetail(
  * string, or `Error`) into a human headline + raw detail.
  */
 export function formatDbError(err: unknown): FormattedDbError {
-  // Structured DbError from the Rust backend: { kind, message }
+  // Structured DbError from the Rust backend: { kind, message, ...codes }
   if (isStructuredDbError(err)) {
-    const { kind, message } = err;
+    const { kind, message, sqlstate, errorCode, category } = err;
+    const withCodes = (formatted: FormattedDbError): FormattedDbError => ({
+      ...formatted,
+      ...(sqlstate && { sqlstate }),
+      ...(errorCode !== undefined && { errorCode }),
+      ...(category && { category }),
+    });
 
     // Query and validation errors carry user-actionable text — surface it.
-    if (kind === "query" || kind === "invalid_input") {
-      return withDetail(stripKnownPrefix(message), message, kind);
+    if (
+      kind === "query" ||
+      kind === "invalidInput" ||
+      kind === "confirmationRequired"
+    ) {
+      return withCodes(withDetail(stripKnownPrefix(message), message, kind));
     }
 
     const headline = KIND_HEADLINES[kind];
     if (headline) {
-      return withDetail(headline, message, kind);
+      return withCodes(withDetail(headline, message, kind));
     }
 
     // Unknown kind (future backend additions) — degrade to the message.
-    return withDetail(stripKnownPrefix(message), message, kind);
+    return withCodes(withDetail(stripKnownPrefix(message), message, kind));
   }
 
   // Plain string rejection (commands with `Result<T, String>` errors)